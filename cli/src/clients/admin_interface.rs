@@ -14,7 +14,9 @@ use super::AdminClient;
 
 use restate_admin_rest_model::deployments::*;
 use restate_admin_rest_model::services::*;
+use restate_admin_rest_model::subscriptions::*;
 use restate_admin_rest_model::version::VersionInformation;
+use restate_types::identifiers::DeploymentId;
 use restate_types::schema::service::ServiceMetadata;
 
 pub trait AdminClientInterface {
@@ -39,10 +41,36 @@ pub trait AdminClientInterface {
         body: RegisterDeploymentRequest,
     ) -> reqwest::Result<Envelope<RegisterDeploymentResponse>>;
 
+    async fn get_subscriptions(&self) -> reqwest::Result<Envelope<ListSubscriptionsResponse>>;
+
+    async fn get_subscription(&self, id: &str) -> reqwest::Result<Envelope<SubscriptionResponse>>;
+
+    async fn get_subscription_status(
+        &self,
+        id: &str,
+    ) -> reqwest::Result<Envelope<SubscriptionStatusResponse>>;
+
     async fn purge_invocation(&self, id: &str) -> reqwest::Result<Envelope<()>>;
 
     async fn cancel_invocation(&self, id: &str, kill: bool) -> reqwest::Result<Envelope<()>>;
 
+    async fn pause_invocation(&self, id: &str) -> reqwest::Result<Envelope<()>>;
+
+    async fn resume_invocation(&self, id: &str) -> reqwest::Result<Envelope<()>>;
+
+    async fn retry_invocation_now(
+        &self,
+        id: &str,
+        deployment: Option<&DeploymentId>,
+    ) -> reqwest::Result<Envelope<()>>;
+
+    async fn restart_invocation(
+        &self,
+        id: &str,
+        from: u32,
+        deployment: Option<&DeploymentId>,
+    ) -> reqwest::Result<Envelope<()>>;
+
     async fn patch_state(
         &self,
         service: &str,
@@ -121,6 +149,30 @@ impl AdminClientInterface for AdminClient {
         self.run_with_body(reqwest::Method::POST, url, body).await
     }
 
+    async fn get_subscriptions(&self) -> reqwest::Result<Envelope<ListSubscriptionsResponse>> {
+        let url = self.base_url.join("/subscriptions").expect("Bad url!");
+        self.run(reqwest::Method::GET, url).await
+    }
+
+    async fn get_subscription(&self, id: &str) -> reqwest::Result<Envelope<SubscriptionResponse>> {
+        let url = self
+            .base_url
+            .join(&format!("/subscriptions/{id}"))
+            .expect("Bad url!");
+        self.run(reqwest::Method::GET, url).await
+    }
+
+    async fn get_subscription_status(
+        &self,
+        id: &str,
+    ) -> reqwest::Result<Envelope<SubscriptionStatusResponse>> {
+        let url = self
+            .base_url
+            .join(&format!("/subscriptions/{id}/status"))
+            .expect("Bad url!");
+        self.run(reqwest::Method::GET, url).await
+    }
+
     async fn purge_invocation(&self, id: &str) -> reqwest::Result<Envelope<()>> {
         let mut url = self
             .base_url
@@ -146,6 +198,62 @@ impl AdminClientInterface for AdminClient {
         self.run(reqwest::Method::DELETE, url).await
     }
 
+    async fn pause_invocation(&self, id: &str) -> reqwest::Result<Envelope<()>> {
+        let url = self
+            .base_url
+            .join(&format!("/invocations/{id}/pause"))
+            .expect("Bad url!");
+
+        self.run(reqwest::Method::PATCH, url).await
+    }
+
+    async fn resume_invocation(&self, id: &str) -> reqwest::Result<Envelope<()>> {
+        let url = self
+            .base_url
+            .join(&format!("/invocations/{id}/resume"))
+            .expect("Bad url!");
+
+        self.run(reqwest::Method::PATCH, url).await
+    }
+
+    async fn retry_invocation_now(
+        &self,
+        id: &str,
+        deployment: Option<&DeploymentId>,
+    ) -> reqwest::Result<Envelope<()>> {
+        let mut url = self
+            .base_url
+            .join(&format!("/invocations/{id}/retry-now"))
+            .expect("Bad url!");
+
+        if let Some(deployment) = deployment {
+            url.set_query(Some(&format!("deployment={deployment}")));
+        }
+
+        self.run(reqwest::Method::PATCH, url).await
+    }
+
+    async fn restart_invocation(
+        &self,
+        id: &str,
+        from: u32,
+        deployment: Option<&DeploymentId>,
+    ) -> reqwest::Result<Envelope<()>> {
+        let mut url = self
+            .base_url
+            .join(&format!("/invocations/{id}/restart"))
+            .expect("Bad url!");
+
+        match deployment {
+            Some(deployment) => {
+                url.set_query(Some(&format!("from={from}&deployment={deployment}")));
+            }
+            None => url.set_query(Some(&format!("from={from}"))),
+        }
+
+        self.run(reqwest::Method::PATCH, url).await
+    }
+
     async fn patch_state(
         &self,
         service: &str,
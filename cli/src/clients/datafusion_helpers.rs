@@ -128,6 +128,7 @@ pub enum InvocationState {
     Running,
     Suspended,
     BackingOff,
+    Paused,
     Killed,
     Completed,
 }
@@ -142,6 +143,7 @@ impl FromStr for InvocationState {
             "running" => Self::Running,
             "suspended" => Self::Suspended,
             "backing-off" => Self::BackingOff,
+            "paused" => Self::Paused,
             "completed" => Self::Completed,
             "killed" => Self::Killed,
             _ => Self::Unknown,
@@ -159,6 +161,7 @@ impl Display for InvocationState {
             InvocationState::Running => write!(f, "running"),
             InvocationState::Suspended => write!(f, "suspended"),
             InvocationState::BackingOff => write!(f, "backing-off"),
+            InvocationState::Paused => write!(f, "paused"),
             InvocationState::Killed => write!(f, "killed"),
             InvocationState::Completed => write!(f, "completed"),
         }
@@ -116,6 +116,7 @@ async fn list(env: &CliEnv, list_opts: &List) -> Result<()> {
             Cell::new(deployment.id),
             Cell::new(match &deployment.deployment {
                 Deployment::Http { created_at, .. } => created_at,
+                Deployment::Unix { created_at, .. } => created_at,
                 Deployment::Lambda { created_at, .. } => created_at,
             }),
         ];
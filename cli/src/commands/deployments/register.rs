@@ -10,6 +10,7 @@
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use anyhow::Result;
@@ -22,6 +23,7 @@ use restate_admin_rest_model::deployments::{Deployment, RegisterDeploymentReques
 use restate_cli_util::ui::console::{confirm_or_exit, Styled, StyledTable};
 use restate_cli_util::ui::stylesheet::Style;
 use restate_cli_util::{c_eprintln, c_error, c_indent_table, c_indentln, c_success, c_warn};
+use restate_types::config::TlsClientOptions;
 use restate_types::identifiers::LambdaARN;
 use restate_types::schema::service::ServiceMetadata;
 
@@ -58,10 +60,31 @@ pub struct Register {
     #[clap(long = "use-http1.1")]
     use_http_11: bool,
 
+    /// Path to a PEM file with the CA certificates used to verify the deployment's server
+    /// certificate, instead of the CA bundle configured on the Restate server.
+    ///
+    /// The path refers to a file on the Restate server.
+    #[clap(long = "tls-ca-bundle")]
+    tls_ca_bundle_path: Option<PathBuf>,
+
+    /// Path to a PEM file with the client certificate Restate server presents to the deployment.
+    ///
+    /// The path refers to a file on the Restate server.
+    #[clap(long = "tls-client-cert", requires = "tls_client_key_path")]
+    tls_client_cert_path: Option<PathBuf>,
+
+    /// Path to a PEM file with the private key of the client certificate.
+    ///
+    /// The path refers to a file on the Restate server.
+    #[clap(long = "tls-client-key", requires = "tls_client_cert_path")]
+    tls_client_key_path: Option<PathBuf>,
+
     /// The URL or ARN that Restate server needs to fetch service information from.
     ///
-    /// The URL must be network-accessible from Restate server. In case of using
-    /// Lambda ARN, the ARN should include the function version.
+    /// The URL must be network-accessible from Restate server. Deployments listening on a
+    /// Unix domain socket on the Restate server's host can be addressed with
+    /// `unix:///path/to/socket`. In case of using Lambda ARN, the ARN should include the
+    /// function version.
     #[clap(value_parser = parse_deployment)]
     deployment: DeploymentEndpoint,
 }
@@ -75,6 +98,7 @@ struct HeaderKeyValue {
 #[derive(Clone, Debug)]
 enum DeploymentEndpoint {
     Uri(Uri),
+    Unix(PathBuf),
     Lambda(LambdaARN),
 }
 
@@ -82,6 +106,9 @@ impl Display for DeploymentEndpoint {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             DeploymentEndpoint::Uri(uri) => write!(f, "URL {uri}"),
+            DeploymentEndpoint::Unix(socket_path) => {
+                write!(f, "Unix socket {}", socket_path.display())
+            }
             DeploymentEndpoint::Lambda(arn) => write!(f, "AWS Lambda ARN {arn}"),
         }
     }
@@ -109,6 +136,15 @@ fn parse_deployment(
 ) -> Result<DeploymentEndpoint, Box<dyn std::error::Error + Send + Sync + 'static>> {
     let deployment = if raw.starts_with("arn:") {
         DeploymentEndpoint::Lambda(LambdaARN::from_str(raw)?)
+    } else if let Some(socket_path) = raw.strip_prefix("unix://") {
+        let socket_path = PathBuf::from(socket_path);
+        if !socket_path.is_absolute() {
+            return Err(format!(
+                "invalid Unix socket URL, expected unix:///absolute/path but got {raw}"
+            )
+            .into());
+        }
+        DeploymentEndpoint::Unix(socket_path)
     } else {
         let mut uri = Uri::from_str(raw).map_err(|e| format!("invalid URL({e})"))?;
         let mut parts = uri.into_parts();
@@ -177,11 +213,26 @@ pub async fn run_register(State(env): State<CliEnv>, discover_opts: &Register) -
         other => other.clone(),
     };
 
+    let tls = TlsClientOptions {
+        ca_bundle_path: discover_opts.tls_ca_bundle_path.clone(),
+        client_cert_path: discover_opts.tls_client_cert_path.clone(),
+        client_key_path: discover_opts.tls_client_key_path.clone(),
+    };
+    let tls = (!tls.is_default()).then_some(tls);
+
     let mk_request_body = |force, dry_run| match &deployment {
         DeploymentEndpoint::Uri(uri) => RegisterDeploymentRequest::Http {
             uri: uri.clone(),
             additional_headers: headers.clone().map(Into::into),
             use_http_11: discover_opts.use_http_11,
+            tls: tls.clone(),
+            force,
+            dry_run,
+        },
+        DeploymentEndpoint::Unix(socket_path) => RegisterDeploymentRequest::Unix {
+            socket_path: socket_path.clone(),
+            additional_headers: headers.clone().map(Into::into),
+            use_http_11: discover_opts.use_http_11,
             force,
             dry_run,
         },
@@ -11,7 +11,11 @@
 mod cancel;
 mod describe;
 mod list;
+mod pause;
 mod purge;
+mod restart;
+mod resume;
+mod retry_now;
 
 use cling::prelude::*;
 
@@ -25,4 +29,12 @@ pub enum Invocations {
     Cancel(cancel::Cancel),
     /// Purge a completed invocation, or a set of invocations. This command affects only completed invocations.
     Purge(purge::Purge),
+    /// Pause a running invocation, or a set of invocations. Paused invocations are not retried until resumed.
+    Pause(pause::Pause),
+    /// Resume a paused invocation, or a set of invocations.
+    Resume(resume::Resume),
+    /// Retry a backing-off invocation, or a set of invocations, without waiting for the retry backoff.
+    RetryNow(retry_now::RetryNow),
+    /// Restart a paused or suspended invocation, replaying its journal up to the given entry.
+    Restart(restart::Restart),
 }
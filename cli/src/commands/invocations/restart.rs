@@ -0,0 +1,89 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::cli_env::CliEnv;
+use crate::clients::datafusion_helpers::{find_active_invocations_simple, InvocationState};
+use crate::clients::{self, AdminClientInterface};
+use crate::ui::invocations::render_simple_invocation_list;
+
+use anyhow::{bail, Result};
+use cling::prelude::*;
+use restate_cli_util::ui::console::confirm_or_exit;
+use restate_cli_util::{c_println, c_success, c_warn};
+use restate_types::identifiers::{DeploymentId, InvocationId};
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_restart")]
+pub struct Restart {
+    /// The invocation id
+    invocation_id: InvocationId,
+
+    /// Index of the last journal entry to keep, all the following entries are removed.
+    /// The entry 0 is the input entry.
+    #[clap(long)]
+    from: u32,
+
+    /// Pin the invocation to this deployment when restarting.
+    /// The deployment must expose the service of the invocation.
+    #[clap(long)]
+    deployment: Option<DeploymentId>,
+}
+
+pub async fn run_restart(State(env): State<CliEnv>, opts: &Restart) -> Result<()> {
+    let client = clients::AdminClient::new(&env).await?;
+    let sql_client = clients::DataFusionHttpClient::from(client.clone());
+
+    let invocation_id = opts.invocation_id;
+    let invocations =
+        find_active_invocations_simple(&sql_client, &format!("id = '{invocation_id}'")).await?;
+    let Some(invocation) = invocations.first() else {
+        bail!("Invocation {invocation_id} not found!");
+    };
+    if !matches!(
+        invocation.status,
+        InvocationState::Paused | InvocationState::Suspended
+    ) {
+        bail!(
+            "Invocation {invocation_id} is {}. Only paused or suspended invocations can be restarted, \
+            use `restate invocations pause {invocation_id}` first.",
+            invocation.status
+        );
+    }
+
+    render_simple_invocation_list(&invocations);
+
+    c_warn!(
+        "The journal entries after index {} will be removed. Calls and one way calls recorded in \
+        the removed entries are sent again when the invocation replays them.",
+        opts.from
+    );
+    if let Some(deployment) = &opts.deployment {
+        c_warn!(
+            "The invocation will be pinned to the deployment {deployment}. \
+            Make sure it is compatible with the entries kept in its journal."
+        );
+    }
+
+    confirm_or_exit("Are you sure you want to restart this invocation?")?;
+
+    let result = client
+        .restart_invocation(
+            &invocation_id.to_string(),
+            opts.from,
+            opts.deployment.as_ref(),
+        )
+        .await?;
+    let _ = result.success_or_error()?;
+
+    c_println!();
+    c_success!("Request was sent successfully");
+
+    Ok(())
+}
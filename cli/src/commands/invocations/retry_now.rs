@@ -0,0 +1,91 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::cli_env::CliEnv;
+use crate::clients::datafusion_helpers::find_active_invocations_simple;
+use crate::clients::{self, AdminClientInterface};
+use crate::ui::invocations::render_simple_invocation_list;
+
+use anyhow::{bail, Result};
+use cling::prelude::*;
+use restate_cli_util::ui::console::confirm_or_exit;
+use restate_cli_util::{c_println, c_success, c_warn};
+use restate_types::identifiers::{DeploymentId, InvocationId};
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_retry_now")]
+pub struct RetryNow {
+    /// Either an invocation id, or a target string exact match or prefix, e.g.:
+    /// * `invocationId`
+    /// * `serviceName`
+    /// * `serviceName/handler`
+    /// * `virtualObjectName`
+    /// * `virtualObjectName/key`
+    /// * `virtualObjectName/key/handler`
+    /// * `workflowName`
+    /// * `workflowName/key`
+    /// * `workflowName/key/handler`
+    query: String,
+
+    /// Pin the invocations to this deployment, starting from the next attempt.
+    /// The deployment must expose the service of the invocations.
+    #[clap(long)]
+    deployment: Option<DeploymentId>,
+}
+
+pub async fn run_retry_now(State(env): State<CliEnv>, opts: &RetryNow) -> Result<()> {
+    let client = clients::AdminClient::new(&env).await?;
+    let sql_client = clients::DataFusionHttpClient::from(client.clone());
+
+    let q = opts.query.trim();
+    let filter = if let Ok(id) = q.parse::<InvocationId>() {
+        format!("id = '{id}'")
+    } else {
+        match q.find('/').unwrap_or_default() {
+            0 => format!("target LIKE '{q}/%'"),
+            // If there's one slash, let's add the wildcard depending on the service type,
+            // so we discriminate correctly with serviceName/handlerName with workflowName/workflowKey
+            1 => format!("((target = '{q}' AND target_service_ty = 'service') OR (target LIKE '{q}/%' AND target_service_ty != 'service'))"),
+            // Can only be exact match here
+            _ => format!("target LIKE '{q}'"),
+        }
+    };
+    // Only invocations waiting for a retry, or paused, can be retried
+    let filter = format!("{filter} AND status IN ('backing-off', 'paused')");
+
+    let invocations = find_active_invocations_simple(&sql_client, &filter).await?;
+    if invocations.is_empty() {
+        bail!("No invocations found for query {}! Note that the retry-now command works only on backing-off or paused invocations.", opts.query);
+    };
+
+    render_simple_invocation_list(&invocations);
+
+    if let Some(deployment) = &opts.deployment {
+        c_warn!(
+            "The invocations will be pinned to the deployment {deployment}. \
+            Make sure it is compatible with the entries already recorded in their journals."
+        );
+    }
+
+    // Get the invocation and confirm
+    confirm_or_exit("Are you sure you want to retry these invocations now?")?;
+
+    for inv in invocations {
+        let result = client
+            .retry_invocation_now(&inv.id, opts.deployment.as_ref())
+            .await?;
+        let _ = result.success_or_error()?;
+    }
+
+    c_println!();
+    c_success!("Request was sent successfully");
+
+    Ok(())
+}
@@ -17,4 +17,5 @@ pub mod invocations;
 pub mod services;
 pub mod sql;
 pub mod state;
+pub mod subscriptions;
 pub mod whoami;
@@ -95,6 +95,18 @@ fn write_out_edit_toml(w: &mut impl io::Write, service_type: ServiceType) -> Res
     writeln!(w, "# abort_timeout = \"10min\"")?;
     writeln!(w)?;
 
+    write_prefixed_lines(w, "# ", super::view::RETRY_POLICY)?;
+    writeln!(w, "# Example:")?;
+    writeln!(w, "# [retry_policy]")?;
+    writeln!(w, "# on_max_attempts = \"pause\"")?;
+    writeln!(w, "# [retry_policy.retry_policy]")?;
+    writeln!(w, "# type = \"exponential\"")?;
+    writeln!(w, "# initial-interval = \"100ms\"")?;
+    writeln!(w, "# factor = 2.0")?;
+    writeln!(w, "# max-attempts = 10")?;
+    writeln!(w, "# max-interval = \"10s\"")?;
+    writeln!(w)?;
+
     Ok(())
 }
 
@@ -93,6 +93,9 @@ async fn patch(env: &CliEnv, opts: &Patch) -> Result<()> {
             .as_ref()
             .map(|s| DurationString::parse_duration(s).context("Cannot parse abort_timeout"))
             .transpose()?,
+        cors: None,
+        retry_policy: None,
+        handler_retry_policies: Default::default(),
     };
 
     apply_service_configuration_patch(opts.service.clone(), admin_client, modify_request).await
@@ -109,6 +112,9 @@ pub(super) async fn apply_service_configuration_patch(
         && modify_request.idempotency_retention.is_none()
         && modify_request.inactivity_timeout.is_none()
         && modify_request.abort_timeout.is_none()
+        && modify_request.cors.is_none()
+        && modify_request.retry_policy.is_none()
+        && modify_request.handler_retry_policies.is_empty()
     {
         c_println!("No changes requested");
         return Ok(());
@@ -140,6 +146,32 @@ pub(super) async fn apply_service_configuration_patch(
     if let Some(abort_timeout) = &modify_request.abort_timeout {
         table.add_kv_row("Abort timeout:", humantime::Duration::from(*abort_timeout));
     }
+    if let Some(cors) = &modify_request.cors {
+        match cors {
+            Some(cors) => {
+                table.add_kv_row("CORS allowed origins:", cors.allowed_origins.join(", "))
+            }
+            None => table.add_kv_row("CORS policy:", "ingress default"),
+        };
+    }
+    if let Some(retry_policy) = &modify_request.retry_policy {
+        table.add_kv_row(
+            "Retry policy:",
+            retry_policy
+                .as_ref()
+                .map(super::view::retry_policy_summary)
+                .unwrap_or("<DEFAULT>".to_string()),
+        );
+    }
+    for (handler, retry_policy) in &modify_request.handler_retry_policies {
+        table.add_kv_row(
+            &format!("Retry policy of {handler}:"),
+            retry_policy
+                .as_ref()
+                .map(super::view::retry_policy_summary)
+                .unwrap_or("service default".to_string()),
+        );
+    }
     c_println!("{table}");
     confirm_or_exit("Are you sure you want to apply these changes?")?;
 
@@ -17,6 +17,8 @@ use indoc::indoc;
 use restate_cli_util::ui::console::StyledTable;
 use restate_cli_util::{c_println, c_tip};
 use restate_types::invocation::ServiceType;
+use restate_types::retries::RetryPolicy;
+use restate_types::schema::service::{InvocationRetryPolicy, OnMaxAttempts};
 
 // TODO we could infer this text from the OpenAPI docs!
 pub(super) const PUBLIC_DESCRIPTION: &str = indoc! {
@@ -55,6 +57,14 @@ pub(super) const ABORT_TIMEOUT: &str = indoc! {
 
     This overrides the default abort timeout set in invoker options."
 };
+pub(super) const RETRY_POLICY: &str = indoc! {
+    "The retry policy applied to the invocations of this service, and the action to take
+    once the maximum number of attempts is reached: fail the invocation, or pause it
+    until it is resumed.
+    Handlers can override the service retry policy.
+
+    This overrides the default retry policy set in invoker options."
+};
 
 #[derive(Run, Parser, Collect, Clone)]
 #[cling(run = "run_view")]
@@ -129,5 +139,54 @@ async fn view(env: &CliEnv, opts: &View) -> Result<()> {
     c_tip!("{}", ABORT_TIMEOUT);
     c_println!();
 
+    let mut table = Table::new_styled();
+    table.add_kv_row(
+        "Retry policy:",
+        service
+            .retry_policy
+            .as_ref()
+            .map(retry_policy_summary)
+            .unwrap_or("<DEFAULT>".to_string()),
+    );
+    for handler in &service.handlers {
+        if let Some(retry_policy) = &handler.retry_policy {
+            table.add_kv_row(
+                &format!("Retry policy of {}:", handler.name),
+                retry_policy_summary(retry_policy),
+            );
+        }
+    }
+    c_println!("{table}");
+    c_tip!("{}", RETRY_POLICY);
+    c_println!();
+
     Ok(())
 }
+
+pub(super) fn retry_policy_summary(retry_policy: &InvocationRetryPolicy) -> String {
+    let strategy = match &retry_policy.retry_policy {
+        RetryPolicy::None => return "no retries".to_string(),
+        RetryPolicy::FixedDelay { interval, .. } => format!("every {interval}"),
+        RetryPolicy::Exponential {
+            initial_interval,
+            factor,
+            max_interval,
+            ..
+        } => match max_interval {
+            Some(max_interval) => {
+                format!("exponential from {initial_interval} (x{factor}, at most {max_interval})")
+            }
+            None => format!("exponential from {initial_interval} (x{factor})"),
+        },
+    };
+    match retry_policy.retry_policy.max_attempts() {
+        Some(max_attempts) => {
+            let action = match retry_policy.on_max_attempts {
+                OnMaxAttempts::Fail => "fail",
+                OnMaxAttempts::Pause => "pause",
+            };
+            format!("{strategy}, {action} after {max_attempts} attempts")
+        }
+        None => format!("{strategy}, unlimited attempts"),
+    }
+}
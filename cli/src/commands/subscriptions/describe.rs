@@ -0,0 +1,99 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Result;
+use cling::prelude::*;
+use comfy_table::{Cell, Table};
+
+use restate_cli_util::ui::console::StyledTable;
+use restate_cli_util::ui::watcher::Watch;
+use restate_cli_util::{c_indent_table, c_println, c_title};
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+use crate::ui::subscriptions::{render_offset, render_subscription_status};
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_describe")]
+#[clap(visible_alias = "get")]
+pub struct Describe {
+    /// Subscription ID
+    subscription_id: String,
+
+    #[clap(flatten)]
+    watch: Watch,
+}
+
+pub async fn run_describe(State(env): State<CliEnv>, opts: &Describe) -> Result<()> {
+    opts.watch.run(|| describe(&env, opts)).await
+}
+
+async fn describe(env: &CliEnv, opts: &Describe) -> Result<()> {
+    let client = AdminClient::new(env).await?;
+
+    let subscription = client
+        .get_subscription(&opts.subscription_id)
+        .await?
+        .into_body()
+        .await?;
+
+    let mut table = Table::new_styled();
+    table.add_kv_row("ID:", subscription.id);
+    table.add_kv_row("Source:", &subscription.source);
+    table.add_kv_row("Sink:", &subscription.sink);
+    table.add_kv_row("Status:", render_subscription_status(subscription.paused));
+
+    c_title!("📜", "Subscription Information");
+    c_println!("{}", table);
+
+    if !subscription.options.is_empty() {
+        c_println!();
+        c_title!("⚙️", "Options");
+        let mut options: Vec<_> = subscription.options.into_iter().collect();
+        options.sort_unstable();
+        let mut options_table = Table::new_styled();
+        for (key, value) in options {
+            options_table.add_kv_row(&key, value);
+        }
+        c_indent_table!(1, options_table);
+    }
+
+    // Only subscriptions consuming a Kafka topic have offsets
+    if subscription.source.starts_with("kafka://") {
+        let status = client
+            .get_subscription_status(&opts.subscription_id)
+            .await?
+            .into_body()
+            .await?;
+
+        c_println!();
+        c_title!("📈", "Offsets");
+        let mut offsets_table = Table::new_styled();
+        offsets_table.set_styled_header(vec![
+            "TOPIC",
+            "PARTITION",
+            "COMMITTED OFFSET",
+            "HIGH WATERMARK",
+            "LAG",
+        ]);
+        for partition in status.partitions {
+            offsets_table.add_row(vec![
+                Cell::new(partition.topic),
+                Cell::new(partition.partition),
+                render_offset(partition.committed_offset),
+                Cell::new(partition.high_watermark),
+                render_offset(partition.lag),
+            ]);
+        }
+        c_indent_table!(1, offsets_table);
+    }
+
+    Ok(())
+}
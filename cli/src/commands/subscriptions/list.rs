@@ -0,0 +1,66 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Result;
+use cling::prelude::*;
+use comfy_table::{Cell, Table};
+
+use restate_cli_util::c_error;
+use restate_cli_util::ui::console::StyledTable;
+use restate_cli_util::ui::watcher::Watch;
+
+use crate::cli_env::CliEnv;
+use crate::clients::AdminClientInterface;
+use crate::console::c_println;
+use crate::ui::subscriptions::render_subscription_status;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[clap(visible_alias = "ls")]
+#[cling(run = "run_list")]
+pub struct List {
+    #[clap(flatten)]
+    watch: Watch,
+}
+
+pub async fn run_list(State(env): State<CliEnv>, opts: &List) -> Result<()> {
+    opts.watch.run(|| list(&env)).await
+}
+
+async fn list(env: &CliEnv) -> Result<()> {
+    let client = crate::clients::AdminClient::new(env).await?;
+
+    let mut subscriptions = client
+        .get_subscriptions()
+        .await?
+        .into_body()
+        .await?
+        .subscriptions;
+
+    if subscriptions.is_empty() {
+        c_error!("No subscriptions were found!");
+        return Ok(());
+    }
+    subscriptions.sort_unstable_by(|a, b| a.source.cmp(&b.source));
+
+    let mut table = Table::new_styled();
+    table.set_styled_header(vec!["ID", "SOURCE", "SINK", "STATUS"]);
+    for subscription in subscriptions {
+        table.add_row(vec![
+            Cell::new(subscription.id),
+            Cell::new(subscription.source),
+            Cell::new(subscription.sink),
+            render_subscription_status(subscription.paused),
+        ]);
+    }
+
+    c_println!("{}", table);
+
+    Ok(())
+}
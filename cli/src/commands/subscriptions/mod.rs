@@ -0,0 +1,23 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+mod describe;
+mod list;
+
+use cling::prelude::*;
+
+#[derive(Run, Subcommand, Clone)]
+#[clap(visible_alias = "sub", alias = "subscription")]
+pub enum Subscriptions {
+    /// List the subscriptions
+    List(list::List),
+    /// Prints detailed information about a given subscription, including the lag of its consumer
+    Describe(describe::Describe),
+}
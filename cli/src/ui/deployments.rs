@@ -33,6 +33,7 @@ pub enum DeploymentStatus {
 pub fn render_deployment_url(deployment: &Deployment) -> String {
     match deployment {
         Deployment::Http { uri, .. } => uri.to_string(),
+        Deployment::Unix { socket_path, .. } => format!("unix://{}", socket_path.display()),
         Deployment::Lambda { arn, .. } => arn.to_string(),
     }
 }
@@ -42,6 +43,9 @@ pub fn render_deployment_type(deployment: &Deployment) -> String {
         Deployment::Http { http_version, .. } => {
             format!("{http_version:?}")
         }
+        Deployment::Unix { http_version, .. } => {
+            format!("{http_version:?} (Unix socket)")
+        }
         Deployment::Lambda { .. } => "AWS Lambda".to_string(),
     }
 }
@@ -119,6 +123,30 @@ pub fn add_deployment_to_kv_table(deployment: &Deployment, table: &mut Table) {
                     max_protocol_version,
                 )
             }
+            Deployment::Unix {
+                socket_path,
+                protocol_type,
+                http_version: _,
+                additional_headers,
+                created_at,
+                min_protocol_version,
+                max_protocol_version,
+            } => {
+                let protocol_type = match protocol_type {
+                    ProtocolType::RequestResponse => "Request/Response",
+                    ProtocolType::BidiStream => "Streaming",
+                }
+                .to_string();
+                table.add_kv_row("Protocol Style:", protocol_type);
+
+                table.add_kv_row("Endpoint:", format!("unix://{}", socket_path.display()));
+                (
+                    additional_headers.clone(),
+                    created_at,
+                    min_protocol_version,
+                    max_protocol_version,
+                )
+            }
             Deployment::Lambda {
                 arn,
                 assume_role_arn,
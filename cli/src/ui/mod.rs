@@ -11,3 +11,4 @@
 pub mod deployments;
 pub mod invocations;
 pub mod service_handlers;
+pub mod subscriptions;
@@ -0,0 +1,27 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use comfy_table::{Cell, Color};
+
+pub fn render_subscription_status(paused: bool) -> Cell {
+    if paused {
+        Cell::new("Paused").fg(Color::Yellow)
+    } else {
+        Cell::new("Active").fg(Color::Green)
+    }
+}
+
+/// Offsets are unknown until the consumer group committed one.
+pub fn render_offset(offset: Option<i64>) -> Cell {
+    match offset {
+        Some(offset) => Cell::new(offset),
+        None => Cell::new("-").fg(Color::Grey),
+    }
+}
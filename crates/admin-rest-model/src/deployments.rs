@@ -11,6 +11,7 @@
 use http::Uri;
 use http::Version;
 use restate_serde_util::SerdeableHeaderHashMap;
+use restate_types::config::TlsClientOptions;
 use restate_types::identifiers::ServiceRevision;
 use restate_types::identifiers::{DeploymentId, LambdaARN};
 use restate_types::schema::deployment::DeploymentType;
@@ -18,6 +19,7 @@ use restate_types::schema::deployment::{DeploymentMetadata, ProtocolType};
 use restate_types::schema::service::ServiceMetadata;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
+use std::path::PathBuf;
 use std::time::SystemTime;
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
@@ -42,6 +44,21 @@ pub enum Deployment {
         min_protocol_version: i32,
         max_protocol_version: i32,
     },
+    Unix {
+        socket_path: PathBuf,
+        protocol_type: ProtocolType,
+        #[serde(with = "http_serde::version")]
+        #[cfg_attr(feature = "schema", schemars(with = "String"))]
+        http_version: Version,
+        #[serde(skip_serializing_if = "SerdeableHeaderHashMap::is_empty")]
+        #[serde(default)]
+        additional_headers: SerdeableHeaderHashMap,
+        #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+        #[cfg_attr(feature = "schema", schemars(with = "String"))]
+        created_at: humantime::Timestamp,
+        min_protocol_version: i32,
+        max_protocol_version: i32,
+    },
     Lambda {
         arn: LambdaARN,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -77,6 +94,18 @@ enum DeploymentShadow {
         min_protocol_version: i32,
         max_protocol_version: i32,
     },
+    Unix {
+        socket_path: PathBuf,
+        protocol_type: ProtocolType,
+        #[serde(with = "http_serde::version")]
+        http_version: Version,
+        #[serde(default)]
+        additional_headers: SerdeableHeaderHashMap,
+        #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+        created_at: humantime::Timestamp,
+        min_protocol_version: i32,
+        max_protocol_version: i32,
+    },
     Lambda {
         arn: LambdaARN,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -113,6 +142,23 @@ impl From<DeploymentShadow> for Deployment {
                 min_protocol_version,
                 max_protocol_version,
             },
+            DeploymentShadow::Unix {
+                socket_path,
+                protocol_type,
+                http_version,
+                additional_headers,
+                created_at,
+                min_protocol_version,
+                max_protocol_version,
+            } => Self::Unix {
+                socket_path,
+                protocol_type,
+                http_version,
+                additional_headers,
+                created_at,
+                min_protocol_version,
+                max_protocol_version,
+            },
             DeploymentShadow::Lambda {
                 arn,
                 assume_role_arn,
@@ -174,6 +220,19 @@ impl From<DeploymentMetadata> for Deployment {
                 min_protocol_version: *value.supported_protocol_versions.start(),
                 max_protocol_version: *value.supported_protocol_versions.end(),
             },
+            DeploymentType::Unix {
+                socket_path,
+                protocol_type,
+                http_version,
+            } => Self::Unix {
+                socket_path,
+                protocol_type,
+                http_version,
+                additional_headers: value.delivery_options.additional_headers.into(),
+                created_at: SystemTime::from(value.created_at).into(),
+                min_protocol_version: *value.supported_protocol_versions.start(),
+                max_protocol_version: *value.supported_protocol_versions.end(),
+            },
             DeploymentType::Lambda {
                 arn,
                 assume_role_arn,
@@ -219,6 +278,14 @@ pub enum RegisterDeploymentRequest {
         #[serde(default = "restate_serde_util::default::bool::<false>")]
         use_http_11: bool,
 
+        /// # TLS
+        ///
+        /// Overrides of the CA bundle and client certificate configured on the Restate nodes,
+        /// used when connecting to this deployment over HTTPS. Paths refer to files on the
+        /// Restate nodes.
+        #[serde(default)]
+        tls: Option<TlsClientOptions>,
+
         /// # Force
         ///
         /// If `true`, it will override, if existing, any deployment using the same `uri`.
@@ -237,6 +304,46 @@ pub enum RegisterDeploymentRequest {
         #[serde(default = "restate_serde_util::default::bool::<false>")]
         dry_run: bool,
     },
+    Unix {
+        /// # Socket path
+        ///
+        /// Path of the Unix domain socket to use to discover/invoke the deployment. The socket
+        /// must be reachable from the Restate server.
+        socket_path: PathBuf,
+
+        /// # Additional headers
+        ///
+        /// Additional headers added to the discover/invoke requests to the deployment.
+        ///
+        additional_headers: Option<SerdeableHeaderHashMap>,
+
+        /// # Use http1.1
+        ///
+        /// If `true`, discovery will be attempted using a client that defaults to HTTP1.1
+        /// instead of a prior-knowledge HTTP2 client. HTTP1.1 deployments will only work in
+        /// request-response mode.
+        ///
+        #[serde(default = "restate_serde_util::default::bool::<false>")]
+        use_http_11: bool,
+
+        /// # Force
+        ///
+        /// If `true`, it will override, if existing, any deployment using the same `socket_path`.
+        /// Beware that this can lead in-flight invocations to an unrecoverable error state.
+        ///
+        /// By default, this is `true` but it might change in future to `false`.
+        ///
+        /// See the [versioning documentation](https://docs.restate.dev/operate/versioning) for more information.
+        #[serde(default = "restate_serde_util::default::bool::<true>")]
+        force: bool,
+
+        /// # Dry-run mode
+        ///
+        /// If `true`, discovery will run but the deployment will not be registered.
+        /// This is useful to see the impact of a new deployment before registering it.
+        #[serde(default = "restate_serde_util::default::bool::<false>")]
+        dry_run: bool,
+    },
     Lambda {
         /// # ARN
         ///
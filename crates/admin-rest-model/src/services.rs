@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
 
-use restate_types::schema::service::ServiceMetadata;
+use restate_types::schema::service::{CorsPolicy, InvocationRetryPolicy, ServiceMetadata};
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Serialize, Deserialize)]
@@ -93,6 +93,37 @@ pub struct ModifyServiceRequest {
     )]
     #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
     pub abort_timeout: Option<Duration>,
+
+    /// # CORS policy
+    ///
+    /// CORS policy applied by the ingress to the requests for this service, overriding the
+    /// policy configured in the ingress options. Set to `null` to remove the override.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_with::rust::double_option"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<CorsPolicy>"))]
+    pub cors: Option<Option<CorsPolicy>>,
+
+    /// # Retry policy
+    ///
+    /// Retry policy applied by the invoker to the invocations of this service, overriding the
+    /// retry policy set in invoker options. Set to `null` to remove the override.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_with::rust::double_option"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<InvocationRetryPolicy>"))]
+    pub retry_policy: Option<Option<InvocationRetryPolicy>>,
+
+    /// # Handler retry policies
+    ///
+    /// Retry policies of individual handlers, keyed by handler name. These take precedence
+    /// over the service retry policy. Set a handler to `null` to remove its override.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub handler_retry_policies: HashMap<String, Option<InvocationRetryPolicy>>,
 }
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
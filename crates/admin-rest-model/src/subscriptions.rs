@@ -26,6 +26,8 @@ pub struct CreateSubscriptionRequest {
     /// Source uri. Accepted forms:
     ///
     /// * `kafka://<cluster_name>/<topic_name>`, e.g. `kafka://my-cluster/my-topic`
+    /// * `service://<service_name>/<handler_name>`, e.g. `service://Counter/count`, to publish
+    ///   the output of the handler to the Kafka topic of the sink
     #[serde_as(as = "serde_with::DisplayFromStr")]
     #[cfg_attr(feature = "schema", schemars(with = "String"))]
     pub source: Uri,
@@ -34,12 +36,49 @@ pub struct CreateSubscriptionRequest {
     /// Sink uri. Accepted forms:
     ///
     /// * `service://<service_name>/<service_name>`, e.g. `service://Counter/count`
+    /// * `kafka://<cluster_name>/<topic_name>`, e.g. `kafka://my-cluster/my-topic`, for service
+    ///   sources
     #[serde_as(as = "serde_with::DisplayFromStr")]
     #[cfg_attr(feature = "schema", schemars(with = "String"))]
     pub sink: Uri,
     /// # Options
     ///
-    /// Additional options to apply to the subscription.
+    /// Additional options to apply to the subscription. Options starting with `restate.` configure
+    /// Restate, all the other options are passed to the Kafka client:
+    ///
+    /// * `restate.error-policy`: what to do with records which cannot be turned into invocations.
+    ///   One of `fail` (default, stops the subscription), `skip`, `dead-letter` or `park`.
+    ///   Parked records can be inspected in `sys_kafka_parked_record` and replayed with
+    ///   `POST /subscriptions/{subscription}/parked-records/replay`.
+    /// * `restate.dead-letter-topic`: topic on the same cluster to publish records to with the
+    ///   `dead-letter` error policy.
+    /// * `restate.value-format`: encoding of the record values. One of `raw` (default, values are
+    ///   passed as is), `avro` or `protobuf`. Avro and Protobuf values must be in the Confluent
+    ///   wire format, and are converted to JSON using their schema in the schema registry.
+    ///   Records which cannot be decoded are handled by the error policy with their raw value,
+    ///   hence replayed parked records are not converted.
+    /// * `restate.schema-registry-url`: URL of the schema registry, required by the `avro` and
+    ///   `protobuf` value formats.
+    /// * `restate.schema-registry-basic-auth`: `user:password` to authenticate to the schema
+    ///   registry with.
+    pub options: Option<HashMap<String, String>>,
+}
+
+#[serde_as]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateSubscriptionRequest {
+    /// # Sink
+    ///
+    /// New sink uri, in the same forms accepted when creating the subscription. The sink must
+    /// be of the same kind as before. If not provided, the sink is left unchanged.
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+    pub sink: Option<Uri>,
+    /// # Options
+    ///
+    /// New options of the subscription, replacing all the previous ones. If not provided, the
+    /// options are left unchanged.
     pub options: Option<HashMap<String, String>>,
 }
 
@@ -50,6 +89,9 @@ pub struct SubscriptionResponse {
     pub source: String,
     pub sink: String,
     pub options: HashMap<String, String>,
+    /// Whether the subscription is paused
+    #[serde(default)]
+    pub paused: bool,
 }
 
 impl From<Subscription> for SubscriptionResponse {
@@ -59,6 +101,7 @@ impl From<Subscription> for SubscriptionResponse {
             source: value.source().to_string(),
             sink: value.sink().to_string(),
             options: value.metadata().clone(),
+            paused: value.is_paused(),
         }
     }
 }
@@ -75,3 +118,49 @@ pub struct ListSubscriptionsParams {
 pub struct ListSubscriptionsResponse {
     pub subscriptions: Vec<SubscriptionResponse>,
 }
+
+/// Where to move the consumer group of a subscription to. The records from the new offsets on
+/// are processed again, even if they were processed before the reset.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "to", rename_all = "snake_case")]
+pub enum ResetSubscriptionOffsetsRequest {
+    /// Oldest record retained by each partition
+    Earliest,
+    /// Next record appended to each partition
+    Latest,
+    /// First record of each partition whose timestamp is greater than or equal to the given one
+    Timestamp {
+        /// Milliseconds since the Unix epoch
+        timestamp: i64,
+    },
+    /// Explicit offsets. Partitions which are not listed are left untouched.
+    Offsets {
+        /// Offset of the next record to process, by partition
+        offsets: HashMap<i32, i64>,
+    },
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SubscriptionStatusResponse {
+    pub id: SubscriptionId,
+    pub paused: bool,
+    /// Offsets of the consumer group of the subscription, by partition of the source topic
+    pub partitions: Vec<PartitionOffsetsResponse>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PartitionOffsetsResponse {
+    pub topic: String,
+    pub partition: i32,
+    /// Offset of the next record the consumer group will process. Missing if the consumer group
+    /// didn't commit any offset for this partition yet.
+    pub committed_offset: Option<i64>,
+    /// Offset of the next record appended to the partition
+    pub high_watermark: i64,
+    /// Number of records which were not committed yet. Missing if the consumer group didn't
+    /// commit any offset for this partition yet.
+    pub lag: Option<i64>,
+}
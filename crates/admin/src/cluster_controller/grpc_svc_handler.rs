@@ -23,6 +23,7 @@ use restate_types::identifiers::PartitionId;
 use restate_types::logs::metadata::{Logs, SegmentIndex};
 use restate_types::logs::{LogId, Lsn, SequenceNumber};
 use restate_types::metadata_store::keys::{BIFROST_CONFIG_KEY, NODES_CONFIG_KEY};
+use restate_types::net::partition_processor_manager::SnapshotSummary;
 use restate_types::nodes_config::NodesConfiguration;
 use restate_types::storage::{StorageCodec, StorageEncode};
 use restate_types::{Version, Versioned};
@@ -32,13 +33,15 @@ use crate::cluster_controller::protobuf::{
     ClusterStateRequest, ClusterStateResponse, CreatePartitionSnapshotRequest,
     CreatePartitionSnapshotResponse, DescribeLogRequest, DescribeLogResponse, FindTailRequest,
     FindTailResponse, ListLogsRequest, ListLogsResponse, ListNodesRequest, ListNodesResponse,
-    SealAndExtendChainRequest, SealAndExtendChainResponse, SealedSegment, TailState,
-    TrimLogRequest,
+    ListPartitionSnapshotsRequest, ListPartitionSnapshotsResponse, PartitionSnapshot,
+    PrunePartitionSnapshotsRequest, PrunePartitionSnapshotsResponse, SealAndExtendChainRequest,
+    SealAndExtendChainResponse, SealedSegment, TailState, TrimLogRequest,
 };
 
 use super::protobuf::{
-    GetClusterConfigurationRequest, GetClusterConfigurationResponse,
-    SetClusterConfigurationRequest, SetClusterConfigurationResponse,
+    GetClusterConfigurationRequest, GetClusterConfigurationResponse, MergePartitionsRequest,
+    MergePartitionsResponse, SetClusterConfigurationRequest, SetClusterConfigurationResponse,
+    SplitPartitionRequest, SplitPartitionResponse,
 };
 use super::service::ChainExtension;
 use super::ClusterControllerHandle;
@@ -211,6 +214,61 @@ impl ClusterCtrlSvc for ClusterCtrlSvcHandler {
         }
     }
 
+    /// Lists the snapshots of a partition, as requested by `restatectl snapshots list`.
+    async fn list_partition_snapshots(
+        &self,
+        request: Request<ListPartitionSnapshotsRequest>,
+    ) -> Result<Response<ListPartitionSnapshotsResponse>, Status> {
+        let request = request.into_inner();
+        let partition_id = PartitionId::from(
+            u16::try_from(request.partition_id)
+                .map_err(|id| Status::invalid_argument(format!("Invalid partition id: {id}")))?,
+        );
+
+        match self
+            .controller_handle
+            .list_partition_snapshots(partition_id)
+            .await
+            .map_err(|_| Status::aborted("Node is shutting down"))?
+        {
+            Err(err) => {
+                info!("Failed listing partition snapshots: {err}");
+                Err(Status::internal(err.to_string()))
+            }
+            Ok(snapshots) => Ok(Response::new(ListPartitionSnapshotsResponse {
+                snapshots: snapshots.into_iter().map(Into::into).collect(),
+            })),
+        }
+    }
+
+    /// Deletes the snapshots of a partition which fall outside the configured retention policy,
+    /// as requested by `restatectl snapshots prune`.
+    async fn prune_partition_snapshots(
+        &self,
+        request: Request<PrunePartitionSnapshotsRequest>,
+    ) -> Result<Response<PrunePartitionSnapshotsResponse>, Status> {
+        let request = request.into_inner();
+        let partition_id = PartitionId::from(
+            u16::try_from(request.partition_id)
+                .map_err(|id| Status::invalid_argument(format!("Invalid partition id: {id}")))?,
+        );
+
+        match self
+            .controller_handle
+            .prune_partition_snapshots(partition_id, request.dry_run)
+            .await
+            .map_err(|_| Status::aborted("Node is shutting down"))?
+        {
+            Err(err) => {
+                info!("Failed pruning partition snapshots: {err}");
+                Err(Status::internal(err.to_string()))
+            }
+            Ok(snapshots) => Ok(Response::new(PrunePartitionSnapshotsResponse {
+                pruned_snapshots: snapshots.into_iter().map(Into::into).collect(),
+            })),
+        }
+    }
+
     async fn seal_and_extend_chain(
         &self,
         request: Request<SealAndExtendChainRequest>,
@@ -354,6 +412,64 @@ impl ClusterCtrlSvc for ClusterCtrlSvcHandler {
 
         Ok(Response::new(SetClusterConfigurationResponse {}))
     }
+
+    /// Splits a partition into two, as requested by `restatectl partitions split`.
+    async fn split_partition(
+        &self,
+        request: Request<SplitPartitionRequest>,
+    ) -> Result<Response<SplitPartitionResponse>, Status> {
+        let request = request.into_inner();
+        let partition_id = to_partition_id(request.partition_id)?;
+
+        let partition_ids = self
+            .controller_handle
+            .split_partition(partition_id, request.split_key)
+            .await
+            .map_err(|_| Status::aborted("Node is shutting down"))?
+            .map_err(|err| {
+                info!("Failed splitting partition {partition_id}: {err}");
+                Status::internal(err.to_string())
+            })?;
+
+        Ok(Response::new(SplitPartitionResponse {
+            partition_ids: partition_ids.into_iter().map(u32::from).collect(),
+        }))
+    }
+
+    /// Merges two adjacent partitions, as requested by `restatectl partitions merge`.
+    async fn merge_partitions(
+        &self,
+        request: Request<MergePartitionsRequest>,
+    ) -> Result<Response<MergePartitionsResponse>, Status> {
+        let request = request.into_inner();
+        let partition_id = to_partition_id(request.partition_id)?;
+        let other_partition_id = to_partition_id(request.other_partition_id)?;
+
+        let partition_ids = self
+            .controller_handle
+            .merge_partitions(partition_id, other_partition_id)
+            .await
+            .map_err(|_| Status::aborted("Node is shutting down"))?
+            .map_err(|err| {
+                info!("Failed merging partitions {partition_id} and {other_partition_id}: {err}");
+                Status::internal(err.to_string())
+            })?;
+
+        let partition_id = partition_ids
+            .into_iter()
+            .next()
+            .ok_or_else(|| Status::internal("merging did not create a new partition"))?;
+
+        Ok(Response::new(MergePartitionsResponse {
+            partition_id: u32::from(partition_id),
+        }))
+    }
+}
+
+fn to_partition_id(partition_id: u32) -> Result<PartitionId, Status> {
+    u16::try_from(partition_id)
+        .map(PartitionId::from)
+        .map_err(|_| Status::invalid_argument(format!("Invalid partition id: {partition_id}")))
 }
 
 fn serialize_value<T: StorageEncode>(value: T) -> Bytes {
@@ -361,3 +477,15 @@ fn serialize_value<T: StorageEncode>(value: T) -> Bytes {
     StorageCodec::encode(&value, &mut buf).expect("We can always serialize");
     buf.freeze()
 }
+
+impl From<SnapshotSummary> for PartitionSnapshot {
+    fn from(value: SnapshotSummary) -> Self {
+        PartitionSnapshot {
+            snapshot_id: value.snapshot_id.to_string(),
+            min_applied_lsn: value.min_applied_lsn.as_u64(),
+            created_at: value.created_at.as_u64(),
+            node_name: value.node_name,
+            is_latest: value.is_latest,
+        }
+    }
+}
@@ -13,7 +13,7 @@ mod nodeset_selection;
 use futures::never::Never;
 use rand::prelude::IteratorRandom;
 use rand::thread_rng;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::iter;
 use std::ops::Deref;
 use std::sync::Arc;
@@ -563,6 +563,9 @@ impl TryFrom<&LogletConfig> for LogletConfiguration {
                     .map(LogletConfiguration::Replicated)
                     .map_err(Into::into)
             }
+            ProviderKind::ObjectStore => Err(
+                "object-store loglets only serve offloaded segments and can't be written to".into(),
+            ),
         }
     }
 }
@@ -634,6 +637,10 @@ enum Event {
 struct LogsControllerInner {
     logs_state: HashMap<LogId, LogState, Xxh3Builder>,
     logs_write_in_progress: Option<Version>,
+    // Logs of the unfrozen partitions in the latest known partition table. Logs of partitions
+    // that are being split or merged, or that have been, must stay sealed. None until we have seen
+    // the first partition table.
+    partition_logs: Option<HashSet<LogId, Xxh3Builder>>,
 
     // We are storing the logs explicitly (not relying on Metadata::current()) because we need a fixed
     // snapshot to keep logs_state in sync.
@@ -647,6 +654,7 @@ impl LogsControllerInner {
             current_logs: Arc::new(Logs::with_logs_configuration(configuration)),
             logs_state: HashMap::with_hasher(Xxh3Builder::default()),
             logs_write_in_progress: None,
+            partition_logs: None,
             retry_policy,
         }
     }
@@ -743,6 +751,15 @@ impl LogsControllerInner {
         node_set_selector_hints: impl NodeSetSelectorHints,
     ) -> Result<()> {
         for (log_id, log_state) in &mut self.logs_state {
+            if self
+                .partition_logs
+                .as_ref()
+                .is_some_and(|partition_logs| !partition_logs.contains(log_id))
+            {
+                // don't extend the sealed logs of split or merged partitions
+                continue;
+            }
+
             log_state.try_reconfiguring(
                 self.current_logs.configuration(),
                 observed_cluster_state,
@@ -891,6 +908,14 @@ impl LogsControllerInner {
     }
 
     fn on_partition_table_update(&mut self, partition_table: &PartitionTable) {
+        self.partition_logs = Some(
+            partition_table
+                .partitions()
+                .filter(|(_, partition)| !partition.frozen)
+                .map(|(partition_id, _)| LogId::from(*partition_id))
+                .collect(),
+        );
+
         // update the provisioning logs
         for (partition_id, _) in partition_table.partitions() {
             self.logs_state
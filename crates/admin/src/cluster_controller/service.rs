@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod repartition;
 mod state;
 
 use std::num::NonZeroU16;
@@ -49,11 +50,13 @@ use restate_core::{
 use restate_types::cluster::cluster_state::ClusterState;
 use restate_types::config::{AdminOptions, Configuration};
 use restate_types::health::HealthStatus;
-use restate_types::identifiers::{PartitionId, SnapshotId};
+use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId};
 use restate_types::live::Live;
 use restate_types::logs::{LogId, Lsn};
 use restate_types::net::metadata::MetadataKind;
-use restate_types::net::partition_processor_manager::CreateSnapshotRequest;
+use restate_types::net::partition_processor_manager::{
+    CreateSnapshotRequest, ListSnapshotsRequest, PruneSnapshotsRequest, SnapshotSummary,
+};
 use restate_types::protobuf::common::AdminStatus;
 use restate_types::{GenerationalNodeId, Version, Versioned};
 
@@ -63,6 +66,7 @@ use super::protobuf::cluster_ctrl_svc_server::ClusterCtrlSvcServer;
 use crate::cluster_controller::logs_controller::{self, NodeSetSelectorHints};
 use crate::cluster_controller::observed_cluster_state::ObservedClusterState;
 use crate::cluster_controller::scheduler::SchedulingPlanNodeSetSelectorHints;
+use repartition::{Repartition, RepartitionTask};
 use state::ClusterControllerState;
 
 #[derive(Debug, thiserror::Error, CodedError)]
@@ -86,6 +90,9 @@ pub struct Service<T> {
     health_status: HealthStatus<AdminStatus>,
     heartbeat_interval: Interval,
     observed_cluster_state: ObservedClusterState,
+    /// Repartitioning operations run one at a time, since concurrent operations on overlapping
+    /// partitions could freeze a partition which the other operation then can't change anymore.
+    repartition_lock: Arc<tokio::sync::Mutex<()>>,
 }
 
 impl<T> Service<T>
@@ -145,6 +152,7 @@ where
             command_rx,
             heartbeat_interval,
             observed_cluster_state: ObservedClusterState::default(),
+            repartition_lock: Arc::default(),
         }
     }
 
@@ -179,6 +187,15 @@ enum ClusterControllerCommand {
         partition_id: PartitionId,
         response_tx: oneshot::Sender<anyhow::Result<SnapshotId>>,
     },
+    ListSnapshots {
+        partition_id: PartitionId,
+        response_tx: oneshot::Sender<anyhow::Result<Vec<SnapshotSummary>>>,
+    },
+    PruneSnapshots {
+        partition_id: PartitionId,
+        dry_run: bool,
+        response_tx: oneshot::Sender<anyhow::Result<Vec<SnapshotSummary>>>,
+    },
     UpdateClusterConfiguration {
         num_partitions: NonZeroU16,
         replication_strategy: ReplicationStrategy,
@@ -191,6 +208,10 @@ enum ClusterControllerCommand {
         extension: Option<ChainExtension>,
         response_tx: oneshot::Sender<anyhow::Result<SealedSegment>>,
     },
+    Repartition {
+        operation: Repartition,
+        response_tx: oneshot::Sender<anyhow::Result<Vec<PartitionId>>>,
+    },
 }
 
 pub struct ClusterControllerHandle {
@@ -244,6 +265,42 @@ impl ClusterControllerHandle {
         response_rx.await.map_err(|_| ShutdownError)
     }
 
+    pub async fn list_partition_snapshots(
+        &self,
+        partition_id: PartitionId,
+    ) -> Result<anyhow::Result<Vec<SnapshotSummary>>, ShutdownError> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        let _ = self
+            .tx
+            .send(ClusterControllerCommand::ListSnapshots {
+                partition_id,
+                response_tx,
+            })
+            .await;
+
+        response_rx.await.map_err(|_| ShutdownError)
+    }
+
+    pub async fn prune_partition_snapshots(
+        &self,
+        partition_id: PartitionId,
+        dry_run: bool,
+    ) -> Result<anyhow::Result<Vec<SnapshotSummary>>, ShutdownError> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        let _ = self
+            .tx
+            .send(ClusterControllerCommand::PruneSnapshots {
+                partition_id,
+                dry_run,
+                response_tx,
+            })
+            .await;
+
+        response_rx.await.map_err(|_| ShutdownError)
+    }
+
     pub async fn update_cluster_configuration(
         &self,
         num_partitions: NonZeroU16,
@@ -285,6 +342,49 @@ impl ClusterControllerHandle {
 
         response_rx.await.map_err(|_| ShutdownError)
     }
+
+    /// Splits the partition at `split_key` and returns the ids of the two new partitions.
+    pub async fn split_partition(
+        &self,
+        partition_id: PartitionId,
+        split_key: PartitionKey,
+    ) -> Result<anyhow::Result<Vec<PartitionId>>, ShutdownError> {
+        self.repartition(Repartition::Split {
+            partition_id,
+            split_key,
+        })
+        .await
+    }
+
+    /// Merges two partitions with adjacent key ranges and returns the id of the new partition.
+    pub async fn merge_partitions(
+        &self,
+        partition_id: PartitionId,
+        other_partition_id: PartitionId,
+    ) -> Result<anyhow::Result<Vec<PartitionId>>, ShutdownError> {
+        self.repartition(Repartition::Merge {
+            partition_id,
+            other_partition_id,
+        })
+        .await
+    }
+
+    async fn repartition(
+        &self,
+        operation: Repartition,
+    ) -> Result<anyhow::Result<Vec<PartitionId>>, ShutdownError> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        let _ = self
+            .tx
+            .send(ClusterControllerCommand::Repartition {
+                operation,
+                response_tx,
+            })
+            .await;
+
+        response_rx.await.map_err(|_| ShutdownError)
+    }
 }
 
 impl<T: TransportConnect> Service<T> {
@@ -380,33 +480,34 @@ impl<T: TransportConnect> Service<T> {
 
         Ok(())
     }
-    /// Triggers a snapshot creation for the given partition by issuing an RPC
-    /// to the node hosting the active leader.
-    async fn create_partition_snapshot(
-        &self,
-        partition_id: PartitionId,
-        response_tx: oneshot::Sender<anyhow::Result<SnapshotId>>,
-    ) {
+    /// Returns the node hosting the active leader of the given partition, if any.
+    fn find_partition_leader_node(&self, partition_id: PartitionId) -> Option<GenerationalNodeId> {
         let cluster_state = self.cluster_state_refresher.get_cluster_state();
 
         // For now, we just pick the leader node since we know that every partition is likely to
         // have one. We'll want to update the algorithm to be smart about scheduling snapshot tasks
         // in the future to avoid disrupting the leader when there are up-to-date followers.
-        let leader_node = cluster_state
+        cluster_state
             .alive_nodes()
-            .filter_map(|node| {
+            .find(|node| {
                 node.partitions
                     .get(&partition_id)
-                    .filter(|status| status.is_effective_leader())
-                    .map(|_| node)
-                    .cloned()
+                    .is_some_and(|status| status.is_effective_leader())
             })
-            .next();
+            .map(|node| node.generational_node_id)
+    }
 
-        match leader_node {
-            Some(node) => {
+    /// Triggers a snapshot creation for the given partition by issuing an RPC
+    /// to the node hosting the active leader.
+    async fn create_partition_snapshot(
+        &self,
+        partition_id: PartitionId,
+        response_tx: oneshot::Sender<anyhow::Result<SnapshotId>>,
+    ) {
+        match self.find_partition_leader_node(partition_id) {
+            Some(node_id) => {
                 debug!(
-                    node_id = %node.generational_node_id,
+                    %node_id,
                     ?partition_id,
                     "Asking node to snapshot partition"
                 );
@@ -416,11 +517,8 @@ impl<T: TransportConnect> Service<T> {
                     TaskKind::Disposable,
                     "create-snapshot-response",
                     async move {
-                        let _ = response_tx.send(
-                            node_rpc_client
-                                .create_snapshot(node.generational_node_id, partition_id)
-                                .await,
-                        );
+                        let _ = response_tx
+                            .send(node_rpc_client.create_snapshot(node_id, partition_id).await);
                         Ok(())
                     },
                 );
@@ -434,6 +532,62 @@ impl<T: TransportConnect> Service<T> {
         };
     }
 
+    /// Lists the snapshots of the given partition in the snapshot repository by issuing an RPC
+    /// to the node hosting the active leader.
+    async fn list_partition_snapshots(
+        &self,
+        partition_id: PartitionId,
+        response_tx: oneshot::Sender<anyhow::Result<Vec<SnapshotSummary>>>,
+    ) {
+        let Some(node_id) = self.find_partition_leader_node(partition_id) else {
+            let _ = response_tx.send(Err(anyhow::anyhow!(
+                "Can not find a suitable node to list snapshots of partition {partition_id}"
+            )));
+            return;
+        };
+
+        let mut node_rpc_client = self.processor_manager_client.clone();
+        let _ = TaskCenter::spawn_child(
+            TaskKind::Disposable,
+            "list-snapshots-response",
+            async move {
+                let _ =
+                    response_tx.send(node_rpc_client.list_snapshots(node_id, partition_id).await);
+                Ok(())
+            },
+        );
+    }
+
+    /// Prunes the snapshots of the given partition according to the configured retention policy,
+    /// by issuing an RPC to the node hosting the active leader.
+    async fn prune_partition_snapshots(
+        &self,
+        partition_id: PartitionId,
+        dry_run: bool,
+        response_tx: oneshot::Sender<anyhow::Result<Vec<SnapshotSummary>>>,
+    ) {
+        let Some(node_id) = self.find_partition_leader_node(partition_id) else {
+            let _ = response_tx.send(Err(anyhow::anyhow!(
+                "Can not find a suitable node to prune snapshots of partition {partition_id}"
+            )));
+            return;
+        };
+
+        let mut node_rpc_client = self.processor_manager_client.clone();
+        let _ = TaskCenter::spawn_child(
+            TaskKind::Disposable,
+            "prune-snapshots-response",
+            async move {
+                let _ = response_tx.send(
+                    node_rpc_client
+                        .prune_snapshots(node_id, partition_id, dry_run)
+                        .await,
+                );
+                Ok(())
+            },
+        );
+    }
+
     async fn update_cluster_configuration(
         &self,
         num_partitions: u16,
@@ -569,6 +723,29 @@ impl<T: TransportConnect> Service<T> {
         });
     }
 
+    fn repartition(
+        &self,
+        operation: Repartition,
+        response_tx: oneshot::Sender<anyhow::Result<Vec<PartitionId>>>,
+    ) {
+        let task = RepartitionTask {
+            operation,
+            bifrost: self.bifrost.clone(),
+            metadata_writer: self.metadata_writer.clone(),
+            metadata_store_client: self.metadata_store_client.clone(),
+            cluster_state_watcher: self.cluster_state_refresher.cluster_state_watcher(),
+            processor_manager_client: self.processor_manager_client.clone(),
+        };
+
+        let repartition_lock = Arc::clone(&self.repartition_lock);
+        _ = TaskCenter::spawn(TaskKind::Disposable, "repartition", async move {
+            let _running = repartition_lock.lock_owned().await;
+            let result = task.run().await;
+            _ = response_tx.send(result);
+            Ok(())
+        });
+    }
+
     async fn on_cluster_cmd(
         &self,
         command: ClusterControllerCommand,
@@ -598,6 +775,22 @@ impl<T: TransportConnect> Service<T> {
                 self.create_partition_snapshot(partition_id, response_tx)
                     .await;
             }
+            ClusterControllerCommand::ListSnapshots {
+                partition_id,
+                response_tx,
+            } => {
+                self.list_partition_snapshots(partition_id, response_tx)
+                    .await;
+            }
+            ClusterControllerCommand::PruneSnapshots {
+                partition_id,
+                dry_run,
+                response_tx,
+            } => {
+                info!(?partition_id, dry_run, "Prune snapshots command received");
+                self.prune_partition_snapshots(partition_id, dry_run, response_tx)
+                    .await;
+            }
             ClusterControllerCommand::UpdateClusterConfiguration {
                 num_partitions,
                 replication_strategy,
@@ -619,6 +812,13 @@ impl<T: TransportConnect> Service<T> {
                 extension,
                 response_tx,
             } => self.seal_and_extend_chain(log_id, min_version, extension, response_tx),
+            ClusterControllerCommand::Repartition {
+                operation,
+                response_tx,
+            } => {
+                info!(?operation, "Repartition command received");
+                self.repartition(operation, response_tx)
+            }
         }
     }
 }
@@ -672,6 +872,8 @@ where
 {
     network_sender: N,
     create_snapshot_router: RpcRouter<CreateSnapshotRequest>,
+    list_snapshots_router: RpcRouter<ListSnapshotsRequest>,
+    prune_snapshots_router: RpcRouter<PruneSnapshotsRequest>,
 }
 
 impl<N> PartitionProcessorManagerClient<N>
@@ -680,10 +882,14 @@ where
 {
     pub fn new(network_sender: N, router_builder: &mut MessageRouterBuilder) -> Self {
         let create_snapshot_router = RpcRouter::new(router_builder);
+        let list_snapshots_router = RpcRouter::new(router_builder);
+        let prune_snapshots_router = RpcRouter::new(router_builder);
 
         PartitionProcessorManagerClient {
             network_sender,
             create_snapshot_router,
+            list_snapshots_router,
+            prune_snapshots_router,
         }
     }
 
@@ -707,6 +913,50 @@ where
             .result
             .map_err(|e| anyhow!("Failed to create snapshot: {:?}", e))
     }
+
+    pub async fn list_snapshots(
+        &mut self,
+        node_id: GenerationalNodeId,
+        partition_id: PartitionId,
+    ) -> anyhow::Result<Vec<SnapshotSummary>> {
+        let response = tokio::time::timeout(
+            Duration::from_secs(30),
+            self.list_snapshots_router.call(
+                &self.network_sender,
+                node_id,
+                ListSnapshotsRequest { partition_id },
+            ),
+        )
+        .await?;
+        response?
+            .into_body()
+            .result
+            .map_err(|e| anyhow!("Failed to list snapshots: {:?}", e))
+    }
+
+    pub async fn prune_snapshots(
+        &mut self,
+        node_id: GenerationalNodeId,
+        partition_id: PartitionId,
+        dry_run: bool,
+    ) -> anyhow::Result<Vec<SnapshotSummary>> {
+        let response = tokio::time::timeout(
+            Duration::from_secs(90),
+            self.prune_snapshots_router.call(
+                &self.network_sender,
+                node_id,
+                PruneSnapshotsRequest {
+                    partition_id,
+                    dry_run,
+                },
+            ),
+        )
+        .await?;
+        response?
+            .into_body()
+            .result
+            .map_err(|e| anyhow!("Failed to prune snapshots: {:?}", e))
+    }
 }
 
 struct SealAndExtendTask {
@@ -781,6 +1031,9 @@ impl SealAndExtendTask {
                     Some(replicated_loglet_params),
                 )
             }
+            ProviderKind::ObjectStore => {
+                anyhow::bail!("the tail segment of a chain can't be an object-store loglet")
+            }
         };
 
         let (provider, params) = match &logs.configuration().default_provider {
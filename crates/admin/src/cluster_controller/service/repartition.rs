@@ -0,0 +1,536 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, info};
+
+use restate_bifrost::{Bifrost, BifrostAdmin};
+use restate_core::metadata_store::{MetadataStoreClient, ReadModifyWriteError};
+use restate_core::network::{Networking, TransportConnect};
+use restate_core::{Metadata, MetadataWriter};
+use restate_types::cluster_controller::SchedulingPlan;
+use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId};
+use restate_types::logs::{LogId, Lsn, SequenceNumber};
+use restate_types::metadata_store::keys::{PARTITION_TABLE_KEY, SCHEDULING_PLAN_KEY};
+use restate_types::partition_table::{self, PartitionTable, PartitionTableBuilder};
+
+use super::PartitionProcessorManagerClient;
+use crate::cluster_controller::cluster_state_refresher::ClusterStateWatcher;
+
+/// How long we wait for the leader of a split or merged partition to apply its sealed log and to
+/// create the snapshot from which the new partitions bootstrap.
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Changes the key ranges of the running partitions.
+#[derive(Debug, Clone, Copy)]
+pub enum Repartition {
+    /// Splits the partition at `split_key` into two new partitions.
+    Split {
+        partition_id: PartitionId,
+        split_key: PartitionKey,
+    },
+    /// Merges two partitions with adjacent key ranges into a new partition.
+    Merge {
+        partition_id: PartitionId,
+        other_partition_id: PartitionId,
+    },
+}
+
+impl Repartition {
+    fn parents(&self) -> Vec<PartitionId> {
+        match self {
+            Repartition::Split { partition_id, .. } => vec![*partition_id],
+            Repartition::Merge {
+                partition_id,
+                other_partition_id,
+            } => vec![*partition_id, *other_partition_id],
+        }
+    }
+
+    fn apply(
+        &self,
+        builder: &mut PartitionTableBuilder,
+    ) -> Result<(), partition_table::BuilderError> {
+        match self {
+            Repartition::Split {
+                partition_id,
+                split_key,
+            } => builder
+                .split_partition(*partition_id, *split_key)
+                .map(|_| ()),
+            Repartition::Merge {
+                partition_id,
+                other_partition_id,
+            } => builder
+                .merge_partitions(*partition_id, *other_partition_id)
+                .map(|_| ()),
+        }
+    }
+
+    /// Returns the partitions which have been created by this operation if the given partition
+    /// table already reflects it.
+    fn children(&self, partition_table: &PartitionTable) -> Option<Vec<PartitionId>> {
+        let parents = self.parents();
+
+        if parents
+            .iter()
+            .any(|parent| partition_table.contains_partition(parent))
+        {
+            return None;
+        }
+
+        let children: Vec<_> = partition_table
+            .partitions()
+            .filter(|(_, partition)| partition.parents == parents)
+            .map(|(partition_id, _)| *partition_id)
+            .collect();
+
+        (!children.is_empty()).then_some(children)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum UpdateError {
+    #[error("the partition table has not been initialized yet")]
+    MissingPartitionTable,
+    #[error("the partition table already reflects the requested repartitioning")]
+    AlreadyApplied,
+    #[error("the scheduling plan is unchanged")]
+    Unchanged,
+    #[error(transparent)]
+    Builder(#[from] partition_table::BuilderError),
+}
+
+/// Splits or merges partitions while the cluster is running.
+///
+/// The split or merged partitions (the parents) are replaced by new partitions (the children)
+/// which only start once the parents have reached a consistent cut, see [`ConsistentCut`]. Once
+/// the leader of each parent has applied its log up to the sealed tail, it creates a snapshot. The
+/// children bootstrap their partition stores from these snapshots. Finally, the parents are
+/// removed from the scheduling plan which stops their partition processors.
+///
+/// Running the same operation again after a failure resumes it.
+pub(super) struct RepartitionTask<T> {
+    pub(super) operation: Repartition,
+    pub(super) bifrost: Bifrost,
+    pub(super) metadata_writer: MetadataWriter,
+    pub(super) metadata_store_client: MetadataStoreClient,
+    pub(super) cluster_state_watcher: ClusterStateWatcher,
+    pub(super) processor_manager_client: PartitionProcessorManagerClient<Networking<T>>,
+}
+
+impl<T: TransportConnect> RepartitionTask<T> {
+    pub(super) async fn run(mut self) -> anyhow::Result<Vec<PartitionId>> {
+        let parents = self.operation.parents();
+
+        info!(
+            ?parents,
+            "Repartitioning, waiting for the parent partitions to reach a consistent cut"
+        );
+
+        let (children, tails) = ConsistentCut {
+            operation: &self.operation,
+            bifrost: &self.bifrost,
+            metadata_writer: &self.metadata_writer,
+            metadata_store_client: &self.metadata_store_client,
+        }
+        .run()
+        .await?;
+
+        for (parent, tail) in tails {
+            let snapshot_id = self.create_snapshot(parent, tail).await?;
+            debug!(
+                partition_id = %parent,
+                %snapshot_id,
+                %tail,
+                "Created snapshot at the tail of the sealed log"
+            );
+        }
+
+        self.remove_from_scheduling_plan(&parents).await?;
+
+        info!(?parents, ?children, "Repartitioning completed");
+
+        Ok(children)
+    }
+
+    /// Waits until the leader of the given partition has applied all records before `tail` and
+    /// then asks it to create a snapshot.
+    async fn create_snapshot(
+        &mut self,
+        partition_id: PartitionId,
+        tail: Lsn,
+    ) -> anyhow::Result<SnapshotId> {
+        tokio::time::timeout(
+            SNAPSHOT_TIMEOUT,
+            self.create_snapshot_inner(partition_id, tail),
+        )
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "timed out waiting for partition '{partition_id}' to create a snapshot at the \
+                    tail of its sealed log; is a snapshot repository configured?"
+            )
+        })?
+    }
+
+    async fn create_snapshot_inner(
+        &mut self,
+        partition_id: PartitionId,
+        tail: Lsn,
+    ) -> anyhow::Result<SnapshotId> {
+        let mut cluster_state = self.cluster_state_watcher.current();
+
+        loop {
+            let caught_up_leader = cluster_state.alive_nodes().find_map(|node| {
+                node.partitions
+                    .get(&partition_id)
+                    .filter(|status| {
+                        status.is_effective_leader()
+                            && status
+                                .last_applied_log_lsn
+                                .is_some_and(|lsn| lsn.next() >= tail)
+                    })
+                    .map(|_| node.generational_node_id)
+            });
+
+            if let Some(node_id) = caught_up_leader {
+                match self
+                    .processor_manager_client
+                    .create_snapshot(node_id, partition_id)
+                    .await
+                {
+                    Ok(snapshot_id) => return Ok(snapshot_id),
+                    Err(err) => {
+                        debug!(%partition_id, %node_id, "Failed to create snapshot: {err}");
+                    }
+                }
+            }
+
+            cluster_state = self.cluster_state_watcher.next_cluster_state().await?;
+        }
+    }
+
+    async fn remove_from_scheduling_plan(&self, parents: &[PartitionId]) -> anyhow::Result<()> {
+        let result = self
+            .metadata_store_client
+            .read_modify_write(
+                SCHEDULING_PLAN_KEY.clone(),
+                |current: Option<SchedulingPlan>| {
+                    let mut builder = current.unwrap_or_default().into_builder();
+
+                    for parent in parents {
+                        builder.remove_partition(parent);
+                    }
+
+                    builder.build_if_modified().ok_or(UpdateError::Unchanged)
+                },
+            )
+            .await;
+
+        match result {
+            Ok(_) | Err(ReadModifyWriteError::FailedOperation(UpdateError::Unchanged)) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Moves the key ranges of the parents to the children such that every record of a parent
+/// precedes every record of its children, which preserves the order of the records of each key:
+///
+/// 1. The parents are frozen in the partition table so that the logs controller no longer extends
+///    their logs.
+/// 2. The logs of the parents are sealed. Writers which still route records to the parents can't
+///    append to them anymore.
+/// 3. Only then the partition table is updated so that new records are appended to the logs of
+///    the children.
+///
+/// Every step is idempotent. Once the partition table reflects the repartitioning, the logs of the
+/// parents are known to be sealed.
+struct ConsistentCut<'a> {
+    operation: &'a Repartition,
+    bifrost: &'a Bifrost,
+    metadata_writer: &'a MetadataWriter,
+    metadata_store_client: &'a MetadataStoreClient,
+}
+
+impl ConsistentCut<'_> {
+    /// Returns the children and the tails of the sealed logs of the parents.
+    async fn run(&self) -> anyhow::Result<(Vec<PartitionId>, Vec<(PartitionId, Lsn)>)> {
+        self.freeze_parents().await?;
+
+        let mut tails = Vec::new();
+        for parent in self.operation.parents() {
+            tails.push((parent, self.seal_log(parent).await?));
+        }
+
+        let children = self.update_partition_table().await?;
+
+        Ok((children, tails))
+    }
+
+    async fn freeze_parents(&self) -> anyhow::Result<()> {
+        let result = self
+            .metadata_store_client
+            .read_modify_write(
+                PARTITION_TABLE_KEY.clone(),
+                |current: Option<PartitionTable>| {
+                    let partition_table = current.ok_or(UpdateError::MissingPartitionTable)?;
+
+                    if self.operation.children(&partition_table).is_some() {
+                        return Err(UpdateError::AlreadyApplied);
+                    }
+
+                    // Freezing the parents and sealing their logs can't be rolled back, hence the
+                    // operation is validated before anything is changed
+                    self.operation
+                        .apply(&mut PartitionTableBuilder::from(partition_table.clone()))?;
+
+                    let mut builder = PartitionTableBuilder::from(partition_table);
+                    for parent in self.operation.parents() {
+                        builder.freeze_partition(parent)?;
+                    }
+                    builder.build_if_modified().ok_or(UpdateError::Unchanged)
+                },
+            )
+            .await;
+
+        match result {
+            Ok(partition_table) => {
+                self.metadata_writer
+                    .update(Arc::new(partition_table))
+                    .await?;
+                Ok(())
+            }
+            Err(ReadModifyWriteError::FailedOperation(
+                UpdateError::AlreadyApplied | UpdateError::Unchanged,
+            )) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn update_partition_table(&self) -> anyhow::Result<Vec<PartitionId>> {
+        let result = self
+            .metadata_store_client
+            .read_modify_write(
+                PARTITION_TABLE_KEY.clone(),
+                |current: Option<PartitionTable>| {
+                    let partition_table = current.ok_or(UpdateError::MissingPartitionTable)?;
+
+                    if self.operation.children(&partition_table).is_some() {
+                        return Err(UpdateError::AlreadyApplied);
+                    }
+
+                    let mut builder = PartitionTableBuilder::from(partition_table);
+                    self.operation.apply(&mut builder)?;
+                    Ok(builder.build())
+                },
+            )
+            .await;
+
+        let partition_table = match result {
+            Ok(partition_table) => {
+                let partition_table = Arc::new(partition_table);
+                self.metadata_writer
+                    .update(Arc::clone(&partition_table))
+                    .await?;
+                partition_table
+            }
+            Err(ReadModifyWriteError::FailedOperation(UpdateError::AlreadyApplied)) => {
+                let partition_table = self
+                    .metadata_store_client
+                    .get::<PartitionTable>(PARTITION_TABLE_KEY.clone())
+                    .await?
+                    .ok_or(UpdateError::MissingPartitionTable)?;
+                Arc::new(partition_table)
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(self
+            .operation
+            .children(&partition_table)
+            .expect("partition table reflects the repartitioning"))
+    }
+
+    /// Seals the log of the given partition and returns its tail. Sealing an already sealed log
+    /// returns the same tail.
+    async fn seal_log(&self, partition_id: PartitionId) -> anyhow::Result<Lsn> {
+        let log_id = LogId::from(partition_id);
+        let segment_index =
+            Metadata::with_current(|m| m.logs_ref().chain(&log_id).map(|chain| chain.tail_index()))
+                .ok_or_else(|| anyhow::anyhow!("unknown log '{log_id}'"))?;
+
+        let bifrost_admin = BifrostAdmin::new(
+            self.bifrost,
+            self.metadata_writer,
+            self.metadata_store_client,
+        );
+        let sealed_segment = bifrost_admin.seal(log_id, segment_index).await?;
+
+        Ok(sealed_segment.tail.offset())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use test_log::test;
+
+    use restate_bifrost::providers::memory_loglet;
+    use restate_bifrost::BifrostService;
+    use restate_core::{TaskCenter, TaskKind, TestCoreEnvBuilder};
+    use restate_types::logs::TailState;
+    use restate_types::net::metadata::MetadataKind;
+
+    #[test(restate_core::test(start_paused = true))]
+    async fn parent_log_is_sealed_before_children_take_over() -> anyhow::Result<()> {
+        let parent = PartitionId::from(0);
+        let parent_log = LogId::from(parent);
+
+        let builder = TestCoreEnvBuilder::with_incoming_only_connector();
+        let bifrost_svc = BifrostService::new().with_factory(memory_loglet::Factory::default());
+        let bifrost = bifrost_svc.handle();
+        let env = builder.build().await;
+        bifrost_svc.start().await?;
+
+        let split_key = *Metadata::with_current(|m| m.partition_table_ref())
+            .get_partition(&parent)
+            .expect("partition should exist")
+            .key_range
+            .end()
+            / 2;
+
+        // A writer that keeps appending to the parent, unaware of the repartitioning
+        let stale_appends = Arc::new(AtomicU64::new(0));
+        let mut stale_appender = bifrost.create_appender(parent_log)?;
+        let stale_writer = TaskCenter::spawn_unmanaged(TaskKind::Disposable, "stale-writer", {
+            let stale_appends = Arc::clone(&stale_appends);
+            async move {
+                while stale_appender.append("stale").await.is_ok() {
+                    stale_appends.fetch_add(1, Ordering::Relaxed);
+                    tokio::task::yield_now().await;
+                }
+            }
+        })?;
+        while stale_appends.load(Ordering::Relaxed) < 10 {
+            tokio::task::yield_now().await;
+        }
+
+        // Once the children appear in the partition table, the parent log must be sealed
+        let mut partition_table_watch = env.metadata.watch(MetadataKind::PartitionTable);
+        let parent_tail_on_takeover = async {
+            loop {
+                partition_table_watch.changed().await?;
+                if !Metadata::with_current(|m| m.partition_table_ref()).contains_partition(&parent)
+                {
+                    return anyhow::Ok(bifrost.find_tail(parent_log).await?);
+                }
+            }
+        };
+
+        let operation = Repartition::Split {
+            partition_id: parent,
+            split_key,
+        };
+        let cut = ConsistentCut {
+            operation: &operation,
+            bifrost: &bifrost,
+            metadata_writer: &env.metadata_writer,
+            metadata_store_client: &env.metadata_store_client,
+        };
+        let ((children, tails), parent_tail_on_takeover) =
+            tokio::try_join!(cut.run(), parent_tail_on_takeover)?;
+
+        assert_eq!(children.len(), 2);
+        let tail = tails
+            .into_iter()
+            .find_map(|(partition_id, tail)| (partition_id == parent).then_some(tail))
+            .expect("parent should have been sealed");
+        assert_eq!(parent_tail_on_takeover, TailState::Sealed(tail));
+
+        // The stale writer can't append to the parent anymore, all its records precede the cut
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        let appends_at_cut = stale_appends.load(Ordering::Relaxed);
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        assert_eq!(stale_appends.load(Ordering::Relaxed), appends_at_cut);
+        assert_eq!(
+            bifrost.find_tail(parent_log).await?,
+            TailState::Sealed(tail)
+        );
+        assert_eq!(tail, Lsn::new(appends_at_cut + 1));
+        stale_writer.abort();
+
+        // Resuming the operation finds the same cut
+        let (resumed_children, resumed_tails) = cut.run().await?;
+        assert_eq!(resumed_children, children);
+        assert_eq!(resumed_tails, vec![(parent, tail)]);
+
+        Ok(())
+    }
+
+    #[test(restate_core::test(start_paused = true))]
+    async fn invalid_operation_does_not_freeze_parents() -> anyhow::Result<()> {
+        let builder = TestCoreEnvBuilder::with_incoming_only_connector();
+        let bifrost_svc = BifrostService::new().with_factory(memory_loglet::Factory::default());
+        let bifrost = bifrost_svc.handle();
+        let env = builder.build().await;
+        bifrost_svc.start().await?;
+
+        let partition_table = Metadata::with_current(|m| m.partition_table_snapshot());
+        let (partition_id, partition) = partition_table
+            .partitions()
+            .next()
+            .expect("partition should exist");
+        let invalid_operations = [
+            Repartition::Split {
+                partition_id: *partition_id,
+                split_key: *partition.key_range.start(),
+            },
+            Repartition::Merge {
+                partition_id: *partition_id,
+                other_partition_id: *partition_id,
+            },
+        ];
+
+        for operation in invalid_operations {
+            let cut = ConsistentCut {
+                operation: &operation,
+                bifrost: &bifrost,
+                metadata_writer: &env.metadata_writer,
+                metadata_store_client: &env.metadata_store_client,
+            };
+            assert!(cut.run().await.is_err(), "{operation:?}");
+
+            let partition_table = env
+                .metadata_store_client
+                .get::<PartitionTable>(PARTITION_TABLE_KEY.clone())
+                .await?
+                .expect("partition table should exist");
+            assert!(
+                !partition_table
+                    .get_partition(partition_id)
+                    .expect("partition should exist")
+                    .frozen
+            );
+            assert!(!bifrost
+                .find_tail(LogId::from(*partition_id))
+                .await?
+                .is_sealed());
+        }
+
+        Ok(())
+    }
+}
@@ -9,20 +9,23 @@
 // by the Apache License, Version 2.0.
 
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
 use futures::future::OptionFuture;
 use itertools::Itertools;
 use tokio::sync::watch;
+use tokio::task::JoinSet;
 use tokio::time;
 use tokio::time::{Interval, MissedTickBehavior};
 use tracing::{debug, info, warn};
 
+use restate_bifrost::providers::object_store_loglet::SegmentOffloader;
 use restate_bifrost::{Bifrost, BifrostAdmin};
 use restate_core::metadata_store::MetadataStoreClient;
 use restate_core::network::TransportConnect;
-use restate_core::{my_node_id, Metadata, MetadataWriter};
+use restate_core::{my_node_id, Metadata, MetadataWriter, TaskCenterFutureExt};
 use restate_types::cluster::cluster_state::{AliveNode, NodeState};
-use restate_types::config::{AdminOptions, Configuration};
+use restate_types::config::{AdminOptions, Configuration, TieredStorageOptions};
 use restate_types::identifiers::PartitionId;
 use restate_types::logs::{LogId, Lsn, SequenceNumber};
 use restate_types::net::metadata::MetadataKind;
@@ -129,6 +132,7 @@ where
 #[derive(Debug)]
 pub enum LeaderEvent {
     TrimLogs,
+    OffloadSegments,
     LogsUpdate,
     PartitionTableUpdate,
 }
@@ -141,6 +145,11 @@ pub struct Leader<T> {
     partition_table_watcher: watch::Receiver<Version>,
     find_logs_tail_interval: Interval,
     log_trim_interval: Option<Interval>,
+    segment_offloader: Option<Arc<SegmentOffloader>>,
+    offload_interval: Option<Interval>,
+    /// Offloading segments can take a long time, so it runs in the background. Holds at most one
+    /// run, which is aborted once this node stops being the leader.
+    segment_offloads: JoinSet<Result<(), restate_bifrost::Error>>,
     logs_controller: LogsController,
     scheduler: Scheduler<T>,
     cluster_state_watcher: ClusterStateWatcher,
@@ -171,6 +180,8 @@ where
 
         let (log_trim_interval, log_trim_threshold) =
             create_log_trim_interval(&configuration.admin);
+        let (segment_offloader, offload_interval) =
+            create_segment_offloader(&configuration.bifrost.tiered_storage).unzip();
 
         let mut find_logs_tail_interval =
             time::interval(configuration.admin.log_tail_update_interval.into());
@@ -187,6 +198,9 @@ where
             find_logs_tail_interval,
             log_trim_interval,
             log_trim_threshold,
+            segment_offloader,
+            offload_interval,
+            segment_offloads: JoinSet::new(),
             logs_controller,
             scheduler,
         };
@@ -223,6 +237,8 @@ where
     fn reconfigure(&mut self, configuration: &Configuration) {
         (self.log_trim_interval, self.log_trim_threshold) =
             create_log_trim_interval(&configuration.admin);
+        (self.segment_offloader, self.offload_interval) =
+            create_segment_offloader(&configuration.bifrost.tiered_storage).unzip();
     }
 
     async fn run(&mut self) -> anyhow::Result<LeaderEvent> {
@@ -234,6 +250,16 @@ where
                 _ = OptionFuture::from(self.log_trim_interval.as_mut().map(|interval| interval.tick())) => {
                     return Ok(LeaderEvent::TrimLogs);
                 }
+                _ = OptionFuture::from(self.offload_interval.as_mut().map(|interval| interval.tick())) => {
+                    return Ok(LeaderEvent::OffloadSegments);
+                }
+                Some(result) = self.segment_offloads.join_next() => {
+                    match result {
+                        Ok(Ok(())) => debug!("Finished offloading sealed log segments"),
+                        Ok(Err(err)) => warn!("Could not offload sealed log segments to the object store: {err}"),
+                        Err(err) => warn!("Offloading sealed log segments to the object store failed: {err}"),
+                    }
+                }
                 result = self.logs_controller.run_async_operations() => {
                     result?;
                 }
@@ -253,6 +279,9 @@ where
             LeaderEvent::TrimLogs => {
                 self.trim_logs().await;
             }
+            LeaderEvent::OffloadSegments => {
+                self.offload_segments();
+            }
             LeaderEvent::LogsUpdate => {
                 self.on_logs_update().await?;
             }
@@ -295,6 +324,33 @@ where
         }
     }
 
+    /// Starts offloading the sealed segments in the background, unless the previous run is still
+    /// in progress. The result is reported by [`Self::run`].
+    fn offload_segments(&mut self) {
+        let Some(segment_offloader) = &self.segment_offloader else {
+            return;
+        };
+        if !self.segment_offloads.is_empty() {
+            debug!(
+                "Skip offloading sealed log segments because the previous run has not finished yet"
+            );
+            return;
+        }
+
+        let segment_offloader = Arc::clone(segment_offloader);
+        let bifrost = self.bifrost.clone();
+        let metadata_writer = self.metadata_writer.clone();
+        let metadata_store_client = self.metadata_store_client.clone();
+        self.segment_offloads.spawn(
+            async move {
+                let bifrost_admin =
+                    BifrostAdmin::new(&bifrost, &metadata_writer, &metadata_store_client);
+                segment_offloader.run(bifrost_admin).await
+            }
+            .in_current_tc(),
+        );
+    }
+
     async fn trim_logs_inner(&self) -> Result<(), restate_bifrost::Error> {
         let bifrost_admin = BifrostAdmin::new(
             &self.bifrost,
@@ -370,3 +426,26 @@ fn create_log_trim_interval(options: &AdminOptions) -> (Option<Interval>, Lsn) {
 
     (log_trim_interval, log_trim_threshold)
 }
+
+fn create_segment_offloader(
+    options: &TieredStorageOptions,
+) -> Option<(Arc<SegmentOffloader>, Interval)> {
+    let offload_interval = options.offload_interval?;
+
+    let segment_offloader = match SegmentOffloader::create(options) {
+        Ok(Some(segment_offloader)) => segment_offloader,
+        Ok(None) => {
+            warn!("Not offloading sealed log segments because no tiered storage destination is configured");
+            return None;
+        }
+        Err(err) => {
+            warn!("Not offloading sealed log segments: {err}");
+            return None;
+        }
+    };
+
+    let mut interval = tokio::time::interval(offload_interval.into());
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    Some((Arc::new(segment_offloader), interval))
+}
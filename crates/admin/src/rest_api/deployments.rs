@@ -20,8 +20,9 @@ use http::uri::Scheme;
 use okapi_operation::*;
 use restate_admin_rest_model::deployments::*;
 use restate_errors::warn_it;
-use restate_service_client::Endpoint;
+use restate_service_client::{validate_tls_options, Endpoint};
 use restate_service_protocol::discovery::DiscoverEndpoint;
+use restate_types::config::Configuration;
 use restate_types::identifiers::{DeploymentId, InvalidLambdaARN};
 use serde::Deserialize;
 
@@ -50,6 +51,7 @@ pub async fn create_deployment<V>(
             uri,
             additional_headers,
             use_http_11,
+            tls,
             force,
             dry_run,
         } => {
@@ -63,6 +65,21 @@ pub async fn create_deployment<V>(
                 ));
             }
 
+            if let Some(tls) = &tls {
+                // Validate the effective options, the overrides are applied on top of the ones
+                // of the node
+                let tls_options = Configuration::pinned()
+                    .common
+                    .service_client
+                    .http
+                    .tls
+                    .merge(tls);
+                tokio::task::spawn_blocking(move || validate_tls_options(&tls_options))
+                    .await
+                    .expect("loading the TLS certificate files does not panic")
+                    .map_err(|err| MetaApiError::InvalidField("tls", err.to_string()))?;
+            }
+
             let is_using_https = uri.scheme().unwrap() == &Scheme::HTTPS;
 
             (
@@ -80,6 +97,41 @@ pub async fn create_deployment<V>(
                         },
                     ),
                     additional_headers.unwrap_or_default().into(),
+                )
+                .with_tls(tls),
+                force,
+                dry_run,
+            )
+        }
+        RegisterDeploymentRequest::Unix {
+            socket_path,
+            additional_headers,
+            use_http_11,
+            force,
+            dry_run,
+        } => {
+            if !socket_path.is_absolute() {
+                return Err(MetaApiError::InvalidField(
+                    "socket_path",
+                    format!(
+                        "The provided socket path {} is not absolute, only absolute paths can be used.",
+                        socket_path.display()
+                    ),
+                ));
+            }
+
+            (
+                DiscoverEndpoint::new(
+                    Endpoint::Unix(
+                        socket_path,
+                        if use_http_11 {
+                            Some(http::Version::HTTP_11)
+                        } else {
+                            // By default, we use h2c like on HTTP
+                            Some(http::Version::HTTP_2)
+                        },
+                    ),
+                    additional_headers.unwrap_or_default().into(),
                 ),
                 force,
                 dry_run,
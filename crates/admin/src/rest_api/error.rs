@@ -9,7 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use crate::schema_registry::error::{
-    DeploymentError, SchemaError, SchemaRegistryError, ServiceError,
+    DeploymentError, SchemaError, SchemaRegistryError, ServiceError, SubscriptionError,
 };
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
@@ -20,7 +20,7 @@ use okapi_operation::okapi::map;
 use okapi_operation::okapi::openapi3::Responses;
 use okapi_operation::{okapi, Components, ToMediaTypes, ToResponses};
 use restate_core::ShutdownError;
-use restate_types::identifiers::{DeploymentId, SubscriptionId};
+use restate_types::identifiers::{DeploymentId, InvocationId, SubscriptionId};
 use restate_types::invocation::ServiceType;
 use schemars::JsonSchema;
 use serde::Serialize;
@@ -42,6 +42,14 @@ pub enum MetaApiError {
     },
     #[error("The requested subscription '{0}' does not exist")]
     SubscriptionNotFound(SubscriptionId),
+    #[error("The requested invocation '{0}' does not exist")]
+    InvocationNotFound(InvocationId),
+    #[error("Cannot {operation} the invocation '{invocation_id}' with status '{status}'")]
+    InvocationStatusConflict {
+        invocation_id: InvocationId,
+        operation: &'static str,
+        status: String,
+    },
     #[error("Cannot {0} for service type {1}")]
     UnsupportedOperation(&'static str, ServiceType),
     #[error(transparent)]
@@ -70,7 +78,9 @@ impl IntoResponse for MetaApiError {
             MetaApiError::ServiceNotFound(_)
             | MetaApiError::HandlerNotFound { .. }
             | MetaApiError::DeploymentNotFound(_)
-            | MetaApiError::SubscriptionNotFound(_) => StatusCode::NOT_FOUND,
+            | MetaApiError::SubscriptionNotFound(_)
+            | MetaApiError::InvocationNotFound(_) => StatusCode::NOT_FOUND,
+            MetaApiError::InvocationStatusConflict { .. } => StatusCode::CONFLICT,
             MetaApiError::InvalidField(_, _) | MetaApiError::UnsupportedOperation(_, _) => {
                 StatusCode::BAD_REQUEST
             }
@@ -79,7 +89,8 @@ impl IntoResponse for MetaApiError {
                 SchemaError::Override(_)
                 | SchemaError::Service(ServiceError::DifferentType { .. })
                 | SchemaError::Service(ServiceError::RemovedHandlers { .. })
-                | SchemaError::Deployment(DeploymentError::IncorrectId { .. }) => {
+                | SchemaError::Deployment(DeploymentError::IncorrectId { .. })
+                | SchemaError::Subscription(SubscriptionError::NotPaused(_)) => {
                     StatusCode::CONFLICT
                 }
                 SchemaError::Service(_) => StatusCode::BAD_REQUEST,
@@ -15,9 +15,18 @@ use crate::rest_api::create_envelope_header;
 use crate::state::AdminServiceState;
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
+use datafusion::arrow::array::{Array, AsArray};
+use datafusion::arrow::datatypes::UInt32Type;
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::TryStreamExt;
 use okapi_operation::*;
-use restate_types::identifiers::{InvocationId, WithPartitionKey};
-use restate_types::invocation::{InvocationTermination, PurgeInvocationRequest};
+use restate_types::deployment::PinnedDeployment;
+use restate_types::identifiers::{DeploymentId, InvocationId, WithPartitionKey};
+use restate_types::invocation::{
+    InvocationTermination, PauseInvocationRequest, PurgeInvocationRequest,
+    RestartInvocationRequest, ResumeInvocationRequest, RetryInvocationNowRequest,
+};
+use restate_types::service_protocol::ServiceProtocolVersion;
 use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
 use serde::Deserialize;
 use tracing::warn;
@@ -109,3 +118,381 @@ pub async fn delete_invocation<V>(
         Ok(StatusCode::ACCEPTED)
     }
 }
+
+/// Pause an invocation
+#[openapi(
+    summary = "Pause an invocation",
+    description = "Pause the given running invocation. A paused invocation keeps its journal, \
+    but it won't be executed nor retried until it's resumed. Completions received while paused \
+    are stored and replayed on resume.",
+    operation_id = "pause_invocation",
+    tags = "invocation",
+    parameters(path(
+        name = "invocation_id",
+        description = "Invocation identifier.",
+        schema = "std::string::String"
+    )),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn pause_invocation<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(invocation_id): Path<String>,
+) -> Result<StatusCode, MetaApiError> {
+    let invocation_id = invocation_id
+        .parse::<InvocationId>()
+        .map_err(|e| MetaApiError::InvalidField("invocation_id", e.to_string()))?;
+
+    append_invocation_command(
+        &state,
+        invocation_id,
+        Command::PauseInvocation(PauseInvocationRequest { invocation_id }),
+    )
+    .await
+}
+
+/// Resume an invocation
+#[openapi(
+    summary = "Resume an invocation",
+    description = "Resume the given paused invocation. The invocation is executed again, \
+    replaying its journal.",
+    operation_id = "resume_invocation",
+    tags = "invocation",
+    parameters(path(
+        name = "invocation_id",
+        description = "Invocation identifier.",
+        schema = "std::string::String"
+    )),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn resume_invocation<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(invocation_id): Path<String>,
+) -> Result<StatusCode, MetaApiError> {
+    let invocation_id = invocation_id
+        .parse::<InvocationId>()
+        .map_err(|e| MetaApiError::InvalidField("invocation_id", e.to_string()))?;
+
+    append_invocation_command(
+        &state,
+        invocation_id,
+        Command::ResumeInvocation(ResumeInvocationRequest { invocation_id }),
+    )
+    .await
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct RetryInvocationNowParams {
+    pub deployment: Option<String>,
+}
+
+/// Retry an invocation now
+#[openapi(
+    summary = "Retry an invocation now",
+    description = "Retry the given invocation right away, skipping the current retry backoff. \
+    If the invocation is not waiting for a retry, this has no effect, unless the invocation is paused: \
+    in this case it is resumed. Only invoked or paused invocations can be retried. \
+    Optionally, the invocation can be pinned to another deployment, \
+    which is used starting from the next attempt.",
+    operation_id = "retry_invocation_now",
+    tags = "invocation",
+    parameters(
+        path(
+            name = "invocation_id",
+            description = "Invocation identifier.",
+            schema = "std::string::String"
+        ),
+        query(
+            name = "deployment",
+            description = "Identifier of the deployment the invocation should be pinned to. \
+            The deployment must expose the service of the invocation, and it should be compatible \
+            with the entries already recorded in the journal.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        )
+    ),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn retry_invocation_now<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(invocation_id): Path<String>,
+    Query(RetryInvocationNowParams { deployment }): Query<RetryInvocationNowParams>,
+) -> Result<StatusCode, MetaApiError> {
+    let invocation_id = invocation_id
+        .parse::<InvocationId>()
+        .map_err(|e| MetaApiError::InvalidField("invocation_id", e.to_string()))?;
+
+    let invocation = find_invocation(&state, invocation_id).await?;
+    if let Some(invocation) = &invocation {
+        if !matches!(invocation.status.as_str(), "invoked" | "paused") {
+            return Err(MetaApiError::InvocationStatusConflict {
+                invocation_id,
+                operation: "retry",
+                status: invocation.status.clone(),
+            });
+        }
+    }
+
+    let pinned_deployment = resolve_pinned_deployment(&state, deployment, invocation.as_ref())?;
+
+    append_invocation_command(
+        &state,
+        invocation_id,
+        Command::RetryInvocationNow(RetryInvocationNowRequest {
+            invocation_id,
+            pinned_deployment,
+        }),
+    )
+    .await
+}
+
+/// Resolves the deployment an invocation should be pinned to, choosing the highest service
+/// protocol version supported by both the deployment and this server. The deployment must
+/// expose the handler of the invocation.
+fn resolve_pinned_deployment<V>(
+    state: &AdminServiceState<V>,
+    deployment: Option<String>,
+    invocation: Option<&InvocationRow>,
+) -> Result<Option<PinnedDeployment>, MetaApiError> {
+    let Some(deployment_id) = deployment else {
+        return Ok(None);
+    };
+    let deployment_id = deployment_id
+        .parse::<DeploymentId>()
+        .map_err(|e| MetaApiError::InvalidField("deployment", e.to_string()))?;
+    let (deployment, services) = state
+        .schema_registry
+        .get_deployment(deployment_id)
+        .ok_or(MetaApiError::DeploymentNotFound(deployment_id))?;
+    if let Some(invocation) = invocation {
+        let exposes_handler = services.iter().any(|service| {
+            service.name == invocation.service_name
+                && service
+                    .handlers
+                    .iter()
+                    .any(|handler| handler.name == invocation.handler_name)
+        });
+        if !exposes_handler {
+            return Err(MetaApiError::InvalidField(
+                "deployment",
+                format!(
+                    "deployment '{deployment_id}' doesn't expose the handler '{}/{}' of the invocation",
+                    invocation.service_name, invocation.handler_name
+                ),
+            ));
+        }
+    }
+    let service_protocol_version = ServiceProtocolVersion::choose_max_supported_version(
+        &deployment.metadata.supported_protocol_versions,
+    )
+    .ok_or_else(|| {
+        MetaApiError::InvalidField(
+            "deployment",
+            format!(
+                "deployment '{deployment_id}' doesn't support any of the service protocol versions of this server"
+            ),
+        )
+    })?;
+
+    Ok(Some(PinnedDeployment::new(
+        deployment_id,
+        service_protocol_version,
+    )))
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct RestartInvocationParams {
+    pub from: u32,
+    pub deployment: Option<String>,
+}
+
+/// Restart an invocation from a journal entry
+#[openapi(
+    summary = "Restart an invocation from a journal entry",
+    description = "Restart the given invocation, removing all the journal entries after the given entry index \
+    and replaying the journal from there. Only paused or suspended invocations can be restarted, \
+    running invocations must be paused first. The restart has no effect if any of the removed entries, \
+    other than sleeps, is still waiting for its completion. Side effects of the removed entries \
+    are not rolled back: calls, one way calls, state changes and completed promises remain in effect, \
+    and are executed again if the handler performs them again when replaying. \
+    Optionally, the invocation can be pinned to another deployment.",
+    operation_id = "restart_invocation",
+    tags = "invocation",
+    parameters(
+        path(
+            name = "invocation_id",
+            description = "Invocation identifier.",
+            schema = "std::string::String"
+        ),
+        query(
+            name = "from",
+            description = "Index of the last journal entry to keep. \
+            All the following entries are removed. The entry 0 is the input entry.",
+            required = true,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "u32",
+        ),
+        query(
+            name = "deployment",
+            description = "Identifier of the deployment the invocation should be pinned to. \
+            The deployment must expose the service of the invocation, and it should be compatible \
+            with the entries kept in the journal.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        )
+    ),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn restart_invocation<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(invocation_id): Path<String>,
+    Query(RestartInvocationParams { from, deployment }): Query<RestartInvocationParams>,
+) -> Result<StatusCode, MetaApiError> {
+    let invocation_id = invocation_id
+        .parse::<InvocationId>()
+        .map_err(|e| MetaApiError::InvalidField("invocation_id", e.to_string()))?;
+
+    let invocation = find_invocation(&state, invocation_id).await?;
+    if let Some(invocation) = &invocation {
+        if !matches!(invocation.status.as_str(), "paused" | "suspended") {
+            return Err(MetaApiError::InvocationStatusConflict {
+                invocation_id,
+                operation: "restart",
+                status: invocation.status.clone(),
+            });
+        }
+        if from >= invocation.journal_size {
+            return Err(MetaApiError::InvalidField(
+                "from",
+                format!(
+                    "the journal of the invocation has only {} entries",
+                    invocation.journal_size
+                ),
+            ));
+        }
+    }
+
+    let pinned_deployment = resolve_pinned_deployment(&state, deployment, invocation.as_ref())?;
+
+    append_invocation_command(
+        &state,
+        invocation_id,
+        Command::RestartInvocation(RestartInvocationRequest {
+            invocation_id,
+            journal_index: from,
+            pinned_deployment,
+        }),
+    )
+    .await
+}
+
+/// The columns of `sys_invocation_status` needed to validate the commands on an invocation
+struct InvocationRow {
+    status: String,
+    service_name: String,
+    handler_name: String,
+    journal_size: u32,
+}
+
+/// Looks up the invocation, failing if it doesn't exist. Returns `None` if invocations can't be
+/// queried, in which case the partition processor ignores invalid commands.
+async fn find_invocation<V>(
+    state: &AdminServiceState<V>,
+    invocation_id: InvocationId,
+) -> Result<Option<InvocationRow>, MetaApiError> {
+    let Some(query_context) = &state.query_context else {
+        return Ok(None);
+    };
+
+    let query_error = |err: datafusion::error::DataFusionError| {
+        MetaApiError::Internal(format!("Failed looking up the invocation: {err}"))
+    };
+    let batches: Vec<RecordBatch> = query_context
+        .execute(&format!(
+            "SELECT status, target_service_name, target_handler_name, journal_size \
+            FROM sys_invocation_status WHERE id = '{invocation_id}'"
+        ))
+        .await
+        .map_err(query_error)?
+        .try_collect()
+        .await
+        .map_err(query_error)?;
+
+    let batch = batches
+        .iter()
+        .find(|batch| batch.num_rows() > 0)
+        .ok_or(MetaApiError::InvocationNotFound(invocation_id))?;
+    let string = |column: usize| batch.column(column).as_string::<i64>().value(0).to_owned();
+    let journal_size = batch.column(3).as_primitive::<UInt32Type>();
+
+    Ok(Some(InvocationRow {
+        status: string(0),
+        service_name: string(1),
+        handler_name: string(2),
+        journal_size: if journal_size.is_null(0) {
+            0
+        } else {
+            journal_size.value(0)
+        },
+    }))
+}
+
+async fn append_invocation_command<V>(
+    state: &AdminServiceState<V>,
+    invocation_id: InvocationId,
+    cmd: Command,
+) -> Result<StatusCode, MetaApiError> {
+    let command_name = cmd.name();
+    let partition_key = invocation_id.partition_key();
+
+    let result = append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(create_envelope_header(partition_key), cmd)),
+    )
+    .await;
+
+    if let Err(err) = result {
+        warn!("Could not append {command_name} command to Bifrost: {err}");
+        Err(MetaApiError::Internal(format!(
+            "Failed sending {command_name} to the cluster."
+        )))
+    } else {
+        Ok(StatusCode::ACCEPTED)
+    }
+}
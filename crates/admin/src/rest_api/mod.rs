@@ -78,6 +78,22 @@ where
             "/invocations/:invocation_id",
             delete(openapi_handler!(invocations::delete_invocation)),
         )
+        .route(
+            "/invocations/:invocation_id/pause",
+            patch(openapi_handler!(invocations::pause_invocation)),
+        )
+        .route(
+            "/invocations/:invocation_id/resume",
+            patch(openapi_handler!(invocations::resume_invocation)),
+        )
+        .route(
+            "/invocations/:invocation_id/retry-now",
+            patch(openapi_handler!(invocations::retry_invocation_now)),
+        )
+        .route(
+            "/invocations/:invocation_id/restart",
+            patch(openapi_handler!(invocations::restart_invocation)),
+        )
         .route(
             "/subscriptions",
             post(openapi_handler!(subscriptions::create_subscription)),
@@ -94,6 +110,30 @@ where
             "/subscriptions/:subscription",
             delete(openapi_handler!(subscriptions::delete_subscription)),
         )
+        .route(
+            "/subscriptions/:subscription",
+            patch(openapi_handler!(subscriptions::update_subscription)),
+        )
+        .route(
+            "/subscriptions/:subscription/status",
+            get(openapi_handler!(subscriptions::get_subscription_status)),
+        )
+        .route(
+            "/subscriptions/:subscription/offsets/reset",
+            post(openapi_handler!(subscriptions::reset_subscription_offsets)),
+        )
+        .route(
+            "/subscriptions/:subscription/pause",
+            patch(openapi_handler!(subscriptions::pause_subscription)),
+        )
+        .route(
+            "/subscriptions/:subscription/resume",
+            patch(openapi_handler!(subscriptions::resume_subscription)),
+        )
+        .route(
+            "/subscriptions/:subscription/parked-records/replay",
+            post(openapi_handler!(subscriptions::replay_parked_records)),
+        )
         .route("/health", get(openapi_handler!(health::health)))
         .route("/version", get(openapi_handler!(version::version)))
         .finish_openapi("/openapi", "Admin API", env!("CARGO_PKG_VERSION"))
@@ -116,6 +116,9 @@ pub async fn modify_service<V>(
         workflow_completion_retention,
         inactivity_timeout,
         abort_timeout,
+        cors,
+        retry_policy,
+        handler_retry_policies,
     }): Json<ModifyServiceRequest>,
 ) -> Result<Json<ServiceMetadata>, MetaApiError> {
     let mut modify_request = vec![];
@@ -138,6 +141,18 @@ pub async fn modify_service<V>(
     if let Some(abort_timeout) = abort_timeout {
         modify_request.push(ModifyServiceChange::AbortTimeout(abort_timeout));
     }
+    if let Some(cors) = cors {
+        modify_request.push(ModifyServiceChange::Cors(cors));
+    }
+    if let Some(retry_policy) = retry_policy {
+        modify_request.push(ModifyServiceChange::RetryPolicy(retry_policy));
+    }
+    for (handler, retry_policy) in handler_retry_policies {
+        modify_request.push(ModifyServiceChange::HandlerRetryPolicy {
+            handler,
+            retry_policy,
+        });
+    }
 
     if modify_request.is_empty() {
         // No need to do anything
@@ -9,7 +9,10 @@
 // by the Apache License, Version 2.0.
 
 use super::error::*;
+use crate::rest_api::create_envelope_header;
+use crate::schema_registry::error::{SchemaError, SubscriptionError};
 use crate::state::AdminServiceState;
+use std::sync::Arc;
 
 use restate_admin_rest_model::subscriptions::*;
 use restate_types::schema::subscriptions::{ListSubscriptionFilter, SubscriptionValidator};
@@ -19,8 +22,14 @@ use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::{http, Json};
 use okapi_operation::*;
+use restate_core::Metadata;
 use restate_errors::warn_it;
+use restate_ingress_kafka::{OffsetReset, PartitionOffsets};
+use restate_types::config::Configuration;
 use restate_types::identifiers::SubscriptionId;
+use restate_wal_protocol::kafka::ReplayParkedKafkaRecords;
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
+use tracing::warn;
 
 /// Create subscription.
 #[openapi(
@@ -82,6 +91,237 @@ pub async fn get_subscription<V>(
     Ok(SubscriptionResponse::from(subscription).into())
 }
 
+/// Update subscription.
+#[openapi(
+    summary = "Update subscription",
+    description = "Update the sink and/or the options of a subscription in place. The consumer of \
+    the subscription is restarted, resuming from the offsets committed by its consumer group.",
+    operation_id = "update_subscription",
+    tags = "subscription",
+    parameters(path(
+        name = "subscription",
+        description = "Subscription identifier",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn update_subscription<V: SubscriptionValidator>(
+    State(state): State<AdminServiceState<V>>,
+    Path(subscription_id): Path<SubscriptionId>,
+    #[request_body(required = true)] Json(payload): Json<UpdateSubscriptionRequest>,
+) -> Result<Json<SubscriptionResponse>, MetaApiError> {
+    let subscription = state
+        .schema_registry
+        .update_subscription(subscription_id, payload.sink, payload.options)
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    Ok(SubscriptionResponse::from(subscription).into())
+}
+
+/// Pause subscription.
+#[openapi(
+    summary = "Pause subscription",
+    description = "Pause the given subscription. A paused subscription stops consuming, or \
+    publishing to, its Kafka topic, but keeps the offsets committed by its consumer group.",
+    operation_id = "pause_subscription",
+    tags = "subscription",
+    parameters(path(
+        name = "subscription",
+        description = "Subscription identifier",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn pause_subscription<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(subscription_id): Path<SubscriptionId>,
+) -> Result<Json<SubscriptionResponse>, MetaApiError> {
+    let subscription = state
+        .schema_registry
+        .set_subscription_paused(subscription_id, true)
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    Ok(SubscriptionResponse::from(subscription).into())
+}
+
+/// Resume subscription.
+#[openapi(
+    summary = "Resume subscription",
+    description = "Resume the given paused subscription, from the offsets committed by its \
+    consumer group.",
+    operation_id = "resume_subscription",
+    tags = "subscription",
+    parameters(path(
+        name = "subscription",
+        description = "Subscription identifier",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn resume_subscription<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(subscription_id): Path<SubscriptionId>,
+) -> Result<Json<SubscriptionResponse>, MetaApiError> {
+    let subscription = state
+        .schema_registry
+        .set_subscription_paused(subscription_id, false)
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    Ok(SubscriptionResponse::from(subscription).into())
+}
+
+/// Get subscription status.
+#[openapi(
+    summary = "Get subscription status",
+    description = "Get the offsets committed by the consumer group of the subscription, together \
+    with the high watermarks and the lag of the partitions of its Kafka topic.",
+    operation_id = "get_subscription_status",
+    tags = "subscription",
+    parameters(path(
+        name = "subscription",
+        description = "Subscription identifier",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn get_subscription_status<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(subscription_id): Path<SubscriptionId>,
+) -> Result<Json<SubscriptionStatusResponse>, MetaApiError> {
+    let subscription = state
+        .schema_registry
+        .get_subscription(subscription_id)
+        .ok_or_else(|| MetaApiError::SubscriptionNotFound(subscription_id))?;
+    if subscription.is_egress() {
+        return Err(MetaApiError::InvalidField(
+            "subscription",
+            "egress subscriptions don't consume a Kafka topic".to_owned(),
+        ));
+    }
+
+    let paused = subscription.is_paused();
+    // Fetching the offsets blocks until the Kafka brokers responded
+    let partition_offsets = tokio::task::spawn_blocking(move || {
+        restate_ingress_kafka::fetch_subscription_offsets(
+            &Configuration::pinned().ingress,
+            &subscription,
+        )
+    })
+    .await
+    .map_err(|err| MetaApiError::Internal(err.to_string()))?
+    .map_err(|err| {
+        warn!("Could not fetch the offsets of subscription {subscription_id}: {err:#}");
+        MetaApiError::Internal(format!(
+            "Failed fetching the offsets of the subscription from Kafka: {err:#}"
+        ))
+    })?;
+
+    Ok(status_response(subscription_id, paused, partition_offsets).into())
+}
+
+/// Reset subscription offsets.
+#[openapi(
+    summary = "Reset subscription offsets",
+    description = "Move the consumer group of a paused subscription to the earliest or latest \
+    offsets, to the offsets of a timestamp, or to explicit offsets. Once resumed, the subscription \
+    processes the records from the new offsets on, including the ones it processed before.",
+    operation_id = "reset_subscription_offsets",
+    tags = "subscription",
+    parameters(path(
+        name = "subscription",
+        description = "Subscription identifier",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn reset_subscription_offsets<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(subscription_id): Path<SubscriptionId>,
+    #[request_body(required = true)] Json(payload): Json<ResetSubscriptionOffsetsRequest>,
+) -> Result<Json<SubscriptionStatusResponse>, MetaApiError> {
+    let subscription = state
+        .schema_registry
+        .get_subscription(subscription_id)
+        .ok_or_else(|| MetaApiError::SubscriptionNotFound(subscription_id))?;
+    if subscription.is_egress() {
+        return Err(MetaApiError::InvalidField(
+            "subscription",
+            "egress subscriptions don't consume a Kafka topic".to_owned(),
+        ));
+    }
+
+    // Kafka rejects the commit while the consumer group is active, and a running consumer would
+    // deduplicate the records against the old bases
+    if !subscription.is_paused() {
+        return Err(
+            SchemaError::Subscription(SubscriptionError::NotPaused(subscription_id)).into(),
+        );
+    }
+
+    let reset = match payload {
+        ResetSubscriptionOffsetsRequest::Earliest => OffsetReset::Earliest,
+        ResetSubscriptionOffsetsRequest::Latest => OffsetReset::Latest,
+        ResetSubscriptionOffsetsRequest::Timestamp { timestamp } => {
+            OffsetReset::Timestamp(timestamp)
+        }
+        ResetSubscriptionOffsetsRequest::Offsets { offsets } => OffsetReset::Offsets(offsets),
+    };
+    // Committing the offsets blocks until the Kafka brokers responded
+    let partition_offsets = tokio::task::spawn_blocking({
+        let reset = reset.clone();
+        move || {
+            restate_ingress_kafka::reset_subscription_offsets(
+                &Configuration::pinned().ingress,
+                &subscription,
+                reset,
+            )
+        }
+    })
+    .await
+    .map_err(|err| MetaApiError::Internal(err.to_string()))?
+    .map_err(|err| {
+        warn!("Could not reset the offsets of subscription {subscription_id}: {err:#}");
+        MetaApiError::Internal(format!(
+            "Failed resetting the offsets of the subscription in Kafka: {err:#}"
+        ))
+    })?;
+
+    // Only once the new offsets are committed, the records of the reset partitions must no longer
+    // be deduplicated against the ones consumed before. If this fails, the reset can be retried.
+    let high_watermarks = partition_offsets
+        .iter()
+        .filter(|offsets| reset.resets_partition(offsets.partition))
+        .map(|offsets| (offsets.partition, offsets.high_watermark))
+        .collect();
+    let subscription = state
+        .schema_registry
+        .raise_subscription_deduplication_bases(subscription_id, high_watermarks)
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+    let paused = subscription.is_paused();
+
+    Ok(status_response(subscription_id, paused, partition_offsets).into())
+}
+
+fn status_response(
+    id: SubscriptionId,
+    paused: bool,
+    partition_offsets: Vec<PartitionOffsets>,
+) -> SubscriptionStatusResponse {
+    SubscriptionStatusResponse {
+        id,
+        paused,
+        partitions: partition_offsets
+            .into_iter()
+            .map(|offsets| PartitionOffsetsResponse {
+                topic: offsets.topic,
+                partition: offsets.partition,
+                committed_offset: offsets.committed_offset,
+                high_watermark: offsets.high_watermark,
+                lag: offsets.lag,
+            })
+            .collect(),
+    }
+}
+
 /// List subscriptions.
 #[openapi(
     summary = "List subscriptions",
@@ -166,3 +406,77 @@ pub async fn delete_subscription<V>(
         .inspect_err(|e| warn_it!(e))?;
     Ok(StatusCode::ACCEPTED)
 }
+
+/// Replay parked records of a subscription.
+#[openapi(
+    summary = "Replay parked records",
+    description = "Replay the Kafka records which the subscription parked because they could not be \
+    turned into invocations. The records are sent to the current sink of the subscription, \
+    records which fail again stay parked.",
+    operation_id = "replay_parked_records",
+    tags = "subscription",
+    parameters(path(
+        name = "subscription",
+        description = "Subscription identifier",
+        schema = "std::string::String"
+    )),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn replay_parked_records<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(subscription_id): Path<SubscriptionId>,
+) -> Result<StatusCode, MetaApiError> {
+    let subscription = state
+        .schema_registry
+        .get_subscription(subscription_id)
+        .ok_or_else(|| MetaApiError::SubscriptionNotFound(subscription_id))?;
+    if subscription.is_egress() {
+        return Err(MetaApiError::InvalidField(
+            "subscription",
+            "egress subscriptions don't park records".to_owned(),
+        ));
+    }
+
+    // Records are parked on the partition owning their Kafka partition, so every partition
+    // needs to replay its own
+    let partition_key_ranges: Vec<_> = Metadata::with_current(|m| {
+        m.partition_table_ref()
+            .partitions()
+            .map(|(_, partition)| partition.key_range.clone())
+            .collect()
+    });
+
+    for partition_key_range in partition_key_ranges {
+        let cmd = Command::ReplayParkedKafkaRecords(ReplayParkedKafkaRecords {
+            subscription_id,
+            sink: subscription.sink().clone(),
+            partition_key_range: partition_key_range.clone(),
+        });
+
+        let result = append_envelope_to_bifrost(
+            &state.bifrost,
+            Arc::new(Envelope::new(
+                create_envelope_header(*partition_key_range.start()),
+                cmd,
+            )),
+        )
+        .await;
+
+        if let Err(err) = result {
+            warn!("Could not append replay parked records command to Bifrost: {err}");
+            return Err(MetaApiError::Internal(
+                "Failed sending replay parked records to the cluster.".to_owned(),
+            ));
+        }
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
@@ -14,9 +14,10 @@ use restate_core::metadata_store::ReadModifyWriteError;
 use restate_core::ShutdownError;
 use restate_types::endpoint_manifest;
 use restate_types::errors::GenericError;
-use restate_types::identifiers::DeploymentId;
+use restate_types::identifiers::{DeploymentId, SubscriptionId};
 use restate_types::invocation::ServiceType;
 use restate_types::schema::invocation_target::BadInputContentType;
+use restate_types::schema::service::InvalidCorsPolicy;
 
 use crate::schema_registry::ServiceName;
 
@@ -105,29 +106,48 @@ pub enum ServiceError {
     #[error("modifying retention time for service type {0} is unsupported")]
     #[code(unknown)]
     CannotModifyRetentionTime(ServiceType),
+    #[error(transparent)]
+    #[code(unknown)]
+    BadCorsPolicy(#[from] InvalidCorsPolicy),
+    #[error("the service '{0}' has no handler named '{1}'")]
+    #[code(unknown)]
+    UnknownHandler(String, String),
 }
 
 #[derive(Debug, thiserror::Error, codederror::CodedError)]
 #[code(restate_errors::META0009)]
 pub enum SubscriptionError {
     #[error(
-        "invalid source URI '{0}': must have a scheme segment, with supported schemes: [kafka]."
+        "invalid source URI '{0}': must have a scheme segment, with supported schemes: [kafka, service]."
     )]
     InvalidSourceScheme(Uri),
     #[error("invalid source URI '{0}': source URI of Kafka type must have a authority segment containing the cluster name.")]
     InvalidKafkaSourceAuthority(Uri),
+    #[error("invalid source URI '{0}': source URI of service type must have a authority segment containing the service name.")]
+    InvalidServiceSourceAuthority(Uri),
+    #[error("invalid source URI '{0}': cannot find service/handler specified in the source URI.")]
+    SourceServiceNotFound(Uri),
 
     #[error(
-        "invalid sink URI '{0}': must have a scheme segment, with supported schemes: [service]."
+        "invalid sink URI '{0}': must have a scheme segment, with supported schemes: [service] for Kafka sources and [kafka] for service sources."
     )]
     InvalidSinkScheme(Uri),
     #[error("invalid sink URI '{0}': sink URI of service type must have a authority segment containing the service name.")]
     InvalidServiceSinkAuthority(Uri),
+    #[error("invalid sink URI '{0}': sink URI of Kafka type must have a authority segment containing the cluster name.")]
+    InvalidKafkaSinkAuthority(Uri),
     #[error("invalid sink URI '{0}': cannot find service/handler specified in the sink URI.")]
     SinkServiceNotFound(Uri),
     #[error("invalid sink URI '{0}': shared handlers cannot be used as sinks.")]
     InvalidSinkSharedHandler(Uri),
 
+    #[error(
+        "the offsets of subscription '{0}' can only be reset while the subscription is paused."
+    )]
+    NotPaused(SubscriptionId),
+    #[error("the group.id of subscription '{0}' cannot be changed, as the committed offsets belong to its consumer group.")]
+    GroupIdChanged(SubscriptionId),
+
     #[error(transparent)]
     #[code(unknown)]
     Validation(GenericError),
@@ -28,7 +28,9 @@ use restate_types::metadata_store::keys::SCHEMA_INFORMATION_KEY;
 use restate_types::schema::deployment::{
     DeliveryOptions, Deployment, DeploymentMetadata, DeploymentResolver,
 };
-use restate_types::schema::service::{HandlerMetadata, ServiceMetadata, ServiceMetadataResolver};
+use restate_types::schema::service::{
+    CorsPolicy, HandlerMetadata, InvocationRetryPolicy, ServiceMetadata, ServiceMetadataResolver,
+};
 use restate_types::schema::subscriptions::{
     ListSubscriptionFilter, Subscription, SubscriptionResolver, SubscriptionValidator,
 };
@@ -71,6 +73,15 @@ pub enum ModifyServiceChange {
     WorkflowCompletionRetention(Duration),
     InactivityTimeout(Duration),
     AbortTimeout(Duration),
+    /// Set or, if `None`, remove the CORS policy overriding the ingress default
+    Cors(Option<CorsPolicy>),
+    /// Set or, if `None`, remove the retry policy overriding the invoker default
+    RetryPolicy(Option<InvocationRetryPolicy>),
+    /// Set or, if `None`, remove the retry policy of a single handler
+    HandlerRetryPolicy {
+        handler: String,
+        retry_policy: Option<InvocationRetryPolicy>,
+    },
 }
 
 /// Responsible for updating the registered schema information. This includes the discovery of
@@ -119,13 +130,20 @@ impl<V> SchemaRegistry<V> {
                 uri.clone(),
                 discovered_metadata.protocol_type,
                 http_version,
-                DeliveryOptions::new(discovered_metadata.headers),
+                DeliveryOptions::new(discovered_metadata.headers, discovered_metadata.tls),
+                discovered_metadata.supported_protocol_versions,
+            ),
+            DiscoveredEndpoint::Unix(socket_path, http_version) => DeploymentMetadata::new_unix(
+                socket_path,
+                discovered_metadata.protocol_type,
+                http_version,
+                DeliveryOptions::new(discovered_metadata.headers, discovered_metadata.tls),
                 discovered_metadata.supported_protocol_versions,
             ),
             DiscoveredEndpoint::Lambda(arn, assume_role_arn) => DeploymentMetadata::new_lambda(
                 arn,
                 assume_role_arn,
-                DeliveryOptions::new(discovered_metadata.headers),
+                DeliveryOptions::new(discovered_metadata.headers, discovered_metadata.tls),
                 discovered_metadata.supported_protocol_versions,
             ),
         };
@@ -302,6 +320,69 @@ impl<V> SchemaRegistry<V> {
         Ok(())
     }
 
+    pub async fn set_subscription_paused(
+        &self,
+        subscription_id: SubscriptionId,
+        paused: bool,
+    ) -> Result<Subscription, SchemaRegistryError> {
+        let schema_information = self
+            .metadata_store_client
+            .read_modify_write(
+                SCHEMA_INFORMATION_KEY.clone(),
+                |schema_information: Option<Schema>| {
+                    let mut updater = SchemaUpdater::new(
+                        schema_information.unwrap_or_default(),
+                        self.experimental_feature_kafka_ingress_next,
+                    );
+                    updater.set_subscription_paused(subscription_id, paused)?;
+                    Ok::<_, SchemaError>(updater.into_inner())
+                },
+            )
+            .await?;
+
+        let subscription = schema_information
+            .get_subscription(subscription_id)
+            .expect("subscription was just updated");
+        self.metadata_writer
+            .update(Arc::new(schema_information))
+            .await?;
+
+        Ok(subscription)
+    }
+
+    pub async fn raise_subscription_deduplication_bases(
+        &self,
+        subscription_id: SubscriptionId,
+        high_watermarks: Vec<(i32, i64)>,
+    ) -> Result<Subscription, SchemaRegistryError> {
+        let schema_information = self
+            .metadata_store_client
+            .read_modify_write(
+                SCHEMA_INFORMATION_KEY.clone(),
+                |schema_information: Option<Schema>| {
+                    let mut updater = SchemaUpdater::new(
+                        schema_information.unwrap_or_default(),
+                        self.experimental_feature_kafka_ingress_next,
+                    );
+                    updater.raise_subscription_deduplication_bases(
+                        subscription_id,
+                        high_watermarks.iter().copied(),
+                    )?;
+                    Ok::<_, SchemaError>(updater.into_inner())
+                },
+            )
+            .await?;
+
+        let subscription = schema_information
+            .get_subscription(subscription_id)
+            .expect("subscription was just updated");
+        self.metadata_writer
+            .update(Arc::new(schema_information))
+            .await?;
+
+        Ok(subscription)
+    }
+
     pub fn list_services(&self) -> Vec<ServiceMetadata> {
         Metadata::with_current(|m| m.schema()).list_services()
     }
@@ -400,6 +481,43 @@ where
 
         Ok(subscription)
     }
+
+    pub(crate) async fn update_subscription(
+        &self,
+        subscription_id: SubscriptionId,
+        sink: Option<Uri>,
+        options: Option<HashMap<String, String>>,
+    ) -> Result<Subscription, SchemaRegistryError> {
+        let schema_information = self
+            .metadata_store_client
+            .read_modify_write(
+                SCHEMA_INFORMATION_KEY.clone(),
+                |schema_information: Option<Schema>| {
+                    let mut updater = SchemaUpdater::new(
+                        schema_information.unwrap_or_default(),
+                        self.experimental_feature_kafka_ingress_next,
+                    );
+                    updater.update_subscription(
+                        subscription_id,
+                        sink.clone(),
+                        options.clone(),
+                        &self.subscription_validator,
+                    )?;
+
+                    Ok::<_, SchemaError>(updater.into_inner())
+                },
+            )
+            .await?;
+
+        let subscription = schema_information
+            .get_subscription(subscription_id)
+            .expect("subscription was just updated");
+        self.metadata_writer
+            .update(Arc::new(schema_information))
+            .await?;
+
+        Ok(subscription)
+    }
 }
 
 /// Newtype for service names
@@ -133,7 +133,7 @@ impl SchemaUpdater {
         // Compute service schemas
         for (service_name, service) in proposed_services {
             let service_type = ServiceType::from(service.ty);
-            let handlers = DiscoveredHandlerMetadata::compute_handlers(
+            let mut handlers = DiscoveredHandlerMetadata::compute_handlers(
                 service
                     .handlers
                     .into_iter()
@@ -196,6 +196,14 @@ impl SchemaUpdater {
                     rpc.service = %service_name,
                     "Overwriting existing service schemas"
                 );
+                // Retry policies are configured by the operator, so they survive new revisions
+                for (handler_name, handler) in handlers.iter_mut() {
+                    handler.retry_policy = existing_service
+                        .handlers
+                        .get(handler_name)
+                        .and_then(|existing_handler| existing_handler.retry_policy.clone());
+                }
+
                 let mut service_schemas = existing_service.clone();
                 service_schemas.revision = existing_service.revision.wrapping_add(1);
                 service_schemas.ty = service_type;
@@ -223,6 +231,8 @@ impl SchemaUpdater {
                     },
                     inactivity_timeout: None,
                     abort_timeout: None,
+                    cors: None,
+                    retry_policy: None,
                     service_openapi_cache: Default::default(),
                     documentation: service.documentation,
                     metadata: service.metadata,
@@ -317,6 +327,36 @@ impl SchemaUpdater {
                     topic: topic_name.to_string(),
                 }
             }
+            Some("service") => {
+                let service_name = source
+                    .authority()
+                    .ok_or_else(|| {
+                        SchemaError::Subscription(SubscriptionError::InvalidServiceSourceAuthority(
+                            source.clone(),
+                        ))
+                    })?
+                    .as_str();
+                let handler_name = &source.path()[1..];
+
+                // The published output is the one of the given handler
+                if !self
+                    .schema_information
+                    .services
+                    .get(service_name)
+                    .is_some_and(|service_schemas| {
+                        service_schemas.handlers.contains_key(handler_name)
+                    })
+                {
+                    return Err(SchemaError::Subscription(
+                        SubscriptionError::SourceServiceNotFound(source),
+                    ));
+                }
+
+                Source::Service {
+                    name: service_name.to_owned(),
+                    handler: handler_name.to_owned(),
+                }
+            }
             _ => {
                 return Err(SchemaError::Subscription(
                     SubscriptionError::InvalidSourceScheme(source),
@@ -324,9 +364,124 @@ impl SchemaUpdater {
             }
         };
 
-        // Parse sink
-        let sink = match sink.scheme_str() {
-            Some("service") => {
+        let sink = self.parse_sink(&source, sink)?;
+
+        let subscription = validator
+            .validate(Subscription::new(
+                id,
+                source,
+                sink,
+                metadata.unwrap_or_default(),
+            ))
+            .map_err(|e| SchemaError::Subscription(SubscriptionError::Validation(e.into())))?;
+
+        self.schema_information
+            .subscriptions
+            .insert(id, subscription);
+        self.modified = true;
+
+        Ok(id)
+    }
+
+    pub fn update_subscription<V: SubscriptionValidator>(
+        &mut self,
+        id: SubscriptionId,
+        sink: Option<Uri>,
+        metadata: Option<HashMap<String, String>>,
+        validator: &V,
+    ) -> Result<(), SchemaError> {
+        let Some(subscription) = self.schema_information.subscriptions.get(&id) else {
+            return Err(SchemaError::NotFound(format!(
+                "subscription with id '{id}'"
+            )));
+        };
+
+        // The source can't change, as the committed offsets belong to it
+        let mut updated_subscription = subscription.clone();
+        if let Some(sink) = sink {
+            updated_subscription.set_sink(self.parse_sink(subscription.source(), sink)?);
+        }
+        if let Some(mut metadata) = metadata {
+            // The committed offsets belong to the consumer group, it can't change either
+            if matches!(subscription.source(), Source::Kafka { .. }) {
+                match (
+                    subscription.metadata().get("group.id"),
+                    metadata.get("group.id"),
+                ) {
+                    (Some(group_id), None) => {
+                        metadata.insert("group.id".to_owned(), group_id.clone());
+                    }
+                    (current, Some(group_id)) if current != Some(group_id) => {
+                        return Err(SchemaError::Subscription(
+                            SubscriptionError::GroupIdChanged(id),
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+            *updated_subscription.metadata_mut() = metadata;
+        }
+
+        let updated_subscription = validator
+            .validate(updated_subscription)
+            .map_err(|e| SchemaError::Subscription(SubscriptionError::Validation(e.into())))?;
+
+        self.schema_information
+            .subscriptions
+            .insert(id, updated_subscription);
+        self.modified = true;
+
+        Ok(())
+    }
+
+    pub fn set_subscription_paused(
+        &mut self,
+        id: SubscriptionId,
+        paused: bool,
+    ) -> Result<(), SchemaError> {
+        let Some(subscription) = self.schema_information.subscriptions.get_mut(&id) else {
+            return Err(SchemaError::NotFound(format!(
+                "subscription with id '{id}'"
+            )));
+        };
+
+        if subscription.is_paused() != paused {
+            subscription.set_paused(paused);
+            self.modified = true;
+        }
+
+        Ok(())
+    }
+
+    /// Raises the deduplication bases of the given Kafka partitions to their high watermarks,
+    /// such that the consumer group reprocesses the records it already consumed once its offsets
+    /// were moved back. See [`Subscription::raise_deduplication_base`].
+    pub fn raise_subscription_deduplication_bases(
+        &mut self,
+        id: SubscriptionId,
+        high_watermarks: impl IntoIterator<Item = (i32, i64)>,
+    ) -> Result<(), SchemaError> {
+        let Some(subscription) = self.schema_information.subscriptions.get_mut(&id) else {
+            return Err(SchemaError::NotFound(format!(
+                "subscription with id '{id}'"
+            )));
+        };
+
+        // The consumer of a running subscription could still process records with the old bases
+        if !subscription.is_paused() {
+            return Err(SchemaError::Subscription(SubscriptionError::NotPaused(id)));
+        }
+        for (partition, high_watermark) in high_watermarks {
+            subscription.raise_deduplication_base(partition, high_watermark);
+            self.modified = true;
+        }
+
+        Ok(())
+    }
+
+    fn parse_sink(&self, source: &Source, sink: Uri) -> Result<Sink, SchemaError> {
+        Ok(match (source, sink.scheme_str()) {
+            (Source::Kafka { .. }, Some("service")) => {
                 let service_name = sink
                     .authority()
                     .ok_or_else(|| {
@@ -406,28 +561,27 @@ impl SchemaUpdater {
                     }
                 }
             }
+            (Source::Service { .. }, Some("kafka")) => {
+                let cluster_name = sink
+                    .authority()
+                    .ok_or_else(|| {
+                        SchemaError::Subscription(SubscriptionError::InvalidKafkaSinkAuthority(
+                            sink.clone(),
+                        ))
+                    })?
+                    .as_str();
+                let topic_name = &sink.path()[1..];
+                Sink::Kafka {
+                    cluster: cluster_name.to_string(),
+                    topic: topic_name.to_string(),
+                }
+            }
             _ => {
                 return Err(SchemaError::Subscription(
                     SubscriptionError::InvalidSinkScheme(sink),
                 ))
             }
-        };
-
-        let subscription = validator
-            .validate(Subscription::new(
-                id,
-                source,
-                sink,
-                metadata.unwrap_or_default(),
-            ))
-            .map_err(|e| SchemaError::Subscription(SubscriptionError::Validation(e.into())))?;
-
-        self.schema_information
-            .subscriptions
-            .insert(id, subscription);
-        self.modified = true;
-
-        Ok(id)
+        })
     }
 
     pub fn remove_subscription(&mut self, subscription_id: SubscriptionId) {
@@ -487,6 +641,26 @@ impl SchemaUpdater {
                     ModifyServiceChange::AbortTimeout(abort_timeout) => {
                         schemas.abort_timeout = Some(abort_timeout);
                     }
+                    ModifyServiceChange::Cors(cors) => {
+                        if let Some(cors) = &cors {
+                            cors.validate().map_err(ServiceError::from)?;
+                        }
+                        schemas.cors = cors;
+                    }
+                    ModifyServiceChange::RetryPolicy(retry_policy) => {
+                        schemas.retry_policy = retry_policy;
+                    }
+                    ModifyServiceChange::HandlerRetryPolicy {
+                        handler,
+                        retry_policy,
+                    } => {
+                        let Some(handler_schemas) = schemas.handlers.get_mut(&handler) else {
+                            return Err(SchemaError::Service(ServiceError::UnknownHandler(
+                                name, handler,
+                            )));
+                        };
+                        handler_schemas.retry_policy = retry_policy;
+                    }
                 }
             }
         }
@@ -675,6 +849,7 @@ impl DiscoveredHandlerMetadata {
                         },
                         documentation: handler.documentation,
                         metadata: handler.metadata,
+                        retry_policy: None,
                     },
                 )
             })
@@ -687,10 +862,15 @@ mod tests {
     use super::*;
 
     use restate_test_util::{assert, assert_eq, let_assert};
+    use restate_types::retries::RetryPolicy;
     use restate_types::schema::deployment::{Deployment, DeploymentResolver};
-    use restate_types::schema::service::ServiceMetadataResolver;
+    use restate_types::schema::service::{
+        InvocationRetryPolicy, OnMaxAttempts, ServiceMetadataResolver,
+    };
+    use restate_types::schema::subscriptions::SubscriptionResolver;
 
     use restate_types::Versioned;
+    use std::time::Duration;
     use test_log::test;
 
     const GREETER_SERVICE_NAME: &str = "greeter.Greeter";
@@ -857,6 +1037,306 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn handler_retry_policy_survives_new_revision() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::HandlerRetryPolicy {
+                handler: "greet".to_owned(),
+                retry_policy: Some(InvocationRetryPolicy {
+                    retry_policy: RetryPolicy::fixed_delay(Duration::from_secs(1), Some(3)),
+                    on_max_attempts: OnMaxAttempts::Pause,
+                }),
+            }],
+        )?;
+        let rejection = updater
+            .modify_service(
+                GREETER_SERVICE_NAME.to_owned(),
+                vec![ModifyServiceChange::HandlerRetryPolicy {
+                    handler: "unknown".to_owned(),
+                    retry_policy: None,
+                }],
+            )
+            .unwrap_err();
+        let_assert!(SchemaError::Service(ServiceError::UnknownHandler(_, handler)) = rejection);
+        assert_eq!(handler, "unknown");
+
+        let schemas = updater.into_inner();
+        updater = SchemaUpdater::new(schemas, false);
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            true,
+        )?;
+
+        let schemas = updater.into_inner();
+        schemas.assert_service_revision(GREETER_SERVICE_NAME, 2);
+        let_assert!(
+            Some(retry_policy) =
+                schemas.resolve_latest_invocation_retry_policy(GREETER_SERVICE_NAME, "greet")
+        );
+        assert_eq!(retry_policy.on_max_attempts, OnMaxAttempts::Pause);
+
+        Ok(())
+    }
+
+    struct AcceptAllSubscriptions;
+
+    impl SubscriptionValidator for AcceptAllSubscriptions {
+        type Error = std::convert::Infallible;
+
+        fn validate(&self, subscription: Subscription) -> Result<Subscription, Self::Error> {
+            Ok(subscription)
+        }
+    }
+
+    #[test]
+    fn add_egress_subscription() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+
+        let subscription_id = updater.add_subscription(
+            None,
+            format!("service://{GREETER_SERVICE_NAME}/greet")
+                .parse()
+                .unwrap(),
+            "kafka://my-cluster/greetings".parse().unwrap(),
+            None,
+            &AcceptAllSubscriptions,
+        )?;
+
+        let rejection = updater
+            .add_subscription(
+                None,
+                format!("service://{GREETER_SERVICE_NAME}/unknown")
+                    .parse()
+                    .unwrap(),
+                "kafka://my-cluster/greetings".parse().unwrap(),
+                None,
+                &AcceptAllSubscriptions,
+            )
+            .unwrap_err();
+        let_assert!(
+            SchemaError::Subscription(SubscriptionError::SourceServiceNotFound(_)) = rejection
+        );
+
+        // the output of a service can only be published to Kafka
+        let rejection = updater
+            .add_subscription(
+                None,
+                format!("service://{GREETER_SERVICE_NAME}/greet")
+                    .parse()
+                    .unwrap(),
+                format!("service://{GREETER_SERVICE_NAME}/greet")
+                    .parse()
+                    .unwrap(),
+                None,
+                &AcceptAllSubscriptions,
+            )
+            .unwrap_err();
+        let_assert!(SchemaError::Subscription(SubscriptionError::InvalidSinkScheme(_)) = rejection);
+
+        let schemas = updater.into_inner();
+        let_assert!(Some(subscription) = schemas.get_subscription(subscription_id));
+        assert!(subscription.is_egress());
+        assert_eq!(
+            subscription.sink(),
+            &Sink::Kafka {
+                cluster: "my-cluster".to_owned(),
+                topic: "greetings".to_owned(),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_and_pause_subscription() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+
+        let subscription_id = updater.add_subscription(
+            None,
+            format!("service://{GREETER_SERVICE_NAME}/greet")
+                .parse()
+                .unwrap(),
+            "kafka://my-cluster/greetings".parse().unwrap(),
+            Some(HashMap::from([("acks".to_owned(), "all".to_owned())])),
+            &AcceptAllSubscriptions,
+        )?;
+
+        updater.set_subscription_paused(subscription_id, true)?;
+        updater.update_subscription(
+            subscription_id,
+            Some("kafka://my-cluster/salutations".parse().unwrap()),
+            None,
+            &AcceptAllSubscriptions,
+        )?;
+
+        // the output of a service can still only be published to Kafka
+        let rejection = updater
+            .update_subscription(
+                subscription_id,
+                Some(
+                    format!("service://{GREETER_SERVICE_NAME}/greet")
+                        .parse()
+                        .unwrap(),
+                ),
+                None,
+                &AcceptAllSubscriptions,
+            )
+            .unwrap_err();
+        let_assert!(SchemaError::Subscription(SubscriptionError::InvalidSinkScheme(_)) = rejection);
+
+        let rejection = updater
+            .set_subscription_paused(SubscriptionId::new(), true)
+            .unwrap_err();
+        let_assert!(SchemaError::NotFound(_) = rejection);
+
+        let schemas = updater.into_inner();
+        let_assert!(Some(subscription) = schemas.get_subscription(subscription_id));
+        // pausing and options survive the update of the sink
+        assert!(subscription.is_paused());
+        assert_eq!(subscription.metadata().get("acks"), Some(&"all".to_owned()));
+        assert_eq!(
+            subscription.sink(),
+            &Sink::Kafka {
+                cluster: "my-cluster".to_owned(),
+                topic: "salutations".to_owned(),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn raise_subscription_deduplication_bases() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+
+        let subscription_id = updater.add_subscription(
+            None,
+            "kafka://my-cluster/greetings".parse().unwrap(),
+            format!("service://{GREETER_SERVICE_NAME}/greet")
+                .parse()
+                .unwrap(),
+            None,
+            &AcceptAllSubscriptions,
+        )?;
+
+        // offsets can only be reset while no consumer is running
+        let rejection = updater
+            .raise_subscription_deduplication_bases(subscription_id, [(0, 100)])
+            .unwrap_err();
+        let_assert!(SchemaError::Subscription(SubscriptionError::NotPaused(_)) = rejection);
+
+        updater.set_subscription_paused(subscription_id, true)?;
+        updater.raise_subscription_deduplication_bases(subscription_id, [(0, 100)])?;
+        updater.update_subscription(
+            subscription_id,
+            None,
+            Some(HashMap::from([("acks".to_owned(), "all".to_owned())])),
+            &AcceptAllSubscriptions,
+        )?;
+
+        let schemas = updater.into_inner();
+        let_assert!(Some(subscription) = schemas.get_subscription(subscription_id));
+        // the bases survive updates and only apply to the reset partition
+        assert_eq!(subscription.deduplication_index(0, 0), 100);
+        assert_eq!(subscription.deduplication_index(1, 0), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_subscription_keeps_group_id() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+
+        let subscription_id = updater.add_subscription(
+            None,
+            "kafka://my-cluster/greetings".parse().unwrap(),
+            format!("service://{GREETER_SERVICE_NAME}/greet")
+                .parse()
+                .unwrap(),
+            Some(HashMap::from([(
+                "group.id".to_owned(),
+                "my-group".to_owned(),
+            )])),
+            &AcceptAllSubscriptions,
+        )?;
+
+        // the committed offsets belong to the consumer group
+        let rejection = updater
+            .update_subscription(
+                subscription_id,
+                None,
+                Some(HashMap::from([(
+                    "group.id".to_owned(),
+                    "other-group".to_owned(),
+                )])),
+                &AcceptAllSubscriptions,
+            )
+            .unwrap_err();
+        let_assert!(SchemaError::Subscription(SubscriptionError::GroupIdChanged(_)) = rejection);
+
+        // options without a group.id keep the existing one
+        updater.update_subscription(
+            subscription_id,
+            None,
+            Some(HashMap::from([("acks".to_owned(), "all".to_owned())])),
+            &AcceptAllSubscriptions,
+        )?;
+
+        let schemas = updater.into_inner();
+        let_assert!(Some(subscription) = schemas.get_subscription(subscription_id));
+        assert_eq!(
+            subscription.metadata().get("group.id"),
+            Some(&"my-group".to_owned())
+        );
+        assert_eq!(subscription.metadata().get("acks"), Some(&"all".to_owned()));
+
+        Ok(())
+    }
+
     mod change_instance_type {
         use super::*;
 
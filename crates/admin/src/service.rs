@@ -70,7 +70,11 @@ where
     ) -> anyhow::Result<()> {
         let opts = updateable_config.live_load();
 
-        let rest_state = state::AdminServiceState::new(self.schema_registry, self.bifrost);
+        let rest_state = state::AdminServiceState::new(
+            self.schema_registry,
+            self.bifrost,
+            self.query_context.clone(),
+        );
 
         let router = self
             .query_context
@@ -101,6 +105,7 @@ where
 
         net_util::run_hyper_server(
             &BindAddress::Socket(opts.bind_address),
+            None,
             service,
             "admin-api-server",
             || (),
@@ -16,6 +16,8 @@ use restate_storage_query_datafusion::context::QueryContext;
 pub struct AdminServiceState<V> {
     pub schema_registry: SchemaRegistry<V>,
     pub bifrost: Bifrost,
+    /// Used to look up invocations before changing them
+    pub query_context: Option<QueryContext>,
 }
 
 #[derive(Clone)]
@@ -24,10 +26,15 @@ pub struct QueryServiceState {
 }
 
 impl<V> AdminServiceState<V> {
-    pub fn new(schema_registry: SchemaRegistry<V>, bifrost: Bifrost) -> Self {
+    pub fn new(
+        schema_registry: SchemaRegistry<V>,
+        bifrost: Bifrost,
+        query_context: Option<QueryContext>,
+    ) -> Self {
         Self {
             schema_registry,
             bifrost,
+            query_context,
         }
     }
 }
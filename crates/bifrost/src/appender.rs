@@ -14,7 +14,7 @@ use std::time::Instant;
 use tracing::{debug, info, instrument, warn};
 
 use restate_core::Metadata;
-use restate_types::config::Configuration;
+use restate_types::config::{BifrostOptions, Configuration};
 use restate_types::live::Live;
 use restate_types::logs::metadata::SegmentIndex;
 use restate_types::logs::{LogId, Lsn, Record};
@@ -86,12 +86,9 @@ impl Appender {
 
     pub(crate) async fn append_batch_erased(&mut self, batch: Arc<[Record]>) -> Result<Lsn> {
         self.bifrost_inner.fail_if_shutting_down()?;
-        let mut retry_iter = self
-            .config
-            .live_load()
-            .bifrost
-            .append_retry_policy()
-            .into_iter();
+        let bifrost_options = &self.config.live_load().bifrost;
+        let mut retry_iter = bifrost_options.append_retry_policy().into_iter();
+        let batch = compress_batch(batch, bifrost_options);
 
         let mut attempt = 0;
         loop {
@@ -180,3 +177,59 @@ impl Appender {
         Err(Error::LogSealed(log_id))
     }
 }
+
+/// Compresses the bodies of the records if record compression is enabled. Records which can't be
+/// compressed are appended as they are.
+fn compress_batch(batch: Arc<[Record]>, options: &BifrostOptions) -> Arc<[Record]> {
+    let Some(compression) = options.record_compression else {
+        return batch;
+    };
+    let min_size = options.record_compression_threshold.as_usize();
+
+    batch
+        .iter()
+        .map(|record| match record.compressed(compression, min_size) {
+            Ok(Some(compressed)) => compressed,
+            Ok(None) => record.clone(),
+            Err(err) => {
+                warn!(%err, "Failed to compress record, appending it uncompressed");
+                record.clone()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::storage::{PolyBytes, StorageCodecKind, StorageCompression};
+
+    #[test]
+    fn compress_batch_compresses_large_records() {
+        let large = "restate".repeat(100);
+        let batch: Arc<[Record]> =
+            Arc::from(vec![Record::from(large.as_str()), Record::from("small")]);
+
+        // disabled by default
+        let uncompressed = compress_batch(Arc::clone(&batch), &BifrostOptions::default());
+        assert!(Arc::ptr_eq(&batch, &uncompressed));
+
+        let options = BifrostOptions {
+            record_compression: Some(StorageCompression::Zstd),
+            record_compression_threshold: 64u64.into(),
+            ..Default::default()
+        };
+        let compressed = compress_batch(batch, &options);
+
+        let PolyBytes::Bytes(body) = compressed[0].body() else {
+            panic!("large record should be compressed");
+        };
+        assert_eq!(u8::from(StorageCodecKind::Zstd), body[0]);
+        assert!(body.len() < large.len());
+        assert!(matches!(compressed[1].body(), PolyBytes::Typed(_)));
+
+        assert_eq!(large, compressed[0].clone().decode::<String>().unwrap());
+        assert_eq!("small", compressed[1].clone().decode::<String>().unwrap());
+    }
+}
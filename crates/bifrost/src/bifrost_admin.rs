@@ -17,12 +17,13 @@ use restate_core::{Metadata, MetadataKind, MetadataWriter};
 use restate_metadata_store::MetadataStoreClient;
 use restate_types::config::Configuration;
 use restate_types::logs::builder::BuilderError;
-use restate_types::logs::metadata::{LogletParams, Logs, ProviderKind, SegmentIndex};
+use restate_types::logs::metadata::{LogletConfig, LogletParams, Logs, ProviderKind, SegmentIndex};
 use restate_types::logs::{LogId, Lsn, TailState};
 use restate_types::metadata_store::keys::BIFROST_CONFIG_KEY;
 use restate_types::Version;
 
 use crate::error::AdminError;
+use crate::loglet::OperationError;
 use crate::loglet_wrapper::LogletWrapper;
 use crate::{Bifrost, Error, Result};
 
@@ -173,6 +174,58 @@ impl<'a> BifrostAdmin<'a> {
         })
     }
 
+    /// Replaces the loglet backing a sealed segment with a loglet of `provider`, for instance
+    /// when the segment has been copied to another storage tier.
+    ///
+    /// The segment is only updated if it is still backed by the loglet described by `expected`.
+    #[instrument(level = "debug", skip(self, expected), err)]
+    pub async fn replace_segment_loglet(
+        &self,
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        expected: &LogletConfig,
+        provider: ProviderKind,
+        params: LogletParams,
+    ) -> Result<()> {
+        self.bifrost.inner.fail_if_shutting_down()?;
+        let logs = self
+            .metadata_store_client
+            .read_modify_write(BIFROST_CONFIG_KEY.clone(), move |logs: Option<Logs>| {
+                let logs = logs.ok_or(Error::UnknownLogId(log_id))?;
+
+                let mut builder = logs.into_builder();
+                let mut chain_builder = builder.chain(log_id).ok_or(Error::UnknownLogId(log_id))?;
+
+                let unchanged = chain_builder.iter().any(|segment| {
+                    segment.config.index() == segment_index
+                        && segment.config.kind == expected.kind
+                        && segment.config.params == expected.params
+                });
+                if !unchanged {
+                    return Err(Error::from(AdminError::SegmentChanged(segment_index)));
+                }
+
+                match chain_builder.replace_segment_loglet(segment_index, provider, params.clone())
+                {
+                    Err(e) => match e {
+                        BuilderError::UnknownSegment(_) | BuilderError::WriteableSegment(_) => {
+                            Err(Error::from(AdminError::SegmentChanged(segment_index)))
+                        }
+                        BuilderError::ParamsSerde(e) => {
+                            Err(Error::from(OperationError::terminal(e)))
+                        }
+                        _ => unreachable!("replacing a loglet doesn't add logs or segments"),
+                    },
+                    Ok(_) => Ok(builder.build()),
+                }
+            })
+            .await
+            .map_err(|e| e.transpose())?;
+
+        self.metadata_writer.update(Arc::new(logs)).await?;
+        Ok(())
+    }
+
     /// Adds a segment to the end of the chain
     ///
     /// The loglet must be sealed first. This operations assumes that the loglet with
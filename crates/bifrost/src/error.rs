@@ -60,6 +60,8 @@ pub enum AdminError {
         expected: SegmentIndex,
         found: SegmentIndex,
     },
+    #[error("segment {0} has been changed or removed concurrently")]
+    SegmentChanged(SegmentIndex),
 }
 
 impl From<OperationError> for Error {
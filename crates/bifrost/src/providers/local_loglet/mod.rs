@@ -15,7 +15,7 @@ mod log_store_writer;
 mod metric_definitions;
 mod provider;
 mod read_stream;
-mod record_format;
+pub(crate) mod record_format;
 
 pub use self::provider::Factory;
 
@@ -24,7 +24,7 @@ pub(super) const FORMAT_FOR_NEW_APPENDS: RecordFormat = RecordFormat::Legacy;
 #[derive(Debug, derive_more::TryFrom, Eq, PartialEq, Ord, PartialOrd)]
 #[try_from(repr)]
 #[repr(u8)]
-pub(super) enum RecordFormat {
+pub(crate) enum RecordFormat {
     Legacy = 0x02, // matches  StorageCodecKind::FlexBufferSerde
     CustomEncoding = 0x03,
 }
@@ -35,7 +35,7 @@ static_assertions::const_assert!(
 
 #[derive(Debug, thiserror::Error)]
 #[error("Record decode error: {0}")]
-pub(super) enum RecordDecodeError {
+pub(crate) enum RecordDecodeError {
     UnsupportedFormatVersion(u8),
     UnsupportedKeyStyle(u8),
     DecodeError(#[from] StorageDecodeError),
@@ -79,7 +79,7 @@ enum KeyStyle {
     RangeInclusive = 3,
 }
 
-pub(super) fn encode_record_and_split(
+pub(crate) fn encode_record_and_split(
     format_version: RecordFormat,
     record: &Record,
     serde_buffer: &mut BytesMut,
@@ -90,7 +90,7 @@ pub(super) fn encode_record_and_split(
     }
 }
 
-pub(super) fn decode_and_filter_record(
+pub(crate) fn decode_and_filter_record(
     mut buffer: &[u8],
     filter: &KeyFilter,
 ) -> Result<Option<Record>, RecordDecodeError> {
@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 pub mod local_loglet;
+pub mod object_store_loglet;
 
 #[cfg(any(test, feature = "memory-loglet"))]
 pub mod memory_loglet;
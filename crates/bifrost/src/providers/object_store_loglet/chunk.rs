@@ -0,0 +1,192 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use restate_types::logs::{KeyFilter, LogletOffset, Record};
+
+use crate::providers::local_loglet::record_format::{
+    decode_and_filter_record, encode_record_and_split, RecordDecodeError, RecordFormat,
+};
+
+/// first offset (u32) + number of records (u32)
+const HEADER_SIZE: usize = 8;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ChunkError {
+    #[error("chunk is truncated")]
+    Truncated,
+    #[error("chunk does not contain offset {0}")]
+    MissingOffset(LogletOffset),
+    #[error(transparent)]
+    Decode(#[from] RecordDecodeError),
+}
+
+/// Serializes a consecutive range of records into a single object.
+///
+/// Layout: `[first offset: u32][record count: u32]` followed by `[length: u32][record]` for every
+/// record. All integers are little endian. Records use the encoding of the local loglet.
+pub(crate) struct ChunkWriter {
+    first_offset: LogletOffset,
+    count: u32,
+    buf: BytesMut,
+    serde_buf: BytesMut,
+}
+
+impl ChunkWriter {
+    pub fn new(first_offset: LogletOffset) -> Self {
+        let mut buf = BytesMut::with_capacity(HEADER_SIZE);
+        buf.put_u32_le(*first_offset);
+        buf.put_u32_le(0);
+
+        Self {
+            first_offset,
+            count: 0,
+            buf,
+            serde_buf: BytesMut::new(),
+        }
+    }
+
+    /// The offset which the next pushed record must have
+    pub fn next_offset(&self) -> LogletOffset {
+        LogletOffset::new(*self.first_offset + self.count)
+    }
+
+    pub fn push(&mut self, record: &Record) {
+        let encoded =
+            encode_record_and_split(RecordFormat::CustomEncoding, record, &mut self.serde_buf);
+        self.buf
+            .put_u32_le(u32::try_from(encoded.len()).expect("record size should fit into u32"));
+        self.buf.put_slice(&encoded);
+        self.count += 1;
+    }
+
+    pub fn finish(mut self) -> Bytes {
+        self.buf[4..HEADER_SIZE].copy_from_slice(&self.count.to_le_bytes());
+        self.buf.freeze()
+    }
+}
+
+/// A chunk fetched from the object store
+#[derive(Debug)]
+pub(crate) struct Chunk {
+    first_offset: LogletOffset,
+    records: Vec<Bytes>,
+}
+
+impl Chunk {
+    pub fn decode(mut buf: Bytes) -> Result<Self, ChunkError> {
+        if buf.len() < HEADER_SIZE {
+            return Err(ChunkError::Truncated);
+        }
+        let first_offset = LogletOffset::new(buf.get_u32_le());
+        let count = buf.get_u32_le() as usize;
+
+        let mut records = Vec::with_capacity(count);
+        for _ in 0..count {
+            if buf.len() < 4 {
+                return Err(ChunkError::Truncated);
+            }
+            let len = buf.get_u32_le() as usize;
+            if buf.len() < len {
+                return Err(ChunkError::Truncated);
+            }
+            records.push(buf.split_to(len));
+        }
+
+        Ok(Self {
+            first_offset,
+            records,
+        })
+    }
+
+    pub fn first_offset(&self) -> LogletOffset {
+        self.first_offset
+    }
+
+    /// The offset after the last record of this chunk
+    pub fn end_offset(&self) -> LogletOffset {
+        LogletOffset::new(*self.first_offset + self.records.len() as u32)
+    }
+
+    /// Returns the record at `offset` or `None` if it doesn't match the filter.
+    pub fn read(
+        &self,
+        offset: LogletOffset,
+        filter: &KeyFilter,
+    ) -> Result<Option<Record>, ChunkError> {
+        if offset < self.first_offset || offset >= self.end_offset() {
+            return Err(ChunkError::MissingOffset(offset));
+        }
+        let index = (*offset - *self.first_offset) as usize;
+        Ok(decode_and_filter_record(&self.records[index], filter)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::logs::{Keys, MatchKeyQuery, SequenceNumber};
+    use restate_types::storage::PolyBytes;
+    use restate_types::time::NanosSinceEpoch;
+
+    fn record(key: u64, payload: &'static str) -> Record {
+        Record::from_parts(
+            NanosSinceEpoch::now(),
+            Keys::Single(key),
+            PolyBytes::Bytes(Bytes::from_static(payload.as_bytes())),
+        )
+    }
+
+    #[test]
+    fn chunk_roundtrip() -> googletest::Result<()> {
+        let mut writer = ChunkWriter::new(LogletOffset::new(11));
+        writer.push(&record(1, "one"));
+        writer.push(&record(2, "two"));
+        writer.push(&record(3, "three"));
+        assert_eq!(LogletOffset::new(14), writer.next_offset());
+
+        let chunk = Chunk::decode(writer.finish())?;
+        assert_eq!(LogletOffset::new(11), chunk.first_offset());
+        assert_eq!(LogletOffset::new(14), chunk.end_offset());
+
+        let second = chunk
+            .read(LogletOffset::new(12), &KeyFilter::Any)?
+            .expect("record matches filter");
+        assert!(second.matches_key_query(&KeyFilter::Include(2)));
+
+        // filtered out
+        assert!(chunk
+            .read(LogletOffset::new(13), &KeyFilter::Include(1))?
+            .is_none());
+
+        assert!(matches!(
+            chunk.read(LogletOffset::new(10), &KeyFilter::Any),
+            Err(ChunkError::MissingOffset(_))
+        ));
+        assert!(matches!(
+            chunk.read(LogletOffset::new(14), &KeyFilter::Any),
+            Err(ChunkError::MissingOffset(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_chunk() {
+        let mut writer = ChunkWriter::new(LogletOffset::OLDEST);
+        writer.push(&record(1, "one"));
+        let mut buf = writer.finish();
+        buf.truncate(buf.len() - 1);
+
+        assert!(matches!(Chunk::decode(buf), Err(ChunkError::Truncated)));
+    }
+}
@@ -0,0 +1,401 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::{FutureExt, Stream};
+use object_store::path::Path;
+use object_store::ObjectStore;
+use tracing::debug;
+
+use restate_types::logs::{KeyFilter, LogletOffset, Record, SequenceNumber, TailState};
+
+use super::chunk::Chunk;
+use super::{chunk_path, ObjectStoreLogletParams};
+use crate::loglet::util::TailOffsetWatch;
+use crate::loglet::{
+    Loglet, LogletCommit, LogletReadStream, OperationError, SendableLogletReadStream,
+};
+use crate::LogEntry;
+
+#[derive(derive_more::Debug)]
+pub(super) struct ObjectStoreLoglet {
+    #[debug(skip)]
+    object_store: Arc<dyn ObjectStore>,
+    /// Location of the segment's chunks
+    path: Path,
+    params: ObjectStoreLogletParams,
+    // Trims only raise the trim point in memory. The segment offloader persists it in the
+    // segment's params and deletes the trimmed chunks afterwards, so that chunks are never
+    // missing above a persisted trim point.
+    trim_point: AtomicU32,
+    #[debug(skip)]
+    tail_watch: TailOffsetWatch,
+}
+
+impl ObjectStoreLoglet {
+    pub fn new(
+        object_store: Arc<dyn ObjectStore>,
+        path: Path,
+        params: ObjectStoreLogletParams,
+    ) -> Self {
+        Self {
+            object_store,
+            path,
+            trim_point: AtomicU32::new(*params.trim_point),
+            tail_watch: TailOffsetWatch::new(TailState::Sealed(params.tail)),
+            params,
+        }
+    }
+
+    fn trim_point(&self) -> LogletOffset {
+        LogletOffset::new(self.trim_point.load(Ordering::Relaxed))
+    }
+
+    /// Raises the trim point to a trim point which has been persisted in the segment's params.
+    pub fn observe_trim_point(&self, trim_point: LogletOffset) {
+        self.trim_point.fetch_max(*trim_point, Ordering::Relaxed);
+    }
+
+    /// Fetches the chunk with the given index.
+    fn fetch_chunk(&self, chunk_index: u32) -> BoxFuture<'static, Result<Chunk, OperationError>> {
+        let object_store = Arc::clone(&self.object_store);
+        let path = chunk_path(&self.path, chunk_index);
+
+        async move {
+            let bytes = match object_store.get(&path).await {
+                Ok(result) => result.bytes().await.map_err(OperationError::retryable)?,
+                // Chunks are only deleted after the trim point covering them was persisted. A
+                // reader can still race with the deletion until it observes the new trim point,
+                // so the read is retried.
+                Err(object_store::Error::NotFound { .. }) => {
+                    return Err(OperationError::retryable(MissingChunk { path }))
+                }
+                Err(err) => return Err(OperationError::retryable(err)),
+            };
+            Chunk::decode(bytes).map_err(OperationError::terminal)
+        }
+        .boxed()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("untrimmed chunk '{path}' is missing in the object store")]
+struct MissingChunk {
+    path: Path,
+}
+
+#[async_trait]
+impl Loglet for ObjectStoreLoglet {
+    async fn create_read_stream(
+        self: Arc<Self>,
+        filter: KeyFilter,
+        from: LogletOffset,
+        to: Option<LogletOffset>,
+    ) -> Result<SendableLogletReadStream, OperationError> {
+        Ok(Box::pin(ObjectStoreReadStream::new(self, filter, from, to)))
+    }
+
+    fn watch_tail(&self) -> BoxStream<'static, TailState<LogletOffset>> {
+        Box::pin(self.tail_watch.to_stream())
+    }
+
+    async fn enqueue_batch(
+        &self,
+        _payloads: Arc<[Record]>,
+    ) -> Result<LogletCommit, OperationError> {
+        // Only sealed segments are offloaded
+        Ok(LogletCommit::sealed())
+    }
+
+    async fn find_tail(&self) -> Result<TailState<LogletOffset>, OperationError> {
+        Ok(TailState::Sealed(self.params.tail))
+    }
+
+    async fn get_trim_point(&self) -> Result<Option<LogletOffset>, OperationError> {
+        let trim_point = self.trim_point();
+        if trim_point == LogletOffset::INVALID {
+            Ok(None)
+        } else {
+            Ok(Some(trim_point))
+        }
+    }
+
+    async fn trim(&self, new_trim_point: LogletOffset) -> Result<(), OperationError> {
+        let new_trim_point = new_trim_point.min(self.params.tail.prev());
+        let current_trim_point = LogletOffset::new(
+            self.trim_point
+                .fetch_max(*new_trim_point, Ordering::Relaxed),
+        );
+
+        if current_trim_point < new_trim_point {
+            debug!(
+                path = %self.path,
+                trim_point = %new_trim_point,
+                "Trimmed offloaded segment"
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn seal(&self) -> Result<(), OperationError> {
+        // Offloaded segments are always sealed
+        Ok(())
+    }
+}
+
+struct ObjectStoreReadStream {
+    loglet: Arc<ObjectStoreLoglet>,
+    /// Chooses which records to read/return
+    filter: KeyFilter,
+    /// The next offset to read from
+    read_pointer: LogletOffset,
+    /// Last offset to read before terminating the stream
+    read_to: LogletOffset,
+    /// The chunk holding the read pointer (if fetched)
+    chunk: Option<(u32, Chunk)>,
+    fetch: Option<(u32, BoxFuture<'static, Result<Chunk, OperationError>>)>,
+    terminated: bool,
+}
+
+impl ObjectStoreReadStream {
+    fn new(
+        loglet: Arc<ObjectStoreLoglet>,
+        filter: KeyFilter,
+        from: LogletOffset,
+        to: Option<LogletOffset>,
+    ) -> Self {
+        // The segment is sealed, readers never need to wait for records beyond its tail.
+        let last_offset = loglet.params.tail.prev();
+        let read_to = to.map_or(last_offset, |to| to.min(last_offset));
+
+        Self {
+            loglet,
+            filter,
+            read_pointer: from,
+            read_to,
+            chunk: None,
+            fetch: None,
+            terminated: false,
+        }
+    }
+}
+
+impl LogletReadStream for ObjectStoreReadStream {
+    /// Current read pointer. This points to the next offset to be read.
+    fn read_pointer(&self) -> LogletOffset {
+        self.read_pointer
+    }
+    /// Returns true if the stream is terminated.
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl Stream for ObjectStoreReadStream {
+    type Item = Result<LogEntry<LogletOffset>, OperationError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.terminated {
+                return Poll::Ready(None);
+            }
+
+            // We have reached the limit we are allowed to read
+            if this.read_pointer > this.read_to {
+                this.terminated = true;
+                return Poll::Ready(None);
+            }
+
+            // Are we reading behind the loglet head? -> TrimGap
+            let trim_point = this.loglet.trim_point();
+            if this.read_pointer <= trim_point {
+                let trim_gap = LogEntry::new_trim_gap(this.read_pointer, trim_point);
+                this.read_pointer = trim_point.next();
+                return Poll::Ready(Some(Ok(trim_gap)));
+            }
+
+            let chunk_index = this.loglet.params.chunk_index(this.read_pointer);
+
+            if this
+                .chunk
+                .as_ref()
+                .is_none_or(|(index, _)| *index != chunk_index)
+            {
+                if this
+                    .fetch
+                    .as_ref()
+                    .is_none_or(|(index, _)| *index != chunk_index)
+                {
+                    this.chunk = None;
+                    this.fetch = Some((chunk_index, this.loglet.fetch_chunk(chunk_index)));
+                }
+
+                let (_, fetch) = this.fetch.as_mut().expect("fetch is in progress");
+                let result = ready!(fetch.poll_unpin(cx));
+                this.fetch = None;
+
+                match result {
+                    Ok(chunk) => {
+                        this.chunk = Some((chunk_index, chunk));
+                    }
+                    Err(err) => {
+                        // Retryable errors are retried on the next poll
+                        return Poll::Ready(Some(Err(err)));
+                    }
+                }
+            }
+
+            let (_, chunk) = this.chunk.as_ref().expect("chunk is fetched");
+
+            // The first chunk of a segment starts after the trim point at the time the segment
+            // was offloaded.
+            if this.read_pointer < chunk.first_offset() {
+                let trim_gap =
+                    LogEntry::new_trim_gap(this.read_pointer, chunk.first_offset().prev());
+                this.read_pointer = chunk.first_offset();
+                return Poll::Ready(Some(Ok(trim_gap)));
+            }
+
+            let offset = this.read_pointer;
+            match chunk.read(offset, &this.filter) {
+                Ok(Some(record)) => {
+                    this.read_pointer = offset.next();
+                    return Poll::Ready(Some(Ok(LogEntry::new_data(offset, record))));
+                }
+                Ok(None) => {
+                    // filtered out, fast-forward
+                    this.read_pointer = offset.next();
+                }
+                Err(err) => {
+                    this.terminated = true;
+                    return Poll::Ready(Some(Err(OperationError::terminal(err))));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bytes::Bytes;
+    use futures::StreamExt;
+    use object_store::memory::InMemory;
+    use object_store::PutPayload;
+
+    use restate_types::logs::Keys;
+    use restate_types::storage::PolyBytes;
+    use restate_types::time::NanosSinceEpoch;
+
+    use crate::providers::object_store_loglet::chunk::ChunkWriter;
+
+    const SEGMENT_PATH: &str = "1/0";
+
+    /// Creates a loglet over a segment of 6 records which are stored in chunks of 2 records.
+    async fn offloaded_loglet(object_store: &Arc<dyn ObjectStore>) -> Arc<ObjectStoreLoglet> {
+        let path = Path::from(SEGMENT_PATH);
+        for chunk_index in 0..3 {
+            let mut writer = ChunkWriter::new(LogletOffset::new(chunk_index * 2 + 1));
+            for _ in 0..2 {
+                writer.push(&Record::from_parts(
+                    NanosSinceEpoch::now(),
+                    Keys::None,
+                    PolyBytes::Bytes(Bytes::from_static(b"record")),
+                ));
+            }
+            object_store
+                .put(
+                    &chunk_path(&path, chunk_index),
+                    PutPayload::from_bytes(writer.finish()),
+                )
+                .await
+                .unwrap();
+        }
+
+        let params = ObjectStoreLogletParams {
+            path: SEGMENT_PATH.to_owned(),
+            trim_point: LogletOffset::INVALID,
+            deleted_chunks: 0,
+            tail: LogletOffset::new(7),
+            records_per_chunk: 2,
+            source: None,
+        };
+        Arc::new(ObjectStoreLoglet::new(
+            Arc::clone(object_store),
+            path,
+            params,
+        ))
+    }
+
+    #[restate_core::test]
+    async fn trim_keeps_chunks_until_trim_point_is_persisted() -> googletest::Result<()> {
+        let object_store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let loglet = offloaded_loglet(&object_store).await;
+
+        loglet.trim(LogletOffset::new(3)).await?;
+        assert_eq!(Some(LogletOffset::new(3)), loglet.get_trim_point().await?);
+        // chunks are only deleted by the offloader
+        object_store
+            .head(&chunk_path(&Path::from(SEGMENT_PATH), 0))
+            .await?;
+
+        let mut stream = Arc::clone(&loglet)
+            .create_read_stream(KeyFilter::Any, LogletOffset::OLDEST, None)
+            .await?;
+        let entry = stream.next().await.unwrap()?;
+        assert_eq!(
+            Some(LogletOffset::new(3)),
+            entry.trim_gap_to_sequence_number()
+        );
+        let entry = stream.next().await.unwrap()?;
+        assert_eq!(LogletOffset::new(4), entry.sequence_number());
+        assert!(entry.is_data_record());
+
+        Ok(())
+    }
+
+    #[restate_core::test]
+    async fn missing_untrimmed_chunk_is_an_error() -> googletest::Result<()> {
+        let object_store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let loglet = offloaded_loglet(&object_store).await;
+        let path = Path::from(SEGMENT_PATH);
+        object_store.delete(&chunk_path(&path, 0)).await?;
+        object_store.delete(&chunk_path(&path, 1)).await?;
+
+        let mut stream = Arc::clone(&loglet)
+            .create_read_stream(KeyFilter::Any, LogletOffset::OLDEST, None)
+            .await?;
+        assert!(stream.next().await.unwrap().is_err());
+
+        // once the persisted trim point is observed, the deleted chunks are a trim gap
+        loglet.observe_trim_point(LogletOffset::new(4));
+        let entry = stream.next().await.unwrap()?;
+        assert_eq!(
+            Some(LogletOffset::new(4)),
+            entry.trim_gap_to_sequence_number()
+        );
+        let entry = stream.next().await.unwrap()?;
+        assert_eq!(LogletOffset::new(5), entry.sequence_number());
+        assert!(entry.is_data_record());
+
+        Ok(())
+    }
+}
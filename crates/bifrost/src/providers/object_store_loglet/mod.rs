@@ -0,0 +1,234 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! A read-only loglet which serves sealed segments from an object store.
+//!
+//! Segments are never created on this provider. Instead, the [`SegmentOffloader`] copies the
+//! records of a sealed segment to the object store and replaces the segment's loglet in the
+//! chain. The records are stored in chunks of a fixed number of records, so that a reader can
+//! compute the object holding an offset without listing the store.
+
+mod chunk;
+mod loglet;
+mod offloader;
+mod provider;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use aws_config::default_provider::credentials::DefaultCredentialsChain;
+use aws_config::BehaviorVersion;
+use aws_credential_types::provider::ProvideCredentials;
+use object_store::aws::AmazonS3Builder;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use restate_types::logs::metadata::{LogletParams, ProviderKind, SegmentIndex};
+use restate_types::logs::{LogId, LogletOffset};
+use restate_types::time::MillisSinceEpoch;
+
+pub use offloader::SegmentOffloader;
+pub use provider::Factory;
+
+/// Parameters of a segment that has been offloaded to the object store.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ObjectStoreLogletParams {
+    /// Location of the chunks relative to the destination
+    pub path: String,
+    /// Records up to and including this offset are trimmed. Trims of the offloaded segment are
+    /// persisted here before the trimmed chunks are deleted.
+    pub trim_point: LogletOffset,
+    /// Number of leading chunks which have been deleted because all their records are trimmed
+    #[serde(default)]
+    pub deleted_chunks: u32,
+    /// The sealed tail of the segment
+    pub tail: LogletOffset,
+    pub records_per_chunk: u32,
+    /// The loglet which held the segment before it was offloaded. Its data is released once the
+    /// source retention has elapsed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceLoglet>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SourceLoglet {
+    pub kind: ProviderKind,
+    pub params: LogletParams,
+    pub offloaded_at: MillisSinceEpoch,
+}
+
+impl ObjectStoreLogletParams {
+    pub fn deserialize_from(slice: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(slice)
+    }
+
+    pub fn serialize(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Index of the chunk which holds the record at `offset`
+    fn chunk_index(&self, offset: LogletOffset) -> u32 {
+        debug_assert!(offset > LogletOffset::INVALID);
+        (*offset - 1) / self.records_per_chunk
+    }
+
+    /// Number of leading chunks whose records are all trimmed
+    fn trimmed_chunks(&self) -> u32 {
+        let last_offset = self.tail.prev();
+        if last_offset == LogletOffset::INVALID {
+            0
+        } else if self.trim_point >= last_offset {
+            self.chunk_index(last_offset) + 1
+        } else {
+            self.chunk_index(self.trim_point.next())
+        }
+    }
+}
+
+/// Location of a segment's chunks relative to the destination
+fn relative_segment_path(log_id: LogId, segment_index: SegmentIndex) -> String {
+    format!("{log_id}/{segment_index}")
+}
+
+fn segment_path(prefix: &Path, relative_path: &str) -> Path {
+    prefix
+        .parts()
+        .chain(Path::from(relative_path).parts())
+        .collect()
+}
+
+fn chunk_path(segment_path: &Path, chunk_index: u32) -> Path {
+    segment_path.child(format!("{chunk_index:010}"))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DestinationError {
+    #[error("invalid tiered storage destination: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("unsupported tiered storage destination scheme '{0}', expected 's3' or 'file'")]
+    UnsupportedScheme(String),
+    #[error("invalid tiered storage destination path: {0}")]
+    Path(#[from] object_store::path::Error),
+    #[error("unable to determine the AWS region to use with S3")]
+    MissingAwsRegion,
+    #[error(transparent)]
+    ObjectStore(#[from] object_store::Error),
+}
+
+/// Parses the configured destination and checks that its scheme is supported.
+fn parse_destination(destination: &str) -> Result<Url, DestinationError> {
+    let url = Url::parse(destination)?;
+    match url.scheme() {
+        "s3" | "file" => Ok(url),
+        scheme => Err(DestinationError::UnsupportedScheme(scheme.to_owned())),
+    }
+}
+
+/// Creates the object store client for the configured destination. Returns the client and the
+/// path prefix under which segments are stored.
+async fn create_object_store(
+    destination: &str,
+) -> Result<(Arc<dyn ObjectStore>, Path), DestinationError> {
+    let url = parse_destination(destination)?;
+
+    if url.scheme() == "s3" {
+        // Like the snapshot repository, we use the AWS SDK to resolve the region and the
+        // credentials, so that the conventional AWS environment variables, config files and
+        // session credentials work for the tiered storage as well.
+        let aws_region = aws_config::load_defaults(BehaviorVersion::v2024_03_28())
+            .await
+            .region()
+            .ok_or(DestinationError::MissingAwsRegion)?
+            .clone();
+
+        let object_store = AmazonS3Builder::new()
+            .with_url(url.as_str())
+            .with_region(aws_region.to_string())
+            .with_credentials(Arc::new(AwsSdkCredentialsProvider {
+                credentials_provider: DefaultCredentialsChain::builder().build().await,
+            }))
+            .build()?;
+        let prefix = Path::from_url_path(url.path())?;
+        Ok((Arc::new(object_store), prefix))
+    } else {
+        let (object_store, prefix) = object_store::parse_url(&url)?;
+        Ok((Arc::from(object_store), prefix))
+    }
+}
+
+#[derive(Debug)]
+struct AwsSdkCredentialsProvider {
+    credentials_provider: DefaultCredentialsChain,
+}
+
+#[async_trait]
+impl object_store::CredentialProvider for AwsSdkCredentialsProvider {
+    type Credential = object_store::aws::AwsCredential;
+
+    async fn get_credential(&self) -> object_store::Result<Arc<Self::Credential>> {
+        let creds = self
+            .credentials_provider
+            .provide_credentials()
+            .await
+            .map_err(|e| {
+                // aws_config logs the detailed underlying cause at WARN level
+                object_store::Error::Unauthenticated {
+                    path: "<n/a>".to_string(),
+                    source: e.into(),
+                }
+            })?;
+
+        Ok(Arc::new(object_store::aws::AwsCredential {
+            key_id: creds.access_key_id().to_string(),
+            secret_key: creds.secret_access_key().to_string(),
+            token: creds.session_token().map(|t| t.to_string()),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(trim_point: u32, tail: u32) -> ObjectStoreLogletParams {
+        ObjectStoreLogletParams {
+            path: "1/0".to_owned(),
+            trim_point: LogletOffset::new(trim_point),
+            deleted_chunks: 0,
+            tail: LogletOffset::new(tail),
+            records_per_chunk: 10,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn trimmed_chunks() {
+        // empty segment
+        assert_eq!(0, params(0, 1).trimmed_chunks());
+        assert_eq!(0, params(0, 25).trimmed_chunks());
+        // the first chunk holds offsets 1 to 10
+        assert_eq!(0, params(9, 25).trimmed_chunks());
+        assert_eq!(1, params(10, 25).trimmed_chunks());
+        assert_eq!(2, params(20, 25).trimmed_chunks());
+        // a fully trimmed segment has no chunks left
+        assert_eq!(3, params(24, 25).trimmed_chunks());
+    }
+
+    #[test]
+    fn params_without_deleted_chunks() {
+        let params = ObjectStoreLogletParams::deserialize_from(
+            br#"{"path":"1/0","trim_point":3,"tail":7,"records_per_chunk":2}"#,
+        )
+        .unwrap();
+        assert_eq!(0, params.deleted_chunks);
+    }
+}
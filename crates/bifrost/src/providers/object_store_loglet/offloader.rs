@@ -0,0 +1,440 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{ObjectStore, PutPayload};
+use tokio::sync::OnceCell;
+use tracing::{debug, info, warn};
+
+use restate_core::Metadata;
+use restate_types::config::TieredStorageOptions;
+use restate_types::logs::metadata::{LogletConfig, ProviderKind};
+use restate_types::logs::{KeyFilter, LogId, LogletOffset, SequenceNumber};
+use restate_types::time::MillisSinceEpoch;
+
+use super::chunk::ChunkWriter;
+use super::{
+    chunk_path, create_object_store, parse_destination, relative_segment_path, segment_path,
+    DestinationError, ObjectStoreLogletParams, SourceLoglet,
+};
+use crate::loglet::{Loglet, OperationError};
+use crate::{BifrostAdmin, Result};
+
+/// Moves sealed segments to the object store.
+///
+/// Every sealed segment of a log is copied to the object store chunk by chunk and the segment is
+/// then switched to the object store loglet. The original loglet is trimmed once the configured
+/// source retention has elapsed, so that readers which were still reading it can finish.
+///
+/// Trims of offloaded segments are persisted in the segment's params first. The trimmed chunks
+/// are deleted on a later run, once readers had the chance to observe the new trim point.
+pub struct SegmentOffloader {
+    destination: String,
+    /// Client and path prefix of the destination, created on the first run
+    object_store: OnceCell<(Arc<dyn ObjectStore>, Path)>,
+    records_per_chunk: u32,
+    source_retention: Duration,
+}
+
+impl SegmentOffloader {
+    /// Returns `None` if no destination is configured.
+    pub fn create(options: &TieredStorageOptions) -> Result<Option<Self>, DestinationError> {
+        let Some(destination) = &options.destination else {
+            return Ok(None);
+        };
+        parse_destination(destination)?;
+
+        Ok(Some(Self {
+            destination: destination.clone(),
+            object_store: OnceCell::new(),
+            records_per_chunk: options.records_per_chunk.get(),
+            source_retention: options.source_retention.into(),
+        }))
+    }
+
+    async fn object_store(&self) -> Result<&(Arc<dyn ObjectStore>, Path)> {
+        let object_store = self
+            .object_store
+            .get_or_try_init(|| create_object_store(&self.destination))
+            .await
+            .map_err(OperationError::retryable)?;
+        Ok(object_store)
+    }
+
+    /// Offloads all sealed segments which are not stored in the object store yet and releases
+    /// the original loglets whose retention has elapsed.
+    pub async fn run(&self, bifrost_admin: BifrostAdmin<'_>) -> Result<()> {
+        let logs = Metadata::with_current(|m| m.logs_ref());
+
+        for (log_id, chain) in logs.iter() {
+            let tail_index = chain.tail_index();
+
+            for segment in chain.iter() {
+                let segment_index = segment.config.index();
+                if segment_index == tail_index {
+                    break;
+                }
+
+                let result = match segment.config.kind {
+                    ProviderKind::ObjectStore => {
+                        self.maintain_offloaded_segment(bifrost_admin, *log_id, segment.config)
+                            .await
+                    }
+                    ProviderKind::Local | ProviderKind::Replicated => {
+                        self.offload_segment(bifrost_admin, *log_id, segment.config)
+                            .await
+                    }
+                    #[allow(unreachable_patterns)]
+                    _ => Ok(()),
+                };
+
+                if let Err(err) = result {
+                    bifrost_admin.inner.fail_if_shutting_down()?;
+                    warn!(
+                        %log_id,
+                        segment = %segment_index,
+                        "Failed to offload segment: {err}"
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn offload_segment(
+        &self,
+        bifrost_admin: BifrostAdmin<'_>,
+        log_id: LogId,
+        config: &LogletConfig,
+    ) -> Result<()> {
+        let segment_index = config.index();
+        let loglet = bifrost_admin
+            .inner
+            .provider_for(config.kind)?
+            .get_loglet(log_id, segment_index, &config.params)
+            .await?;
+
+        let tail = loglet.find_tail().await?;
+        if !tail.is_sealed() {
+            // All but the last segment should be sealed. Try again later.
+            debug!(%log_id, segment = %segment_index, "Segment is not sealed yet");
+            return Ok(());
+        }
+        let tail = tail.offset();
+
+        let relative_path = relative_segment_path(log_id, segment_index);
+        let mut params = ObjectStoreLogletParams {
+            path: relative_path,
+            trim_point: loglet
+                .get_trim_point()
+                .await?
+                .unwrap_or(LogletOffset::INVALID),
+            deleted_chunks: 0,
+            tail,
+            records_per_chunk: self.records_per_chunk,
+            source: None,
+        };
+
+        let (object_store, prefix) = self.object_store().await?;
+        let path = segment_path(prefix, &params.path);
+        let num_chunks = self
+            .copy_records(object_store, loglet, &path, &mut params)
+            .await?;
+        // The chunks of records which were trimmed before the segment was offloaded have never
+        // been written
+        params.deleted_chunks = params.trimmed_chunks();
+
+        // Readers may still read the segment from the source until the retention has elapsed
+        params.source = Some(SourceLoglet {
+            kind: config.kind,
+            params: config.params.clone(),
+            offloaded_at: MillisSinceEpoch::now(),
+        });
+        let params = params.serialize().map_err(OperationError::terminal)?.into();
+        bifrost_admin
+            .replace_segment_loglet(
+                log_id,
+                segment_index,
+                config,
+                ProviderKind::ObjectStore,
+                params,
+            )
+            .await?;
+
+        info!(
+            %log_id,
+            segment = %segment_index,
+            %tail,
+            num_chunks,
+            "Offloaded sealed segment to the object store"
+        );
+        Ok(())
+    }
+
+    /// Copies the untrimmed records of the sealed loglet to the object store. Returns the number
+    /// of written chunks.
+    async fn copy_records(
+        &self,
+        object_store: &Arc<dyn ObjectStore>,
+        loglet: Arc<dyn Loglet>,
+        path: &Path,
+        params: &mut ObjectStoreLogletParams,
+    ) -> Result<usize> {
+        if params.trim_point.next() >= params.tail {
+            // Nothing left to copy
+            return Ok(0);
+        }
+
+        let mut read_stream = loglet
+            .create_read_stream(
+                KeyFilter::Any,
+                params.trim_point.next(),
+                Some(params.tail.prev()),
+            )
+            .await?;
+
+        let mut num_chunks = 0;
+        let mut chunk: Option<(u32, ChunkWriter)> = None;
+
+        while let Some(entry) = read_stream.next().await {
+            let entry = entry?;
+
+            if let Some(trim_point) = entry.trim_gap_to_sequence_number() {
+                // The loglet was trimmed while we were reading. The records of the current chunk
+                // are trimmed as well.
+                params.trim_point = params.trim_point.max(trim_point);
+                chunk = None;
+                continue;
+            }
+
+            let offset = entry.sequence_number();
+            let chunk_index = params.chunk_index(offset);
+
+            if chunk
+                .as_ref()
+                .is_some_and(|(index, _)| *index != chunk_index)
+            {
+                let (index, writer) = chunk.take().expect("chunk is present");
+                put_chunk(object_store, path, index, writer).await?;
+                num_chunks += 1;
+            }
+
+            let (_, writer) = chunk.get_or_insert_with(|| (chunk_index, ChunkWriter::new(offset)));
+            debug_assert_eq!(writer.next_offset(), offset);
+            writer.push(&entry.into_record().expect("entry is a data record"));
+        }
+
+        if let Some((index, writer)) = chunk {
+            put_chunk(object_store, path, index, writer).await?;
+            num_chunks += 1;
+        }
+
+        Ok(num_chunks)
+    }
+
+    /// Persists trims of the offloaded segment, deletes the chunks trimmed by a previous run and
+    /// trims the loglet which held the segment before it was offloaded, once the retention of
+    /// the source has elapsed.
+    async fn maintain_offloaded_segment(
+        &self,
+        bifrost_admin: BifrostAdmin<'_>,
+        log_id: LogId,
+        config: &LogletConfig,
+    ) -> Result<()> {
+        let segment_index = config.index();
+        let mut params = ObjectStoreLogletParams::deserialize_from(config.params.as_bytes())
+            .map_err(OperationError::terminal)?;
+        let mut changed = false;
+
+        let loglet = bifrost_admin
+            .inner
+            .provider_for(ProviderKind::ObjectStore)?
+            .get_loglet(log_id, segment_index, &config.params)
+            .await?;
+        let trim_point = loglet
+            .get_trim_point()
+            .await?
+            .unwrap_or(LogletOffset::INVALID);
+
+        if trim_point > params.trim_point {
+            params.trim_point = trim_point;
+            changed = true;
+        } else if params.deleted_chunks < params.trimmed_chunks() {
+            // The trim point was persisted by a previous run
+            let (object_store, prefix) = self.object_store().await?;
+            let path = segment_path(prefix, &params.path);
+            let trimmed_chunks = params.trimmed_chunks();
+            for chunk_index in params.deleted_chunks..trimmed_chunks {
+                match object_store.delete(&chunk_path(&path, chunk_index)).await {
+                    Ok(()) | Err(object_store::Error::NotFound { .. }) => {}
+                    Err(err) => return Err(OperationError::retryable(err).into()),
+                }
+            }
+            debug!(
+                %log_id,
+                segment = %segment_index,
+                trim_point = %params.trim_point,
+                "Deleted trimmed chunks of the offloaded segment"
+            );
+            params.deleted_chunks = trimmed_chunks;
+            changed = true;
+        }
+
+        if let Some(source) = params
+            .source
+            .take_if(|source| source.offloaded_at.elapsed() >= self.source_retention)
+        {
+            let source_loglet = bifrost_admin
+                .inner
+                .provider_for(source.kind)?
+                .get_loglet(log_id, segment_index, &source.params)
+                .await?;
+            source_loglet.trim(LogletOffset::MAX).await?;
+            debug!(
+                %log_id,
+                segment = %segment_index,
+                source = %source.kind,
+                "Released the source loglet of the offloaded segment"
+            );
+            changed = true;
+        }
+
+        if !changed {
+            return Ok(());
+        }
+
+        let params = params.serialize().map_err(OperationError::terminal)?.into();
+        bifrost_admin
+            .replace_segment_loglet(
+                log_id,
+                segment_index,
+                config,
+                ProviderKind::ObjectStore,
+                params,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+async fn put_chunk(
+    object_store: &Arc<dyn ObjectStore>,
+    path: &Path,
+    chunk_index: u32,
+    writer: ChunkWriter,
+) -> Result<()> {
+    object_store
+        .put(
+            &chunk_path(path, chunk_index),
+            PutPayload::from_bytes(writer.finish()),
+        )
+        .await
+        .map_err(OperationError::retryable)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::num::NonZeroU32;
+
+    use googletest::prelude::*;
+
+    use restate_core::TestCoreEnvBuilder;
+    use restate_types::logs::metadata::new_single_node_loglet_params;
+    use restate_types::logs::Lsn;
+    use restate_types::partition_table::PartitionTable;
+    use restate_types::Version;
+
+    use crate::providers::object_store_loglet::Factory;
+    use crate::{Bifrost, BifrostService};
+
+    const LOG_ID: LogId = LogId::new(0);
+
+    #[restate_core::test]
+    async fn offloaded_segment_is_read_from_the_object_store() -> googletest::Result<()> {
+        let node_env = TestCoreEnvBuilder::with_incoming_only_connector()
+            .set_partition_table(PartitionTable::with_equally_sized_partitions(
+                Version::MIN,
+                1,
+            ))
+            .set_provider_kind(ProviderKind::InMemory)
+            .build()
+            .await;
+
+        let destination_dir = tempfile::tempdir()?;
+        let options = TieredStorageOptions {
+            destination: Some(format!("file://{}", destination_dir.path().display())),
+            records_per_chunk: NonZeroU32::new(2).unwrap(),
+            ..TieredStorageOptions::default()
+        };
+
+        let bifrost_svc = BifrostService::new()
+            .enable_in_memory_loglet()
+            .with_factory(Factory::new(options.destination.clone().unwrap()));
+        let bifrost: Bifrost = bifrost_svc.handle();
+        bifrost_svc.start().await.expect("bifrost must start");
+        let bifrost_admin = BifrostAdmin::new(
+            &bifrost,
+            &node_env.metadata_writer,
+            &node_env.metadata_store_client,
+        );
+
+        let mut appender = bifrost.create_appender(LOG_ID)?;
+        for i in 1..=5 {
+            appender.append(format!("record{i}")).await?;
+        }
+        bifrost_admin
+            .seal_and_extend_chain(
+                LOG_ID,
+                None,
+                Version::MIN,
+                ProviderKind::InMemory,
+                new_single_node_loglet_params(ProviderKind::InMemory),
+            )
+            .await?;
+        let mut appender = bifrost.create_appender(LOG_ID)?;
+        for i in 6..=7 {
+            appender.append(format!("record{i}")).await?;
+        }
+
+        let segment_offloader = SegmentOffloader::create(&options)?.unwrap();
+        segment_offloader.run(bifrost_admin).await?;
+
+        // the sealed segment has been replaced by the object store loglet, the tail segment is
+        // left alone
+        let logs = Metadata::with_current(|m| m.logs_ref());
+        let chain = logs.chain(&LOG_ID).unwrap();
+        let kinds: Vec<_> = chain.iter().map(|segment| segment.config.kind).collect();
+        assert_that!(
+            kinds,
+            elements_are![eq(ProviderKind::ObjectStore), eq(ProviderKind::InMemory)]
+        );
+
+        let records = bifrost.read_all(LOG_ID).await?;
+        assert_that!(records.len(), eq(7));
+        for (i, record) in records.into_iter().enumerate() {
+            assert_that!(record.sequence_number(), eq(Lsn::from(i as u64 + 1)));
+            assert_that!(
+                record.try_decode::<String>().unwrap()?,
+                eq(format!("record{}", i + 1))
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::{hash_map, HashMap};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use tracing::debug;
+
+use restate_types::logs::metadata::{LogletParams, ProviderKind, SegmentIndex};
+use restate_types::logs::LogId;
+
+use super::loglet::ObjectStoreLoglet;
+use super::{create_object_store, segment_path, ObjectStoreLogletParams};
+use crate::loglet::{Loglet, LogletProvider, LogletProviderFactory, OperationError};
+use crate::Error;
+
+pub struct Factory {
+    destination: String,
+}
+
+impl Factory {
+    pub fn new(destination: String) -> Self {
+        Self { destination }
+    }
+}
+
+#[async_trait]
+impl LogletProviderFactory for Factory {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::ObjectStore
+    }
+
+    async fn create(self: Box<Self>) -> Result<Arc<dyn LogletProvider>, OperationError> {
+        let (object_store, prefix) = create_object_store(&self.destination)
+            .await
+            .map_err(OperationError::terminal)?;
+        debug!(destination = %self.destination, "Created object store loglet provider");
+
+        Ok(Arc::new(ObjectStoreLogletProvider {
+            object_store,
+            prefix,
+            active_loglets: Default::default(),
+        }))
+    }
+}
+
+struct ObjectStoreLogletProvider {
+    object_store: Arc<dyn ObjectStore>,
+    prefix: Path,
+    active_loglets: Mutex<HashMap<(LogId, SegmentIndex), Arc<ObjectStoreLoglet>>>,
+}
+
+#[async_trait]
+impl LogletProvider for ObjectStoreLogletProvider {
+    async fn get_loglet(
+        &self,
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        params: &LogletParams,
+    ) -> crate::Result<Arc<dyn Loglet>> {
+        let params = ObjectStoreLogletParams::deserialize_from(params.as_bytes())
+            .map_err(|err| Error::from(OperationError::terminal(err)))?;
+        let mut guard = self.active_loglets.lock().unwrap();
+
+        let loglet = match guard.entry((log_id, segment_index)) {
+            hash_map::Entry::Vacant(entry) => {
+                let path = segment_path(&self.prefix, &params.path);
+
+                let loglet = entry.insert(Arc::new(ObjectStoreLoglet::new(
+                    Arc::clone(&self.object_store),
+                    path,
+                    params,
+                )));
+                Arc::clone(loglet)
+            }
+            hash_map::Entry::Occupied(entry) => {
+                // The params change when a trim of the segment has been persisted
+                entry.get().observe_trim_point(params.trim_point);
+                Arc::clone(entry.get())
+            }
+        };
+
+        Ok(loglet as Arc<dyn Loglet>)
+    }
+}
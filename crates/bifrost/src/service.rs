@@ -24,6 +24,7 @@ use crate::bifrost::BifrostInner;
 use crate::providers::local_loglet;
 #[cfg(any(test, feature = "memory-loglet"))]
 use crate::providers::memory_loglet;
+use crate::providers::object_store_loglet;
 use crate::watchdog::{Watchdog, WatchdogCommand};
 use crate::{loglet::LogletProviderFactory, Bifrost};
 
@@ -75,6 +76,16 @@ impl BifrostService {
         self
     }
 
+    /// Enables reading segments which have been offloaded to the object store. This is a no-op
+    /// if no tiered storage destination is configured.
+    pub fn enable_object_store_loglet(mut self, config: &Configuration) -> Self {
+        if let Some(destination) = &config.bifrost.tiered_storage.destination {
+            let factory = object_store_loglet::Factory::new(destination.clone());
+            self.factories.insert(factory.kind(), Box::new(factory));
+        }
+        self
+    }
+
     pub fn handle(&self) -> Bifrost {
         self.bifrost.clone()
     }
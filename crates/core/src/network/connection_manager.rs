@@ -24,6 +24,7 @@ use tracing::{debug, info, instrument, trace, warn, Instrument, Span};
 use restate_types::config::NetworkingOptions;
 use restate_types::net::codec::MessageBodyExt;
 use restate_types::net::metadata::MetadataKind;
+use restate_types::net::AdvertisedAddress;
 use restate_types::nodes_config::NodesConfiguration;
 use restate_types::protobuf::node::message::{self, ConnectionControl};
 use restate_types::protobuf::node::{Header, Hello, Message, Welcome};
@@ -36,6 +37,7 @@ use super::metric_definitions::{
     self, CONNECTION_DROPPED, INCOMING_CONNECTION, MESSAGE_PROCESSING_DURATION, MESSAGE_RECEIVED,
     ONGOING_DRAIN, OUTGOING_CONNECTION,
 };
+use super::tls::{self, PeerCertificate};
 use super::transport_connector::TransportConnect;
 use super::{Handler, MessageRouter};
 use crate::metadata::Urgency;
@@ -144,8 +146,22 @@ impl<T: TransportConnect> ConnectionManager<T> {
 
     /// Accept a new incoming connection stream and register a network reactor task for it.
     pub async fn accept_incoming_connection<S>(
+        &self,
+        incoming: S,
+    ) -> Result<impl Stream<Item = Message> + Unpin + Send + 'static, NetworkError>
+    where
+        S: Stream<Item = Result<Message, ProtocolError>> + Unpin + Send + 'static,
+    {
+        self.accept_incoming_connection_with_certificate(incoming, None)
+            .await
+    }
+
+    /// Like [`Self::accept_incoming_connection`] for a connection over TLS. The peer's
+    /// certificate must be issued for the node it claims to be in its Hello message.
+    pub async fn accept_incoming_connection_with_certificate<S>(
         &self,
         mut incoming: S,
+        peer_certificate: Option<PeerCertificate>,
     ) -> Result<impl Stream<Item = Message> + Unpin + Send + 'static, NetworkError>
     where
         S: Stream<Item = Result<Message, ProtocolError>> + Unpin + Send + 'static,
@@ -215,6 +231,9 @@ impl<T: TransportConnect> ConnectionManager<T> {
         );
 
         self.verify_node_id(peer_node_id, &header, &nodes_config)?;
+        if let Some(peer_certificate) = &peer_certificate {
+            self.verify_peer_certificate(peer_node_id, peer_certificate, &nodes_config)?;
+        }
 
         let (tx, output_stream) =
             mpsc::channel(self.networking_options.outbound_queue_length.into());
@@ -402,6 +421,45 @@ impl<T: TransportConnect> ConnectionManager<T> {
         Ok(())
     }
 
+    /// Binds the peer's certificate to the node id it claims. The certificate must be issued for
+    /// the host of the node's advertised address, or for the configured server name if all nodes
+    /// share a certificate.
+    fn verify_peer_certificate(
+        &self,
+        peer_node_id: GenerationalNodeId,
+        peer_certificate: &PeerCertificate,
+        nodes_config: &NodesConfiguration,
+    ) -> Result<(), NetworkError> {
+        let Some(tls_options) = &self.networking_options.tls else {
+            return Ok(());
+        };
+
+        let node = nodes_config.find_node_by_id(peer_node_id)?;
+        let AdvertisedAddress::Http(uri) = &node.address else {
+            return Err(ProtocolError::HandshakeFailed(
+                "peer connected over TLS but advertises a unix domain socket",
+            )
+            .into());
+        };
+
+        if let Err(err) = tls::verify_peer_certificate(
+            tls_options,
+            peer_certificate,
+            uri.host().unwrap_or_default(),
+        ) {
+            info!(
+                "Rejecting connection of '{}' whose certificate is not issued for its address '{}': {}",
+                peer_node_id, node.address, err
+            );
+            return Err(ProtocolError::HandshakeFailed(
+                "peer certificate does not match the node id",
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
     fn start_connection_reactor<S>(
         &self,
         connection: OwnedConnection,
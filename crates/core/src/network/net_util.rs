@@ -14,20 +14,28 @@ use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 
+use http::uri::Scheme;
 use http::Uri;
 use hyper::body::{Body, Incoming};
 use hyper_util::rt::TokioIo;
 use hyper_util::server::graceful::GracefulShutdown;
+use rustls::pki_types::ServerName;
 use tokio::io;
-use tokio::net::{TcpListener, UnixListener, UnixStream};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+use tokio_util::either::Either;
 use tokio_util::net::Listener;
 use tonic::transport::{Channel, Endpoint};
 use tracing::{debug, info, instrument, trace, Instrument, Span};
 
-use restate_types::config::{Configuration, MetadataStoreClientOptions, NetworkingOptions};
+use restate_types::config::{
+    Configuration, MetadataStoreClientOptions, NetworkingOptions, NetworkingTlsOptions,
+};
 use restate_types::errors::GenericError;
 use restate_types::net::{AdvertisedAddress, BindAddress};
 
+use super::tls::{self, PeerCertificate};
 use crate::{cancellation_watcher, ShutdownError, TaskCenter, TaskKind};
 
 pub fn create_tonic_channel_from_advertised_address<T: CommonClientConnectionOptions>(
@@ -49,17 +57,71 @@ pub fn create_tonic_channel_from_advertised_address<T: CommonClientConnectionOpt
                     }
                 }))
         }
-        AdvertisedAddress::Http(uri) => Channel::builder(uri)
-            .connect_timeout(options.connect_timeout())
-            .http2_keep_alive_interval(options.keep_alive_interval())
-            .keep_alive_timeout(options.keep_alive_timeout())
-            .http2_adaptive_window(options.http2_adaptive_window())
-            // this true by default, but this is to guard against any change in defaults
-            .tcp_nodelay(true)
-            .connect_lazy(),
+        AdvertisedAddress::Http(uri) => {
+            let endpoint = Endpoint::from(uri.clone())
+                .connect_timeout(options.connect_timeout())
+                .http2_keep_alive_interval(options.keep_alive_interval())
+                .keep_alive_timeout(options.keep_alive_timeout())
+                .http2_adaptive_window(options.http2_adaptive_window())
+                // this true by default, but this is to guard against any change in defaults
+                .tcp_nodelay(true);
+
+            match options.tls() {
+                None => endpoint.connect_lazy(),
+                Some(tls_options) => {
+                    // Configuration errors are reported on every connection attempt, this
+                    // keeps channel creation infallible.
+                    let tls = tls::tls_connector(tls_options)
+                        .and_then(|connector| {
+                            let server_name =
+                                tls::server_name(tls_options, uri.host().unwrap_or_default())?;
+                            Ok((connector, server_name))
+                        })
+                        .map_err(|err| err.to_string());
+                    let connect_timeout = options.connect_timeout();
+
+                    endpoint.connect_with_connector_lazy(tower::service_fn(move |uri: Uri| {
+                        let tls = tls.clone();
+                        async move {
+                            let (connector, server_name) = tls.map_err(io::Error::other)?;
+                            tokio::time::timeout(
+                                connect_timeout,
+                                connect_tls(uri, connector, server_name),
+                            )
+                            .await
+                            .map_err(|_| {
+                                io::Error::new(io::ErrorKind::TimedOut, "TLS connect timed out")
+                            })?
+                        }
+                    }))
+                }
+            }
+        }
     }
 }
 
+/// Opens a TLS connection to the peer at `uri`, verifying its certificate against `server_name`.
+pub async fn connect_tls(
+    uri: Uri,
+    connector: TlsConnector,
+    server_name: ServerName<'static>,
+) -> Result<TokioIo<TlsStream<TcpStream>>, io::Error> {
+    let host = uri
+        .host()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "address without host"))?;
+    // IPv6 hosts of URIs are enclosed in brackets
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let port = uri
+        .port_u16()
+        .or_else(|| (uri.scheme() == Some(&Scheme::HTTPS)).then_some(443))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "address without port"))?;
+
+    let stream = TcpStream::connect((host, port)).await?;
+    stream.set_nodelay(true)?;
+
+    Ok(TokioIo::new(connector.connect(server_name, stream).await?))
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("failed binding to address '{address}': {source}")]
@@ -85,6 +147,7 @@ pub enum Error {
 #[instrument(level = "error", name = "server", skip_all, fields(server_name = %server_name, uds.path = tracing::field::Empty, net.host.addr = tracing::field::Empty, net.host.port = tracing::field::Empty))]
 pub async fn run_hyper_server<S, B>(
     bind_address: &BindAddress,
+    tls_acceptor: Option<TlsAcceptor>,
     service: S,
     server_name: &'static str,
     on_bind: impl Fn(),
@@ -116,7 +179,8 @@ where
             info!("Server listening");
             on_bind();
 
-            run_listener_loop(unix_listener, service, server_name).await?;
+            // connections over unix domain sockets are local and never use TLS
+            run_listener_loop(unix_listener, None, service, server_name).await?;
         }
         BindAddress::Socket(socket_addr) => {
             let tcp_listener =
@@ -137,7 +201,7 @@ where
             info!("Server listening");
             on_bind();
 
-            run_listener_loop(tcp_listener, service, server_name).await?;
+            run_listener_loop(tcp_listener, tls_acceptor, service, server_name).await?;
         }
     }
     on_stop();
@@ -149,6 +213,7 @@ where
 
 async fn run_listener_loop<L, S, B>(
     mut listener: L,
+    tls_acceptor: Option<TlsAcceptor>,
     service: S,
     server_name: &'static str,
 ) -> Result<(), Error>
@@ -179,9 +244,9 @@ where
             }
             incoming_connection = listener.accept() => {
                 let (stream, remote_addr) = incoming_connection?;
-                let io = TokioIo::new(stream);
 
                 let network_options = &configuration.live_load().networking;
+                let tls_handshake_timeout: Duration = network_options.handshake_timeout.into();
                 let mut builder = hyper_util::server::conn::auto::Builder::new(TaskCenterExecutor);
                 builder
                     .http2()
@@ -190,15 +255,50 @@ where
                     .keep_alive_interval(Some(network_options.http2_keep_alive_interval.into()))
                     .keep_alive_timeout(network_options.http2_keep_alive_timeout.into());
 
-                let connection = graceful_shutdown.watch(builder
-                    .serve_connection(io, service.clone()).into_owned())
-                    .in_current_span();
+                let tls_acceptor = tls_acceptor.clone();
+                let watcher = graceful_shutdown.watcher();
+                let service = service.clone();
 
                 // TaskCenter will wait for the parent task, we don't need individual connection
                 // handlers to be managed tasks. We just need to make sure that we actually try and
                 // shutdown connections, that's why H2Stream tasks are managed.
                 TaskCenter::spawn_unmanaged(TaskKind::SocketHandler, server_name, async move {
                     trace!("Connection accepted from {remote_addr:?}");
+                    let (io, peer_certificate) = match tls_acceptor {
+                        Some(tls_acceptor) => match tokio::time::timeout(
+                            tls_handshake_timeout,
+                            tls_acceptor.accept(stream),
+                        )
+                        .await
+                        {
+                            Ok(Ok(stream)) => {
+                                let peer_certificate = stream
+                                    .get_ref()
+                                    .1
+                                    .peer_certificates()
+                                    .and_then(|certificates| certificates.first())
+                                    .map(|certificate| PeerCertificate(certificate.clone().into_owned()));
+                                (Either::Right(stream), peer_certificate)
+                            }
+                            Ok(Err(err)) => {
+                                debug!("TLS handshake with {remote_addr:?} failed: {err}");
+                                return;
+                            }
+                            Err(_) => {
+                                debug!("TLS handshake with {remote_addr:?} timed out");
+                                return;
+                            }
+                        },
+                        None => (Either::Left(stream), None),
+                    };
+
+                    let service = WithPeerCertificate {
+                        inner: service,
+                        peer_certificate,
+                    };
+                    let connection = watcher.watch(
+                        builder.serve_connection(TokioIo::new(io), service).into_owned(),
+                    );
                     if let Err(e) = connection.await {
                         if let Some(hyper_error) = e.downcast_ref::<hyper::Error>() {
                             if hyper_error.is_incomplete_message() {
@@ -210,7 +310,7 @@ where
                     } else {
                         trace!("Connection completed cleanly");
                     }
-                })?;
+                }.in_current_span())?;
             }
         }
     }
@@ -229,6 +329,30 @@ where
     Ok(())
 }
 
+/// Adds the certificate the peer presented during the TLS handshake to the extensions of its
+/// requests, so that handlers can verify the peer's identity.
+#[derive(Clone)]
+struct WithPeerCertificate<S> {
+    inner: S,
+    peer_certificate: Option<PeerCertificate>,
+}
+
+impl<S> hyper::service::Service<http::Request<Incoming>> for WithPeerCertificate<S>
+where
+    S: hyper::service::Service<http::Request<Incoming>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn call(&self, mut request: http::Request<Incoming>) -> Self::Future {
+        if let Some(peer_certificate) = &self.peer_certificate {
+            request.extensions_mut().insert(peer_certificate.clone());
+        }
+        self.inner.call(request)
+    }
+}
+
 #[derive(Clone, Default)]
 struct TaskCenterExecutor;
 
@@ -252,6 +376,7 @@ pub trait CommonClientConnectionOptions {
     fn keep_alive_interval(&self) -> Duration;
     fn keep_alive_timeout(&self) -> Duration;
     fn http2_adaptive_window(&self) -> bool;
+    fn tls(&self) -> Option<&NetworkingTlsOptions>;
 }
 
 impl CommonClientConnectionOptions for NetworkingOptions {
@@ -270,6 +395,10 @@ impl CommonClientConnectionOptions for NetworkingOptions {
     fn http2_adaptive_window(&self) -> bool {
         self.http2_adaptive_window
     }
+
+    fn tls(&self) -> Option<&NetworkingTlsOptions> {
+        self.tls.as_ref()
+    }
 }
 
 impl CommonClientConnectionOptions for MetadataStoreClientOptions {
@@ -288,4 +417,8 @@ impl CommonClientConnectionOptions for MetadataStoreClientOptions {
     fn http2_adaptive_window(&self) -> bool {
         true
     }
+
+    fn tls(&self) -> Option<&NetworkingTlsOptions> {
+        self.metadata_store_tls.as_ref()
+    }
 }
@@ -19,12 +19,14 @@ use tower::ServiceExt;
 use tower_http::trace::TraceLayer;
 use tracing::debug;
 
+use restate_types::config::NetworkingTlsOptions;
 use restate_types::health::HealthStatus;
 use restate_types::net::BindAddress;
 use restate_types::protobuf::common::NodeRpcStatus;
 
 use super::multiplex::MultiplexService;
 use super::net_util::run_hyper_server;
+use super::tls::tls_acceptor;
 
 #[derive(Debug, Default)]
 pub struct NetworkServerBuilder {
@@ -65,8 +67,11 @@ impl NetworkServerBuilder {
         self,
         node_rpc_health: HealthStatus<NodeRpcStatus>,
         bind_address: &BindAddress,
+        tls_options: Option<&NetworkingTlsOptions>,
     ) -> Result<(), anyhow::Error> {
         node_rpc_health.update(NodeRpcStatus::StartingUp);
+        let tls_acceptor = tls_options.map(tls_acceptor).transpose()?;
+
         // Trace layer
         let span_factory = tower_http::trace::DefaultMakeSpan::new()
             .include_headers(true)
@@ -97,6 +102,7 @@ impl NetworkServerBuilder {
 
         run_hyper_server(
             bind_address,
+            tls_acceptor,
             service,
             "node-rpc-server",
             || node_rpc_health.update(NodeRpcStatus::Ready),
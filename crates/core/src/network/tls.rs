@@ -0,0 +1,250 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Mutually authenticated TLS between the nodes of a cluster.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use rustls::pki_types::{CertificateDer, ServerName};
+use rustls::server::{ParsedCertificate, WebPkiClientVerifier};
+use rustls::{ClientConfig, ServerConfig};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+use restate_types::config::NetworkingTlsOptions;
+use restate_types::tls::{load_certs, load_private_key, load_roots, PemError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TlsConfigError {
+    #[error(transparent)]
+    Pem(#[from] PemError),
+    #[error("invalid CA certificates in '{path}': {source}")]
+    Ca {
+        path: PathBuf,
+        #[source]
+        source: rustls::server::VerifierBuilderError,
+    },
+    #[error("invalid TLS server name '{0}'")]
+    InvalidServerName(String),
+    #[error(transparent)]
+    Rustls(#[from] rustls::Error),
+}
+
+/// Builds the acceptor of the node's RPC server. Peers must present a certificate signed by the
+/// configured CA.
+pub fn tls_acceptor(options: &NetworkingTlsOptions) -> Result<TlsAcceptor, TlsConfigError> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+    let roots = Arc::new(load_roots(&options.ca_path)?);
+    let verifier = WebPkiClientVerifier::builder_with_provider(roots, Arc::clone(&provider))
+        .build()
+        .map_err(|source| TlsConfigError::Ca {
+            path: options.ca_path.clone(),
+            source,
+        })?;
+
+    let mut config = ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .with_client_cert_verifier(verifier)
+        .with_single_cert(
+            load_certs(&options.cert_path)?,
+            load_private_key(&options.key_path)?,
+        )?;
+    // the node's RPC server also serves plain HTTP endpoints like metrics
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Builds the connector used to connect to peers, presenting the node's certificate.
+pub fn tls_connector(options: &NetworkingTlsOptions) -> Result<TlsConnector, TlsConfigError> {
+    let mut config =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(load_roots(&options.ca_path)?)
+            .with_client_auth_cert(
+                load_certs(&options.cert_path)?,
+                load_private_key(&options.key_path)?,
+            )?;
+    config.alpn_protocols = vec![b"h2".to_vec()];
+
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+/// The certificate a peer presented when connecting to the node's RPC server. It is added to
+/// the extensions of the peer's requests.
+#[derive(Debug, Clone)]
+pub struct PeerCertificate(pub CertificateDer<'static>);
+
+/// Verifies that the certificate of a peer, whose chain has already been verified during the
+/// TLS handshake, is issued for the name of the peer at `host`.
+pub fn verify_peer_certificate(
+    options: &NetworkingTlsOptions,
+    certificate: &PeerCertificate,
+    host: &str,
+) -> Result<(), TlsConfigError> {
+    let server_name = server_name(options, host)?;
+    let certificate = ParsedCertificate::try_from(&certificate.0)?;
+    rustls::client::verify_server_name(&certificate, &server_name)?;
+    Ok(())
+}
+
+/// Returns the name to verify the certificate of the peer at `host` against.
+pub fn server_name(
+    options: &NetworkingTlsOptions,
+    host: &str,
+) -> Result<ServerName<'static>, TlsConfigError> {
+    let name = options.server_name.as_deref().unwrap_or(host);
+    // IPv6 hosts of URIs are enclosed in brackets
+    let name = name.trim_start_matches('[').trim_end_matches(']');
+
+    ServerName::try_from(name.to_owned())
+        .map_err(|_| TlsConfigError::InvalidServerName(name.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::Path;
+
+    use rcgen::{BasicConstraints, CertificateParams, ExtendedKeyUsagePurpose, IsCa, KeyPair};
+    use tempfile::TempDir;
+
+    /// A CA and a node certificate issued by it, written to PEM files.
+    struct TestPki {
+        dir: TempDir,
+        ca: rcgen::Certificate,
+        ca_key: KeyPair,
+    }
+
+    impl TestPki {
+        fn new() -> Self {
+            let ca_key = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            let ca = params.self_signed(&ca_key).unwrap();
+
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::write(dir.path().join("ca.pem"), ca.pem()).unwrap();
+            Self { dir, ca, ca_key }
+        }
+
+        /// Issues a certificate for `name` and returns the options presenting it.
+        fn node_options(&self, name: &str) -> NetworkingTlsOptions {
+            let key = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::new(vec![name.to_owned()]).unwrap();
+            params.extended_key_usages = vec![
+                ExtendedKeyUsagePurpose::ServerAuth,
+                ExtendedKeyUsagePurpose::ClientAuth,
+            ];
+            let cert = params.signed_by(&key, &self.ca, &self.ca_key).unwrap();
+
+            let cert_path = self.dir.path().join(format!("{name}.pem"));
+            let key_path = self.dir.path().join(format!("{name}.key"));
+            std::fs::write(&cert_path, cert.pem()).unwrap();
+            std::fs::write(&key_path, key.serialize_pem()).unwrap();
+            NetworkingTlsOptions {
+                cert_path,
+                key_path,
+                ca_path: self.dir.path().join("ca.pem"),
+                server_name: None,
+            }
+        }
+    }
+
+    /// Runs a handshake between a client and a server over an in-memory stream and returns the
+    /// certificate the client presented.
+    async fn handshake(
+        server: &NetworkingTlsOptions,
+        client: &NetworkingTlsOptions,
+        server_host: &str,
+    ) -> Result<PeerCertificate, std::io::Error> {
+        let (client_io, server_io) = tokio::io::duplex(16 * 1024);
+        let acceptor = tls_acceptor(server).unwrap();
+        let connector = tls_connector(client).unwrap();
+        let server_name = server_name(client, server_host).unwrap();
+
+        let (accepted, connected) = tokio::join!(
+            acceptor.accept(server_io),
+            connector.connect(server_name, client_io)
+        );
+        connected?;
+        let accepted = accepted?;
+        let (_, connection) = accepted.get_ref();
+        Ok(PeerCertificate(
+            connection.peer_certificates().unwrap()[0].clone(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn mutually_authenticated_handshake() {
+        let pki = TestPki::new();
+        let node1 = pki.node_options("node1.restate.internal");
+        let node2 = pki.node_options("node2.restate.internal");
+
+        let peer_certificate = handshake(&node1, &node2, "node1.restate.internal")
+            .await
+            .unwrap();
+        // the presented certificate is bound to the name of the peer
+        verify_peer_certificate(&node1, &peer_certificate, "node2.restate.internal").unwrap();
+        assert!(
+            verify_peer_certificate(&node1, &peer_certificate, "node3.restate.internal").is_err()
+        );
+
+        // the server's certificate is verified against the name of the address
+        assert!(handshake(&node1, &node2, "node3.restate.internal")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn handshake_rejects_certificates_of_other_cas() {
+        let node1 = TestPki::new().node_options("node1.restate.internal");
+        let other = TestPki::new().node_options("node2.restate.internal");
+
+        assert!(handshake(&node1, &other, "node1.restate.internal")
+            .await
+            .is_err());
+    }
+
+    fn options(server_name: Option<&str>) -> NetworkingTlsOptions {
+        NetworkingTlsOptions {
+            cert_path: "/does/not/exist.pem".into(),
+            key_path: "/does/not/exist.key".into(),
+            ca_path: "/does/not/exist-ca.pem".into(),
+            server_name: server_name.map(ToOwned::to_owned),
+        }
+    }
+
+    #[test]
+    fn missing_ca_file() {
+        assert!(matches!(
+            tls_connector(&options(None)),
+            Err(TlsConfigError::Pem(PemError::Io { path, .. })) if path == Path::new("/does/not/exist-ca.pem")
+        ));
+    }
+
+    #[test]
+    fn server_name_override() {
+        assert_eq!(
+            server_name(&options(None), "node1.restate.internal").unwrap(),
+            ServerName::try_from("node1.restate.internal").unwrap()
+        );
+        assert_eq!(
+            server_name(&options(Some("cluster.restate.internal")), "10.0.0.1").unwrap(),
+            ServerName::try_from("cluster.restate.internal").unwrap()
+        );
+        assert!(matches!(
+            server_name(&options(None), "[::1]").unwrap(),
+            ServerName::IpAddress(_)
+        ));
+    }
+}
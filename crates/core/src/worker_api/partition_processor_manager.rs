@@ -16,6 +16,7 @@ use tokio::sync::{mpsc, oneshot};
 use restate_types::{
     cluster::cluster_state::PartitionProcessorStatus,
     identifiers::{PartitionId, SnapshotId},
+    net::partition_processor_manager::SnapshotSummary,
 };
 
 use crate::ShutdownError;
@@ -23,6 +24,12 @@ use crate::ShutdownError;
 #[derive(Debug)]
 pub enum ProcessorsManagerCommand {
     CreateSnapshot(PartitionId, oneshot::Sender<SnapshotResult>),
+    ListSnapshots(PartitionId, oneshot::Sender<SnapshotListResult>),
+    PruneSnapshots {
+        partition_id: PartitionId,
+        dry_run: bool,
+        sender: oneshot::Sender<SnapshotListResult>,
+    },
     GetState(oneshot::Sender<BTreeMap<PartitionId, PartitionProcessorStatus>>),
 }
 
@@ -50,6 +57,46 @@ impl ProcessorsManagerHandle {
         })?
     }
 
+    pub async fn list_snapshots(&self, partition_id: PartitionId) -> SnapshotListResult {
+        let (tx, rx) = oneshot::channel();
+        self.0
+            .send(ProcessorsManagerCommand::ListSnapshots(partition_id, tx))
+            .await
+            .map_err(|_| {
+                SnapshotError::Internal(
+                    partition_id,
+                    "Unable to send command to PartitionProcessorManager".to_string(),
+                )
+            })?;
+        rx.await.map_err(|_| {
+            SnapshotError::Internal(partition_id, "Unable to receive response".to_string())
+        })?
+    }
+
+    pub async fn prune_snapshots(
+        &self,
+        partition_id: PartitionId,
+        dry_run: bool,
+    ) -> SnapshotListResult {
+        let (tx, rx) = oneshot::channel();
+        self.0
+            .send(ProcessorsManagerCommand::PruneSnapshots {
+                partition_id,
+                dry_run,
+                sender: tx,
+            })
+            .await
+            .map_err(|_| {
+                SnapshotError::Internal(
+                    partition_id,
+                    "Unable to send command to PartitionProcessorManager".to_string(),
+                )
+            })?;
+        rx.await.map_err(|_| {
+            SnapshotError::Internal(partition_id, "Unable to receive response".to_string())
+        })?
+    }
+
     pub async fn get_state(
         &self,
     ) -> Result<BTreeMap<PartitionId, PartitionProcessorStatus>, ShutdownError> {
@@ -64,6 +111,8 @@ impl ProcessorsManagerHandle {
 
 pub type SnapshotResult = Result<SnapshotCreated, SnapshotError>;
 
+pub type SnapshotListResult = Result<Vec<SnapshotSummary>, SnapshotError>;
+
 #[derive(Debug, Clone, derive_more::Display)]
 #[display("{}", snapshot_id)]
 pub struct SnapshotCreated {
@@ -89,7 +138,7 @@ pub enum SnapshotError {
     SnapshotIo(PartitionId, #[source] io::Error),
     #[error("Snapshot repository IO error: {1}")]
     RepositoryIo(PartitionId, #[source] anyhow::Error),
-    #[error("Internal error creating snapshot: {1}")]
+    #[error("Internal snapshot error: {1}")]
     Internal(PartitionId, String),
 }
 
@@ -0,0 +1,677 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures::future::{self, Either, Ready};
+use http::header::InvalidHeaderName;
+use http::{header, HeaderName, HeaderValue, Request, Response, StatusCode};
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tower::{Layer, Service};
+use tracing::debug;
+
+use restate_types::config::{IngressAuthOptions, JwtAuthOptions};
+
+use super::target_service;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuthConfigError {
+    #[error("failed reading JWKS file '{path}': {source}")]
+    JwksIo {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed parsing JWKS file '{path}': {source}")]
+    JwksParse {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("key in JWKS file '{path}' has no 'kid'")]
+    MissingKeyId { path: PathBuf },
+    #[error("unsupported algorithm of key '{kid}' in JWKS file '{path}'")]
+    UnsupportedAlgorithm { path: PathBuf, kid: String },
+    #[error("unsupported key '{kid}' in JWKS file '{path}': {source}")]
+    UnsupportedKey {
+        path: PathBuf,
+        kid: String,
+        #[source]
+        source: jsonwebtoken::errors::Error,
+    },
+    #[error("invalid principal header: {0}")]
+    PrincipalHeader(#[from] InvalidHeaderName),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    #[error("missing credentials")]
+    MissingCredentials,
+    #[error("invalid credentials")]
+    InvalidCredentials,
+    #[error("invalid token: {0}")]
+    InvalidToken(#[from] jsonwebtoken::errors::Error),
+    #[error("unknown token key id")]
+    UnknownKeyId,
+    #[error("principal '{0}' is not allowed to invoke service '{1}'")]
+    Forbidden(String, String),
+    #[error("principal '{0}' is not allowed to address invocations and awakeables by id")]
+    ForbiddenById(String),
+}
+
+impl AuthError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AuthError::Forbidden(_, _) | AuthError::ForbiddenById(_) => StatusCode::FORBIDDEN,
+            _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+/// Principal a request has been authenticated as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal(String);
+
+/// Verifies the credentials presented by a caller.
+pub trait CredentialsVerifier: Send + Sync {
+    /// Returns `Ok(None)` if the credentials are not meant for this verifier, so that the next
+    /// configured verifier can be tried.
+    fn verify(&self, credentials: &str) -> Result<Option<Principal>, AuthError>;
+}
+
+/// Static API keys. Only the SHA-256 digests of the keys are kept in memory.
+pub struct ApiKeyVerifier {
+    keys: HashMap<[u8; 32], Principal>,
+}
+
+impl ApiKeyVerifier {
+    pub fn new<'a>(keys: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        Self {
+            keys: keys
+                .into_iter()
+                .map(|(principal, key)| (digest(key), Principal(principal.to_owned())))
+                .collect(),
+        }
+    }
+}
+
+fn digest(key: &str) -> [u8; 32] {
+    Sha256::digest(key.as_bytes()).into()
+}
+
+impl CredentialsVerifier for ApiKeyVerifier {
+    fn verify(&self, credentials: &str) -> Result<Option<Principal>, AuthError> {
+        Ok(self.keys.get(&digest(credentials)).cloned())
+    }
+}
+
+/// JWT verification against the keys of local JWKS files.
+pub struct JwtVerifier {
+    keys: HashMap<String, JwtKey>,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+/// The algorithm is pinned per key rather than taken from the token header, so that a token
+/// can't pick a weaker algorithm than the one the key is meant for.
+struct JwtKey {
+    key: DecodingKey,
+    algorithm: Algorithm,
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+}
+
+impl JwtVerifier {
+    pub fn from_options(options: &JwtAuthOptions) -> Result<Self, AuthConfigError> {
+        let mut keys = HashMap::new();
+        for path in &options.jwks_paths {
+            for jwk in read_jwks(path)?.keys {
+                let Some(kid) = jwk.common.key_id.clone() else {
+                    return Err(AuthConfigError::MissingKeyId { path: path.clone() });
+                };
+                let Some(algorithm) = key_algorithm(&jwk) else {
+                    return Err(AuthConfigError::UnsupportedAlgorithm {
+                        path: path.clone(),
+                        kid,
+                    });
+                };
+                let key = DecodingKey::from_jwk(&jwk).map_err(|source| {
+                    AuthConfigError::UnsupportedKey {
+                        path: path.clone(),
+                        kid: kid.clone(),
+                        source,
+                    }
+                })?;
+                keys.insert(kid, JwtKey { key, algorithm });
+            }
+        }
+
+        Ok(Self {
+            keys,
+            issuer: options.issuer.clone(),
+            audience: options.audience.clone(),
+        })
+    }
+}
+
+/// Signature algorithm of the key, either its `alg` or the default algorithm of its type.
+fn key_algorithm(jwk: &Jwk) -> Option<Algorithm> {
+    if let Some(key_algorithm) = &jwk.common.key_algorithm {
+        return match key_algorithm {
+            KeyAlgorithm::HS256 => Some(Algorithm::HS256),
+            KeyAlgorithm::HS384 => Some(Algorithm::HS384),
+            KeyAlgorithm::HS512 => Some(Algorithm::HS512),
+            KeyAlgorithm::ES256 => Some(Algorithm::ES256),
+            KeyAlgorithm::ES384 => Some(Algorithm::ES384),
+            KeyAlgorithm::RS256 => Some(Algorithm::RS256),
+            KeyAlgorithm::RS384 => Some(Algorithm::RS384),
+            KeyAlgorithm::RS512 => Some(Algorithm::RS512),
+            KeyAlgorithm::PS256 => Some(Algorithm::PS256),
+            KeyAlgorithm::PS384 => Some(Algorithm::PS384),
+            KeyAlgorithm::PS512 => Some(Algorithm::PS512),
+            KeyAlgorithm::EdDSA => Some(Algorithm::EdDSA),
+            // Encryption algorithms can't verify signatures
+            _ => None,
+        };
+    }
+
+    match &jwk.algorithm {
+        AlgorithmParameters::RSA(_) => Some(Algorithm::RS256),
+        AlgorithmParameters::EllipticCurve(params) => match params.curve {
+            EllipticCurve::P256 => Some(Algorithm::ES256),
+            EllipticCurve::P384 => Some(Algorithm::ES384),
+            _ => None,
+        },
+        AlgorithmParameters::OctetKeyPair(params) => {
+            (params.curve == EllipticCurve::Ed25519).then_some(Algorithm::EdDSA)
+        }
+        AlgorithmParameters::OctetKey(_) => Some(Algorithm::HS256),
+    }
+}
+
+fn read_jwks(path: &Path) -> Result<JwkSet, AuthConfigError> {
+    let content = std::fs::read(path).map_err(|source| AuthConfigError::JwksIo {
+        path: path.to_owned(),
+        source,
+    })?;
+    serde_json::from_slice(&content).map_err(|source| AuthConfigError::JwksParse {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+impl CredentialsVerifier for JwtVerifier {
+    fn verify(&self, credentials: &str) -> Result<Option<Principal>, AuthError> {
+        let Ok(header) = jsonwebtoken::decode_header(credentials) else {
+            // Not a JWT
+            return Ok(None);
+        };
+        let key = header
+            .kid
+            .as_ref()
+            .and_then(|kid| self.keys.get(kid))
+            .ok_or(AuthError::UnknownKeyId)?;
+
+        // Tokens signed with any other algorithm than the one of the key are rejected
+        let mut validation = Validation::new(key.algorithm);
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        if let Some(audience) = &self.audience {
+            validation.set_audience(&[audience]);
+        } else {
+            validation.validate_aud = false;
+        }
+
+        let token = jsonwebtoken::decode::<Claims>(credentials, &key.key, &validation)?;
+        Ok(Some(Principal(token.claims.sub)))
+    }
+}
+
+pub struct Authenticator {
+    verifiers: Vec<Box<dyn CredentialsVerifier>>,
+    service_allowlist: HashMap<String, HashSet<String>>,
+    principal_header: HeaderName,
+}
+
+impl Authenticator {
+    pub fn from_options(options: &IngressAuthOptions) -> Result<Self, AuthConfigError> {
+        let mut verifiers: Vec<Box<dyn CredentialsVerifier>> = Vec::new();
+        if !options.api_keys.is_empty() {
+            verifiers.push(Box::new(ApiKeyVerifier::new(
+                options
+                    .api_keys
+                    .iter()
+                    .map(|api_key| (api_key.principal.as_str(), api_key.key.as_str())),
+            )));
+        }
+        if let Some(jwt) = &options.jwt {
+            verifiers.push(Box::new(JwtVerifier::from_options(jwt)?));
+        }
+
+        Ok(Self {
+            verifiers,
+            service_allowlist: options
+                .service_allowlist
+                .iter()
+                .map(|(service, principals)| {
+                    (service.clone(), principals.iter().cloned().collect())
+                })
+                .collect(),
+            principal_header: HeaderName::try_from(options.principal_header.as_str())?,
+        })
+    }
+
+    fn is_enabled(&self) -> bool {
+        !self.verifiers.is_empty()
+    }
+
+    fn authenticate<B>(&self, req: &Request<B>) -> Result<Principal, AuthError> {
+        let credentials = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .ok_or(AuthError::MissingCredentials)?;
+
+        for verifier in &self.verifiers {
+            if let Some(principal) = verifier.verify(credentials)? {
+                return Ok(principal);
+            }
+        }
+        Err(AuthError::InvalidCredentials)
+    }
+
+    fn authorize(&self, principal: &Principal, path: &str) -> Result<(), AuthError> {
+        // Requests addressing invocations or awakeables by id can act on any service, hence
+        // only principals which are allowed to invoke every service may send them.
+        let Some(service) = target_service(path) else {
+            return if self
+                .service_allowlist
+                .values()
+                .all(|principals| principals.contains(&principal.0))
+            {
+                Ok(())
+            } else {
+                Err(AuthError::ForbiddenById(principal.0.clone()))
+            };
+        };
+        match self.service_allowlist.get(service) {
+            Some(principals) if !principals.contains(&principal.0) => Err(AuthError::Forbidden(
+                principal.0.clone(),
+                service.to_owned(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn is_health_check(path: &str) -> bool {
+    path.trim_end_matches('/') == "/restate/health"
+}
+
+#[derive(Clone)]
+pub struct AuthenticationLayer {
+    authenticator: Arc<Authenticator>,
+}
+
+impl AuthenticationLayer {
+    pub fn new(authenticator: Authenticator) -> Self {
+        Self {
+            authenticator: Arc::new(authenticator),
+        }
+    }
+}
+
+impl<S> Layer<S> for AuthenticationLayer {
+    type Service = Authentication<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Authentication {
+            inner,
+            authenticator: Arc::clone(&self.authenticator),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Authentication<S> {
+    inner: S,
+    authenticator: Arc<Authenticator>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for Authentication<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    ResBody: From<Bytes>,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Response<ResBody>, S::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        // Never trust a principal sent by the caller, also when authentication is disabled
+        req.headers_mut()
+            .remove(&self.authenticator.principal_header);
+
+        if !self.authenticator.is_enabled() || is_health_check(req.uri().path()) {
+            return Either::Right(self.inner.call(req));
+        }
+
+        let principal = match self.authenticator.authenticate(&req).and_then(|principal| {
+            self.authenticator
+                .authorize(&principal, req.uri().path())
+                .map(|_| principal)
+        }) {
+            Ok(principal) => principal,
+            Err(err) => {
+                debug!(%err, "Rejecting ingress request");
+                return Either::Left(future::ready(Ok(error_response(err))));
+            }
+        };
+
+        let Ok(principal_value) = HeaderValue::try_from(principal.0.as_str()) else {
+            return Either::Left(future::ready(Ok(error_response(
+                AuthError::InvalidCredentials,
+            ))));
+        };
+
+        // The credentials must not leak to the services
+        req.headers_mut().remove(header::AUTHORIZATION);
+        req.headers_mut()
+            .insert(self.authenticator.principal_header.clone(), principal_value);
+        req.extensions_mut().insert(principal);
+
+        Either::Right(self.inner.call(req))
+    }
+}
+
+fn error_response<B: From<Bytes>>(err: AuthError) -> Response<B> {
+    let mut builder = Response::builder()
+        .status(err.status_code())
+        .header(header::CONTENT_TYPE, "application/json");
+    if err.status_code() == StatusCode::UNAUTHORIZED {
+        builder = builder.header(header::WWW_AUTHENTICATE, "Bearer");
+    }
+    builder
+        .body(
+            Bytes::from(
+                serde_json::to_vec(&serde_json::json!({ "message": err.to_string() }))
+                    .expect("Serializing error response should not fail"),
+            )
+            .into(),
+        )
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+    use std::io::Write;
+
+    use http_body_util::Full;
+    use jsonwebtoken::{EncodingKey, Header};
+    use restate_types::config::ApiKeyOptions;
+    use tower::ServiceExt;
+
+    const HMAC_SECRET: &[u8] = b"an-hmac-secret-for-tests";
+    // Encoding is the same for base64 with and without the URL safe alphabet
+    const HMAC_SECRET_BASE64: &str = "YW4taG1hYy1zZWNyZXQtZm9yLXRlc3Rz";
+
+    fn auth_options() -> IngressAuthOptions {
+        IngressAuthOptions {
+            api_keys: vec![ApiKeyOptions {
+                principal: "billing".to_owned(),
+                key: "billing-key".to_owned(),
+            }],
+            service_allowlist: HashMap::from([("Payments".to_owned(), vec!["billing".to_owned()])]),
+            ..Default::default()
+        }
+    }
+
+    async fn call(
+        options: &IngressAuthOptions,
+        path: &str,
+        authorization: Option<&str>,
+    ) -> (StatusCode, Option<HeaderValue>) {
+        let principal_header = HeaderName::try_from(options.principal_header.as_str()).unwrap();
+        let service = AuthenticationLayer::new(Authenticator::from_options(options).unwrap())
+            .layer(tower::service_fn(move |req: Request<Full<Bytes>>| {
+                let principal = req.headers().get(&principal_header).cloned();
+                async move {
+                    Ok::<_, Infallible>(
+                        Response::builder()
+                            .extension(principal)
+                            .body(Full::<Bytes>::default())
+                            .unwrap(),
+                    )
+                }
+            }));
+
+        let mut req = Request::post(path).header("x-restate-principal", "spoofed");
+        if let Some(authorization) = authorization {
+            req = req.header(header::AUTHORIZATION, authorization);
+        }
+        let res = service
+            .oneshot(req.body(Full::default()).unwrap())
+            .await
+            .unwrap();
+
+        let principal = res
+            .extensions()
+            .get::<Option<HeaderValue>>()
+            .cloned()
+            .flatten();
+        (res.status(), principal)
+    }
+
+    #[tokio::test]
+    async fn api_key() {
+        let options = auth_options();
+
+        let (status, principal) =
+            call(&options, "/Greeter/greet", Some("Bearer billing-key")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(principal.unwrap(), "billing");
+
+        let (status, _) = call(&options, "/Greeter/greet", Some("Bearer wrong-key")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = call(&options, "/Greeter/greet", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn health_is_not_authenticated() {
+        let (status, principal) = call(&auth_options(), "/restate/health", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(principal.is_none());
+    }
+
+    #[tokio::test]
+    async fn service_allowlist() {
+        let mut options = auth_options();
+        options.api_keys.push(ApiKeyOptions {
+            principal: "frontend".to_owned(),
+            key: "frontend-key".to_owned(),
+        });
+
+        let (status, _) = call(&options, "/Payments/pay", Some("Bearer billing-key")).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _) = call(&options, "/Payments/pay", Some("Bearer frontend-key")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = call(&options, "/Greeter/greet", Some("Bearer frontend-key")).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn requests_by_id_require_access_to_every_service() {
+        let mut options = auth_options();
+        options.api_keys.push(ApiKeyOptions {
+            principal: "frontend".to_owned(),
+            key: "frontend-key".to_owned(),
+        });
+
+        for path in [
+            "/restate/invocation/inv_1abc/attach",
+            "/restate/awakeables/sign_1abc/resolve",
+        ] {
+            let (status, _) = call(&options, path, Some("Bearer frontend-key")).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{path}");
+
+            let (status, principal) = call(&options, path, Some("Bearer billing-key")).await;
+            assert_eq!(status, StatusCode::OK, "{path}");
+            assert_eq!(principal.unwrap(), "billing");
+        }
+    }
+
+    #[tokio::test]
+    async fn principal_header_is_stripped_without_authentication() {
+        let (status, principal) =
+            call(&IngressAuthOptions::default(), "/Greeter/greet", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(principal.is_none());
+    }
+
+    #[tokio::test]
+    async fn jwt() {
+        let mut jwks_file = tempfile::NamedTempFile::new().unwrap();
+        jwks_file
+            .write_all(
+                serde_json::json!({
+                    "keys": [{
+                        "kty": "oct",
+                        "kid": "key-1",
+                        "alg": "HS256",
+                        "k": HMAC_SECRET_BASE64,
+                    }]
+                })
+                .to_string()
+                .as_bytes(),
+            )
+            .unwrap();
+
+        let options = IngressAuthOptions {
+            jwt: Some(JwtAuthOptions {
+                jwks_paths: vec![jwks_file.path().to_owned()],
+                issuer: Some("https://issuer.example.com".to_owned()),
+                audience: None,
+            }),
+            ..Default::default()
+        };
+
+        let token_with_algorithm = |algorithm, kid: &str, issuer: &str| {
+            let mut header = Header::new(algorithm);
+            header.kid = Some(kid.to_owned());
+            jsonwebtoken::encode(
+                &header,
+                &serde_json::json!({
+                    "sub": "checkout",
+                    "iss": issuer,
+                    "exp": jsonwebtoken::get_current_timestamp() + 60,
+                }),
+                &EncodingKey::from_secret(HMAC_SECRET),
+            )
+            .unwrap()
+        };
+        let token = |kid: &str, issuer: &str| token_with_algorithm(Algorithm::HS256, kid, issuer);
+
+        let (status, principal) = call(
+            &options,
+            "/Greeter/greet",
+            Some(&format!(
+                "Bearer {}",
+                token("key-1", "https://issuer.example.com")
+            )),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(principal.unwrap(), "checkout");
+
+        let (status, _) = call(
+            &options,
+            "/Greeter/greet",
+            Some(&format!("Bearer {}", token("key-1", "https://evil.com"))),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = call(
+            &options,
+            "/Greeter/greet",
+            Some(&format!(
+                "Bearer {}",
+                token("key-2", "https://issuer.example.com")
+            )),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // The key only accepts the algorithm it is configured with
+        let (status, _) = call(
+            &options,
+            "/Greeter/greet",
+            Some(&format!(
+                "Bearer {}",
+                token_with_algorithm(Algorithm::HS512, "key-1", "https://issuer.example.com")
+            )),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn key_algorithm_defaults_to_the_key_type() {
+        let jwk = |value: serde_json::Value| serde_json::from_value::<Jwk>(value).unwrap();
+
+        assert_eq!(
+            key_algorithm(&jwk(serde_json::json!({
+                "kty": "oct",
+                "alg": "HS384",
+                "k": HMAC_SECRET_BASE64,
+            }))),
+            Some(Algorithm::HS384)
+        );
+        assert_eq!(
+            key_algorithm(&jwk(serde_json::json!({
+                "kty": "oct",
+                "k": HMAC_SECRET_BASE64,
+            }))),
+            Some(Algorithm::HS256)
+        );
+        assert_eq!(
+            key_algorithm(&jwk(serde_json::json!({
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo",
+            }))),
+            Some(Algorithm::EdDSA)
+        );
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use http::{HeaderName, HeaderValue, Method, Request, Response};
+use tower::{Layer, Service};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, Cors, CorsLayer, ResponseFuture};
+
+use restate_types::live::Live;
+use restate_types::schema::service::{CorsPolicy, ServiceMetadataResolver};
+use restate_types::{Version, Versioned};
+
+use super::target_service;
+
+/// Builds the [`CorsLayer`] enforcing the given policy.
+///
+/// Wildcards mirror the request rather than answering with `*`, because browsers refuse `*`
+/// when credentials are allowed.
+pub fn cors_layer(policy: &CorsPolicy) -> CorsLayer {
+    let is_wildcard = |values: &[String]| values.iter().any(|v| v == CorsPolicy::WILDCARD);
+
+    let allow_origin = if is_wildcard(&policy.allowed_origins) {
+        AllowOrigin::mirror_request()
+    } else {
+        AllowOrigin::list(
+            policy
+                .allowed_origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+        )
+    };
+    let allow_methods = if is_wildcard(&policy.allowed_methods) {
+        AllowMethods::mirror_request()
+    } else {
+        AllowMethods::list(
+            policy
+                .allowed_methods
+                .iter()
+                .filter_map(|method| Method::from_str(method).ok()),
+        )
+    };
+    let allow_headers = if is_wildcard(&policy.allowed_headers) {
+        AllowHeaders::mirror_request()
+    } else {
+        AllowHeaders::list(
+            policy
+                .allowed_headers
+                .iter()
+                .filter_map(|header| HeaderName::from_str(header).ok()),
+        )
+    };
+
+    let layer = CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(allow_methods)
+        .allow_headers(allow_headers)
+        .allow_credentials(policy.allow_credentials);
+
+    if let Some(max_age) = policy.max_age {
+        layer.max_age(Duration::from(max_age))
+    } else {
+        layer
+    }
+}
+
+/// Applies the CORS policy of the targeted service, falling back to the ingress default policy
+/// for services which don't override it.
+pub struct DynamicCorsLayer<Schemas> {
+    default: CorsLayer,
+    schemas: Live<Schemas>,
+    service_layers: Arc<Mutex<ServiceCorsLayers>>,
+}
+
+impl<Schemas> DynamicCorsLayer<Schemas> {
+    pub fn new(default_policy: &CorsPolicy, schemas: Live<Schemas>) -> Self {
+        Self {
+            default: cors_layer(default_policy),
+            schemas,
+            service_layers: Arc::default(),
+        }
+    }
+}
+
+/// Layers of the services overriding the default policy, built once per schema version.
+#[derive(Default)]
+struct ServiceCorsLayers {
+    version: Option<Version>,
+    layers: HashMap<String, CorsLayer>,
+}
+
+impl<S, Schemas: Clone> Layer<S> for DynamicCorsLayer<Schemas> {
+    type Service = DynamicCors<S, Schemas>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DynamicCors {
+            inner: self.default.layer(inner),
+            schemas: self.schemas.clone(),
+            service_layers: Arc::clone(&self.service_layers),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DynamicCors<S, Schemas> {
+    inner: Cors<S>,
+    schemas: Live<Schemas>,
+    service_layers: Arc<Mutex<ServiceCorsLayers>>,
+}
+
+impl<S, Schemas> DynamicCors<S, Schemas>
+where
+    Schemas: ServiceMetadataResolver + Versioned,
+{
+    fn service_layer(&self, service: &str) -> Option<CorsLayer> {
+        let schemas = self.schemas.pinned();
+        let mut service_layers = self
+            .service_layers
+            .lock()
+            .expect("service CORS layers lock is not poisoned");
+        if service_layers.version != Some(schemas.version()) {
+            service_layers.version = Some(schemas.version());
+            service_layers.layers.clear();
+        }
+
+        if let Some(layer) = service_layers.layers.get(service) {
+            return Some(layer.clone());
+        }
+        // Only overriding services are cached, so that requests to arbitrary paths can't grow
+        // the cache
+        let layer = cors_layer(&schemas.resolve_latest_service_cors(service)?);
+        service_layers
+            .layers
+            .insert(service.to_owned(), layer.clone());
+        Some(layer)
+    }
+}
+
+impl<S, Schemas, ReqBody, ResBody> Service<Request<ReqBody>> for DynamicCors<S, Schemas>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone,
+    Schemas: ServiceMetadataResolver + Versioned,
+    ResBody: Default,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let service_layer =
+            target_service(req.uri().path()).and_then(|service| self.service_layer(service));
+
+        match service_layer {
+            Some(layer) => {
+                // The inner service has been driven to readiness by poll_ready above,
+                // make sure we use that instance and leave a fresh clone in its place.
+                let clone = self.inner.get_ref().clone();
+                let inner = std::mem::replace(self.inner.get_mut(), clone);
+                layer.layer(inner).call(req)
+            }
+            None => self.inner.call(req),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bytes::Bytes;
+    use http::header;
+    use http_body_util::Full;
+    use restate_types::schema::service::test_util::MockServiceMetadataResolver;
+    use restate_types::schema::service::{InvalidCorsPolicy, ServiceMetadata};
+    use std::convert::Infallible;
+    use tower::ServiceExt;
+
+    async fn preflight(
+        layer: &DynamicCorsLayer<MockServiceMetadataResolver>,
+        path: &str,
+        origin: &str,
+    ) -> Option<HeaderValue> {
+        let service = layer.layer(tower::service_fn(|_: Request<Full<Bytes>>| async {
+            Ok::<_, Infallible>(Response::new(Full::<Bytes>::default()))
+        }));
+
+        let res = service
+            .oneshot(
+                Request::options(path)
+                    .header(header::ORIGIN, origin)
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                    .body(Full::default())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        res.headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .cloned()
+    }
+
+    #[tokio::test]
+    async fn per_service_override() {
+        let mut schemas = MockServiceMetadataResolver::default();
+        schemas.add(ServiceMetadata::mock_service("Greeter", ["greet"]));
+        let mut payments = ServiceMetadata::mock_service("Payments", ["pay"]);
+        payments.cors = Some(CorsPolicy {
+            allowed_origins: vec!["https://shop.example.com".to_owned()],
+            allowed_methods: vec!["POST".to_owned()],
+            allowed_headers: vec![],
+            allow_credentials: false,
+            max_age: None,
+        });
+        schemas.add(payments);
+
+        let layer = DynamicCorsLayer::new(&CorsPolicy::permissive(), Live::from_value(schemas));
+
+        assert_eq!(
+            preflight(&layer, "/Greeter/greet", "https://evil.com").await,
+            Some(HeaderValue::from_static("https://evil.com"))
+        );
+        assert_eq!(
+            preflight(&layer, "/Payments/pay", "https://shop.example.com").await,
+            Some(HeaderValue::from_static("https://shop.example.com"))
+        );
+        assert_eq!(
+            preflight(&layer, "/Payments/pay", "https://evil.com").await,
+            None
+        );
+    }
+
+    #[test]
+    fn credentials_are_not_allowed_by_default() {
+        assert!(!CorsPolicy::default().allow_credentials);
+
+        let policy: CorsPolicy = serde_json::from_value(serde_json::json!({
+            "allowed-origins": ["https://shop.example.com"],
+            "allowed-methods": ["POST"],
+            "allowed-headers": ["content-type"],
+        }))
+        .unwrap();
+        assert!(!policy.allow_credentials);
+        assert_eq!(policy.max_age, None);
+    }
+
+    #[test]
+    fn credentials_require_explicit_origins() {
+        let mut policy = CorsPolicy {
+            allow_credentials: true,
+            ..CorsPolicy::permissive()
+        };
+        assert!(matches!(
+            policy.validate(),
+            Err(InvalidCorsPolicy::CredentialsWithWildcardOrigin)
+        ));
+
+        policy.allowed_origins = vec!["https://shop.example.com".to_owned()];
+        assert!(policy.validate().is_ok());
+    }
+}
@@ -8,5 +8,38 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+pub mod authentication;
+pub mod cors;
 pub mod load_shed;
 pub mod tracing_context_extractor;
+
+/// Returns the name of the service targeted by a request path, if any.
+pub(crate) fn target_service(path: &str) -> Option<&str> {
+    let mut segments = path.trim_start_matches('/').split('/');
+    match segments.next()? {
+        "restate" => match segments.next()? {
+            "workflow" => segments.next(),
+            _ => None,
+        },
+        "" => None,
+        service => Some(service),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_services() {
+        assert_eq!(target_service("/Greeter/greet"), Some("Greeter"));
+        assert_eq!(target_service("/Counter/my-key/add"), Some("Counter"));
+        assert_eq!(
+            target_service("/restate/workflow/Signup/my-key/output"),
+            Some("Signup")
+        );
+        assert_eq!(target_service("/restate/awakeables/abc/resolve"), None);
+        assert_eq!(target_service("/restate/health"), None);
+        assert_eq!(target_service("/"), None);
+    }
+}
@@ -13,6 +13,7 @@ mod layers;
 mod metric_definitions;
 pub mod rpc_request_dispatcher;
 mod server;
+mod tls;
 
 pub use server::{HyperServerIngress, IngressServerError, StartSignal};
 
@@ -100,6 +101,7 @@ mod mocks {
     use restate_types::schema::service::{
         HandlerMetadata, ServiceMetadata, ServiceMetadataResolver,
     };
+    use restate_types::{Version, Versioned};
     use serde::{Deserialize, Serialize};
     use serde_json::Value;
     use std::sync::Arc;
@@ -140,6 +142,7 @@ mod mocks {
                     output_description: "any".to_string(),
                     input_json_schema: None,
                     output_json_schema: None,
+                    retry_policy: None,
                 }],
                 ty: invocation_target_metadata.target_ty.into(),
                 documentation: None,
@@ -151,6 +154,8 @@ mod mocks {
                 workflow_completion_retention: None,
                 inactivity_timeout: None,
                 abort_timeout: None,
+                cors: None,
+                retry_policy: None,
             });
             self.1
                 .add(service_name, [(handler_name, invocation_target_metadata)]);
@@ -167,6 +172,12 @@ mod mocks {
         }
     }
 
+    impl Versioned for MockSchemas {
+        fn version(&self) -> Version {
+            Version::MIN
+        }
+    }
+
     impl ServiceMetadataResolver for MockSchemas {
         fn resolve_latest_service(&self, service_name: impl AsRef<str>) -> Option<ServiceMetadata> {
             self.0.resolve_latest_service(service_name)
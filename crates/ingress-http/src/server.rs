@@ -11,6 +11,9 @@
 use super::*;
 
 use crate::handler::Handler;
+use crate::layers::authentication::{AuthConfigError, AuthenticationLayer, Authenticator};
+use crate::layers::cors::DynamicCorsLayer;
+use crate::tls::{ReloadableTlsAcceptor, TlsConfigError, TlsReloader};
 use codederror::CodedError;
 use http::{Request, Response};
 use http_body_util::Full;
@@ -18,24 +21,31 @@ use hyper::body::Incoming;
 use hyper_util::rt::TokioIo;
 use hyper_util::server::conn::auto;
 use restate_core::{cancellation_watcher, TaskCenter, TaskKind};
-use restate_types::config::IngressOptions;
+use restate_types::config::{IngressAuthOptions, IngressOptions, IngressTlsOptions};
 use restate_types::health::HealthStatus;
 use restate_types::live::Live;
 use restate_types::protobuf::common::IngressStatus;
 use restate_types::schema::invocation_target::InvocationTargetResolver;
 use restate_types::schema::service::ServiceMetadataResolver;
+use restate_types::schema::service::{CorsPolicy, InvalidCorsPolicy};
+use restate_types::Versioned;
 use std::convert::Infallible;
 use std::future::Future;
 use std::net::SocketAddr;
+use std::time::Duration;
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::oneshot;
+use tokio_util::either::Either;
 use tower::{ServiceBuilder, ServiceExt};
-use tower_http::cors::CorsLayer;
 use tower_http::normalize_path::NormalizePathLayer;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 pub type StartSignal = oneshot::Receiver<SocketAddr>;
 
+/// Connections which don't complete the TLS handshake within this time are closed, so that idle
+/// peers can't hold on to connection tasks.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Debug, thiserror::Error, CodedError)]
 pub enum IngressServerError {
     #[error(
@@ -47,6 +57,15 @@ pub enum IngressServerError {
         #[source]
         source: std::io::Error,
     },
+    #[error("failed loading the TLS configuration specified in 'ingress.tls': {0}")]
+    #[code(unknown)]
+    Tls(#[from] TlsConfigError),
+    #[error("failed loading the authentication configuration specified in 'ingress.auth': {0}")]
+    #[code(unknown)]
+    Auth(#[from] AuthConfigError),
+    #[error("invalid CORS policy specified in 'ingress.cors': {0}")]
+    #[code(unknown)]
+    Cors(#[from] InvalidCorsPolicy),
     #[error("error while running ingress http server: {0}")]
     #[code(unknown)]
     Running(#[from] hyper::Error),
@@ -55,6 +74,9 @@ pub enum IngressServerError {
 pub struct HyperServerIngress<Schemas, Dispatcher> {
     listening_addr: SocketAddr,
     concurrency_limit: usize,
+    tls_options: Option<IngressTlsOptions>,
+    auth_options: IngressAuthOptions,
+    cors_policy: CorsPolicy,
 
     // Parameters to build the layers
     schemas: Live<Schemas>,
@@ -67,7 +89,13 @@ pub struct HyperServerIngress<Schemas, Dispatcher> {
 
 impl<Schemas, Dispatcher> HyperServerIngress<Schemas, Dispatcher>
 where
-    Schemas: ServiceMetadataResolver + InvocationTargetResolver + Clone + Send + Sync + 'static,
+    Schemas: ServiceMetadataResolver
+        + InvocationTargetResolver
+        + Versioned
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
 {
     pub fn from_options(
@@ -80,6 +108,9 @@ where
         let (hyper_ingress_server, _) = HyperServerIngress::new(
             ingress_options.bind_address,
             ingress_options.concurrent_api_requests_limit(),
+            ingress_options.tls.clone(),
+            ingress_options.auth.clone(),
+            ingress_options.cors.clone(),
             schemas,
             dispatcher,
             health,
@@ -91,12 +122,21 @@ where
 
 impl<Schemas, Dispatcher> HyperServerIngress<Schemas, Dispatcher>
 where
-    Schemas: ServiceMetadataResolver + InvocationTargetResolver + Clone + Send + Sync + 'static,
+    Schemas: ServiceMetadataResolver
+        + InvocationTargetResolver
+        + Versioned
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
 {
     pub(crate) fn new(
         listening_addr: SocketAddr,
         concurrency_limit: usize,
+        tls_options: Option<IngressTlsOptions>,
+        auth_options: IngressAuthOptions,
+        cors_policy: CorsPolicy,
         schemas: Live<Schemas>,
         dispatcher: Dispatcher,
         health: HealthStatus<IngressStatus>,
@@ -107,6 +147,9 @@ where
         let ingress = Self {
             listening_addr,
             concurrency_limit,
+            tls_options,
+            auth_options,
+            cors_policy,
             schemas,
             dispatcher,
             health,
@@ -120,12 +163,30 @@ where
         let HyperServerIngress {
             listening_addr,
             concurrency_limit,
+            tls_options,
+            auth_options,
+            cors_policy,
             schemas,
             dispatcher,
             health,
             start_signal_tx,
         } = self;
 
+        // Load the TLS configuration before binding, so that a broken certificate setup
+        // never results in serving plaintext.
+        let tls_acceptor = match tls_options {
+            Some(tls_options) => {
+                let (reloader, acceptor) =
+                    TlsReloader::load(tls_options).map_err(IngressServerError::from)?;
+                TaskCenter::spawn_child(TaskKind::Ingress, "ingress-tls-reloader", reloader.run())?;
+                Some(acceptor)
+            }
+            None => None,
+        };
+        let authenticator =
+            Authenticator::from_options(&auth_options).map_err(IngressServerError::from)?;
+        cors_policy.validate().map_err(IngressServerError::from)?;
+
         // We create a TcpListener and bind it
         let listener =
             TcpListener::bind(listening_addr)
@@ -145,13 +206,15 @@ where
         let service = ServiceBuilder::new()
             .layer(NormalizePathLayer::trim_trailing_slash())
             .layer(layers::load_shed::LoadShedLayer::new(concurrency_limit))
-            .layer(CorsLayer::very_permissive())
+            .layer(DynamicCorsLayer::new(&cors_policy, schemas.clone()))
+            .layer(AuthenticationLayer::new(authenticator))
             .layer(layers::tracing_context_extractor::HttpTraceContextExtractorLayer)
             .service(Handler::new(schemas, dispatcher));
 
         info!(
             net.host.addr = %local_addr.ip(),
             net.host.port = %local_addr.port(),
+            tls = tls_acceptor.is_some(),
             "Ingress HTTP listening"
         );
 
@@ -167,7 +230,7 @@ where
             tokio::select! {
                 res = listener.accept() => {
                     let (stream, remote_peer) = res?;
-                    Self::handle_connection(stream, remote_peer, service.clone())?;
+                    Self::handle_connection(stream, remote_peer, tls_acceptor.clone(), service.clone())?;
                 }
                   _ = &mut shutdown => {
                     return Ok(());
@@ -179,6 +242,7 @@ where
     fn handle_connection<T, F>(
         stream: TcpStream,
         remote_peer: SocketAddr,
+        tls_acceptor: Option<ReloadableTlsAcceptor>,
         handler: T,
     ) -> anyhow::Result<()>
     where
@@ -193,7 +257,6 @@ where
             + 'static,
     {
         let connect_info = ConnectInfo::new(remote_peer);
-        let io = TokioIo::new(stream);
         let handler = hyper_util::service::TowerToHyperService::new(handler.map_request(
             move |mut req: Request<Incoming>| {
                 req.extensions_mut().insert(connect_info);
@@ -204,6 +267,34 @@ where
         // Spawn a tokio task to serve the connection
         TaskCenter::spawn(TaskKind::Ingress, "ingress", async move {
             let shutdown = cancellation_watcher();
+            tokio::pin!(shutdown);
+
+            let stream = match tls_acceptor {
+                Some(tls_acceptor) => {
+                    // The handshake runs in the connection task, to not hold up the accept loop
+                    let handshake = tokio::time::timeout(
+                        TLS_HANDSHAKE_TIMEOUT,
+                        tls_acceptor.acceptor().accept(stream),
+                    );
+                    tokio::select! {
+                        res = handshake => match res {
+                            Ok(Ok(tls_stream)) => Either::Right(tls_stream),
+                            Ok(Err(err)) => {
+                                debug!(%remote_peer, "TLS handshake failed: {}", err);
+                                return Ok(());
+                            }
+                            Err(_) => {
+                                debug!(%remote_peer, "TLS handshake timed out");
+                                return Ok(());
+                            }
+                        },
+                        _ = &mut shutdown => return Ok(()),
+                    }
+                }
+                None => Either::Left(stream),
+            };
+
+            let io = TokioIo::new(stream);
             let auto_connection = auto::Builder::new(TaskCenterExecutor);
             let serve_connection_fut = auto_connection.serve_connection(io, handler);
 
@@ -342,6 +433,9 @@ mod tests {
         let (ingress, start_signal) = HyperServerIngress::new(
             "0.0.0.0:0".parse().unwrap(),
             Semaphore::MAX_PERMITS,
+            None,
+            IngressAuthOptions::default(),
+            CorsPolicy::permissive(),
             Live::from_value(mock_schemas()),
             Arc::new(mock_request_dispatcher),
             health.ingress_status(),
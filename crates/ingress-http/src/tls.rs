@@ -0,0 +1,390 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use arc_swap::ArcSwap;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, info, warn};
+
+use restate_core::cancellation_watcher;
+use restate_types::config::IngressTlsOptions;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TlsConfigError {
+    #[error("failed reading '{path}': {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("no certificate found in '{0}'")]
+    NoCertificate(PathBuf),
+    #[error("no private key found in '{0}'")]
+    NoPrivateKey(PathBuf),
+    #[error("invalid client CA certificates in '{path}': {source}")]
+    ClientCa {
+        path: PathBuf,
+        #[source]
+        source: rustls::server::VerifierBuilderError,
+    },
+    #[error("'reload-interval' must be greater than 0")]
+    ZeroReloadInterval,
+    #[error(transparent)]
+    Rustls(#[from] rustls::Error),
+}
+
+/// Hands out [`TlsAcceptor`]s for the currently loaded server certificate. The certificate can
+/// be swapped at runtime by the [`TlsReloader`], which only affects connections accepted
+/// afterwards.
+#[derive(Clone)]
+pub(crate) struct ReloadableTlsAcceptor {
+    config: Arc<ArcSwap<ServerConfig>>,
+}
+
+impl ReloadableTlsAcceptor {
+    pub(crate) fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.config.load_full())
+    }
+}
+
+/// Periodically checks the configured certificate files and reloads them on change.
+pub(crate) struct TlsReloader {
+    options: IngressTlsOptions,
+    last_modified: Vec<Option<SystemTime>>,
+    config: Arc<ArcSwap<ServerConfig>>,
+}
+
+impl TlsReloader {
+    /// Loads the initial configuration. Fails if the configured files cannot be read, so that
+    /// misconfigurations are reported on startup rather than on the first connection.
+    pub(crate) fn load(
+        options: IngressTlsOptions,
+    ) -> Result<(Self, ReloadableTlsAcceptor), TlsConfigError> {
+        // tokio's interval panics on a zero period
+        if options.reload_interval.is_zero() {
+            return Err(TlsConfigError::ZeroReloadInterval);
+        }
+
+        let last_modified = modification_times(&options);
+        let config = Arc::new(ArcSwap::from_pointee(build_server_config(&options)?));
+
+        Ok((
+            Self {
+                options,
+                last_modified,
+                config: Arc::clone(&config),
+            },
+            ReloadableTlsAcceptor { config },
+        ))
+    }
+
+    pub(crate) async fn run(mut self) -> anyhow::Result<()> {
+        let mut interval = tokio::time::interval(self.options.reload_interval.into());
+        // the first tick completes immediately
+        interval.tick().await;
+
+        let shutdown = cancellation_watcher();
+        tokio::pin!(shutdown);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    self.reload_if_changed();
+                }
+                _ = &mut shutdown => {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn reload_if_changed(&mut self) {
+        let last_modified = modification_times(&self.options);
+        if last_modified == self.last_modified {
+            return;
+        }
+
+        debug!("Ingress TLS files changed, reloading");
+        match build_server_config(&self.options) {
+            Ok(config) => {
+                self.config.store(Arc::new(config));
+                self.last_modified = last_modified;
+                info!(
+                    cert_path = %self.options.cert_path.display(),
+                    "Reloaded ingress TLS certificate"
+                );
+            }
+            Err(err) => {
+                // Files might be in the middle of being rotated. We keep serving with the
+                // previous certificate and retry on the next tick.
+                warn!(%err, "Failed reloading ingress TLS certificate, keeping the previous one");
+            }
+        }
+    }
+}
+
+fn modification_times(options: &IngressTlsOptions) -> Vec<Option<SystemTime>> {
+    [
+        Some(options.cert_path.as_path()),
+        Some(options.key_path.as_path()),
+        options.client_ca_path.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|path| {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    })
+    .collect()
+}
+
+pub(crate) fn build_server_config(
+    options: &IngressTlsOptions,
+) -> Result<ServerConfig, TlsConfigError> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+    let certs = load_certs(&options.cert_path)?;
+    let key = load_private_key(&options.key_path)?;
+
+    let builder = ServerConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()?;
+
+    let builder = if let Some(client_ca_path) = &options.client_ca_path {
+        let mut roots = RootCertStore::empty();
+        for cert in load_certs(client_ca_path)? {
+            roots.add(cert)?;
+        }
+        let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+            .build()
+            .map_err(|source| TlsConfigError::ClientCa {
+                path: client_ca_path.clone(),
+                source,
+            })?;
+        builder.with_client_cert_verifier(verifier)
+    } else {
+        builder.with_no_client_auth()
+    };
+
+    let mut config = builder.with_single_cert(certs, key)?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(config)
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, TlsConfigError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|source| TlsConfigError::Io {
+            path: path.to_owned(),
+            source,
+        })
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, TlsConfigError> {
+    let certs = rustls_pemfile::certs(&mut open(path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|source| TlsConfigError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+
+    if certs.is_empty() {
+        return Err(TlsConfigError::NoCertificate(path.to_owned()));
+    }
+
+    Ok(certs)
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>, TlsConfigError> {
+    rustls_pemfile::private_key(&mut open(path)?)
+        .map_err(|source| TlsConfigError::Io {
+            path: path.to_owned(),
+            source,
+        })?
+        .ok_or_else(|| TlsConfigError::NoPrivateKey(path.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+    use std::time::Duration;
+
+    use rcgen::{BasicConstraints, CertificateParams, IsCa, KeyPair};
+    use rustls::pki_types::ServerName;
+    use rustls::ClientConfig;
+    use tempfile::TempDir;
+    use tokio_rustls::TlsConnector;
+
+    /// A CA whose certificates are written to PEM files.
+    struct TestPki {
+        dir: TempDir,
+        ca: rcgen::Certificate,
+        ca_key: KeyPair,
+    }
+
+    impl TestPki {
+        fn new() -> Self {
+            let ca_key = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            let ca = params.self_signed(&ca_key).unwrap();
+
+            Self {
+                dir: tempfile::tempdir().unwrap(),
+                ca,
+                ca_key,
+            }
+        }
+
+        /// Issues a certificate for `localhost` and writes it to the files of `options`.
+        fn issue_server_certificate(&self, options: &IngressTlsOptions) -> CertificateDer<'static> {
+            let key = KeyPair::generate().unwrap();
+            let params = CertificateParams::new(vec!["localhost".to_owned()]).unwrap();
+            let cert = params.signed_by(&key, &self.ca, &self.ca_key).unwrap();
+
+            std::fs::write(&options.cert_path, cert.pem()).unwrap();
+            std::fs::write(&options.key_path, key.serialize_pem()).unwrap();
+            cert.der().clone()
+        }
+
+        fn server_options(&self) -> IngressTlsOptions {
+            IngressTlsOptions::new(
+                self.dir.path().join("server.pem"),
+                self.dir.path().join("server.key"),
+            )
+        }
+
+        fn connector(&self) -> TlsConnector {
+            let mut roots = RootCertStore::empty();
+            roots.add(self.ca.der().clone()).unwrap();
+            let config = ClientConfig::builder_with_provider(Arc::new(
+                rustls::crypto::ring::default_provider(),
+            ))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+            TlsConnector::from(Arc::new(config))
+        }
+    }
+
+    /// Runs a handshake over an in-memory stream and returns the certificate the server
+    /// presented.
+    async fn handshake(
+        acceptor: &ReloadableTlsAcceptor,
+        connector: &TlsConnector,
+    ) -> Result<CertificateDer<'static>, std::io::Error> {
+        let (client_io, server_io) = tokio::io::duplex(16 * 1024);
+        let (accepted, connected) = tokio::join!(
+            acceptor.acceptor().accept(server_io),
+            connector.connect(ServerName::try_from("localhost").unwrap(), client_io)
+        );
+        accepted?;
+        let connected = connected?;
+        let (_, connection) = connected.get_ref();
+        Ok(connection.peer_certificates().unwrap()[0].clone())
+    }
+
+    #[tokio::test]
+    async fn handshake_presents_the_loaded_certificate() {
+        let pki = TestPki::new();
+        let options = pki.server_options();
+        let certificate = pki.issue_server_certificate(&options);
+
+        let (_reloader, acceptor) = TlsReloader::load(options).unwrap();
+
+        assert_eq!(
+            handshake(&acceptor, &pki.connector()).await.unwrap(),
+            certificate
+        );
+        // clients which don't trust the CA reject the certificate
+        assert!(handshake(&acceptor, &TestPki::new().connector())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn reloads_changed_certificate() {
+        let pki = TestPki::new();
+        let options = pki.server_options();
+        let certificate = pki.issue_server_certificate(&options);
+        let (mut reloader, acceptor) = TlsReloader::load(options.clone()).unwrap();
+
+        // unchanged files are not reloaded
+        reloader.reload_if_changed();
+        assert_eq!(
+            handshake(&acceptor, &pki.connector()).await.unwrap(),
+            certificate
+        );
+
+        let renewed_certificate = pki.issue_server_certificate(&options);
+        // the files might have been written within the timestamp granularity of the filesystem
+        for path in [&options.cert_path, &options.key_path] {
+            File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(SystemTime::now() + Duration::from_secs(1))
+                .unwrap();
+        }
+        reloader.reload_if_changed();
+
+        assert_eq!(
+            handshake(&acceptor, &pki.connector()).await.unwrap(),
+            renewed_certificate
+        );
+    }
+
+    #[test]
+    fn zero_reload_interval() {
+        let pki = TestPki::new();
+        let mut options = pki.server_options();
+        pki.issue_server_certificate(&options);
+        options.reload_interval = Duration::ZERO.into();
+
+        assert!(matches!(
+            TlsReloader::load(options),
+            Err(TlsConfigError::ZeroReloadInterval)
+        ));
+    }
+
+    #[test]
+    fn missing_certificate_file() {
+        let options = IngressTlsOptions::new("/does/not/exist.pem", "/does/not/exist.key");
+
+        assert!(matches!(
+            build_server_config(&options),
+            Err(TlsConfigError::Io { path, .. }) if path == Path::new("/does/not/exist.pem")
+        ));
+    }
+
+    #[test]
+    fn empty_certificate_file() {
+        let mut cert_file = tempfile::NamedTempFile::new().unwrap();
+        cert_file.write_all(b"not a pem file").unwrap();
+        let options = IngressTlsOptions::new(cert_file.path(), "/does/not/exist.key");
+
+        assert!(matches!(
+            build_server_config(&options),
+            Err(TlsConfigError::NoCertificate(path)) if path == cert_file.path()
+        ));
+    }
+}
@@ -9,31 +9,43 @@
 // by the Apache License, Version 2.0.
 
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use base64::Engine;
 use bytes::Bytes;
-use metrics::counter;
+use metrics::{counter, gauge, Label};
 use opentelemetry::trace::TraceContextExt;
 use rdkafka::consumer::stream_consumer::StreamPartitionQueue;
-use rdkafka::consumer::{Consumer, DefaultConsumerContext, StreamConsumer};
+use rdkafka::consumer::{CommitMode, Consumer, ConsumerContext, StreamConsumer};
 use rdkafka::error::KafkaError;
-use rdkafka::message::BorrowedMessage;
-use rdkafka::{ClientConfig, Message};
+use rdkafka::message::{BorrowedMessage, Headers, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::{ClientConfig, ClientContext, Message, Statistics};
 use tokio::sync::oneshot;
-use tracing::{debug, info, info_span, Instrument};
+use tracing::{debug, info, info_span, warn, Instrument};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use crate::dispatcher::{DispatchKafkaEvent, KafkaIngressDispatcher, KafkaIngressEvent};
-use crate::metric_definitions::KAFKA_INGRESS_REQUESTS;
+use crate::metric_definitions::{
+    KAFKA_INGRESS_COMMITTED_OFFSET, KAFKA_INGRESS_CONSUMER_LAG, KAFKA_INGRESS_HIGH_WATERMARK,
+    KAFKA_INGRESS_POISON_RECORDS, KAFKA_INGRESS_REQUESTS,
+};
+use crate::offsets::consumer_lag;
+use crate::value_decoder::{DecodeError, ValueDecoder};
 use restate_core::{cancellation_watcher, TaskCenter, TaskId, TaskKind};
+use restate_storage_api::parked_kafka_record_table::ParkedKafkaRecord;
+use restate_types::identifiers::partitioner::HashPartitioner;
 use restate_types::invocation::{Header, SpanRelation};
 use restate_types::message::MessageIndex;
 use restate_types::schema::subscriptions::{
-    EventInvocationTargetTemplate, EventReceiverServiceType, Sink, Subscription,
+    ErrorPolicy, EventInvocationTargetTemplate, EventReceiverServiceType, Sink, Subscription,
 };
+use restate_types::time::MillisSinceEpoch;
+
+/// How long to wait for space in the queue of the dead-letter producer
+const DEAD_LETTER_QUEUE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -49,15 +61,124 @@ pub enum Error {
         #[source]
         cause: anyhow::Error,
     },
+    #[error("cannot decode message topic {topic} partition {partition} offset {offset}: {cause}")]
+    SchemaRegistry {
+        topic: String,
+        partition: i32,
+        offset: i64,
+        #[source]
+        cause: DecodeError,
+    },
     #[error("ingress dispatcher channel is closed")]
     IngressDispatcherClosed,
+    #[error("error publishing message topic {topic} partition {partition} offset {offset} to the dead-letter topic: {cause}")]
+    DeadLetter {
+        topic: String,
+        partition: i32,
+        offset: i64,
+        #[source]
+        cause: KafkaError,
+    },
     #[error("topic {0} partition {1} queue split didn't succeed")]
     TopicPartitionSplit(String, i32),
 }
 
-type MessageConsumer = StreamConsumer<DefaultConsumerContext>;
+type MessageConsumer = StreamConsumer<OffsetsReportingContext>;
 
-#[derive(Debug, Hash)]
+/// Exports the offsets of the consumed partitions as metrics, from the statistics which
+/// librdkafka emits every `statistics.interval.ms`.
+///
+/// Partitions stop being reported once they are revoked from the consumer, or once the consumer
+/// is dropped because the subscription was stopped, paused or deleted. Since metrics can't be
+/// unregistered, their lag is reset so that the consumers which took over are the only ones
+/// reporting a lag for them.
+pub struct OffsetsReportingContext {
+    subscription_id: String,
+    reported_partitions: Mutex<HashSet<(String, i32)>>,
+}
+
+impl OffsetsReportingContext {
+    fn new(subscription_id: String) -> Self {
+        Self {
+            subscription_id,
+            reported_partitions: Default::default(),
+        }
+    }
+
+    fn labels(&self, topic: &str, partition: i32) -> Vec<Label> {
+        vec![
+            Label::new("subscription", self.subscription_id.clone()),
+            Label::new("topic", topic.to_owned()),
+            Label::new("partition", partition.to_string()),
+        ]
+    }
+
+    fn clear_partition(&self, topic: &str, partition: i32) {
+        gauge!(KAFKA_INGRESS_CONSUMER_LAG, self.labels(topic, partition)).set(0.0);
+    }
+}
+
+impl ClientContext for OffsetsReportingContext {
+    fn stats(&self, statistics: Statistics) {
+        let mut reported_partitions = self
+            .reported_partitions
+            .lock()
+            .expect("reported partitions lock is not poisoned");
+        let mut still_reported = HashSet::with_capacity(reported_partitions.len());
+
+        for (topic, topic_statistics) in statistics.topics {
+            for (partition, partition_statistics) in topic_statistics.partitions {
+                // librdkafka reports the internal unassigned partition as -1, and keeps
+                // reporting partitions which were revoked as not desired. Offsets are
+                // negative as long as they are unknown.
+                if partition < 0
+                    || !partition_statistics.desired
+                    || partition_statistics.committed_offset < 0
+                    || partition_statistics.hi_offset < 0
+                {
+                    continue;
+                }
+
+                let labels = self.labels(&topic, partition);
+                gauge!(KAFKA_INGRESS_COMMITTED_OFFSET, labels.clone())
+                    .set(partition_statistics.committed_offset as f64);
+                gauge!(KAFKA_INGRESS_HIGH_WATERMARK, labels.clone())
+                    .set(partition_statistics.hi_offset as f64);
+                gauge!(KAFKA_INGRESS_CONSUMER_LAG, labels).set(consumer_lag(
+                    partition_statistics.committed_offset,
+                    partition_statistics.hi_offset,
+                ) as f64);
+                still_reported.insert((topic.clone(), partition));
+            }
+        }
+
+        for (topic, partition) in reported_partitions.difference(&still_reported) {
+            self.clear_partition(topic, *partition);
+        }
+        *reported_partitions = still_reported;
+    }
+}
+
+impl Drop for OffsetsReportingContext {
+    fn drop(&mut self) {
+        let reported_partitions = std::mem::take(
+            self.reported_partitions
+                .get_mut()
+                .expect("reported partitions lock is not poisoned"),
+        );
+        for (topic, partition) in &reported_partitions {
+            self.clear_partition(topic, *partition);
+        }
+    }
+}
+
+impl ConsumerContext for OffsetsReportingContext {}
+
+// The producer id and the hash must not change, otherwise the partition processors no longer
+// deduplicate the records consumed before, and proxied records are routed to another partition.
+// Resetting the offsets only changes the sequence numbers, see
+// [`Subscription::deduplication_index`].
+#[derive(Debug, Clone, Hash)]
 pub struct KafkaDeduplicationId {
     consumer_group: String,
     topic: String,
@@ -89,10 +210,48 @@ impl KafkaDeduplicationId {
     }
 }
 
+/// Applies the [`ErrorPolicy`] of a subscription to records which cannot be turned into
+/// invocations.
+#[derive(Clone)]
+pub enum PoisonRecordHandler {
+    Fail,
+    Skip,
+    DeadLetter {
+        topic: String,
+        producer: FutureProducer,
+    },
+    Park,
+}
+
+impl PoisonRecordHandler {
+    pub fn new(error_policy: ErrorPolicy, client_config: &ClientConfig) -> Result<Self, Error> {
+        Ok(match error_policy {
+            ErrorPolicy::Fail => PoisonRecordHandler::Fail,
+            ErrorPolicy::Skip => PoisonRecordHandler::Skip,
+            ErrorPolicy::DeadLetter { topic } => PoisonRecordHandler::DeadLetter {
+                topic,
+                producer: client_config.create()?,
+            },
+            ErrorPolicy::Park => PoisonRecordHandler::Park,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            PoisonRecordHandler::Fail => "fail",
+            PoisonRecordHandler::Skip => "skip",
+            PoisonRecordHandler::DeadLetter { .. } => "dead-letter",
+            PoisonRecordHandler::Park => "park",
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct MessageSender {
     subscription: Subscription,
     dispatcher: KafkaIngressDispatcher,
+    poison_record_handler: PoisonRecordHandler,
+    value_decoder: ValueDecoder,
     experimental_feature_kafka_ingress_next: bool,
 
     subscription_id: String,
@@ -103,6 +262,8 @@ impl MessageSender {
     pub fn new(
         subscription: Subscription,
         dispatcher: KafkaIngressDispatcher,
+        poison_record_handler: PoisonRecordHandler,
+        value_decoder: ValueDecoder,
         experimental_feature_kafka_ingress_next: bool,
     ) -> Self {
         Self {
@@ -113,6 +274,8 @@ impl MessageSender {
             ),
             subscription,
             dispatcher,
+            poison_record_handler,
+            value_decoder,
             experimental_feature_kafka_ingress_next,
         }
     }
@@ -145,23 +308,59 @@ impl MessageSender {
         let headers = Self::generate_events_attributes(&msg, &self.subscription_id);
 
         let (deduplication_id, deduplication_index) =
-            Self::generate_deduplication_id(consumer_group_id, &msg);
-        let req = KafkaIngressEvent::new(
-            &self.subscription,
-            key,
-            payload,
-            SpanRelation::Parent(ingress_span_context),
-            deduplication_id,
-            deduplication_index,
-            headers,
-            self.experimental_feature_kafka_ingress_next,
-        )
-        .map_err(|cause| Error::Event {
-            topic: msg.topic().to_string(),
-            partition: msg.partition(),
-            offset: msg.offset(),
-            cause,
-        })?;
+            Self::generate_deduplication_id(&self.subscription, consumer_group_id, &msg);
+
+        // Poison records keep the raw value read from Kafka, also when they failed after decoding
+        let mut event_headers = headers.clone();
+        let value = if msg.payload().is_none() {
+            // Tombstones have no value to decode, they are passed on with an empty body
+            Ok(payload.clone())
+        } else {
+            if self.value_decoder.is_json() {
+                event_headers.push(Header::new("content-type", "application/json"));
+            }
+            match self.value_decoder.decode(payload.clone()).await {
+                Ok(value) => Ok(value),
+                // The record is fine, but it cannot be decoded right now. The consumer is
+                // restarted and consumes it again.
+                Err(cause @ DecodeError::Registry(_)) => {
+                    return Err(Error::SchemaRegistry {
+                        topic: msg.topic().to_string(),
+                        partition: msg.partition(),
+                        offset: msg.offset(),
+                        cause,
+                    })
+                }
+                Err(DecodeError::Value(cause)) => Err(cause),
+            }
+        };
+        let req = match value.and_then(|value| {
+            KafkaIngressEvent::new(
+                &self.subscription,
+                key.clone(),
+                value,
+                SpanRelation::Parent(ingress_span_context),
+                deduplication_id.clone(),
+                deduplication_index,
+                event_headers,
+                self.experimental_feature_kafka_ingress_next,
+            )
+        }) {
+            Ok(req) => req,
+            Err(cause) => {
+                return self
+                    .handle_poison_record(
+                        &msg,
+                        key,
+                        payload,
+                        headers,
+                        (deduplication_id, deduplication_index),
+                        cause,
+                    )
+                    .instrument(ingress_span)
+                    .await
+            }
+        };
 
         self.ingress_request_counter.increment(1);
 
@@ -173,6 +372,104 @@ impl MessageSender {
         Ok(())
     }
 
+    async fn handle_poison_record(
+        &self,
+        msg: &BorrowedMessage<'_>,
+        key: Bytes,
+        payload: Bytes,
+        headers: Vec<Header>,
+        (deduplication_id, deduplication_index): (KafkaDeduplicationId, MessageIndex),
+        cause: anyhow::Error,
+    ) -> Result<(), Error> {
+        counter!(
+            KAFKA_INGRESS_POISON_RECORDS,
+            "subscription" => self.subscription_id.clone(),
+            "policy" => self.poison_record_handler.name()
+        )
+        .increment(1);
+
+        match &self.poison_record_handler {
+            PoisonRecordHandler::Fail => {
+                return Err(Error::Event {
+                    topic: msg.topic().to_string(),
+                    partition: msg.partition(),
+                    offset: msg.offset(),
+                    cause,
+                })
+            }
+            PoisonRecordHandler::Skip => {
+                warn!(
+                    "Skipping message topic {} partition {} offset {}: {cause:#}",
+                    msg.topic(),
+                    msg.partition(),
+                    msg.offset()
+                );
+            }
+            PoisonRecordHandler::DeadLetter { topic, producer } => {
+                // Forward the record as is, telling the consumers of the dead-letter topic where
+                // it comes from and why it could not be processed
+                let error = format!("{cause:#}");
+                let partition = msg.partition().to_string();
+                let offset = msg.offset().to_string();
+                let dead_letter_headers = msg
+                    .headers()
+                    .map(|headers| headers.detach())
+                    .unwrap_or_else(|| OwnedHeaders::new_with_capacity(5))
+                    .insert(kafka_header(
+                        "restate.subscription.id",
+                        &self.subscription_id,
+                    ))
+                    .insert(kafka_header("restate.error", &error))
+                    .insert(kafka_header("kafka.topic", msg.topic()))
+                    .insert(kafka_header("kafka.partition", &partition))
+                    .insert(kafka_header("kafka.offset", &offset));
+
+                let mut record = FutureRecord::<[u8], [u8]>::to(topic)
+                    .payload(&payload[..])
+                    .headers(dead_letter_headers);
+                if msg.key().is_some() {
+                    record = record.key(&key[..]);
+                }
+                producer
+                    .send(record, DEAD_LETTER_QUEUE_TIMEOUT)
+                    .await
+                    .map_err(|(cause, _)| Error::DeadLetter {
+                        topic: msg.topic().to_string(),
+                        partition: msg.partition(),
+                        offset: msg.offset(),
+                        cause,
+                    })?;
+                debug!(
+                    "Published message topic {} partition {} offset {} to the dead-letter topic {topic}: {error}",
+                    msg.topic(),
+                    msg.partition(),
+                    msg.offset()
+                );
+            }
+            PoisonRecordHandler::Park => {
+                let record = ParkedKafkaRecord {
+                    partition_key: HashPartitioner::compute_partition_key(&deduplication_id),
+                    subscription_id: self.subscription.id(),
+                    topic: msg.topic().into(),
+                    kafka_partition: msg.partition(),
+                    offset: msg.offset(),
+                    deduplication_index,
+                    key,
+                    payload,
+                    headers,
+                    error: format!("{cause:#}").into(),
+                    parked_at: MillisSinceEpoch::now(),
+                };
+                self.dispatcher
+                    .park_kafka_record(record, deduplication_id, deduplication_index)
+                    .await
+                    .map_err(|_| Error::IngressDispatcherClosed)?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn generate_events_attributes(msg: &impl Message, subscription_id: &str) -> Vec<Header> {
         let mut headers = Vec::with_capacity(6);
         headers.push(Header::new("kafka.offset", msg.offset().to_string()));
@@ -197,6 +494,7 @@ impl MessageSender {
     }
 
     fn generate_deduplication_id(
+        subscription: &Subscription,
         consumer_group: &str,
         msg: &impl Message,
     ) -> (KafkaDeduplicationId, MessageIndex) {
@@ -206,11 +504,18 @@ impl MessageSender {
                 topic: msg.topic().to_owned(),
                 partition: msg.partition(),
             },
-            msg.offset() as u64,
+            subscription.deduplication_index(msg.partition(), msg.offset()),
         )
     }
 }
 
+fn kafka_header<'a>(key: &'a str, value: &'a str) -> rdkafka::message::Header<'a, &'a str> {
+    rdkafka::message::Header {
+        key,
+        value: Some(value),
+    }
+}
+
 #[derive(Clone)]
 pub struct ConsumerTask {
     client_config: ClientConfig,
@@ -241,7 +546,9 @@ impl ConsumerTask {
             self.topics, self.client_config
         );
 
-        let consumer: Arc<MessageConsumer> = Arc::new(self.client_config.create()?);
+        let consumer: Arc<MessageConsumer> = Arc::new(self.client_config.create_with_context(
+            OffsetsReportingContext::new(self.sender.subscription_id.clone()),
+        )?);
         let topics: Vec<&str> = self.topics.iter().map(|x| &**x).collect();
         consumer.subscribe(&topics)?;
 
@@ -305,18 +612,39 @@ impl ConsumerTask {
                 }
             }
         };
-        for task_id in topic_partition_tasks.into_values() {
-            TaskCenter::cancel_task(task_id);
+        let partition_task_handles: Vec<_> = topic_partition_tasks
+            .into_values()
+            .filter_map(TaskCenter::cancel_task)
+            .collect();
+
+        if result.is_ok() {
+            // The subscription is stopped on purpose, e.g. because it was paused or updated.
+            // Let the partition consumers finish the records in flight, and commit the stored
+            // offsets right away, so that the next consumer resumes where this one stopped.
+            for handle in partition_task_handles {
+                let _ = handle.await;
+            }
+            if let Err(e) = consumer.commit_consumer_state(CommitMode::Sync) {
+                debug!(
+                    restate.subscription.id = %self.sender.subscription.id(),
+                    messaging.consumer.group.name = consumer_group_id,
+                    "Could not commit the offsets of the stopped consumer: {e}"
+                );
+            }
         }
         result
     }
+
+    pub fn subscription(&self) -> &Subscription {
+        &self.sender.subscription
+    }
 }
 
 async fn topic_partition_queue_consumption_loop(
     sender: MessageSender,
     topic: String,
     partition: i32,
-    topic_partition_consumer: StreamPartitionQueue<DefaultConsumerContext>,
+    topic_partition_consumer: StreamPartitionQueue<OffsetsReportingContext>,
     consumer: Arc<MessageConsumer>,
     consumer_group_id: String,
 ) -> Result<(), anyhow::Error> {
@@ -336,3 +664,53 @@ async fn topic_partition_queue_consumption_loop(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rdkafka::message::{OwnedMessage, Timestamp};
+
+    fn record(partition: i32, offset: i64) -> OwnedMessage {
+        OwnedMessage::new(
+            None,
+            None,
+            "my-topic".to_owned(),
+            Timestamp::NotAvailable,
+            partition,
+            offset,
+            None,
+        )
+    }
+
+    #[test]
+    fn deduplication_id_is_stable_across_offsets_resets() {
+        let mut subscription = Subscription::mock();
+        let (id, index) =
+            MessageSender::generate_deduplication_id(&subscription, "my-group", &record(3, 42));
+        assert_eq!(id.to_string(), "my-group-my-topic-3");
+        assert_eq!(index, 42);
+
+        subscription.raise_deduplication_base(3, 100);
+        let (reset_id, reset_index) =
+            MessageSender::generate_deduplication_id(&subscription, "my-group", &record(3, 0));
+
+        // same producer id and partition key, so the partition processor deduplicating the
+        // records of the Kafka partition stays the same, and only the sequence numbers move on
+        assert_eq!(reset_id.to_string(), id.to_string());
+        assert_eq!(
+            HashPartitioner::compute_partition_key(&reset_id),
+            HashPartitioner::compute_partition_key(&id)
+        );
+        assert_eq!(
+            HashPartitioner::compute_partition_key(&id),
+            HashPartitioner::compute_partition_key(("my-group", "my-topic", 3_i32))
+        );
+        assert!(reset_index > index);
+
+        // other Kafka partitions keep deduplicating the records consumed before
+        let (_, other_index) =
+            MessageSender::generate_deduplication_id(&subscription, "my-group", &record(4, 42));
+        assert_eq!(other_index, 42);
+    }
+}
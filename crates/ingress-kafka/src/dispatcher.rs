@@ -13,18 +13,14 @@ use bytes::Bytes;
 use restate_bifrost::Bifrost;
 use restate_core::{my_node_id, Metadata};
 use restate_storage_api::deduplication_table::DedupInformation;
+use restate_storage_api::parked_kafka_record_table::ParkedKafkaRecord;
 use restate_types::identifiers::{
     partitioner, InvocationId, PartitionKey, PartitionProcessorRpcRequestId, WithPartitionKey,
 };
-use restate_types::invocation::{
-    InvocationTarget, ServiceInvocation, SpanRelation, VirtualObjectHandlerType,
-    WorkflowHandlerType,
-};
+use restate_types::invocation::{ServiceInvocation, SpanRelation};
 use restate_types::message::MessageIndex;
 use restate_types::partition_table::PartitionTableError;
-use restate_types::schema::subscriptions::{
-    EventInvocationTargetTemplate, EventReceiverServiceType, Sink, Subscription,
-};
+use restate_types::schema::subscriptions::Subscription;
 use restate_types::GenerationalNodeId;
 use restate_wal_protocol::{
     append_envelope_to_bifrost, Command, Destination, Envelope, Header, Source,
@@ -61,66 +57,7 @@ impl KafkaIngressEvent {
             None
         };
 
-        let invocation_target = match subscription.sink() {
-            Sink::DeprecatedService {
-                ref name,
-                ref handler,
-                ty,
-            } => match ty {
-                EventReceiverServiceType::VirtualObject => InvocationTarget::virtual_object(
-                    &**name,
-                    std::str::from_utf8(&key)
-                        .map_err(|e| anyhow::anyhow!("The key must be valid UTF-8: {e}"))?
-                        .to_owned(),
-                    &**handler,
-                    VirtualObjectHandlerType::Exclusive,
-                ),
-                EventReceiverServiceType::Workflow => InvocationTarget::workflow(
-                    &**name,
-                    std::str::from_utf8(&key)
-                        .map_err(|e| anyhow::anyhow!("The key must be valid UTF-8: {e}"))?
-                        .to_owned(),
-                    &**handler,
-                    WorkflowHandlerType::Workflow,
-                ),
-                EventReceiverServiceType::Service => InvocationTarget::service(&**name, &**handler),
-            },
-            Sink::Invocation {
-                event_invocation_target_template,
-            } => match event_invocation_target_template {
-                EventInvocationTargetTemplate::Service { name, handler } => {
-                    InvocationTarget::service(name.clone(), handler.clone())
-                }
-                EventInvocationTargetTemplate::VirtualObject {
-                    name,
-                    handler,
-                    handler_ty,
-                } => InvocationTarget::virtual_object(
-                    name.clone(),
-                    std::str::from_utf8(&key)
-                        .map_err(|e| {
-                            anyhow::anyhow!("The Kafka record key must be valid UTF-8: {e}")
-                        })?
-                        .to_owned(),
-                    handler.clone(),
-                    *handler_ty,
-                ),
-                EventInvocationTargetTemplate::Workflow {
-                    name,
-                    handler,
-                    handler_ty,
-                } => InvocationTarget::workflow(
-                    name.clone(),
-                    std::str::from_utf8(&key)
-                        .map_err(|e| {
-                            anyhow::anyhow!("The Kafka record key must be valid UTF-8: {e}")
-                        })?
-                        .to_owned(),
-                    handler.clone(),
-                    *handler_ty,
-                ),
-            },
-        };
+        let invocation_target = subscription.sink().invocation_target(&key)?;
 
         // Generate service invocation
         let invocation_id = InvocationId::generate(&invocation_target, None);
@@ -160,6 +97,15 @@ pub trait DispatchKafkaEvent {
         &self,
         event: KafkaIngressEvent,
     ) -> impl std::future::Future<Output = Result<(), IngressDispatchError>> + Send;
+
+    /// Parks a record which could not be turned into an invocation. The record is deduplicated
+    /// like the events of its Kafka partition.
+    fn park_kafka_record(
+        &self,
+        record: ParkedKafkaRecord,
+        deduplication_id: KafkaDeduplicationId,
+        deduplication_index: MessageIndex,
+    ) -> impl std::future::Future<Output = Result<(), IngressDispatchError>> + Send;
 }
 
 #[derive(Clone)]
@@ -203,6 +149,31 @@ impl DispatchKafkaEvent for KafkaIngressDispatcher {
         );
         Ok(())
     }
+
+    async fn park_kafka_record(
+        &self,
+        record: ParkedKafkaRecord,
+        deduplication_id: KafkaDeduplicationId,
+        deduplication_index: MessageIndex,
+    ) -> Result<(), IngressDispatchError> {
+        let envelope = Envelope::new(
+            ingress_header(
+                record.partition_key,
+                my_node_id(),
+                deduplication_id.to_string(),
+                deduplication_index,
+            ),
+            Command::ParkKafkaRecord(record),
+        );
+        let (log_id, lsn) = append_envelope_to_bifrost(&self.bifrost, Arc::new(envelope)).await?;
+
+        debug!(
+            log_id = %log_id,
+            lsn = %lsn,
+            "Parked Kafka record written to bifrost"
+        );
+        Ok(())
+    }
 }
 
 fn wrap_service_invocation_in_envelope(
@@ -212,7 +183,24 @@ fn wrap_service_invocation_in_envelope(
     deduplication_source: String,
     deduplication_index: MessageIndex,
 ) -> Envelope {
-    let header = Header {
+    Envelope::new(
+        ingress_header(
+            partition_key,
+            from_node_id,
+            deduplication_source,
+            deduplication_index,
+        ),
+        Command::ProxyThrough(service_invocation),
+    )
+}
+
+fn ingress_header(
+    partition_key: PartitionKey,
+    from_node_id: GenerationalNodeId,
+    deduplication_source: String,
+    deduplication_index: MessageIndex,
+) -> Header {
+    Header {
         source: Source::Ingress {
             node_id: from_node_id,
             nodes_config_version: Metadata::with_current(|m| m.nodes_config_version()),
@@ -224,7 +212,5 @@ fn wrap_service_invocation_in_envelope(
                 deduplication_index,
             )),
         },
-    };
-
-    Envelope::new(header, Command::ProxyThrough(service_invocation))
+    }
 }
@@ -0,0 +1,224 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use metrics::counter;
+use rdkafka::error::KafkaError;
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+
+use restate_types::config::IngressOptions;
+use restate_types::identifiers::SubscriptionId;
+use restate_types::schema::subscriptions::{Sink, Subscription};
+
+use crate::metric_definitions::{KAFKA_EGRESS_DROPPED_RECORDS, KAFKA_EGRESS_RECORDS};
+
+/// How long to wait for space in the producer queue
+const QUEUE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, thiserror::Error)]
+pub enum EgressError {
+    #[error("subscription '{0}' does not publish to Kafka")]
+    NotEgress(SubscriptionId),
+    #[error("KafkaOptions is expected to contain the cluster '{0}'. This might happen if you registered a subscription with a cluster name, but this cluster is not available anymore in the configuration.")]
+    UnknownCluster(String),
+    #[error(transparent)]
+    Kafka(#[from] KafkaError),
+}
+
+impl EgressError {
+    /// Whether publishing the record again can succeed without changing the subscription. A
+    /// missing cluster can still be added to the configuration.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, EgressError::UnknownCluster(_) | EgressError::Kafka(_))
+    }
+}
+
+/// Publishes records to the Kafka topics of egress subscriptions.
+///
+/// Producers are created lazily, one per subscription, from the options of the cluster merged
+/// with the metadata of the subscription. A producer is created anew once the sink or the
+/// metadata of its subscription changed.
+#[derive(Clone, Default)]
+pub struct KafkaEgress {
+    producers: Arc<Mutex<HashMap<SubscriptionId, CachedProducer>>>,
+}
+
+struct CachedProducer {
+    sink: Sink,
+    metadata: HashMap<String, String>,
+    producer: FutureProducer,
+}
+
+impl CachedProducer {
+    fn is_current(&self, subscription: &Subscription) -> bool {
+        self.sink == *subscription.sink() && self.metadata == *subscription.metadata()
+    }
+}
+
+impl KafkaEgress {
+    /// Publishes the record and waits until the Kafka cluster acknowledged it.
+    pub async fn publish(
+        &self,
+        options: &IngressOptions,
+        subscription: &Subscription,
+        key: &[u8],
+        payload: &[u8],
+        headers: &[(&str, &str)],
+    ) -> Result<(), EgressError> {
+        let Sink::Kafka { cluster, topic } = subscription.sink() else {
+            return Err(EgressError::NotEgress(subscription.id()));
+        };
+        let producer = self.producer(options, subscription, cluster)?;
+
+        let mut kafka_headers = OwnedHeaders::new_with_capacity(headers.len());
+        for &(name, value) in headers {
+            kafka_headers = kafka_headers.insert(Header {
+                key: name,
+                value: Some(value),
+            });
+        }
+
+        producer
+            .send(
+                FutureRecord::to(topic)
+                    .key(key)
+                    .payload(payload)
+                    .headers(kafka_headers),
+                QUEUE_TIMEOUT,
+            )
+            .await
+            .map_err(|(err, _)| err)?;
+
+        counter!(KAFKA_EGRESS_RECORDS, "subscription" => subscription.id().to_string())
+            .increment(1);
+        Ok(())
+    }
+
+    /// Records that a record of the subscription has been dropped because it cannot be published.
+    pub fn record_dropped(&self, subscription_id: SubscriptionId) {
+        counter!(KAFKA_EGRESS_DROPPED_RECORDS, "subscription" => subscription_id.to_string())
+            .increment(1);
+    }
+
+    /// Drops the producer of a subscription which has been removed.
+    pub fn remove(&self, subscription_id: SubscriptionId) {
+        self.producers.lock().unwrap().remove(&subscription_id);
+    }
+
+    fn producer(
+        &self,
+        options: &IngressOptions,
+        subscription: &Subscription,
+        cluster: &str,
+    ) -> Result<FutureProducer, EgressError> {
+        let mut producers = self.producers.lock().unwrap();
+        if let Some(cached) = producers.get(&subscription.id()) {
+            if cached.is_current(subscription) {
+                return Ok(cached.producer.clone());
+            }
+        }
+
+        let cluster_options = options
+            .get_kafka_cluster(cluster)
+            .ok_or_else(|| EgressError::UnknownCluster(cluster.to_owned()))?;
+
+        let mut client_config = ClientConfig::new();
+        client_config.set("metadata.broker.list", cluster_options.brokers.join(","));
+        for (k, v) in &cluster_options.additional_options {
+            client_config.set(k, v);
+        }
+        for (k, v) in subscription.kafka_options() {
+            client_config.set(k, v);
+        }
+        // Retries of the producer must neither duplicate nor reorder records
+        if client_config.get("enable.idempotence").is_none() {
+            client_config.set("enable.idempotence", "true");
+        }
+
+        let producer: FutureProducer = client_config.create()?;
+        producers.insert(
+            subscription.id(),
+            CachedProducer {
+                sink: subscription.sink().clone(),
+                metadata: subscription.metadata().clone(),
+                producer: producer.clone(),
+            },
+        );
+        Ok(producer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn egress_subscription() -> Subscription {
+        let mut subscription = Subscription::mock();
+        subscription.set_sink(Sink::Kafka {
+            cluster: "my-cluster".to_owned(),
+            topic: "my-output".to_owned(),
+        });
+        subscription
+    }
+
+    #[test]
+    fn cached_producer_is_replaced_after_sink_or_metadata_changed() {
+        let subscription = egress_subscription();
+        let cached = CachedProducer {
+            sink: subscription.sink().clone(),
+            metadata: subscription.metadata().clone(),
+            producer: ClientConfig::new()
+                .set("metadata.broker.list", "localhost:9092")
+                .create()
+                .unwrap(),
+        };
+        assert!(cached.is_current(&subscription));
+
+        let mut paused = subscription.clone();
+        paused.set_paused(true);
+        assert!(cached.is_current(&paused));
+
+        let mut other_cluster = subscription.clone();
+        other_cluster.set_sink(Sink::Kafka {
+            cluster: "other-cluster".to_owned(),
+            topic: "my-output".to_owned(),
+        });
+        assert!(!cached.is_current(&other_cluster));
+
+        let mut other_options = subscription;
+        other_options
+            .metadata_mut()
+            .insert("linger.ms".to_owned(), "5".to_owned());
+        assert!(!cached.is_current(&other_options));
+    }
+
+    #[tokio::test]
+    async fn records_of_unknown_clusters_are_retried() {
+        let result = KafkaEgress::default()
+            .publish(
+                &IngressOptions::default(),
+                &egress_subscription(),
+                b"key",
+                b"payload",
+                &[],
+            )
+            .await;
+
+        let err = result.unwrap_err();
+        assert!(matches!(err, EgressError::UnknownCluster(ref cluster) if cluster == "my-cluster"));
+        assert!(err.is_retryable());
+        assert!(!EgressError::NotEgress(SubscriptionId::new()).is_retryable());
+    }
+}
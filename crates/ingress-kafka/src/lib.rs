@@ -10,11 +10,18 @@
 
 mod consumer_task;
 mod dispatcher;
+mod egress;
 mod metric_definitions;
+mod offsets;
 mod subscription_controller;
+mod value_decoder;
 
 use tokio::sync::mpsc;
 
+pub use egress::{EgressError, KafkaEgress};
+pub use offsets::{
+    fetch_subscription_offsets, reset_subscription_offsets, OffsetReset, PartitionOffsets,
+};
 pub use subscription_controller::{Command, Error, Service};
 
 pub type SubscriptionCommandSender = mpsc::Sender<Command>;
@@ -8,9 +8,15 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use metrics::{describe_counter, Unit};
+use metrics::{describe_counter, describe_gauge, Unit};
 
 pub const KAFKA_INGRESS_REQUESTS: &str = "restate.kafka_ingress.requests.total";
+pub const KAFKA_INGRESS_POISON_RECORDS: &str = "restate.kafka_ingress.poison_records.total";
+pub const KAFKA_INGRESS_COMMITTED_OFFSET: &str = "restate.kafka_ingress.committed_offset";
+pub const KAFKA_INGRESS_HIGH_WATERMARK: &str = "restate.kafka_ingress.high_watermark";
+pub const KAFKA_INGRESS_CONSUMER_LAG: &str = "restate.kafka_ingress.consumer_lag";
+pub const KAFKA_EGRESS_RECORDS: &str = "restate.kafka_egress.records.total";
+pub const KAFKA_EGRESS_DROPPED_RECORDS: &str = "restate.kafka_egress.dropped_records.total";
 
 pub(crate) fn describe_metrics() {
     describe_counter!(
@@ -18,4 +24,34 @@ pub(crate) fn describe_metrics() {
         Unit::Count,
         "Number of Kafka ingress requests"
     );
+    describe_counter!(
+        KAFKA_INGRESS_POISON_RECORDS,
+        Unit::Count,
+        "Number of Kafka records which could not be turned into invocations, by error policy"
+    );
+    describe_gauge!(
+        KAFKA_INGRESS_COMMITTED_OFFSET,
+        Unit::Count,
+        "Offset committed by the consumer group of a subscription, by topic partition"
+    );
+    describe_gauge!(
+        KAFKA_INGRESS_HIGH_WATERMARK,
+        Unit::Count,
+        "Offset of the next record appended to a topic partition consumed by a subscription"
+    );
+    describe_gauge!(
+        KAFKA_INGRESS_CONSUMER_LAG,
+        Unit::Count,
+        "Number of records of a topic partition which a subscription did not commit yet"
+    );
+    describe_counter!(
+        KAFKA_EGRESS_RECORDS,
+        Unit::Count,
+        "Number of records published to Kafka by egress subscriptions"
+    );
+    describe_counter!(
+        KAFKA_EGRESS_DROPPED_RECORDS,
+        Unit::Count,
+        "Number of egress records dropped because they cannot be published to Kafka"
+    );
 }
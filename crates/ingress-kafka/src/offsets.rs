@@ -0,0 +1,223 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::bail;
+use rdkafka::consumer::{BaseConsumer, CommitMode, Consumer};
+use rdkafka::{Offset, TopicPartitionList};
+
+use restate_types::config::IngressOptions;
+use restate_types::schema::subscriptions::{Source, Subscription};
+
+use crate::subscription_controller::client_config;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Progress of the consumer group of a subscription on a partition of its topic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionOffsets {
+    pub topic: String,
+    pub partition: i32,
+    /// Offset of the next record the consumer group will process, if it committed any
+    pub committed_offset: Option<i64>,
+    /// Offset of the next record appended to the partition
+    pub high_watermark: i64,
+    /// Number of records which were not committed yet, if the consumer group committed any
+    pub lag: Option<i64>,
+}
+
+/// Where to move the consumer group of a subscription to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OffsetReset {
+    /// Oldest record retained by each partition
+    Earliest,
+    /// Next record appended to each partition
+    Latest,
+    /// First record of each partition whose timestamp, in milliseconds since the Unix epoch, is
+    /// greater than or equal to the given one
+    Timestamp(i64),
+    /// Explicit offset by partition. Partitions which are not listed are left untouched.
+    Offsets(HashMap<i32, i64>),
+}
+
+impl OffsetReset {
+    /// Whether this reset moves the offsets of the given partition.
+    pub fn resets_partition(&self, partition: i32) -> bool {
+        match self {
+            OffsetReset::Offsets(offsets) => offsets.contains_key(&partition),
+            OffsetReset::Earliest | OffsetReset::Latest | OffsetReset::Timestamp(_) => true,
+        }
+    }
+}
+
+/// Commits new offsets for the consumer group of the subscription.
+///
+/// Kafka accepts the commit only if no consumer of the group is running, hence the subscription
+/// must be paused. This blocks until the brokers responded, call it from a blocking task.
+pub fn reset_subscription_offsets(
+    options: &IngressOptions,
+    subscription: &Subscription,
+    reset: OffsetReset,
+) -> anyhow::Result<Vec<PartitionOffsets>> {
+    let Source::Kafka { cluster, topic } = subscription.source() else {
+        bail!(
+            "subscription '{}' does not consume a Kafka topic",
+            subscription.id()
+        );
+    };
+
+    let consumer: BaseConsumer = client_config(options, cluster, subscription)?.create()?;
+    let partitions = topic_partitions(&consumer, topic)?;
+
+    let mut new_offsets = TopicPartitionList::new();
+    match reset {
+        OffsetReset::Earliest | OffsetReset::Latest => {
+            for partition in partitions {
+                let (low_watermark, high_watermark) =
+                    consumer.fetch_watermarks(topic, partition, FETCH_TIMEOUT)?;
+                let offset = if reset == OffsetReset::Earliest {
+                    low_watermark
+                } else {
+                    high_watermark
+                };
+                new_offsets.add_partition_offset(topic, partition, Offset::Offset(offset))?;
+            }
+        }
+        OffsetReset::Timestamp(timestamp) => {
+            let mut timestamps = TopicPartitionList::new();
+            for partition in partitions {
+                timestamps.add_partition_offset(topic, partition, Offset::Offset(timestamp))?;
+            }
+            for element in consumer
+                .offsets_for_times(timestamps, FETCH_TIMEOUT)?
+                .elements()
+            {
+                let offset = match element.offset() {
+                    Offset::Offset(offset) => offset,
+                    // No record is newer than the timestamp
+                    _ => {
+                        consumer
+                            .fetch_watermarks(topic, element.partition(), FETCH_TIMEOUT)?
+                            .1
+                    }
+                };
+                new_offsets.add_partition_offset(
+                    topic,
+                    element.partition(),
+                    Offset::Offset(offset),
+                )?;
+            }
+        }
+        OffsetReset::Offsets(offsets) => {
+            for (partition, offset) in offsets {
+                if !partitions.contains(&partition) {
+                    bail!("topic '{topic}' has no partition {partition}");
+                }
+                new_offsets.add_partition_offset(topic, partition, Offset::Offset(offset))?;
+            }
+        }
+    }
+
+    consumer.commit(&new_offsets, CommitMode::Sync)?;
+
+    fetch_subscription_offsets(options, subscription)
+}
+
+/// Fetches the offsets committed by the consumer group of the subscription, together with the
+/// high watermarks of all the partitions of its topic.
+///
+/// This blocks until the brokers responded, call it from a blocking task.
+pub fn fetch_subscription_offsets(
+    options: &IngressOptions,
+    subscription: &Subscription,
+) -> anyhow::Result<Vec<PartitionOffsets>> {
+    let Source::Kafka { cluster, topic } = subscription.source() else {
+        bail!(
+            "subscription '{}' does not consume a Kafka topic",
+            subscription.id()
+        );
+    };
+
+    // The consumer joins neither the consumer group nor the topic, it only reads their offsets
+    let consumer: BaseConsumer = client_config(options, cluster, subscription)?.create()?;
+
+    let mut partitions = TopicPartitionList::new();
+    for partition in topic_partitions(&consumer, topic)? {
+        partitions.add_partition(topic, partition);
+    }
+
+    let committed_offsets = consumer.committed_offsets(partitions, FETCH_TIMEOUT)?;
+
+    let mut partition_offsets = Vec::with_capacity(committed_offsets.count());
+    for element in committed_offsets.elements() {
+        let (_, high_watermark) =
+            consumer.fetch_watermarks(topic, element.partition(), FETCH_TIMEOUT)?;
+        let committed_offset = match element.offset() {
+            Offset::Offset(offset) => Some(offset),
+            _ => None,
+        };
+
+        partition_offsets.push(PartitionOffsets {
+            topic: topic.clone(),
+            partition: element.partition(),
+            committed_offset,
+            high_watermark,
+            lag: committed_offset.map(|offset| consumer_lag(offset, high_watermark)),
+        });
+    }
+    partition_offsets.sort_by_key(|offsets| offsets.partition);
+
+    Ok(partition_offsets)
+}
+
+/// Number of records of a partition which were not committed yet. The committed offset can
+/// exceed the high watermark while it is being refreshed, the lag is never negative.
+pub(crate) fn consumer_lag(committed_offset: i64, high_watermark: i64) -> i64 {
+    (high_watermark - committed_offset).max(0)
+}
+
+fn topic_partitions(consumer: &BaseConsumer, topic: &str) -> anyhow::Result<Vec<i32>> {
+    let metadata = consumer.fetch_metadata(Some(topic), FETCH_TIMEOUT)?;
+    let mut partitions = Vec::new();
+    for topic_metadata in metadata.topics() {
+        if let Some(err) = topic_metadata.error() {
+            bail!("cannot fetch the metadata of topic '{topic}': {err:?}");
+        }
+        partitions.extend(topic_metadata.partitions().iter().map(|p| p.id()));
+    }
+    Ok(partitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_offsets_only_reset_the_listed_partitions() {
+        let reset = OffsetReset::Offsets(HashMap::from([(1, 42)]));
+        assert!(reset.resets_partition(1));
+        assert!(!reset.resets_partition(0));
+
+        assert!(OffsetReset::Earliest.resets_partition(0));
+        assert!(OffsetReset::Latest.resets_partition(0));
+        assert!(OffsetReset::Timestamp(0).resets_partition(0));
+    }
+
+    #[test]
+    fn lag_of_committed_offsets() {
+        assert_eq!(consumer_lag(42, 100), 58);
+        // the consumer group caught up with the partition
+        assert_eq!(consumer_lag(100, 100), 0);
+        // stale high watermarks don't make the lag negative
+        assert_eq!(consumer_lag(101, 100), 0);
+    }
+}
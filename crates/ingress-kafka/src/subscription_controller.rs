@@ -8,12 +8,13 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use super::consumer_task::MessageSender;
+use super::consumer_task::{MessageSender, PoisonRecordHandler};
 use super::*;
 use std::collections::HashSet;
 
 use crate::dispatcher::KafkaIngressDispatcher;
 use crate::subscription_controller::task_orchestrator::TaskOrchestrator;
+use crate::value_decoder::ValueDecoder;
 use anyhow::Context;
 use rdkafka::error::KafkaError;
 use restate_bifrost::Bifrost;
@@ -40,6 +41,30 @@ pub enum Error {
     Kafka(#[from] KafkaError),
 }
 
+const STATISTICS_INTERVAL_MS: &str = "15000";
+
+/// Copies the cluster options and the subscription options into a new client config.
+pub(crate) fn client_config(
+    options: &IngressOptions,
+    cluster: &str,
+    subscription: &Subscription,
+) -> anyhow::Result<rdkafka::ClientConfig> {
+    let mut client_config = rdkafka::ClientConfig::new();
+
+    let cluster_options = options
+        .get_kafka_cluster(cluster)
+        .with_context(|| format!("KafkaOptions is expected to contain the cluster '{}'. This might happen if you registered a subscription with a cluster name, but this cluster is not available anymore in the configuration. Configured Kafka clusters: {:?}", cluster, options.available_kafka_clusters()))?;
+
+    client_config.set("metadata.broker.list", cluster_options.brokers.join(","));
+    for (k, v) in cluster_options.additional_options.clone() {
+        client_config.set(k, v);
+    }
+    for (k, v) in subscription.kafka_options() {
+        client_config.set(k, v);
+    }
+    Ok(client_config)
+}
+
 // For simplicity of the current implementation, this currently lives in this module
 // In future versions, we should either pull this out in a separate process, or generify it and move it to the worker, or an ad-hoc module
 pub struct Service {
@@ -116,27 +141,29 @@ impl Service {
         subscription: Subscription,
         task_orchestrator: &mut TaskOrchestrator,
     ) -> anyhow::Result<()> {
-        let mut client_config = rdkafka::ClientConfig::new();
+        let Source::Kafka { cluster, topic, .. } = subscription.source() else {
+            // Egress subscriptions are published by the partition processors
+            return Ok(());
+        };
+        if subscription.is_paused() {
+            return Ok(());
+        }
 
-        let Source::Kafka { cluster, topic, .. } = subscription.source();
+        let mut client_config = client_config(options, cluster, &subscription)?;
 
-        // Copy cluster options and subscription metadata into client_config
-        let cluster_options = options
-            .get_kafka_cluster(cluster)
-            .with_context(|| format!("KafkaOptions is expected to contain the cluster '{}'. This might happen if you registered a subscription with a cluster name, but this cluster is not available anymore in the configuration. Configured Kafka clusters: {:?}", cluster, options.available_kafka_clusters()))?;
-
-        client_config.set("metadata.broker.list", cluster_options.brokers.join(","));
-        for (k, v) in cluster_options.additional_options.clone() {
-            client_config.set(k, v);
-        }
-        for (k, v) in subscription.metadata() {
-            client_config.set(k, v);
-        }
+        // The dead-letter producer shares the connection options of the consumer
+        let poison_record_handler =
+            PoisonRecordHandler::new(subscription.error_policy()?, &client_config)?;
+        let value_decoder = ValueDecoder::new(subscription.value_format()?)?;
 
         // Options required by the business logic of our consumer,
         // see ConsumerTask::run
         client_config.set("enable.auto.commit", "true");
         client_config.set("enable.auto.offset.store", "false");
+        // Statistics feed the offset metrics, see OffsetsReportingContext
+        if client_config.get("statistics.interval.ms").is_none() {
+            client_config.set("statistics.interval.ms", STATISTICS_INTERVAL_MS);
+        }
 
         let subscription_id = subscription.id();
 
@@ -147,6 +174,8 @@ impl Service {
             MessageSender::new(
                 subscription,
                 self.dispatcher.clone(),
+                poison_record_handler,
+                value_decoder,
                 options.experimental_feature_kafka_ingress_next(),
             ),
         );
@@ -174,10 +203,17 @@ impl Service {
             task_orchestrator.running_subscriptions().cloned().collect();
 
         for subscription in subscriptions {
-            if !running_subscriptions.contains(&subscription.id()) {
+            if subscription.is_paused() {
+                // Paused subscriptions which are running are stopped below
+                continue;
+            }
+
+            if !running_subscriptions.remove(&subscription.id()) {
+                self.handle_start_subscription(options, subscription, task_orchestrator)?;
+            } else if task_orchestrator.subscription(subscription.id()) != Some(&subscription) {
+                // The subscription was updated, restart its consumer. The consumer group stays
+                // the same, hence the new consumer resumes from the committed offsets.
                 self.handle_start_subscription(options, subscription, task_orchestrator)?;
-            } else {
-                running_subscriptions.remove(&subscription.id());
             }
         }
 
@@ -194,6 +230,7 @@ mod task_orchestrator {
     use restate_timer_queue::TimerQueue;
     use restate_types::identifiers::SubscriptionId;
     use restate_types::retries::{RetryIter, RetryPolicy};
+    use restate_types::schema::subscriptions::Subscription;
     use std::collections::HashMap;
     use std::time::SystemTime;
     use tokio::sync::oneshot;
@@ -385,5 +422,14 @@ mod task_orchestrator {
         pub(super) fn running_subscriptions(&self) -> impl Iterator<Item = &SubscriptionId> {
             self.subscription_id_to_task_state.keys()
         }
+
+        pub(super) fn subscription(
+            &self,
+            subscription_id: SubscriptionId,
+        ) -> Option<&Subscription> {
+            self.subscription_id_to_task_state
+                .get(&subscription_id)
+                .map(|task_state| task_state.consumer_task_clone.subscription())
+        }
     }
 }
@@ -0,0 +1,570 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Decoding of record values in the Confluent wire format: a zero magic byte, the id of the
+//! value schema in the schema registry as a big endian `u32`, and the encoded value. Protobuf
+//! values additionally start with the indexes of their message type in the schema.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use apache_avro::Schema as AvroSchema;
+use bytes::{Buf, Bytes};
+use prost_reflect::{DynamicMessage, FileDescriptor, MessageDescriptor};
+use protox::file::{ChainFileResolver, File, FileResolver, GoogleFileResolver};
+use serde::Deserialize;
+
+use restate_types::schema::subscriptions::{SchemaRegistryOptions, ValueFormat};
+
+const MAGIC_BYTE: u8 = 0;
+
+const REGISTRY_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REGISTRY_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Schema as returned by the schema registry.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RegisteredSchema {
+    pub(crate) schema: String,
+    #[serde(default)]
+    pub(crate) schema_type: SchemaType,
+    #[serde(default)]
+    pub(crate) references: Vec<SchemaReference>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub(crate) enum SchemaType {
+    // The registry omits the type of Avro schemas
+    #[default]
+    Avro,
+    Protobuf,
+    Json,
+}
+
+/// Reference of a schema to another one, e.g. an import of a Protobuf schema.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct SchemaReference {
+    pub(crate) name: String,
+    pub(crate) subject: String,
+    pub(crate) version: i32,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    /// The schema registry could not be reached or answered with an error. Decoding the value
+    /// again later can succeed, hence the record must not be treated as a poison record.
+    #[error("schema registry is unavailable: {0:#}")]
+    Registry(anyhow::Error),
+    /// The value cannot be decoded
+    #[error(transparent)]
+    Value(#[from] anyhow::Error),
+}
+
+pub(crate) trait SchemaRegistry: Send + Sync {
+    fn schema_by_id(&self, id: u32) -> BoxFuture<'_, Result<RegisteredSchema, DecodeError>>;
+
+    fn schema_by_subject<'a>(
+        &'a self,
+        subject: &'a str,
+        version: i32,
+    ) -> BoxFuture<'a, Result<RegisteredSchema, DecodeError>>;
+}
+
+/// Client of the REST API of a Confluent compatible schema registry.
+struct HttpSchemaRegistry {
+    client: reqwest::Client,
+    options: SchemaRegistryOptions,
+}
+
+impl HttpSchemaRegistry {
+    /// Fetches a schema. A schema which doesn't exist is a problem of the value referencing it,
+    /// every other failure is a problem of the registry.
+    async fn get(&self, path: String) -> Result<RegisteredSchema, DecodeError> {
+        let url = format!("{}{path}", self.options.url.trim_end_matches('/'));
+        let mut request = self.client.get(&url);
+        if let Some((user, password)) = self
+            .options
+            .basic_auth
+            .as_ref()
+            .and_then(|basic_auth| basic_auth.split_once(':'))
+        {
+            request = request.basic_auth(user, Some(password));
+        }
+
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("cannot fetch schema '{url}'"))
+            .map_err(DecodeError::Registry)?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(DecodeError::Value(anyhow!("schema '{url}' does not exist")));
+        }
+
+        response
+            .error_for_status()
+            .with_context(|| format!("cannot fetch schema '{url}'"))
+            .map_err(DecodeError::Registry)?
+            .json()
+            .await
+            .with_context(|| format!("invalid schema '{url}'"))
+            .map_err(DecodeError::Registry)
+    }
+}
+
+impl SchemaRegistry for HttpSchemaRegistry {
+    fn schema_by_id(&self, id: u32) -> BoxFuture<'_, Result<RegisteredSchema, DecodeError>> {
+        Box::pin(self.get(format!("/schemas/ids/{id}")))
+    }
+
+    fn schema_by_subject<'a>(
+        &'a self,
+        subject: &'a str,
+        version: i32,
+    ) -> BoxFuture<'a, Result<RegisteredSchema, DecodeError>> {
+        Box::pin(self.get(format!("/subjects/{subject}/versions/{version}")))
+    }
+}
+
+enum ResolvedSchema {
+    Avro(AvroSchema),
+    Protobuf(FileDescriptor),
+}
+
+struct Decoder {
+    registry: Box<dyn SchemaRegistry>,
+    expected_schema_type: SchemaType,
+    // Schemas are immutable once registered, hence they can be cached forever
+    schemas: Mutex<HashMap<u32, Arc<ResolvedSchema>>>,
+}
+
+/// Converts the values of the records of a subscription according to its [`ValueFormat`].
+#[derive(Clone)]
+pub struct ValueDecoder(Option<Arc<Decoder>>);
+
+impl ValueDecoder {
+    pub fn new(value_format: ValueFormat) -> Result<Self, reqwest::Error> {
+        let (options, expected_schema_type) = match value_format {
+            ValueFormat::Raw => return Ok(ValueDecoder(None)),
+            ValueFormat::Avro(options) => (options, SchemaType::Avro),
+            ValueFormat::Protobuf(options) => (options, SchemaType::Protobuf),
+        };
+
+        // Consumers wait for the registry while decoding, it must not hold them forever
+        let client = reqwest::Client::builder()
+            .connect_timeout(REGISTRY_CONNECT_TIMEOUT)
+            .timeout(REGISTRY_REQUEST_TIMEOUT)
+            .build()?;
+
+        Ok(Self::with_registry(
+            HttpSchemaRegistry { client, options },
+            expected_schema_type,
+        ))
+    }
+
+    pub(crate) fn with_registry(
+        registry: impl SchemaRegistry + 'static,
+        expected_schema_type: SchemaType,
+    ) -> Self {
+        ValueDecoder(Some(Arc::new(Decoder {
+            registry: Box::new(registry),
+            expected_schema_type,
+            schemas: Mutex::default(),
+        })))
+    }
+
+    /// Whether values are converted to JSON.
+    pub fn is_json(&self) -> bool {
+        self.0.is_some()
+    }
+
+    pub async fn decode(&self, value: Bytes) -> Result<Bytes, DecodeError> {
+        let Some(decoder) = &self.0 else {
+            return Ok(value);
+        };
+
+        let mut value = value;
+        if value.len() < 5 || value.get_u8() != MAGIC_BYTE {
+            return Err(anyhow!("the value is not in the Confluent wire format").into());
+        }
+        let schema_id = value.get_u32();
+
+        let json = match &*decoder.schema(schema_id).await? {
+            ResolvedSchema::Avro(schema) => decode_avro(schema, &value)?,
+            ResolvedSchema::Protobuf(file) => decode_protobuf(file, value)?,
+        };
+        Ok(Bytes::from(json))
+    }
+}
+
+impl Decoder {
+    async fn schema(&self, id: u32) -> Result<Arc<ResolvedSchema>, DecodeError> {
+        if let Some(schema) = self.schemas.lock().unwrap().get(&id) {
+            return Ok(Arc::clone(schema));
+        }
+
+        let registered_schema = self.registry.schema_by_id(id).await?;
+        if registered_schema.schema_type != self.expected_schema_type {
+            return Err(anyhow!(
+                "schema {id} is of type {:?}, but the subscription expects {:?} values",
+                registered_schema.schema_type,
+                self.expected_schema_type
+            )
+            .into());
+        }
+
+        let mut referenced_schemas = Vec::new();
+        self.fetch_references(&registered_schema.references, &mut referenced_schemas)
+            .await?;
+
+        let schema = Arc::new(match registered_schema.schema_type {
+            SchemaType::Avro => {
+                // Referenced schemas define the named types used by the schema, so they are
+                // parsed first
+                let mut schemas: Vec<&str> = referenced_schemas
+                    .iter()
+                    .map(|(_, schema)| schema.as_str())
+                    .collect();
+                schemas.push(&registered_schema.schema);
+                let schema = AvroSchema::parse_list(&schemas)
+                    .with_context(|| format!("invalid Avro schema {id}"))?
+                    .pop()
+                    .expect("the schema was parsed");
+                ResolvedSchema::Avro(schema)
+            }
+            SchemaType::Protobuf => ResolvedSchema::Protobuf(
+                compile_protobuf(&registered_schema.schema, referenced_schemas)
+                    .with_context(|| format!("invalid Protobuf schema {id}"))?,
+            ),
+            SchemaType::Json => unreachable!("checked against the expected schema type"),
+        });
+
+        self.schemas.lock().unwrap().insert(id, Arc::clone(&schema));
+        Ok(schema)
+    }
+
+    /// Fetches the references transitively, dependencies before their dependents.
+    fn fetch_references<'a>(
+        &'a self,
+        references: &'a [SchemaReference],
+        referenced_schemas: &'a mut Vec<(String, String)>,
+    ) -> BoxFuture<'a, Result<(), DecodeError>> {
+        Box::pin(async move {
+            for reference in references {
+                if referenced_schemas
+                    .iter()
+                    .any(|(name, _)| name == &reference.name)
+                {
+                    continue;
+                }
+
+                let referenced_schema = self
+                    .registry
+                    .schema_by_subject(&reference.subject, reference.version)
+                    .await?;
+                self.fetch_references(&referenced_schema.references, referenced_schemas)
+                    .await?;
+                referenced_schemas.push((reference.name.clone(), referenced_schema.schema));
+            }
+            Ok(())
+        })
+    }
+}
+
+fn decode_avro(schema: &AvroSchema, mut datum: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let value = apache_avro::from_avro_datum(schema, &mut datum, None)
+        .context("cannot decode the Avro value")?;
+    let json =
+        serde_json::Value::try_from(value).context("cannot convert the Avro value to JSON")?;
+    Ok(serde_json::to_vec(&json)?)
+}
+
+/// Name of the root file of the Protobuf schemas compiled by [`compile_protobuf`].
+const ROOT_PROTO_FILE: &str = "restate-subscription-value.proto";
+
+struct InMemoryFileResolver(HashMap<String, String>);
+
+impl FileResolver for InMemoryFileResolver {
+    fn open_file(&self, name: &str) -> Result<File, protox::Error> {
+        match self.0.get(name) {
+            Some(source) => File::from_source(name, source),
+            None => Err(protox::Error::file_not_found(name)),
+        }
+    }
+}
+
+fn compile_protobuf(
+    schema: &str,
+    referenced_schemas: Vec<(String, String)>,
+) -> anyhow::Result<FileDescriptor> {
+    let mut files: HashMap<String, String> = referenced_schemas.into_iter().collect();
+    files.insert(ROOT_PROTO_FILE.to_owned(), schema.to_owned());
+
+    let mut resolver = ChainFileResolver::new();
+    resolver.add(InMemoryFileResolver(files));
+    // Schemas can import the well-known types without referencing them
+    resolver.add(GoogleFileResolver::new());
+
+    let mut compiler = protox::Compiler::with_file_resolver(resolver);
+    compiler.open_file(ROOT_PROTO_FILE)?;
+    compiler
+        .descriptor_pool()
+        .get_file_by_name(ROOT_PROTO_FILE)
+        .ok_or_else(|| anyhow!("the schema was compiled"))
+}
+
+fn decode_protobuf(file: &FileDescriptor, mut value: Bytes) -> anyhow::Result<Vec<u8>> {
+    let message_descriptor = message_descriptor(file, message_indexes(&mut value)?)?;
+    let message = DynamicMessage::decode(message_descriptor, value)
+        .context("cannot decode the Protobuf value")?;
+    Ok(serde_json::to_vec(&message)?)
+}
+
+/// Reads the path of the message type of the value in the schema. The path is encoded as an
+/// array of zig-zag varints, where the empty array is a shorthand for the first message.
+fn message_indexes(value: &mut Bytes) -> anyhow::Result<Vec<usize>> {
+    let mut read_varint = || -> anyhow::Result<usize> {
+        let varint = prost::encoding::decode_varint(value)
+            .context("cannot read the message indexes of the Protobuf value")?;
+        let index = ((varint >> 1) as i64) ^ -((varint & 1) as i64);
+        usize::try_from(index).context("negative message index")
+    };
+
+    let len = read_varint()?;
+    if len == 0 {
+        return Ok(vec![0]);
+    }
+    (0..len).map(|_| read_varint()).collect()
+}
+
+fn message_descriptor(
+    file: &FileDescriptor,
+    indexes: Vec<usize>,
+) -> anyhow::Result<MessageDescriptor> {
+    let mut messages: Vec<MessageDescriptor> = file.messages().collect();
+    let mut message_descriptor = None;
+    for index in indexes {
+        let message = messages
+            .get(index)
+            .cloned()
+            .ok_or_else(|| anyhow!("the schema has no message at index {index}"))?;
+        messages = message.child_messages().collect();
+        message_descriptor = Some(message);
+    }
+    message_descriptor.ok_or_else(|| anyhow!("the Protobuf value has no message indexes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use apache_avro::types::Record;
+    use bytes::BufMut;
+    use prost_reflect::Value;
+    use serde_json::json;
+
+    #[derive(Default)]
+    struct MockSchemaRegistry {
+        by_id: HashMap<u32, RegisteredSchema>,
+        by_subject: HashMap<(String, i32), RegisteredSchema>,
+    }
+
+    impl SchemaRegistry for MockSchemaRegistry {
+        fn schema_by_id(&self, id: u32) -> BoxFuture<'_, Result<RegisteredSchema, DecodeError>> {
+            let schema = self.by_id.get(&id).cloned();
+            Box::pin(async move {
+                schema.ok_or_else(|| DecodeError::Value(anyhow!("schema {id} not found")))
+            })
+        }
+
+        fn schema_by_subject<'a>(
+            &'a self,
+            subject: &'a str,
+            version: i32,
+        ) -> BoxFuture<'a, Result<RegisteredSchema, DecodeError>> {
+            let schema = self.by_subject.get(&(subject.to_owned(), version)).cloned();
+            Box::pin(async move {
+                schema.ok_or_else(|| DecodeError::Value(anyhow!("subject {subject} not found")))
+            })
+        }
+    }
+
+    fn registered_schema(
+        schema: &str,
+        schema_type: SchemaType,
+        references: Vec<SchemaReference>,
+    ) -> RegisteredSchema {
+        RegisteredSchema {
+            schema: schema.to_owned(),
+            schema_type,
+            references,
+        }
+    }
+
+    fn wire_format(schema_id: u32, message_indexes: &[u8], value: &[u8]) -> Bytes {
+        let mut bytes = Vec::new();
+        bytes.put_u8(MAGIC_BYTE);
+        bytes.put_u32(schema_id);
+        bytes.put_slice(message_indexes);
+        bytes.put_slice(value);
+        bytes.into()
+    }
+
+    #[tokio::test]
+    async fn decode_avro_value() {
+        let greeting_schema = r#"{
+            "type": "record",
+            "name": "Greeting",
+            "fields": [
+                {"name": "name", "type": "string"},
+                {"name": "count", "type": "int"}
+            ]
+        }"#;
+        let mut registry = MockSchemaRegistry::default();
+        registry.by_id.insert(
+            1,
+            registered_schema(greeting_schema, SchemaType::Avro, vec![]),
+        );
+        let decoder = ValueDecoder::with_registry(registry, SchemaType::Avro);
+
+        let schema = AvroSchema::parse_str(greeting_schema).unwrap();
+        let mut record = Record::new(&schema).unwrap();
+        record.put("name", "Till");
+        record.put("count", 3);
+        let datum = apache_avro::to_avro_datum(&schema, record).unwrap();
+
+        let json = decoder.decode(wire_format(1, &[], &datum)).await.unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&json).unwrap(),
+            json!({"name": "Till", "count": 3})
+        );
+
+        // Unknown schemas and values in another format are rejected
+        assert!(matches!(
+            decoder.decode(wire_format(2, &[], &datum)).await,
+            Err(DecodeError::Value(_))
+        ));
+        assert!(matches!(
+            decoder.decode(Bytes::from_static(b"{}")).await,
+            Err(DecodeError::Value(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn decode_protobuf_value_with_references() {
+        let mut registry = MockSchemaRegistry::default();
+        registry.by_subject.insert(
+            ("name-value".to_owned(), 1),
+            registered_schema(
+                r#"syntax = "proto3";
+                package greeter;
+                message Name { string first = 1; }"#,
+                SchemaType::Protobuf,
+                vec![],
+            ),
+        );
+        registry.by_id.insert(
+            1,
+            registered_schema(
+                r#"syntax = "proto3";
+                package greeter;
+                import "name.proto";
+                message Other { bool flag = 1; }
+                message Greeting {
+                  Name name = 1;
+                  message Nested { int32 count = 1; }
+                }"#,
+                SchemaType::Protobuf,
+                vec![SchemaReference {
+                    name: "name.proto".to_owned(),
+                    subject: "name-value".to_owned(),
+                    version: 1,
+                }],
+            ),
+        );
+        let decoder = ValueDecoder::with_registry(registry, SchemaType::Protobuf);
+
+        // Greeting is the second message of the schema, Nested the first message of Greeting
+        let file = match &*decoder.0.as_ref().unwrap().schema(1).await.unwrap() {
+            ResolvedSchema::Protobuf(file) => file.clone(),
+            ResolvedSchema::Avro(_) => panic!("expected a Protobuf schema"),
+        };
+        let nested = file
+            .parent_pool()
+            .get_message_by_name("greeter.Greeting.Nested")
+            .unwrap();
+        let mut message = DynamicMessage::new(nested);
+        message.set_field_by_name("count", Value::I32(3));
+        let value = prost::Message::encode_to_vec(&message);
+
+        // [1, 0] as zig-zag varints, preceded by the length of the array
+        let json = decoder
+            .decode(wire_format(1, &[4, 2, 0], &value))
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&json).unwrap(),
+            json!({"count": 3})
+        );
+    }
+
+    #[tokio::test]
+    async fn unavailable_registry_is_not_a_value_error() {
+        struct UnavailableSchemaRegistry;
+
+        impl SchemaRegistry for UnavailableSchemaRegistry {
+            fn schema_by_id(
+                &self,
+                _id: u32,
+            ) -> BoxFuture<'_, Result<RegisteredSchema, DecodeError>> {
+                Box::pin(async { Err(DecodeError::Registry(anyhow!("connection refused"))) })
+            }
+
+            fn schema_by_subject<'a>(
+                &'a self,
+                _subject: &'a str,
+                _version: i32,
+            ) -> BoxFuture<'a, Result<RegisteredSchema, DecodeError>> {
+                Box::pin(async { Err(DecodeError::Registry(anyhow!("connection refused"))) })
+            }
+        }
+
+        let decoder = ValueDecoder::with_registry(UnavailableSchemaRegistry, SchemaType::Avro);
+        assert!(matches!(
+            decoder.decode(wire_format(1, &[], b"datum")).await,
+            Err(DecodeError::Registry(_))
+        ));
+        // the failure is not cached
+        assert!(decoder
+            .0
+            .as_ref()
+            .unwrap()
+            .schemas
+            .lock()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn raw_values_are_passed_as_is() {
+        let decoder = ValueDecoder::new(ValueFormat::Raw).unwrap();
+        assert!(!decoder.is_json());
+        assert_eq!(
+            decoder.decode(Bytes::from_static(b"raw")).await.unwrap(),
+            Bytes::from_static(b"raw")
+        );
+    }
+}
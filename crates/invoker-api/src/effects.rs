@@ -12,6 +12,7 @@ use restate_types::deployment::PinnedDeployment;
 use restate_types::errors::InvocationError;
 use restate_types::identifiers::EntryIndex;
 use restate_types::identifiers::InvocationId;
+use restate_types::identifiers::SubscriptionId;
 use restate_types::journal::enriched::EnrichedRawEntry;
 use std::collections::HashSet;
 
@@ -36,6 +37,15 @@ pub enum EffectKind {
     },
     /// This is sent always after [`Self::JournalEntry`] with `OutputStreamEntry`(s).
     End,
+    /// This is sent instead of [`Self::End`] when egress subscriptions publish the output of the
+    /// invoked handler. The subscriptions are resolved when the invocation ends, so that the
+    /// partition processors don't depend on the schema.
+    EndWithEgress {
+        egress_subscriptions: Vec<SubscriptionId>,
+    },
     /// This is sent when the invoker exhausted all its attempts to make progress on the specific invocation.
     Failed(InvocationError),
+    /// This is sent instead of [`Self::Failed`] when the retry policy of the invocation asks to pause
+    /// it once the attempts are exhausted. The error is the last one the invocation failed with.
+    Paused(InvocationError),
 }
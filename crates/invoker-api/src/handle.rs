@@ -64,6 +64,13 @@ pub trait InvokerHandle<SR> {
         acknowledge: bool,
     ) -> impl Future<Output = Result<(), NotRunningError>> + Send;
 
+    /// Retry the invocation right away if it's currently waiting for its retry backoff to expire.
+    fn retry_invocation_now(
+        &mut self,
+        partition_leader_epoch: PartitionLeaderEpoch,
+        invocation_id: InvocationId,
+    ) -> impl Future<Output = Result<(), NotRunningError>> + Send;
+
     fn register_partition(
         &mut self,
         partition: PartitionLeaderEpoch,
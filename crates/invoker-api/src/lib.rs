@@ -133,6 +133,14 @@ pub mod test_util {
             Ok(())
         }
 
+        async fn retry_invocation_now(
+            &mut self,
+            _partition_leader_epoch: PartitionLeaderEpoch,
+            _invocation_id: InvocationId,
+        ) -> Result<(), NotRunningError> {
+            Ok(())
+        }
+
         async fn register_partition(
             &mut self,
             _partition: PartitionLeaderEpoch,
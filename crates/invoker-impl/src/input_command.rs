@@ -48,6 +48,12 @@ pub(crate) enum InputCommand<SR> {
         acknowledge: bool,
     },
 
+    /// Skip the retry backoff of specific invocation id
+    RetryNow {
+        partition: PartitionLeaderEpoch,
+        invocation_id: InvocationId,
+    },
+
     /// Command used to clean up internal state when a partition leader is going away
     AbortAllPartition {
         partition: PartitionLeaderEpoch,
@@ -141,6 +147,19 @@ impl<SR: Send> restate_invoker_api::InvokerHandle<SR> for InvokerHandle<SR> {
             .map_err(|_| NotRunningError)
     }
 
+    async fn retry_invocation_now(
+        &mut self,
+        partition: PartitionLeaderEpoch,
+        invocation_id: InvocationId,
+    ) -> Result<(), NotRunningError> {
+        self.input
+            .send(InputCommand::RetryNow {
+                partition,
+                invocation_id,
+            })
+            .map_err(|_| NotRunningError)
+    }
+
     async fn register_partition(
         &mut self,
         partition: PartitionLeaderEpoch,
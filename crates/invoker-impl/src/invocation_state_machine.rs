@@ -12,6 +12,7 @@ use super::*;
 
 use restate_types::journal::Completion;
 use restate_types::retries;
+use restate_types::schema::service::{InvocationRetryPolicy, OnMaxAttempts};
 use std::fmt;
 use std::time::Duration;
 use tokio::sync::mpsc;
@@ -23,6 +24,7 @@ pub(super) struct InvocationStateMachine {
     pub(super) invocation_target: InvocationTarget,
     invocation_state: InvocationState,
     retry_iter: retries::RetryIter<'static>,
+    pub(super) on_max_attempts: OnMaxAttempts,
     /// This retry count is passed in the StartMessage.
     /// For more details of when we bump it, see [`InvocationTaskError::should_bump_start_message_retry_count_since_last_stored_entry`].
     pub(super) start_message_retry_count_since_last_stored_entry: u32,
@@ -132,12 +134,13 @@ impl fmt::Debug for InvocationState {
 impl InvocationStateMachine {
     pub(super) fn create(
         invocation_target: InvocationTarget,
-        retry_policy: RetryPolicy,
+        retry_policy: InvocationRetryPolicy,
     ) -> InvocationStateMachine {
         Self {
             invocation_target,
             invocation_state: InvocationState::New,
-            retry_iter: retry_policy.into_iter(),
+            retry_iter: retry_policy.retry_policy.into_iter(),
+            on_max_attempts: retry_policy.on_max_attempts,
             start_message_retry_count_since_last_stored_entry: 0,
         }
     }
@@ -315,6 +318,10 @@ impl InvocationStateMachine {
         }
     }
 
+    pub(super) fn is_waiting_retry(&self) -> bool {
+        matches!(self.invocation_state, InvocationState::WaitingRetry { .. })
+    }
+
     pub(super) fn is_ready_to_retry(&self) -> bool {
         match self.invocation_state {
             InvocationState::WaitingRetry {
@@ -349,7 +356,7 @@ mod tests {
     fn handle_error_when_waiting_for_retry() {
         let mut invocation_state_machine = InvocationStateMachine::create(
             InvocationTarget::mock_virtual_object(),
-            RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)),
+            RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)).into(),
         );
 
         assert!(invocation_state_machine
@@ -370,7 +377,7 @@ mod tests {
     async fn handle_error_counts_attempts_on_same_entry() {
         let mut invocation_state_machine = InvocationStateMachine::create(
             InvocationTarget::mock_virtual_object(),
-            RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)),
+            RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)).into(),
         );
 
         // Start invocation
@@ -425,7 +432,7 @@ mod tests {
     async fn handle_requires_ack() {
         let mut invocation_state_machine = InvocationStateMachine::create(
             InvocationTarget::mock_virtual_object(),
-            RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)),
+            RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)).into(),
         );
 
         let abort_handle = tokio::spawn(async {}).abort_handle();
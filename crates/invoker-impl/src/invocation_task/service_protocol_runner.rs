@@ -265,13 +265,22 @@ where
                 http_version,
                 ..
             } => Endpoint::Http(address, Some(http_version)),
+            DeploymentType::Unix {
+                socket_path,
+                http_version,
+                ..
+            } => Endpoint::Unix(socket_path, Some(http_version)),
         };
 
         headers.extend(deployment_metadata.delivery_options.additional_headers);
 
         (
             http_stream_tx,
-            Request::new(Parts::new(Method::POST, address, path, headers), req_body),
+            Request::new(
+                Parts::new(Method::POST, address, path, headers)
+                    .with_tls(deployment_metadata.delivery_options.tls),
+                req_body,
+            ),
         )
     }
 
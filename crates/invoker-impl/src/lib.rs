@@ -62,8 +62,12 @@ pub use input_command::InvokerHandle;
 use restate_service_client::{AssumeRoleCacheMode, ServiceClient};
 use restate_types::deployment::PinnedDeployment;
 use restate_types::errors::KILLED_INVOCATION_ERROR;
+use restate_types::identifiers::SubscriptionId;
 use restate_types::invocation::InvocationTarget;
-use restate_types::schema::service::ServiceMetadataResolver;
+use restate_types::schema::service::{
+    InvocationRetryPolicy, OnMaxAttempts, ServiceMetadataResolver,
+};
+use restate_types::schema::subscriptions::{ListSubscriptionFilter, SubscriptionResolver};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum Notification {
@@ -88,6 +92,23 @@ trait InvocationTaskRunner<SR> {
         input_journal: InvokeInputJournal,
         task_pool: &mut JoinSet<()>,
     ) -> AbortHandle;
+
+    /// Returns the retry policy to use for the given invocation target.
+    fn resolve_retry_policy(
+        &self,
+        options: &InvokerOptions,
+        _invocation_target: &InvocationTarget,
+    ) -> InvocationRetryPolicy {
+        options.retry_policy.clone().into()
+    }
+
+    /// Returns the egress subscriptions which publish the output of the given invocation target.
+    fn resolve_egress_subscriptions(
+        &self,
+        _invocation_target: &InvocationTarget,
+    ) -> Vec<SubscriptionId> {
+        vec![]
+    }
 }
 
 struct DefaultInvocationTaskRunner<EE, Schemas> {
@@ -102,7 +123,13 @@ where
     <SR as JournalReader>::JournalStream: Unpin + Send + 'static,
     <SR as StateReader>::StateIter: Send,
     EE: EntryEnricher + Clone + Send + Sync + 'static,
-    Schemas: DeploymentResolver + ServiceMetadataResolver + Clone + Send + Sync + 'static,
+    Schemas: DeploymentResolver
+        + ServiceMetadataResolver
+        + SubscriptionResolver
+        + Clone
+        + Send
+        + Sync
+        + 'static,
 {
     fn start_invocation_task(
         &self,
@@ -139,6 +166,38 @@ where
             .run(input_journal),
         )
     }
+
+    fn resolve_retry_policy(
+        &self,
+        options: &InvokerOptions,
+        invocation_target: &InvocationTarget,
+    ) -> InvocationRetryPolicy {
+        // Handler and service overrides take precedence over the invoker options
+        self.schemas
+            .pinned()
+            .resolve_latest_invocation_retry_policy(
+                invocation_target.service_name(),
+                invocation_target.handler_name(),
+            )
+            .unwrap_or_else(|| options.retry_policy.clone().into())
+    }
+
+    fn resolve_egress_subscriptions(
+        &self,
+        invocation_target: &InvocationTarget,
+    ) -> Vec<SubscriptionId> {
+        self.schemas
+            .pinned()
+            .list_subscriptions(&[ListSubscriptionFilter::ExactMatchSource(format!(
+                "service://{}/{}",
+                invocation_target.service_name(),
+                invocation_target.handler_name()
+            ))])
+            .into_iter()
+            .filter(|subscription| subscription.is_egress() && !subscription.is_paused())
+            .map(|subscription| subscription.id())
+            .collect()
+    }
 }
 
 // -- Service implementation
@@ -170,7 +229,7 @@ impl<SR, EE, Schemas> Service<SR, EE, Schemas> {
         SR: JournalReader<JournalStream = JS> + StateReader + Clone + Send + Sync + 'static,
         JS: Stream<Item = PlainRawEntry> + Unpin + Send + 'static,
         EE: EntryEnricher,
-        Schemas: DeploymentResolver + ServiceMetadataResolver,
+        Schemas: DeploymentResolver + ServiceMetadataResolver + SubscriptionResolver,
     {
         let (input_tx, input_rx) = mpsc::unbounded_channel();
         let (status_tx, status_rx) = mpsc::unbounded_channel();
@@ -209,7 +268,7 @@ impl<SR, EE, Schemas> Service<SR, EE, Schemas> {
         SR: JournalReader<JournalStream = JS> + StateReader + Clone + Send + Sync + 'static,
         JS: Stream<Item = PlainRawEntry> + Unpin + Send + 'static,
         EE: EntryEnricher,
-        Schemas: DeploymentResolver + ServiceMetadataResolver,
+        Schemas: DeploymentResolver + ServiceMetadataResolver + SubscriptionResolver,
     {
         metric_definitions::describe_metrics();
         let client =
@@ -236,7 +295,13 @@ where
     <SR as JournalReader>::JournalStream: Unpin + Send + 'static,
     <SR as StateReader>::StateIter: Send,
     EE: EntryEnricher + Clone + Send + Sync + 'static,
-    Schemas: DeploymentResolver + ServiceMetadataResolver + Clone + Send + Sync + 'static,
+    Schemas: DeploymentResolver
+        + ServiceMetadataResolver
+        + SubscriptionResolver
+        + Clone
+        + Send
+        + Sync
+        + 'static,
 {
     pub fn handle(&self) -> InvokerHandle<SR> {
         InvokerHandle {
@@ -355,6 +420,9 @@ where
                     InputCommand::Abort { partition, invocation_id,  acknowledge } => {
                         self.handle_abort_invocation(partition, invocation_id, acknowledge).await;
                     }
+                    InputCommand::RetryNow { partition, invocation_id } => {
+                        self.handle_retry_now(options, partition, invocation_id);
+                    }
                     InputCommand::AbortAllPartition { partition } => {
                         self.handle_abort_partition(partition);
                     }
@@ -492,6 +560,9 @@ where
             .invocation_state_machine_manager
             .partition_storage_reader(partition)
             .expect("partition is registered");
+        let retry_policy = self
+            .invocation_task_runner
+            .resolve_retry_policy(options, &invocation_target);
         self.quota.reserve_slot();
         self.start_invocation_task(
             options,
@@ -499,7 +570,7 @@ where
             storage_reader.clone(),
             invocation_id,
             journal,
-            InvocationStateMachine::create(invocation_target, options.retry_policy.clone()),
+            InvocationStateMachine::create(invocation_target, retry_policy),
         )
     }
 
@@ -523,6 +594,51 @@ where
         });
     }
 
+    #[instrument(
+        level = "trace",
+        skip_all,
+        fields(
+            restate.invocation.id = %invocation_id,
+            restate.invoker.partition_leader_epoch = ?partition,
+        )
+    )]
+    fn handle_retry_now(
+        &mut self,
+        options: &InvokerOptions,
+        partition: PartitionLeaderEpoch,
+        invocation_id: InvocationId,
+    ) {
+        match self
+            .invocation_state_machine_manager
+            .resolve_invocation(partition, &invocation_id)
+        {
+            Some((_, ism)) if ism.is_waiting_retry() => {
+                trace!(
+                    restate.invocation.target = %ism.invocation_target,
+                    "Skipping the retry backoff"
+                );
+            }
+            Some((_, ism)) => {
+                trace!(
+                    restate.invocation.target = %ism.invocation_target,
+                    "Ignoring retry now, the invocation is not waiting for a retry. Invocation state: {:?}",
+                    ism.invocation_state_debug()
+                );
+                return;
+            }
+            None => {
+                trace!("No state machine found for retry now");
+                return;
+            }
+        }
+
+        // Drop the pending timer, so it won't fire on the next attempt
+        self.retry_timers.remove(&(partition, invocation_id));
+        self.handle_retry_event(options, partition, invocation_id, |sm| {
+            sm.notify_retry_timer_fired()
+        });
+    }
+
     #[instrument(
         level = "trace",
         skip_all,
@@ -725,10 +841,19 @@ where
                 "Invocation task closed correctly");
             self.quota.unreserve_slot();
             self.status_store.on_end(&partition, &invocation_id);
+            let egress_subscriptions = self
+                .invocation_task_runner
+                .resolve_egress_subscriptions(&ism.invocation_target);
             let _ = sender
                 .send(Effect {
                     invocation_id,
-                    kind: EffectKind::End,
+                    kind: if egress_subscriptions.is_empty() {
+                        EffectKind::End
+                    } else {
+                        EffectKind::EndWithEgress {
+                            egress_subscriptions,
+                        }
+                    },
                 })
                 .await;
         } else {
@@ -923,21 +1048,37 @@ where
                     "transient" => "false"
                 )
                 .increment(1);
-                warn_it!(
-                    error,
-                    restate.invocation.id = %invocation_id,
-                    restate.invocation.target = %ism.invocation_target,
-                    "Error when executing the invocation, not going to retry.");
+                // Only transient errors can end up here because the retries are exhausted,
+                // other errors always fail the invocation.
+                let pause = error.is_transient() && ism.on_max_attempts == OnMaxAttempts::Pause;
+                if pause {
+                    warn_it!(
+                        error,
+                        restate.invocation.id = %invocation_id,
+                        restate.invocation.target = %ism.invocation_target,
+                        "Error when executing the invocation, retries exhausted. Pausing the invocation.");
+                } else {
+                    warn_it!(
+                        error,
+                        restate.invocation.id = %invocation_id,
+                        restate.invocation.target = %ism.invocation_target,
+                        "Error when executing the invocation, not going to retry.");
+                }
                 self.quota.unreserve_slot();
                 self.status_store.on_end(&partition, &invocation_id);
 
+                let invocation_error = error.into_invocation_error();
                 let _ = self
                     .invocation_state_machine_manager
                     .resolve_partition_sender(partition)
                     .expect("Partition should be registered")
                     .send(Effect {
                         invocation_id,
-                        kind: EffectKind::Failed(error.into_invocation_error()),
+                        kind: if pause {
+                            EffectKind::Paused(invocation_error)
+                        } else {
+                            EffectKind::Failed(invocation_error)
+                        },
                     })
                     .await;
             }
@@ -1057,6 +1198,7 @@ mod tests {
     use restate_types::retries::RetryPolicy;
     use restate_types::schema::deployment::Deployment;
     use restate_types::schema::service::ServiceMetadata;
+    use restate_types::schema::subscriptions::Subscription;
 
     use crate::invocation_task::InvocationTaskError;
     use crate::quota::InvokerConcurrencyQuota;
@@ -1202,6 +1344,16 @@ mod tests {
         }
     }
 
+    impl SubscriptionResolver for MockSchemas {
+        fn get_subscription(&self, _: SubscriptionId) -> Option<Subscription> {
+            None
+        }
+
+        fn list_subscriptions(&self, _: &[ListSubscriptionFilter]) -> Vec<Subscription> {
+            vec![]
+        }
+    }
+
     #[test(restate_core::test)]
     async fn input_order_is_maintained() {
         let invoker_options = InvokerOptionsBuilder::default()
@@ -1444,4 +1596,56 @@ mod tests {
         let_assert!(InvokerConcurrencyQuota::Limited { available_slots } = &service_inner.quota);
         assert_eq!(*available_slots, 2);
     }
+    #[test(restate_core::test)]
+    async fn retry_now_skips_backoff() {
+        let invoker_options = InvokerOptionsBuilder::default()
+            .retry_policy(RetryPolicy::fixed_delay(Duration::from_secs(3600), None))
+            .inactivity_timeout(Duration::ZERO.into())
+            .abort_timeout(Duration::ZERO.into())
+            .disable_eager_state(false)
+            .message_size_warning(NonZeroUsize::new(1024).unwrap())
+            .message_size_limit(None)
+            .build()
+            .unwrap();
+        let invocation_id = InvocationId::mock_random();
+
+        let (_, _status_tx, mut service_inner) =
+            ServiceInner::mock(|_, _, _, _, _, _, _| pending(), None);
+        let _ = service_inner.register_mock_partition(EmptyStorageReader);
+
+        service_inner.handle_invoke(
+            &invoker_options,
+            MOCK_PARTITION,
+            invocation_id,
+            InvocationTarget::mock_virtual_object(),
+            InvokeInputJournal::NoCachedJournal,
+        );
+        service_inner
+            .handle_invocation_task_failed(
+                MOCK_PARTITION,
+                invocation_id,
+                InvocationTaskError::EmptySuspensionMessage, /* any error is fine */
+            )
+            .await;
+
+        // The invocation is now waiting for the retry timer to fire
+        assert!(!service_inner.retry_timers.is_empty());
+        let report = service_inner
+            .status_store
+            .resolve_invocation(MOCK_PARTITION, &invocation_id)
+            .unwrap();
+        assert!(!report.in_flight());
+        assert_eq!(report.retry_count(), 1);
+
+        service_inner.handle_retry_now(&invoker_options, MOCK_PARTITION, invocation_id);
+
+        // Retried right away, and the old timer is gone
+        assert!(service_inner.retry_timers.is_empty());
+        let report = service_inner
+            .status_store
+            .resolve_invocation(MOCK_PARTITION, &invocation_id)
+            .unwrap();
+        assert!(report.in_flight());
+        assert_eq!(report.retry_count(), 2);
+    }
 }
@@ -14,6 +14,7 @@ use std::{
     future::Future,
     io::{self, ErrorKind},
     net::SocketAddr,
+    num::NonZeroU64,
     ops::{Deref, DerefMut},
     path::PathBuf,
     pin::Pin,
@@ -41,7 +42,7 @@ use tracing::{error, info, warn};
 use typed_builder::TypedBuilder;
 
 use restate_types::{
-    config::{Configuration, MetadataStoreClient},
+    config::{Configuration, MetadataStoreClient, MetadataStoreKind, RaftOptions, RaftPeer},
     errors::GenericError,
     metadata_store::keys::NODES_CONFIG_KEY,
     net::{AdvertisedAddress, BindAddress},
@@ -183,7 +184,7 @@ impl Node {
             );
             let metadata_node_address = metadata_node.advertised_address().clone();
             *metadata_node.metadata_store_client_mut() = MetadataStoreClient::Embedded {
-                address: metadata_node_address.clone(),
+                addresses: vec![metadata_node_address.clone()],
             };
 
             nodes.push(metadata_node);
@@ -212,7 +213,7 @@ impl Node {
                 roles,
             );
             *node.metadata_store_client_mut() = MetadataStoreClient::Embedded {
-                address: metadata_node_address.clone(),
+                addresses: vec![metadata_node_address.clone()],
             };
             nodes.push(node);
         }
@@ -220,6 +221,65 @@ impl Node {
         nodes
     }
 
+    // Creates a group of Nodes ["node-1", ..] which all run the metadata-store role, replicating
+    // the metadata store using Raft, and the provided roles. "node-1" additionally runs the admin
+    // role. Every node lists the addresses of all members, its own member first, so that it can
+    // fall back to the other members if its own member is unavailable. Node name,
+    // roles, bind/advertise addresses, and the metadata store options from the base_config will
+    // all be overwritten.
+    pub fn new_test_nodes_with_raft_metadata(
+        base_config: Configuration,
+        binary_source: BinarySource,
+        roles: EnumSet<Role>,
+        size: u32,
+    ) -> Vec<Self> {
+        let mut nodes: Vec<Self> = (1..=size)
+            .map(|node_id| {
+                let mut base_config = base_config.clone();
+                base_config.common.force_node_id = Some(PlainNodeId::new(node_id));
+
+                let roles = if node_id == 1 {
+                    roles | Role::Admin | Role::MetadataStore
+                } else {
+                    roles | Role::MetadataStore
+                };
+
+                Self::new_test_node(
+                    format!("node-{node_id}"),
+                    base_config,
+                    binary_source.clone(),
+                    roles,
+                )
+            })
+            .collect();
+
+        let peers: Vec<_> = nodes
+            .iter()
+            .zip(1..)
+            .map(|(node, id)| RaftPeer {
+                id: NonZeroU64::new(id).expect("non-zero raft member id"),
+                address: node.advertised_address().clone(),
+            })
+            .collect();
+
+        for (node, peer) in nodes.iter_mut().zip(&peers) {
+            node.base_config.metadata_store.kind =
+                MetadataStoreKind::Raft(RaftOptions::new(peer.id, peers.clone()));
+            let addresses = std::iter::once(&peer.address)
+                .chain(
+                    peers
+                        .iter()
+                        .filter(|other| other.id != peer.id)
+                        .map(|other| &other.address),
+                )
+                .cloned()
+                .collect();
+            *node.metadata_store_client_mut() = MetadataStoreClient::Embedded { addresses };
+        }
+
+        nodes
+    }
+
     /// Start this Node, providing the base_dir and the cluster_name of the cluster its
     /// expected to attach to. All relative file paths addresses specified in the node config
     /// (eg, nodename/node.sock) will be absolutized against the base path, and the base dir
@@ -232,15 +292,17 @@ impl Node {
         let base_dir = base_dir.into();
 
         // ensure file paths are relative to the base dir
-        if let MetadataStoreClient::Embedded {
-            address: AdvertisedAddress::Uds(file),
-        } = &mut self
+        if let MetadataStoreClient::Embedded { addresses } = &mut self
             .base_config
             .common
             .metadata_store_client
             .metadata_store_client
         {
-            *file = base_dir.join(&*file)
+            for address in addresses {
+                if let AdvertisedAddress::Uds(file) = address {
+                    *file = base_dir.join(&*file)
+                }
+            }
         }
         if self.base_config.common.bind_address.is_none() {
             // Derive bind_address from advertised_address
@@ -256,6 +318,14 @@ impl Node {
             *file = base_dir.join(&*file);
         }
 
+        if let MetadataStoreKind::Raft(raft_options) = &mut self.base_config.metadata_store.kind {
+            for peer in &mut raft_options.peers {
+                if let AdvertisedAddress::Uds(file) = &mut peer.address {
+                    *file = base_dir.join(&*file)
+                }
+            }
+        }
+
         if let AdvertisedAddress::Uds(file) = &mut self.base_config.common.advertised_address {
             *file = base_dir.join(&*file)
         }
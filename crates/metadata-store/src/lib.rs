@@ -10,7 +10,81 @@
 
 mod grpc_svc;
 pub mod local;
+pub mod raft;
+
+use restate_core::network::NetworkServerBuilder;
+use restate_types::config::{MetadataStoreKind, MetadataStoreOptions, RocksDbOptions};
+use restate_types::health::HealthStatus;
+use restate_types::live::BoxedLiveLoad;
+use restate_types::protobuf::common::MetadataServerStatus;
 
 pub use restate_core::metadata_store::{
     MetadataStoreClient, Precondition, ReadError, ReadModifyWriteError, WriteError,
 };
+
+use crate::local::LocalMetadataStoreService;
+use crate::raft::RaftMetadataStoreService;
+
+/// The embedded metadata store run by nodes with the `MetadataStore` role.
+pub enum MetadataStoreService {
+    Local(LocalMetadataStoreService),
+    Raft(RaftMetadataStoreService),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BuildError {
+    #[error(transparent)]
+    Local(#[from] local::BuildError),
+    #[error(transparent)]
+    Raft(#[from] raft::BuildError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Local(#[from] local::Error),
+    #[error(transparent)]
+    Raft(#[from] raft::Error),
+}
+
+impl MetadataStoreService {
+    pub async fn create(
+        health_status: HealthStatus<MetadataServerStatus>,
+        options: &MetadataStoreOptions,
+        rocksdb_options: BoxedLiveLoad<RocksDbOptions>,
+        server_builder: &mut NetworkServerBuilder,
+    ) -> Result<Self, BuildError> {
+        let service = match &options.kind {
+            MetadataStoreKind::Local => MetadataStoreService::Local(
+                LocalMetadataStoreService::create(
+                    health_status,
+                    options,
+                    rocksdb_options,
+                    server_builder,
+                )
+                .await?,
+            ),
+            MetadataStoreKind::Raft(raft_options) => MetadataStoreService::Raft(
+                RaftMetadataStoreService::create(
+                    health_status,
+                    options,
+                    raft_options,
+                    rocksdb_options,
+                    server_builder,
+                )
+                .await?,
+            ),
+        };
+
+        Ok(service)
+    }
+
+    pub async fn run(self) -> Result<(), Error> {
+        match self {
+            MetadataStoreService::Local(service) => service.run().await?,
+            MetadataStoreService::Raft(service) => service.run().await?,
+        }
+
+        Ok(())
+    }
+}
@@ -8,10 +8,15 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use bytestring::ByteString;
 use tonic::transport::Channel;
-use tonic::{Code, Status};
+use tonic::{Code, Response, Status};
+use tracing::debug;
 
 use restate_core::metadata_store::{
     MetadataStore, Precondition, ReadError, VersionedValue, WriteError,
@@ -26,49 +31,103 @@ use crate::grpc_svc::{DeleteRequest, GetRequest, PutRequest};
 use crate::local::grpc::pb_conversions::ConversionError;
 
 /// Client end to interact with the [`LocalMetadataStore`].
+///
+/// If the metadata store is replicated, requests are sent to the member which answered the last
+/// request. Unavailable members are skipped in favor of the next member, members forward the
+/// requests to their leader.
 #[derive(Debug, Clone)]
 pub struct LocalMetadataStoreClient {
-    svc_client: MetadataStoreSvcClient<Channel>,
+    svc_clients: Arc<[(AdvertisedAddress, MetadataStoreSvcClient<Channel>)]>,
+    current_member: Arc<AtomicUsize>,
 }
 
 impl LocalMetadataStoreClient {
+    /// # Panics
+    ///
+    /// If no address is provided.
     pub fn new<T: CommonClientConnectionOptions>(
-        metadata_store_address: AdvertisedAddress,
+        metadata_store_addresses: Vec<AdvertisedAddress>,
         options: &T,
     ) -> Self {
-        let channel = create_tonic_channel_from_advertised_address(metadata_store_address, options);
+        assert!(
+            !metadata_store_addresses.is_empty(),
+            "at least one metadata store address is required"
+        );
+
+        let svc_clients = metadata_store_addresses
+            .into_iter()
+            .map(|address| {
+                let channel =
+                    create_tonic_channel_from_advertised_address(address.clone(), options);
+                (address, MetadataStoreSvcClient::new(channel))
+            })
+            .collect();
 
         Self {
-            svc_client: MetadataStoreSvcClient::new(channel),
+            svc_clients,
+            current_member: Arc::default(),
+        }
+    }
+
+    /// Sends the request to the current member, trying every other member once if it is
+    /// unavailable. Fails with the status of the last member if none was available.
+    async fn call<R, F, Fut>(&self, request: F) -> Result<R, Status>
+    where
+        F: Fn(MetadataStoreSvcClient<Channel>) -> Fut,
+        Fut: Future<Output = Result<Response<R>, Status>>,
+    {
+        let current_member = self.current_member.load(Ordering::Relaxed);
+        let mut last_status = None;
+
+        for attempt in 0..self.svc_clients.len() {
+            let member = (current_member + attempt) % self.svc_clients.len();
+            let (address, svc_client) = &self.svc_clients[member];
+
+            match request(svc_client.clone()).await {
+                Ok(response) => {
+                    self.current_member.store(member, Ordering::Relaxed);
+                    return Ok(response.into_inner());
+                }
+                Err(status) if status.code() == Code::Unavailable => {
+                    debug!(%address, "Metadata store member is unavailable: {}", status.message());
+                    last_status = Some(status);
+                }
+                Err(status) => return Err(status),
+            }
         }
+
+        Err(last_status.expect("at least one metadata store member"))
     }
 }
 
 #[async_trait]
 impl MetadataStore for LocalMetadataStoreClient {
     async fn get(&self, key: ByteString) -> Result<Option<VersionedValue>, ReadError> {
+        let key: bytes::Bytes = key.into();
         let response = self
-            .svc_client
-            .clone()
-            .get(GetRequest { key: key.into() })
+            .call(|mut svc_client| {
+                let key = key.clone();
+                async move { svc_client.get(GetRequest { key }).await }
+            })
             .await
             .map_err(map_status_to_read_error)?;
 
         response
-            .into_inner()
             .try_into()
             .map_err(|err: ConversionError| ReadError::Internal(err.to_string()))
     }
 
     async fn get_version(&self, key: ByteString) -> Result<Option<Version>, ReadError> {
+        let key: bytes::Bytes = key.into();
         let response = self
-            .svc_client
-            .clone()
-            .get_version(GetRequest { key: key.into() })
+            .call(|mut svc_client| {
+                let key = key.clone();
+                async move { svc_client.get_version(GetRequest { key }).await }
+            })
             .await
             .map_err(map_status_to_read_error)?;
 
-        Ok(response.into_inner().into())
+        Ok(response.into())
     }
 
     async fn put(
@@ -77,28 +136,32 @@ impl MetadataStore for LocalMetadataStoreClient {
         value: VersionedValue,
         precondition: Precondition,
     ) -> Result<(), WriteError> {
-        self.svc_client
-            .clone()
-            .put(PutRequest {
-                key: key.into(),
-                value: Some(value.into()),
-                precondition: Some(precondition.into()),
-            })
-            .await
-            .map_err(map_status_to_write_error)?;
+        let request = PutRequest {
+            key: key.into(),
+            value: Some(value.into()),
+            precondition: Some(precondition.into()),
+        };
+        self.call(|mut svc_client| {
+            let request = request.clone();
+            async move { svc_client.put(request).await }
+        })
+        .await
+        .map_err(map_status_to_write_error)?;
 
         Ok(())
     }
 
     async fn delete(&self, key: ByteString, precondition: Precondition) -> Result<(), WriteError> {
-        self.svc_client
-            .clone()
-            .delete(DeleteRequest {
-                key: key.into(),
-                precondition: Some(precondition.into()),
-            })
-            .await
-            .map_err(map_status_to_write_error)?;
+        let request = DeleteRequest {
+            key: key.into(),
+            precondition: Some(precondition.into()),
+        };
+        self.call(|mut svc_client| {
+            let request = request.clone();
+            async move { svc_client.delete(request).await }
+        })
+        .await
+        .map_err(map_status_to_write_error)?;
 
         Ok(())
     }
@@ -16,7 +16,8 @@ use async_trait::async_trait;
 use tokio::sync::oneshot;
 use tonic::{Request, Response, Status};
 
-/// Grpc svc handler for the [`LocalMetadataStore`].
+/// Grpc svc handler for the [`LocalMetadataStore`]. It is also used by the Raft metadata store
+/// which processes the same requests.
 #[derive(Debug)]
 pub struct LocalMetadataStoreHandler {
     request_tx: RequestSender,
@@ -133,6 +134,8 @@ impl From<Error> for Status {
     fn from(err: Error) -> Self {
         match err {
             Error::FailedPrecondition(msg) => Status::failed_precondition(msg),
+            Error::Unavailable(msg) => Status::unavailable(msg),
+            Error::UnknownOutcome(msg) => Status::unknown(msg),
             err => Status::internal(err.to_string()),
         }
     }
@@ -8,8 +8,8 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-mod grpc;
-mod store;
+pub(crate) mod grpc;
+pub(crate) mod store;
 
 mod service;
 
@@ -34,8 +34,11 @@ pub async fn create_client(
     );
 
     let client = match metadata_store_client_options.metadata_store_client.clone() {
-        MetadataStoreClientConfig::Embedded { address } => {
-            let store = LocalMetadataStoreClient::new(address, &metadata_store_client_options);
+        MetadataStoreClientConfig::Embedded { addresses } => {
+            if addresses.is_empty() {
+                return Err("no address of the embedded metadata store is configured".into());
+            }
+            let store = LocalMetadataStoreClient::new(addresses, &metadata_store_client_options);
             MetadataStoreClient::new(store, backoff_policy)
         }
         MetadataStoreClientConfig::Etcd { addresses } => {
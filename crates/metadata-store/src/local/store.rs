@@ -72,14 +72,19 @@ pub enum Error {
     Encode(#[from] StorageEncodeError),
     #[error("decode error: {0}")]
     Decode(#[from] StorageDecodeError),
+    #[error("unavailable: {0}")]
+    Unavailable(String),
+    /// The request might or might not have been applied
+    #[error("unknown outcome: {0}")]
+    UnknownOutcome(String),
 }
 
 impl Error {
-    fn kv_pair_exists() -> Self {
+    pub(crate) fn kv_pair_exists() -> Self {
         Error::FailedPrecondition("key-value pair already exists".to_owned())
     }
 
-    fn version_mismatch(expected: Version, actual: Option<Version>) -> Self {
+    pub(crate) fn version_mismatch(expected: Version, actual: Option<Version>) -> Self {
         Error::FailedPrecondition(format!(
             "Expected version '{expected}' but found version '{actual:?}'"
         ))
@@ -340,11 +345,11 @@ impl LocalMetadataStore {
     }
 }
 
-fn db_options(_options: &MetadataStoreOptions) -> rocksdb::Options {
+pub(crate) fn db_options(_options: &MetadataStoreOptions) -> rocksdb::Options {
     rocksdb::Options::default()
 }
 
-fn cf_options(
+pub(crate) fn cf_options(
     memory_budget: usize,
 ) -> impl Fn(rocksdb::Options) -> rocksdb::Options + Send + Sync + 'static {
     move |mut opts| {
@@ -314,7 +314,7 @@ async fn start_metadata_store(
     let uds = tempfile::tempdir()?.into_path().join("metadata-rpc-server");
     let bind_address = BindAddress::Uds(uds.clone());
     metadata_store_client_options.metadata_store_client = config::MetadataStoreClient::Embedded {
-        address: AdvertisedAddress::Uds(uds),
+        addresses: vec![AdvertisedAddress::Uds(uds)],
     };
 
     let rpc_server_health_status = HealthStatus::default();
@@ -322,7 +322,7 @@ async fn start_metadata_store(
         let rpc_server_health_status = rpc_server_health_status.clone();
         async move {
             server_builder
-                .run(rpc_server_health_status, &bind_address)
+                .run(rpc_server_health_status, &bind_address, None)
                 .await
         }
     })?;
@@ -337,7 +337,7 @@ async fn start_metadata_store(
     )?;
 
     assert2::let_assert!(
-        config::MetadataStoreClient::Embedded { address } =
+        config::MetadataStoreClient::Embedded { addresses } =
             metadata_store_client_options.metadata_store_client.clone()
     );
 
@@ -345,7 +345,7 @@ async fn start_metadata_store(
         .wait_for_value(NodeRpcStatus::Ready)
         .await;
 
-    let rocksdb_client = LocalMetadataStoreClient::new(address, &metadata_store_client_options);
+    let rocksdb_client = LocalMetadataStoreClient::new(addresses, &metadata_store_client_options);
     let client = MetadataStoreClient::new(
         rocksdb_client,
         Some(metadata_store_client_options.metadata_store_client_backoff_policy),
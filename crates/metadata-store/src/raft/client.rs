@@ -0,0 +1,63 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use tonic::transport::Channel;
+use tonic::Status;
+
+use restate_core::network::net_util::{
+    create_tonic_channel_from_advertised_address, CommonClientConnectionOptions,
+};
+use restate_types::net::AdvertisedAddress;
+
+use crate::grpc_svc::raft_metadata_store_svc_client::RaftMetadataStoreSvcClient;
+use crate::grpc_svc::{AddMemberRequest, RemoveMemberRequest};
+
+/// Client to change the members of a Raft metadata store. Requests can be sent to any member,
+/// they are forwarded to the leader.
+#[derive(Debug, Clone)]
+pub struct RaftMembershipClient {
+    svc_client: RaftMetadataStoreSvcClient<Channel>,
+}
+
+impl RaftMembershipClient {
+    pub fn new<T: CommonClientConnectionOptions>(
+        member_address: AdvertisedAddress,
+        options: &T,
+    ) -> Self {
+        let channel = create_tonic_channel_from_advertised_address(member_address, options);
+
+        Self {
+            svc_client: RaftMetadataStoreSvcClient::new(channel),
+        }
+    }
+
+    /// Adds the member `id` which is reachable at `address`. The node of the new member must run
+    /// the Raft metadata store without being part of its initial members.
+    pub async fn add_member(&self, id: u64, address: &AdvertisedAddress) -> Result<(), Status> {
+        self.svc_client
+            .clone()
+            .add_member(AddMemberRequest {
+                id,
+                address: address.to_string(),
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_member(&self, id: u64) -> Result<(), Status> {
+        self.svc_client
+            .clone()
+            .remove_member(RemoveMemberRequest { id })
+            .await?;
+
+        Ok(())
+    }
+}
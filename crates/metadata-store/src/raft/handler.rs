@@ -0,0 +1,110 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use async_trait::async_trait;
+use protobuf::Message as ProtobufMessage;
+use raft::prelude::Message;
+use tokio::sync::oneshot;
+use tonic::{Request, Response, Status};
+
+use restate_types::net::AdvertisedAddress;
+
+use crate::grpc_svc::raft_metadata_store_svc_server::RaftMetadataStoreSvc;
+use crate::grpc_svc::{AddMemberRequest, RaftMessage, RemoveMemberRequest};
+use crate::local::store::Error;
+use crate::raft::store::{MembershipRequest, MembershipSender, RaftSender};
+
+/// Grpc svc handler for the messages exchanged between the members of a [`RaftMetadataStore`]
+/// and for its membership changes.
+#[derive(Debug)]
+pub struct RaftMetadataStoreHandler {
+    raft_tx: RaftSender,
+    membership_tx: MembershipSender,
+}
+
+impl RaftMetadataStoreHandler {
+    pub fn new(raft_tx: RaftSender, membership_tx: MembershipSender) -> Self {
+        Self {
+            raft_tx,
+            membership_tx,
+        }
+    }
+
+    async fn change_membership(
+        &self,
+        request: MembershipRequest,
+        result_rx: oneshot::Receiver<Result<(), Error>>,
+    ) -> Result<Response<()>, Status> {
+        self.membership_tx
+            .send(request)
+            .await
+            .map_err(|_| Status::unavailable("metadata store is shut down"))?;
+
+        result_rx
+            .await
+            .map_err(|_| Status::unavailable("metadata store is shut down"))??;
+
+        Ok(Response::new(()))
+    }
+}
+
+#[async_trait]
+impl RaftMetadataStoreSvc for RaftMetadataStoreHandler {
+    async fn raft(&self, request: Request<RaftMessage>) -> Result<Response<()>, Status> {
+        let message = Message::parse_from_bytes(&request.into_inner().message)
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+
+        self.raft_tx
+            .send(message)
+            .await
+            .map_err(|_| Status::unavailable("metadata store is shut down"))?;
+
+        Ok(Response::new(()))
+    }
+
+    async fn add_member(&self, request: Request<AddMemberRequest>) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+        let id = member_id(request.id)?;
+        let address: AdvertisedAddress = request
+            .address
+            .parse()
+            .map_err(|err| Status::invalid_argument(format!("invalid address: {err}")))?;
+
+        let (result_tx, result_rx) = oneshot::channel();
+        self.change_membership(
+            MembershipRequest::AddMember {
+                id,
+                address,
+                result_tx,
+            },
+            result_rx,
+        )
+        .await
+    }
+
+    async fn remove_member(
+        &self,
+        request: Request<RemoveMemberRequest>,
+    ) -> Result<Response<()>, Status> {
+        let id = member_id(request.into_inner().id)?;
+
+        let (result_tx, result_rx) = oneshot::channel();
+        self.change_membership(MembershipRequest::RemoveMember { id, result_tx }, result_rx)
+            .await
+    }
+}
+
+fn member_id(id: u64) -> Result<u64, Status> {
+    if id == raft::INVALID_ID {
+        Err(Status::invalid_argument("member id must not be 0"))
+    } else {
+        Ok(id)
+    }
+}
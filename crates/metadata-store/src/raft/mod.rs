@@ -0,0 +1,26 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Metadata store which is replicated across the nodes running the `MetadataStore` role using
+//! Raft. It serves the same grpc service as the [`local`](crate::local) metadata store, so that
+//! clients can use any member as embedded metadata store address.
+
+mod client;
+mod handler;
+mod networking;
+mod service;
+mod storage;
+mod store;
+
+#[cfg(test)]
+mod tests;
+
+pub use client::RaftMembershipClient;
+pub use service::{BuildError, Error, RaftMetadataStoreService};
@@ -0,0 +1,173 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use protobuf::Message as ProtobufMessage;
+use raft::prelude::{Message, MessageType};
+use raft::SnapshotStatus;
+use tokio::sync::mpsc;
+use tonic::transport::Channel;
+use tracing::{debug, trace};
+
+use restate_core::network::net_util::create_tonic_channel_from_advertised_address;
+use restate_core::{TaskCenter, TaskKind};
+use restate_types::config::NetworkingOptions;
+use restate_types::net::AdvertisedAddress;
+
+use crate::grpc_svc::raft_metadata_store_svc_client::RaftMetadataStoreSvcClient;
+use crate::grpc_svc::RaftMessage;
+
+/// Number of messages which are buffered per peer. Raft retransmits lost messages, so it is
+/// safe to drop messages if a peer cannot keep up.
+const PEER_QUEUE_LENGTH: usize = 128;
+
+/// Outcome of sending messages which Raft needs to learn about.
+#[derive(Debug)]
+pub enum PeerFeedback {
+    /// A message could not be delivered to the member
+    Unreachable(u64),
+    /// A snapshot was sent to the member, or failed to be sent
+    Snapshot { to: u64, status: SnapshotStatus },
+}
+
+pub type PeerFeedbackSender = mpsc::UnboundedSender<PeerFeedback>;
+
+/// Sends Raft messages to the other members of the Raft group. Each peer has its own connection
+/// task so that a slow or unreachable peer does not hold up the others.
+pub struct Networking {
+    options: NetworkingOptions,
+    addresses: HashMap<u64, AdvertisedAddress>,
+    connections: HashMap<u64, mpsc::Sender<Message>>,
+    feedback_tx: PeerFeedbackSender,
+}
+
+impl Networking {
+    pub fn new(options: NetworkingOptions, feedback_tx: PeerFeedbackSender) -> Self {
+        Self {
+            options,
+            addresses: HashMap::default(),
+            connections: HashMap::default(),
+            feedback_tx,
+        }
+    }
+
+    pub fn register_address(&mut self, id: u64, address: AdvertisedAddress) {
+        if self.addresses.get(&id) != Some(&address) {
+            // reconnect to the new address
+            self.connections.remove(&id);
+            self.addresses.insert(id, address);
+        }
+    }
+
+    pub fn remove_address(&mut self, id: u64) {
+        self.connections.remove(&id);
+        self.addresses.remove(&id);
+    }
+
+    pub fn send(&mut self, messages: Vec<Message>) {
+        for message in messages {
+            let to = message.to;
+            let is_snapshot = message.get_msg_type() == MessageType::MsgSnapshot;
+            let Some(connection) = self.connection(to) else {
+                trace!("Dropping raft message to member {to} with unknown address");
+                report_dropped(&self.feedback_tx, to, is_snapshot);
+                continue;
+            };
+
+            match connection.try_send(message) {
+                Ok(()) => {}
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    trace!("Dropping raft message to member {to} because its queue is full");
+                    report_dropped(&self.feedback_tx, to, is_snapshot);
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    self.connections.remove(&to);
+                    report_dropped(&self.feedback_tx, to, is_snapshot);
+                }
+            }
+        }
+    }
+
+    fn connection(&mut self, id: u64) -> Option<&mpsc::Sender<Message>> {
+        if !self.connections.contains_key(&id) {
+            let address = self.addresses.get(&id)?.clone();
+            let channel = create_tonic_channel_from_advertised_address(address, &self.options);
+            let (tx, rx) = mpsc::channel(PEER_QUEUE_LENGTH);
+
+            if let Err(err) = TaskCenter::spawn_child(
+                TaskKind::MetadataStore,
+                "raft-peer-connection",
+                run_connection(
+                    id,
+                    RaftMetadataStoreSvcClient::new(channel),
+                    rx,
+                    self.feedback_tx.clone(),
+                ),
+            ) {
+                debug!("Failed spawning connection to raft member {id}: {err}");
+                return None;
+            }
+
+            self.connections.insert(id, tx);
+        }
+
+        self.connections.get(&id)
+    }
+}
+
+/// Tells Raft that a message was not delivered, so that it probes the member before sending it
+/// more entries, and sends a failed snapshot again.
+fn report_dropped(feedback_tx: &PeerFeedbackSender, to: u64, is_snapshot: bool) {
+    let _ = feedback_tx.send(PeerFeedback::Unreachable(to));
+    if is_snapshot {
+        let _ = feedback_tx.send(PeerFeedback::Snapshot {
+            to,
+            status: SnapshotStatus::Failure,
+        });
+    }
+}
+
+async fn run_connection(
+    id: u64,
+    mut client: RaftMetadataStoreSvcClient<Channel>,
+    mut rx: mpsc::Receiver<Message>,
+    feedback_tx: PeerFeedbackSender,
+) -> anyhow::Result<()> {
+    while let Some(message) = rx.recv().await {
+        let is_snapshot = message.get_msg_type() == MessageType::MsgSnapshot;
+        let message = match message.write_to_bytes() {
+            Ok(message) => Bytes::from(message),
+            Err(err) => {
+                debug!("Failed encoding raft message for member {id}: {err}");
+                report_dropped(&feedback_tx, id, is_snapshot);
+                continue;
+            }
+        };
+
+        match client.raft(RaftMessage { message }).await {
+            Ok(_) => {
+                if is_snapshot {
+                    let _ = feedback_tx.send(PeerFeedback::Snapshot {
+                        to: id,
+                        status: SnapshotStatus::Finish,
+                    });
+                }
+            }
+            Err(status) => {
+                trace!("Failed sending raft message to member {id}: {status}");
+                report_dropped(&feedback_tx, id, is_snapshot);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,414 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use protobuf::Message as ProtobufMessage;
+use raft::prelude::{ConfState, Entry, HardState, Snapshot};
+use raft::{GetEntriesContext, RaftState, Storage, StorageError};
+use restate_rocksdb::{
+    CfName, CfPrefixPattern, DbName, DbSpecBuilder, IoMode, Priority, RocksDb, RocksDbManager,
+    RocksError,
+};
+use restate_types::config::{MetadataStoreOptions, RocksDbOptions};
+use restate_types::live::BoxedLiveLoad;
+use rocksdb::{BoundColumnFamily, WriteBatch, WriteOptions, DB};
+
+use crate::local::store::{cf_options, db_options};
+
+const DB_NAME: &str = "raft-metadata-store";
+const RAFT_CF: &str = "raft";
+
+const HARD_STATE_KEY: &[u8] = b"hard_state";
+const CONF_STATE_KEY: &[u8] = b"conf_state";
+const SNAPSHOT_KEY: &[u8] = b"snapshot";
+const ENTRY_KEY_PREFIX: u8 = b'e';
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("storage error: {0}")]
+    Storage(#[from] rocksdb::Error),
+    #[error("rocksdb error: {0}")]
+    RocksDb(#[from] RocksError),
+    #[error("protobuf error: {0}")]
+    Protobuf(#[from] protobuf::ProtobufError),
+    #[error("missing log entry with index {0}")]
+    MissingEntry(u64),
+}
+
+impl From<Error> for raft::Error {
+    fn from(err: Error) -> Self {
+        raft::Error::Store(StorageError::Other(Box::new(err)))
+    }
+}
+
+/// Durable [`Storage`] of a Raft member which keeps the log, the hard state, the configuration
+/// state and the latest snapshot in RocksDB.
+///
+/// The log only contains the entries after the latest snapshot. The metadata is cached in memory
+/// since it is needed by almost every Raft operation.
+pub struct RocksDbStorage {
+    db: Arc<DB>,
+    rocksdb: Arc<RocksDb>,
+    rocksdb_options: BoxedLiveLoad<RocksDbOptions>,
+
+    hard_state: HardState,
+    conf_state: ConfState,
+    snapshot: Snapshot,
+    last_index: u64,
+}
+
+impl RocksDbStorage {
+    pub async fn create(
+        options: &MetadataStoreOptions,
+        updateable_rocksdb_options: BoxedLiveLoad<RocksDbOptions>,
+    ) -> Result<Self, Error> {
+        let db_name = DbName::new(DB_NAME);
+        let db_manager = RocksDbManager::get();
+        let cfs = vec![CfName::new(RAFT_CF)];
+        let db_spec = DbSpecBuilder::new(
+            db_name.clone(),
+            options.raft_data_dir(),
+            db_options(options),
+        )
+        .add_cf_pattern(
+            CfPrefixPattern::ANY,
+            cf_options(options.rocksdb_memory_budget()),
+        )
+        .ensure_column_families(cfs)
+        .build()
+        .expect("valid spec");
+
+        let db = db_manager
+            .open_db(updateable_rocksdb_options.clone(), db_spec)
+            .await?;
+        let rocksdb = db_manager
+            .get_db(db_name)
+            .expect("raft metadata store db is open");
+
+        let mut storage = Self {
+            db,
+            rocksdb,
+            rocksdb_options: updateable_rocksdb_options,
+            hard_state: HardState::default(),
+            conf_state: ConfState::default(),
+            snapshot: Snapshot::default(),
+            last_index: 0,
+        };
+
+        storage.hard_state = storage.read_value(HARD_STATE_KEY)?.unwrap_or_default();
+        storage.conf_state = storage.read_value(CONF_STATE_KEY)?.unwrap_or_default();
+        storage.snapshot = storage.read_value(SNAPSHOT_KEY)?.unwrap_or_default();
+        storage.last_index = storage.read_last_index()?;
+
+        Ok(storage)
+    }
+
+    /// Returns true if the member has been bootstrapped or has joined a Raft group.
+    pub fn is_initialized(&self) -> bool {
+        self.conf_state != ConfState::default()
+    }
+
+    pub fn snapshot_index(&self) -> u64 {
+        self.snapshot.get_metadata().index
+    }
+
+    pub fn conf_state(&self) -> &ConfState {
+        &self.conf_state
+    }
+
+    /// Returns the latest snapshot of the member.
+    pub fn latest_snapshot(&self) -> &Snapshot {
+        &self.snapshot
+    }
+
+    /// Initializes the storage of a member which bootstraps a new Raft group.
+    pub async fn initialize(&mut self, conf_state: ConfState) -> Result<(), Error> {
+        self.store_conf_state(conf_state).await
+    }
+
+    /// Appends the given entries to the log. Entries of the log which conflict with the given
+    /// entries are removed.
+    pub async fn append(&mut self, entries: &[Entry]) -> Result<(), Error> {
+        let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+            return Ok(());
+        };
+
+        let cf = self.raft_cf_handle();
+        let mut wb = WriteBatch::default();
+        if first.index <= self.last_index {
+            wb.delete_range_cf(&cf, entry_key(first.index), entry_key(self.last_index + 1));
+        }
+        for entry in entries {
+            wb.put_cf(&cf, entry_key(entry.index), entry.write_to_bytes()?);
+        }
+        drop(cf);
+
+        self.commit_write_batch(wb).await?;
+        self.last_index = last.index;
+
+        Ok(())
+    }
+
+    pub async fn store_hard_state(&mut self, hard_state: HardState) -> Result<(), Error> {
+        let mut wb = WriteBatch::default();
+        wb.put_cf(
+            &self.raft_cf_handle(),
+            HARD_STATE_KEY,
+            hard_state.write_to_bytes()?,
+        );
+        self.commit_write_batch(wb).await?;
+        self.hard_state = hard_state;
+
+        Ok(())
+    }
+
+    pub async fn store_commit(&mut self, commit: u64) -> Result<(), Error> {
+        let mut hard_state = self.hard_state.clone();
+        hard_state.commit = commit;
+        self.store_hard_state(hard_state).await
+    }
+
+    pub async fn store_conf_state(&mut self, conf_state: ConfState) -> Result<(), Error> {
+        let mut wb = WriteBatch::default();
+        wb.put_cf(
+            &self.raft_cf_handle(),
+            CONF_STATE_KEY,
+            conf_state.write_to_bytes()?,
+        );
+        self.commit_write_batch(wb).await?;
+        self.conf_state = conf_state;
+
+        Ok(())
+    }
+
+    /// Replaces the state of the member with the given snapshot received from the leader. The
+    /// whole log is discarded.
+    pub async fn apply_snapshot(&mut self, snapshot: Snapshot) -> Result<(), Error> {
+        let metadata = snapshot.get_metadata();
+        let index = metadata.index;
+
+        if index <= self.snapshot_index() {
+            // we already have a more recent snapshot
+            return Ok(());
+        }
+
+        let mut hard_state = self.hard_state.clone();
+        hard_state.term = hard_state.term.max(metadata.term);
+        hard_state.commit = index;
+        let conf_state = metadata.get_conf_state().clone();
+
+        let cf = self.raft_cf_handle();
+        let mut wb = WriteBatch::default();
+        wb.delete_range_cf(
+            &cf,
+            entry_key(self.first_index_inner()),
+            entry_key(self.last_index.max(index) + 1),
+        );
+        wb.put_cf(&cf, SNAPSHOT_KEY, snapshot.write_to_bytes()?);
+        wb.put_cf(&cf, HARD_STATE_KEY, hard_state.write_to_bytes()?);
+        wb.put_cf(&cf, CONF_STATE_KEY, conf_state.write_to_bytes()?);
+        drop(cf);
+
+        self.commit_write_batch(wb).await?;
+
+        self.snapshot = snapshot;
+        self.hard_state = hard_state;
+        self.conf_state = conf_state;
+        self.last_index = index;
+
+        Ok(())
+    }
+
+    /// Stores a snapshot which the member took of its own state and removes all log entries
+    /// which are covered by it.
+    pub async fn compact(&mut self, snapshot: Snapshot) -> Result<(), Error> {
+        let index = snapshot.get_metadata().index;
+        assert!(
+            index <= self.last_index,
+            "cannot compact log beyond its last index {}",
+            self.last_index
+        );
+
+        if index <= self.snapshot_index() {
+            return Ok(());
+        }
+
+        let cf = self.raft_cf_handle();
+        let mut wb = WriteBatch::default();
+        wb.delete_range_cf(
+            &cf,
+            entry_key(self.first_index_inner()),
+            entry_key(index + 1),
+        );
+        wb.put_cf(&cf, SNAPSHOT_KEY, snapshot.write_to_bytes()?);
+        drop(cf);
+
+        self.commit_write_batch(wb).await?;
+        self.snapshot = snapshot;
+
+        Ok(())
+    }
+
+    fn first_index_inner(&self) -> u64 {
+        self.snapshot_index() + 1
+    }
+
+    fn read_entry(&self, index: u64) -> Result<Entry, Error> {
+        let slice = self
+            .db
+            .get_pinned_cf(&self.raft_cf_handle(), entry_key(index))?
+            .ok_or(Error::MissingEntry(index))?;
+        Ok(Entry::parse_from_bytes(&slice)?)
+    }
+
+    fn read_value<T: ProtobufMessage>(&self, key: &[u8]) -> Result<Option<T>, Error> {
+        self.db
+            .get_pinned_cf(&self.raft_cf_handle(), key)?
+            .map(|slice| T::parse_from_bytes(&slice))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn read_last_index(&self) -> Result<u64, Error> {
+        let mut iterator = self.db.raw_iterator_cf(&self.raft_cf_handle());
+        iterator.seek_for_prev(entry_key(u64::MAX));
+
+        if let Some(key) = iterator.key() {
+            if key.len() == 9 && key[0] == ENTRY_KEY_PREFIX {
+                let index = u64::from_be_bytes(key[1..].try_into().expect("8 bytes"));
+                return Ok(index.max(self.snapshot_index()));
+            }
+        }
+        iterator.status()?;
+
+        Ok(self.snapshot_index())
+    }
+
+    fn raft_cf_handle(&self) -> Arc<BoundColumnFamily> {
+        self.db
+            .cf_handle(RAFT_CF)
+            .expect("RAFT_CF column family exists")
+    }
+
+    fn write_options(&mut self) -> WriteOptions {
+        let opts = self.rocksdb_options.live_load();
+        let mut write_opts = WriteOptions::default();
+
+        write_opts.disable_wal(opts.rocksdb_disable_wal());
+
+        if !opts.rocksdb_disable_wal() {
+            // always sync if we have wal enabled
+            write_opts.set_sync(true);
+        }
+
+        write_opts
+    }
+
+    async fn commit_write_batch(&mut self, wb: WriteBatch) -> Result<(), Error> {
+        let write_options = self.write_options();
+        Ok(self
+            .rocksdb
+            .write_batch(
+                "raft-metadata-write-batch",
+                Priority::High,
+                IoMode::default(),
+                write_options,
+                wb,
+            )
+            .await?)
+    }
+}
+
+impl Storage for RocksDbStorage {
+    fn initial_state(&self) -> raft::Result<RaftState> {
+        Ok(RaftState::new(
+            self.hard_state.clone(),
+            self.conf_state.clone(),
+        ))
+    }
+
+    fn entries(
+        &self,
+        low: u64,
+        high: u64,
+        max_size: impl Into<Option<u64>>,
+        _context: GetEntriesContext,
+    ) -> raft::Result<Vec<Entry>> {
+        if low < self.first_index_inner() {
+            return Err(raft::Error::Store(StorageError::Compacted));
+        }
+
+        if high > self.last_index + 1 {
+            return Err(raft::Error::Store(StorageError::Unavailable));
+        }
+
+        let max_size = max_size.into().unwrap_or(u64::MAX);
+        let mut entries = Vec::with_capacity((high - low) as usize);
+        let mut size = 0;
+
+        for index in low..high {
+            let entry = self.read_entry(index)?;
+            size += u64::from(entry.compute_size());
+
+            // always return at least one entry
+            if !entries.is_empty() && size > max_size {
+                break;
+            }
+
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+    fn term(&self, idx: u64) -> raft::Result<u64> {
+        let snapshot_metadata = self.snapshot.get_metadata();
+
+        if idx == snapshot_metadata.index {
+            return Ok(snapshot_metadata.term);
+        }
+
+        if idx < snapshot_metadata.index {
+            return Err(raft::Error::Store(StorageError::Compacted));
+        }
+
+        if idx > self.last_index {
+            return Err(raft::Error::Store(StorageError::Unavailable));
+        }
+
+        Ok(self.read_entry(idx)?.term)
+    }
+
+    fn first_index(&self) -> raft::Result<u64> {
+        Ok(self.first_index_inner())
+    }
+
+    fn last_index(&self) -> raft::Result<u64> {
+        Ok(self.last_index)
+    }
+
+    fn snapshot(&self, request_index: u64, _to: u64) -> raft::Result<Snapshot> {
+        if self.snapshot_index() < request_index {
+            return Err(raft::Error::Store(
+                StorageError::SnapshotTemporarilyUnavailable,
+            ));
+        }
+
+        Ok(self.snapshot.clone())
+    }
+}
+
+fn entry_key(index: u64) -> [u8; 9] {
+    let mut key = [0; 9];
+    key[0] = ENTRY_KEY_PREFIX;
+    key[1..].copy_from_slice(&index.to_be_bytes());
+    key
+}
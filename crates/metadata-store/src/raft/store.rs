@@ -0,0 +1,954 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bytes::BytesMut;
+use bytestring::ByteString;
+use protobuf::Message as ProtobufMessage;
+use raft::prelude::{ConfChange, ConfChangeType, ConfState, Entry, EntryType, Message, Snapshot};
+use raft::{RawNode, ReadState, StateRole, Storage};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{Instant, MissedTickBehavior};
+use tracing::{debug, error, info, trace, warn};
+
+use restate_core::cancellation_watcher;
+use restate_core::metadata_store::{Precondition, VersionedValue};
+use restate_types::config::{MetadataStoreOptions, NetworkingOptions, RaftOptions, RocksDbOptions};
+use restate_types::live::BoxedLiveLoad;
+use restate_types::net::AdvertisedAddress;
+use restate_types::storage::{StorageCodec, StorageDecodeError, StorageEncodeError};
+use restate_types::{flexbuffers_storage_encode_decode, Version};
+
+use crate::local::store::{
+    Error as RequestError, MetadataStoreRequest, RequestReceiver, RequestSender,
+};
+use crate::raft::networking::{Networking, PeerFeedback};
+use crate::raft::storage::{self, RocksDbStorage};
+
+pub type RaftSender = mpsc::Sender<Message>;
+pub type MembershipSender = mpsc::Sender<MembershipRequest>;
+
+/// Requests which are not answered within this time are failed so that clients can retry them,
+/// e.g. because the leader dropped them. Timed out writes fail with an unknown outcome since
+/// they might still get committed.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub enum MembershipRequest {
+    AddMember {
+        id: u64,
+        address: AdvertisedAddress,
+        result_tx: oneshot::Sender<Result<(), RequestError>>,
+    },
+    RemoveMember {
+        id: u64,
+        result_tx: oneshot::Sender<Result<(), RequestError>>,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("raft error: {0}")]
+    Raft(#[from] raft::Error),
+    #[error("storage error: {0}")]
+    Storage(#[from] storage::Error),
+    #[error("protobuf error: {0}")]
+    Protobuf(#[from] protobuf::ProtobufError),
+    #[error("encode error: {0}")]
+    Encode(#[from] StorageEncodeError),
+    #[error("decode error: {0}")]
+    Decode(#[from] StorageDecodeError),
+}
+
+/// Metadata store which replicates the key value pairs across the members of a Raft group.
+///
+/// Writes are appended to the replicated log and answered once they are applied. Reads are
+/// linearizable: they are answered once the member has applied the leader's commit index at the
+/// time of the read. Proposals and reads issued on followers are forwarded to the leader.
+pub struct RaftMetadataStore {
+    id: u64,
+    raw_node: RawNode<RocksDbStorage>,
+    kv_storage: KvMemoryStorage,
+    networking: Networking,
+    tick_interval: Duration,
+    log_trim_threshold: u64,
+    leader_id: u64,
+    applied_index: u64,
+    buffer: BytesMut,
+
+    pending_requests: HashMap<RequestId, PendingRequest>,
+    pending_reads: HashMap<RequestId, PendingRead>,
+    // reads for which the read index is known; they are answered once it has been applied
+    ready_reads: Vec<(u64, PendingRead)>,
+
+    request_rx: RequestReceiver,
+    raft_rx: mpsc::Receiver<Message>,
+    membership_rx: mpsc::Receiver<MembershipRequest>,
+    peer_feedback_rx: mpsc::UnboundedReceiver<PeerFeedback>,
+
+    // for creating other senders
+    request_tx: RequestSender,
+    raft_tx: RaftSender,
+    membership_tx: MembershipSender,
+}
+
+impl RaftMetadataStore {
+    pub async fn create(
+        options: &MetadataStoreOptions,
+        raft_options: &RaftOptions,
+        networking_options: NetworkingOptions,
+        updateable_rocksdb_options: BoxedLiveLoad<RocksDbOptions>,
+    ) -> Result<Self, Error> {
+        let (request_tx, request_rx) = mpsc::channel(options.request_queue_length());
+        let (raft_tx, raft_rx) = mpsc::channel(options.request_queue_length());
+        let (membership_tx, membership_rx) = mpsc::channel(1);
+
+        let id = raft_options.id.get();
+        let mut storage = RocksDbStorage::create(options, updateable_rocksdb_options).await?;
+
+        if !storage.is_initialized() {
+            if raft_options.peers.iter().any(|peer| peer.id.get() == id) {
+                let voters: Vec<_> = raft_options
+                    .peers
+                    .iter()
+                    .map(|peer| peer.id.get())
+                    .collect();
+                info!("Bootstrapping raft metadata store with members {voters:?}");
+                storage
+                    .initialize(ConfState::from((voters, Vec::<u64>::new())))
+                    .await?;
+            } else {
+                info!("Raft metadata store member {id} waits to be added to the raft group");
+            }
+        }
+
+        let (peer_feedback_tx, peer_feedback_rx) = mpsc::unbounded_channel();
+        let mut networking = Networking::new(networking_options, peer_feedback_tx);
+        for peer in &raft_options.peers {
+            networking.register_address(peer.id.get(), peer.address.clone());
+        }
+
+        let snapshot = storage.latest_snapshot();
+        let applied_index = snapshot.get_metadata().index;
+        let kv_storage = KvMemoryStorage::from_snapshot(snapshot)?;
+        for (member_id, address) in &kv_storage.members {
+            networking.register_address(*member_id, address.clone());
+        }
+
+        let config = raft::Config {
+            id,
+            election_tick: raft_options.raft_election_tick.get(),
+            heartbeat_tick: raft_options.raft_heartbeat_tick.get(),
+            applied: applied_index,
+            check_quorum: true,
+            pre_vote: true,
+            ..Default::default()
+        };
+        config.validate()?;
+
+        let logger = slog::Logger::root(TracingDrain, slog::o!());
+        let raw_node = RawNode::new(&config, storage, &logger)?;
+
+        Ok(Self {
+            id,
+            raw_node,
+            kv_storage,
+            networking,
+            tick_interval: raft_options.raft_tick_interval.into(),
+            log_trim_threshold: raft_options.log_trim_threshold.get(),
+            leader_id: raft::INVALID_ID,
+            applied_index,
+            buffer: BytesMut::default(),
+            pending_requests: HashMap::default(),
+            pending_reads: HashMap::default(),
+            ready_reads: Vec::default(),
+            request_rx,
+            raft_rx,
+            membership_rx,
+            peer_feedback_rx,
+            request_tx,
+            raft_tx,
+            membership_tx,
+        })
+    }
+
+    pub fn request_sender(&self) -> RequestSender {
+        self.request_tx.clone()
+    }
+
+    pub fn raft_sender(&self) -> RaftSender {
+        self.raft_tx.clone()
+    }
+
+    pub fn membership_sender(&self) -> MembershipSender {
+        self.membership_tx.clone()
+    }
+
+    pub async fn run(mut self) -> Result<(), Error> {
+        debug!(id = self.id, "Running RaftMetadataStore");
+
+        let mut tick_interval = tokio::time::interval(self.tick_interval);
+        tick_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                request = self.request_rx.recv() => {
+                    let request = request.expect("receiver should not be closed since we own one clone.");
+                    self.handle_request(request);
+                }
+                message = self.raft_rx.recv() => {
+                    let message = message.expect("receiver should not be closed since we own one clone.");
+                    if let Err(err) = self.raw_node.step(message) {
+                        debug!("Failed stepping raft message: {err}");
+                    }
+                }
+                request = self.membership_rx.recv() => {
+                    let request = request.expect("receiver should not be closed since we own one clone.");
+                    self.handle_membership_request(request);
+                }
+                Some(feedback) = self.peer_feedback_rx.recv() => {
+                    match feedback {
+                        PeerFeedback::Unreachable(id) => self.raw_node.report_unreachable(id),
+                        PeerFeedback::Snapshot { to, status } => self.raw_node.report_snapshot(to, status),
+                    }
+                }
+                _ = tick_interval.tick() => {
+                    self.raw_node.tick();
+                    self.fail_timed_out_requests();
+                }
+                _ = cancellation_watcher() => {
+                    break;
+                },
+            }
+
+            self.on_ready().await?;
+        }
+
+        debug!("Stopped RaftMetadataStore");
+
+        Ok(())
+    }
+
+    fn handle_request(&mut self, request: MetadataStoreRequest) {
+        trace!("Handle request '{:?}'", request);
+
+        match request {
+            MetadataStoreRequest::Get { key, result_tx } => {
+                self.read(ReadRequest::Get { key, result_tx });
+            }
+            MetadataStoreRequest::GetVersion { key, result_tx } => {
+                self.read(ReadRequest::GetVersion { key, result_tx });
+            }
+            MetadataStoreRequest::Put {
+                key,
+                value,
+                precondition,
+                result_tx,
+            } => {
+                self.propose(
+                    RequestKind::Put {
+                        key,
+                        value,
+                        precondition,
+                    },
+                    result_tx,
+                );
+            }
+            MetadataStoreRequest::Delete {
+                key,
+                precondition,
+                result_tx,
+            } => {
+                self.propose(RequestKind::Delete { key, precondition }, result_tx);
+            }
+        }
+    }
+
+    fn read(&mut self, request: ReadRequest) {
+        if self.leader_id == raft::INVALID_ID {
+            // raft drops reads while there is no leader
+            request.fail(RequestError::Unavailable("no known leader".to_owned()));
+            return;
+        }
+
+        let request_id = RequestId::random();
+        self.raw_node.read_index(request_id.to_bytes());
+        self.pending_reads.insert(
+            request_id,
+            PendingRead {
+                request,
+                deadline: Instant::now() + REQUEST_TIMEOUT,
+            },
+        );
+    }
+
+    fn propose(&mut self, kind: RequestKind, result_tx: oneshot::Sender<Result<(), RequestError>>) {
+        let request_id = RequestId::random();
+        let request = Request { request_id, kind };
+
+        self.buffer.clear();
+        if let Err(err) = StorageCodec::encode(&request, &mut self.buffer) {
+            let _ = result_tx.send(Err(err.into()));
+            return;
+        }
+
+        if let Err(err) = self.raw_node.propose(vec![], self.buffer.to_vec()) {
+            let _ = result_tx.send(Err(RequestError::Unavailable(format!(
+                "failed proposing request: {err}"
+            ))));
+            return;
+        }
+
+        self.pending_requests.insert(
+            request_id,
+            PendingRequest {
+                result_tx,
+                index: self.proposed_index(),
+                deadline: Instant::now() + REQUEST_TIMEOUT,
+            },
+        );
+    }
+
+    /// Index of the entry which has just been proposed, if this member is the leader and hence
+    /// appended the entry to its log. Proposals of followers are forwarded to the leader.
+    fn proposed_index(&self) -> Option<u64> {
+        (self.raw_node.raft.state == StateRole::Leader)
+            .then(|| self.raw_node.raft.raft_log.last_index())
+    }
+
+    fn handle_membership_request(&mut self, request: MembershipRequest) {
+        trace!("Handle membership request '{:?}'", request);
+
+        let (id, change_type, address, result_tx) = match request {
+            MembershipRequest::AddMember {
+                id,
+                address,
+                result_tx,
+            } => (id, ConfChangeType::AddNode, Some(address), result_tx),
+            MembershipRequest::RemoveMember { id, result_tx } => {
+                (id, ConfChangeType::RemoveNode, None, result_tx)
+            }
+        };
+
+        let request_id = RequestId::random();
+        self.buffer.clear();
+        if let Err(err) = StorageCodec::encode(
+            &MembershipChange {
+                request_id,
+                address,
+            },
+            &mut self.buffer,
+        ) {
+            let _ = result_tx.send(Err(err.into()));
+            return;
+        }
+
+        let mut conf_change = ConfChange::default();
+        conf_change.node_id = id;
+        conf_change.set_change_type(change_type);
+        conf_change.context = self.buffer.to_vec().into();
+
+        if let Err(err) = self.raw_node.propose_conf_change(vec![], conf_change) {
+            let _ = result_tx.send(Err(RequestError::Unavailable(format!(
+                "failed proposing membership change: {err}"
+            ))));
+            return;
+        }
+
+        self.pending_requests.insert(
+            request_id,
+            PendingRequest {
+                result_tx,
+                index: self.proposed_index(),
+                deadline: Instant::now() + REQUEST_TIMEOUT,
+            },
+        );
+    }
+
+    async fn on_ready(&mut self) -> Result<(), Error> {
+        if !self.raw_node.has_ready() {
+            return Ok(());
+        }
+
+        let mut ready = self.raw_node.ready();
+
+        if let Some(soft_state) = ready.ss() {
+            self.on_leader_change(soft_state.leader_id);
+        }
+
+        if !ready.messages().is_empty() {
+            self.networking.send(ready.take_messages());
+        }
+
+        if *ready.snapshot() != Snapshot::default() {
+            self.restore_snapshot(ready.snapshot().clone()).await?;
+        }
+
+        self.apply_committed_entries(ready.take_committed_entries())
+            .await?;
+
+        if !ready.entries().is_empty() {
+            self.raw_node.mut_store().append(ready.entries()).await?;
+        }
+
+        if let Some(hard_state) = ready.hs() {
+            self.raw_node
+                .mut_store()
+                .store_hard_state(hard_state.clone())
+                .await?;
+        }
+
+        if !ready.read_states().is_empty() {
+            self.on_read_states(ready.take_read_states());
+        }
+
+        if !ready.persisted_messages().is_empty() {
+            self.networking.send(ready.take_persisted_messages());
+        }
+
+        let mut light_ready = self.raw_node.advance(ready);
+
+        if let Some(commit) = light_ready.commit_index() {
+            self.raw_node.mut_store().store_commit(commit).await?;
+        }
+
+        self.networking.send(light_ready.take_messages());
+        self.apply_committed_entries(light_ready.take_committed_entries())
+            .await?;
+        self.raw_node.advance_apply();
+
+        self.answer_ready_reads();
+        self.maybe_trim_log().await?;
+
+        Ok(())
+    }
+
+    fn on_leader_change(&mut self, leader_id: u64) {
+        if leader_id == self.leader_id {
+            return;
+        }
+
+        debug!(
+            "Raft metadata store member {} sees new leader {leader_id}",
+            self.id
+        );
+        self.leader_id = leader_id;
+
+        // Pending writes might still get committed by the new leader. They are answered once they
+        // are applied, failed once their entry was replaced by another one, or time out with an
+        // unknown outcome. Pending reads are dropped by raft, fail them so that clients retry.
+        for (_, pending) in self.pending_reads.drain() {
+            pending
+                .request
+                .fail(RequestError::Unavailable("leader changed".to_owned()));
+        }
+    }
+
+    fn fail_timed_out_requests(&mut self) {
+        let now = Instant::now();
+
+        let timed_out_requests: Vec<_> = self
+            .pending_requests
+            .iter()
+            .filter(|(_, pending)| pending.deadline <= now)
+            .map(|(request_id, _)| *request_id)
+            .collect();
+        for request_id in timed_out_requests {
+            if let Some(pending) = self.pending_requests.remove(&request_id) {
+                let _ = pending.result_tx.send(Err(RequestError::UnknownOutcome(
+                    "request timed out, it might still be applied".to_owned(),
+                )));
+            }
+        }
+
+        let timed_out_reads: Vec<_> = self
+            .pending_reads
+            .iter()
+            .filter(|(_, pending)| pending.deadline <= now)
+            .map(|(request_id, _)| *request_id)
+            .collect();
+        for request_id in timed_out_reads {
+            if let Some(pending) = self.pending_reads.remove(&request_id) {
+                pending
+                    .request
+                    .fail(RequestError::Unavailable("request timed out".to_owned()));
+            }
+        }
+    }
+
+    async fn restore_snapshot(&mut self, snapshot: Snapshot) -> Result<(), Error> {
+        let kv_storage = KvMemoryStorage::from_snapshot(&snapshot)?;
+        for (member_id, address) in &kv_storage.members {
+            self.networking
+                .register_address(*member_id, address.clone());
+        }
+
+        self.applied_index = snapshot.get_metadata().index;
+        self.kv_storage = kv_storage;
+        self.raw_node.mut_store().apply_snapshot(snapshot).await?;
+
+        Ok(())
+    }
+
+    async fn apply_committed_entries(&mut self, entries: Vec<Entry>) -> Result<(), Error> {
+        for entry in entries {
+            self.applied_index = entry.index;
+
+            if entry.data.is_empty() {
+                // empty entry of a newly elected leader
+                continue;
+            }
+
+            match entry.get_entry_type() {
+                EntryType::EntryNormal => self.apply_request(&entry.data)?,
+                EntryType::EntryConfChange => self.apply_conf_change(&entry.data).await?,
+                EntryType::EntryConfChangeV2 => {
+                    warn!(
+                        "Ignoring unsupported conf change v2 at index {}",
+                        entry.index
+                    );
+                }
+            }
+        }
+
+        self.fail_dropped_requests();
+
+        Ok(())
+    }
+
+    /// Fails the requests whose entry has been replaced by an entry of another leader. Their
+    /// index has been applied without answering them, so they will never be applied.
+    fn fail_dropped_requests(&mut self) {
+        let applied_index = self.applied_index;
+        let dropped_requests: Vec<_> = self
+            .pending_requests
+            .iter()
+            .filter(|(_, pending)| pending.index.is_some_and(|index| index <= applied_index))
+            .map(|(request_id, _)| *request_id)
+            .collect();
+
+        for request_id in dropped_requests {
+            if let Some(pending) = self.pending_requests.remove(&request_id) {
+                let _ = pending.result_tx.send(Err(RequestError::Unavailable(
+                    "request was dropped by a new leader".to_owned(),
+                )));
+            }
+        }
+    }
+
+    fn apply_request(&mut self, mut data: &[u8]) -> Result<(), Error> {
+        let request: Request = StorageCodec::decode(&mut data)?;
+
+        let result = match request.kind {
+            RequestKind::Put {
+                key,
+                value,
+                precondition,
+            } => self.kv_storage.put(key, value, precondition),
+            RequestKind::Delete { key, precondition } => self.kv_storage.delete(&key, precondition),
+        };
+
+        if let Some(pending) = self.pending_requests.remove(&request.request_id) {
+            let _ = pending.result_tx.send(result);
+        }
+
+        Ok(())
+    }
+
+    async fn apply_conf_change(&mut self, data: &[u8]) -> Result<(), Error> {
+        let conf_change = ConfChange::parse_from_bytes(data)?;
+        let mut context: &[u8] = &conf_change.context;
+        let membership_change: MembershipChange = StorageCodec::decode(&mut context)?;
+        let member_id = conf_change.node_id;
+
+        let result = match self.raw_node.apply_conf_change(&conf_change) {
+            Ok(conf_state) => {
+                self.raw_node
+                    .mut_store()
+                    .store_conf_state(conf_state)
+                    .await?;
+
+                match (conf_change.get_change_type(), membership_change.address) {
+                    (ConfChangeType::RemoveNode, _) => {
+                        info!("Removed member {member_id} from the raft metadata store");
+                        self.kv_storage.members.remove(&member_id);
+                        self.networking.remove_address(member_id);
+                    }
+                    (_, Some(address)) => {
+                        info!("Added member {member_id} with address {address} to the raft metadata store");
+                        self.kv_storage.members.insert(member_id, address.clone());
+                        self.networking.register_address(member_id, address);
+                    }
+                    (_, None) => {}
+                }
+
+                Ok(())
+            }
+            Err(err) => Err(RequestError::InvalidArgument(format!(
+                "invalid membership change: {err}"
+            ))),
+        };
+
+        if let Some(pending) = self.pending_requests.remove(&membership_change.request_id) {
+            let _ = pending.result_tx.send(result);
+        }
+
+        Ok(())
+    }
+
+    fn on_read_states(&mut self, read_states: Vec<ReadState>) {
+        for read_state in read_states {
+            let Some(request_id) = RequestId::from_bytes(&read_state.request_ctx) else {
+                continue;
+            };
+
+            if let Some(pending) = self.pending_reads.remove(&request_id) {
+                self.ready_reads.push((read_state.index, pending));
+            }
+        }
+    }
+
+    fn answer_ready_reads(&mut self) {
+        let applied_index = self.applied_index;
+        let (ready, waiting) = std::mem::take(&mut self.ready_reads)
+            .into_iter()
+            .partition(|(read_index, _)| *read_index <= applied_index);
+        self.ready_reads = waiting;
+
+        for (_, pending) in ready {
+            pending.request.answer(&self.kv_storage);
+        }
+    }
+
+    /// Snapshots the state and trims the log once enough entries have been applied since the
+    /// last snapshot.
+    async fn maybe_trim_log(&mut self) -> Result<(), Error> {
+        let storage = self.raw_node.store();
+        if self.applied_index - storage.snapshot_index() < self.log_trim_threshold {
+            return Ok(());
+        }
+
+        let mut snapshot = Snapshot::default();
+        let metadata = snapshot.mut_metadata();
+        metadata.index = self.applied_index;
+        metadata.term = storage.term(self.applied_index)?;
+        metadata.set_conf_state(storage.conf_state().clone());
+
+        self.buffer.clear();
+        StorageCodec::encode(&self.kv_storage, &mut self.buffer)?;
+        snapshot.data = self.buffer.to_vec().into();
+
+        debug!(
+            "Trimming raft log of member {} up to index {}",
+            self.id, self.applied_index
+        );
+        self.raw_node.mut_store().compact(snapshot).await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct RequestId(u64);
+
+impl RequestId {
+    fn random() -> Self {
+        RequestId(rand::random())
+    }
+
+    fn to_bytes(self) -> Vec<u8> {
+        self.0.to_be_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(RequestId(u64::from_be_bytes(bytes.try_into().ok()?)))
+    }
+}
+
+/// Request which is replicated via the Raft log.
+#[derive(Debug, Serialize, Deserialize)]
+struct Request {
+    request_id: RequestId,
+    kind: RequestKind,
+}
+
+flexbuffers_storage_encode_decode!(Request);
+
+#[derive(Debug, Serialize, Deserialize)]
+enum RequestKind {
+    Put {
+        key: ByteString,
+        value: VersionedValue,
+        precondition: Precondition,
+    },
+    Delete {
+        key: ByteString,
+        precondition: Precondition,
+    },
+}
+
+/// Context of a replicated membership change.
+#[derive(Debug, Serialize, Deserialize)]
+struct MembershipChange {
+    request_id: RequestId,
+    address: Option<AdvertisedAddress>,
+}
+
+flexbuffers_storage_encode_decode!(MembershipChange);
+
+struct PendingRequest {
+    result_tx: oneshot::Sender<Result<(), RequestError>>,
+    /// Index of the request's entry in the log, if it was proposed on the leader
+    index: Option<u64>,
+    deadline: Instant,
+}
+
+struct PendingRead {
+    request: ReadRequest,
+    deadline: Instant,
+}
+
+enum ReadRequest {
+    Get {
+        key: ByteString,
+        result_tx: oneshot::Sender<Result<Option<VersionedValue>, RequestError>>,
+    },
+    GetVersion {
+        key: ByteString,
+        result_tx: oneshot::Sender<Result<Option<Version>, RequestError>>,
+    },
+}
+
+impl ReadRequest {
+    fn answer(self, kv_storage: &KvMemoryStorage) {
+        match self {
+            ReadRequest::Get { key, result_tx } => {
+                let _ = result_tx.send(Ok(kv_storage.get(&key).cloned()));
+            }
+            ReadRequest::GetVersion { key, result_tx } => {
+                let _ = result_tx.send(Ok(kv_storage.get_version(&key)));
+            }
+        }
+    }
+
+    fn fail(self, err: RequestError) {
+        match self {
+            ReadRequest::Get { result_tx, .. } => {
+                let _ = result_tx.send(Err(err));
+            }
+            ReadRequest::GetVersion { result_tx, .. } => {
+                let _ = result_tx.send(Err(err));
+            }
+        }
+    }
+}
+
+/// The replicated state: the key value pairs and the addresses of the members.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct KvMemoryStorage {
+    kv_entries: HashMap<ByteString, VersionedValue>,
+    // stored as a list since flexbuffers only supports string keys
+    #[serde(with = "members_serde")]
+    members: HashMap<u64, AdvertisedAddress>,
+}
+
+flexbuffers_storage_encode_decode!(KvMemoryStorage);
+
+impl KvMemoryStorage {
+    fn from_snapshot(snapshot: &Snapshot) -> Result<Self, StorageDecodeError> {
+        if snapshot.data.is_empty() {
+            Ok(Self::default())
+        } else {
+            let mut data: &[u8] = &snapshot.data;
+            StorageCodec::decode(&mut data)
+        }
+    }
+
+    fn get(&self, key: &ByteString) -> Option<&VersionedValue> {
+        self.kv_entries.get(key)
+    }
+
+    fn get_version(&self, key: &ByteString) -> Option<Version> {
+        self.kv_entries.get(key).map(|value| value.version)
+    }
+
+    fn put(
+        &mut self,
+        key: ByteString,
+        value: VersionedValue,
+        precondition: Precondition,
+    ) -> Result<(), RequestError> {
+        match precondition {
+            Precondition::None => {}
+            Precondition::DoesNotExist => {
+                if self.kv_entries.contains_key(&key) {
+                    return Err(RequestError::kv_pair_exists());
+                }
+            }
+            Precondition::MatchesVersion(version) => {
+                let current_version = self.get_version(&key);
+                if current_version != Some(version) {
+                    return Err(RequestError::version_mismatch(version, current_version));
+                }
+            }
+        }
+
+        self.kv_entries.insert(key, value);
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &ByteString, precondition: Precondition) -> Result<(), RequestError> {
+        match precondition {
+            Precondition::None => {}
+            // this condition does not really make sense for the delete operation
+            Precondition::DoesNotExist => {
+                return if self.kv_entries.contains_key(key) {
+                    Err(RequestError::kv_pair_exists())
+                } else {
+                    // nothing to do
+                    Ok(())
+                };
+            }
+            Precondition::MatchesVersion(version) => {
+                let current_version = self.get_version(key);
+                if current_version != Some(version) {
+                    return Err(RequestError::version_mismatch(version, current_version));
+                }
+            }
+        }
+
+        self.kv_entries.remove(key);
+        Ok(())
+    }
+}
+
+mod members_serde {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use restate_types::net::AdvertisedAddress;
+
+    pub fn serialize<S: Serializer>(
+        members: &HashMap<u64, AdvertisedAddress>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        members.iter().collect::<Vec<_>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<u64, AdvertisedAddress>, D::Error> {
+        Ok(Vec::<(u64, AdvertisedAddress)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+/// Forwards the logs of the raft library to tracing.
+struct TracingDrain;
+
+impl slog::Drain for TracingDrain {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(
+        &self,
+        record: &slog::Record<'_>,
+        _values: &slog::OwnedKVList,
+    ) -> Result<Self::Ok, Self::Err> {
+        match record.level() {
+            slog::Level::Critical | slog::Level::Error => {
+                error!(target: "raft", "{}", record.msg())
+            }
+            slog::Level::Warning => warn!(target: "raft", "{}", record.msg()),
+            slog::Level::Info => debug!(target: "raft", "{}", record.msg()),
+            slog::Level::Debug | slog::Level::Trace => {
+                trace!(target: "raft", "{}", record.msg())
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(version: u32) -> VersionedValue {
+        VersionedValue::new(Version::from(version), bytes::Bytes::from_static(b"value"))
+    }
+
+    #[test]
+    fn preconditions() {
+        let mut storage = KvMemoryStorage::default();
+        let key = ByteString::from_static("key");
+
+        storage
+            .put(key.clone(), value(1), Precondition::DoesNotExist)
+            .unwrap();
+        assert!(matches!(
+            storage.put(key.clone(), value(2), Precondition::DoesNotExist),
+            Err(RequestError::FailedPrecondition(_))
+        ));
+        assert!(matches!(
+            storage.put(
+                key.clone(),
+                value(2),
+                Precondition::MatchesVersion(Version::from(2))
+            ),
+            Err(RequestError::FailedPrecondition(_))
+        ));
+        storage
+            .put(
+                key.clone(),
+                value(2),
+                Precondition::MatchesVersion(Version::from(1)),
+            )
+            .unwrap();
+        assert_eq!(storage.get_version(&key), Some(Version::from(2)));
+
+        assert!(matches!(
+            storage.delete(&key, Precondition::MatchesVersion(Version::from(1))),
+            Err(RequestError::FailedPrecondition(_))
+        ));
+        storage
+            .delete(&key, Precondition::MatchesVersion(Version::from(2)))
+            .unwrap();
+        assert!(storage.get(&key).is_none());
+    }
+
+    #[test]
+    fn snapshot_roundtrip() {
+        let mut storage = KvMemoryStorage::default();
+        storage
+            .put(ByteString::from_static("key"), value(1), Precondition::None)
+            .unwrap();
+        storage
+            .members
+            .insert(1, "http://127.0.0.1:5122".parse().unwrap());
+
+        let mut buffer = BytesMut::new();
+        StorageCodec::encode(&storage, &mut buffer).unwrap();
+        let mut snapshot = Snapshot::default();
+        snapshot.data = buffer.to_vec().into();
+
+        let restored = KvMemoryStorage::from_snapshot(&snapshot).unwrap();
+        assert_eq!(
+            restored.get_version(&ByteString::from_static("key")),
+            Some(Version::from(1))
+        );
+        assert_eq!(restored.members, storage.members);
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::num::NonZeroU64;
+use std::time::Duration;
+
+use bytestring::ByteString;
+use serde::{Deserialize, Serialize};
+use test_log::test;
+
+use restate_core::metadata_store::retry_on_network_error;
+use restate_core::network::NetworkServerBuilder;
+use restate_core::{TaskCenter, TaskKind, TestCoreEnvBuilder};
+use restate_rocksdb::RocksDbManager;
+use restate_types::config::{
+    self, reset_base_temp_dir_and_retain, Configuration, MetadataStoreClientOptions,
+    MetadataStoreKind, MetadataStoreOptions, RaftOptions, RaftPeer,
+};
+use restate_types::health::HealthStatus;
+use restate_types::live::Live;
+use restate_types::net::{AdvertisedAddress, BindAddress};
+use restate_types::protobuf::common::NodeRpcStatus;
+use restate_types::retries::RetryPolicy;
+use restate_types::{flexbuffers_storage_encode_decode, Version};
+
+use crate::local::grpc::client::LocalMetadataStoreClient;
+use crate::raft::RaftMetadataStoreService;
+use crate::{MetadataStoreClient, Precondition, WriteError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Value {
+    version: Version,
+    value: String,
+}
+
+flexbuffers_storage_encode_decode!(Value);
+
+impl restate_types::Versioned for Value {
+    fn version(&self) -> Version {
+        self.version
+    }
+}
+
+/// Tests that a single member raft metadata store keeps the precondition semantics and recovers
+/// its state from snapshot and log after a restart.
+#[test(restate_core::test(flavor = "multi_thread", worker_threads = 2))]
+async fn single_member_operations_and_restart() -> anyhow::Result<()> {
+    let base_path = reset_base_temp_dir_and_retain();
+    let uds = tempfile::tempdir()?.into_path().join("metadata-rpc-server");
+
+    let opts = MetadataStoreOptions {
+        kind: MetadataStoreKind::Raft(RaftOptions {
+            raft_tick_interval: Duration::from_millis(10).into(),
+            // trim the log often to exercise restoring from a snapshot
+            log_trim_threshold: NonZeroU64::new(5).unwrap(),
+            ..RaftOptions::new(
+                NonZeroU64::new(1).unwrap(),
+                vec![RaftPeer {
+                    id: NonZeroU64::new(1).unwrap(),
+                    address: AdvertisedAddress::Uds(uds.clone()),
+                }],
+            )
+        }),
+        ..MetadataStoreOptions::default()
+    };
+    let config = Configuration {
+        metadata_store: opts.clone(),
+        ..Default::default()
+    };
+    restate_types::config::set_current_config(config.clone());
+    let config = Live::from_value(config);
+    let _env = TestCoreEnvBuilder::with_incoming_only_connector()
+        .build()
+        .await;
+    RocksDbManager::init(config.clone().map(|c| &c.common));
+
+    let client = start_metadata_store(&config, &uds).await?;
+
+    let key: ByteString = "key".into();
+    let value = Value {
+        version: Version::MIN,
+        value: "test_value".to_owned(),
+    };
+
+    assert!(client.get::<Value>(key.clone()).await?.is_none());
+    client
+        .put(key.clone(), &value, Precondition::DoesNotExist)
+        .await?;
+    assert!(matches!(
+        client
+            .put(key.clone(), &value, Precondition::DoesNotExist)
+            .await,
+        Err(WriteError::FailedPrecondition(_))
+    ));
+    assert_eq!(client.get(key.clone()).await?, Some(value.clone()));
+
+    for version in 2u32..=10 {
+        client
+            .put(
+                key.clone(),
+                &Value {
+                    version: Version::from(version),
+                    value: version.to_string(),
+                },
+                Precondition::MatchesVersion(Version::from(version - 1)),
+            )
+            .await?;
+    }
+
+    // restart the metadata store
+    TaskCenter::cancel_tasks(Some(TaskKind::MetadataStore), None).await;
+    TaskCenter::cancel_tasks(Some(TaskKind::RpcServer), None).await;
+    RocksDbManager::get().reset().await?;
+    let uds = tempfile::tempdir()?.into_path().join("metadata-rpc-server");
+    let client = start_metadata_store(&config, &uds).await?;
+
+    assert_eq!(
+        client.get_version(key.clone()).await?,
+        Some(Version::from(10))
+    );
+    client
+        .delete(key.clone(), Precondition::MatchesVersion(Version::from(10)))
+        .await?;
+    assert!(client.get::<Value>(key.clone()).await?.is_none());
+
+    // members which are unavailable are skipped
+    let unavailable_member = tempfile::tempdir()?.into_path().join("unavailable-member");
+    let client = MetadataStoreClient::new(
+        LocalMetadataStoreClient::new(
+            vec![
+                AdvertisedAddress::Uds(unavailable_member),
+                AdvertisedAddress::Uds(uds.clone()),
+            ],
+            &MetadataStoreClientOptions::default(),
+        ),
+        None,
+    );
+    client
+        .put(key.clone(), &value, Precondition::DoesNotExist)
+        .await?;
+    assert_eq!(client.get(key).await?, Some(value));
+
+    TaskCenter::shutdown_node("shutdown", 0).await;
+    std::fs::remove_dir_all(base_path)?;
+    Ok(())
+}
+
+async fn start_metadata_store(
+    config: &Live<Configuration>,
+    uds: &std::path::Path,
+) -> anyhow::Result<MetadataStoreClient> {
+    let pinned = config.pinned();
+    let MetadataStoreKind::Raft(raft_options) = &pinned.metadata_store.kind else {
+        panic!("raft metadata store expected");
+    };
+
+    let mut server_builder = NetworkServerBuilder::default();
+    let service = RaftMetadataStoreService::create(
+        HealthStatus::default(),
+        &pinned.metadata_store,
+        raft_options,
+        config.clone().map(|c| &c.metadata_store.rocksdb).boxed(),
+        &mut server_builder,
+    )
+    .await?;
+
+    let bind_address = BindAddress::Uds(uds.to_owned());
+    let rpc_server_health_status = HealthStatus::default();
+    TaskCenter::spawn(TaskKind::RpcServer, "metadata-rpc-server", {
+        let rpc_server_health_status = rpc_server_health_status.clone();
+        async move {
+            server_builder
+                .run(rpc_server_health_status, &bind_address, None)
+                .await
+        }
+    })?;
+
+    TaskCenter::spawn(TaskKind::MetadataStore, "raft-metadata-store", async move {
+        service.run().await?;
+        Ok(())
+    })?;
+
+    rpc_server_health_status
+        .wait_for_value(NodeRpcStatus::Ready)
+        .await;
+
+    let addresses = vec![AdvertisedAddress::Uds(uds.to_owned())];
+    let metadata_store_client_options = MetadataStoreClientOptions {
+        metadata_store_client: config::MetadataStoreClient::Embedded {
+            addresses: addresses.clone(),
+        },
+        ..MetadataStoreClientOptions::default()
+    };
+    let client = LocalMetadataStoreClient::new(addresses, &metadata_store_client_options);
+
+    let client = MetadataStoreClient::new(
+        client,
+        Some(metadata_store_client_options.metadata_store_client_backoff_policy),
+    );
+
+    // wait until the member has elected itself as leader
+    retry_on_network_error(
+        RetryPolicy::fixed_delay(Duration::from_millis(50), Some(100)),
+        || client.get_version("leader".into()),
+    )
+    .await?;
+
+    Ok(client)
+}
@@ -30,8 +30,8 @@ use restate_core::{
 };
 #[cfg(feature = "replicated-loglet")]
 use restate_log_server::LogServerService;
-use restate_metadata_store::local::LocalMetadataStoreService;
 use restate_metadata_store::MetadataStoreClient;
+use restate_metadata_store::MetadataStoreService;
 use restate_types::config::{CommonOptions, Configuration};
 use restate_types::errors::GenericError;
 use restate_types::health::Health;
@@ -104,7 +104,7 @@ pub enum BuildError {
 
     #[error("building metadata store failed: {0}")]
     #[code(unknown)]
-    MetadataStore(#[from] restate_metadata_store::local::BuildError),
+    MetadataStore(#[from] restate_metadata_store::BuildError),
 }
 
 pub struct Node {
@@ -115,7 +115,7 @@ pub struct Node {
     partition_routing_refresher: PartitionRoutingRefresher,
     metadata_store_client: MetadataStoreClient,
     bifrost: BifrostService,
-    metadata_store_role: Option<LocalMetadataStoreService>,
+    metadata_store_role: Option<MetadataStoreService>,
     base_role: BaseRole,
     admin_role: Option<AdminRole<GrpcConnector>>,
     worker_role: Option<WorkerRole>,
@@ -136,7 +136,7 @@ impl Node {
 
         let metadata_store_role = if config.has_role(Role::MetadataStore) {
             Some(
-                LocalMetadataStoreService::create(
+                MetadataStoreService::create(
                     health.metadata_server_status(),
                     &config.metadata_store,
                     updateable_config
@@ -151,11 +151,15 @@ impl Node {
             None
         };
 
-        let metadata_store_client = restate_metadata_store::local::create_client(
-            config.common.metadata_store_client.clone(),
-        )
-        .await
-        .map_err(BuildError::MetadataStoreClient)?;
+        let mut metadata_store_client_options = config.common.metadata_store_client.clone();
+        // the embedded metadata store is served by the node's RPC server
+        if metadata_store_client_options.metadata_store_tls.is_none() {
+            metadata_store_client_options.metadata_store_tls = config.networking.tls.clone();
+        }
+        let metadata_store_client =
+            restate_metadata_store::local::create_client(metadata_store_client_options)
+                .await
+                .map_err(BuildError::MetadataStoreClient)?;
 
         let mut router_builder = MessageRouterBuilder::default();
         let metadata_builder = MetadataBuilder::default();
@@ -184,7 +188,9 @@ impl Node {
             record_cache.clone(),
             &mut router_builder,
         );
-        let bifrost_svc = BifrostService::new().enable_local_loglet(&updateable_config);
+        let bifrost_svc = BifrostService::new()
+            .enable_local_loglet(&updateable_config)
+            .enable_object_store_loglet(&config);
 
         #[cfg(feature = "replicated-loglet")]
         let bifrost_svc = bifrost_svc.with_factory(replicated_loglet_factory);
@@ -330,6 +336,7 @@ impl Node {
         TaskCenter::spawn(TaskKind::RpcServer, "node-rpc-server", {
             let health = self.health.clone();
             let common_options = config.common.clone();
+            let tls_options = config.networking.tls.clone();
             let connection_manager = self.networking.connection_manager().clone();
             async move {
                 NetworkServer::run(
@@ -337,6 +344,7 @@ impl Node {
                     connection_manager,
                     self.server_builder,
                     common_options,
+                    tls_options,
                 )
                 .await?;
                 Ok(())
@@ -344,14 +352,10 @@ impl Node {
         })?;
 
         if let Some(metadata_store) = self.metadata_store_role {
-            TaskCenter::spawn(
-                TaskKind::MetadataStore,
-                "local-metadata-store",
-                async move {
-                    metadata_store.run().await?;
-                    Ok(())
-                },
-            )?;
+            TaskCenter::spawn(TaskKind::MetadataStore, "metadata-store", async move {
+                metadata_store.run().await?;
+                Ok(())
+            })?;
         }
 
         // Start partition routing information refresher
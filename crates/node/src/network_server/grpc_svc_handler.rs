@@ -19,6 +19,7 @@ use restate_core::network::protobuf::node_ctl_svc::node_ctl_svc_server::NodeCtlS
 use restate_core::network::protobuf::node_ctl_svc::{
     GetMetadataRequest, GetMetadataResponse, IdentResponse,
 };
+use restate_core::network::tls::PeerCertificate;
 use restate_core::network::ConnectionManager;
 use restate_core::network::{ProtocolError, TransportConnect};
 use restate_core::task_center::TaskCenterMonitoring;
@@ -145,11 +146,12 @@ where
         &self,
         request: Request<Streaming<Message>>,
     ) -> Result<Response<Self::CreateConnectionStream>, Status> {
+        let peer_certificate = request.extensions().get::<PeerCertificate>().cloned();
         let incoming = request.into_inner();
         let transformed = incoming.map(|x| x.map_err(ProtocolError::from));
         let output_stream = self
             .connections
-            .accept_incoming_connection(transformed)
+            .accept_incoming_connection_with_certificate(transformed, peer_certificate)
             .await?;
 
         // For uniformity with outbound connections, we map all responses to Ok, we never rely on
@@ -14,12 +14,14 @@ use axum::extract::State;
 use metrics_exporter_prometheus::formatting;
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
 use metrics_tracing_context::TracingContextLayer;
-use metrics_util::layers::Layer;
+use metrics_util::layers::{FanoutBuilder, Layer};
 use metrics_util::MetricKindMask;
+use opentelemetry_sdk::metrics::MetricError;
 use rocksdb::statistics::{Histogram, Ticker};
 
 use restate_core::task_center::TaskCenterMonitoring;
 use restate_rocksdb::{CfName, RocksDbManager};
+use restate_tracing_instrumentation::{OtlpMeterProviders, OtlpMetricsRecorder};
 use restate_types::config::CommonOptions;
 
 use crate::network_server::prometheus_helpers::{
@@ -184,21 +186,43 @@ const ROCKSDB_CF_PROPERTIES: &[(&str, MetricUnit)] = &[
     ("rocksdb.num-files-at-level6", MetricUnit::Count),
 ];
 
-pub(crate) fn install_global_prometheus_recorder(opts: &CommonOptions) -> PrometheusHandle {
-    let builder = PrometheusBuilder::default()
-        // Remove a metric from registry if it was not updated for that duration
-        .idle_timeout(
-            MetricKindMask::HISTOGRAM,
-            opts.histogram_inactivity_timeout.map(Into::into),
-        );
-    let recorder = builder.build_recorder();
-    let prometheus_handle = recorder.handle();
-    let recorder = TracingContextLayer::only_allow(ALLOWED_LABELS).layer(recorder);
+/// Installs the global metrics recorder which fans out to the enabled exporters. Returns the
+/// handle for rendering Prometheus metrics if the Prometheus exporter is enabled and the meter
+/// provider pushing metrics to the OTLP collector if an OTLP metrics endpoint is configured.
+pub(crate) fn install_global_metrics_recorder(
+    opts: &CommonOptions,
+) -> Result<(Option<PrometheusHandle>, Option<OtlpMeterProviders>), MetricError> {
+    let mut fanout = FanoutBuilder::default();
+
+    let prometheus_handle = if !opts.disable_prometheus {
+        let builder = PrometheusBuilder::default()
+            // Remove a metric from registry if it was not updated for that duration
+            .idle_timeout(
+                MetricKindMask::HISTOGRAM,
+                opts.histogram_inactivity_timeout.map(Into::into),
+            );
+        let recorder = builder.build_recorder();
+        let prometheus_handle = recorder.handle();
+        fanout = fanout.add_recorder(recorder);
+        Some(prometheus_handle)
+    } else {
+        None
+    };
+
+    let meter_provider = if let Some(recorder) = OtlpMetricsRecorder::create(opts)? {
+        let meter_provider = recorder.meter_providers();
+        fanout = fanout.add_recorder(recorder);
+        Some(meter_provider)
+    } else {
+        None
+    };
+
+    let recorder = TracingContextLayer::only_allow(ALLOWED_LABELS).layer(fanout.build());
 
     // We do not expect this to fail except due to atomic CAS failure
     // which should never happen in practice.
     metrics::set_global_recorder(recorder).expect("no global metrics recorder should be installed");
-    prometheus_handle
+    Ok((prometheus_handle, meter_provider))
 }
 
 // -- Direct HTTP Handlers --
@@ -15,14 +15,14 @@ use tokio::time::MissedTickBehavior;
 use tonic::codec::CompressionEncoding;
 use tracing::{debug, trace};
 
-use crate::network_server::metrics::{install_global_prometheus_recorder, render_metrics};
+use crate::network_server::metrics::{install_global_metrics_recorder, render_metrics};
 use crate::network_server::state::NodeCtrlHandlerStateBuilder;
 use restate_core::network::protobuf::core_node_svc::core_node_svc_server::CoreNodeSvcServer;
 use restate_core::network::protobuf::node_ctl_svc::node_ctl_svc_server::NodeCtlSvcServer;
 use restate_core::network::tonic_service_filter::{TonicServiceFilter, WaitForReady};
 use restate_core::network::{ConnectionManager, NetworkServerBuilder, TransportConnect};
 use restate_core::{cancellation_watcher, TaskCenter, TaskKind};
-use restate_types::config::CommonOptions;
+use restate_types::config::{CommonOptions, NetworkingTlsOptions};
 use restate_types::health::Health;
 use restate_types::protobuf::common::NodeStatus;
 
@@ -37,14 +37,45 @@ impl NetworkServer {
         connection_manager: ConnectionManager<T>,
         mut server_builder: NetworkServerBuilder,
         options: CommonOptions,
+        tls_options: Option<NetworkingTlsOptions>,
     ) -> Result<(), anyhow::Error> {
         // Configure Metric Exporter
         let mut state_builder = NodeCtrlHandlerStateBuilder::default();
         state_builder.task_center(TaskCenter::current());
 
-        if !options.disable_prometheus {
-            let prometheus_handle = install_global_prometheus_recorder(&options);
+        let (prometheus_handle, meter_provider) = if !options.disable_prometheus
+            || options.otlp_metrics.otlp_metrics_endpoint.is_some()
+        {
+            install_global_metrics_recorder(&options)?
+        } else {
+            (None, None)
+        };
+
+        if let Some(meter_provider) = meter_provider {
+            TaskCenter::spawn_child(
+                TaskKind::SystemService,
+                "otlp-metrics-exporter",
+                async move {
+                    debug!("OTLP metrics exporter started");
+                    cancellation_watcher().await;
+                    // Push the last metrics before shutting down. The shutdown blocks until the
+                    // export finished, hence it must not run on the async runtime.
+                    match tokio::task::spawn_blocking(move || meter_provider.shutdown()).await {
+                        Ok(Ok(())) => {}
+                        Ok(Err(err)) => {
+                            debug!("Failed to shut down the OTLP metrics exporter: {err}")
+                        }
+                        Err(err) => {
+                            debug!("Failed to shut down the OTLP metrics exporter: {err}")
+                        }
+                    }
+                    debug!("OTLP metrics exporter stopped");
+                    Ok(())
+                },
+            )?;
+        }
 
+        if let Some(prometheus_handle) = prometheus_handle {
             TaskCenter::spawn_child(TaskKind::SystemService, "prometheus-metrics-upkeep", {
                 let prometheus_handle = prometheus_handle.clone();
                 async move {
@@ -122,7 +153,11 @@ impl NetworkServer {
         );
 
         server_builder
-            .run(node_rpc_health, &options.bind_address.unwrap())
+            .run(
+                node_rpc_health,
+                &options.bind_address.unwrap(),
+                tls_options.as_ref(),
+            )
             .await?;
 
         Ok(())
@@ -108,6 +108,7 @@ impl<'a> DeduplicationTable for PartitionStoreTransaction<'a> {
         let key = DeduplicationKey::default()
             .partition_id(self.partition_id().into())
             .producer_id(producer_id);
-        self.put_kv(key, dedup_sequence_number);
+        let result = self.put_kv(key, dedup_sequence_number);
+        self.record_write(result);
     }
 }
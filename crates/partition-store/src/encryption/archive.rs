@@ -0,0 +1,241 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use bytes::{BufMut, BytesMut};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+use super::{EncryptionError, KeyProvider, WrappedKey, KEY_LEN};
+
+/// Size of the plaintext chunks in which snapshot files are encrypted
+const DEFAULT_CHUNK_SIZE: u32 = 1024 * 1024;
+
+/// Describes how the files of a snapshot archive are encrypted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveEncryption {
+    /// The key which encrypts the files of the snapshot
+    pub archive_key: WrappedKey,
+    /// Files are encrypted in chunks of this many plaintext bytes
+    pub chunk_size: u32,
+}
+
+/// Encrypts the files of a snapshot archive.
+///
+/// Every snapshot has its own archive key. Files are split into chunks which are encrypted
+/// individually, so that they can be streamed. The nonce of a chunk is derived from the index of
+/// the file within the snapshot and the index of the chunk within the file. The file name and
+/// whether the chunk is the file's last one are authenticated, so chunks can neither be
+/// reordered, moved between files nor truncated.
+pub struct ArchiveCipher {
+    key: LessSafeKey,
+    chunk_size: u32,
+}
+
+impl ArchiveCipher {
+    /// Creates a new archive key. Returns the cipher and the description to be stored in the
+    /// snapshot metadata.
+    pub fn create(
+        provider: &dyn KeyProvider,
+    ) -> Result<(Self, ArchiveEncryption), EncryptionError> {
+        let mut key = [0; KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut key)
+            .map_err(|_| EncryptionError::Random)?;
+
+        let encryption = ArchiveEncryption {
+            archive_key: provider.wrap_key(&key)?,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        };
+        Ok((Self::new(&key, encryption.chunk_size)?, encryption))
+    }
+
+    pub fn open(
+        provider: &dyn KeyProvider,
+        encryption: &ArchiveEncryption,
+    ) -> Result<Self, EncryptionError> {
+        let key = provider.unwrap_key(&encryption.archive_key)?;
+        Self::new(&key, encryption.chunk_size)
+    }
+
+    fn new(key: &[u8], chunk_size: u32) -> Result<Self, EncryptionError> {
+        let key = UnboundKey::new(&AES_256_GCM, key)
+            .map_err(|_| EncryptionError::InvalidKey("archive key".to_owned()))?;
+        Ok(Self {
+            key: LessSafeKey::new(key),
+            chunk_size,
+        })
+    }
+
+    /// Size of a plaintext chunk
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size as usize
+    }
+
+    /// Size of an encrypted chunk
+    pub fn encrypted_chunk_size(&self) -> usize {
+        self.chunk_size() + AES_256_GCM.tag_len()
+    }
+
+    /// Size of the encrypted file. Empty files consist of a single empty chunk.
+    pub fn encrypted_file_size(&self, plaintext_size: u64) -> u64 {
+        plaintext_size + self.num_chunks(plaintext_size) * AES_256_GCM.tag_len() as u64
+    }
+
+    fn num_chunks(&self, plaintext_size: u64) -> u64 {
+        plaintext_size.div_ceil(self.chunk_size as u64).max(1)
+    }
+
+    /// Encrypts the consecutive chunks in `plaintext`, starting with the chunk at
+    /// `first_chunk_index`, and appends them to `out`. All but the last chunk must be complete.
+    pub fn encrypt_chunks(
+        &self,
+        file_index: u32,
+        file_name: &str,
+        file_size: u64,
+        first_chunk_index: u64,
+        plaintext: &[u8],
+        out: &mut BytesMut,
+    ) -> Result<(), EncryptionError> {
+        let last_chunk_index = self.num_chunks(file_size) - 1;
+        let mut chunks: Vec<&[u8]> = plaintext.chunks(self.chunk_size()).collect();
+        if file_size == 0 {
+            chunks.push(&[]);
+        }
+
+        for (chunk_index, chunk) in (first_chunk_index..).zip(chunks) {
+            let start = out.len();
+            out.put_slice(chunk);
+            let tag = self
+                .key
+                .seal_in_place_separate_tag(
+                    chunk_nonce(file_index, chunk_index),
+                    chunk_aad(file_name, chunk_index == last_chunk_index),
+                    &mut out[start..],
+                )
+                .map_err(|_| EncryptionError::Encrypt)?;
+            out.put_slice(tag.as_ref());
+        }
+
+        Ok(())
+    }
+
+    /// Decrypts a single encrypted chunk in place and returns the plaintext length.
+    pub fn decrypt_chunk(
+        &self,
+        file_index: u32,
+        file_name: &str,
+        file_size: u64,
+        chunk_index: u64,
+        chunk: &mut [u8],
+    ) -> Result<usize, EncryptionError> {
+        let last_chunk_index = self.num_chunks(file_size) - 1;
+        if chunk_index > last_chunk_index {
+            return Err(EncryptionError::Decrypt);
+        }
+
+        self.key
+            .open_in_place(
+                chunk_nonce(file_index, chunk_index),
+                chunk_aad(file_name, chunk_index == last_chunk_index),
+                chunk,
+            )
+            .map(|plaintext| plaintext.len())
+            .map_err(|_| EncryptionError::Decrypt)
+    }
+}
+
+fn chunk_nonce(file_index: u32, chunk_index: u64) -> Nonce {
+    let mut nonce = [0; NONCE_LEN];
+    nonce[..4].copy_from_slice(&file_index.to_be_bytes());
+    nonce[4..].copy_from_slice(&chunk_index.to_be_bytes());
+    Nonce::assume_unique_for_key(nonce)
+}
+
+fn chunk_aad(file_name: &str, last: bool) -> Aad<Vec<u8>> {
+    let mut aad = Vec::with_capacity(file_name.len() + 1);
+    aad.extend_from_slice(file_name.as_bytes());
+    aad.push(u8::from(last));
+    Aad::from(aad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::encryption::LocalKeyProvider;
+
+    fn provider(dir: &std::path::Path) -> LocalKeyProvider {
+        LocalKeyProvider::from_kms_directory(dir, None).unwrap()
+    }
+
+    fn decrypt_file(
+        cipher: &ArchiveCipher,
+        file_name: &str,
+        file_size: u64,
+        mut encrypted: BytesMut,
+    ) -> Result<Vec<u8>, EncryptionError> {
+        let mut plaintext = Vec::new();
+        for (chunk_index, chunk) in encrypted
+            .chunks_mut(cipher.encrypted_chunk_size())
+            .enumerate()
+        {
+            let len = cipher.decrypt_chunk(0, file_name, file_size, chunk_index as u64, chunk)?;
+            plaintext.extend_from_slice(&chunk[..len]);
+        }
+        Ok(plaintext)
+    }
+
+    #[test]
+    fn encrypt_decrypt_file() -> googletest::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let provider = provider(dir.path());
+        let (cipher, encryption) = ArchiveCipher::create(&provider)?;
+        let cipher = ArchiveCipher {
+            chunk_size: 4,
+            ..cipher
+        };
+
+        let data = b"0123456789";
+        let mut encrypted = BytesMut::new();
+        // in two parts, as the snapshot upload does
+        cipher.encrypt_chunks(0, "000.sst", 10, 0, &data[..8], &mut encrypted)?;
+        cipher.encrypt_chunks(0, "000.sst", 10, 2, &data[8..], &mut encrypted)?;
+        assert_eq!(cipher.encrypted_file_size(10), encrypted.len() as u64);
+
+        let reopened = ArchiveCipher::open(&provider, &encryption)?;
+        assert_eq!(DEFAULT_CHUNK_SIZE as usize, reopened.chunk_size());
+
+        assert_eq!(
+            data.as_slice(),
+            decrypt_file(&cipher, "000.sst", 10, encrypted.clone())?
+        );
+        // bound to the file name
+        assert!(decrypt_file(&cipher, "001.sst", 10, encrypted.clone()).is_err());
+        // truncation is detected even if the file size is forged
+        encrypted.truncate(cipher.encrypted_chunk_size() * 2);
+        assert!(decrypt_file(&cipher, "000.sst", 8, encrypted).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn empty_file() -> googletest::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (cipher, _) = ArchiveCipher::create(&provider(dir.path()))?;
+
+        let mut encrypted = BytesMut::new();
+        cipher.encrypt_chunks(3, "empty", 0, 0, &[], &mut encrypted)?;
+        assert_eq!(cipher.encrypted_file_size(0), encrypted.len() as u64);
+        assert_eq!(0, cipher.decrypt_chunk(3, "empty", 0, 0, &mut encrypted)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,284 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use tracing::info;
+
+use restate_types::config::{EncryptionOptions, KeySource};
+use restate_types::time::MillisSinceEpoch;
+
+use super::{EncryptionError, WrappedKey, KEY_LEN};
+
+/// Source of the key-encryption keys which wrap the data keys.
+///
+/// Key-encryption keys never leave the provider, callers only get to see wrapped keys.
+pub trait KeyProvider: Send + Sync + fmt::Debug + 'static {
+    /// Id of the key-encryption key which wraps new keys
+    fn active_key_id(&self) -> &str;
+
+    /// Wraps the key with the active key-encryption key.
+    fn wrap_key(&self, key: &[u8]) -> Result<WrappedKey, EncryptionError>;
+
+    fn unwrap_key(&self, wrapped: &WrappedKey) -> Result<Vec<u8>, EncryptionError>;
+}
+
+/// Creates the key provider for the configured key source.
+pub fn create_key_provider(
+    options: &EncryptionOptions,
+) -> Result<Arc<dyn KeyProvider>, EncryptionError> {
+    let provider = match &options.key_source {
+        KeySource::Keyfile { path } => {
+            LocalKeyProvider::from_keyfile(path, options.active_key_id.as_deref())?
+        }
+        KeySource::LocalKms { path } => {
+            LocalKeyProvider::from_kms_directory(path, options.active_key_id.as_deref())?
+        }
+    };
+    Ok(Arc::new(provider))
+}
+
+/// Holds the key-encryption keys in memory and wraps keys with AES-256-GCM.
+pub struct LocalKeyProvider {
+    keys: HashMap<String, LessSafeKey>,
+    active_key_id: String,
+    rng: SystemRandom,
+}
+
+impl fmt::Debug for LocalKeyProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalKeyProvider")
+            .field("keys", &self.keys.keys())
+            .field("active_key_id", &self.active_key_id)
+            .finish()
+    }
+}
+
+impl LocalKeyProvider {
+    /// Reads the keys from a keyfile. The last key of the file is the active one, unless
+    /// configured otherwise.
+    pub fn from_keyfile(path: &Path, active_key_id: Option<&str>) -> Result<Self, EncryptionError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| EncryptionError::Keyfile(path.display().to_string(), err.to_string()))?;
+
+        let mut keys = Vec::new();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key_id, key)) = line.split_once(':') else {
+                return Err(EncryptionError::Keyfile(
+                    path.display().to_string(),
+                    "expected lines in the format '<key-id>:<hex encoded key>'".to_owned(),
+                ));
+            };
+            let key = hex::decode(key.trim()).map_err(|err| {
+                EncryptionError::Keyfile(path.display().to_string(), err.to_string())
+            })?;
+            keys.push((key_id.trim().to_owned(), key));
+        }
+
+        Self::new(keys, active_key_id)
+    }
+
+    /// Stand-in for an external key management service which keeps its keys as
+    /// `<key-id>.key` files in the given directory. Creates a first key if there is none.
+    pub fn from_kms_directory(
+        path: &Path,
+        active_key_id: Option<&str>,
+    ) -> Result<Self, EncryptionError> {
+        let kms_error = |err: std::io::Error| {
+            EncryptionError::Keyfile(path.display().to_string(), err.to_string())
+        };
+        std::fs::create_dir_all(path).map_err(kms_error)?;
+
+        let mut keys = Vec::new();
+        for entry in std::fs::read_dir(path).map_err(kms_error)? {
+            let entry = entry.map_err(kms_error)?;
+            let file_name = entry.file_name();
+            let Some(key_id) = file_name
+                .to_str()
+                .and_then(|name| name.strip_suffix(".key"))
+            else {
+                continue;
+            };
+            keys.push((
+                key_id.to_owned(),
+                std::fs::read(entry.path()).map_err(kms_error)?,
+            ));
+        }
+
+        if keys.is_empty() {
+            // Key ids sort in the order of their creation
+            let key_id = format!("kek-{:020}", MillisSinceEpoch::now().as_u64());
+            let mut key = vec![0; KEY_LEN];
+            SystemRandom::new()
+                .fill(&mut key)
+                .map_err(|_| EncryptionError::Random)?;
+            std::fs::write(path.join(format!("{key_id}.key")), &key).map_err(kms_error)?;
+            info!(%key_id, "Created key-encryption key in the local key management service");
+            keys.push((key_id, key));
+        }
+        keys.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Self::new(keys, active_key_id)
+    }
+
+    /// The last key is the active one if no active key id is given.
+    fn new(
+        keys: Vec<(String, Vec<u8>)>,
+        active_key_id: Option<&str>,
+    ) -> Result<Self, EncryptionError> {
+        let Some((last_key_id, _)) = keys.last() else {
+            return Err(EncryptionError::NoKeys);
+        };
+        let active_key_id = active_key_id.unwrap_or(last_key_id).to_owned();
+
+        let keys = keys
+            .into_iter()
+            .map(|(key_id, key)| {
+                let key = UnboundKey::new(&AES_256_GCM, &key)
+                    .map_err(|_| EncryptionError::InvalidKey(key_id.clone()))?;
+                Ok((key_id, LessSafeKey::new(key)))
+            })
+            .collect::<Result<HashMap<_, _>, EncryptionError>>()?;
+
+        if !keys.contains_key(&active_key_id) {
+            return Err(EncryptionError::UnknownKey(active_key_id));
+        }
+
+        Ok(Self {
+            keys,
+            active_key_id,
+            rng: SystemRandom::new(),
+        })
+    }
+}
+
+impl KeyProvider for LocalKeyProvider {
+    fn active_key_id(&self) -> &str {
+        &self.active_key_id
+    }
+
+    fn wrap_key(&self, key: &[u8]) -> Result<WrappedKey, EncryptionError> {
+        let key_encryption_key = &self.keys[&self.active_key_id];
+
+        let mut nonce = [0; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| EncryptionError::Random)?;
+
+        // nonce + ciphertext + tag
+        let mut ciphertext = Vec::with_capacity(NONCE_LEN + key.len() + AES_256_GCM.tag_len());
+        ciphertext.extend_from_slice(&nonce);
+        ciphertext.extend_from_slice(key);
+        let tag = key_encryption_key
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(self.active_key_id.as_bytes()),
+                &mut ciphertext[NONCE_LEN..],
+            )
+            .map_err(|_| EncryptionError::Encrypt)?;
+        ciphertext.extend_from_slice(tag.as_ref());
+
+        Ok(WrappedKey {
+            key_encryption_key: self.active_key_id.clone(),
+            ciphertext,
+        })
+    }
+
+    fn unwrap_key(&self, wrapped: &WrappedKey) -> Result<Vec<u8>, EncryptionError> {
+        let key_encryption_key = self
+            .keys
+            .get(&wrapped.key_encryption_key)
+            .ok_or_else(|| EncryptionError::UnknownKey(wrapped.key_encryption_key.clone()))?;
+
+        if wrapped.ciphertext.len() < NONCE_LEN {
+            return Err(EncryptionError::Decrypt);
+        }
+        let (nonce, ciphertext) = wrapped.ciphertext.split_at(NONCE_LEN);
+        let nonce =
+            Nonce::try_assume_unique_for_key(nonce).map_err(|_| EncryptionError::Decrypt)?;
+
+        let mut key = ciphertext.to_vec();
+        let len = key_encryption_key
+            .open_in_place(
+                nonce,
+                Aad::from(wrapped.key_encryption_key.as_bytes()),
+                &mut key,
+            )
+            .map_err(|_| EncryptionError::Decrypt)?
+            .len();
+        key.truncate(len);
+        Ok(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_and_unwrap_with_rotated_keyfile() -> googletest::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let keyfile = dir.path().join("keys");
+        std::fs::write(
+            &keyfile,
+            format!("# old key\nkey-1:{}\n", "11".repeat(KEY_LEN)),
+        )?;
+
+        let provider = LocalKeyProvider::from_keyfile(&keyfile, None)?;
+        let wrapped = provider.wrap_key(b"data key")?;
+        assert_eq!("key-1", wrapped.key_encryption_key);
+        assert_ne!(b"data key".as_slice(), &wrapped.ciphertext[NONCE_LEN..]);
+
+        // rotate
+        std::fs::write(
+            &keyfile,
+            format!(
+                "key-1:{}\nkey-2:{}\n",
+                "11".repeat(KEY_LEN),
+                "22".repeat(KEY_LEN)
+            ),
+        )?;
+        let provider = LocalKeyProvider::from_keyfile(&keyfile, None)?;
+        assert_eq!("key-2", provider.active_key_id());
+        assert_eq!(b"data key".as_slice(), provider.unwrap_key(&wrapped)?);
+
+        let mut tampered = wrapped.clone();
+        tampered.key_encryption_key = "key-2".to_owned();
+        assert!(matches!(
+            provider.unwrap_key(&tampered),
+            Err(EncryptionError::Decrypt)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn local_kms_creates_first_key() -> googletest::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let provider = LocalKeyProvider::from_kms_directory(dir.path(), None)?;
+        let wrapped = provider.wrap_key(b"data key")?;
+
+        // the key survives restarts
+        let provider = LocalKeyProvider::from_kms_directory(dir.path(), None)?;
+        assert_eq!(wrapped.key_encryption_key, provider.active_key_id());
+        assert_eq!(b"data key".as_slice(), provider.unwrap_key(&wrapped)?);
+
+        Ok(())
+    }
+}
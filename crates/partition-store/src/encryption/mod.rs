@@ -0,0 +1,625 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Envelope encryption of the partition store values and snapshots.
+//!
+//! Values are encrypted with AES-256-GCM using data keys. The data keys are wrapped with a
+//! key-encryption key of a [`KeyProvider`] and only the wrapped data keys are persisted, in the
+//! keyring file next to the database and in the metadata of snapshots. Rotating the
+//! key-encryption key therefore only requires re-wrapping the data keys.
+//!
+//! An encrypted value has the layout `[version: u8][data key id: u64][nonce][ciphertext][tag]`.
+//! The key of the value is used as associated data, so that values can't be swapped.
+//!
+//! Nonces are random, so a data key must not encrypt more than 2^32 values (NIST SP 800-38D),
+//! otherwise nonce collisions become likely. The number of encryptions is counted per data key
+//! and persisted in the keyring in reservations, a data key which reaches the limit is replaced
+//! by a new one.
+
+mod archive;
+mod key_provider;
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+use bytes::{BufMut, BytesMut};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_with::hex::Hex;
+use serde_with::serde_as;
+use tracing::info;
+
+use restate_types::config::EncryptionOptions;
+
+use crate::keys::KeyKind;
+
+pub use archive::{ArchiveCipher, ArchiveEncryption};
+pub use key_provider::{create_key_provider, KeyProvider, LocalKeyProvider};
+
+const KEY_LEN: usize = 32;
+const FORMAT_VERSION: u8 = 1;
+const HEADER_LEN: usize = 1 + size_of::<u64>() + NONCE_LEN;
+const KEYRING_FILE_NAME: &str = "encryption-keyring.json";
+/// Limit of encryptions with random nonces per data key
+const MAX_ENCRYPTIONS_PER_DATA_KEY: u64 = 1 << 32;
+/// Number of encryptions which are reserved at once by persisting them in the keyring
+const ENCRYPTIONS_RESERVATION: u64 = 1 << 16;
+
+/// Marks a column family whose values are all encrypted. The key is padded to the length of the
+/// key prefix.
+pub(crate) const ENCRYPTED_MARKER_KEY: [u8; 10] = {
+    let kind = KeyKind::Encryption.as_bytes();
+    [kind[0], kind[1], 0, 0, 0, 0, 0, 0, 0, 0]
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptionError {
+    #[error("failed reading key-encryption keys from '{0}': {1}")]
+    Keyfile(String, String),
+    #[error("no key-encryption keys found")]
+    NoKeys,
+    #[error("invalid key '{0}', expected a 256 bit key")]
+    InvalidKey(String),
+    #[error("unknown key '{0}'")]
+    UnknownKey(String),
+    #[error("unknown data key {0:016x}")]
+    UnknownDataKey(u64),
+    #[error("unsupported encryption format version {0}")]
+    UnsupportedVersion(u8),
+    #[error("failed generating random bytes")]
+    Random,
+    #[error("failed encrypting")]
+    Encrypt,
+    #[error("failed decrypting, the data is corrupted or was encrypted with a different key")]
+    Decrypt,
+    #[error("the data is encrypted but encryption at rest is not configured")]
+    NotConfigured,
+    #[error("failed accessing keyring '{0}': {1}")]
+    Keyring(PathBuf, String),
+}
+
+/// A key wrapped by a key-encryption key
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WrappedKey {
+    /// Id of the key-encryption key
+    pub key_encryption_key: String,
+    #[serde_as(as = "Hex")]
+    pub ciphertext: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WrappedDataKey {
+    pub id: u64,
+    #[serde(flatten)]
+    pub key: WrappedKey,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct KeyringFile {
+    active: Option<u64>,
+    /// Encryptions which might have been done with the active data key
+    #[serde(default)]
+    reserved_encryptions: u64,
+    keys: Vec<WrappedDataKey>,
+}
+
+struct DataKey {
+    key: LessSafeKey,
+    wrapped: WrappedDataKey,
+}
+
+struct Keyring {
+    active: u64,
+    keys: HashMap<u64, DataKey>,
+    /// Encryptions done with the active data key. Can exceed the reservation, the values
+    /// beyond it have not been encrypted.
+    encryptions: AtomicU64,
+    /// Encryptions with the active data key which are persisted in the keyring file
+    reserved_encryptions: u64,
+}
+
+/// Encrypts and decrypts the values of the partition store.
+///
+/// The data keys are shared by all partition stores of the node. They are persisted in a keyring
+/// file and handed out with snapshots, so that other nodes can decrypt the snapshots' values.
+pub struct ValueCipher {
+    provider: Arc<dyn KeyProvider>,
+    keyring_path: PathBuf,
+    keyring: RwLock<Keyring>,
+    rng: SystemRandom,
+    max_encryptions_per_data_key: u64,
+}
+
+impl std::fmt::Debug for ValueCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValueCipher")
+            .field("provider", &self.provider)
+            .field("keyring_path", &self.keyring_path)
+            .finish()
+    }
+}
+
+impl ValueCipher {
+    /// Creates the cipher for the configured key source, keeping the keyring in `data_dir`.
+    pub fn create(options: &EncryptionOptions, data_dir: &Path) -> Result<Self, EncryptionError> {
+        Self::open(
+            create_key_provider(options)?,
+            data_dir.join(KEYRING_FILE_NAME),
+        )
+    }
+
+    /// Loads the keyring. If the key-encryption key has been rotated, all data keys are
+    /// re-wrapped with the new key-encryption key and a new data key is created for new writes.
+    pub fn open(
+        provider: Arc<dyn KeyProvider>,
+        keyring_path: PathBuf,
+    ) -> Result<Self, EncryptionError> {
+        Self::open_with_limit(provider, keyring_path, MAX_ENCRYPTIONS_PER_DATA_KEY)
+    }
+
+    fn open_with_limit(
+        provider: Arc<dyn KeyProvider>,
+        keyring_path: PathBuf,
+        max_encryptions_per_data_key: u64,
+    ) -> Result<Self, EncryptionError> {
+        let keyring_file: KeyringFile = match std::fs::read(&keyring_path) {
+            Ok(content) => serde_json::from_slice(&content)
+                .map_err(|err| EncryptionError::Keyring(keyring_path.clone(), err.to_string()))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => KeyringFile::default(),
+            Err(err) => return Err(EncryptionError::Keyring(keyring_path, err.to_string())),
+        };
+
+        let mut keys = HashMap::with_capacity(keyring_file.keys.len() + 1);
+        let mut rewrapped = false;
+        for mut wrapped in keyring_file.keys {
+            let key = provider.unwrap_key(&wrapped.key)?;
+            if wrapped.key.key_encryption_key != provider.active_key_id() {
+                wrapped.key = provider.wrap_key(&key)?;
+                rewrapped = true;
+            }
+            keys.insert(wrapped.id, DataKey::new(&key, wrapped)?);
+        }
+
+        // A rotated key-encryption key also rotates the data key of new writes
+        let active = keyring_file
+            .active
+            .filter(|active| !rewrapped && keys.contains_key(active));
+
+        // All reserved encryptions might have been used before the restart
+        let reserved_encryptions = keyring_file.reserved_encryptions;
+        let cipher = Self {
+            provider,
+            keyring_path,
+            keyring: RwLock::new(Keyring {
+                active: active.unwrap_or_default(),
+                keys,
+                encryptions: AtomicU64::new(reserved_encryptions),
+                reserved_encryptions,
+            }),
+            rng: SystemRandom::new(),
+            max_encryptions_per_data_key,
+        };
+
+        if active.is_none() {
+            let new_key = cipher.generate_data_key()?;
+            cipher.install_data_key(&mut cipher.keyring.write().unwrap(), new_key)?;
+        }
+
+        Ok(cipher)
+    }
+
+    pub fn key_provider(&self) -> &Arc<dyn KeyProvider> {
+        &self.provider
+    }
+
+    /// The wrapped data keys, to be handed out with snapshots
+    pub fn export_data_keys(&self) -> Vec<WrappedDataKey> {
+        let keyring = self.keyring.read().unwrap();
+        let mut keys: Vec<_> = keyring
+            .keys
+            .values()
+            .map(|key| key.wrapped.clone())
+            .collect();
+        keys.sort_by_key(|key| key.id);
+        keys
+    }
+
+    /// Adds the data keys of a snapshot which was created by another node to the keyring.
+    pub fn import_data_keys(&self, data_keys: &[WrappedDataKey]) -> Result<(), EncryptionError> {
+        let mut keyring = self.keyring.write().unwrap();
+        let mut imported = false;
+        for wrapped in data_keys {
+            if keyring.keys.contains_key(&wrapped.id) {
+                continue;
+            }
+            let key = self.provider.unwrap_key(&wrapped.key)?;
+            let wrapped = WrappedDataKey {
+                id: wrapped.id,
+                key: self.provider.wrap_key(&key)?,
+            };
+            keyring
+                .keys
+                .insert(wrapped.id, DataKey::new(&key, wrapped)?);
+            imported = true;
+        }
+
+        if imported {
+            self.persist(&keyring)?;
+        }
+        Ok(())
+    }
+
+    /// Encrypts the value and appends it to `out`. Fails if a new data key is needed and can't
+    /// be persisted.
+    pub fn encrypt(
+        &self,
+        key: &[u8],
+        value: &[u8],
+        out: &mut BytesMut,
+    ) -> Result<(), EncryptionError> {
+        let mut nonce = [0; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| EncryptionError::Random)?;
+
+        let keyring = self.reserve_encryption()?;
+        let data_key = &keyring.keys[&keyring.active];
+
+        out.reserve(HEADER_LEN + value.len() + AES_256_GCM.tag_len());
+        out.put_u8(FORMAT_VERSION);
+        out.put_u64(keyring.active);
+        out.put_slice(&nonce);
+        let start = out.len();
+        out.put_slice(value);
+        let tag = data_key
+            .key
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(key),
+                &mut out[start..],
+            )
+            .map_err(|_| EncryptionError::Encrypt)?;
+        out.put_slice(tag.as_ref());
+        Ok(())
+    }
+
+    /// Counts an encryption with the active data key. Reserves more encryptions if the
+    /// reservation is used up, or rotates the data key once it reached its limit.
+    fn reserve_encryption(&self) -> Result<RwLockReadGuard<'_, Keyring>, EncryptionError> {
+        loop {
+            let keyring = self.keyring.read().unwrap();
+            if keyring.encryptions.fetch_add(1, Ordering::Relaxed) < keyring.reserved_encryptions {
+                return Ok(keyring);
+            }
+            drop(keyring);
+
+            let mut keyring = self.keyring.write().unwrap();
+            let reserved_encryptions = keyring.reserved_encryptions;
+            if keyring.encryptions.load(Ordering::Relaxed) < reserved_encryptions {
+                // reserved concurrently
+                continue;
+            }
+
+            if reserved_encryptions >= self.max_encryptions_per_data_key {
+                // Wrapping the new key can call out to a key management service, don't block
+                // all encryptions and decryptions meanwhile
+                let previous = keyring.active;
+                drop(keyring);
+                let new_key = self.generate_data_key()?;
+
+                let mut keyring = self.keyring.write().unwrap();
+                if keyring.active != previous
+                    || keyring.encryptions.load(Ordering::Relaxed) < keyring.reserved_encryptions
+                {
+                    // rotated or reserved concurrently
+                    continue;
+                }
+                let id = self.install_data_key(&mut keyring, new_key)?;
+                info!(
+                    data_key = format!("{id:016x}"),
+                    previous_data_key = format!("{previous:016x}"),
+                    "Rotated data key which reached its encryption limit"
+                );
+            } else {
+                keyring.reserved_encryptions = self.next_reservation(reserved_encryptions);
+                *keyring.encryptions.get_mut() = reserved_encryptions;
+                self.persist(&keyring)?;
+            }
+        }
+    }
+
+    fn next_reservation(&self, reserved_encryptions: u64) -> u64 {
+        reserved_encryptions
+            .saturating_add(ENCRYPTIONS_RESERVATION)
+            .min(self.max_encryptions_per_data_key)
+    }
+
+    pub fn decrypt(&self, key: &[u8], value: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        if value.len() < HEADER_LEN {
+            return Err(EncryptionError::Decrypt);
+        }
+        if value[0] != FORMAT_VERSION {
+            return Err(EncryptionError::UnsupportedVersion(value[0]));
+        }
+        let data_key_id = u64::from_be_bytes(value[1..9].try_into().expect("8 bytes"));
+        let nonce = Nonce::try_assume_unique_for_key(&value[9..HEADER_LEN])
+            .map_err(|_| EncryptionError::Decrypt)?;
+
+        let keyring = self.keyring.read().unwrap();
+        let data_key = keyring
+            .keys
+            .get(&data_key_id)
+            .ok_or(EncryptionError::UnknownDataKey(data_key_id))?;
+
+        let mut plaintext = value[HEADER_LEN..].to_vec();
+        let len = data_key
+            .key
+            .open_in_place(nonce, Aad::from(key), &mut plaintext)
+            .map_err(|_| EncryptionError::Decrypt)?
+            .len();
+        plaintext.truncate(len);
+        Ok(plaintext)
+    }
+
+    /// Whether the value has the header of a value encrypted with one of the keyring's data keys
+    pub(crate) fn is_encrypted(&self, value: &[u8]) -> bool {
+        if value.len() < HEADER_LEN + AES_256_GCM.tag_len() || value[0] != FORMAT_VERSION {
+            return false;
+        }
+        let data_key_id = u64::from_be_bytes(value[1..9].try_into().expect("8 bytes"));
+        self.keyring.read().unwrap().keys.contains_key(&data_key_id)
+    }
+
+    /// Creates a new data key, wrapped with the active key-encryption key.
+    fn generate_data_key(&self) -> Result<([u8; KEY_LEN], WrappedKey), EncryptionError> {
+        let mut key = [0; KEY_LEN];
+        self.rng
+            .fill(&mut key)
+            .map_err(|_| EncryptionError::Random)?;
+        let wrapped = self.provider.wrap_key(&key)?;
+        Ok((key, wrapped))
+    }
+
+    /// Adds the new data key to the keyring and makes it the active one.
+    fn install_data_key(
+        &self,
+        keyring: &mut Keyring,
+        (key, wrapped): ([u8; KEY_LEN], WrappedKey),
+    ) -> Result<u64, EncryptionError> {
+        let id = loop {
+            let mut id = [0; size_of::<u64>()];
+            self.rng
+                .fill(&mut id)
+                .map_err(|_| EncryptionError::Random)?;
+            let id = u64::from_be_bytes(id);
+            if !keyring.keys.contains_key(&id) {
+                break id;
+            }
+        };
+
+        let wrapped = WrappedDataKey { id, key: wrapped };
+        keyring.keys.insert(id, DataKey::new(&key, wrapped)?);
+        keyring.active = id;
+        keyring.reserved_encryptions = self.next_reservation(0);
+        *keyring.encryptions.get_mut() = 0;
+
+        self.persist(keyring)?;
+        info!(
+            data_key = format!("{id:016x}"),
+            key_encryption_key = self.provider.active_key_id(),
+            "Created new data key for encrypting partition store values"
+        );
+        Ok(id)
+    }
+
+    fn persist(&self, keyring: &Keyring) -> Result<(), EncryptionError> {
+        let mut keys: Vec<_> = keyring
+            .keys
+            .values()
+            .map(|key| key.wrapped.clone())
+            .collect();
+        keys.sort_by_key(|key| key.id);
+        let keyring_file = KeyringFile {
+            active: Some(keyring.active),
+            reserved_encryptions: keyring.reserved_encryptions,
+            keys,
+        };
+
+        let keyring_error = |err: std::io::Error| {
+            EncryptionError::Keyring(self.keyring_path.clone(), err.to_string())
+        };
+        if let Some(parent) = self.keyring_path.parent() {
+            std::fs::create_dir_all(parent).map_err(keyring_error)?;
+        }
+        // Replace atomically, the keyring must never get lost
+        let tmp_path = self.keyring_path.with_extension("json.tmp");
+        let mut tmp_file = std::fs::File::create(&tmp_path).map_err(keyring_error)?;
+        tmp_file
+            .write_all(&serde_json::to_vec_pretty(&keyring_file).expect("keyring is serializable"))
+            .map_err(keyring_error)?;
+        tmp_file.sync_all().map_err(keyring_error)?;
+        drop(tmp_file);
+        std::fs::rename(&tmp_path, &self.keyring_path).map_err(keyring_error)?;
+        // Make the rename durable
+        if let Some(parent) = self.keyring_path.parent() {
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            std::fs::File::open(parent)
+                .and_then(|dir| dir.sync_all())
+                .map_err(keyring_error)?;
+        }
+        Ok(())
+    }
+}
+
+impl DataKey {
+    fn new(key: &[u8], wrapped: WrappedDataKey) -> Result<Self, EncryptionError> {
+        let key = UnboundKey::new(&AES_256_GCM, key)
+            .map_err(|_| EncryptionError::InvalidKey(format!("{:016x}", wrapped.id)))?;
+        Ok(Self {
+            key: LessSafeKey::new(key),
+            wrapped,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyfile(dir: &Path, keys: &[(&str, u8)]) -> PathBuf {
+        let path = dir.join("keys");
+        let content: String = keys
+            .iter()
+            .map(|(id, byte)| format!("{id}:{}\n", hex::encode([*byte; KEY_LEN])))
+            .collect();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn open(dir: &Path, keyfile: &Path) -> ValueCipher {
+        ValueCipher::open(
+            Arc::new(LocalKeyProvider::from_keyfile(keyfile, None).unwrap()),
+            dir.join(KEYRING_FILE_NAME),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn encrypt_decrypt() -> googletest::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cipher = open(dir.path(), &keyfile(dir.path(), &[("key-1", 1)]));
+
+        let mut buf = BytesMut::new();
+        cipher.encrypt(b"key", b"secret value", &mut buf)?;
+        assert!(!buf.windows(6).any(|window| window == b"secret"));
+        assert_eq!(b"secret value".as_slice(), cipher.decrypt(b"key", &buf)?);
+
+        // the value is bound to its key
+        assert!(matches!(
+            cipher.decrypt(b"other key", &buf),
+            Err(EncryptionError::Decrypt)
+        ));
+        assert!(cipher.is_encrypted(&buf));
+        assert!(!cipher.is_encrypted(b"secret value"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rotate_key_encryption_key() -> googletest::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cipher = open(dir.path(), &keyfile(dir.path(), &[("key-1", 1)]));
+        let mut old_value = BytesMut::new();
+        cipher.encrypt(b"key", b"old", &mut old_value)?;
+        drop(cipher);
+
+        // restarting without rotation keeps the data key
+        let cipher = open(dir.path(), &keyfile(dir.path(), &[("key-1", 1)]));
+        assert_eq!(1, cipher.export_data_keys().len());
+        drop(cipher);
+
+        let cipher = open(
+            dir.path(),
+            &keyfile(dir.path(), &[("key-1", 1), ("key-2", 2)]),
+        );
+        let data_keys = cipher.export_data_keys();
+        assert_eq!(2, data_keys.len());
+        assert!(data_keys
+            .iter()
+            .all(|key| key.key.key_encryption_key == "key-2"));
+
+        let mut new_value = BytesMut::new();
+        cipher.encrypt(b"key", b"new", &mut new_value)?;
+        assert_ne!(old_value[1..9], new_value[1..9]);
+        drop(cipher);
+
+        // the old key-encryption key can be retired
+        let cipher = open(dir.path(), &keyfile(dir.path(), &[("key-2", 2)]));
+        assert_eq!(b"old".as_slice(), cipher.decrypt(b"key", &old_value)?);
+        assert_eq!(b"new".as_slice(), cipher.decrypt(b"key", &new_value)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn import_data_keys() -> googletest::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let keyfile = keyfile(dir.path(), &[("key-1", 1)]);
+        let node_a = dir.path().join("a");
+        let node_b = dir.path().join("b");
+
+        let cipher_a = open(&node_a, &keyfile);
+        let mut value = BytesMut::new();
+        cipher_a.encrypt(b"key", b"value", &mut value)?;
+
+        let cipher_b = open(&node_b, &keyfile);
+        assert!(matches!(
+            cipher_b.decrypt(b"key", &value),
+            Err(EncryptionError::UnknownDataKey(_))
+        ));
+        cipher_b.import_data_keys(&cipher_a.export_data_keys())?;
+        assert_eq!(b"value".as_slice(), cipher_b.decrypt(b"key", &value)?);
+        drop(cipher_b);
+
+        // imported keys are persisted
+        let cipher_b = open(&node_b, &keyfile);
+        assert_eq!(b"value".as_slice(), cipher_b.decrypt(b"key", &value)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rotate_data_key_at_encryption_limit() -> googletest::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let keyfile = keyfile(dir.path(), &[("key-1", 1)]);
+        let open = || {
+            ValueCipher::open_with_limit(
+                Arc::new(LocalKeyProvider::from_keyfile(&keyfile, None).unwrap()),
+                dir.path().join(KEYRING_FILE_NAME),
+                3,
+            )
+            .unwrap()
+        };
+        let data_key_id = |value: &BytesMut| u64::from_be_bytes(value[1..9].try_into().unwrap());
+
+        let cipher = open();
+        let mut values = Vec::new();
+        for i in 0..4u8 {
+            let mut value = BytesMut::new();
+            cipher.encrypt(b"key", &[i], &mut value)?;
+            values.push(value);
+        }
+        assert_eq!(data_key_id(&values[0]), data_key_id(&values[2]));
+        assert_ne!(data_key_id(&values[2]), data_key_id(&values[3]));
+        drop(cipher);
+
+        // the reserved encryptions count as used after a restart
+        let cipher = open();
+        let mut value = BytesMut::new();
+        cipher.encrypt(b"key", &[4], &mut value)?;
+        assert_ne!(data_key_id(&values[3]), data_key_id(&value));
+        assert_eq!(3, cipher.export_data_keys().len());
+
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(vec![i as u8], cipher.decrypt(b"key", value)?);
+        }
+
+        Ok(())
+    }
+}
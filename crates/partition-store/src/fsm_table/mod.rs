@@ -42,11 +42,11 @@ fn put<S: StorageAccess>(
     partition_id: PartitionId,
     state_id: u64,
     state_value: &impl StorageEncode,
-) {
+) -> Result<()> {
     let key = PartitionStateMachineKey::default()
         .partition_id(partition_id.into())
         .state_id(state_id);
-    storage.put_kv(key, state_value);
+    storage.put_kv(key, state_value)
 }
 
 fn clear<S: StorageAccess>(storage: &mut S, partition_id: PartitionId, state_id: u64) {
@@ -80,7 +80,8 @@ impl<'a> FsmTable for PartitionStoreTransaction<'a> {
         state_id: u64,
         state_value: impl StorageEncode,
     ) -> impl Future<Output = ()> + Send {
-        put(self, self.partition_id(), state_id, &state_value);
+        let result = put(self, self.partition_id(), state_id, &state_value);
+        self.record_write(result);
         future::ready(())
     }
 
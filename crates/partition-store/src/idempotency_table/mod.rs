@@ -67,7 +67,8 @@ fn all_idempotency_metadata<S: StorageAccess>(
     let iter = storage.iterator_from(TableScan::FullScanPartitionKeyRange::<IdempotencyKey>(
         range,
     ));
-    stream::iter(OwnedIterator::new(iter).map(|(mut k, mut v)| {
+    stream::iter(OwnedIterator::new(iter, storage.cipher()).map(|item| {
+        let (mut k, mut v) = item?;
         let key = IdempotencyKey::deserialize_from(&mut k)?;
         let idempotency_metadata = StorageCodec::decode::<IdempotencyMetadata, _>(&mut v)
             .map_err(|err| StorageError::Generic(err.into()))?;
@@ -91,8 +92,8 @@ fn put_idempotency_metadata<S: StorageAccess>(
     storage: &mut S,
     idempotency_id: &IdempotencyId,
     metadata: &IdempotencyMetadata,
-) {
-    storage.put_kv(create_key(idempotency_id), metadata);
+) -> Result<()> {
+    storage.put_kv(create_key(idempotency_id), metadata)
 }
 
 fn delete_idempotency_metadata<S: StorageAccess>(storage: &mut S, idempotency_id: &IdempotencyId) {
@@ -141,7 +142,8 @@ impl<'a> IdempotencyTable for PartitionStoreTransaction<'a> {
         metadata: &IdempotencyMetadata,
     ) {
         self.assert_partition_key(idempotency_id);
-        put_idempotency_metadata(self, idempotency_id, metadata)
+        let result = put_idempotency_metadata(self, idempotency_id, metadata);
+        self.record_write(result)
     }
 
     async fn delete_idempotency_metadata(&mut self, idempotency_id: &IdempotencyId) {
@@ -155,7 +155,8 @@ impl<'a> InboxTable for PartitionStoreTransaction<'a> {
             .service_key(service_id.key.clone())
             .sequence_number(inbox_sequence_number);
 
-        self.put_kv(key, inbox_entry);
+        let result = self.put_kv(key, inbox_entry);
+        self.record_write(result);
     }
 
     async fn delete_inbox_entry(&mut self, service_id: &ServiceId, sequence_number: u64) {
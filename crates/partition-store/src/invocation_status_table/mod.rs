@@ -91,14 +91,13 @@ fn put_invocation_status<S: StorageAccess>(
     storage: &mut S,
     invocation_id: &InvocationId,
     status: &InvocationStatus,
-) {
+) -> Result<()> {
     match status {
         InvocationStatus::Free => {
             storage.delete_key(&create_invocation_status_key(invocation_id));
+            Ok(())
         }
-        _ => {
-            storage.put_kv(create_invocation_status_key(invocation_id), status);
-        }
+        _ => storage.put_kv(create_invocation_status_key(invocation_id), status),
     }
 }
 
@@ -202,10 +201,14 @@ fn all_invocation_status<S: StorageAccess>(
     range: RangeInclusive<PartitionKey>,
 ) -> impl Stream<Item = Result<(InvocationId, InvocationStatus)>> + Send + '_ {
     stream::iter(
-        OwnedIterator::new(storage.iterator_from(
-            FullScanPartitionKeyRange::<InvocationStatusKeyV1>(range.clone()),
-        ))
-        .map(|(mut key, mut value)| {
+        OwnedIterator::new(
+            storage.iterator_from(FullScanPartitionKeyRange::<InvocationStatusKeyV1>(
+                range.clone(),
+            )),
+            storage.cipher(),
+        )
+        .map(|item| {
+            let (mut key, mut value) = item?;
             let state_key = InvocationStatusKeyV1::deserialize_from(&mut key)?;
             let state_value = StorageCodec::decode::<InvocationStatusV1, _>(&mut value)
                 .map_err(|err| StorageError::Conversion(err.into()))?;
@@ -217,10 +220,14 @@ fn all_invocation_status<S: StorageAccess>(
             ))
         })
         .chain(
-            OwnedIterator::new(storage.iterator_from(FullScanPartitionKeyRange::<
-                InvocationStatusKey,
-            >(range.clone())))
-            .map(|(mut key, mut value)| {
+            OwnedIterator::new(
+                storage.iterator_from(FullScanPartitionKeyRange::<InvocationStatusKey>(
+                    range.clone(),
+                )),
+                storage.cipher(),
+            )
+            .map(|item| {
+                let (mut key, mut value) = item?;
                 let state_key = InvocationStatusKey::deserialize_from(&mut key)?;
                 let state_value = StorageCodec::decode::<InvocationStatus, _>(&mut value)
                     .map_err(|err| StorageError::Conversion(err.into()))?;
@@ -337,7 +344,8 @@ impl<'a> InvocationStatusTable for PartitionStoreTransaction<'a> {
         status: &InvocationStatus,
     ) {
         self.assert_partition_key(invocation_id);
-        put_invocation_status(self, invocation_id, status)
+        let result = put_invocation_status(self, invocation_id, status);
+        self.record_write(result)
     }
 
     async fn delete_invocation_status(&mut self, invocation_id: &InvocationId) {
@@ -25,7 +25,7 @@ use restate_types::identifiers::{
 };
 use restate_types::storage::StorageCodec;
 use std::io::Cursor;
-use std::ops::RangeInclusive;
+use std::ops::{Range, RangeInclusive};
 
 define_table_key!(
     Journal,
@@ -49,10 +49,10 @@ fn put_journal_entry<S: StorageAccess>(
     invocation_id: &InvocationId,
     journal_index: u32,
     journal_entry: &JournalEntry,
-) {
+) -> Result<()> {
     let key = write_journal_entry_key(invocation_id, journal_index);
 
-    storage.put_kv(key, journal_entry);
+    storage.put_kv(key, journal_entry)
 }
 
 fn get_journal_entry<S: StorageAccess>(
@@ -104,7 +104,8 @@ fn all_journals<S: StorageAccess>(
     range: RangeInclusive<PartitionKey>,
 ) -> impl Stream<Item = Result<(JournalEntryId, JournalEntry)>> + Send + '_ {
     let iter = storage.iterator_from(FullScanPartitionKeyRange::<JournalKey>(range));
-    stream::iter(OwnedIterator::new(iter).map(|(mut key, mut value)| {
+    stream::iter(OwnedIterator::new(iter, storage.cipher()).map(|item| {
+        let (mut key, mut value) = item?;
         let journal_key = JournalKey::deserialize_from(&mut key)?;
         let journal_entry = StorageCodec::decode::<JournalEntry, _>(&mut value)
             .map_err(|err| StorageError::Conversion(err.into()))?;
@@ -124,11 +125,11 @@ fn all_journals<S: StorageAccess>(
 fn delete_journal<S: StorageAccess>(
     storage: &mut S,
     invocation_id: &InvocationId,
-    journal_length: EntryIndex,
+    journal_range: Range<EntryIndex>,
 ) {
     let mut key = write_journal_entry_key(invocation_id, 0);
     let k = &mut key;
-    for journal_index in 0..journal_length {
+    for journal_index in journal_range {
         k.journal_index = Some(journal_index);
         storage.delete_key(k);
     }
@@ -198,13 +199,25 @@ impl<'a> JournalTable for PartitionStoreTransaction<'a> {
         journal_entry: &JournalEntry,
     ) {
         self.assert_partition_key(invocation_id);
-        put_journal_entry(self, invocation_id, journal_index, journal_entry)
+        let result = put_journal_entry(self, invocation_id, journal_index, journal_entry);
+        self.record_write(result)
     }
 
     async fn delete_journal(&mut self, invocation_id: &InvocationId, journal_length: EntryIndex) {
         self.assert_partition_key(invocation_id);
         let _x = RocksDbPerfGuard::new("delete-journal");
-        delete_journal(self, invocation_id, journal_length)
+        delete_journal(self, invocation_id, 0..journal_length)
+    }
+
+    async fn truncate_journal(
+        &mut self,
+        invocation_id: &InvocationId,
+        new_length: EntryIndex,
+        journal_length: EntryIndex,
+    ) {
+        self.assert_partition_key(invocation_id);
+        let _x = RocksDbPerfGuard::new("truncate-journal");
+        delete_journal(self, invocation_id, new_length..journal_length)
     }
 }
 
@@ -38,6 +38,9 @@ pub enum KeyKind {
     State,
     Timers,
     Promise,
+    ParkedKafkaRecord,
+    /// Marks a column family whose values are encrypted. Doesn't belong to any table.
+    Encryption,
 }
 
 impl KeyKind {
@@ -78,6 +81,8 @@ impl KeyKind {
             KeyKind::State => b"st",
             KeyKind::Timers => b"ti",
             KeyKind::Promise => b"pr",
+            KeyKind::ParkedKafkaRecord => b"pk",
+            KeyKind::Encryption => b"en",
         }
     }
 
@@ -103,6 +108,8 @@ impl KeyKind {
             b"st" => Some(KeyKind::State),
             b"ti" => Some(KeyKind::Timers),
             b"pr" => Some(KeyKind::Promise),
+            b"pk" => Some(KeyKind::ParkedKafkaRecord),
+            b"en" => Some(KeyKind::Encryption),
             _ => None,
         }
     }
@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 pub mod deduplication_table;
+pub mod encryption;
 pub mod fsm_table;
 pub mod idempotency_table;
 pub mod inbox_table;
@@ -17,6 +18,7 @@ pub mod journal_table;
 pub mod keys;
 pub mod outbox_table;
 mod owned_iter;
+pub mod parked_kafka_record_table;
 mod partition_store;
 mod partition_store_manager;
 pub mod promise_table;
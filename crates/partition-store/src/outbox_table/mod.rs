@@ -11,6 +11,9 @@
 use std::io::Cursor;
 use std::ops::RangeInclusive;
 
+use futures::Stream;
+use futures_util::stream;
+
 use restate_rocksdb::RocksDbPerfGuard;
 use restate_storage_api::outbox_table::{OutboxMessage, OutboxTable, ReadOnlyOutboxTable};
 use restate_storage_api::{Result, StorageError};
@@ -21,6 +24,7 @@ use crate::keys::{define_table_key, KeyKind, TableKey};
 use crate::TableKind::Outbox;
 use crate::{
     PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan,
+    TableScanIterationDecision,
 };
 
 define_table_key!(
@@ -34,12 +38,12 @@ fn add_message<S: StorageAccess>(
     partition_id: PartitionId,
     message_index: u64,
     outbox_message: &OutboxMessage,
-) {
+) -> Result<()> {
     let key = OutboxKey::default()
         .partition_id(partition_id.into())
         .message_index(message_index);
 
-    storage.put_kv(key, outbox_message);
+    storage.put_kv(key, outbox_message)
 }
 
 fn get_outbox_head_seq_number<S: StorageAccess>(
@@ -119,15 +123,30 @@ fn truncate_outbox<S: StorageAccess>(
     }
 }
 
+fn all_outbox_messages<S: StorageAccess>(
+    storage: &S,
+    partition_id: PartitionId,
+) -> impl Stream<Item = Result<(u64, OutboxMessage)>> + Send {
+    stream::iter(storage.for_each_key_value_in_place(
+        TableScan::SinglePartition::<OutboxKey>(partition_id),
+        |k, v| TableScanIterationDecision::Emit(decode_key_value(k, v)),
+    ))
+}
+
 impl ReadOnlyOutboxTable for PartitionStore {
     async fn get_outbox_head_seq_number(&mut self) -> Result<Option<u64>> {
         get_outbox_head_seq_number(self, self.partition_id())
     }
+
+    fn all_outbox_messages(&self) -> impl Stream<Item = Result<(u64, OutboxMessage)>> + Send {
+        all_outbox_messages(self, self.partition_id())
+    }
 }
 
 impl OutboxTable for PartitionStore {
     async fn put_outbox_message(&mut self, message_index: u64, outbox_message: &OutboxMessage) {
         add_message(self, self.partition_id(), message_index, outbox_message)
+            .expect("direct writes to the partition store must not fail")
     }
 
     async fn get_next_outbox_message(
@@ -150,11 +169,16 @@ impl<'a> ReadOnlyOutboxTable for PartitionStoreTransaction<'a> {
     async fn get_outbox_head_seq_number(&mut self) -> Result<Option<u64>> {
         get_outbox_head_seq_number(self, self.partition_id())
     }
+
+    fn all_outbox_messages(&self) -> impl Stream<Item = Result<(u64, OutboxMessage)>> + Send {
+        all_outbox_messages(self, self.partition_id())
+    }
 }
 
 impl<'a> OutboxTable for PartitionStoreTransaction<'a> {
     async fn put_outbox_message(&mut self, message_index: u64, outbox_message: &OutboxMessage) {
-        add_message(self, self.partition_id(), message_index, outbox_message)
+        let result = add_message(self, self.partition_id(), message_index, outbox_message);
+        self.record_write(result)
     }
 
     async fn get_next_outbox_message(
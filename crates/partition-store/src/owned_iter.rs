@@ -11,34 +11,52 @@
 use bytes::{BufMut, Bytes, BytesMut};
 use rocksdb::{DBAccess, DBRawIteratorWithThreadMode};
 
+use restate_storage_api::{Result, StorageError};
+
+use crate::encryption::ValueCipher;
+
 pub struct OwnedIterator<'a, DB: DBAccess> {
     iter: DBRawIteratorWithThreadMode<'a, DB>,
+    cipher: Option<&'a ValueCipher>,
     arena: BytesMut,
 }
 
 impl<'a, DB: DBAccess> OwnedIterator<'a, DB> {
-    pub(crate) fn new(iter: DBRawIteratorWithThreadMode<'a, DB>) -> Self {
+    /// Values are decrypted with the given cipher if the store is encrypted.
+    pub(crate) fn new(
+        iter: DBRawIteratorWithThreadMode<'a, DB>,
+        cipher: Option<&'a ValueCipher>,
+    ) -> Self {
         Self {
             iter,
+            cipher,
             arena: BytesMut::with_capacity(8196),
         }
     }
 }
 
 impl<'a, DB: DBAccess> Iterator for OwnedIterator<'a, DB> {
-    type Item = (Bytes, Bytes);
+    type Item = Result<(Bytes, Bytes)>;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         self.arena.reserve(8192);
 
         if let Some((k, v)) = self.iter.item() {
+            let value = match self.cipher {
+                Some(cipher) => match cipher.decrypt(k, v) {
+                    Ok(value) => Bytes::from(value),
+                    Err(err) => return Some(Err(StorageError::Generic(err.into()))),
+                },
+                None => {
+                    self.arena.put_slice(v);
+                    self.arena.split().freeze()
+                }
+            };
             self.arena.put_slice(k);
             let key = self.arena.split().freeze();
-            self.arena.put_slice(v);
-            let value = self.arena.split().freeze();
             self.iter.next();
-            Some((key, value))
+            Some(Ok((key, value)))
         } else {
             None
         }
@@ -0,0 +1,104 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::keys::{define_table_key, KeyKind, TableKey};
+use crate::owned_iter::OwnedIterator;
+use crate::scan::TableScan;
+use crate::{PartitionStore, TableKind};
+use crate::{PartitionStoreTransaction, StorageAccess};
+use bytes::Bytes;
+use bytestring::ByteString;
+use futures::Stream;
+use futures_util::stream;
+use restate_storage_api::parked_kafka_record_table::{
+    ParkedKafkaRecord, ParkedKafkaRecordTable, ReadOnlyParkedKafkaRecordTable,
+};
+use restate_storage_api::{Result, StorageError};
+use restate_types::identifiers::PartitionKey;
+use restate_types::storage::StorageCodec;
+use std::ops::RangeInclusive;
+
+define_table_key!(
+    TableKind::ParkedKafkaRecord,
+    KeyKind::ParkedKafkaRecord,
+    ParkedKafkaRecordKey(
+        partition_key: PartitionKey,
+        subscription_id: Bytes,
+        topic: ByteString,
+        kafka_partition: u32,
+        offset: u64
+    )
+);
+
+fn create_key(record: &ParkedKafkaRecord) -> ParkedKafkaRecordKey {
+    // Kafka partitions and offsets are never negative
+    ParkedKafkaRecordKey::default()
+        .partition_key(record.partition_key)
+        .subscription_id(Bytes::copy_from_slice(&record.subscription_id.to_bytes()))
+        .topic(record.topic.clone())
+        .kafka_partition(record.kafka_partition as u32)
+        .offset(record.offset as u64)
+}
+
+fn all_parked_kafka_records<S: StorageAccess>(
+    storage: &S,
+    range: RangeInclusive<PartitionKey>,
+) -> impl Stream<Item = Result<ParkedKafkaRecord>> + Send + '_ {
+    let iter =
+        storage.iterator_from(TableScan::FullScanPartitionKeyRange::<ParkedKafkaRecordKey>(range));
+    stream::iter(OwnedIterator::new(iter, storage.cipher()).map(|item| {
+        let (_, mut v) = item?;
+        StorageCodec::decode::<ParkedKafkaRecord, _>(&mut v)
+            .map_err(|err| StorageError::Generic(err.into()))
+    }))
+}
+
+fn put_parked_kafka_record<S: StorageAccess>(
+    storage: &mut S,
+    record: &ParkedKafkaRecord,
+) -> Result<()> {
+    storage.put_kv(create_key(record), record)
+}
+
+fn delete_parked_kafka_record<S: StorageAccess>(storage: &mut S, record: &ParkedKafkaRecord) {
+    let key = create_key(record);
+    storage.delete_key(&key);
+}
+
+impl ReadOnlyParkedKafkaRecordTable for PartitionStore {
+    fn all_parked_kafka_records(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<ParkedKafkaRecord>> + Send {
+        all_parked_kafka_records(self, range)
+    }
+}
+
+impl<'a> ReadOnlyParkedKafkaRecordTable for PartitionStoreTransaction<'a> {
+    fn all_parked_kafka_records(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<ParkedKafkaRecord>> + Send {
+        all_parked_kafka_records(self, range)
+    }
+}
+
+impl<'a> ParkedKafkaRecordTable for PartitionStoreTransaction<'a> {
+    async fn put_parked_kafka_record(&mut self, record: &ParkedKafkaRecord) {
+        self.assert_partition_key(record);
+        let result = put_parked_kafka_record(self, record);
+        self.record_write(result)
+    }
+
+    async fn delete_parked_kafka_record(&mut self, record: &ParkedKafkaRecord) {
+        self.assert_partition_key(record);
+        delete_parked_kafka_record(self, record)
+    }
+}
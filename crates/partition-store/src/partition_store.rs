@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::borrow::Cow;
 use std::ops::RangeInclusive;
 use std::path::PathBuf;
 use std::slice;
@@ -35,6 +36,7 @@ use restate_core::ShutdownError;
 use restate_rocksdb::{RocksDb, RocksError};
 use restate_storage_api::{Storage, StorageError, Transaction};
 
+use crate::encryption::ValueCipher;
 use crate::keys::KeyKind;
 use crate::keys::TableKey;
 use crate::scan::PhysicalScan;
@@ -129,6 +131,7 @@ pub enum TableKind {
     Inbox,
     Journal,
     Promise,
+    ParkedKafkaRecord,
 }
 
 impl TableKind {
@@ -145,6 +148,7 @@ impl TableKind {
             Self::Timers => &[KeyKind::Timers],
             Self::Journal => &[KeyKind::Journal],
             Self::Promise => &[KeyKind::Promise],
+            Self::ParkedKafkaRecord => &[KeyKind::ParkedKafkaRecord],
         }
     }
 
@@ -192,6 +196,7 @@ pub struct PartitionStore {
     key_range: RangeInclusive<PartitionKey>,
     key_buffer: BytesMut,
     value_buffer: BytesMut,
+    cipher: Option<Arc<ValueCipher>>,
 }
 
 impl std::fmt::Debug for PartitionStore {
@@ -202,6 +207,7 @@ impl std::fmt::Debug for PartitionStore {
             .field("cf", &self.data_cf_name)
             .field("key_buffer", &self.key_buffer.len())
             .field("value_buffer", &self.value_buffer.len())
+            .field("encrypted", &self.cipher.is_some())
             .finish()
     }
 }
@@ -216,6 +222,7 @@ impl Clone for PartitionStore {
             key_range: self.key_range.clone(),
             key_buffer: BytesMut::default(),
             value_buffer: BytesMut::default(),
+            cipher: self.cipher.clone(),
         }
     }
 }
@@ -278,6 +285,7 @@ impl PartitionStore {
         data_cf_name: CfName,
         partition_id: PartitionId,
         key_range: RangeInclusive<PartitionKey>,
+        cipher: Option<Arc<ValueCipher>>,
     ) -> Self {
         Self {
             raw_db,
@@ -287,6 +295,7 @@ impl PartitionStore {
             key_range,
             key_buffer: BytesMut::new(),
             value_buffer: BytesMut::new(),
+            cipher,
         }
     }
 
@@ -408,8 +417,10 @@ impl PartitionStore {
             rocksdb,
             key_buffer: &mut self.key_buffer,
             value_buffer: &mut self.value_buffer,
+            cipher: self.cipher.as_deref(),
             partition_id: self.partition_id,
             partition_key_range: &self.key_range,
+            write_error: None,
         }
     }
 
@@ -458,6 +469,11 @@ impl PartitionStore {
             db_comparator_name: metadata.get_db_comparator_name(),
             min_applied_lsn: applied_lsn,
             key_range: self.key_range.clone(),
+            data_keys: self
+                .cipher
+                .as_ref()
+                .map(|cipher| cipher.export_data_keys())
+                .unwrap_or_default(),
         })
     }
 }
@@ -517,9 +533,29 @@ impl StorageAccess for PartitionStore {
     }
 
     #[inline]
-    fn put_cf(&mut self, table: TableKind, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) {
+    fn cipher(&self) -> Option<&ValueCipher> {
+        self.cipher.as_deref()
+    }
+
+    #[inline]
+    fn put_cf(
+        &mut self,
+        table: TableKind,
+        key: impl AsRef<[u8]>,
+        value: impl AsRef<[u8]>,
+    ) -> Result<()> {
         let table = self.table_handle(table);
-        self.raw_db.put_cf(&table, key, value).unwrap();
+        match &self.cipher {
+            Some(cipher) => {
+                self.value_buffer.clear();
+                cipher
+                    .encrypt(key.as_ref(), value.as_ref(), &mut self.value_buffer)
+                    .map_err(|err| StorageError::Generic(err.into()))?;
+                self.raw_db.put_cf(&table, key, &self.value_buffer)
+            }
+            None => self.raw_db.put_cf(&table, key, value),
+        }
+        .map_err(|error| StorageError::Generic(error.into()))
     }
 
     #[inline]
@@ -548,6 +584,9 @@ pub struct PartitionStoreTransaction<'a> {
     data_cf_handle: Arc<BoundColumnFamily<'a>>,
     key_buffer: &'a mut BytesMut,
     value_buffer: &'a mut BytesMut,
+    cipher: Option<&'a ValueCipher>,
+    /// The first write which failed, fails the commit
+    write_error: Option<StorageError>,
 }
 
 impl<'a> PartitionStoreTransaction<'a> {
@@ -608,6 +647,15 @@ impl<'a> PartitionStoreTransaction<'a> {
     pub(crate) fn assert_partition_key(&self, partition_key: &impl WithPartitionKey) {
         assert_partition_key(self.partition_key_range, partition_key);
     }
+
+    /// Remembers a failed write. The writes of the table traits can't fail, the transaction
+    /// fails to commit instead.
+    #[inline]
+    pub(crate) fn record_write(&mut self, result: Result<()>) {
+        if let Err(err) = result {
+            self.write_error.get_or_insert(err);
+        }
+    }
 }
 
 #[inline]
@@ -622,6 +670,9 @@ fn assert_partition_key(
 
 impl<'a> Transaction for PartitionStoreTransaction<'a> {
     async fn commit(self) -> Result<()> {
+        if let Some(err) = self.write_error {
+            return Err(err);
+        }
         // We cannot directly commit the txn because it might fail because of unrelated concurrent
         // writes to RocksDB. However, it is safe to write the WriteBatch for a given partition,
         // because there can only be a single writer (the leading PartitionProcessor).
@@ -724,9 +775,34 @@ impl<'a> StorageAccess for PartitionStoreTransaction<'a> {
     }
 
     #[inline]
-    fn put_cf(&mut self, _table: TableKind, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) {
-        self.write_batch_with_index
-            .put_cf(&self.data_cf_handle, key, value);
+    fn cipher(&self) -> Option<&ValueCipher> {
+        self.cipher
+    }
+
+    #[inline]
+    fn put_cf(
+        &mut self,
+        _table: TableKind,
+        key: impl AsRef<[u8]>,
+        value: impl AsRef<[u8]>,
+    ) -> Result<()> {
+        match self.cipher {
+            Some(cipher) => {
+                self.value_buffer.clear();
+                cipher
+                    .encrypt(key.as_ref(), value.as_ref(), self.value_buffer)
+                    .map_err(|err| StorageError::Generic(err.into()))?;
+                self.write_batch_with_index.put_cf(
+                    &self.data_cf_handle,
+                    key,
+                    &self.value_buffer[..],
+                );
+            }
+            None => self
+                .write_batch_with_index
+                .put_cf(&self.data_cf_handle, key, value),
+        }
+        Ok(())
     }
 
     #[inline]
@@ -752,21 +828,30 @@ pub(crate) trait StorageAccess {
 
     fn get<K: AsRef<[u8]>>(&self, table: TableKind, key: K) -> Result<Option<DBPinnableSlice>>;
 
-    fn put_cf(&mut self, table: TableKind, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>);
+    /// The cipher of the values if the store is encrypted
+    fn cipher(&self) -> Option<&ValueCipher>;
+
+    /// Puts the value, encrypting it if the store is encrypted.
+    fn put_cf(
+        &mut self,
+        table: TableKind,
+        key: impl AsRef<[u8]>,
+        value: impl AsRef<[u8]>,
+    ) -> Result<()>;
 
     fn delete_cf(&mut self, table: TableKind, key: impl AsRef<[u8]>);
 
     #[inline]
-    fn put_kv_raw<K: TableKey, V: AsRef<[u8]>>(&mut self, key: K, value: V) {
+    fn put_kv_raw<K: TableKey, V: AsRef<[u8]>>(&mut self, key: K, value: V) -> Result<()> {
         let key_buffer = self.cleared_key_buffer_mut(key.serialized_length());
         key.serialize_to(key_buffer);
         let key_buffer = key_buffer.split();
 
-        self.put_cf(K::TABLE, key_buffer, value);
+        self.put_cf(K::TABLE, key_buffer, value)
     }
 
     #[inline]
-    fn put_kv<K: TableKey, V: StorageEncode>(&mut self, key: K, value: &V) {
+    fn put_kv<K: TableKey, V: StorageEncode>(&mut self, key: K, value: &V) -> Result<()> {
         let key_buffer = self.cleared_key_buffer_mut(key.serialized_length());
         key.serialize_to(key_buffer);
         let key_buffer = key_buffer.split();
@@ -775,7 +860,19 @@ pub(crate) trait StorageAccess {
         StorageCodec::encode(value, value_buffer).unwrap();
         let value_buffer = value_buffer.split();
 
-        self.put_cf(K::TABLE, key_buffer, value_buffer);
+        self.put_cf(K::TABLE, key_buffer, value_buffer)
+    }
+
+    /// Decrypts the value if the store is encrypted.
+    #[inline]
+    fn decrypt<'v>(&self, key: &[u8], value: &'v [u8]) -> Result<Cow<'v, [u8]>> {
+        match self.cipher() {
+            Some(cipher) => cipher
+                .decrypt(key, value)
+                .map(Cow::Owned)
+                .map_err(|err| StorageError::Generic(err.into())),
+            None => Ok(Cow::Borrowed(value)),
+        }
     }
 
     #[inline]
@@ -801,9 +898,10 @@ pub(crate) trait StorageAccess {
             Ok(value) => {
                 let slice = value.as_ref().map(|v| v.as_ref());
 
-                if let Some(mut slice) = slice {
+                if let Some(slice) = slice {
+                    let value = self.decrypt(&buf, slice)?;
                     Ok(Some(
-                        StorageCodec::decode::<V, _>(&mut slice)
+                        StorageCodec::decode::<V, _>(&mut value.as_ref())
                             .map_err(|err| StorageError::Generic(err.into()))?,
                     ))
                 } else {
@@ -821,7 +919,13 @@ pub(crate) trait StorageAccess {
         F: FnOnce(Option<(&[u8], &[u8])>) -> Result<R>,
     {
         let iterator = self.iterator_from(scan);
-        f(iterator.item())
+        match iterator.item() {
+            Some((k, v)) => {
+                let v = self.decrypt(k, v)?;
+                f(Some((k, &v)))
+            }
+            None => f(None),
+        }
     }
 
     #[inline]
@@ -836,8 +940,11 @@ pub(crate) trait StorageAccess {
 
         match self.get(K::TABLE, &buf) {
             Ok(value) => {
-                let slice = value.as_ref().map(|v| v.as_ref());
-                f(&buf, slice)
+                let slice = value
+                    .as_ref()
+                    .map(|v| self.decrypt(&buf, v.as_ref()))
+                    .transpose()?;
+                f(&buf, slice.as_deref())
             }
             Err(err) => Err(err),
         }
@@ -854,7 +961,14 @@ pub(crate) trait StorageAccess {
         let mut iterator = self.iterator_from(scan);
 
         while let Some((k, v)) = iterator.item() {
-            match op(k, v) {
+            let v = match self.decrypt(k, v) {
+                Ok(v) => v,
+                Err(err) => {
+                    res.push(Err(err));
+                    break;
+                }
+            };
+            match op(k, &v) {
                 TableScanIterationDecision::Emit(result) => {
                     res.push(result);
                     iterator.next();
@@ -8,16 +8,19 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::RangeInclusive;
 use std::path::Path;
 use std::sync::Arc;
 
-use rocksdb::ExportImportFilesMetaData;
+use bytes::BytesMut;
+use futures::TryStreamExt;
+use rocksdb::{AsColumnFamilyRef, ExportImportFilesMetaData, IteratorMode, WriteBatch};
 use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 
 use crate::cf_options;
+use crate::encryption::{EncryptionError, ValueCipher, ENCRYPTED_MARKER_KEY};
 use crate::snapshots::LocalPartitionSnapshot;
 use crate::PartitionStore;
 use crate::DB;
@@ -25,12 +28,21 @@ use restate_core::worker_api::SnapshotError;
 use restate_rocksdb::{
     CfName, CfPrefixPattern, DbName, DbSpecBuilder, RocksDb, RocksDbManager, RocksError,
 };
+use restate_storage_api::deduplication_table::{
+    DedupSequenceNumber, DeduplicationTable, ProducerId, ReadOnlyDeduplicationTable,
+};
+use restate_storage_api::fsm_table::{FsmTable, ReadOnlyFsmTable};
+use restate_storage_api::outbox_table::{OutboxTable, ReadOnlyOutboxTable};
+use restate_storage_api::timer_table::{ReadOnlyTimerTable, TimerTable};
+use restate_storage_api::{StorageError, Transaction};
 use restate_types::config::{RocksDbOptions, StorageOptions};
-use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId};
+use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId, WithPartitionKey};
 use restate_types::live::{BoxedLiveLoad, LiveLoad};
 
 const DB_NAME: &str = "db";
 const PARTITION_CF_PREFIX: &str = "data-";
+/// Number of records copied per write batch when merging parent snapshots.
+const IMPORT_BATCH_SIZE: usize = 1024;
 
 /// Controls how a partition store is opened
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -44,6 +56,7 @@ pub struct PartitionStoreManager {
     lookup: Arc<Mutex<PartitionLookup>>,
     rocksdb: Arc<RocksDb>,
     raw_db: Arc<DB>,
+    cipher: Option<Arc<ValueCipher>>,
 }
 
 #[derive(Default, Debug)]
@@ -83,9 +96,21 @@ impl PartitionStoreManager {
             raw_db,
             rocksdb,
             lookup: Arc::default(),
+            cipher: None,
         })
     }
 
+    /// Encrypts the values of all partition stores with the given cipher. Stores which have been
+    /// written without encryption are encrypted when they are opened.
+    pub fn with_encryption(mut self, cipher: Arc<ValueCipher>) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    pub fn cipher(&self) -> Option<&Arc<ValueCipher>> {
+        self.cipher.as_ref()
+    }
+
     /// Check whether we have a partition store for the given partition id, irrespective of whether
     /// the store is open or not.
     pub async fn has_partition_store(&self, partition_id: PartitionId) -> bool {
@@ -107,7 +132,7 @@ impl PartitionStoreManager {
         partition_key_range: RangeInclusive<PartitionKey>,
         open_mode: OpenMode,
         opts: &RocksDbOptions,
-    ) -> Result<PartitionStore, RocksError> {
+    ) -> Result<PartitionStore, StorageError> {
         let mut guard = self.lookup.lock().await;
         if let Some(store) = guard.live.get(&partition_id) {
             return Ok(store.clone());
@@ -118,18 +143,24 @@ impl PartitionStoreManager {
         if !already_exists {
             if open_mode == OpenMode::CreateIfMissing {
                 debug!("Initializing storage for partition {}", partition_id);
-                self.rocksdb.open_cf(cf_name.clone(), opts).await?;
+                self.rocksdb
+                    .open_cf(cf_name.clone(), opts)
+                    .await
+                    .map_err(|err| StorageError::Generic(err.into()))?;
             } else {
-                return Err(RocksError::AlreadyOpen);
+                return Err(StorageError::Generic(RocksError::AlreadyOpen.into()));
             }
         }
 
+        self.ensure_encryption(partition_id, &cf_name).await?;
+
         let partition_store = PartitionStore::new(
             self.raw_db.clone(),
             self.rocksdb.clone(),
             cf_name,
             partition_id,
             partition_key_range,
+            self.cipher.clone(),
         );
         guard.live.insert(partition_id, partition_store.clone());
 
@@ -145,14 +176,39 @@ impl PartitionStoreManager {
         partition_key_range: RangeInclusive<PartitionKey>,
         snapshot: LocalPartitionSnapshot,
         opts: &RocksDbOptions,
-    ) -> Result<PartitionStore, RocksError> {
+    ) -> Result<PartitionStore, StorageError> {
+        if snapshot.key_range.start() > partition_key_range.start()
+            || snapshot.key_range.end() < partition_key_range.end()
+        {
+            warn!(
+                %partition_id,
+                snapshot_range = ?snapshot.key_range,
+                partition_range = ?partition_key_range,
+                "The snapshot key range does not fully cover the partition key range"
+            );
+            return Err(StorageError::Generic(
+                RocksError::SnapshotKeyRangeMismatch.into(),
+            ));
+        }
+
+        self.import_partition_snapshot(partition_id, partition_key_range, snapshot, opts)
+            .await
+    }
+
+    async fn import_partition_snapshot(
+        &self,
+        partition_id: PartitionId,
+        partition_key_range: RangeInclusive<PartitionKey>,
+        snapshot: LocalPartitionSnapshot,
+        opts: &RocksDbOptions,
+    ) -> Result<PartitionStore, StorageError> {
         let mut guard = self.lookup.lock().await;
         if guard.live.contains_key(&partition_id) {
             warn!(
                 %partition_id,
                 "The partition store is already open, refusing to import snapshot"
             );
-            return Err(RocksError::AlreadyOpen);
+            return Err(StorageError::Generic(RocksError::AlreadyOpen.into()));
         }
 
         let cf_name = cf_for_partition(partition_id);
@@ -163,20 +219,10 @@ impl PartitionStoreManager {
                 %cf_name,
                 "The column family for partition already exists in the database, cannot import snapshot"
             );
-            return Err(RocksError::ColumnFamilyExists);
+            return Err(StorageError::Generic(RocksError::ColumnFamilyExists.into()));
         }
 
-        if snapshot.key_range.start() > partition_key_range.start()
-            || snapshot.key_range.end() < partition_key_range.end()
-        {
-            warn!(
-                %partition_id,
-                snapshot_range = ?snapshot.key_range,
-                partition_range = ?partition_key_range,
-                "The snapshot key range does not fully cover the partition key range"
-            );
-            return Err(RocksError::SnapshotKeyRangeMismatch);
-        }
+        self.import_data_keys(&snapshot)?;
 
         let mut import_metadata = ExportImportFilesMetaData::default();
         import_metadata.set_db_comparator_name(snapshot.db_comparator_name.as_str());
@@ -191,21 +237,245 @@ impl PartitionStoreManager {
 
         self.rocksdb
             .import_cf(cf_name.clone(), opts, import_metadata)
-            .await?;
+            .await
+            .map_err(|err| StorageError::Generic(err.into()))?;
 
         assert!(self.rocksdb.inner().cf_handle(&cf_name).is_some());
+        self.ensure_encryption(partition_id, &cf_name).await?;
+
         let partition_store = PartitionStore::new(
             self.raw_db.clone(),
             self.rocksdb.clone(),
             cf_name,
             partition_id,
             partition_key_range,
+            self.cipher.clone(),
         );
         guard.live.insert(partition_id, partition_store.clone());
 
         Ok(partition_store)
     }
 
+    /// Creates the partition store of a partition which has been split off from or merged out of
+    /// the given parent partitions, using a snapshot of each parent taken at the tail of its
+    /// sealed log. The snapshots must jointly cover the partition key range.
+    ///
+    /// The keyed data of all parents is copied into the new column family. Data which is keyed by
+    /// the parent's partition id (fsm variables, outbox, timers and deduplication information) is
+    /// adopted by the new partition as far as it falls into its key range.
+    pub async fn open_partition_store_from_parent_snapshots(
+        &self,
+        partition_id: PartitionId,
+        partition_key_range: RangeInclusive<PartitionKey>,
+        mut snapshots: Vec<(PartitionId, LocalPartitionSnapshot)>,
+        opts: &RocksDbOptions,
+    ) -> Result<PartitionStore, StorageError> {
+        snapshots.sort_by_key(|(_, snapshot)| *snapshot.key_range.start());
+        if !covers(&snapshots, &partition_key_range) {
+            warn!(
+                %partition_id,
+                snapshot_ranges = ?snapshots.iter().map(|(_, s)| &s.key_range).collect::<Vec<_>>(),
+                partition_range = ?partition_key_range,
+                "The parent snapshots do not fully cover the partition key range"
+            );
+            return Err(StorageError::Generic(
+                RocksError::SnapshotKeyRangeMismatch.into(),
+            ));
+        }
+
+        let parents: Vec<_> = snapshots
+            .iter()
+            .map(|(parent_id, snapshot)| (*parent_id, snapshot.key_range.clone()))
+            .collect();
+        let mut snapshots = snapshots.into_iter().map(|(_, snapshot)| snapshot);
+
+        let mut partition_store = self
+            .import_partition_snapshot(
+                partition_id,
+                partition_key_range,
+                snapshots.next().expect("at least one snapshot"),
+                opts,
+            )
+            .await?;
+
+        let result = self
+            .merge_parent_snapshots(partition_id, &mut partition_store, snapshots, parents, opts)
+            .await;
+
+        if let Err(err) = result {
+            // Don't leave a partially initialized store behind, the next attempt starts over
+            drop(partition_store);
+            self.drop_partition(partition_id).await;
+            return Err(err);
+        }
+
+        Ok(partition_store)
+    }
+
+    async fn merge_parent_snapshots(
+        &self,
+        partition_id: PartitionId,
+        partition_store: &mut PartitionStore,
+        snapshots: impl Iterator<Item = LocalPartitionSnapshot>,
+        parents: Vec<(PartitionId, RangeInclusive<PartitionKey>)>,
+        opts: &RocksDbOptions,
+    ) -> Result<(), StorageError> {
+        for snapshot in snapshots {
+            self.merge_snapshot(partition_id, snapshot, opts).await?;
+        }
+
+        // The parents' rows live in the column family of the new partition now
+        let cf_name = cf_for_partition(partition_id);
+        adopt_parent_state(
+            |parent_id, parent_key_range| {
+                PartitionStore::new(
+                    self.raw_db.clone(),
+                    self.rocksdb.clone(),
+                    cf_name.clone(),
+                    parent_id,
+                    parent_key_range,
+                    self.cipher.clone(),
+                )
+            },
+            partition_store,
+            parents,
+        )
+        .await
+    }
+
+    /// Imports the snapshot into a temporary column family and copies its records into the
+    /// column family of the given partition.
+    async fn merge_snapshot(
+        &self,
+        partition_id: PartitionId,
+        snapshot: LocalPartitionSnapshot,
+        opts: &RocksDbOptions,
+    ) -> Result<(), StorageError> {
+        let import_cf_name = CfName::from(format!("{PARTITION_CF_PREFIX}import-{partition_id}"));
+        let target_cf_name = cf_for_partition(partition_id);
+
+        if self.rocksdb.inner().cf_handle(&import_cf_name).is_some() {
+            // left behind by an interrupted import
+            self.raw_db
+                .drop_cf(&import_cf_name)
+                .map_err(|err| StorageError::Generic(err.into()))?;
+        }
+
+        self.import_data_keys(&snapshot)?;
+
+        let mut import_metadata = ExportImportFilesMetaData::default();
+        import_metadata.set_db_comparator_name(snapshot.db_comparator_name.as_str());
+        import_metadata.set_files(&snapshot.files);
+
+        info!(
+            %partition_id,
+            min_lsn = %snapshot.min_applied_lsn,
+            path = ?snapshot.base_dir,
+            "Merging partition store snapshot"
+        );
+
+        self.rocksdb
+            .import_cf(import_cf_name.clone(), opts, import_metadata)
+            .await
+            .map_err(|err| StorageError::Generic(err.into()))?;
+
+        let raw_db = self.raw_db.clone();
+        let cipher = self.cipher.clone();
+        tokio::task::spawn_blocking(move || {
+            let source = raw_db
+                .cf_handle(&import_cf_name)
+                .expect("imported column family exists");
+            let target = raw_db
+                .cf_handle(&target_cf_name)
+                .expect("partition column family exists");
+
+            // The values of the target are encrypted iff a cipher is configured
+            let source_encrypted = is_encrypted(&raw_db, &source)?;
+            let cipher = match (cipher, source_encrypted) {
+                (None, true) => {
+                    return Err(StorageError::Generic(EncryptionError::NotConfigured.into()))
+                }
+                (Some(_), true) | (None, false) => None,
+                (Some(cipher), false) => Some(cipher),
+            };
+
+            let mut batch = WriteBatch::default();
+            let mut buf = BytesMut::new();
+            for item in raw_db.iterator_cf(&source, IteratorMode::Start) {
+                let (key, value) = item.map_err(|err| StorageError::Generic(err.into()))?;
+                if key.as_ref() == ENCRYPTED_MARKER_KEY {
+                    continue;
+                }
+                match &cipher {
+                    Some(cipher) => {
+                        buf.clear();
+                        cipher
+                            .encrypt(&key, &value, &mut buf)
+                            .map_err(|err| StorageError::Generic(err.into()))?;
+                        batch.put_cf(&target, key, &buf[..]);
+                    }
+                    None => batch.put_cf(&target, key, value),
+                }
+                if batch.len() >= IMPORT_BATCH_SIZE {
+                    raw_db
+                        .write(std::mem::take(&mut batch))
+                        .map_err(|err| StorageError::Generic(err.into()))?;
+                }
+            }
+            raw_db
+                .write(batch)
+                .map_err(|err| StorageError::Generic(err.into()))?;
+
+            drop(source);
+            raw_db
+                .drop_cf(&import_cf_name)
+                .map_err(|err| StorageError::Generic(err.into()))
+        })
+        .await
+        .map_err(|err| StorageError::Generic(err.into()))?
+    }
+
+    /// Makes the data keys of an encrypted snapshot known to the cipher.
+    fn import_data_keys(&self, snapshot: &LocalPartitionSnapshot) -> Result<(), StorageError> {
+        match &self.cipher {
+            Some(cipher) if !snapshot.data_keys.is_empty() => cipher
+                .import_data_keys(&snapshot.data_keys)
+                .map_err(|err| StorageError::Generic(err.into())),
+            // Imports without a cipher fail once the encrypted column family is opened
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the values of the partition's column family are encrypted iff encryption is
+    /// configured. Column families which have been written without encryption are encrypted in
+    /// place.
+    async fn ensure_encryption(
+        &self,
+        partition_id: PartitionId,
+        cf_name: &CfName,
+    ) -> Result<(), StorageError> {
+        let raw_db = self.raw_db.clone();
+        let cipher = self.cipher.clone();
+        let cf_name = cf_name.clone();
+        tokio::task::spawn_blocking(move || {
+            let cf = raw_db
+                .cf_handle(&cf_name)
+                .expect("partition column family exists");
+
+            match (cipher, is_encrypted(&raw_db, &cf)?) {
+                (Some(_), true) | (None, false) => Ok(()),
+                (None, true) => Err(StorageError::Generic(EncryptionError::NotConfigured.into())),
+                (Some(cipher), false) => {
+                    info!(%partition_id, "Encrypting the partition store");
+                    encrypt_in_place(&raw_db, &cf, &cipher)
+                        .map_err(|err| StorageError::Generic(err.into()))
+                }
+            }
+        })
+        .await
+        .map_err(|err| StorageError::Generic(err.into()))?
+    }
+
     pub async fn export_partition_snapshot(
         &self,
         partition_id: PartitionId,
@@ -239,6 +509,154 @@ impl PartitionStoreManager {
     }
 }
 
+/// Moves the state which the parent partitions keyed by their partition id into the new
+/// partition.
+async fn adopt_parent_state(
+    mut parent_store: impl FnMut(PartitionId, RangeInclusive<PartitionKey>) -> PartitionStore,
+    partition_store: &mut PartitionStore,
+    parents: Vec<(PartitionId, RangeInclusive<PartitionKey>)>,
+) -> Result<(), StorageError> {
+    let key_range = partition_store.partition_key_range().clone();
+
+    let mut inbox_seq_number = 0;
+    let mut outbox_messages = Vec::new();
+    let mut timers = Vec::new();
+    let mut dedup_seq_numbers: HashMap<ProducerId, DedupSequenceNumber> = HashMap::new();
+
+    for (parent_id, parent_key_range) in parents {
+        let mut parent = parent_store(parent_id, parent_key_range.clone());
+
+        inbox_seq_number = inbox_seq_number.max(parent.get_inbox_seq_number().await?);
+
+        // Only one of the children of a split partition takes over its outbox, otherwise the
+        // messages would be sent twice.
+        if key_range.contains(parent_key_range.start()) {
+            outbox_messages.extend(
+                parent
+                    .all_outbox_messages()
+                    .map_ok(|(_, message)| message)
+                    .try_collect::<Vec<_>>()
+                    .await?,
+            );
+        }
+
+        timers.extend(
+            parent
+                .all_timers()
+                .try_filter(|(_, timer)| {
+                    futures::future::ready(key_range.contains(&timer.partition_key()))
+                })
+                .try_collect::<Vec<_>>()
+                .await?,
+        );
+
+        let parent_dedup_seq_numbers: Vec<_> =
+            parent.get_all_sequence_numbers().try_collect().await?;
+        for dedup_information in parent_dedup_seq_numbers {
+            if dedup_information.producer_id == ProducerId::self_producer() {
+                continue;
+            }
+            dedup_seq_numbers
+                .entry(dedup_information.producer_id)
+                .and_modify(|current| {
+                    if is_newer(&dedup_information.sequence_number, current) {
+                        *current = dedup_information.sequence_number;
+                    }
+                })
+                .or_insert(dedup_information.sequence_number);
+        }
+    }
+
+    debug!(
+        partition_id = %partition_store.partition_id(),
+        %inbox_seq_number,
+        outbox_messages = outbox_messages.len(),
+        timers = timers.len(),
+        "Adopting the state of the parent partitions"
+    );
+
+    let mut txn = partition_store.transaction();
+    txn.put_inbox_seq_number(inbox_seq_number).await;
+    for (message_index, message) in outbox_messages.iter().enumerate() {
+        txn.put_outbox_message(message_index as u64, message).await;
+    }
+    txn.put_outbox_seq_number(outbox_messages.len() as u64)
+        .await;
+    for (timer_key, timer) in &timers {
+        txn.put_timer(timer_key, timer).await;
+    }
+    for (producer_id, sequence_number) in dedup_seq_numbers {
+        txn.put_dedup_seq_number(producer_id, &sequence_number)
+            .await;
+    }
+    txn.commit().await
+}
+
+fn is_newer(candidate: &DedupSequenceNumber, current: &DedupSequenceNumber) -> bool {
+    match (candidate, current) {
+        (DedupSequenceNumber::Sn(candidate), DedupSequenceNumber::Sn(current)) => {
+            candidate > current
+        }
+        (DedupSequenceNumber::Esn(candidate), DedupSequenceNumber::Esn(current)) => {
+            candidate > current
+        }
+        _ => false,
+    }
+}
+
+/// Checks whether the sorted key ranges of the snapshots cover the given key range without gaps.
+fn covers(
+    snapshots: &[(PartitionId, LocalPartitionSnapshot)],
+    key_range: &RangeInclusive<PartitionKey>,
+) -> bool {
+    let mut next = *key_range.start();
+    for (_, snapshot) in snapshots {
+        if *snapshot.key_range.start() > next {
+            return false;
+        }
+        if *snapshot.key_range.end() >= *key_range.end() {
+            return true;
+        }
+        next = next.max(snapshot.key_range.end() + 1);
+    }
+    false
+}
+
+fn is_encrypted(raw_db: &DB, cf: &impl AsColumnFamilyRef) -> Result<bool, StorageError> {
+    raw_db
+        .get_pinned_cf(cf, ENCRYPTED_MARKER_KEY)
+        .map(|marker| marker.is_some())
+        .map_err(|err| StorageError::Generic(err.into()))
+}
+
+/// Encrypts all values of the column family and marks it as encrypted. Values which are already
+/// encrypted, because a previous attempt got interrupted, are skipped.
+fn encrypt_in_place(
+    raw_db: &DB,
+    cf: &impl AsColumnFamilyRef,
+    cipher: &ValueCipher,
+) -> anyhow::Result<()> {
+    let mut batch = WriteBatch::default();
+    let mut buf = BytesMut::new();
+    for item in raw_db.iterator_cf(cf, IteratorMode::Start) {
+        let (key, value) = item?;
+        if cipher.is_encrypted(&value) {
+            continue;
+        }
+        buf.clear();
+        cipher.encrypt(&key, &value, &mut buf)?;
+        batch.put_cf(cf, key, &buf[..]);
+        if batch.len() >= IMPORT_BATCH_SIZE {
+            raw_db.write(std::mem::take(&mut batch))?;
+        }
+    }
+    batch.put_cf(cf, ENCRYPTED_MARKER_KEY, []);
+    raw_db.write(batch)?;
+    // The marker must not outlive the encrypted values if the WAL is disabled
+    raw_db.flush_cf(cf)?;
+    Ok(())
+}
+
 fn cf_for_partition(partition_id: PartitionId) -> CfName {
     CfName::from(format!("{PARTITION_CF_PREFIX}{partition_id}"))
 }
@@ -60,7 +60,8 @@ fn all_promise<S: StorageAccess>(
     range: RangeInclusive<PartitionKey>,
 ) -> impl Stream<Item = Result<OwnedPromiseRow>> + Send + '_ {
     let iter = storage.iterator_from(TableScan::FullScanPartitionKeyRange::<PromiseKey>(range));
-    stream::iter(OwnedIterator::new(iter).map(|(mut k, mut v)| {
+    stream::iter(OwnedIterator::new(iter, storage.cipher()).map(|item| {
+        let (mut k, mut v) = item?;
         let key = PromiseKey::deserialize_from(&mut k)?;
         let metadata = StorageCodec::decode::<Promise, _>(&mut v)
             .map_err(|err| StorageError::Generic(err.into()))?;
@@ -85,8 +86,8 @@ fn put_promise<S: StorageAccess>(
     service_id: &ServiceId,
     key: &ByteString,
     metadata: &Promise,
-) {
-    storage.put_kv(create_key(service_id, key), metadata);
+) -> Result<()> {
+    storage.put_kv(create_key(service_id, key), metadata)
 }
 
 fn delete_all_promises<S: StorageAccess>(storage: &mut S, service_id: &ServiceId) {
@@ -144,7 +145,8 @@ impl<'a> ReadOnlyPromiseTable for PartitionStoreTransaction<'a> {
 impl<'a> PromiseTable for PartitionStoreTransaction<'a> {
     async fn put_promise(&mut self, service_id: &ServiceId, key: &ByteString, promise: &Promise) {
         self.assert_partition_key(service_id);
-        put_promise(self, service_id, key, promise)
+        let result = put_promise(self, service_id, key, promise);
+        self.record_write(result)
     }
 
     async fn delete_all_promises(&mut self, service_id: &ServiceId) {
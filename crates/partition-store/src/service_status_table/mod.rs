@@ -47,15 +47,16 @@ fn put_virtual_object_status<S: StorageAccess>(
     storage: &mut S,
     service_id: &ServiceId,
     status: &VirtualObjectStatus,
-) {
+) -> Result<()> {
     let key = ServiceStatusKey::default()
         .partition_key(service_id.partition_key())
         .service_name(service_id.service_name.clone())
         .service_key(service_id.key.clone());
     if *status == VirtualObjectStatus::Unlocked {
         storage.delete_key(&key);
+        Ok(())
     } else {
-        storage.put_kv(key, status);
+        storage.put_kv(key, status)
     }
 }
 
@@ -79,7 +80,8 @@ fn all_virtual_object_status<S: StorageAccess>(
     range: RangeInclusive<PartitionKey>,
 ) -> impl Stream<Item = Result<(ServiceId, VirtualObjectStatus)>> + Send + '_ {
     let iter = storage.iterator_from(FullScanPartitionKeyRange::<ServiceStatusKey>(range));
-    stream::iter(OwnedIterator::new(iter).map(|(mut key, mut value)| {
+    stream::iter(OwnedIterator::new(iter, storage.cipher()).map(|item| {
+        let (mut key, mut value) = item?;
         let state_key = ServiceStatusKey::deserialize_from(&mut key)?;
         let state_value = StorageCodec::decode::<VirtualObjectStatus, _>(&mut value)
             .map_err(|err| StorageError::Conversion(err.into()))?;
@@ -139,7 +141,8 @@ impl<'a> VirtualObjectStatusTable for PartitionStoreTransaction<'a> {
         status: &VirtualObjectStatus,
     ) {
         self.assert_partition_key(service_id);
-        put_virtual_object_status(self, service_id, status)
+        let result = put_virtual_object_status(self, service_id, status);
+        self.record_write(result)
     }
 
     async fn delete_virtual_object_status(&mut self, service_id: &ServiceId) {
@@ -19,6 +19,8 @@ use serde_with::{serde_as, DeserializeAs, SerializeAs};
 use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId};
 use restate_types::logs::Lsn;
 
+use crate::encryption::{ArchiveEncryption, WrappedDataKey};
+
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub enum SnapshotFormatVersion {
     #[default]
@@ -61,6 +63,14 @@ pub struct PartitionSnapshotMetadata {
     /// The RocksDB SST files comprising the snapshot.
     #[serde_as(as = "Vec<SnapshotSstFile>")]
     pub files: Vec<LiveFile>,
+
+    /// The wrapped data keys which encrypt the values of an encrypted partition store.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub data_keys: Vec<WrappedDataKey>,
+
+    /// How the snapshot files are encrypted, if they are.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_encryption: Option<ArchiveEncryption>,
 }
 
 /// A locally-stored partition snapshot.
@@ -71,6 +81,8 @@ pub struct LocalPartitionSnapshot {
     pub db_comparator_name: String,
     pub files: Vec<LiveFile>,
     pub key_range: RangeInclusive<PartitionKey>,
+    /// The data keys needed to decrypt the values, empty if the values are not encrypted
+    pub data_keys: Vec<WrappedDataKey>,
 }
 
 /// RocksDB SST file that is part of a snapshot. Serialization wrapper around [LiveFile].
@@ -60,9 +60,9 @@ fn put_user_state<S: StorageAccess>(
     service_id: &ServiceId,
     state_key: impl AsRef<[u8]>,
     state_value: impl AsRef<[u8]>,
-) {
+) -> Result<()> {
     let key = write_state_entry_key(service_id, state_key);
-    storage.put_kv_raw(key, state_value.as_ref());
+    storage.put_kv_raw(key, state_value.as_ref())
 }
 
 fn delete_user_state<S: StorageAccess>(
@@ -124,7 +124,8 @@ fn get_all_user_states<S: StorageAccess>(
 ) -> impl Stream<Item = Result<(ServiceId, Bytes, Bytes)>> + Send + '_ {
     let _x = RocksDbPerfGuard::new("get-all-user-state");
     let iter = storage.iterator_from(TableScan::FullScanPartitionKeyRange::<StateKey>(range));
-    stream::iter(OwnedIterator::new(iter).map(|(mut key, value)| {
+    stream::iter(OwnedIterator::new(iter, storage.cipher()).map(|item| {
+        let (mut key, value) = item?;
         let row_key = StateKey::deserialize_from(&mut key)?;
         let (partition_key, service_name, service_key, state_key) = row_key.into_inner_ok_or()?;
 
@@ -190,7 +191,8 @@ impl<'a> StateTable for PartitionStoreTransaction<'a> {
         state_value: impl AsRef<[u8]>,
     ) -> impl Future<Output = ()> + Send {
         self.assert_partition_key(service_id);
-        put_user_state(self, service_id, state_key, state_value);
+        let result = put_user_state(self, service_id, state_key, state_value);
+        self.record_write(result);
         future::ready(())
     }
 
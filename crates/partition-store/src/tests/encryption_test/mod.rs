@@ -0,0 +1,104 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use rocksdb::IteratorMode;
+use tempfile::tempdir;
+
+use super::storage_test_environment_with_manager;
+use crate::encryption::{LocalKeyProvider, ValueCipher, ENCRYPTED_MARKER_KEY};
+use restate_storage_api::state_table::{ReadOnlyStateTable, StateTable};
+use restate_storage_api::Transaction;
+use restate_types::config::WorkerOptions;
+use restate_types::identifiers::{PartitionId, PartitionKey, ServiceId};
+use restate_types::live::Live;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn encrypt_existing_partition_store() {
+    let (manager, mut partition_store) = storage_test_environment_with_manager().await;
+    let service_id = ServiceId::with_partition_key(1337, "svc-1", "key-1");
+
+    let mut txn = partition_store.transaction();
+    txn.put_user_state(&service_id, b"k1", b"v1").await;
+    txn.commit().await.expect("commit succeeds");
+
+    let snapshots_dir = tempdir().unwrap();
+    let snapshot = partition_store
+        .create_snapshot(snapshots_dir.path().join("plaintext"))
+        .await
+        .unwrap();
+    assert!(snapshot.data_keys.is_empty());
+
+    drop(partition_store);
+    manager.drop_partition(PartitionId::MIN).await;
+
+    let keys_dir = tempdir().unwrap();
+    let cipher = Arc::new(
+        ValueCipher::open(
+            Arc::new(LocalKeyProvider::from_kms_directory(keys_dir.path(), None).unwrap()),
+            keys_dir.path().join("keyring.json"),
+        )
+        .unwrap(),
+    );
+    let manager = manager.with_encryption(Arc::clone(&cipher));
+
+    let worker_options = Live::from_value(WorkerOptions::default());
+    let mut partition_store = manager
+        .open_partition_store_from_snapshot(
+            PartitionId::MIN,
+            RangeInclusive::new(0, PartitionKey::MAX - 1),
+            snapshot,
+            &worker_options.pinned().storage.rocksdb,
+        )
+        .await
+        .unwrap();
+
+    // values written before and after enabling encryption can be read
+    let mut txn = partition_store.transaction();
+    txn.put_user_state(&service_id, b"k2", b"v2").await;
+    txn.commit().await.expect("commit succeeds");
+
+    assert_eq!(
+        Some(Bytes::from_static(b"v1")),
+        partition_store
+            .get_user_state(&service_id, b"k1")
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        Some(Bytes::from_static(b"v2")),
+        partition_store
+            .get_user_state(&service_id, b"k2")
+            .await
+            .unwrap()
+    );
+
+    // all values are encrypted at rest
+    let raw_db = partition_store.inner();
+    let cf = raw_db.cf_handle("data-0").unwrap();
+    let mut num_values = 0;
+    for item in raw_db.iterator_cf(&cf, IteratorMode::Start) {
+        let (key, value) = item.unwrap();
+        if key.as_ref() != ENCRYPTED_MARKER_KEY {
+            assert!(cipher.decrypt(&key, &value).is_ok());
+            num_values += 1;
+        }
+    }
+    assert!(num_values >= 2);
+
+    let snapshot = partition_store
+        .create_snapshot(snapshots_dir.path().join("encrypted"))
+        .await
+        .unwrap();
+    assert_eq!(cipher.export_data_keys(), snapshot.data_keys);
+}
@@ -226,7 +226,8 @@ async fn test_migration() {
             .partition_key(invocation_id.partition_key())
             .invocation_uuid(invocation_id.invocation_uuid()),
         &InvocationStatusV1(status.clone()),
-    );
+    )
+    .unwrap();
     txn.commit().await.unwrap();
 
     // Make sure we can read without mutating
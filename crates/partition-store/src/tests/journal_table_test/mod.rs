@@ -116,6 +116,29 @@ async fn point_lookups<T: JournalTable>(txn: &mut T) {
     assert!(result.is_none());
 }
 
+async fn truncate_journal<T: JournalTable>(txn: &mut T) {
+    txn.truncate_journal(&MOCK_INVOCATION_ID_1, 3, 5).await;
+}
+
+async fn verify_journal_truncated<T: JournalTable>(txn: &mut T) {
+    for i in 0..3 {
+        let result = txn
+            .get_journal_entry(&MOCK_INVOCATION_ID_1, i)
+            .await
+            .expect("should not fail");
+
+        assert!(result.is_some());
+    }
+    for i in 3..5 {
+        let result = txn
+            .get_journal_entry(&MOCK_INVOCATION_ID_1, i)
+            .await
+            .expect("should not fail");
+
+        assert!(result.is_none());
+    }
+}
+
 async fn delete_journal<T: JournalTable>(txn: &mut T) {
     txn.delete_journal(&MOCK_INVOCATION_ID_1, 5).await;
 }
@@ -148,3 +171,19 @@ async fn journal_tests() {
     let mut txn = rocksdb.transaction();
     verify_journal_deleted(&mut txn).await;
 }
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn truncate_journal_tests() {
+    let mut rocksdb = storage_test_environment().await;
+
+    let mut txn = rocksdb.transaction();
+    populate_data(&mut txn).await;
+    txn.commit().await.expect("should not fail");
+
+    let mut txn = rocksdb.transaction();
+    truncate_journal(&mut txn).await;
+    txn.commit().await.expect("should not fail");
+
+    let mut txn = rocksdb.transaction();
+    verify_journal_truncated(&mut txn).await;
+}
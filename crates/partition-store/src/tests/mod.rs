@@ -27,11 +27,13 @@ use restate_types::invocation::{InvocationTarget, ServiceInvocation, Source};
 use restate_types::live::{Constant, Live};
 use restate_types::state_mut::ExternalStateMutation;
 
+mod encryption_test;
 mod idempotency_table_test;
 mod inbox_table_test;
 mod invocation_status_table_test;
 mod journal_table_test;
 mod outbox_table_test;
+mod parked_kafka_record_table_test;
 mod promise_table_test;
 mod snapshots_test;
 mod state_table_test;
@@ -82,6 +84,7 @@ async fn test_read_write() {
     virtual_object_status_table_test::run_tests(store.clone()).await;
     timer_table_test::run_tests(store.clone()).await;
     snapshots_test::run_tests(manager.clone(), store.clone()).await;
+    snapshots_test::run_merge_tests(manager.clone()).await;
 }
 
 pub(crate) fn mock_service_invocation(service_id: ServiceId) -> ServiceInvocation {
@@ -0,0 +1,73 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::{assert_stream_eq, storage_test_environment};
+
+use bytes::Bytes;
+use restate_storage_api::parked_kafka_record_table::{
+    ParkedKafkaRecord, ParkedKafkaRecordTable, ReadOnlyParkedKafkaRecordTable,
+};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{PartitionKey, SubscriptionId};
+use restate_types::invocation::Header;
+use restate_types::time::MillisSinceEpoch;
+
+fn parked_record(
+    partition_key: PartitionKey,
+    subscription_id: SubscriptionId,
+    offset: i64,
+) -> ParkedKafkaRecord {
+    ParkedKafkaRecord {
+        partition_key,
+        subscription_id,
+        topic: "orders".into(),
+        kafka_partition: 3,
+        offset,
+        deduplication_index: offset as u64,
+        key: Bytes::from_static(&[0xff, 0xfe]),
+        payload: Bytes::from_static(b"{}"),
+        headers: vec![Header::new("kafka.offset", offset.to_string())],
+        error: "The Kafka record key must be valid UTF-8".into(),
+        parked_at: MillisSinceEpoch::new(1),
+    }
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_parked_kafka_record_table() {
+    let mut rocksdb = storage_test_environment().await;
+
+    let subscription_id = SubscriptionId::new();
+    let record_1 = parked_record(10, subscription_id, 1);
+    let record_2 = parked_record(10, subscription_id, 2);
+    let record_3 = parked_record(11, SubscriptionId::new(), 1);
+
+    let mut txn = rocksdb.transaction();
+    txn.put_parked_kafka_record(&record_2).await;
+    txn.put_parked_kafka_record(&record_1).await;
+    txn.put_parked_kafka_record(&record_3).await;
+    txn.commit().await.unwrap();
+
+    // Records of the same Kafka partition are ordered by their offset
+    assert_stream_eq(
+        rocksdb.all_parked_kafka_records(10..=10),
+        vec![record_1.clone(), record_2.clone()],
+    )
+    .await;
+
+    let mut txn = rocksdb.transaction();
+    txn.delete_parked_kafka_record(&record_1).await;
+    txn.commit().await.unwrap();
+
+    assert_stream_eq(
+        rocksdb.all_parked_kafka_records(0..=PartitionKey::MAX - 1),
+        vec![record_2, record_3],
+    )
+    .await;
+}
@@ -13,11 +13,11 @@ use std::time::SystemTime;
 use tempfile::tempdir;
 
 use crate::snapshots::{LocalPartitionSnapshot, PartitionSnapshotMetadata, SnapshotFormatVersion};
-use crate::{PartitionStore, PartitionStoreManager};
+use crate::{OpenMode, PartitionStore, PartitionStoreManager};
 use restate_storage_api::fsm_table::{FsmTable, ReadOnlyFsmTable};
 use restate_storage_api::Transaction;
 use restate_types::config::WorkerOptions;
-use restate_types::identifiers::{PartitionKey, SnapshotId};
+use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId};
 use restate_types::live::Live;
 use restate_types::logs::Lsn;
 use restate_types::time::MillisSinceEpoch;
@@ -44,6 +44,8 @@ pub(crate) async fn run_tests(manager: PartitionStoreManager, mut partition_stor
         min_applied_lsn: snapshot.min_applied_lsn,
         db_comparator_name: snapshot.db_comparator_name.clone(),
         files: snapshot.files.clone(),
+        data_keys: snapshot.data_keys.clone(),
+        archive_encryption: None,
     };
     let metadata_json = serde_json::to_string_pretty(&snapshot_meta).unwrap();
 
@@ -60,6 +62,7 @@ pub(crate) async fn run_tests(manager: PartitionStoreManager, mut partition_stor
         db_comparator_name: snapshot_meta.db_comparator_name.clone(),
         files: snapshot_meta.files.clone(),
         key_range,
+        data_keys: snapshot_meta.data_keys.clone(),
     };
 
     let worker_options = Live::from_value(WorkerOptions::default());
@@ -77,6 +80,64 @@ pub(crate) async fn run_tests(manager: PartitionStoreManager, mut partition_stor
     verify_restored_data(&mut new_partition_store).await;
 }
 
+pub(crate) async fn run_merge_tests(manager: PartitionStoreManager) {
+    let snapshots_dir = tempdir().unwrap();
+    let worker_options = Live::from_value(WorkerOptions::default());
+    let rocksdb_opts = &worker_options.pinned().storage.rocksdb;
+
+    let parents = [
+        (PartitionId::from(10), RangeInclusive::new(0, 999), 5),
+        (PartitionId::from(11), RangeInclusive::new(1000, 1999), 7),
+    ];
+
+    let mut snapshots = Vec::new();
+    for (parent_id, key_range, inbox_seq_number) in parents {
+        let mut partition_store = manager
+            .open_partition_store(
+                parent_id,
+                key_range,
+                OpenMode::CreateIfMissing,
+                rocksdb_opts,
+            )
+            .await
+            .unwrap();
+
+        let mut txn = partition_store.transaction();
+        txn.put_applied_lsn(Lsn::new(100)).await;
+        txn.put_inbox_seq_number(inbox_seq_number).await;
+        txn.commit().await.expect("commit succeeds");
+
+        let snapshot = partition_store
+            .create_snapshot(snapshots_dir.path().join(parent_id.to_string()))
+            .await
+            .unwrap();
+        snapshots.push((parent_id, snapshot));
+
+        drop(partition_store);
+        manager.drop_partition(parent_id).await;
+    }
+
+    let mut merged_partition_store = manager
+        .open_partition_store_from_parent_snapshots(
+            PartitionId::from(12),
+            RangeInclusive::new(0, 1999),
+            snapshots,
+            rocksdb_opts,
+        )
+        .await
+        .unwrap();
+
+    // the merged partition starts reading its own log from the beginning
+    assert_eq!(
+        None,
+        merged_partition_store.get_applied_lsn().await.unwrap()
+    );
+    assert_eq!(
+        7,
+        merged_partition_store.get_inbox_seq_number().await.unwrap()
+    );
+}
+
 async fn insert_test_data(partition: &mut PartitionStore) {
     let mut txn = partition.transaction();
     txn.put_applied_lsn(Lsn::new(100)).await;
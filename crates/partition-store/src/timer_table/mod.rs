@@ -12,7 +12,9 @@ use futures::Stream;
 use futures_util::stream;
 
 use restate_rocksdb::RocksDbPerfGuard;
-use restate_storage_api::timer_table::{Timer, TimerKey, TimerKeyKind, TimerTable};
+use restate_storage_api::timer_table::{
+    ReadOnlyTimerTable, Timer, TimerKey, TimerKeyKind, TimerTable,
+};
 use restate_storage_api::{Result, StorageError};
 use restate_types::identifiers::{InvocationUuid, PartitionId};
 use restate_types::storage::StorageCodec;
@@ -139,10 +141,10 @@ fn add_timer<S: StorageAccess>(
     partition_id: PartitionId,
     key: &TimerKey,
     timer: &Timer,
-) {
+) -> Result<()> {
     let key = write_timer_key(partition_id, key);
 
-    storage.put_kv(key, timer);
+    storage.put_kv(key, timer)
 }
 
 fn delete_timer<S: StorageAccess>(storage: &mut S, partition_id: PartitionId, key: &TimerKey) {
@@ -169,9 +171,26 @@ fn next_timers_greater_than<S: StorageAccess>(
     })
 }
 
+fn all_timers<S: StorageAccess>(
+    storage: &S,
+    partition_id: PartitionId,
+) -> impl Stream<Item = Result<(TimerKey, Timer)>> + Send {
+    stream::iter(storage.for_each_key_value_in_place(
+        TableScan::SinglePartition::<TimersKey>(partition_id),
+        |k, v| Emit(decode_seq_timer_key_value(k, v)),
+    ))
+}
+
+impl ReadOnlyTimerTable for PartitionStore {
+    fn all_timers(&self) -> impl Stream<Item = Result<(TimerKey, Timer)>> + Send {
+        all_timers(self, self.partition_id())
+    }
+}
+
 impl TimerTable for PartitionStore {
     async fn put_timer(&mut self, key: &TimerKey, timer: &Timer) {
         add_timer(self, self.partition_id(), key, timer)
+            .expect("direct writes to the partition store must not fail")
     }
 
     async fn delete_timer(&mut self, key: &TimerKey) {
@@ -192,9 +211,16 @@ impl TimerTable for PartitionStore {
     }
 }
 
+impl<'a> ReadOnlyTimerTable for PartitionStoreTransaction<'a> {
+    fn all_timers(&self) -> impl Stream<Item = Result<(TimerKey, Timer)>> + Send {
+        all_timers(self, self.partition_id())
+    }
+}
+
 impl<'a> TimerTable for PartitionStoreTransaction<'a> {
     async fn put_timer(&mut self, key: &TimerKey, timer: &Timer) {
-        add_timer(self, self.partition_id(), key, timer)
+        let result = add_timer(self, self.partition_id(), key, timer);
+        self.record_write(result)
     }
 
     async fn delete_timer(&mut self, key: &TimerKey) {
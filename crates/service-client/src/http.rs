@@ -10,6 +10,7 @@
 
 use super::proxy::ProxyConnector;
 
+use crate::tls::{build_client_config, TlsConfigError};
 use crate::utils::ErrorExt;
 
 use bytes::Bytes;
@@ -24,7 +25,7 @@ use hyper::http::HeaderValue;
 use hyper::{HeaderMap, Method, Request, Response, Uri};
 use hyper_rustls::{ConfigBuilderExt, HttpsConnector};
 use hyper_util::client::legacy::connect::HttpConnector;
-use restate_types::config::HttpOptions;
+use restate_types::config::{HttpOptions, TlsClientOptions};
 use rustls::ClientConfig;
 use std::error::Error;
 use std::fmt::Debug;
@@ -46,7 +47,7 @@ static TLS_CLIENT_CONFIG: LazyLock<ClientConfig> = LazyLock::new(|| {
 //  for the time being we use BoxBody here to simplify the migration to hyper 1.0.
 //  We should consider replacing this with some concrete type that makes sense.
 type BoxError = Box<dyn Error + Send + Sync + 'static>;
-type BoxBody = http_body_util::combinators::BoxBody<Bytes, BoxError>;
+pub(crate) type BoxBody = http_body_util::combinators::BoxBody<Bytes, BoxError>;
 
 #[derive(Clone, Debug)]
 pub struct HttpClient {
@@ -64,7 +65,17 @@ pub struct HttpClient {
 }
 
 impl HttpClient {
-    pub fn from_options(options: &HttpOptions) -> HttpClient {
+    /// Creates a client using the given TLS options instead of the ones in `options`.
+    pub fn with_tls_options(
+        options: &HttpOptions,
+        tls_options: &TlsClientOptions,
+    ) -> Result<HttpClient, TlsConfigError> {
+        let tls_config = if tls_options.is_default() {
+            TLS_CLIENT_CONFIG.clone()
+        } else {
+            build_client_config(tls_options)?
+        };
+
         let mut builder =
             hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::default());
         builder.timer(hyper_util::rt::TokioTimer::default());
@@ -79,13 +90,13 @@ impl HttpClient {
         http_connector.set_connect_timeout(Some(options.connect_timeout.into()));
 
         let https_connector = hyper_rustls::HttpsConnectorBuilder::new()
-            .with_tls_config(TLS_CLIENT_CONFIG.clone())
+            .with_tls_config(tls_config)
             .https_or_http()
             .enable_http1()
             .enable_http2()
             .wrap_connector(http_connector.clone());
 
-        HttpClient {
+        Ok(HttpClient {
             client: builder.clone().build::<_, BoxBody>(ProxyConnector::new(
                 options.http_proxy.clone(),
                 options.no_proxy.clone(),
@@ -99,10 +110,10 @@ impl HttpClient {
                     http_connector,
                 ))
             },
-        }
+        })
     }
 
-    fn build_request<B>(
+    pub(crate) fn build_request<B>(
         uri: Uri,
         version: Option<Version>,
         body: B,
@@ -191,7 +202,7 @@ impl HttpClient {
     }
 }
 
-fn is_possible_h11_only_error(err: &hyper_util::client::legacy::Error) -> bool {
+pub(crate) fn is_possible_h11_only_error(err: &hyper_util::client::legacy::Error) -> bool {
     // this is the error we see from the h2 lib when the server sends back an http1.1 response
     // to an http2 request. http2 is designed to start requests with what looks like an invalid
     // HTTP1.1 method, so typically 1.1 servers respond with a 40x, and the h2 client sees
@@ -210,6 +221,8 @@ pub enum HttpError {
     Http(#[from] http::Error),
     #[error("server possibly only supports HTTP1.1, consider discovery with --use-http1.1: {0}")]
     PossibleHTTP11Only(#[source] hyper_util::client::legacy::Error),
+    #[error("cannot configure TLS: {0}")]
+    Tls(#[from] TlsConfigError),
 }
 
 impl HttpError {
@@ -220,6 +233,8 @@ impl HttpError {
             HttpError::Hyper(err) => err.is_retryable(),
             HttpError::Http(err) => err.is_retryable(),
             HttpError::PossibleHTTP11Only(_) => false,
+            // the certificate files might be fixed or rotated in the meantime
+            HttpError::Tls(_) => true,
         }
     }
 }
@@ -10,12 +10,15 @@
 
 use crate::http::HttpClient;
 use crate::lambda::LambdaClient;
+use crate::unix::UnixClient;
 
 pub use crate::http::HttpError;
 pub use crate::lambda::AssumeRoleCacheMode;
 use crate::request_identity::SignRequest;
+use crate::tls::CertificateFilesVersion;
+pub use crate::tls::TlsConfigError;
 use ::http::Version;
-use arc_swap::ArcSwapOption;
+use arc_swap::{ArcSwap, ArcSwapOption};
 use bytes::Bytes;
 use bytestring::ByteString;
 use core::fmt;
@@ -25,19 +28,26 @@ use hyper::body::Body;
 use hyper::header::HeaderValue;
 use hyper::http::uri::PathAndQuery;
 use hyper::{HeaderMap, Response, Uri};
-use restate_types::config::ServiceClientOptions;
+use parking_lot::Mutex;
+use restate_types::config::{HttpOptions, ServiceClientOptions, TlsClientOptions};
 use restate_types::identifiers::LambdaARN;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::Formatter;
 use std::future;
 use std::future::Future;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
 
 mod aws_hyper_client;
 mod http;
 mod lambda;
 mod proxy;
 mod request_identity;
+mod tls;
+mod unix;
 mod utils;
 
 pub type ResponseBody = http_body_util::Either<hyper::body::Incoming, Full<Bytes>>;
@@ -46,7 +56,12 @@ pub type ResponseBody = http_body_util::Either<hyper::body::Incoming, Full<Bytes
 pub struct ServiceClient {
     // TODO a single client uses the pooling provided by hyper, but this is not enough.
     //  See https://github.com/restatedev/restate/issues/76 for more background on the topic.
-    http: HttpClient,
+    http: Arc<ReloadingHttpClient>,
+    http_options: HttpOptions,
+    /// Clients for deployments overriding the TLS options, keyed by the effective options.
+    tls_override_clients: Arc<Mutex<HashMap<TlsClientOptions, Arc<ReloadingHttpClient>>>>,
+    /// Clients for deployments listening on Unix domain sockets, keyed by the socket path.
+    unix_clients: Arc<Mutex<HashMap<PathBuf, UnixClient>>>,
     lambda: LambdaClient,
     // this can be changed to re-read periodically if necessary
     request_identity_key: Arc<ArcSwapOption<request_identity::v1::SigningKey>>,
@@ -54,12 +69,16 @@ pub struct ServiceClient {
 
 impl ServiceClient {
     pub(crate) fn new(
-        http: HttpClient,
+        http: Arc<ReloadingHttpClient>,
+        http_options: HttpOptions,
         lambda: LambdaClient,
         request_identity_key: Arc<ArcSwapOption<request_identity::v1::SigningKey>>,
     ) -> Self {
         Self {
             http,
+            http_options,
+            tls_override_clients: Arc::default(),
+            unix_clients: Arc::default(),
             lambda,
             request_identity_key,
         }
@@ -82,20 +101,180 @@ impl ServiceClient {
         };
 
         Ok(Self::new(
-            HttpClient::from_options(&options.http),
+            Arc::new(ReloadingHttpClient::new(
+                &options.http,
+                options.http.tls.clone(),
+            )?),
+            options.http.clone(),
             LambdaClient::from_options(&options.lambda, assume_role_cache_mode),
             request_identity_key,
         ))
     }
 }
 
+/// Checks that the certificate files of the given TLS options can be loaded. This blocks.
+pub fn validate_tls_options(options: &TlsClientOptions) -> Result<(), TlsConfigError> {
+    tls::build_client_config(options).map(|_| ())
+}
+
+/// How often the certificate files of the HTTP clients are checked for changes.
+const CERTIFICATE_FILES_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// HTTP client which is recreated once its TLS certificate files change, e.g. because a
+/// certificate was renewed. The files are checked on a blocking thread, requests keep using the
+/// current client meanwhile.
+#[derive(Debug)]
+struct ReloadingHttpClient {
+    http_options: HttpOptions,
+    tls_options: TlsClientOptions,
+    client: ArcSwap<HttpClient>,
+    reload_state: Mutex<ReloadState>,
+}
+
+#[derive(Debug)]
+struct ReloadState {
+    files_version: CertificateFilesVersion,
+    checked_at: Instant,
+    reloading: bool,
+}
+
+impl ReloadingHttpClient {
+    /// Loads the certificate files, this blocks.
+    fn new(
+        http_options: &HttpOptions,
+        tls_options: TlsClientOptions,
+    ) -> Result<Self, TlsConfigError> {
+        let files_version = CertificateFilesVersion::read(&tls_options);
+        let client = HttpClient::with_tls_options(http_options, &tls_options)?;
+        Ok(Self {
+            http_options: http_options.clone(),
+            tls_options,
+            client: ArcSwap::from_pointee(client),
+            reload_state: Mutex::new(ReloadState {
+                files_version,
+                checked_at: Instant::now(),
+                reloading: false,
+            }),
+        })
+    }
+
+    fn client(self: &Arc<Self>) -> Arc<HttpClient> {
+        self.check_certificate_files();
+        self.client.load_full()
+    }
+
+    fn check_certificate_files(self: &Arc<Self>) {
+        // without certificate files the native root certificates are used
+        if self.tls_options.is_default() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        {
+            let mut reload_state = self.reload_state.lock();
+            if reload_state.reloading
+                || reload_state.checked_at.elapsed() < CERTIFICATE_FILES_CHECK_INTERVAL
+            {
+                return;
+            }
+            reload_state.reloading = true;
+        }
+
+        let this = Arc::clone(self);
+        runtime.spawn_blocking(move || this.reload_changed_certificate_files());
+    }
+
+    fn reload_changed_certificate_files(&self) {
+        let files_version = CertificateFilesVersion::read(&self.tls_options);
+        let changed = files_version != self.reload_state.lock().files_version;
+        let files_version = if changed {
+            match HttpClient::with_tls_options(&self.http_options, &self.tls_options) {
+                Ok(client) => {
+                    debug!(
+                        "Reloaded the changed TLS certificate files of {:?}",
+                        self.tls_options
+                    );
+                    self.client.store(Arc::new(client));
+                    Some(files_version)
+                }
+                Err(err) => {
+                    // The files might still be being written, keep the previous client until
+                    // they can be loaded
+                    warn!(
+                        "Failed reloading the changed TLS certificate files of {:?}: {err}",
+                        self.tls_options
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut reload_state = self.reload_state.lock();
+        if let Some(files_version) = files_version {
+            reload_state.files_version = files_version;
+        }
+        reload_state.checked_at = Instant::now();
+        reload_state.reloading = false;
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum BuildError {
     #[error("Failed to read request identity private key: {0}")]
     SigningPrivateKeyReadError(#[from] request_identity::v1::SigningPrivateKeyReadError),
+    #[error("Failed to configure TLS: {0}")]
+    Tls(#[from] TlsConfigError),
 }
 
 impl ServiceClient {
+    /// Returns the client for the effective TLS options. Clients for new TLS overrides load their
+    /// certificate files on a blocking thread.
+    fn http_client(
+        &self,
+        tls_overrides: Option<&TlsClientOptions>,
+    ) -> impl Future<Output = Result<Arc<HttpClient>, HttpError>> + Send + 'static {
+        let tls_options = match tls_overrides {
+            Some(tls_overrides) => self.http_options.tls.merge(tls_overrides),
+            None => self.http_options.tls.clone(),
+        };
+        if tls_options == self.http_options.tls {
+            return future::ready(Ok(self.http.client())).left_future();
+        }
+
+        if let Some(cached) = self.tls_override_clients.lock().get(&tls_options) {
+            return future::ready(Ok(cached.client())).left_future();
+        }
+
+        let clients = Arc::clone(&self.tls_override_clients);
+        let http_options = self.http_options.clone();
+        async move {
+            let client = tokio::task::spawn_blocking(move || {
+                let client = ReloadingHttpClient::new(&http_options, tls_options.clone())?;
+                Ok::<_, TlsConfigError>(Arc::clone(
+                    clients
+                        .lock()
+                        .entry(tls_options)
+                        .or_insert_with(|| Arc::new(client)),
+                ))
+            })
+            .await
+            .expect("loading the TLS certificate files does not panic")?;
+            Ok(client.client())
+        }
+        .right_future()
+    }
+
+    fn unix_client(&self, socket_path: PathBuf) -> UnixClient {
+        self.unix_clients
+            .lock()
+            .entry(socket_path)
+            .or_insert_with_key(|socket_path| UnixClient::new(&self.http_options, socket_path))
+            .clone()
+    }
+
     pub fn call<B>(
         &self,
         req: Request<B>,
@@ -124,15 +303,35 @@ impl ServiceClient {
 
         match parts.address {
             Endpoint::Http(uri, version) => {
-                let fut = self.http.request(
-                    uri,
+                let http = self.http_client(parts.tls.as_ref());
+                async move {
+                    let response = http
+                        .await?
+                        .request(
+                            uri,
+                            version,
+                            parts.method.into(),
+                            body,
+                            parts.path,
+                            parts.headers,
+                        )
+                        .await?;
+                    Ok(response.map(http_body_util::Either::Left))
+                }
+                .left_future()
+                .left_future()
+            }
+            Endpoint::Unix(socket_path, version) => {
+                let fut = self.unix_client(socket_path).request(
                     version,
                     parts.method.into(),
                     body,
                     parts.path,
                     parts.headers,
                 );
-                async move { Ok(fut.await?.map(http_body_util::Either::Left)) }.left_future()
+                async move { Ok(fut.await?.map(http_body_util::Either::Left)) }
+                    .right_future()
+                    .left_future()
             }
             Endpoint::Lambda(arn, assume_role_arn) => {
                 let fut = self.lambda.invoke(
@@ -223,6 +422,9 @@ pub struct Parts {
 
     /// The request's headers - in lambda case, mapped to apigatewayevent.headers
     headers: HeaderMap<HeaderValue>,
+
+    /// Overrides of the client's TLS options, only used for HTTPS endpoints
+    tls: Option<TlsClientOptions>,
 }
 
 impl Parts {
@@ -237,13 +439,20 @@ impl Parts {
             address,
             path,
             headers,
+            tls: None,
         }
     }
+
+    pub fn with_tls(mut self, tls: Option<TlsClientOptions>) -> Self {
+        self.tls = tls;
+        self
+    }
 }
 
 #[derive(Clone, Debug)]
 pub enum Endpoint {
     Http(Uri, Option<Version>),
+    Unix(PathBuf, Option<Version>),
     Lambda(LambdaARN, Option<ByteString>),
 }
 
@@ -251,6 +460,7 @@ impl fmt::Display for Endpoint {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Self::Http(uri, _) => uri.fmt(f),
+            Self::Unix(socket_path, _) => write!(f, "unix://{}", socket_path.display()),
             Self::Lambda(arn, _) => write!(f, "lambda://{arn}"),
         }
     }
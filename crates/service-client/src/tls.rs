@@ -0,0 +1,214 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use hyper_rustls::ConfigBuilderExt;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::{ClientConfig, RootCertStore};
+
+use restate_types::config::TlsClientOptions;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TlsConfigError {
+    #[error("failed reading '{path}': {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("no certificate found in '{0}'")]
+    NoCertificate(PathBuf),
+    #[error("no private key found in '{0}'")]
+    NoPrivateKey(PathBuf),
+    #[error("client certificate and client key must be configured together")]
+    IncompleteClientIdentity,
+    #[error("cannot load native root certificates: {0}")]
+    NativeRoots(#[source] std::io::Error),
+    #[error(transparent)]
+    Rustls(#[from] rustls::Error),
+}
+
+/// Builds the client config for the given options. If a CA bundle is configured, only its
+/// certificates are trusted, otherwise the native root certificates are used.
+pub(crate) fn build_client_config(
+    options: &TlsClientOptions,
+) -> Result<ClientConfig, TlsConfigError> {
+    let builder =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()?;
+
+    let builder = if let Some(ca_bundle_path) = &options.ca_bundle_path {
+        let mut roots = RootCertStore::empty();
+        for cert in load_certs(ca_bundle_path)? {
+            roots.add(cert)?;
+        }
+        builder.with_root_certificates(roots)
+    } else {
+        builder
+            .with_native_roots()
+            .map_err(TlsConfigError::NativeRoots)?
+    };
+
+    match (&options.client_cert_path, &options.client_key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            Ok(builder
+                .with_client_auth_cert(load_certs(cert_path)?, load_private_key(key_path)?)?)
+        }
+        (None, None) => Ok(builder.with_no_client_auth()),
+        _ => Err(TlsConfigError::IncompleteClientIdentity),
+    }
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, TlsConfigError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|source| TlsConfigError::Io {
+            path: path.to_owned(),
+            source,
+        })
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, TlsConfigError> {
+    let certs = rustls_pemfile::certs(&mut open(path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|source| TlsConfigError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+
+    if certs.is_empty() {
+        return Err(TlsConfigError::NoCertificate(path.to_owned()));
+    }
+
+    Ok(certs)
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>, TlsConfigError> {
+    rustls_pemfile::private_key(&mut open(path)?)
+        .map_err(|source| TlsConfigError::Io {
+            path: path.to_owned(),
+            source,
+        })?
+        .ok_or_else(|| TlsConfigError::NoPrivateKey(path.to_owned()))
+}
+
+/// Modification times of the certificate and key files configured by the options. A changed
+/// version means that some of the files were replaced, e.g. because a certificate was renewed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CertificateFilesVersion(Vec<Option<SystemTime>>);
+
+impl CertificateFilesVersion {
+    pub(crate) fn read(options: &TlsClientOptions) -> Self {
+        Self(
+            [
+                &options.ca_bundle_path,
+                &options.client_cert_path,
+                &options.client_key_path,
+            ]
+            .into_iter()
+            .map(|path| {
+                path.as_ref()
+                    .and_then(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+            })
+            .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+    use std::time::Duration;
+
+    #[test]
+    fn missing_ca_bundle() {
+        let options = TlsClientOptions {
+            ca_bundle_path: Some("/does/not/exist.pem".into()),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            build_client_config(&options),
+            Err(TlsConfigError::Io { path, .. }) if path == Path::new("/does/not/exist.pem")
+        ));
+    }
+
+    #[test]
+    fn empty_ca_bundle() {
+        let mut ca_file = tempfile::NamedTempFile::new().unwrap();
+        ca_file.write_all(b"not a pem file").unwrap();
+        let options = TlsClientOptions {
+            ca_bundle_path: Some(ca_file.path().to_owned()),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            build_client_config(&options),
+            Err(TlsConfigError::NoCertificate(path)) if path == ca_file.path()
+        ));
+    }
+
+    #[test]
+    fn certificate_files_version_changes_with_the_files() {
+        let ca_bundle = tempfile::NamedTempFile::new().unwrap();
+        let options = TlsClientOptions {
+            ca_bundle_path: Some(ca_bundle.path().to_owned()),
+            ..Default::default()
+        };
+
+        let version = CertificateFilesVersion::read(&options);
+        assert_eq!(version, CertificateFilesVersion::read(&options));
+
+        ca_bundle
+            .as_file()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert_ne!(version, CertificateFilesVersion::read(&options));
+    }
+
+    #[test]
+    fn merge_overrides() {
+        let node = TlsClientOptions {
+            ca_bundle_path: Some("/node/ca.pem".into()),
+            client_cert_path: Some("/node/client.pem".into()),
+            client_key_path: Some("/node/client.key".into()),
+        };
+
+        let merged = node.merge(&TlsClientOptions {
+            ca_bundle_path: Some("/deployment/ca.pem".into()),
+            ..Default::default()
+        });
+        assert_eq!(merged.ca_bundle_path, Some("/deployment/ca.pem".into()));
+        assert_eq!(merged.client_cert_path, node.client_cert_path);
+        assert_eq!(merged.client_key_path, node.client_key_path);
+
+        let merged = node.merge(&TlsClientOptions {
+            client_cert_path: Some("/deployment/client.pem".into()),
+            client_key_path: Some("/deployment/client.key".into()),
+            ..Default::default()
+        });
+        assert_eq!(merged.ca_bundle_path, node.ca_bundle_path);
+        assert_eq!(
+            merged.client_cert_path,
+            Some("/deployment/client.pem".into())
+        );
+        assert_eq!(
+            merged.client_key_path,
+            Some("/deployment/client.key".into())
+        );
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::http::{is_possible_h11_only_error, BoxBody, HttpClient, HttpError};
+
+use bytes::Bytes;
+use futures::future::{BoxFuture, Either};
+use futures::FutureExt;
+use http::Version;
+use hyper::body::Body;
+use hyper::http::uri::PathAndQuery;
+use hyper::http::HeaderValue;
+use hyper::rt::{Read, ReadBufCursor, Write};
+use hyper::{HeaderMap, Method, Response, Uri};
+use hyper_util::client::legacy::connect::{Connected, Connection};
+use hyper_util::rt::TokioIo;
+use restate_types::config::HttpOptions;
+use std::error::Error;
+use std::future;
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_service::Service;
+
+/// Base URI of requests sent over a Unix domain socket. The host is only used for the `Host`
+/// header, since the connector always connects to its socket.
+const UNIX_BASE_URI: &str = "http://localhost/";
+
+/// HTTP client for a single deployment listening on a Unix domain socket.
+#[derive(Clone, Debug)]
+pub struct UnixClient {
+    /// Client used for HTTP/1.1.
+    client: hyper_util::client::legacy::Client<UnixConnector, BoxBody>,
+    /// Client used for HTTP/2 with prior knowledge, see [`HttpClient`] for the rationale.
+    h2_prior_knowledge_client: hyper_util::client::legacy::Client<UnixConnector, BoxBody>,
+}
+
+impl UnixClient {
+    pub fn new(options: &HttpOptions, socket_path: impl Into<PathBuf>) -> UnixClient {
+        let mut builder =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::default());
+        builder.timer(hyper_util::rt::TokioTimer::default());
+
+        builder
+            .http2_keep_alive_timeout(options.http_keep_alive_options.timeout.into())
+            .http2_keep_alive_interval(Some(options.http_keep_alive_options.interval.into()));
+
+        let connector = UnixConnector {
+            socket_path: Arc::new(socket_path.into()),
+        };
+
+        UnixClient {
+            client: builder.clone().build::<_, BoxBody>(connector.clone()),
+            h2_prior_knowledge_client: {
+                builder.http2_only(true);
+                builder.build::<_, BoxBody>(connector)
+            },
+        }
+    }
+
+    pub fn request<B>(
+        &self,
+        version: Option<Version>,
+        method: Method,
+        body: B,
+        path: PathAndQuery,
+        headers: HeaderMap<HeaderValue>,
+    ) -> impl Future<Output = Result<Response<hyper::body::Incoming>, HttpError>> + Send + 'static
+    where
+        B: Body<Data = Bytes> + Send + Sync + Unpin + Sized + 'static,
+        <B as Body>::Error: Error + Send + Sync + 'static,
+    {
+        let request = match HttpClient::build_request(
+            Uri::from_static(UNIX_BASE_URI),
+            version,
+            body,
+            method,
+            path,
+            headers,
+        ) {
+            Ok(request) => request,
+            Err(err) => return future::ready(Err(err.into())).right_future(),
+        };
+
+        let fut = match request.version() {
+            Version::HTTP_2 => self.h2_prior_knowledge_client.request(request),
+            _ => self.client.request(request),
+        };
+
+        Either::Left(async move {
+            match fut.await {
+                Ok(res) => Ok(res),
+                Err(err) if is_possible_h11_only_error(&err) => {
+                    Err(HttpError::PossibleHTTP11Only(err))
+                }
+                Err(err) => Err(HttpError::Hyper(err)),
+            }
+        })
+    }
+}
+
+/// Connects to a fixed socket path, regardless of the requested URI.
+#[derive(Clone, Debug)]
+struct UnixConnector {
+    socket_path: Arc<PathBuf>,
+}
+
+impl Service<Uri> for UnixConnector {
+    type Response = UnixConnection;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let socket_path = Arc::clone(&self.socket_path);
+        async move { UnixConnection::connect(&socket_path).await }.boxed()
+    }
+}
+
+struct UnixConnection(TokioIo<tokio::net::UnixStream>);
+
+impl UnixConnection {
+    async fn connect(socket_path: &Path) -> io::Result<Self> {
+        let stream = tokio::net::UnixStream::connect(socket_path).await?;
+        Ok(Self(TokioIo::new(stream)))
+    }
+}
+
+impl Connection for UnixConnection {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl Read for UnixConnection {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: ReadBufCursor<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl Write for UnixConnection {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.0.is_write_vectored()
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write_vectored(cx, bufs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http_body_util::{BodyExt, Empty};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixListener;
+
+    #[tokio::test]
+    async fn request_over_unix_socket() {
+        let socket_dir = tempfile::tempdir().unwrap();
+        let socket_path = socket_dir.path().join("deployment.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buf).await.unwrap();
+                assert_ne!(read, 0, "connection closed before the request was received");
+                request.extend_from_slice(&buf[..read]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let client = UnixClient::new(&HttpOptions::default(), &socket_path);
+        let response = client
+            .request(
+                None,
+                Method::GET,
+                Empty::<Bytes>::new(),
+                PathAndQuery::from_static("/discover"),
+                HeaderMap::new(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.into_body().collect().await.unwrap().to_bytes(),
+            Bytes::from_static(b"ok")
+        );
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("GET /discover HTTP/1.1\r\n"));
+        assert!(request.contains("host: localhost\r\n"));
+    }
+}
@@ -12,6 +12,7 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::ops::{Deref, RangeInclusive};
+use std::path::PathBuf;
 use std::sync::LazyLock;
 
 use bytes::Bytes;
@@ -29,6 +30,7 @@ use tracing::{debug, warn};
 
 use restate_errors::{META0003, META0012, META0013, META0014, META0015};
 use restate_service_client::{Endpoint, Method, Parts, Request, ServiceClient, ServiceClientError};
+use restate_types::config::TlsClientOptions;
 use restate_types::endpoint_manifest;
 use restate_types::errors::GenericError;
 use restate_types::identifiers::LambdaARN;
@@ -85,15 +87,31 @@ fn parse_service_discovery_protocol_version_from_content_type(
 }
 
 #[derive(Clone)]
-pub struct DiscoverEndpoint(Endpoint, HashMap<HeaderName, HeaderValue>);
+pub struct DiscoverEndpoint(
+    Endpoint,
+    HashMap<HeaderName, HeaderValue>,
+    Option<TlsClientOptions>,
+);
 
 impl DiscoverEndpoint {
     pub fn new(address: Endpoint, additional_headers: HashMap<HeaderName, HeaderValue>) -> Self {
-        Self(address, additional_headers)
+        Self(address, additional_headers, None)
     }
 
-    pub fn into_inner(self) -> (Endpoint, HashMap<HeaderName, HeaderValue>) {
-        (self.0, self.1)
+    /// Overrides the node's TLS client options when connecting to this endpoint.
+    pub fn with_tls(mut self, tls: Option<TlsClientOptions>) -> Self {
+        self.2 = tls;
+        self
+    }
+
+    pub fn into_inner(
+        self,
+    ) -> (
+        Endpoint,
+        HashMap<HeaderName, HeaderValue>,
+        Option<TlsClientOptions>,
+    ) {
+        (self.0, self.1, self.2)
     }
 
     pub fn address(&self) -> &Endpoint {
@@ -110,7 +128,7 @@ impl DiscoverEndpoint {
         headers.extend(self.1.clone());
         let path = PathAndQuery::from_static(DISCOVER_PATH);
         Request::new(
-            Parts::new(Method::GET, self.0.clone(), path, headers),
+            Parts::new(Method::GET, self.0.clone(), path, headers).with_tls(self.2.clone()),
             Empty::default(),
         )
     }
@@ -119,6 +137,7 @@ impl DiscoverEndpoint {
 #[derive(Clone, Debug)]
 pub enum DiscoveredEndpoint {
     Http(Uri, Version),
+    Unix(PathBuf, Version),
     Lambda(LambdaARN, Option<ByteString>),
 }
 
@@ -126,6 +145,7 @@ pub enum DiscoveredEndpoint {
 pub struct DiscoveredMetadata {
     pub endpoint: DiscoveredEndpoint,
     pub headers: HashMap<HeaderName, HeaderValue>,
+    pub tls: Option<TlsClientOptions>,
     pub protocol_type: ProtocolType,
     pub services: Vec<endpoint_manifest::Service>,
     // type is i32 because the generated ServiceProtocolVersion enum uses this as its representation
@@ -245,7 +265,7 @@ impl ServiceDiscovery {
             }
         };
 
-        let (address, headers) = endpoint.into_inner();
+        let (address, headers, tls) = endpoint.into_inner();
 
         Self::create_discovered_metadata_from_endpoint_response(
             address,
@@ -254,6 +274,7 @@ impl ServiceDiscovery {
             response,
             x_restate_server,
         )
+        .map(|metadata| DiscoveredMetadata { tls, ..metadata })
     }
 
     fn retrieve_service_discovery_protocol_version(
@@ -315,11 +336,18 @@ impl ServiceDiscovery {
             // all endpoints support request response
             (ProtocolType::RequestResponse, _, _) => {}
             // http2 upwards supports bidi
-            (ProtocolType::BidiStream, Endpoint::Http(_, _), Version::HTTP_2 | Version::HTTP_3) => {
-            }
+            (
+                ProtocolType::BidiStream,
+                Endpoint::Http(_, _) | Endpoint::Unix(_, _),
+                Version::HTTP_2 | Version::HTTP_3,
+            ) => {}
             // http1.1 *can* support bidi depending on server implementation (and load balancers)
             // trust the user if this is what they advertise
-            (ProtocolType::BidiStream, Endpoint::Http(_, _), Version::HTTP_11) => {}
+            (
+                ProtocolType::BidiStream,
+                Endpoint::Http(_, _) | Endpoint::Unix(_, _),
+                Version::HTTP_11,
+            ) => {}
             // lambda client and HTTP < 1.1 do not support bidi
             (ProtocolType::BidiStream, _, _) => {
                 return Err(DiscoveryError::BidirectionalNotSupported);
@@ -382,11 +410,15 @@ impl ServiceDiscovery {
         Ok(DiscoveredMetadata {
             endpoint: match endpoint {
                 Endpoint::Http(uri, _) => DiscoveredEndpoint::Http(uri, response_http_version),
+                Endpoint::Unix(socket_path, _) => {
+                    DiscoveredEndpoint::Unix(socket_path, response_http_version)
+                }
                 Endpoint::Lambda(arn, assume_role_arn) => {
                     DiscoveredEndpoint::Lambda(arn, assume_role_arn)
                 }
             },
             headers,
+            tls: None,
             protocol_type,
             services: endpoint_response.services,
             // we need to store the raw representation since the runtime might not know the latest
@@ -453,15 +485,18 @@ impl ServiceDiscovery {
 mod tests {
     use crate::discovery::endpoint_manifest::ProtocolMode;
     use crate::discovery::{
-        parse_service_discovery_protocol_version_from_content_type, DiscoveryError,
-        ServiceDiscovery, SERVICE_DISCOVERY_PROTOCOL_V1_HEADER_VALUE,
+        parse_service_discovery_protocol_version_from_content_type, DiscoveredEndpoint,
+        DiscoveredMetadata, DiscoveryError, ServiceDiscovery,
+        SERVICE_DISCOVERY_PROTOCOL_V1_HEADER_VALUE,
     };
     use http::{Uri, Version};
     use restate_service_client::Endpoint;
     use restate_types::endpoint_manifest;
+    use restate_types::schema::deployment::ProtocolType;
     use restate_types::service_discovery::ServiceDiscoveryProtocolVersion;
     use restate_types::service_protocol::MAX_SERVICE_PROTOCOL_VERSION;
     use std::collections::HashMap;
+    use std::path::Path;
 
     #[test]
     fn fail_on_invalid_min_protocol_version_with_bad_response() {
@@ -510,6 +545,31 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn accept_bidirectional_with_unix_socket() {
+        let response = endpoint_manifest::Endpoint {
+            min_protocol_version: 1,
+            max_protocol_version: 1,
+            services: Vec::new(),
+            protocol_mode: Some(ProtocolMode::BidiStream),
+        };
+
+        assert!(matches!(
+            ServiceDiscovery::create_discovered_metadata_from_endpoint_response(
+                Endpoint::Unix("/run/svc.sock".into(), Some(Version::HTTP_2)),
+                HashMap::default(),
+                Version::HTTP_2,
+                response,
+                None
+            ),
+            Ok(DiscoveredMetadata {
+                endpoint: DiscoveredEndpoint::Unix(socket_path, Version::HTTP_2),
+                protocol_type: ProtocolType::BidiStream,
+                ..
+            }) if socket_path == Path::new("/run/svc.sock")
+        ));
+    }
+
     #[test]
     fn fail_on_invalid_max_protocol_version_with_bad_response() {
         let response = endpoint_manifest::Endpoint {
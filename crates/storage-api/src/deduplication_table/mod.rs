@@ -47,7 +47,7 @@ impl DedupInformation {
 
 static SELF_PRODUCER: ByteString = ByteString::from_static("SELF");
 
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum ProducerId {
     Partition(PartitionId),
     Other(ByteString),
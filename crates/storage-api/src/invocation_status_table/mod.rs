@@ -190,6 +190,8 @@ pub enum InvocationStatus {
         waiting_for_completed_entries: HashSet<EntryIndex>,
     },
     Killed(InFlightInvocationMetadata),
+    /// Invocation is on hold: it keeps its journal, but won't be invoked until it's resumed.
+    Paused(InFlightInvocationMetadata),
     Completed(CompletedInvocation),
     /// Service instance is currently not invoked
     #[default]
@@ -205,6 +207,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => Some(&metadata.invocation_target),
             InvocationStatus::Suspended { metadata, .. } => Some(&metadata.invocation_target),
             InvocationStatus::Killed(metadata) => Some(&metadata.invocation_target),
+            InvocationStatus::Paused(metadata) => Some(&metadata.invocation_target),
             InvocationStatus::Completed(completed) => Some(&completed.invocation_target),
             _ => None,
         }
@@ -218,6 +221,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => Some(&metadata.source),
             InvocationStatus::Suspended { metadata, .. } => Some(&metadata.source),
             InvocationStatus::Killed(metadata) => Some(&metadata.source),
+            InvocationStatus::Paused(metadata) => Some(&metadata.source),
             InvocationStatus::Completed(completed) => Some(&completed.source),
             _ => None,
         }
@@ -231,6 +235,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => metadata.idempotency_key.as_ref(),
             InvocationStatus::Suspended { metadata, .. } => metadata.idempotency_key.as_ref(),
             InvocationStatus::Killed(metadata) => metadata.idempotency_key.as_ref(),
+            InvocationStatus::Paused(metadata) => metadata.idempotency_key.as_ref(),
             InvocationStatus::Completed(completed) => completed.idempotency_key.as_ref(),
             _ => None,
         }
@@ -242,6 +247,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => Some(metadata.journal_metadata),
             InvocationStatus::Suspended { metadata, .. } => Some(metadata.journal_metadata),
             InvocationStatus::Killed(metadata) => Some(metadata.journal_metadata),
+            InvocationStatus::Paused(metadata) => Some(metadata.journal_metadata),
             _ => None,
         }
     }
@@ -252,6 +258,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => Some(&metadata.journal_metadata),
             InvocationStatus::Suspended { metadata, .. } => Some(&metadata.journal_metadata),
             InvocationStatus::Killed(metadata) => Some(&metadata.journal_metadata),
+            InvocationStatus::Paused(metadata) => Some(&metadata.journal_metadata),
             _ => None,
         }
     }
@@ -262,6 +269,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => Some(&mut metadata.journal_metadata),
             InvocationStatus::Suspended { metadata, .. } => Some(&mut metadata.journal_metadata),
             InvocationStatus::Killed(metadata) => Some(&mut metadata.journal_metadata),
+            InvocationStatus::Paused(metadata) => Some(&mut metadata.journal_metadata),
             _ => None,
         }
     }
@@ -272,6 +280,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => Some(metadata),
             InvocationStatus::Suspended { metadata, .. } => Some(metadata),
             InvocationStatus::Killed(metadata) => Some(metadata),
+            InvocationStatus::Paused(metadata) => Some(metadata),
             _ => None,
         }
     }
@@ -282,6 +291,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => Some(metadata),
             InvocationStatus::Suspended { metadata, .. } => Some(metadata),
             InvocationStatus::Killed(metadata) => Some(metadata),
+            InvocationStatus::Paused(metadata) => Some(metadata),
             _ => None,
         }
     }
@@ -292,6 +302,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => Some(metadata),
             InvocationStatus::Suspended { metadata, .. } => Some(metadata),
             InvocationStatus::Killed(metadata) => Some(metadata),
+            InvocationStatus::Paused(metadata) => Some(metadata),
             _ => None,
         }
     }
@@ -306,6 +317,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => Some(&mut metadata.response_sinks),
             InvocationStatus::Suspended { metadata, .. } => Some(&mut metadata.response_sinks),
             InvocationStatus::Killed(metadata) => Some(&mut metadata.response_sinks),
+            InvocationStatus::Paused(metadata) => Some(&mut metadata.response_sinks),
             _ => None,
         }
     }
@@ -318,6 +330,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => Some(&metadata.response_sinks),
             InvocationStatus::Suspended { metadata, .. } => Some(&metadata.response_sinks),
             InvocationStatus::Killed(metadata) => Some(&metadata.response_sinks),
+            InvocationStatus::Paused(metadata) => Some(&metadata.response_sinks),
             _ => None,
         }
     }
@@ -330,6 +343,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => Some(&metadata.timestamps),
             InvocationStatus::Suspended { metadata, .. } => Some(&metadata.timestamps),
             InvocationStatus::Killed(metadata) => Some(&metadata.timestamps),
+            InvocationStatus::Paused(metadata) => Some(&metadata.timestamps),
             InvocationStatus::Completed(completed) => Some(&completed.timestamps),
             _ => None,
         }
@@ -343,6 +357,7 @@ impl InvocationStatus {
             InvocationStatus::Invoked(metadata) => Some(&mut metadata.timestamps),
             InvocationStatus::Suspended { metadata, .. } => Some(&mut metadata.timestamps),
             InvocationStatus::Killed(metadata) => Some(&mut metadata.timestamps),
+            InvocationStatus::Paused(metadata) => Some(&mut metadata.timestamps),
             InvocationStatus::Completed(completed) => Some(&mut completed.timestamps),
             _ => None,
         }
@@ -358,6 +373,7 @@ pub enum InvocationStatusDiscriminants {
     Invoked,
     Suspended,
     Killed,
+    Paused,
     Completed,
 }
 
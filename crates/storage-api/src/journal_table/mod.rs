@@ -79,4 +79,13 @@ pub trait JournalTable: ReadOnlyJournalTable {
         invocation_id: &InvocationId,
         journal_length: EntryIndex,
     ) -> impl Future<Output = ()> + Send;
+
+    /// Deletes the entries in the range `[new_length, journal_length)`, keeping the first
+    /// `new_length` entries of the journal.
+    fn truncate_journal(
+        &mut self,
+        invocation_id: &InvocationId,
+        new_length: EntryIndex,
+        journal_length: EntryIndex,
+    ) -> impl Future<Output = ()> + Send;
 }
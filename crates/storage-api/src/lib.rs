@@ -32,6 +32,7 @@ pub mod inbox_table;
 pub mod invocation_status_table;
 pub mod journal_table;
 pub mod outbox_table;
+pub mod parked_kafka_record_table;
 pub mod promise_table;
 pub mod service_status_table;
 pub mod state_table;
@@ -58,6 +59,7 @@ pub trait Transaction:
     + timer_table::TimerTable
     + idempotency_table::IdempotencyTable
     + promise_table::PromiseTable
+    + parked_kafka_record_table::ParkedKafkaRecordTable
     + Send
 {
     fn commit(self) -> impl Future<Output = Result<()>> + Send;
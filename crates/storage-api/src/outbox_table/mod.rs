@@ -8,7 +8,9 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 use crate::{protobuf_storage_encode_decode, Result};
-use restate_types::identifiers::{PartitionKey, WithPartitionKey};
+use bytes::Bytes;
+use futures_util::Stream;
+use restate_types::identifiers::{InvocationId, PartitionKey, SubscriptionId, WithPartitionKey};
 use restate_types::invocation::{
     AttachInvocationRequest, InvocationResponse, InvocationTermination, ServiceInvocation,
 };
@@ -29,6 +31,9 @@ pub enum OutboxMessage {
 
     /// Attach invocation
     AttachInvocation(AttachInvocationRequest),
+
+    /// Record to publish to the Kafka topic of an egress subscription
+    KafkaEgress(KafkaEgressRecord),
 }
 
 protobuf_storage_encode_decode!(OutboxMessage);
@@ -40,12 +45,28 @@ impl WithPartitionKey for OutboxMessage {
             OutboxMessage::ServiceResponse(sr) => sr.id.partition_key(),
             OutboxMessage::InvocationTermination(it) => it.invocation_id.partition_key(),
             OutboxMessage::AttachInvocation(ai) => ai.invocation_query.partition_key(),
+            OutboxMessage::KafkaEgress(record) => record.invocation_id.partition_key(),
         }
     }
 }
 
+/// Output of an invocation which is published to Kafka by an egress subscription.
+///
+/// The Kafka cluster and topic are resolved from the subscription when publishing the record.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct KafkaEgressRecord {
+    pub subscription_id: SubscriptionId,
+    /// Invocation which produced the output
+    pub invocation_id: InvocationId,
+    pub key: Bytes,
+    pub payload: Bytes,
+}
+
 pub trait ReadOnlyOutboxTable {
     fn get_outbox_head_seq_number(&mut self) -> impl Future<Output = Result<Option<u64>>> + Send;
+
+    /// Returns all the messages of this partition's outbox, ordered by their sequence number.
+    fn all_outbox_messages(&self) -> impl Stream<Item = Result<(u64, OutboxMessage)>> + Send;
 }
 
 pub trait OutboxTable: ReadOnlyOutboxTable {
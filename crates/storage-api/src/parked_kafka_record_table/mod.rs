@@ -0,0 +1,69 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::{protobuf_storage_encode_decode, Result};
+
+use bytes::Bytes;
+use bytestring::ByteString;
+use futures_util::Stream;
+use restate_types::identifiers::{PartitionKey, SubscriptionId, WithPartitionKey};
+use restate_types::invocation::Header;
+use restate_types::time::MillisSinceEpoch;
+use std::future::Future;
+use std::ops::RangeInclusive;
+
+/// Kafka record which a subscription could not turn into an invocation. Parked records are kept
+/// until they are replayed.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ParkedKafkaRecord {
+    /// Records of the same Kafka partition share the partition key
+    pub partition_key: PartitionKey,
+    pub subscription_id: SubscriptionId,
+    pub topic: ByteString,
+    pub kafka_partition: i32,
+    pub offset: i64,
+    /// Sequence number the record was deduplicated with when it was parked. It changes when
+    /// the offsets of the subscription are reset, so that a record which is parked again after
+    /// it was replayed is replayed as a new invocation.
+    pub deduplication_index: u64,
+    pub key: Bytes,
+    pub payload: Bytes,
+    pub headers: Vec<Header>,
+    /// Why the record could not be processed
+    pub error: ByteString,
+    pub parked_at: MillisSinceEpoch,
+}
+
+protobuf_storage_encode_decode!(ParkedKafkaRecord);
+
+impl WithPartitionKey for ParkedKafkaRecord {
+    fn partition_key(&self) -> PartitionKey {
+        self.partition_key
+    }
+}
+
+pub trait ReadOnlyParkedKafkaRecordTable {
+    fn all_parked_kafka_records(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<ParkedKafkaRecord>> + Send;
+}
+
+pub trait ParkedKafkaRecordTable: ReadOnlyParkedKafkaRecordTable {
+    fn put_parked_kafka_record(
+        &mut self,
+        record: &ParkedKafkaRecord,
+    ) -> impl Future<Output = ()> + Send;
+
+    fn delete_parked_kafka_record(
+        &mut self,
+        record: &ParkedKafkaRecord,
+    ) -> impl Future<Output = ()> + Send;
+}
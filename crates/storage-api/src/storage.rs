@@ -104,10 +104,10 @@ pub mod v1 {
             EnrichedEntryHeader, EntryResult, EpochSequenceNumber, Header, IdempotencyId,
             IdempotencyMetadata, InboxEntry, InvocationId, InvocationResolutionResult,
             InvocationStatus, InvocationStatusV2, InvocationTarget, InvocationV2Lite, JournalEntry,
-            JournalEntryId, JournalMeta, KvPair, OutboxMessage, Promise, ResponseResult,
-            SequenceNumber, ServiceId, ServiceInvocation, ServiceInvocationResponseSink, Source,
-            SpanContext, SpanRelation, StateMutation, SubmitNotificationSink, Timer,
-            VirtualObjectStatus,
+            JournalEntryId, JournalMeta, KvPair, OutboxMessage, ParkedKafkaRecord, Promise,
+            ResponseResult, SequenceNumber, ServiceId, ServiceInvocation,
+            ServiceInvocationResponseSink, Source, SpanContext, SpanRelation, StateMutation,
+            SubmitNotificationSink, Timer, VirtualObjectStatus,
         };
         use crate::StorageError;
         use restate_types::errors::{IdDecodeError, InvocationError};
@@ -494,6 +494,28 @@ pub mod v1 {
                             },
                         ))
                     }
+                    invocation_status_v2::Status::Paused => {
+                        Ok(crate::invocation_status_table::InvocationStatus::Paused(
+                            crate::invocation_status_table::InFlightInvocationMetadata {
+                                response_sinks,
+                                timestamps,
+                                invocation_target,
+                                journal_metadata: crate::invocation_status_table::JournalMetadata {
+                                    length: journal_length,
+                                    span_context: expect_or_fail!(span_context)?.try_into()?,
+                                },
+                                pinned_deployment: derive_pinned_deployment(
+                                    deployment_id,
+                                    service_protocol_version,
+                                )?,
+                                source,
+                                completion_retention_duration: completion_retention_duration
+                                    .unwrap_or_default()
+                                    .try_into()?,
+                                idempotency_key: idempotency_key.map(ByteString::from),
+                            },
+                        ))
+                    }
                     invocation_status_v2::Status::Completed => {
                         Ok(crate::invocation_status_table::InvocationStatus::Completed(
                             crate::invocation_status_table::CompletedInvocation {
@@ -818,6 +840,69 @@ pub mod v1 {
                             result: None,
                         }
                     }
+                    crate::invocation_status_table::InvocationStatus::Paused(
+                        crate::invocation_status_table::InFlightInvocationMetadata {
+                            invocation_target,
+                            journal_metadata,
+                            pinned_deployment,
+                            response_sinks,
+                            timestamps,
+                            source,
+                            completion_retention_duration,
+                            idempotency_key,
+                        },
+                    ) => {
+                        let (deployment_id, service_protocol_version) = match pinned_deployment {
+                            None => (None, None),
+                            Some(pinned_deployment) => (
+                                Some(pinned_deployment.deployment_id.to_string()),
+                                Some(pinned_deployment.service_protocol_version.as_repr()),
+                            ),
+                        };
+
+                        InvocationStatusV2 {
+                            status: invocation_status_v2::Status::Paused.into(),
+                            invocation_target: Some(invocation_target.into()),
+                            source: Some(source.into()),
+                            span_context: Some(journal_metadata.span_context.into()),
+                            // SAFETY: We're only mapping data types here
+                            creation_time: unsafe { timestamps.creation_time() }.as_u64(),
+                            modification_time: unsafe { timestamps.modification_time() }.as_u64(),
+                            inboxed_transition_time: unsafe {
+                                timestamps.inboxed_transition_time()
+                            }
+                            .map(|t| t.as_u64()),
+                            scheduled_transition_time: unsafe {
+                                timestamps.scheduled_transition_time()
+                            }
+                            .map(|t| t.as_u64()),
+                            running_transition_time: unsafe {
+                                timestamps.running_transition_time()
+                            }
+                            .map(|t| t.as_u64()),
+                            completed_transition_time: unsafe {
+                                timestamps.completed_transition_time()
+                            }
+                            .map(|t| t.as_u64()),
+                            response_sinks: response_sinks
+                                .into_iter()
+                                .map(|s| ServiceInvocationResponseSink::from(Some(s)))
+                                .collect(),
+                            argument: None,
+                            headers: vec![],
+                            execution_time: None,
+                            completion_retention_duration: Some(
+                                completion_retention_duration.into(),
+                            ),
+                            idempotency_key: idempotency_key.map(|key| key.to_string()),
+                            inbox_sequence_number: None,
+                            journal_length: journal_metadata.length,
+                            deployment_id,
+                            service_protocol_version,
+                            waiting_for_completed_entries: vec![],
+                            result: None,
+                        }
+                    }
                     crate::invocation_status_table::InvocationStatus::Completed(
                         crate::invocation_status_table::CompletedInvocation {
                             invocation_target,
@@ -894,6 +979,9 @@ pub mod v1 {
                     invocation_status_v2::Status::Killed => {
                         crate::invocation_status_table::InvocationStatusDiscriminants::Killed
                     }
+                    invocation_status_v2::Status::Paused => {
+                        crate::invocation_status_table::InvocationStatusDiscriminants::Paused
+                    }
                     invocation_status_v2::Status::Completed => {
                         crate::invocation_status_table::InvocationStatusDiscriminants::Completed
                     }
@@ -1000,6 +1088,9 @@ pub mod v1 {
                     crate::invocation_status_table::InvocationStatus::Killed(_) => {
                         panic!("Unexpected conversion to old InvocationStatus when using Killed variant. This is a bug in the table implementation.")
                     }
+                    crate::invocation_status_table::InvocationStatus::Paused(_) => {
+                        panic!("Unexpected conversion to old InvocationStatus when using Paused variant. This is a bug in the table implementation.")
+                    }
                 };
 
                 InvocationStatus {
@@ -2736,6 +2827,28 @@ pub mod v1 {
                             .ok_or(ConversionError::missing_field("response_sink"))??,
                         },
                     ),
+                    outbox_message::OutboxMessage::KafkaEgressRecord(
+                        outbox_message::OutboxKafkaEgressRecord {
+                            subscription_id,
+                            invocation_id,
+                            key,
+                            payload,
+                        },
+                    ) => crate::outbox_table::OutboxMessage::KafkaEgress(
+                        crate::outbox_table::KafkaEgressRecord {
+                            subscription_id:
+                                restate_types::identifiers::SubscriptionId::from_slice(
+                                    &subscription_id,
+                                )
+                                .map_err(|e| ConversionError::invalid_data(e))?,
+                            invocation_id: restate_types::identifiers::InvocationId::try_from(
+                                invocation_id
+                                    .ok_or(ConversionError::missing_field("invocation_id"))?,
+                            )?,
+                            key,
+                            payload,
+                        },
+                    ),
                 };
 
                 Ok(result)
@@ -2812,6 +2925,21 @@ pub mod v1 {
                             response_sink: Some(Some(response_sink).into()),
                         },
                     ),
+                    crate::outbox_table::OutboxMessage::KafkaEgress(
+                        crate::outbox_table::KafkaEgressRecord {
+                            subscription_id,
+                            invocation_id,
+                            key,
+                            payload,
+                        },
+                    ) => outbox_message::OutboxMessage::KafkaEgressRecord(
+                        outbox_message::OutboxKafkaEgressRecord {
+                            subscription_id: subscription_id.to_bytes().to_vec().into(),
+                            invocation_id: Some(InvocationId::from(invocation_id)),
+                            key,
+                            payload,
+                        },
+                    ),
                 };
 
                 OutboxMessage {
@@ -3079,6 +3207,51 @@ pub mod v1 {
             }
         }
 
+        impl From<crate::parked_kafka_record_table::ParkedKafkaRecord> for ParkedKafkaRecord {
+            fn from(value: crate::parked_kafka_record_table::ParkedKafkaRecord) -> Self {
+                ParkedKafkaRecord {
+                    partition_key: value.partition_key,
+                    subscription_id: value.subscription_id.to_bytes().to_vec().into(),
+                    topic: value.topic.to_string(),
+                    kafka_partition: value.kafka_partition,
+                    offset: value.offset,
+                    deduplication_index: value.deduplication_index,
+                    key: value.key,
+                    payload: value.payload,
+                    headers: value.headers.into_iter().map(Into::into).collect(),
+                    error: value.error.to_string(),
+                    parked_at: value.parked_at.as_u64(),
+                }
+            }
+        }
+
+        impl TryFrom<ParkedKafkaRecord> for crate::parked_kafka_record_table::ParkedKafkaRecord {
+            type Error = ConversionError;
+
+            fn try_from(value: ParkedKafkaRecord) -> Result<Self, Self::Error> {
+                Ok(crate::parked_kafka_record_table::ParkedKafkaRecord {
+                    partition_key: value.partition_key,
+                    subscription_id: restate_types::identifiers::SubscriptionId::from_slice(
+                        &value.subscription_id,
+                    )
+                    .map_err(|e| ConversionError::invalid_data(e))?,
+                    topic: value.topic.into(),
+                    kafka_partition: value.kafka_partition,
+                    offset: value.offset,
+                    deduplication_index: value.deduplication_index,
+                    key: value.key,
+                    payload: value.payload,
+                    headers: value
+                        .headers
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<Result<Vec<_>, _>>()?,
+                    error: value.error.into(),
+                    parked_at: MillisSinceEpoch::new(value.parked_at),
+                })
+            }
+        }
+
         impl From<crate::fsm_table::SequenceNumber> for SequenceNumber {
             fn from(value: crate::fsm_table::SequenceNumber) -> Self {
                 SequenceNumber {
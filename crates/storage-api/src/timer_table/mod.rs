@@ -251,7 +251,12 @@ impl WithPartitionKey for Timer {
 
 protobuf_storage_encode_decode!(Timer);
 
-pub trait TimerTable {
+pub trait ReadOnlyTimerTable {
+    /// Returns all the timers of this partition, ordered by their [`TimerKey`].
+    fn all_timers(&self) -> impl Stream<Item = Result<(TimerKey, Timer)>> + Send;
+}
+
+pub trait TimerTable: ReadOnlyTimerTable {
     fn put_timer(&mut self, timer_key: &TimerKey, timer: &Timer)
         -> impl Future<Output = ()> + Send;
 
@@ -77,6 +77,7 @@ const SYS_INVOCATION_VIEW: &str = "CREATE VIEW sys_invocation as SELECT
                 WHEN ss.status = 'scheduled' THEN 'scheduled'
                 WHEN ss.status = 'completed' THEN 'completed'
                 WHEN ss.status = 'suspended' THEN 'suspended'
+                WHEN ss.status = 'paused' THEN 'paused'
                 WHEN sis.in_flight THEN 'running'
                 WHEN ss.status = 'invoked' AND retry_count > 0 THEN 'backing-off'
                 ELSE 'ready'
@@ -164,6 +165,21 @@ impl QueryContext {
             local_partition_store_manager.clone(),
         )?;
         crate::promise::register_self(
+            &ctx,
+            partition_selector.clone(),
+            local_partition_store_manager.clone(),
+        )?;
+        crate::timer::register_self(
+            &ctx,
+            partition_selector.clone(),
+            local_partition_store_manager.clone(),
+        )?;
+        crate::outbox::register_self(
+            &ctx,
+            partition_selector.clone(),
+            local_partition_store_manager.clone(),
+        )?;
+        crate::parked_kafka_record::register_self(
             &ctx,
             partition_selector.clone(),
             local_partition_store_manager,
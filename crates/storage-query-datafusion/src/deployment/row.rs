@@ -25,6 +25,9 @@ pub(crate) fn append_deployment_row(
         DeploymentType::Http { .. } => {
             row.ty("http");
         }
+        DeploymentType::Unix { .. } => {
+            row.ty("unix");
+        }
         DeploymentType::Lambda { .. } => {
             row.ty("lambda");
         }
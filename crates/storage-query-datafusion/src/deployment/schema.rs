@@ -18,7 +18,7 @@ define_table!(sys_deployment(
     /// The ID of the service deployment.
     id: DataType::LargeUtf8,
 
-    /// The type of the endpoint. Either `http` or `lambda`.
+    /// The type of the endpoint. Either `http`, `unix` or `lambda`.
     ty: DataType::LargeUtf8,
 
     /// The address of the endpoint. Either HTTP URL or Lambda ARN.
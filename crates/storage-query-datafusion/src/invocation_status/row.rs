@@ -87,6 +87,10 @@ pub(crate) fn append_invocation_status_row(
             row.status("killed");
             fill_in_flight_invocation_metadata(&mut row, output, metadata);
         }
+        InvocationStatus::Paused(metadata) => {
+            row.status("paused");
+            fill_in_flight_invocation_metadata(&mut row, output, metadata);
+        }
         InvocationStatus::Free => {
             row.status("free");
         }
@@ -20,6 +20,8 @@ mod invocation_state;
 mod invocation_status;
 mod journal;
 mod keyed_service_status;
+mod outbox;
+mod parked_kafka_record;
 mod partition_store_scanner;
 mod physical_optimizer;
 mod promise;
@@ -30,6 +32,7 @@ pub mod table_docs;
 mod table_macro;
 mod table_providers;
 mod table_util;
+mod timer;
 
 pub use context::BuildError;
 use datafusion::arrow::datatypes::Schema;
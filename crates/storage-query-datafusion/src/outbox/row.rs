@@ -0,0 +1,58 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+use super::schema::SysOutboxBuilder;
+use crate::table_util::format_using;
+use restate_storage_api::outbox_table::OutboxMessage;
+use restate_types::identifiers::{PartitionId, WithPartitionKey};
+
+#[inline]
+pub(crate) fn append_outbox_row(
+    builder: &mut SysOutboxBuilder,
+    output: &mut String,
+    partition_id: PartitionId,
+    sequence_number: u64,
+    outbox_message: OutboxMessage,
+) {
+    let mut row = builder.row();
+
+    row.partition_id(u32::from(partition_id));
+    row.sequence_number(sequence_number);
+    row.target_partition_key(outbox_message.partition_key());
+
+    let target_id = match &outbox_message {
+        OutboxMessage::ServiceInvocation(service_invocation) => {
+            row.message_type("invocation");
+            if row.is_target_defined() {
+                row.target(format_using(output, &service_invocation.invocation_target));
+            }
+            service_invocation.invocation_id
+        }
+        OutboxMessage::ServiceResponse(response) => {
+            row.message_type("response");
+            row.entry_index(response.entry_index);
+            response.id
+        }
+        OutboxMessage::InvocationTermination(termination) => {
+            row.message_type("termination");
+            termination.invocation_id
+        }
+        OutboxMessage::AttachInvocation(attach) => {
+            row.message_type("attach");
+            attach.invocation_query.to_invocation_id()
+        }
+        OutboxMessage::KafkaEgress(record) => {
+            row.message_type("kafka_egress");
+            record.invocation_id
+        }
+    };
+    if row.is_target_id_defined() {
+        row.target_id(format_using(output, &target_id));
+    }
+}
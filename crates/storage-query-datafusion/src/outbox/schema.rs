@@ -0,0 +1,47 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+#![allow(dead_code)]
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_outbox(
+    /// The partition whose outbox contains this message.
+    partition_id: DataType::UInt32,
+
+    /// Sequence number in the outbox.
+    sequence_number: DataType::UInt64,
+
+    /// The message type. Either:
+    /// * `invocation` for a new invocation.
+    /// * `response` for the response to an invocation.
+    /// * `termination` for the cancellation or the kill of an invocation.
+    /// * `attach` for the attachment to an existing invocation.
+    /// * `kafka_egress` for the output of an invocation to publish to Kafka.
+    message_type: DataType::LargeUtf8,
+
+    /// [Invocation ID](/operate/invocation#invocation-identifier) of the invocation this message is addressed to.
+    /// For `kafka_egress` messages, the invocation which produced the output.
+    target_id: DataType::LargeUtf8,
+
+    /// Partition key of the invocation this message is addressed to, used to route the message
+    /// to the partition owning it.
+    target_partition_key: DataType::UInt64,
+
+    /// If this message is a new invocation, indicates the invocation Target. Format
+    /// for plain services: `ServiceName/HandlerName`, e.g. `Greeter/greet`. Format for
+    /// virtual objects/workflows: `VirtualObjectName/Key/HandlerName`, e.g.
+    /// `Greeter/Francesco/greet`.
+    target: DataType::LargeUtf8,
+
+    /// If this message is a response, the index of the journal entry it completes.
+    entry_index: DataType::UInt32,
+));
@@ -0,0 +1,75 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use futures::{Stream, TryStreamExt};
+
+use restate_partition_store::{PartitionStore, PartitionStoreManager};
+use restate_storage_api::outbox_table::{OutboxMessage, ReadOnlyOutboxTable};
+use restate_types::identifiers::{PartitionId, PartitionKey};
+
+use crate::context::{QueryContext, SelectPartitions};
+use crate::outbox::row::append_outbox_row;
+use crate::outbox::schema::SysOutboxBuilder;
+use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
+use crate::table_providers::{PartitionedTableProvider, ScanPartition};
+
+const NAME: &str = "sys_outbox";
+
+pub(crate) fn register_self(
+    ctx: &QueryContext,
+    partition_selector: impl SelectPartitions,
+    local_partition_store_manager: Option<PartitionStoreManager>,
+) -> datafusion::common::Result<()> {
+    let local_partition_scanner = local_partition_store_manager.map(|partition_store_manager| {
+        Arc::new(LocalPartitionsScanner::new(
+            partition_store_manager,
+            OutboxScanner,
+        )) as Arc<dyn ScanPartition>
+    });
+    let table = PartitionedTableProvider::new(
+        partition_selector,
+        SysOutboxBuilder::schema(),
+        ctx.create_distributed_scanner(NAME, local_partition_scanner),
+    );
+    ctx.register_partitioned_table(NAME, Arc::new(table))
+}
+
+#[derive(Debug, Clone)]
+struct OutboxScanner;
+
+impl ScanLocalPartition for OutboxScanner {
+    type Builder = SysOutboxBuilder;
+    type Item = (PartitionId, u64, OutboxMessage);
+
+    fn scan_partition_store(
+        partition_store: &PartitionStore,
+        _range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = restate_storage_api::Result<Self::Item>> + Send {
+        // The outbox belongs to the partition as a whole, its messages are addressed to other partition keys
+        let partition_id = partition_store.partition_id();
+        partition_store
+            .all_outbox_messages()
+            .map_ok(move |(sequence_number, message)| (partition_id, sequence_number, message))
+    }
+
+    fn append_row(row_builder: &mut Self::Builder, string_buffer: &mut String, value: Self::Item) {
+        let (partition_id, sequence_number, outbox_message) = value;
+        append_outbox_row(
+            row_builder,
+            string_buffer,
+            partition_id,
+            sequence_number,
+            outbox_message,
+        );
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+use crate::mocks::*;
+use crate::row;
+use bytes::Bytes;
+use datafusion::arrow::array::{LargeStringArray, UInt32Array, UInt64Array};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::outbox_table::{OutboxMessage, OutboxTable};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{InvocationId, PartitionId, WithPartitionKey};
+use restate_types::invocation::{InvocationResponse, InvocationTermination, ResponseResult};
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_outbox() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let mut tx = engine.partition_store().transaction();
+    let caller_invocation_id = InvocationId::mock_random();
+    tx.put_outbox_message(
+        0,
+        &OutboxMessage::ServiceResponse(InvocationResponse {
+            id: caller_invocation_id,
+            entry_index: 2,
+            result: ResponseResult::Success(Bytes::default()),
+        }),
+    )
+    .await;
+    let cancelled_invocation_id = InvocationId::mock_random();
+    tx.put_outbox_message(
+        1,
+        &OutboxMessage::InvocationTermination(InvocationTermination::cancel(
+            cancelled_invocation_id,
+        )),
+    )
+    .await;
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT * FROM sys_outbox ORDER BY sequence_number")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(
+            row!(
+                0,
+                {
+                    "partition_id" => UInt32Array: eq(u32::from(PartitionId::MIN)),
+                    "sequence_number" => UInt64Array: eq(0),
+                    "message_type" => LargeStringArray: eq("response"),
+                    "target_id" => LargeStringArray: eq(caller_invocation_id.to_string()),
+                    "target_partition_key" => UInt64Array: eq(caller_invocation_id.partition_key()),
+                    "entry_index" => UInt32Array: eq(2),
+                }
+            ),
+            row!(
+                1,
+                {
+                    "sequence_number" => UInt64Array: eq(1),
+                    "message_type" => LargeStringArray: eq("termination"),
+                    "target_id" => LargeStringArray: eq(cancelled_invocation_id.to_string()),
+                }
+            )
+        )
+    );
+}
@@ -0,0 +1,34 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::schema::SysKafkaParkedRecordBuilder;
+use crate::table_util::format_using;
+use restate_storage_api::parked_kafka_record_table::ParkedKafkaRecord;
+
+#[inline]
+pub(crate) fn append_parked_kafka_record_row(
+    builder: &mut SysKafkaParkedRecordBuilder,
+    output: &mut String,
+    record: ParkedKafkaRecord,
+) {
+    let mut row = builder.row();
+
+    row.partition_key(record.partition_key);
+    if row.is_subscription_id_defined() {
+        row.subscription_id(format_using(output, &record.subscription_id));
+    }
+    row.topic(&record.topic);
+    row.kafka_partition(record.kafka_partition);
+    row.offset(record.offset as u64);
+    row.key(&record.key);
+    row.payload(&record.payload);
+    row.error(&record.error);
+    row.parked_at(record.parked_at.as_u64() as i64);
+}
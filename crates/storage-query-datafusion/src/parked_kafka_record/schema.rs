@@ -0,0 +1,43 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+#![allow(dead_code)]
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_kafka_parked_record(
+    /// Internal column that is used for partitioning the parked records. Can be ignored.
+    partition_key: DataType::UInt64,
+
+    /// The ID of the subscription which parked the record.
+    subscription_id: DataType::LargeUtf8,
+
+    /// The Kafka topic of the record.
+    topic: DataType::LargeUtf8,
+
+    /// The Kafka partition of the record.
+    kafka_partition: DataType::Int32,
+
+    /// The offset of the record within its Kafka partition.
+    offset: DataType::UInt64,
+
+    /// The key of the record.
+    key: DataType::LargeBinary,
+
+    /// The payload of the record.
+    payload: DataType::LargeBinary,
+
+    /// Why the record could not be turned into an invocation.
+    error: DataType::LargeUtf8,
+
+    /// Timestamp indicating when the record was parked.
+    parked_at: DataType::Date64,
+));
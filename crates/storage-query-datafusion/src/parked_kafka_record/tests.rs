@@ -0,0 +1,68 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+use crate::mocks::*;
+use crate::row;
+use bytes::Bytes;
+use datafusion::arrow::array::{Int32Array, LargeBinaryArray, LargeStringArray, UInt64Array};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::parked_kafka_record_table::{ParkedKafkaRecord, ParkedKafkaRecordTable};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::SubscriptionId;
+use restate_types::time::MillisSinceEpoch;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_parked_kafka_records() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let subscription_id = SubscriptionId::new();
+    let mut tx = engine.partition_store().transaction();
+    tx.put_parked_kafka_record(&ParkedKafkaRecord {
+        partition_key: 1337,
+        subscription_id,
+        topic: "orders".into(),
+        kafka_partition: 2,
+        offset: 42,
+        deduplication_index: 42,
+        key: Bytes::from_static(&[0xff]),
+        payload: Bytes::from_static(b"{}"),
+        headers: vec![],
+        error: "The Kafka record key must be valid UTF-8".into(),
+        parked_at: MillisSinceEpoch::new(1000),
+    })
+    .await;
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT * FROM sys_kafka_parked_record")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(row!(
+            0,
+            {
+                "subscription_id" => LargeStringArray: eq(subscription_id.to_string()),
+                "topic" => LargeStringArray: eq("orders"),
+                "kafka_partition" => Int32Array: eq(2),
+                "offset" => UInt64Array: eq(42),
+                "key" => LargeBinaryArray: eq(vec![0xff_u8]),
+                "error" => LargeStringArray: eq("The Kafka record key must be valid UTF-8"),
+            }
+        ))
+    );
+}
@@ -10,7 +10,7 @@
 
 use crate::{
     deployment, idempotency, inbox, invocation_state, invocation_status, journal,
-    keyed_service_status, promise, service, state,
+    keyed_service_status, outbox, parked_kafka_record, promise, service, state, timer,
 };
 use std::borrow::Cow;
 
@@ -24,6 +24,9 @@ pub const ALL_TABLE_DOCS: &[StaticTableDocs] = &[
     inbox::schema::TABLE_DOCS,
     idempotency::schema::TABLE_DOCS,
     promise::schema::TABLE_DOCS,
+    timer::schema::TABLE_DOCS,
+    outbox::schema::TABLE_DOCS,
+    parked_kafka_record::schema::TABLE_DOCS,
     service::schema::TABLE_DOCS,
     deployment::schema::TABLE_DOCS,
 ];
@@ -127,7 +130,7 @@ pub fn sys_invocation_table_docs() -> OwnedTableDocs {
         TableColumn {
             name: "status",
             column_type: "Utf8",
-            description: "Either `pending` or `scheduled` or `ready` or `running` or `backing-off` or `suspended` or `completed`.",
+            description: "Either `pending` or `scheduled` or `ready` or `running` or `backing-off` or `suspended` or `paused` or `completed`.",
         },
         sys_invocation_status.remove("completion_result").expect("completion_result should exist"),
         sys_invocation_status.remove("completion_failure").expect("completion_failure should exist"),
@@ -0,0 +1,40 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+use super::schema::SysTimerBuilder;
+use crate::table_util::format_using;
+use restate_storage_api::timer_table::{Timer, TimerKey};
+use restate_types::identifiers::{PartitionId, WithPartitionKey};
+
+#[inline]
+pub(crate) fn append_timer_row(
+    builder: &mut SysTimerBuilder,
+    output: &mut String,
+    partition_id: PartitionId,
+    timer_key: TimerKey,
+    timer: Timer,
+) {
+    let mut row = builder.row();
+
+    row.partition_key(timer.partition_key());
+    row.partition_id(u32::from(partition_id));
+    if row.is_id_defined() {
+        row.id(format_using(output, &timer.invocation_id()));
+    }
+    row.wake_up_at(timer_key.timestamp as i64);
+
+    match timer {
+        Timer::Invoke(_) | Timer::NeoInvoke(_) => row.timer_type("invoke"),
+        Timer::CompleteJournalEntry(_, entry_index) => {
+            row.timer_type("complete-journal-entry");
+            row.entry_index(entry_index);
+        }
+        Timer::CleanInvocationStatus(_) => row.timer_type("clean-invocation-status"),
+    }
+}
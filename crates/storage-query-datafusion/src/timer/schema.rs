@@ -0,0 +1,37 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+#![allow(dead_code)]
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_timer(
+    /// Internal column that is used for partitioning the services invocations. Can be ignored.
+    partition_key: DataType::UInt64,
+
+    /// The partition whose timer table contains this timer.
+    partition_id: DataType::UInt32,
+
+    /// [Invocation ID](/operate/invocation#invocation-identifier) of the invocation this timer belongs to.
+    id: DataType::LargeUtf8,
+
+    /// The timer type. Either:
+    /// * `invoke` if the timer starts a delayed invocation.
+    /// * `complete-journal-entry` if the timer completes a journal entry, e.g. a sleep.
+    /// * `clean-invocation-status` if the timer removes the status of a completed invocation once its retention expires.
+    timer_type: DataType::LargeUtf8,
+
+    /// Timestamp indicating when the timer fires.
+    wake_up_at: DataType::Date64,
+
+    /// If the timer completes a journal entry, the index of that entry.
+    entry_index: DataType::UInt32,
+));
@@ -0,0 +1,69 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use futures::{Stream, TryStreamExt};
+
+use restate_partition_store::{PartitionStore, PartitionStoreManager};
+use restate_storage_api::timer_table::{ReadOnlyTimerTable, Timer, TimerKey};
+use restate_types::identifiers::{PartitionId, PartitionKey};
+
+use crate::context::{QueryContext, SelectPartitions};
+use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
+use crate::table_providers::{PartitionedTableProvider, ScanPartition};
+use crate::timer::row::append_timer_row;
+use crate::timer::schema::SysTimerBuilder;
+
+const NAME: &str = "sys_timer";
+
+pub(crate) fn register_self(
+    ctx: &QueryContext,
+    partition_selector: impl SelectPartitions,
+    local_partition_store_manager: Option<PartitionStoreManager>,
+) -> datafusion::common::Result<()> {
+    let local_partition_scanner = local_partition_store_manager.map(|partition_store_manager| {
+        Arc::new(LocalPartitionsScanner::new(
+            partition_store_manager,
+            TimerScanner,
+        )) as Arc<dyn ScanPartition>
+    });
+    let table = PartitionedTableProvider::new(
+        partition_selector,
+        SysTimerBuilder::schema(),
+        ctx.create_distributed_scanner(NAME, local_partition_scanner),
+    );
+    ctx.register_partitioned_table(NAME, Arc::new(table))
+}
+
+#[derive(Debug, Clone)]
+struct TimerScanner;
+
+impl ScanLocalPartition for TimerScanner {
+    type Builder = SysTimerBuilder;
+    type Item = (PartitionId, TimerKey, Timer);
+
+    fn scan_partition_store(
+        partition_store: &PartitionStore,
+        _range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = restate_storage_api::Result<Self::Item>> + Send {
+        // Timers are keyed by partition id rather than partition key, hence the whole partition is scanned
+        let partition_id = partition_store.partition_id();
+        partition_store
+            .all_timers()
+            .map_ok(move |(timer_key, timer)| (partition_id, timer_key, timer))
+    }
+
+    fn append_row(row_builder: &mut Self::Builder, string_buffer: &mut String, value: Self::Item) {
+        let (partition_id, timer_key, timer) = value;
+        append_timer_row(row_builder, string_buffer, partition_id, timer_key, timer);
+    }
+}
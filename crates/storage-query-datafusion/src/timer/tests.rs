@@ -0,0 +1,66 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+use crate::mocks::*;
+use crate::row;
+use datafusion::arrow::array::{Date64Array, LargeStringArray, UInt32Array};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::timer_table::{Timer, TimerTable};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{InvocationId, PartitionId};
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_timers() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let mut tx = engine.partition_store().transaction();
+    let sleeping_invocation_id = InvocationId::mock_random();
+    let (timer_key, timer) = Timer::complete_journal_entry(1000, sleeping_invocation_id, 3);
+    tx.put_timer(&timer_key, &timer).await;
+    let delayed_invocation_id = InvocationId::mock_random();
+    let (timer_key, timer) = Timer::neo_invoke(2000, delayed_invocation_id);
+    tx.put_timer(&timer_key, &timer).await;
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT * FROM sys_timer ORDER BY wake_up_at")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(
+            row!(
+                0,
+                {
+                    "partition_id" => UInt32Array: eq(u32::from(PartitionId::MIN)),
+                    "id" => LargeStringArray: eq(sleeping_invocation_id.to_string()),
+                    "timer_type" => LargeStringArray: eq("complete-journal-entry"),
+                    "wake_up_at" => Date64Array: eq(1000),
+                    "entry_index" => UInt32Array: eq(3),
+                }
+            ),
+            row!(
+                1,
+                {
+                    "id" => LargeStringArray: eq(delayed_invocation_id.to_string()),
+                    "timer_type" => LargeStringArray: eq("invoke"),
+                    "wake_up_at" => Date64Array: eq(2000),
+                }
+            )
+        )
+    );
+}
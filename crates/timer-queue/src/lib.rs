@@ -69,6 +69,14 @@ impl<T> TimerQueue<T> {
         }))
     }
 
+    /// Removes the pending timers with the given payload.
+    pub fn remove(&mut self, payload: &T)
+    where
+        T: PartialEq,
+    {
+        self.0.retain(|Reverse(timer)| &timer.payload != payload)
+    }
+
     pub async fn await_timer(&mut self) -> Timer<T> {
         if let Some(Reverse(Timer { sleep_until, .. })) = self.0.peek() {
             let system_now = SystemTime::now();
@@ -147,4 +155,20 @@ mod tests {
         assert_eq!(timer_queue.await_timer().await.payload, 1);
         assert_eq!(timer_queue.await_timer().await.payload, 2);
     }
+    #[tokio::test]
+    async fn test_remove_timer() {
+        let now = SystemTime::now();
+
+        let mut timer_queue: TimerQueue<i32> = [
+            (now + Duration::from_millis(1), 1),
+            (now + Duration::from_millis(2), 2),
+        ]
+        .into_iter()
+        .collect();
+
+        timer_queue.remove(&1);
+
+        assert_eq!(timer_queue.await_timer().await.payload, 2);
+        assert!(timer_queue.is_empty());
+    }
 }
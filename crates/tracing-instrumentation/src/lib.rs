@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 mod exporter;
+mod otlp_metrics;
 mod pretty;
 
 use std::collections::HashMap;
@@ -43,6 +44,7 @@ use crate::exporter::UserServiceModifierSpanExporter;
 use crate::pretty::PrettyFields;
 
 pub use exporter::set_global_node_id;
+pub use otlp_metrics::{OtlpMeterProviders, OtlpMetricsRecorder};
 
 const SERVICE_INSTANCE_NAME: &str = "service.instance.name";
 
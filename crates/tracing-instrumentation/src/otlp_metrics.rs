@@ -0,0 +1,511 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+use opentelemetry::metrics::{Meter, MeterProvider};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{MetricExporter, WithExportConfig, WithTonicConfig};
+use opentelemetry_sdk::metrics::exporter::PushMetricExporter;
+use opentelemetry_sdk::metrics::{
+    new_view, Aggregation, Instrument, InstrumentKind, MetricError, PeriodicReader,
+    SdkMeterProvider, Stream, Temporality,
+};
+use opentelemetry_sdk::Resource;
+use tonic::codegen::http::HeaderMap;
+use tonic::metadata::MetadataMap;
+use tonic::transport::{Certificate, ClientTlsConfig};
+
+use restate_types::config::{CommonOptions, OtlpMetricsOptions};
+
+use crate::SERVICE_INSTANCE_NAME;
+
+/// A [`Recorder`] which pushes the metrics recorded through the `metrics` crate to an OTLP
+/// collector.
+///
+/// Counters are exported as cumulative monotonic sums and gauges as gauges. Histograms are
+/// exported as base-2 exponential histograms, which need no configured bucket boundaries, with
+/// delta temporality. A histogram series is therefore only exported while it is recorded to and
+/// idle series are forgotten after an export interval, instead of being exported forever. The
+/// labels of a metric become the attributes of the exported data points.
+pub struct OtlpMetricsRecorder {
+    meter_providers: OtlpMeterProviders,
+    meter: Meter,
+    histogram_meter: Meter,
+    descriptions: Mutex<HashMap<String, (Option<Unit>, SharedString)>>,
+    counters: RwLock<HashMap<Key, Arc<OtlpCounter>>>,
+    gauges: RwLock<HashMap<Key, Arc<OtlpGauge>>>,
+    histograms: RwLock<HashMap<Key, Arc<OtlpHistogram>>>,
+}
+
+impl OtlpMetricsRecorder {
+    /// Creates the recorder if an OTLP metrics endpoint is configured. Must be called from within
+    /// a tokio runtime which periodically exports the metrics.
+    pub fn create(common_opts: &CommonOptions) -> Result<Option<Self>, MetricError> {
+        let opts = &common_opts.otlp_metrics;
+        let Some(endpoint) = &opts.otlp_metrics_endpoint else {
+            return Ok(None);
+        };
+
+        let mut attributes = vec![
+            KeyValue::new(
+                opentelemetry_semantic_conventions::resource::SERVICE_NAME,
+                format!("restate-server@{}", common_opts.node_name()),
+            ),
+            KeyValue::new(
+                opentelemetry_semantic_conventions::resource::SERVICE_NAMESPACE,
+                "Restate",
+            ),
+            KeyValue::new(
+                SERVICE_INSTANCE_NAME,
+                format!("{}/{}", common_opts.cluster_name(), common_opts.node_name()),
+            ),
+            KeyValue::new(
+                opentelemetry_semantic_conventions::resource::SERVICE_VERSION,
+                env!("CARGO_PKG_VERSION"),
+            ),
+        ];
+        attributes.extend(
+            opts.otlp_metrics_resource_attributes
+                .iter()
+                .map(|(key, value)| KeyValue::new(key.clone(), value.clone())),
+        );
+
+        let exporter = build_exporter(opts, endpoint, Temporality::Cumulative)?;
+        let histogram_exporter = build_exporter(opts, endpoint, Temporality::Delta)?;
+
+        // Later attributes override earlier ones with the same key
+        Self::new(
+            Resource::new(attributes),
+            exporter,
+            histogram_exporter,
+            opts.otlp_metrics_export_interval.into(),
+        )
+        .map(Some)
+    }
+
+    fn new<E: PushMetricExporter>(
+        resource: Resource,
+        exporter: E,
+        histogram_exporter: E,
+        export_interval: Duration,
+    ) -> Result<Self, MetricError> {
+        let reader = PeriodicReader::builder(exporter, opentelemetry_sdk::runtime::Tokio)
+            .with_interval(export_interval)
+            .build();
+        let metrics = SdkMeterProvider::builder()
+            .with_resource(resource.clone())
+            .with_reader(reader)
+            .build();
+
+        let histogram_reader =
+            PeriodicReader::builder(histogram_exporter, opentelemetry_sdk::runtime::Tokio)
+                .with_interval(export_interval)
+                .build();
+        let exponential_histograms = new_view(
+            Instrument::new().kind(InstrumentKind::Histogram),
+            Stream::new().aggregation(Aggregation::Base2ExponentialHistogram {
+                max_size: 160,
+                max_scale: 20,
+                record_min_max: true,
+            }),
+        )?;
+        let histograms = SdkMeterProvider::builder()
+            .with_resource(resource)
+            .with_reader(histogram_reader)
+            .with_view(exponential_histograms)
+            .build();
+
+        Ok(Self {
+            meter: metrics.meter("restate"),
+            histogram_meter: histograms.meter("restate"),
+            meter_providers: OtlpMeterProviders {
+                metrics,
+                histograms,
+            },
+            descriptions: Default::default(),
+            counters: Default::default(),
+            gauges: Default::default(),
+            histograms: Default::default(),
+        })
+    }
+
+    /// The meter providers which export the metrics. Shut them down to push the last metrics
+    /// before the process exits.
+    pub fn meter_providers(&self) -> OtlpMeterProviders {
+        self.meter_providers.clone()
+    }
+
+    fn describe(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.descriptions
+            .lock()
+            .unwrap()
+            .insert(key.as_str().to_owned(), (unit, description));
+    }
+
+    fn description(&self, key: &Key) -> (Option<&'static str>, Option<String>) {
+        match self.descriptions.lock().unwrap().get(key.name()) {
+            Some((unit, description)) => {
+                (unit.as_ref().map(otel_unit), Some(description.to_string()))
+            }
+            None => (None, None),
+        }
+    }
+
+    fn get_or_register<T>(
+        registry: &RwLock<HashMap<Key, Arc<T>>>,
+        key: &Key,
+        create: impl FnOnce() -> T,
+    ) -> Arc<T> {
+        if let Some(metric) = registry.read().unwrap().get(key) {
+            return Arc::clone(metric);
+        }
+
+        Arc::clone(
+            registry
+                .write()
+                .unwrap()
+                .entry(key.clone())
+                .or_insert_with(|| Arc::new(create())),
+        )
+    }
+}
+
+impl Recorder for OtlpMetricsRecorder {
+    fn describe_counter(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.describe(key, unit, description)
+    }
+
+    fn describe_gauge(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.describe(key, unit, description)
+    }
+
+    fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.describe(key, unit, description)
+    }
+
+    fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+        let counter = Self::get_or_register(&self.counters, key, || {
+            let (unit, description) = self.description(key);
+            let mut builder = self.meter.u64_counter(key.name().to_owned());
+            if let Some(unit) = unit {
+                builder = builder.with_unit(unit);
+            }
+            if let Some(description) = description {
+                builder = builder.with_description(description);
+            }
+
+            OtlpCounter {
+                counter: builder.build(),
+                attributes: attributes(key),
+                value: AtomicU64::new(0),
+            }
+        });
+
+        Counter::from_arc(counter)
+    }
+
+    fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+        let gauge = Self::get_or_register(&self.gauges, key, || {
+            let (unit, description) = self.description(key);
+            let mut builder = self.meter.f64_gauge(key.name().to_owned());
+            if let Some(unit) = unit {
+                builder = builder.with_unit(unit);
+            }
+            if let Some(description) = description {
+                builder = builder.with_description(description);
+            }
+
+            OtlpGauge {
+                gauge: builder.build(),
+                attributes: attributes(key),
+                value: AtomicU64::new(0f64.to_bits()),
+            }
+        });
+
+        Gauge::from_arc(gauge)
+    }
+
+    fn register_histogram(&self, key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+        let histogram = Self::get_or_register(&self.histograms, key, || {
+            let (unit, description) = self.description(key);
+            let mut builder = self.histogram_meter.f64_histogram(key.name().to_owned());
+            if let Some(unit) = unit {
+                builder = builder.with_unit(unit);
+            }
+            if let Some(description) = description {
+                builder = builder.with_description(description);
+            }
+
+            OtlpHistogram {
+                histogram: builder.build(),
+                attributes: attributes(key),
+            }
+        });
+
+        Histogram::from_arc(histogram)
+    }
+}
+
+/// The meter providers of the [`OtlpMetricsRecorder`], one for the cumulative counters and gauges
+/// and one for the delta histograms.
+#[derive(Clone)]
+pub struct OtlpMeterProviders {
+    metrics: SdkMeterProvider,
+    histograms: SdkMeterProvider,
+}
+
+impl OtlpMeterProviders {
+    /// Pushes the last metrics and stops exporting.
+    pub fn shutdown(&self) -> Result<(), MetricError> {
+        let histograms = self.histograms.shutdown();
+        self.metrics.shutdown().and(histograms)
+    }
+}
+
+fn build_exporter(
+    opts: &OtlpMetricsOptions,
+    endpoint: &str,
+    temporality: Temporality,
+) -> Result<MetricExporter, MetricError> {
+    let tls_config = match &opts.otlp_metrics_ca_path {
+        Some(ca_path) => {
+            let ca = std::fs::read(ca_path).map_err(|err| {
+                MetricError::Other(format!(
+                    "failed reading OTLP metrics CA '{}': {err}",
+                    ca_path.display()
+                ))
+            })?;
+            ClientTlsConfig::new().ca_certificate(Certificate::from_pem(ca))
+        }
+        None => ClientTlsConfig::new().with_native_roots(),
+    };
+    let header_map = HeaderMap::from_iter(HashMap::from(opts.otlp_metrics_headers.clone()));
+
+    MetricExporter::builder()
+        .with_tonic()
+        .with_tls_config(tls_config)
+        .with_endpoint(endpoint)
+        .with_metadata(MetadataMap::from_headers(header_map))
+        .with_temporality(temporality)
+        .build()
+}
+
+fn attributes(key: &Key) -> Vec<KeyValue> {
+    key.labels()
+        .map(|label| KeyValue::new(label.key().to_owned(), label.value().to_owned()))
+        .collect()
+}
+
+/// Maps the unit to its [UCUM](https://ucum.org/ucum) code as expected by OpenTelemetry.
+fn otel_unit(unit: &Unit) -> &'static str {
+    match unit {
+        Unit::Count => "1",
+        Unit::Percent => "%",
+        Unit::Seconds => "s",
+        Unit::Milliseconds => "ms",
+        Unit::Microseconds => "us",
+        Unit::Nanoseconds => "ns",
+        Unit::Tebibytes => "TiBy",
+        Unit::Gibibytes => "GiBy",
+        Unit::Mebibytes => "MiBy",
+        Unit::Kibibytes => "KiBy",
+        Unit::Bytes => "By",
+        Unit::TerabitsPerSecond => "Tbit/s",
+        Unit::GigabitsPerSecond => "Gbit/s",
+        Unit::MegabitsPerSecond => "Mbit/s",
+        Unit::KilobitsPerSecond => "kbit/s",
+        Unit::BitsPerSecond => "bit/s",
+        Unit::CountPerSecond => "1/s",
+    }
+}
+
+struct OtlpCounter {
+    counter: opentelemetry::metrics::Counter<u64>,
+    attributes: Vec<KeyValue>,
+    /// Total so far, needed to translate absolute values into increments
+    value: AtomicU64,
+}
+
+impl CounterFn for OtlpCounter {
+    fn increment(&self, value: u64) {
+        self.value.fetch_add(value, Ordering::Relaxed);
+        self.counter.add(value, &self.attributes);
+    }
+
+    fn absolute(&self, value: u64) {
+        let previous = self.value.fetch_max(value, Ordering::Relaxed);
+        if value > previous {
+            self.counter.add(value - previous, &self.attributes);
+        }
+    }
+}
+
+struct OtlpGauge {
+    gauge: opentelemetry::metrics::Gauge<f64>,
+    attributes: Vec<KeyValue>,
+    /// Bits of the current f64 value, needed to translate increments into absolute values
+    value: AtomicU64,
+}
+
+impl OtlpGauge {
+    fn update(&self, f: impl Fn(f64) -> f64) {
+        let mut current = self.value.load(Ordering::Relaxed);
+        loop {
+            let new = f(f64::from_bits(current));
+            match self.value.compare_exchange_weak(
+                current,
+                new.to_bits(),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    self.gauge.record(new, &self.attributes);
+                    return;
+                }
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
+impl GaugeFn for OtlpGauge {
+    fn increment(&self, value: f64) {
+        self.update(|current| current + value)
+    }
+
+    fn decrement(&self, value: f64) {
+        self.update(|current| current - value)
+    }
+
+    fn set(&self, value: f64) {
+        self.value.store(value.to_bits(), Ordering::Relaxed);
+        self.gauge.record(value, &self.attributes);
+    }
+}
+
+struct OtlpHistogram {
+    histogram: opentelemetry::metrics::Histogram<f64>,
+    attributes: Vec<KeyValue>,
+}
+
+impl HistogramFn for OtlpHistogram {
+    fn record(&self, value: f64) {
+        self.histogram.record(value, &self.attributes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use metrics::{Label, Level};
+    use opentelemetry_sdk::metrics::data::{ExponentialHistogram, ResourceMetrics, Sum};
+    use opentelemetry_sdk::testing::metrics::{
+        InMemoryMetricExporter, InMemoryMetricExporterBuilder,
+    };
+
+    const METADATA: Metadata<'static> = Metadata::new(module_path!(), Level::INFO, None);
+
+    fn recorder() -> (
+        OtlpMetricsRecorder,
+        InMemoryMetricExporter,
+        InMemoryMetricExporter,
+    ) {
+        let exporter = InMemoryMetricExporterBuilder::new()
+            .with_temporality(Temporality::Cumulative)
+            .build();
+        let histogram_exporter = InMemoryMetricExporterBuilder::new()
+            .with_temporality(Temporality::Delta)
+            .build();
+        let recorder = OtlpMetricsRecorder::new(
+            Resource::empty(),
+            exporter.clone(),
+            histogram_exporter.clone(),
+            Duration::from_secs(3600),
+        )
+        .unwrap();
+        (recorder, exporter, histogram_exporter)
+    }
+
+    /// Data of the metric in the last export
+    fn last_export<T: 'static, R>(
+        exporter: &InMemoryMetricExporter,
+        name: &str,
+        f: impl FnOnce(&T) -> R,
+    ) -> Option<R> {
+        let exports: Vec<ResourceMetrics> = exporter.get_finished_metrics().unwrap();
+        let metric = exports
+            .last()?
+            .scope_metrics
+            .iter()
+            .flat_map(|scope| scope.metrics.iter())
+            .find(|metric| metric.name == name)?;
+        Some(f(metric.data.as_any().downcast_ref::<T>().unwrap()))
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn counters_translate_absolute_values() {
+        let (recorder, exporter, _) = recorder();
+        let counter = recorder.register_counter(&Key::from_name("requests"), &METADATA);
+
+        counter.increment(2);
+        counter.absolute(5);
+        // absolute values never decrease a counter
+        counter.absolute(3);
+        recorder.meter_providers.metrics.force_flush().unwrap();
+
+        let value = last_export(&exporter, "requests", |sum: &Sum<u64>| {
+            sum.data_points[0].value
+        });
+        assert_eq!(Some(5), value);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn idle_histogram_series_are_forgotten() {
+        let (recorder, _, histogram_exporter) = recorder();
+        let key = Key::from_parts("latency", vec![Label::new("partition", "1")]);
+        let histogram = recorder.register_histogram(&key, &METADATA);
+
+        histogram.record(0.5);
+        histogram.record(1_000.0);
+        recorder.meter_providers.histograms.force_flush().unwrap();
+
+        let counts = last_export(
+            &histogram_exporter,
+            "latency",
+            |histogram: &ExponentialHistogram<f64>| {
+                histogram
+                    .data_points
+                    .iter()
+                    .map(|data_point| data_point.count)
+                    .collect::<Vec<_>>()
+            },
+        );
+        assert_eq!(Some(vec![2]), counts);
+
+        // nothing recorded since the last export
+        histogram_exporter.reset();
+        recorder.meter_providers.histograms.force_flush().unwrap();
+        let data_points = last_export(
+            &histogram_exporter,
+            "latency",
+            |histogram: &ExponentialHistogram<f64>| histogram.data_points.len(),
+        );
+        assert_eq!(0, data_points.unwrap_or_default());
+    }
+}
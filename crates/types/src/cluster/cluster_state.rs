@@ -138,6 +138,9 @@ pub struct PartitionProcessorStatus {
     pub last_applied_log_lsn: Option<Lsn>,
     pub last_record_applied_at: Option<MillisSinceEpoch>,
     pub num_skipped_records: u64,
+    /// Estimated size of the log records applied since the partition processor started.
+    #[serde(default)]
+    pub num_applied_log_bytes: u64,
     pub replay_status: ReplayStatus,
     pub last_persisted_log_lsn: Option<Lsn>,
     pub last_archived_log_lsn: Option<Lsn>,
@@ -156,6 +159,7 @@ impl Default for PartitionProcessorStatus {
             last_applied_log_lsn: None,
             last_record_applied_at: None,
             num_skipped_records: 0,
+            num_applied_log_bytes: 0,
             replay_status: ReplayStatus::Starting,
             last_persisted_log_lsn: None,
             last_archived_log_lsn: None,
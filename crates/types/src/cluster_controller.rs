@@ -156,6 +156,12 @@ impl SchedulingPlanBuilder {
         self.modified = true;
     }
 
+    pub fn remove_partition(&mut self, partition_id: &PartitionId) {
+        if self.inner.partitions.remove(partition_id).is_some() {
+            self.modified = true;
+        }
+    }
+
     pub fn build_if_modified(mut self) -> Option<SchedulingPlan> {
         if self.modified {
             self.inner.version = self.inner.version.next();
@@ -8,7 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU32, NonZeroUsize};
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -20,6 +20,7 @@ use tracing::warn;
 
 use crate::logs::metadata::ProviderKind;
 use crate::retries::RetryPolicy;
+use crate::storage::StorageCompression;
 
 use super::{CommonOptions, RocksDbOptions, RocksDbOptionsBuilder};
 
@@ -40,6 +41,8 @@ pub struct BifrostOptions {
     pub local: LocalLogletOptions,
     /// Configuration of replicated loglet provider
     pub replicated_loglet: ReplicatedLogletOptions,
+    /// Configuration of the object store tier for sealed log segments
+    pub tiered_storage: TieredStorageOptions,
 
     /// # Read retry policy
     ///
@@ -74,6 +77,27 @@ pub struct BifrostOptions {
     /// Defaults: 20M
     #[cfg_attr(feature = "schemars", schemars(with = "ByteCount"))]
     pub record_cache_memory_size: ByteCount,
+
+    /// # Record compression
+    ///
+    /// Compression applied to the payloads of appended records before they are handed to the
+    /// loglet. Compressed records are smaller on the network, in the record cache and on disk.
+    /// Records are always readable, regardless of this setting.
+    ///
+    /// Only enable compression once all nodes of the cluster run a version which can read
+    /// compressed records, older nodes fail to decode them. Disabling it again is always safe.
+    ///
+    /// Default: `None` - records are not compressed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record_compression: Option<StorageCompression>,
+
+    /// # Record compression threshold
+    ///
+    /// Records whose encoded payload is smaller than this are not compressed.
+    ///
+    /// Default: 4KiB
+    #[cfg_attr(feature = "schemars", schemars(with = "ByteCount"))]
+    pub record_compression_threshold: ByteCount,
 }
 
 impl BifrostOptions {
@@ -93,6 +117,7 @@ impl Default for BifrostOptions {
         Self {
             default_provider: ProviderKind::Local,
             replicated_loglet: ReplicatedLogletOptions::default(),
+            tiered_storage: TieredStorageOptions::default(),
             local: LocalLogletOptions::default(),
             read_retry_policy: RetryPolicy::exponential(
                 Duration::from_millis(50),
@@ -104,6 +129,77 @@ impl Default for BifrostOptions {
             append_retry_max_interval: Duration::from_secs(1).into(),
             seal_retry_interval: Duration::from_secs(2).into(),
             record_cache_memory_size: 20_000_000u64.into(), // 20MB
+            record_compression: None,
+            record_compression_threshold: 4096u64.into(), // 4KiB
+        }
+    }
+}
+
+/// # Tiered storage options
+///
+/// Sealed log segments can be moved to an object store from which they are served for the
+/// remainder of their lifetime. This keeps a long history of the logs available for replay and
+/// audit without growing the disks of log-servers or of the nodes running the local loglet.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(rename = "TieredStorageOptions", default)
+)]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct TieredStorageOptions {
+    /// # Destination
+    ///
+    /// Object store location to which sealed log segments are offloaded. This property supports
+    /// URLs with either `s3://` or `file://` protocol scheme. S3 credentials and the region are
+    /// read from the conventional AWS environment variables.
+    ///
+    /// Every node which reads the logs must be configured with the same destination to be able
+    /// to read offloaded segments.
+    ///
+    /// Example: `s3://logs-bucket/restate/cluster` stores segments in the specified bucket,
+    /// prefixing keys with the URL path.
+    ///
+    /// Default: `None` - offloaded segments can't be read and no segments are offloaded
+    pub destination: Option<String>,
+
+    /// # Offload interval
+    ///
+    /// How often the cluster controller looks for sealed segments to offload to the
+    /// destination.
+    ///
+    /// Default: `None` - segments are not offloaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub offload_interval: Option<humantime::Duration>,
+
+    /// # Records per chunk
+    ///
+    /// Number of records stored in a single object of an offloaded segment. Readers fetch whole
+    /// chunks, so smaller chunks reduce the read amplification of point reads while larger chunks
+    /// reduce the number of requests of sequential reads.
+    pub records_per_chunk: NonZeroU32,
+
+    /// # Source retention
+    ///
+    /// How long the data of an offloaded segment is kept by its original loglet. Readers which
+    /// started reading the segment before it was offloaded continue to read from the original
+    /// loglet until this period elapsed.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub source_retention: humantime::Duration,
+}
+
+impl Default for TieredStorageOptions {
+    fn default() -> Self {
+        Self {
+            destination: None,
+            offload_interval: None,
+            records_per_chunk: NonZeroU32::new(1000).expect("is non zero"),
+            source_retention: Duration::from_secs(60 * 60).into(),
         }
     }
 }
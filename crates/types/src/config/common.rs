@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
 use std::num::{NonZeroU16, NonZeroU32, NonZeroUsize};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -20,7 +21,7 @@ use serde_with::serde_as;
 
 use restate_serde_util::{NonZeroByteCount, SerdeableHeaderHashMap};
 
-use super::{AwsOptions, HttpOptions, PerfStatsLevel, RocksDbOptions};
+use super::{AwsOptions, HttpOptions, NetworkingTlsOptions, PerfStatsLevel, RocksDbOptions};
 use crate::net::{AdvertisedAddress, BindAddress};
 use crate::nodes_config::Role;
 use crate::retries::RetryPolicy;
@@ -152,6 +153,9 @@ pub struct CommonOptions {
     /// Disable prometheus metric recording and reporting. Default is `false`.
     pub disable_prometheus: bool,
 
+    #[serde(flatten)]
+    pub otlp_metrics: OtlpMetricsOptions,
+
     /// Storage high priority thread pool
     ///
     /// This configures the restate-managed storage thread pool for performing
@@ -349,6 +353,7 @@ impl Default for CommonOptions {
             bootstrap_num_partitions: NonZeroU16::new(24).expect("is not zero"),
             histogram_inactivity_timeout: None,
             disable_prometheus: false,
+            otlp_metrics: OtlpMetricsOptions::default(),
             service_client: Default::default(),
             shutdown_timeout: Duration::from_secs(60).into(),
             tracing: TracingOptions::default(),
@@ -466,6 +471,13 @@ pub struct MetadataStoreClientOptions {
     /// Backoff policy used by the metadata store client when it encounters concurrent
     /// modifications.
     pub metadata_store_client_backoff_policy: RetryPolicy,
+
+    /// # Metadata store TLS
+    ///
+    /// TLS settings used to connect to the embedded metadata store. On Restate nodes, this
+    /// defaults to the node's `networking.tls` settings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_store_tls: Option<NetworkingTlsOptions>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -489,6 +501,7 @@ pub enum ObjectStoreCredentials {
     AwsEnv,
 }
 
+#[serde_as]
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(
     tag = "type",
@@ -505,9 +518,14 @@ pub enum ObjectStoreCredentials {
 )]
 pub enum MetadataStoreClient {
     /// Connects to an embedded metadata store that is run by nodes that run with the MetadataStore role.
+    /// If the metadata store is replicated, the addresses of all members should be listed, so
+    /// that requests can be sent to the next member when one is unavailable. A single `address`
+    /// is accepted as well.
     Embedded {
-        #[cfg_attr(feature = "schemars", schemars(with = "String"))]
-        address: AdvertisedAddress,
+        #[serde(alias = "address")]
+        #[serde_as(as = "serde_with::OneOrMany<serde_with::Same>")]
+        #[cfg_attr(feature = "schemars", schemars(with = "Vec<String>"))]
+        addresses: Vec<AdvertisedAddress>,
     },
     /// Uses external etcd as metadata store.
     /// The addresses are formatted as `host:port`
@@ -529,9 +547,9 @@ impl Default for MetadataStoreClientOptions {
     fn default() -> Self {
         Self {
             metadata_store_client: MetadataStoreClient::Embedded {
-                address: "http://127.0.0.1:5122"
+                addresses: vec!["http://127.0.0.1:5122"
                     .parse()
-                    .expect("valid metadata store address"),
+                    .expect("valid metadata store address")],
             },
             metadata_store_connect_timeout: Duration::from_secs(5).into(),
             metadata_store_keep_alive_interval: Duration::from_secs(40).into(),
@@ -542,6 +560,7 @@ impl Default for MetadataStoreClientOptions {
                 None,
                 Some(Duration::from_millis(100)),
             ),
+            metadata_store_tls: None,
         }
     }
 }
@@ -625,11 +644,73 @@ impl Default for TracingOptions {
     }
 }
 
+#[serde_as]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(
+        title = "OTLP metrics",
+        description = "Options for pushing metrics to an OTLP collector"
+    )
+)]
+pub struct OtlpMetricsOptions {
+    /// # OTLP metrics endpoint
+    ///
+    /// Specify the endpoint of an OTLP collector to push metrics to. Metrics will be exported
+    /// using [OTLP gRPC](https://opentelemetry.io/docs/specs/otlp/#otlpgrpc). This is independent
+    /// of the Prometheus endpoint, both can be enabled at the same time.
+    ///
+    /// If unset, metrics are not pushed.
+    pub otlp_metrics_endpoint: Option<String>,
+
+    /// # OTLP metrics export interval
+    ///
+    /// Interval at which metrics are pushed to the OTLP collector.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub otlp_metrics_export_interval: humantime::Duration,
+
+    /// # Additional OTLP metrics headers
+    ///
+    /// Specify additional headers you want the system to send to the OTLP metrics endpoint (e.g.
+    /// authentication headers).
+    pub otlp_metrics_headers: SerdeableHeaderHashMap,
+
+    /// # OTLP metrics resource attributes
+    ///
+    /// Additional resource attributes attached to the exported metrics, e.g.
+    /// `{ "deployment.environment" = "production" }`. They override the default attributes
+    /// describing the node.
+    pub otlp_metrics_resource_attributes: HashMap<String, String>,
+
+    /// # OTLP metrics CA path
+    ///
+    /// Path to a PEM file containing the CA certificates used to verify the certificate of the
+    /// OTLP collector. If unset, the system's native root certificates are used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub otlp_metrics_ca_path: Option<PathBuf>,
+}
+
+impl Default for OtlpMetricsOptions {
+    fn default() -> Self {
+        Self {
+            otlp_metrics_endpoint: None,
+            otlp_metrics_export_interval: Duration::from_secs(30).into(),
+            otlp_metrics_headers: SerdeableHeaderHashMap::default(),
+            otlp_metrics_resource_attributes: HashMap::default(),
+            otlp_metrics_ca_path: None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::net::AdvertisedAddress;
     use crate::nodes_config::Role;
 
-    use super::CommonOptions;
+    use super::{CommonOptions, MetadataStoreClient};
 
     #[test]
     fn roles_compat_test() {
@@ -638,4 +719,25 @@ mod tests {
         // configuration with this role.
         assert!(!opts.roles.contains(Role::HttpIngress));
     }
+
+    #[test]
+    fn embedded_metadata_store_client_addresses() {
+        let addresses = |json| match serde_json::from_str(json).unwrap() {
+            MetadataStoreClient::Embedded { addresses } => addresses,
+            client => panic!("unexpected metadata store client {client:?}"),
+        };
+        let address = |address: &str| address.parse::<AdvertisedAddress>().unwrap();
+
+        // configurations of a single address keep working
+        assert_eq!(
+            addresses(r#"{"type": "embedded", "address": "http://127.0.0.1:5122"}"#),
+            vec![address("http://127.0.0.1:5122")]
+        );
+        assert_eq!(
+            addresses(
+                r#"{"type": "embedded", "addresses": ["http://node1:5122", "http://node2:5122"]}"#
+            ),
+            vec![address("http://node1:5122"), address("http://node2:5122")]
+        );
+    }
 }
@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use std::fmt;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
 
@@ -56,6 +57,12 @@ pub struct HttpOptions {
     #[serde_as(as = "serde_with::DisplayFromStr")]
     #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub connect_timeout: humantime::Duration,
+
+    /// # TLS
+    ///
+    /// TLS settings used when connecting to deployments over HTTPS. These can be overridden
+    /// for single deployments when registering them.
+    pub tls: TlsClientOptions,
 }
 
 impl Default for HttpOptions {
@@ -65,6 +72,7 @@ impl Default for HttpOptions {
             http_proxy: None,
             no_proxy: Vec::new(),
             connect_timeout: HttpOptions::default_connect_timeout(),
+            tls: TlsClientOptions::default(),
         }
     }
 }
@@ -130,6 +138,71 @@ impl Http2KeepAliveOptions {
     }
 }
 
+/// # TLS client options
+///
+/// Certificates used to establish TLS connections to deployments.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(default))]
+#[serde(rename_all = "kebab-case")]
+pub struct TlsClientOptions {
+    /// # CA bundle path
+    ///
+    /// Path to a PEM file containing the CA certificates used to verify the server certificates
+    /// of deployments. If set, these certificates are trusted instead of the system's native
+    /// root certificates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle_path: Option<PathBuf>,
+
+    /// # Client certificate path
+    ///
+    /// Path to a PEM file containing the client certificate, followed by its intermediate
+    /// certificates (if any), presented to deployments requiring mutual TLS. Must be set
+    /// together with `client-key-path`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert_path: Option<PathBuf>,
+
+    /// # Client key path
+    ///
+    /// Path to a PEM file containing the private key of the client certificate. PKCS#1, PKCS#8
+    /// and SEC1 encoded keys are supported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key_path: Option<PathBuf>,
+}
+
+impl TlsClientOptions {
+    /// Returns `true` if neither a CA bundle nor a client identity is configured, in which case
+    /// the native root certificates are used without client authentication.
+    pub fn is_default(&self) -> bool {
+        self.ca_bundle_path.is_none()
+            && self.client_cert_path.is_none()
+            && self.client_key_path.is_none()
+    }
+
+    /// Applies per-deployment overrides on top of these options. The CA bundle and the client
+    /// identity (certificate and key) are overridden independently of each other.
+    pub fn merge(&self, overrides: &TlsClientOptions) -> TlsClientOptions {
+        let (client_cert_path, client_key_path) =
+            if overrides.client_cert_path.is_some() || overrides.client_key_path.is_some() {
+                (
+                    overrides.client_cert_path.clone(),
+                    overrides.client_key_path.clone(),
+                )
+            } else {
+                (self.client_cert_path.clone(), self.client_key_path.clone())
+            };
+
+        TlsClientOptions {
+            ca_bundle_path: overrides
+                .ca_bundle_path
+                .clone()
+                .or_else(|| self.ca_bundle_path.clone()),
+            client_cert_path,
+            client_key_path,
+        }
+    }
+}
+
 #[derive(Clone, Debug, thiserror::Error)]
 #[error("invalid proxy Uri (must have scheme, authority, and path): {0}")]
 pub struct InvalidProxyUri(Uri);
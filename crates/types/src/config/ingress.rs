@@ -8,13 +8,19 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
+use std::fmt;
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use tokio::sync::Semaphore;
 
 use super::KafkaClusterOptions;
+use crate::schema::service::CorsPolicy;
 
 /// # Ingress options
 #[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
@@ -35,6 +41,25 @@ pub struct IngressOptions {
     /// the ingress will reply immediately with an appropriate status code. Default is unlimited.
     concurrent_api_requests_limit: Option<NonZeroUsize>,
 
+    /// # TLS
+    ///
+    /// If set, the ingress terminates TLS itself and only accepts HTTPS connections.
+    /// Configuring a client CA additionally enables mutual TLS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<IngressTlsOptions>,
+
+    /// # Authentication
+    ///
+    /// Authentication of the callers of the ingress. Disabled unless JWT verification or
+    /// API keys are configured.
+    pub auth: IngressAuthOptions,
+
+    /// # CORS
+    ///
+    /// Default CORS policy applied to browser requests. It can be overridden for single
+    /// services through the admin API.
+    pub cors: CorsPolicy,
+
     kafka_clusters: Vec<KafkaClusterOptions>,
 
     /// # Experimental feature to run the ingress independent of the worker role
@@ -91,9 +116,171 @@ impl Default for IngressOptions {
             bind_address: "0.0.0.0:8080".parse().unwrap(),
             // max is limited by Tower's LoadShedLayer.
             concurrent_api_requests_limit: None,
+            tls: None,
+            auth: IngressAuthOptions::default(),
+            cors: CorsPolicy::default(),
             kafka_clusters: Default::default(),
             experimental_feature_enable_separate_ingress_role: false,
             experimental_feature_kafka_ingress_next: false,
         }
     }
 }
+
+/// # Ingress TLS options
+///
+/// Certificates and keys are read from PEM files. The files are periodically checked for
+/// changes, so that rotated certificates are picked up by new connections without a restart.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "IngressTlsOptions"))]
+#[serde(rename_all = "kebab-case")]
+pub struct IngressTlsOptions {
+    /// # Certificate path
+    ///
+    /// Path to a PEM file containing the server certificate, followed by its intermediate
+    /// certificates (if any).
+    pub cert_path: PathBuf,
+
+    /// # Private key path
+    ///
+    /// Path to a PEM file containing the private key of the server certificate. PKCS#1, PKCS#8
+    /// and SEC1 encoded keys are supported.
+    pub key_path: PathBuf,
+
+    /// # Client CA path
+    ///
+    /// Path to a PEM file containing the CA certificates used to verify client certificates.
+    /// If set, every client must present a certificate signed by one of these CAs (mTLS).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_ca_path: Option<PathBuf>,
+
+    /// # Reload interval
+    ///
+    /// How often the certificate, key and client CA files are checked for changes. Must be
+    /// greater than 0.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde(default = "IngressTlsOptions::default_reload_interval")]
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub reload_interval: humantime::Duration,
+}
+
+impl IngressTlsOptions {
+    pub fn new(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            client_ca_path: None,
+            reload_interval: Self::default_reload_interval(),
+        }
+    }
+
+    fn default_reload_interval() -> humantime::Duration {
+        Duration::from_secs(60).into()
+    }
+}
+
+/// # Ingress authentication options
+///
+/// Callers authenticate with an `Authorization: Bearer <credentials>` header, where the
+/// credentials are either one of the configured API keys or a JWT signed by one of the keys
+/// in the configured JWKS files. Requests to the health endpoint are never authenticated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "IngressAuthOptions", default))]
+#[serde(rename_all = "kebab-case")]
+pub struct IngressAuthOptions {
+    /// # JWT verification
+    ///
+    /// If set, bearer tokens are verified as JWTs. The `sub` claim is used as principal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwt: Option<JwtAuthOptions>,
+
+    /// # API keys
+    ///
+    /// Static API keys, each one identifying a principal.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_keys: Vec<ApiKeyOptions>,
+
+    /// # Service allowlist
+    ///
+    /// Maps service names to the principals allowed to invoke them. Services which are not
+    /// listed can be invoked by every authenticated principal. Requests addressing invocations
+    /// or awakeables by id are only allowed to principals listed for every service.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub service_allowlist: HashMap<String, Vec<String>>,
+
+    /// # Principal header
+    ///
+    /// Name of the header used to forward the authenticated principal to the handlers.
+    /// Any value of this header sent by the caller is discarded.
+    pub principal_header: String,
+}
+
+impl IngressAuthOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.jwt.is_some() || !self.api_keys.is_empty()
+    }
+}
+
+impl Default for IngressAuthOptions {
+    fn default() -> Self {
+        Self {
+            jwt: None,
+            api_keys: Vec::new(),
+            service_allowlist: HashMap::new(),
+            principal_header: "x-restate-principal".to_owned(),
+        }
+    }
+}
+
+/// # JWT verification options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct JwtAuthOptions {
+    /// # JWKS paths
+    ///
+    /// Paths to local JSON Web Key Set files. Tokens must carry a `kid` header matching one
+    /// of the keys in these files, and be signed with the `alg` of that key. Keys without an
+    /// `alg` accept the default algorithm of their type: `RS256` for RSA, `ES256` or `ES384`
+    /// for EC depending on the curve, `EdDSA` for OKP and `HS256` for symmetric keys.
+    pub jwks_paths: Vec<PathBuf>,
+
+    /// # Issuer
+    ///
+    /// If set, the `iss` claim of the tokens must match this value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+
+    /// # Audience
+    ///
+    /// If set, the `aud` claim of the tokens must contain this value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
+}
+
+/// # API key
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct ApiKeyOptions {
+    /// # Principal
+    ///
+    /// Principal identified by this key, forwarded to the handlers.
+    pub principal: String,
+
+    /// # Key
+    pub key: String,
+}
+
+impl fmt::Debug for ApiKeyOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKeyOptions")
+            .field("principal", &self.principal)
+            .field("key", &"***")
+            .finish()
+    }
+}
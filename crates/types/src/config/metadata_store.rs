@@ -8,8 +8,9 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::path::PathBuf;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -18,6 +19,7 @@ use restate_serde_util::NonZeroByteCount;
 use tracing::warn;
 
 use super::{data_dir, CommonOptions, RocksDbOptions, RocksDbOptionsBuilder};
+use crate::net::AdvertisedAddress;
 
 /// # Metadata store options
 #[serde_as]
@@ -53,6 +55,11 @@ pub struct MetadataStoreOptions {
     ///
     /// The RocksDB options which will be used to configure the metadata store's RocksDB instance.
     pub rocksdb: RocksDbOptions,
+
+    /// # Metadata store kind
+    ///
+    /// The kind of embedded metadata store that nodes with the `metadata-store` role run.
+    pub kind: MetadataStoreKind,
 }
 
 impl MetadataStoreOptions {
@@ -87,6 +94,10 @@ impl MetadataStoreOptions {
         data_dir("local-metadata-store")
     }
 
+    pub fn raft_data_dir(&self) -> PathBuf {
+        data_dir("raft-metadata-store")
+    }
+
     pub fn request_queue_length(&self) -> usize {
         self.request_queue_length.get()
     }
@@ -104,6 +115,117 @@ impl Default for MetadataStoreOptions {
             rocksdb_memory_budget: None,
             rocksdb_memory_ratio: 0.01,
             rocksdb,
+            kind: MetadataStoreKind::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum MetadataStoreKind {
+    /// Single node metadata store. Clusters depend on the one node running it.
+    #[default]
+    Local,
+    /// Metadata store that is replicated across the members of a Raft group. Each member is a
+    /// node with the `metadata-store` role.
+    Raft(RaftOptions),
+}
+
+/// # Raft metadata store options
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct RaftOptions {
+    /// # Member id
+    ///
+    /// Id of this node within the Raft group. Must be unique among all members.
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
+    pub id: NonZeroU64,
+
+    /// # Initial members
+    ///
+    /// The members the Raft group is bootstrapped with. Nodes which are part of this list
+    /// bootstrap the group, all of them need to be configured with the same list. A node which
+    /// is not part of this list starts without any members and waits to be added to the group
+    /// via a membership change. It uses this list to reach the existing members.
+    #[serde(default)]
+    pub peers: Vec<RaftPeer>,
+
+    /// # Election tick
+    ///
+    /// Number of ticks without hearing from the leader after which a follower starts an election.
+    #[serde(default = "default_raft_election_tick")]
+    #[cfg_attr(feature = "schemars", schemars(with = "usize"))]
+    pub raft_election_tick: NonZeroUsize,
+
+    /// # Heartbeat tick
+    ///
+    /// Number of ticks between heartbeats of the leader. Must be smaller than the election tick.
+    #[serde(default = "default_raft_heartbeat_tick")]
+    #[cfg_attr(feature = "schemars", schemars(with = "usize"))]
+    pub raft_heartbeat_tick: NonZeroUsize,
+
+    /// # Tick interval
+    ///
+    /// Duration of a single Raft tick.
+    #[serde(default = "default_raft_tick_interval")]
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub raft_tick_interval: humantime::Duration,
+
+    /// # Log trim threshold
+    ///
+    /// Number of applied log entries after which the member snapshots its state and trims its
+    /// log.
+    #[serde(default = "default_log_trim_threshold")]
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
+    pub log_trim_threshold: NonZeroU64,
+}
+
+impl RaftOptions {
+    /// Creates the options of member `id` with default Raft settings.
+    pub fn new(id: NonZeroU64, peers: Vec<RaftPeer>) -> Self {
+        Self {
+            id,
+            peers,
+            raft_election_tick: default_raft_election_tick(),
+            raft_heartbeat_tick: default_raft_heartbeat_tick(),
+            raft_tick_interval: default_raft_tick_interval(),
+            log_trim_threshold: default_log_trim_threshold(),
         }
     }
 }
+
+fn default_raft_election_tick() -> NonZeroUsize {
+    NonZeroUsize::new(10).unwrap()
+}
+
+fn default_raft_heartbeat_tick() -> NonZeroUsize {
+    NonZeroUsize::new(2).unwrap()
+}
+
+fn default_raft_tick_interval() -> humantime::Duration {
+    Duration::from_millis(100).into()
+}
+
+fn default_log_trim_threshold() -> NonZeroU64 {
+    NonZeroU64::new(1000).unwrap()
+}
+
+/// # Raft peer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct RaftPeer {
+    /// # Member id
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
+    pub id: NonZeroU64,
+
+    /// # Address
+    ///
+    /// Advertised address of the member's node.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub address: AdvertisedAddress,
+}
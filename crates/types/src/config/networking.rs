@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use crate::retries::RetryPolicy;
@@ -63,6 +64,18 @@ pub struct NetworkingOptions {
     /// The number of messages that can be queued on the outbound stream of a single
     /// connection.
     pub outbound_queue_length: NonZeroUsize,
+
+    /// # TLS
+    ///
+    /// If set, the node's RPC server only accepts TLS connections from peers presenting a
+    /// certificate signed by the configured CA, and the node connects to its peers over TLS.
+    /// This covers the message fabric as well as the metadata store and log-server services.
+    /// Unix domain sockets are not affected.
+    ///
+    /// Note that all endpoints served on the node's RPC port, including metrics and health
+    /// checks, then require a client certificate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<NetworkingTlsOptions>,
 }
 
 impl Default for NetworkingOptions {
@@ -80,6 +93,44 @@ impl Default for NetworkingOptions {
             http2_keep_alive_interval: Duration::from_secs(5).into(),
             http2_keep_alive_timeout: Duration::from_secs(5).into(),
             http2_adaptive_window: true,
+            tls: None,
         }
     }
 }
+
+/// # Networking TLS options
+///
+/// Certificates used for mutually authenticated TLS between the nodes of a cluster.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "NetworkingTlsOptions"))]
+#[serde(rename_all = "kebab-case")]
+pub struct NetworkingTlsOptions {
+    /// # Certificate path
+    ///
+    /// Path to a PEM file containing the node's certificate, followed by its intermediate
+    /// certificates (if any). It is presented both when accepting and when opening connections,
+    /// so it must be valid for server and client authentication.
+    pub cert_path: PathBuf,
+
+    /// # Private key path
+    ///
+    /// Path to a PEM file containing the private key of the node's certificate. PKCS#1, PKCS#8
+    /// and SEC1 encoded keys are supported.
+    pub key_path: PathBuf,
+
+    /// # CA path
+    ///
+    /// Path to a PEM file containing the CA certificates used to verify the certificates of
+    /// peers.
+    pub ca_path: PathBuf,
+
+    /// # Server name
+    ///
+    /// Name to verify the peers' certificates against. If unset, the host of the peer's
+    /// advertised address is used, which binds each certificate to the node advertising that
+    /// address. Setting this allows all nodes to share a certificate issued for a single
+    /// cluster-wide name, in which case any node holding it can claim any node id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
+}
@@ -289,6 +289,15 @@ pub struct StorageOptions {
     #[cfg_attr(feature = "schemars", schemars(skip))]
     #[serde(skip_serializing_if = "std::ops::Not::not", default)]
     pub always_commit_in_background: bool,
+
+    /// # Encryption at rest
+    ///
+    /// If set, the values of the partition store (state, journals, invocation arguments, ...) and
+    /// the partition snapshots uploaded to the snapshot repository are encrypted. Existing
+    /// partition stores are encrypted when they are opened for the first time after enabling
+    /// encryption. Encryption can't be disabled again for a partition store once enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<EncryptionOptions>,
 }
 
 impl StorageOptions {
@@ -354,10 +363,52 @@ impl Default for StorageOptions {
             persist_lsn_interval: Some(Duration::from_secs(60 * 60).into()),
             persist_lsn_threshold: 1000,
             always_commit_in_background: false,
+            encryption: None,
         }
     }
 }
 
+/// # Encryption at rest options
+///
+/// Values are encrypted with data keys which are themselves encrypted (wrapped) with a
+/// key-encryption key of the configured key source. Only the wrapped data keys are persisted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "EncryptionOptions"))]
+#[serde(rename_all = "kebab-case")]
+pub struct EncryptionOptions {
+    /// # Key source
+    ///
+    /// Where the key-encryption keys are taken from.
+    pub key_source: KeySource,
+
+    /// # Active key id
+    ///
+    /// The key-encryption key which wraps new data keys. Defaults to the last key of the keyfile,
+    /// respectively to the most recently created key of the key management service.
+    ///
+    /// To rotate keys, add a new key and make it the active one. On the next start, all data keys
+    /// are re-wrapped with the new key-encryption key and a new data key is created for new
+    /// writes. Retired keys must be kept as long as snapshots which were wrapped with them might
+    /// be restored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_key_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum KeySource {
+    /// Key-encryption keys are read from a local keyfile. Each line of the file holds a key in
+    /// the format `<key-id>:<hex encoded 256 bit key>`. Empty lines and lines starting with `#`
+    /// are ignored.
+    Keyfile { path: PathBuf },
+    /// Stand-in for an external key management service. Key-encryption keys are created by the
+    /// service and never leave it, they are kept in the given directory which should be on an
+    /// access restricted volume. A first key is created if the directory holds no keys.
+    LocalKms { path: PathBuf },
+}
+
 /// # Snapshot options.
 /// Configures the worker store partition snapshot mechanism.
 #[serde_as]
@@ -391,6 +442,55 @@ pub struct SnapshotsOptions {
     ///
     /// Default: `None` - automatic snapshots are disabled by default
     pub snapshot_interval_num_records: Option<NonZeroU64>,
+
+    /// # Automatic snapshot time interval
+    ///
+    /// Maximum wall-clock time between two snapshots of a partition. Once the interval elapsed
+    /// since the last snapshot, a new snapshot is created if any log records were applied in the
+    /// meantime. This bounds the recovery point of partitions with little traffic, which might
+    /// take a long time to reach `snapshot-interval-num-records`.
+    ///
+    /// The interval is measured from the time the previous snapshot was requested, or from the
+    /// time the node started leading the partition.
+    ///
+    /// Default: `None` - time-based snapshots are disabled by default
+    #[serde(with = "serde_with::As::<Option<serde_with::DisplayFromStr>>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub snapshot_interval: Option<humantime::Duration>,
+
+    /// # Automatic snapshot applied log size
+    ///
+    /// Estimated size of the applied log records that triggers a snapshot to be created. Unlike
+    /// `snapshot-interval-num-records`, this accounts for the size of the records, so that
+    /// partitions with large payloads are snapshotted more often.
+    ///
+    /// Default: `None` - size-based snapshots are disabled by default
+    #[serde_as(as = "Option<NonZeroByteCount>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<NonZeroByteCount>"))]
+    pub snapshot_interval_num_bytes: Option<NonZeroUsize>,
+
+    /// # Number of snapshots to retain
+    ///
+    /// Number of most recent snapshots to retain per partition in the snapshot repository. Older
+    /// snapshots are deleted after a new snapshot is created, or when explicitly pruned using
+    /// `restatectl`. If `retain-max-age` is also set, a snapshot is only deleted once it falls
+    /// outside of both limits.
+    ///
+    /// The snapshot referenced by `latest.json`, as well as the oldest snapshot which is still
+    /// needed to cover the log trim point, are never deleted.
+    ///
+    /// Default: `None` - snapshots are retained indefinitely
+    pub retain_num_snapshots: Option<NonZeroUsize>,
+
+    /// # Maximum snapshot age
+    ///
+    /// Snapshots older than this are deleted from the snapshot repository, subject to
+    /// `retain-num-snapshots`.
+    ///
+    /// Default: `None` - snapshots are retained indefinitely
+    #[serde(with = "serde_with::As::<Option<serde_with::DisplayFromStr>>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub retain_max_age: Option<humantime::Duration>,
 }
 
 impl SnapshotsOptions {
@@ -401,4 +501,11 @@ impl SnapshotsOptions {
     pub fn snapshots_dir(&self, partition_id: PartitionId) -> PathBuf {
         super::data_dir("db-snapshots").join(partition_id.to_string())
     }
+
+    /// Returns whether any automatic snapshot trigger is configured.
+    pub fn is_automatic_snapshotting_enabled(&self) -> bool {
+        self.snapshot_interval_num_records.is_some()
+            || self.snapshot_interval.is_some()
+            || self.snapshot_interval_num_bytes.is_some()
+    }
 }
@@ -10,6 +10,7 @@
 
 //! This module contains all the core types representing a service invocation.
 
+use crate::deployment::PinnedDeployment;
 use crate::errors::InvocationError;
 use crate::identifiers::{
     EntryIndex, IdempotencyId, InvocationId, PartitionKey, PartitionProcessorRpcRequestId,
@@ -790,6 +791,36 @@ pub struct PurgeInvocationRequest {
     pub invocation_id: InvocationId,
 }
 
+/// Message to pause a running invocation.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PauseInvocationRequest {
+    pub invocation_id: InvocationId,
+}
+
+/// Message to resume a paused invocation.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ResumeInvocationRequest {
+    pub invocation_id: InvocationId,
+}
+
+/// Message to retry an invocation immediately, skipping the current retry backoff.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RetryInvocationNowRequest {
+    pub invocation_id: InvocationId,
+    /// If set, the invocation is pinned to this deployment starting from the next attempt.
+    pub pinned_deployment: Option<PinnedDeployment>,
+}
+
+/// Message to restart a paused or suspended invocation, replaying its journal up to the given entry.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RestartInvocationRequest {
+    pub invocation_id: InvocationId,
+    /// Index of the last journal entry to keep. All the following entries are removed.
+    pub journal_index: EntryIndex,
+    /// If set, the invocation is pinned to this deployment when restarting.
+    pub pinned_deployment: Option<PinnedDeployment>,
+}
+
 // A hack to allow spancontext to be serialized.
 // Details in https://github.com/open-telemetry/opentelemetry-rust/issues/576#issuecomment-1253396100
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -47,6 +47,7 @@ pub mod state_mut;
 pub mod storage;
 pub mod time;
 pub mod timer;
+pub mod tls;
 
 pub use id_util::{IdDecoder, IdEncoder, IdResourceType, IdStrCursor};
 pub use node_id::*;
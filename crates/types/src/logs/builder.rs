@@ -33,6 +33,10 @@ pub enum BuilderError {
     ParamsSerde(#[from] serde_json::Error),
     #[error("Segment conflicts with existing (base_lsn={0})")]
     SegmentConflict(Lsn),
+    #[error("segment {0} does not exist")]
+    UnknownSegment(SegmentIndex),
+    #[error("segment {0} is the writeable segment of the chain")]
+    WriteableSegment(SegmentIndex),
 }
 
 impl LogsBuilder {
@@ -231,6 +235,47 @@ impl<'a> ChainBuilder<'a> {
             }
         }
     }
+
+    /// Replaces the loglet of a sealed segment, keeping its index and base lsn. The new loglet
+    /// must serve the same records as the loglet it replaces.
+    ///
+    /// The tail segment can't be replaced since it is still being written to.
+    pub fn replace_segment_loglet(
+        &mut self,
+        segment_index: SegmentIndex,
+        provider: ProviderKind,
+        params: LogletParams,
+    ) -> Result<(), BuilderError> {
+        if self.inner.tail_index() == segment_index {
+            return Err(BuilderError::WriteableSegment(segment_index));
+        }
+
+        let loglet_config = self
+            .inner
+            .chain
+            .values_mut()
+            .find(|loglet_config| loglet_config.index() == segment_index)
+            .ok_or(BuilderError::UnknownSegment(segment_index))?;
+
+        if let ProviderKind::Replicated = loglet_config.kind {
+            let old_params =
+                ReplicatedLogletParams::deserialize_from(loglet_config.params.as_bytes())?;
+            self.lookup_index.rm_replicated_loglet_reference(
+                self.log_id,
+                segment_index,
+                old_params.loglet_id,
+            );
+        }
+        if let ProviderKind::Replicated = provider {
+            let new_params = ReplicatedLogletParams::deserialize_from(params.as_bytes())?;
+            self.lookup_index
+                .add_replicated_loglet(self.log_id, segment_index, new_params);
+        }
+
+        *loglet_config = LogletConfig::new(segment_index, provider, params);
+        *self.modified = true;
+        Ok(())
+    }
 }
 
 impl Deref for ChainBuilder<'_> {
@@ -515,6 +560,63 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_replace_segment_loglet() -> googletest::Result<()> {
+        let log_id = LogId::new(1);
+        let mut builder = LogsBuilder::default();
+        let mut chain = builder.add_log(
+            log_id,
+            Chain::new(
+                ProviderKind::InMemory,
+                LogletParams::from("test1".to_owned()),
+            ),
+        )?;
+        chain.append_segment(
+            Lsn::new(10),
+            ProviderKind::InMemory,
+            LogletParams::from("test2".to_owned()),
+        )?;
+
+        chain.replace_segment_loglet(
+            SegmentIndex(0),
+            ProviderKind::Local,
+            LogletParams::from("test3".to_owned()),
+        )?;
+
+        let segment = chain.head();
+        assert_that!(segment.index(), eq(SegmentIndex(0)));
+        assert_that!(segment.base_lsn, eq(Lsn::OLDEST));
+        assert_that!(segment.tail_lsn, eq(Some(Lsn::new(10))));
+        assert_that!(segment.config.kind, eq(ProviderKind::Local));
+        assert_that!(segment.config.params, eq(LogletParams::from("test3")));
+
+        // the writeable segment can't be replaced
+        assert_that!(
+            chain.replace_segment_loglet(
+                SegmentIndex(1),
+                ProviderKind::Local,
+                LogletParams::from("test4".to_owned()),
+            ),
+            err(pat!(BuilderError::WriteableSegment(_)))
+        );
+        assert_that!(
+            chain.replace_segment_loglet(
+                SegmentIndex(2),
+                ProviderKind::Local,
+                LogletParams::from("test4".to_owned()),
+            ),
+            err(pat!(BuilderError::UnknownSegment(_)))
+        );
+
+        let logs = builder.build();
+        assert_that!(
+            logs.chain(&log_id).unwrap().head().config.kind,
+            eq(ProviderKind::Local)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_trim_log_single_segment() -> googletest::Result<()> {
         let log_id = LogId::new(1);
@@ -257,6 +257,11 @@ impl TryFrom<crate::protobuf::cluster::DefaultProvider> for DefaultProvider {
                         .try_into()?,
                 }))
             }
+            ProviderKind::ObjectStore => {
+                anyhow::bail!(
+                    "object-store loglets are read-only and cannot be the default provider"
+                )
+            }
         }
     }
 }
@@ -460,6 +465,11 @@ pub enum ProviderKind {
     /// Replicated loglet implementation. This requires log-server role to run on
     /// enough nodes in the cluster.
     Replicated,
+    /// Read-only loglet serving the records of a sealed segment which has been offloaded to
+    /// an object store. Segments can't be created with this provider, they are only converted
+    /// to it once they are sealed.
+    #[cfg_attr(feature = "clap", value(skip))]
+    ObjectStore,
 }
 
 impl FromStr for ProviderKind {
@@ -470,6 +480,7 @@ impl FromStr for ProviderKind {
             #[cfg(any(test, feature = "memory-loglet"))]
             "in-memory" | "in_memory" | "memory" => Ok(Self::InMemory),
             "replicated" => Ok(Self::Replicated),
+            "object-store" | "object_store" => Ok(Self::ObjectStore),
             _ => anyhow::bail!("Unknown provider kind"),
         }
     }
@@ -502,6 +513,9 @@ impl Logs {
                         NonZeroU8::new(1).expect("1 is not zero"),
                     ),
                 }),
+                ProviderKind::ObjectStore => panic!(
+                    "object-store loglets are read-only and cannot be used as default-provider. Please use 'local' or 'replicated' instead."
+                ),
             },
         })
     }
@@ -679,6 +693,9 @@ pub fn new_single_node_loglet_params(default_provider: ProviderKind) -> LogletPa
         ProviderKind::Replicated => panic!(
             "replicated-loglet is still in development and cannot be used as default-provider in this version. Pleae use 'local' instead."
         ),
+        ProviderKind::ObjectStore => panic!(
+            "object-store loglets are read-only and cannot be used as default-provider. Please use 'local' instead."
+        ),
     }
 }
 
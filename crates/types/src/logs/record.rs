@@ -10,10 +10,12 @@
 
 use std::sync::Arc;
 
+use bytes::BytesMut;
 use serde::{Deserialize, Serialize};
 
 use crate::storage::{
-    EncodedPolyBytes, PolyBytes, StorageCodec, StorageDecode, StorageDecodeError, StorageEncode,
+    EncodedPolyBytes, PolyBytes, StorageCodec, StorageCompression, StorageDecode,
+    StorageDecodeError, StorageEncode, StorageEncodeError,
 };
 use crate::time::NanosSinceEpoch;
 
@@ -59,6 +61,29 @@ impl Record {
         &self.body
     }
 
+    /// Returns a copy of this record with the body encoded and compressed. Returns `None` if the
+    /// body is already encoded, smaller than `min_size` or doesn't compress.
+    pub fn compressed(
+        &self,
+        compression: StorageCompression,
+        min_size: usize,
+    ) -> Result<Option<Record>, StorageEncodeError> {
+        let PolyBytes::Typed(value) = &self.body else {
+            return Ok(None);
+        };
+
+        let mut buf = BytesMut::new();
+        if !StorageCodec::encode_compressed(&**value, compression, min_size, &mut buf)? {
+            return Ok(None);
+        }
+
+        Ok(Some(Record {
+            created_at: self.created_at,
+            body: PolyBytes::Bytes(buf.freeze()),
+            keys: self.keys.clone(),
+        }))
+    }
+
     pub fn dissolve(self) -> (NanosSinceEpoch, PolyBytes, Keys) {
         (self.created_at, self.body, self.keys)
     }
@@ -12,8 +12,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::cluster::cluster_state::RunMode;
 use crate::identifiers::{PartitionId, SnapshotId};
+use crate::logs::Lsn;
 use crate::net::define_rpc;
 use crate::net::{define_message, TargetName};
+use crate::time::MillisSinceEpoch;
 use crate::Version;
 
 define_message! {
@@ -80,4 +82,55 @@ pub struct CreateSnapshotResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SnapshotError {
     SnapshotCreationFailed(String),
+    SnapshotRepositoryFailed(String),
+}
+
+define_rpc! {
+    @request = ListSnapshotsRequest,
+    @response = ListSnapshotsResponse,
+    @request_target = TargetName::PartitionListSnapshotsRequest,
+    @response_target = TargetName::PartitionListSnapshotsResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListSnapshotsRequest {
+    pub partition_id: PartitionId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListSnapshotsResponse {
+    pub result: Result<Vec<SnapshotSummary>, SnapshotError>,
+}
+
+define_rpc! {
+    @request = PruneSnapshotsRequest,
+    @response = PruneSnapshotsResponse,
+    @request_target = TargetName::PartitionPruneSnapshotsRequest,
+    @response_target = TargetName::PartitionPruneSnapshotsResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PruneSnapshotsRequest {
+    pub partition_id: PartitionId,
+    /// Only report the snapshots which would be deleted.
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PruneSnapshotsResponse {
+    /// The deleted snapshots, or the ones which would be deleted in case of a dry run.
+    pub result: Result<Vec<SnapshotSummary>, SnapshotError>,
+}
+
+/// A snapshot stored in the snapshot repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotSummary {
+    pub snapshot_id: SnapshotId,
+    pub partition_id: PartitionId,
+    pub min_applied_lsn: Lsn,
+    pub created_at: MillisSinceEpoch,
+    /// Node that produced this snapshot.
+    pub node_name: String,
+    /// Whether this is the snapshot referenced by the latest snapshot pointer.
+    pub is_latest: bool,
 }
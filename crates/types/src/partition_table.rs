@@ -169,11 +169,36 @@ impl FindPartition for PartitionTable {
 #[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Partition {
     pub key_range: RangeInclusive<PartitionKey>,
+    /// Partitions from which this partition has been created by splitting or merging them. A
+    /// partition with parents bootstraps its partition store from the snapshots that the parents
+    /// took at the tail of their sealed logs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parents: Vec<PartitionId>,
+    /// A frozen partition is being split or merged. Its log is about to be sealed and must not be
+    /// extended anymore, even though the partition still owns its key range until it is replaced
+    /// by its children.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub frozen: bool,
 }
 
 impl Partition {
     pub fn new(key_range: RangeInclusive<PartitionKey>) -> Self {
-        Self { key_range }
+        Self {
+            key_range,
+            parents: Vec::new(),
+            frozen: false,
+        }
+    }
+
+    pub fn with_parents(
+        key_range: RangeInclusive<PartitionKey>,
+        parents: impl IntoIterator<Item = PartitionId>,
+    ) -> Self {
+        Self {
+            key_range,
+            parents: parents.into_iter().collect(),
+            frozen: false,
+        }
     }
 }
 
@@ -186,6 +211,16 @@ pub enum BuilderError {
     Duplicate(PartitionId),
     #[error("partition table has reached its limits")]
     LimitReached,
+    #[error("partition '{0}' does not exist")]
+    UnknownPartition(PartitionId),
+    #[error("split key '{split_key}' must be within ({start}, {end}]")]
+    InvalidSplitKey {
+        split_key: PartitionKey,
+        start: PartitionKey,
+        end: PartitionKey,
+    },
+    #[error("partitions '{0}' and '{1}' are not adjacent")]
+    NotAdjacent(PartitionId, PartitionId),
 }
 
 #[derive(Debug, Default)]
@@ -279,6 +314,123 @@ impl PartitionTableBuilder {
             self.inner
                 .partition_key_index
                 .remove(partition.key_range.end());
+            self.modified = true;
+        }
+    }
+
+    /// Freezes the given partition ahead of splitting or merging it, see [`Partition::frozen`].
+    pub fn freeze_partition(&mut self, partition_id: PartitionId) -> Result<(), BuilderError> {
+        let partition = self
+            .inner
+            .partitions
+            .get_mut(&partition_id)
+            .ok_or(BuilderError::UnknownPartition(partition_id))?;
+
+        if !partition.frozen {
+            partition.frozen = true;
+            self.modified = true;
+        }
+
+        Ok(())
+    }
+
+    /// Splits the given partition at `split_key` into two new partitions. The first partition
+    /// covers the keys smaller than `split_key`, the second one covers the remaining keys. Both
+    /// new partitions list the split partition as their parent. Returns the ids of the new
+    /// partitions in key order.
+    pub fn split_partition(
+        &mut self,
+        partition_id: PartitionId,
+        split_key: PartitionKey,
+    ) -> Result<(PartitionId, PartitionId), BuilderError> {
+        let partition = self
+            .inner
+            .partitions
+            .get(&partition_id)
+            .ok_or(BuilderError::UnknownPartition(partition_id))?;
+
+        let start = *partition.key_range.start();
+        let end = *partition.key_range.end();
+
+        if split_key <= start || split_key > end {
+            return Err(BuilderError::InvalidSplitKey {
+                split_key,
+                start,
+                end,
+            });
+        }
+
+        let left_id = self.next_partition_id()?;
+        let right_id = left_id
+            .checked_add(1)
+            .map(PartitionId::from)
+            .ok_or(BuilderError::LimitReached)?;
+
+        self.remove_partition(&partition_id);
+        self.add_partition(
+            left_id,
+            Partition::with_parents(start..=split_key - 1, [partition_id]),
+        )?;
+        self.add_partition(
+            right_id,
+            Partition::with_parents(split_key..=end, [partition_id]),
+        )?;
+
+        Ok((left_id, right_id))
+    }
+
+    /// Merges two partitions with adjacent key ranges into a new partition which lists both
+    /// merged partitions as its parents. Returns the id of the new partition.
+    pub fn merge_partitions(
+        &mut self,
+        partition_id: PartitionId,
+        other_partition_id: PartitionId,
+    ) -> Result<PartitionId, BuilderError> {
+        let partition = self
+            .inner
+            .partitions
+            .get(&partition_id)
+            .ok_or(BuilderError::UnknownPartition(partition_id))?;
+        let other_partition = self
+            .inner
+            .partitions
+            .get(&other_partition_id)
+            .ok_or(BuilderError::UnknownPartition(other_partition_id))?;
+
+        let (left, right) = if partition.key_range.start() < other_partition.key_range.start() {
+            (partition, other_partition)
+        } else {
+            (other_partition, partition)
+        };
+
+        if left.key_range.end().checked_add(1) != Some(*right.key_range.start()) {
+            return Err(BuilderError::NotAdjacent(partition_id, other_partition_id));
+        }
+
+        let key_range = *left.key_range.start()..=*right.key_range.end();
+        let merged_id = self.next_partition_id()?;
+
+        self.remove_partition(&partition_id);
+        self.remove_partition(&other_partition_id);
+        self.add_partition(
+            merged_id,
+            Partition::with_parents(key_range, [partition_id, other_partition_id]),
+        )?;
+
+        Ok(merged_id)
+    }
+
+    /// Partition ids are never reused because the log of a partition is derived from its id and
+    /// the logs of split or merged partitions continue to exist. Since new partitions always get
+    /// a larger id than their parents, the largest id in the table is the largest id that was
+    /// ever assigned.
+    fn next_partition_id(&self) -> Result<PartitionId, BuilderError> {
+        match self.inner.partitions.keys().next_back() {
+            Some(partition_id) => partition_id
+                .checked_add(1)
+                .map(PartitionId::from)
+                .ok_or(BuilderError::LimitReached),
+            None => Ok(PartitionId::MIN),
         }
     }
 
@@ -522,7 +674,7 @@ mod tests {
     use super::ReplicationStrategy;
     use crate::identifiers::{PartitionId, PartitionKey};
     use crate::partition_table::{
-        EqualSizedPartitionPartitioner, FindPartition, Partition, PartitionTable,
+        BuilderError, EqualSizedPartitionPartitioner, FindPartition, Partition, PartitionTable,
         PartitionTableBuilder,
     };
     use crate::storage::StorageCodec;
@@ -645,4 +797,176 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn freeze_partition() -> googletest::Result<()> {
+        let partition_table = PartitionTable::with_equally_sized_partitions(Version::MIN, 2);
+
+        let mut builder = PartitionTableBuilder::from(partition_table);
+        builder.freeze_partition(PartitionId::from(0))?;
+        let partition_table = builder.build();
+        assert!(
+            partition_table
+                .get_partition(&PartitionId::from(0))
+                .unwrap()
+                .frozen
+        );
+        assert!(
+            !partition_table
+                .get_partition(&PartitionId::from(1))
+                .unwrap()
+                .frozen
+        );
+
+        // freezing is idempotent
+        let mut builder = PartitionTableBuilder::from(partition_table.clone());
+        builder.freeze_partition(PartitionId::from(0))?;
+        assert!(builder.build_if_modified().is_none());
+
+        // the children of a frozen partition are not frozen
+        let mut builder = PartitionTableBuilder::from(partition_table);
+        let (left, right) = builder.split_partition(PartitionId::from(0), 1)?;
+        let partition_table = builder.build();
+        assert!(!partition_table.get_partition(&left).unwrap().frozen);
+        assert!(!partition_table.get_partition(&right).unwrap().frozen);
+
+        assert!(matches!(
+            PartitionTableBuilder::from(partition_table).freeze_partition(PartitionId::from(0)),
+            Err(BuilderError::UnknownPartition(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_partition() -> googletest::Result<()> {
+        let partition_table = PartitionTable::with_equally_sized_partitions(Version::MIN, 2);
+        let partition = partition_table
+            .get_partition(&PartitionId::from(0))
+            .expect("partition should exist")
+            .clone();
+        let split_key = *partition.key_range.end() / 2;
+
+        let mut builder = PartitionTableBuilder::from(partition_table);
+        let (left, right) = builder.split_partition(PartitionId::from(0), split_key)?;
+        let partition_table = builder.build();
+
+        assert_eq!(left, PartitionId::from(2));
+        assert_eq!(right, PartitionId::from(3));
+        assert_eq!(partition_table.num_partitions(), 3);
+        assert!(!partition_table.contains_partition(&PartitionId::from(0)));
+
+        let left_partition = partition_table.get_partition(&left).unwrap();
+        assert_eq!(
+            left_partition.key_range,
+            *partition.key_range.start()..=split_key - 1
+        );
+        assert_eq!(left_partition.parents, vec![PartitionId::from(0)]);
+
+        let right_partition = partition_table.get_partition(&right).unwrap();
+        assert_eq!(
+            right_partition.key_range,
+            split_key..=*partition.key_range.end()
+        );
+        assert_eq!(right_partition.parents, vec![PartitionId::from(0)]);
+
+        assert_eq!(partition_table.find_partition_id(split_key - 1)?, left);
+        assert_eq!(partition_table.find_partition_id(split_key)?, right);
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_partition_rejects_split_key_outside_of_range() {
+        let partition_table = PartitionTable::with_equally_sized_partitions(Version::MIN, 2);
+        let partition = partition_table
+            .get_partition(&PartitionId::from(1))
+            .expect("partition should exist")
+            .clone();
+        let mut builder = PartitionTableBuilder::from(partition_table);
+
+        // splitting at the start would create an empty partition
+        assert!(matches!(
+            builder.split_partition(PartitionId::from(1), *partition.key_range.start()),
+            Err(BuilderError::InvalidSplitKey { .. })
+        ));
+        assert!(matches!(
+            builder.split_partition(PartitionId::from(1), 0),
+            Err(BuilderError::InvalidSplitKey { .. })
+        ));
+        assert!(matches!(
+            builder.split_partition(PartitionId::from(2), 0),
+            Err(BuilderError::UnknownPartition(_))
+        ));
+        assert!(builder.build_if_modified().is_none());
+    }
+
+    #[test]
+    fn merge_partitions() -> googletest::Result<()> {
+        let partition_table = PartitionTable::with_equally_sized_partitions(Version::MIN, 3);
+
+        let mut builder = PartitionTableBuilder::from(partition_table);
+        // the order of the partitions must not matter
+        let merged = builder.merge_partitions(PartitionId::from(2), PartitionId::from(1))?;
+        let partition_table = builder.build();
+
+        assert_eq!(merged, PartitionId::from(3));
+        assert_eq!(partition_table.num_partitions(), 2);
+
+        let merged_partition = partition_table.get_partition(&merged).unwrap();
+        assert_eq!(
+            *merged_partition.key_range.start(),
+            EqualSizedPartitionPartitioner::partition_id_to_partition_range(
+                3,
+                PartitionId::from(1)
+            )
+            .into_inner()
+            .0
+        );
+        assert_eq!(*merged_partition.key_range.end(), PartitionKey::MAX);
+        assert_eq!(
+            merged_partition.parents,
+            vec![PartitionId::from(2), PartitionId::from(1)]
+        );
+        assert_eq!(
+            partition_table.find_partition_id(PartitionKey::MAX)?,
+            merged
+        );
+
+        // partition ids must not be reused even if the largest partition id was merged away
+        let mut builder = PartitionTableBuilder::from(partition_table);
+        let merged_again = builder.merge_partitions(PartitionId::from(0), merged)?;
+        assert_eq!(merged_again, PartitionId::from(4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge_partitions_rejects_non_adjacent_partitions() {
+        let partition_table = PartitionTable::with_equally_sized_partitions(Version::MIN, 3);
+        let mut builder = PartitionTableBuilder::from(partition_table);
+
+        assert!(matches!(
+            builder.merge_partitions(PartitionId::from(0), PartitionId::from(2)),
+            Err(BuilderError::NotAdjacent(_, _))
+        ));
+        assert!(builder.build_if_modified().is_none());
+    }
+
+    #[test]
+    fn partition_parents_survive_serialization() -> anyhow::Result<()> {
+        let mut builder = PartitionTableBuilder::from(
+            PartitionTable::with_equally_sized_partitions(Version::MIN, 1),
+        );
+        builder.split_partition(PartitionId::from(0), 1024)?;
+        let partition_table = builder.build();
+
+        let mut buf = BytesMut::default();
+        StorageCodec::encode(&partition_table, &mut buf)?;
+        let decoded = StorageCodec::decode::<PartitionTable, _>(&mut buf)?;
+
+        assert_eq!(decoded, partition_table);
+
+        Ok(())
+    }
 }
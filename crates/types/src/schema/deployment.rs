@@ -11,6 +11,7 @@ use std::collections::HashMap;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::ops::RangeInclusive;
+use std::path::PathBuf;
 
 use bytestring::ByteString;
 use http::header::{HeaderName, HeaderValue};
@@ -18,6 +19,7 @@ use http::Uri;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+use crate::config::TlsClientOptions;
 use crate::identifiers::{DeploymentId, LambdaARN, ServiceRevision};
 use crate::schema::service::ServiceMetadata;
 use crate::schema::Schema;
@@ -38,11 +40,20 @@ pub struct DeliveryOptions {
     )]
     #[cfg_attr(feature = "schemars", schemars(with = "HashMap<String, String>"))]
     pub additional_headers: HashMap<HeaderName, HeaderValue>,
+    /// Overrides of the node's TLS client options used when connecting to this deployment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsClientOptions>,
 }
 
 impl DeliveryOptions {
-    pub fn new(additional_headers: HashMap<HeaderName, HeaderValue>) -> Self {
-        Self { additional_headers }
+    pub fn new(
+        additional_headers: HashMap<HeaderName, HeaderValue>,
+        tls: Option<TlsClientOptions>,
+    ) -> Self {
+        Self {
+            additional_headers,
+            tls,
+        }
     }
 }
 
@@ -77,6 +88,13 @@ pub enum DeploymentType {
         #[cfg_attr(feature = "schemars", schemars(with = "String"))]
         http_version: http::Version,
     },
+    Unix {
+        socket_path: PathBuf,
+        protocol_type: ProtocolType,
+        #[serde(with = "serde_with::As::<restate_serde_util::VersionSerde>")]
+        #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+        http_version: http::Version,
+    },
     Lambda {
         arn: LambdaARN,
         #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
@@ -97,6 +115,12 @@ enum DeploymentTypeShadow {
         // this field did not used to be stored, so we must consider it optional when deserialising
         http_version: Option<http::Version>,
     },
+    Unix {
+        socket_path: PathBuf,
+        protocol_type: ProtocolType,
+        #[serde(with = "serde_with::As::<restate_serde_util::VersionSerde>")]
+        http_version: http::Version,
+    },
     Lambda {
         arn: LambdaARN,
         assume_role_arn: Option<ByteString>,
@@ -118,6 +142,15 @@ impl From<DeploymentTypeShadow> for DeploymentType {
                     None => Self::backfill_http_version(protocol_type),
                 },
             },
+            DeploymentTypeShadow::Unix {
+                socket_path,
+                protocol_type,
+                http_version,
+            } => Self::Unix {
+                socket_path,
+                protocol_type,
+                http_version,
+            },
             DeploymentTypeShadow::Lambda {
                 arn,
                 assume_role_arn,
@@ -205,7 +238,8 @@ impl DeploymentType {
 
     pub fn protocol_type(&self) -> ProtocolType {
         match self {
-            DeploymentType::Http { protocol_type, .. } => *protocol_type,
+            DeploymentType::Http { protocol_type, .. }
+            | DeploymentType::Unix { protocol_type, .. } => *protocol_type,
             DeploymentType::Lambda { .. } => ProtocolType::RequestResponse,
         }
     }
@@ -220,6 +254,9 @@ impl DeploymentType {
                     address.path()
                 )
             }
+            DeploymentType::Unix { socket_path, .. } => {
+                format!("unix:{}", socket_path.display())
+            }
             DeploymentType::Lambda { arn, .. } => arn.to_string(),
         }
     }
@@ -245,6 +282,25 @@ impl DeploymentMetadata {
         }
     }
 
+    pub fn new_unix(
+        socket_path: PathBuf,
+        protocol_type: ProtocolType,
+        http_version: http::Version,
+        delivery_options: DeliveryOptions,
+        supported_protocol_versions: RangeInclusive<i32>,
+    ) -> Self {
+        Self {
+            ty: DeploymentType::Unix {
+                socket_path,
+                protocol_type,
+                http_version,
+            },
+            delivery_options,
+            created_at: MillisSinceEpoch::now(),
+            supported_protocol_versions,
+        }
+    }
+
     pub fn new_lambda(
         arn: LambdaARN,
         assume_role_arn: Option<ByteString>,
@@ -263,13 +319,16 @@ impl DeploymentMetadata {
     }
 
     // address_display returns a Displayable identifier for the endpoint; for http endpoints this is a URI,
-    // and for Lambda deployments its the ARN
+    // for Unix domain socket deployments a unix:// URI of the socket path, and for Lambda deployments its the ARN
     pub fn address_display(&self) -> impl Display + '_ {
         struct Wrapper<'a>(&'a DeploymentType);
         impl<'a> Display for Wrapper<'a> {
             fn fmt(&self, f: &mut Formatter) -> fmt::Result {
                 match self {
                     Wrapper(DeploymentType::Http { address, .. }) => address.fmt(f),
+                    Wrapper(DeploymentType::Unix { socket_path, .. }) => {
+                        write!(f, "unix://{}", socket_path.display())
+                    }
                     Wrapper(DeploymentType::Lambda { arn, .. }) => arn.fmt(f),
                 }
             }
@@ -14,12 +14,14 @@ use crate::identifiers::{DeploymentId, ServiceRevision};
 use crate::invocation::{
     InvocationTargetType, ServiceType, VirtualObjectHandlerType, WorkflowHandlerType,
 };
+use crate::retries::RetryPolicy;
 use crate::schema::openapi::ServiceOpenAPI;
 use arc_swap::ArcSwapOption;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_with::serde_as;
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -122,6 +124,170 @@ pub struct ServiceMetadata {
     )]
     #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
     pub abort_timeout: Option<humantime::Duration>,
+
+    /// # CORS policy
+    ///
+    /// CORS policy applied by the ingress to the requests for this service.
+    /// If unset, the CORS policy configured in the ingress options is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cors: Option<CorsPolicy>,
+
+    /// # Retry policy
+    ///
+    /// Retry policy applied to the invocations of this service.
+    /// If unset, the retry policy configured in the worker invoker options is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<InvocationRetryPolicy>,
+}
+
+/// # CORS policy
+///
+/// Cross-origin resource sharing policy applied by the ingress to browser requests.
+/// The default allows every origin, method and header, without credentials.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(default))]
+#[serde(rename_all = "kebab-case")]
+pub struct CorsPolicy {
+    /// # Allowed origins
+    ///
+    /// Origins allowed to send requests, e.g. `https://example.com`. `*` allows any origin.
+    pub allowed_origins: Vec<String>,
+
+    /// # Allowed methods
+    ///
+    /// HTTP methods allowed in cross-origin requests. `*` allows any method.
+    pub allowed_methods: Vec<String>,
+
+    /// # Allowed headers
+    ///
+    /// Headers allowed in cross-origin requests. `*` allows any header.
+    pub allowed_headers: Vec<String>,
+
+    /// # Allow credentials
+    ///
+    /// If true, browsers are allowed to send credentials, such as cookies, with cross-origin requests.
+    #[serde(default)]
+    pub allow_credentials: bool,
+
+    /// # Max age
+    ///
+    /// How long browsers may cache the result of a preflight request.
+    /// If unset, browsers use their own default.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub max_age: Option<humantime::Duration>,
+}
+
+impl CorsPolicy {
+    pub const WILDCARD: &'static str = "*";
+
+    /// Policy allowing every origin, method and header. Credentials are not allowed, since
+    /// that would let any website send requests on behalf of the users visiting it.
+    pub fn permissive() -> Self {
+        Self {
+            allowed_origins: vec![Self::WILDCARD.to_owned()],
+            allowed_methods: vec![Self::WILDCARD.to_owned()],
+            allowed_headers: vec![Self::WILDCARD.to_owned()],
+            allow_credentials: false,
+            max_age: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), InvalidCorsPolicy> {
+        // Wildcard origins mirror the request origin, which together with credentials would let
+        // any website send credentialed requests
+        if self.allow_credentials && self.allowed_origins.iter().any(|o| o == Self::WILDCARD) {
+            return Err(InvalidCorsPolicy::CredentialsWithWildcardOrigin);
+        }
+        for origin in &self.allowed_origins {
+            if origin != Self::WILDCARD && http::HeaderValue::from_str(origin).is_err() {
+                return Err(InvalidCorsPolicy::Origin(origin.clone()));
+            }
+        }
+        for method in &self.allowed_methods {
+            if method != Self::WILDCARD && http::Method::from_str(method).is_err() {
+                return Err(InvalidCorsPolicy::Method(method.clone()));
+            }
+        }
+        for header in &self.allowed_headers {
+            if header != Self::WILDCARD && http::HeaderName::from_str(header).is_err() {
+                return Err(InvalidCorsPolicy::Header(header.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for CorsPolicy {
+    fn default() -> Self {
+        Self::permissive()
+    }
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum InvalidCorsPolicy {
+    #[error("invalid CORS allowed origin '{0}'")]
+    Origin(String),
+    #[error("invalid CORS allowed method '{0}'")]
+    Method(String),
+    #[error("invalid CORS allowed header '{0}'")]
+    Header(String),
+    #[error("CORS credentials can't be allowed for any origin '*', list the allowed origins")]
+    CredentialsWithWildcardOrigin,
+}
+
+/// # Invocation retry policy
+///
+/// Retry policy applied by the invoker to the invocations of a service or handler,
+/// overriding the retry policy configured in the worker invoker options.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct InvocationRetryPolicy {
+    /// # Retry policy
+    ///
+    /// Retry strategy, including the maximum number of attempts.
+    #[serde(flatten)]
+    pub retry_policy: RetryPolicy,
+
+    /// # On max attempts
+    ///
+    /// What to do with the invocation once the maximum number of attempts is reached.
+    #[serde(default)]
+    pub on_max_attempts: OnMaxAttempts,
+}
+
+impl From<RetryPolicy> for InvocationRetryPolicy {
+    fn from(retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            on_max_attempts: OnMaxAttempts::default(),
+        }
+    }
+}
+
+/// # On max attempts
+///
+/// Action taken once the retry policy is exhausted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum OnMaxAttempts {
+    /// # Fail
+    ///
+    /// Fail the invocation, returning the last error to the caller.
+    #[default]
+    Fail,
+    /// # Pause
+    ///
+    /// Pause the invocation, keeping its journal. It can be resumed later on,
+    /// for example after deploying a fix.
+    Pause,
 }
 
 // This type is used only for exposing the handler metadata, and not internally. See [ServiceAndHandlerType].
@@ -189,6 +355,13 @@ pub struct HandlerMetadata {
     /// JSON Schema of the handler output
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_json_schema: Option<serde_json::Value>,
+
+    /// # Retry policy
+    ///
+    /// Retry policy applied to the invocations of this handler.
+    /// If unset, the retry policy of the service is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<InvocationRetryPolicy>,
 }
 
 /// This API will return services registered by the user.
@@ -202,6 +375,28 @@ pub trait ServiceMetadataResolver {
 
     fn resolve_latest_service_type(&self, service_name: impl AsRef<str>) -> Option<ServiceType>;
 
+    /// Returns the CORS policy overriding the ingress default for the given service, if any.
+    fn resolve_latest_service_cors(&self, service_name: impl AsRef<str>) -> Option<CorsPolicy> {
+        self.resolve_latest_service(service_name)
+            .and_then(|service| service.cors)
+    }
+
+    /// Returns the retry policy overriding the invoker default for the given handler, if any.
+    /// The handler retry policy takes precedence over the service one.
+    fn resolve_latest_invocation_retry_policy(
+        &self,
+        service_name: impl AsRef<str>,
+        handler_name: impl AsRef<str>,
+    ) -> Option<InvocationRetryPolicy> {
+        let service = self.resolve_latest_service(service_name)?;
+        service
+            .handlers
+            .into_iter()
+            .find(|handler| handler.name == handler_name.as_ref())
+            .and_then(|handler| handler.retry_policy)
+            .or(service.retry_policy)
+    }
+
     fn list_services(&self) -> Vec<ServiceMetadata>;
 }
 
@@ -212,6 +407,8 @@ pub struct HandlerSchemas {
     pub documentation: Option<String>,
     #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
     pub metadata: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<InvocationRetryPolicy>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -225,6 +422,10 @@ pub struct ServiceSchemas {
     pub inactivity_timeout: Option<Duration>,
     pub abort_timeout: Option<Duration>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cors: Option<CorsPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<InvocationRetryPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub documentation: Option<String>,
     #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
     pub metadata: HashMap<String, String>,
@@ -250,6 +451,7 @@ impl ServiceSchemas {
                     output_description: h_schemas.target_meta.output_rules.to_string(),
                     input_json_schema: h_schemas.target_meta.input_rules.json_schema(),
                     output_json_schema: h_schemas.target_meta.output_rules.json_schema(),
+                    retry_policy: h_schemas.retry_policy.clone(),
                 })
                 .collect(),
             ty: self.ty,
@@ -262,6 +464,8 @@ impl ServiceSchemas {
             workflow_completion_retention: self.workflow_completion_retention.map(Into::into),
             inactivity_timeout: self.inactivity_timeout.map(Into::into),
             abort_timeout: self.abort_timeout.map(Into::into),
+            cors: self.cors.clone(),
+            retry_policy: self.retry_policy.clone(),
         }
     }
 
@@ -308,6 +512,28 @@ impl ServiceMetadataResolver for Schema {
         self.use_service_schema(service_name.as_ref(), |service_schemas| service_schemas.ty)
     }
 
+    fn resolve_latest_service_cors(&self, service_name: impl AsRef<str>) -> Option<CorsPolicy> {
+        self.use_service_schema(service_name.as_ref(), |service_schemas| {
+            service_schemas.cors.clone()
+        })
+        .flatten()
+    }
+
+    fn resolve_latest_invocation_retry_policy(
+        &self,
+        service_name: impl AsRef<str>,
+        handler_name: impl AsRef<str>,
+    ) -> Option<InvocationRetryPolicy> {
+        self.use_service_schema(service_name.as_ref(), |service_schemas| {
+            service_schemas
+                .handlers
+                .get(handler_name.as_ref())
+                .and_then(|handler_schemas| handler_schemas.retry_policy.clone())
+                .or_else(|| service_schemas.retry_policy.clone())
+        })
+        .flatten()
+    }
+
     fn list_services(&self) -> Vec<ServiceMetadata> {
         self.services
             .iter()
@@ -326,6 +552,8 @@ pub mod test_util {
     use serde_json::Value;
     use std::collections::HashMap;
 
+    use crate::{Version, Versioned};
+
     #[derive(Debug, Default, Clone)]
     pub struct MockServiceMetadataResolver(HashMap<String, ServiceMetadata>);
 
@@ -336,6 +564,12 @@ pub mod test_util {
         }
     }
 
+    impl Versioned for MockServiceMetadataResolver {
+        fn version(&self) -> Version {
+            Version::MIN
+        }
+    }
+
     impl ServiceMetadataResolver for MockServiceMetadataResolver {
         fn resolve_latest_service(&self, service_name: impl AsRef<str>) -> Option<ServiceMetadata> {
             self.0.get(service_name.as_ref()).cloned()
@@ -375,6 +609,7 @@ pub mod test_util {
                         output_description: "any".to_string(),
                         input_json_schema: None,
                         output_json_schema: None,
+                        retry_policy: None,
                     })
                     .collect(),
                 ty: ServiceType::Service,
@@ -387,6 +622,8 @@ pub mod test_util {
                 workflow_completion_retention: None,
                 inactivity_timeout: None,
                 abort_timeout: None,
+                cors: None,
+                retry_policy: None,
             }
         }
 
@@ -407,6 +644,7 @@ pub mod test_util {
                         output_description: "any".to_string(),
                         input_json_schema: None,
                         output_json_schema: None,
+                        retry_policy: None,
                     })
                     .collect(),
                 ty: ServiceType::VirtualObject,
@@ -419,6 +657,8 @@ pub mod test_util {
                 workflow_completion_retention: None,
                 inactivity_timeout: None,
                 abort_timeout: None,
+                cors: None,
+                retry_policy: None,
             }
         }
     }
@@ -8,23 +8,47 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
 use serde::Deserialize;
 use serde::Serialize;
+use serde_with::serde_as;
 use tracing::warn;
 
 use super::Schema;
 use crate::config::IngressOptions;
 use crate::errors::GenericError;
 use crate::identifiers::SubscriptionId;
-use crate::invocation::{VirtualObjectHandlerType, WorkflowHandlerType};
+use crate::invocation::{InvocationTarget, VirtualObjectHandlerType, WorkflowHandlerType};
+
+/// Options of a subscription starting with this prefix configure Restate rather than the Kafka
+/// client.
+pub const RESTATE_OPTIONS_PREFIX: &str = "restate.";
+/// Option selecting the [`ErrorPolicy`] of a subscription
+pub const ERROR_POLICY_OPTION: &str = "restate.error-policy";
+/// Option setting the topic of [`ErrorPolicy::DeadLetter`]
+pub const DEAD_LETTER_TOPIC_OPTION: &str = "restate.dead-letter-topic";
+/// Option selecting the [`ValueFormat`] of the records consumed by a subscription
+pub const VALUE_FORMAT_OPTION: &str = "restate.value-format";
+/// Option setting the URL of the schema registry of [`ValueFormat::Avro`] and
+/// [`ValueFormat::Protobuf`]
+pub const SCHEMA_REGISTRY_URL_OPTION: &str = "restate.schema-registry-url";
+/// Option setting the `user:password` credentials of the schema registry
+pub const SCHEMA_REGISTRY_BASIC_AUTH_OPTION: &str = "restate.schema-registry-basic-auth";
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Source {
-    Kafka { cluster: String, topic: String },
+    Kafka {
+        cluster: String,
+        topic: String,
+    },
+    /// Output of a handler, published by egress subscriptions
+    Service {
+        name: String,
+        handler: String,
+    },
 }
 
 impl fmt::Display for Source {
@@ -33,6 +57,9 @@ impl fmt::Display for Source {
             Source::Kafka { cluster, topic, .. } => {
                 write!(f, "kafka://{cluster}/{topic}")
             }
+            Source::Service { name, handler } => {
+                write!(f, "service://{name}/{handler}")
+            }
         }
     }
 }
@@ -65,6 +92,8 @@ pub enum Sink {
     Invocation {
         event_invocation_target_template: EventInvocationTargetTemplate,
     },
+    /// Kafka topic which egress subscriptions publish to
+    Kafka { cluster: String, topic: String },
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -106,16 +135,79 @@ impl fmt::Display for Sink {
             } => {
                 write!(f, "service://{name}/{handler}")
             }
+            Sink::Kafka { cluster, topic } => {
+                write!(f, "kafka://{cluster}/{topic}")
+            }
         }
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum EventTargetError {
+    #[error("the Kafka record key must be valid UTF-8: {0}")]
+    InvalidKey(#[from] std::str::Utf8Error),
+    #[error("the sink '{0}' cannot receive events")]
+    NotInvocable(String),
+}
+
+impl Sink {
+    /// Target of the invocation of an event with the given key.
+    pub fn invocation_target(&self, key: &[u8]) -> Result<InvocationTarget, EventTargetError> {
+        Ok(match self {
+            Sink::DeprecatedService { name, handler, ty } => match ty {
+                EventReceiverServiceType::VirtualObject => InvocationTarget::virtual_object(
+                    &**name,
+                    std::str::from_utf8(key)?.to_owned(),
+                    &**handler,
+                    VirtualObjectHandlerType::Exclusive,
+                ),
+                EventReceiverServiceType::Workflow => InvocationTarget::workflow(
+                    &**name,
+                    std::str::from_utf8(key)?.to_owned(),
+                    &**handler,
+                    WorkflowHandlerType::Workflow,
+                ),
+                EventReceiverServiceType::Service => InvocationTarget::service(&**name, &**handler),
+            },
+            Sink::Invocation {
+                event_invocation_target_template,
+            } => match event_invocation_target_template {
+                EventInvocationTargetTemplate::Service { name, handler } => {
+                    InvocationTarget::service(name.clone(), handler.clone())
+                }
+                EventInvocationTargetTemplate::VirtualObject {
+                    name,
+                    handler,
+                    handler_ty,
+                } => InvocationTarget::virtual_object(
+                    name.clone(),
+                    std::str::from_utf8(key)?.to_owned(),
+                    handler.clone(),
+                    *handler_ty,
+                ),
+                EventInvocationTargetTemplate::Workflow {
+                    name,
+                    handler,
+                    handler_ty,
+                } => InvocationTarget::workflow(
+                    name.clone(),
+                    std::str::from_utf8(key)?.to_owned(),
+                    handler.clone(),
+                    *handler_ty,
+                ),
+            },
+            Sink::Kafka { .. } => return Err(EventTargetError::NotInvocable(self.to_string())),
+        })
+    }
+}
+
 impl PartialEq<&str> for Sink {
     fn eq(&self, other: &&str) -> bool {
         self.to_string().as_str() == *other
     }
 }
 
+#[serde_as]
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Subscription {
@@ -123,6 +215,15 @@ pub struct Subscription {
     source: Source,
     sink: Sink,
     metadata: HashMap<String, String>,
+    /// Paused subscriptions keep their consumer group offsets, but don't consume nor publish
+    #[serde(default)]
+    paused: bool,
+    /// Base of the deduplication sequence numbers by Kafka partition, see
+    /// [`Subscription::deduplication_index`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Vec<(i32, u64)>"))]
+    deduplication_bases: BTreeMap<i32, u64>,
 }
 
 impl Subscription {
@@ -137,6 +238,8 @@ impl Subscription {
             source,
             sink,
             metadata,
+            paused: false,
+            deduplication_bases: BTreeMap::new(),
         }
     }
 
@@ -152,6 +255,10 @@ impl Subscription {
         &self.sink
     }
 
+    pub fn set_sink(&mut self, sink: Sink) {
+        self.sink = sink;
+    }
+
     pub fn metadata(&self) -> &HashMap<String, String> {
         &self.metadata
     }
@@ -159,6 +266,148 @@ impl Subscription {
     pub fn metadata_mut(&mut self) -> &mut HashMap<String, String> {
         &mut self.metadata
     }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Sequence number by which the partition processors deduplicate the record at `offset` of
+    /// the given Kafka partition.
+    pub fn deduplication_index(&self, partition: i32, offset: i64) -> u64 {
+        self.deduplication_bases
+            .get(&partition)
+            .copied()
+            .unwrap_or_default()
+            + offset as u64
+    }
+
+    /// Makes the records of the given Kafka partition look new to the deduplication, so that
+    /// they are processed again after the offsets of the consumer group were moved backwards.
+    /// Since all consumed records are below the high watermark, the sequence numbers of the
+    /// records consumed from now on exceed the ones of the records consumed before. Raising the
+    /// base again for the same reset is harmless.
+    pub fn raise_deduplication_base(&mut self, partition: i32, high_watermark: i64) {
+        *self.deduplication_bases.entry(partition).or_default() += high_watermark.max(0) as u64;
+    }
+
+    /// Options which are passed on to the Kafka client.
+    pub fn kafka_options(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.metadata
+            .iter()
+            .filter(|(k, _)| !k.starts_with(RESTATE_OPTIONS_PREFIX))
+    }
+
+    /// What to do with records which cannot be turned into invocations.
+    pub fn error_policy(&self) -> Result<ErrorPolicy, ValidationError> {
+        let Some(policy) = self.metadata.get(ERROR_POLICY_OPTION) else {
+            return Ok(ErrorPolicy::default());
+        };
+
+        match policy.as_str() {
+            "fail" => Ok(ErrorPolicy::Fail),
+            "skip" => Ok(ErrorPolicy::Skip),
+            "dead-letter" => {
+                let topic = self
+                    .metadata
+                    .get(DEAD_LETTER_TOPIC_OPTION)
+                    .ok_or(ValidationError {
+                        name: DEAD_LETTER_TOPIC_OPTION,
+                        reason: "the dead-letter error policy requires a dead-letter topic",
+                    })?;
+                Ok(ErrorPolicy::DeadLetter {
+                    topic: topic.clone(),
+                })
+            }
+            "park" => Ok(ErrorPolicy::Park),
+            _ => Err(ValidationError {
+                name: ERROR_POLICY_OPTION,
+                reason: "supported error policies are [fail, skip, dead-letter, park]",
+            }),
+        }
+    }
+
+    /// How to decode the values of the consumed records.
+    pub fn value_format(&self) -> Result<ValueFormat, ValidationError> {
+        let Some(format) = self.metadata.get(VALUE_FORMAT_OPTION) else {
+            return Ok(ValueFormat::default());
+        };
+
+        let schema_registry = || -> Result<SchemaRegistryOptions, ValidationError> {
+            let url = self
+                .metadata
+                .get(SCHEMA_REGISTRY_URL_OPTION)
+                .ok_or(ValidationError {
+                    name: SCHEMA_REGISTRY_URL_OPTION,
+                    reason: "the avro and protobuf value formats require a schema registry",
+                })?;
+            let basic_auth = self.metadata.get(SCHEMA_REGISTRY_BASIC_AUTH_OPTION);
+            if basic_auth.is_some_and(|basic_auth| !basic_auth.contains(':')) {
+                return Err(ValidationError {
+                    name: SCHEMA_REGISTRY_BASIC_AUTH_OPTION,
+                    reason: "the basic auth credentials must be formatted as user:password",
+                });
+            }
+            Ok(SchemaRegistryOptions {
+                url: url.clone(),
+                basic_auth: basic_auth.cloned(),
+            })
+        };
+
+        match format.as_str() {
+            "raw" => Ok(ValueFormat::Raw),
+            "avro" => Ok(ValueFormat::Avro(schema_registry()?)),
+            "protobuf" => Ok(ValueFormat::Protobuf(schema_registry()?)),
+            _ => Err(ValidationError {
+                name: VALUE_FORMAT_OPTION,
+                reason: "supported value formats are [raw, avro, protobuf]",
+            }),
+        }
+    }
+
+    /// Whether this subscription publishes the output of a handler to Kafka, rather than
+    /// consuming a Kafka topic.
+    pub fn is_egress(&self) -> bool {
+        matches!(self.source, Source::Service { .. })
+    }
+}
+
+/// What the Kafka ingress does with a record which cannot be turned into an invocation, for
+/// example because a keyed handler receives a record whose key is not valid UTF-8.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Stop consuming the Kafka partition and retry the record until it succeeds
+    #[default]
+    Fail,
+    /// Log the error and skip the record
+    Skip,
+    /// Publish the raw record to the given topic of the subscription's cluster
+    DeadLetter { topic: String },
+    /// Park the record in Restate, from where it can be inspected and replayed
+    Park,
+}
+
+/// Format of the values of the records consumed by a subscription.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ValueFormat {
+    /// Values are passed as is to the handler
+    #[default]
+    Raw,
+    /// Values are Avro datums in the Confluent wire format, converted to JSON
+    Avro(SchemaRegistryOptions),
+    /// Values are Protobuf messages in the Confluent wire format, converted to JSON
+    Protobuf(SchemaRegistryOptions),
+}
+
+/// Schema registry implementing the Confluent schema registry API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaRegistryOptions {
+    pub url: String,
+    /// `user:password` credentials
+    pub basic_auth: Option<String>,
 }
 
 pub enum ListSubscriptionFilter {
@@ -218,9 +467,23 @@ pub struct ValidationError {
 impl SubscriptionValidator for IngressOptions {
     type Error = ValidationError;
 
-    fn validate(&self, mut subscription: Subscription) -> Result<Subscription, Self::Error> {
+    fn validate(&self, subscription: Subscription) -> Result<Subscription, Self::Error> {
+        match subscription.source() {
+            Source::Kafka { .. } => self.validate_ingress(subscription),
+            Source::Service { .. } => self.validate_egress(subscription),
+        }
+    }
+}
+
+impl IngressOptions {
+    fn validate_ingress(
+        &self,
+        mut subscription: Subscription,
+    ) -> Result<Subscription, ValidationError> {
         // Retrieve the cluster option and merge them with subscription metadata
-        let Source::Kafka { cluster, .. } = subscription.source();
+        let Source::Kafka { cluster, .. } = subscription.source() else {
+            unreachable!("ingress subscriptions have a Kafka source");
+        };
         let cluster_options = &self.get_kafka_cluster(cluster).ok_or(ValidationError {
             name: "source",
             reason: "specified cluster in the source URI does not exist. Make sure it is defined in the KafkaOptions",
@@ -239,6 +502,9 @@ impl SubscriptionValidator for IngressOptions {
             warn!("The configuration option enable.auto.offset.store should not be set and it will be ignored.");
         }
 
+        subscription.error_policy()?;
+        subscription.value_format()?;
+
         // Set the group.id if unset
         if !(cluster_options.contains_key("group.id")
             || subscription.metadata().contains_key("group.id"))
@@ -261,6 +527,33 @@ impl SubscriptionValidator for IngressOptions {
 
         Ok(subscription)
     }
+
+    fn validate_egress(
+        &self,
+        mut subscription: Subscription,
+    ) -> Result<Subscription, ValidationError> {
+        let Sink::Kafka { cluster, .. } = subscription.sink() else {
+            return Err(ValidationError {
+                name: "sink",
+                reason: "the output of a service can only be published to a Kafka topic",
+            });
+        };
+        let cluster_options = &self.get_kafka_cluster(cluster).ok_or(ValidationError {
+            name: "sink",
+            reason: "specified cluster in the sink URI does not exist. Make sure it is defined in the KafkaOptions",
+        })?.additional_options;
+
+        // Set client.id if unset
+        if !(cluster_options.contains_key("client.id")
+            || subscription.metadata().contains_key("client.id"))
+        {
+            subscription
+                .metadata_mut()
+                .insert("client.id".to_string(), "restate".to_string());
+        }
+
+        Ok(subscription)
+    }
 }
 
 #[cfg(feature = "test-util")]
@@ -286,7 +579,136 @@ pub mod mocks {
                     },
                 },
                 metadata: Default::default(),
+                paused: false,
+                deduplication_bases: BTreeMap::new(),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(options: &[(&str, &str)]) -> Subscription {
+        Subscription::new(
+            SubscriptionId::new(),
+            Source::Kafka {
+                cluster: "my-cluster".to_string(),
+                topic: "my-topic".to_string(),
+            },
+            Sink::Invocation {
+                event_invocation_target_template: EventInvocationTargetTemplate::Service {
+                    name: "MySvc".to_string(),
+                    handler: "MyMethod".to_string(),
+                },
+            },
+            options
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn error_policy_from_options() {
+        assert_eq!(subscription(&[]).error_policy().unwrap(), ErrorPolicy::Fail);
+        assert_eq!(
+            subscription(&[(ERROR_POLICY_OPTION, "skip")])
+                .error_policy()
+                .unwrap(),
+            ErrorPolicy::Skip
+        );
+        assert_eq!(
+            subscription(&[
+                (ERROR_POLICY_OPTION, "dead-letter"),
+                (DEAD_LETTER_TOPIC_OPTION, "my-topic-dlq")
+            ])
+            .error_policy()
+            .unwrap(),
+            ErrorPolicy::DeadLetter {
+                topic: "my-topic-dlq".to_string()
+            }
+        );
+        assert!(subscription(&[(ERROR_POLICY_OPTION, "dead-letter")])
+            .error_policy()
+            .is_err());
+        assert!(subscription(&[(ERROR_POLICY_OPTION, "retry")])
+            .error_policy()
+            .is_err());
+    }
+
+    #[test]
+    fn value_format_from_options() {
+        assert_eq!(subscription(&[]).value_format().unwrap(), ValueFormat::Raw);
+        assert_eq!(
+            subscription(&[
+                (VALUE_FORMAT_OPTION, "avro"),
+                (SCHEMA_REGISTRY_URL_OPTION, "http://localhost:8081")
+            ])
+            .value_format()
+            .unwrap(),
+            ValueFormat::Avro(SchemaRegistryOptions {
+                url: "http://localhost:8081".to_string(),
+                basic_auth: None,
+            })
+        );
+        assert!(subscription(&[(VALUE_FORMAT_OPTION, "protobuf")])
+            .value_format()
+            .is_err());
+        assert!(subscription(&[(VALUE_FORMAT_OPTION, "json")])
+            .value_format()
+            .is_err());
+        assert!(subscription(&[
+            (VALUE_FORMAT_OPTION, "protobuf"),
+            (SCHEMA_REGISTRY_URL_OPTION, "http://localhost:8081"),
+            (SCHEMA_REGISTRY_BASIC_AUTH_OPTION, "user")
+        ])
+        .value_format()
+        .is_err());
+    }
+
+    #[test]
+    fn deduplication_index_after_offsets_reset() {
+        let mut subscription = subscription(&[]);
+        assert_eq!(subscription.deduplication_index(0, 42), 42);
+
+        // records consumed again after moving the offsets of partition 0 back look new
+        subscription.raise_deduplication_base(0, 100);
+        assert!(subscription.deduplication_index(0, 0) > 99);
+        assert_eq!(subscription.deduplication_index(0, 42), 142);
+        // other partitions keep deduplicating against the records consumed before
+        assert_eq!(subscription.deduplication_index(1, 42), 42);
+
+        let last_before_reset = subscription.deduplication_index(0, 9);
+        subscription.raise_deduplication_base(0, 10);
+        assert!(subscription.deduplication_index(0, 0) > last_before_reset);
+    }
+
+    #[test]
+    fn deduplication_bases_survive_serialization() {
+        let mut subscription = subscription(&[]);
+        assert_eq!(
+            flexbuffers::from_slice::<Subscription>(&flexbuffers::to_vec(&subscription).unwrap())
+                .unwrap(),
+            subscription
+        );
+
+        subscription.raise_deduplication_base(3, 100);
+        assert_eq!(
+            flexbuffers::from_slice::<Subscription>(&flexbuffers::to_vec(&subscription).unwrap())
+                .unwrap(),
+            subscription
+        );
+    }
+
+    #[test]
+    fn restate_options_are_not_passed_to_kafka() {
+        let subscription = subscription(&[(ERROR_POLICY_OPTION, "park"), ("group.id", "my-group")]);
+
+        assert_eq!(
+            subscription.kafka_options().collect::<Vec<_>>(),
+            vec![(&"group.id".to_string(), &"my-group".to_string())]
+        );
+    }
+}
@@ -45,6 +45,49 @@ pub enum StorageCodecKind {
     FlexbuffersSerde = 2,
     // length-prefixed raw-bytes. length is u32
     LengthPrefixedRawBytes = 3,
+    // zstd-compressed value of another codec, see [`StorageCodec::encode_compressed`]
+    Zstd = 4,
+    // lz4-compressed value of another codec, see [`StorageCodec::encode_compressed`]
+    Lz4 = 5,
+}
+
+impl StorageCodecKind {
+    fn is_compressed(&self) -> bool {
+        matches!(self, StorageCodecKind::Zstd | StorageCodecKind::Lz4)
+    }
+}
+
+/// Compression algorithm applied to encoded values by [`StorageCodec::encode_compressed`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum StorageCompression {
+    /// Better compression ratio, slower
+    Zstd,
+    /// Faster, lower compression ratio
+    Lz4,
+}
+
+impl StorageCompression {
+    const ZSTD_LEVEL: i32 = 3;
+    /// Larger values are not compressed. Decompression refuses larger lengths, so that a corrupted
+    /// length can't make it allocate an arbitrary amount of memory.
+    const MAX_UNCOMPRESSED_LENGTH: usize = 64 * 1024 * 1024;
+
+    fn codec(&self) -> StorageCodecKind {
+        match self {
+            StorageCompression::Zstd => StorageCodecKind::Zstd,
+            StorageCompression::Lz4 => StorageCodecKind::Lz4,
+        }
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, StorageEncodeError> {
+        match self {
+            StorageCompression::Zstd => zstd::bulk::compress(data, Self::ZSTD_LEVEL)
+                .map_err(|err| StorageEncodeError::EncodeValue(err.into())),
+            StorageCompression::Lz4 => Ok(lz4_flex::block::compress(data)),
+        }
+    }
 }
 
 impl From<StorageCodecKind> for u8 {
@@ -90,7 +133,68 @@ impl StorageCodec {
         Ok(buf.split())
     }
 
+    /// Encodes the value like [`Self::encode`] and compresses the encoded value if it is at least
+    /// `min_size` bytes and at most 64MiB large and compression reduces its size. Compressed
+    /// values are written as the codec byte of the compression, the uncompressed and compressed
+    /// lengths (u32) and the compressed value.
+    ///
+    /// Returns whether the value has been compressed. Values encoded by this method can be
+    /// decoded by [`Self::decode`].
+    pub fn encode_compressed<T: StorageEncode + ?Sized>(
+        value: &T,
+        compression: StorageCompression,
+        min_size: usize,
+        buf: &mut BytesMut,
+    ) -> Result<bool, StorageEncodeError> {
+        let start = buf.len();
+        Self::encode(value, buf)?;
+
+        let encoded_length = buf.len() - start;
+        if encoded_length < min_size || encoded_length > StorageCompression::MAX_UNCOMPRESSED_LENGTH
+        {
+            return Ok(false);
+        }
+
+        let uncompressed = buf.split_off(start);
+        let compressed = compression.compress(&uncompressed)?;
+        if compressed.len() >= uncompressed.len() {
+            buf.unsplit(uncompressed);
+            return Ok(false);
+        }
+
+        let length_overflow = |_| {
+            StorageEncodeError::EncodeValue(
+                anyhow::anyhow!("only support compressing values of size <= 4GB").into(),
+            )
+        };
+        buf.reserve(mem::size_of::<u8>() + 2 * mem::size_of::<u32>() + compressed.len());
+        buf.put_u8(compression.codec().into());
+        buf.put_u32_le(u32::try_from(uncompressed.len()).map_err(length_overflow)?);
+        buf.put_u32_le(u32::try_from(compressed.len()).map_err(length_overflow)?);
+        buf.put_slice(&compressed);
+        Ok(true)
+    }
+
     pub fn decode<T: StorageDecode, B: Buf>(buf: &mut B) -> Result<T, StorageDecodeError> {
+        // read version
+        let codec = Self::read_codec(buf)?;
+
+        if codec.is_compressed() {
+            let mut decompressed = Self::decompress(codec, buf)?;
+            let codec = Self::read_codec(&mut decompressed)?;
+            if codec.is_compressed() {
+                return Err(StorageDecodeError::ReadingCodec(format!(
+                    "compressed value contains another compressed value ({codec})"
+                )));
+            }
+            return T::decode(&mut decompressed, codec);
+        }
+
+        // decode value
+        T::decode(buf, codec)
+    }
+
+    fn read_codec<B: Buf>(buf: &mut B) -> Result<StorageCodecKind, StorageDecodeError> {
         if buf.remaining() < mem::size_of::<u8>() {
             return Err(StorageDecodeError::ReadingCodec(format!(
                 "remaining bytes in buf '{}' < version bytes '{}'",
@@ -99,11 +203,56 @@ impl StorageCodec {
             )));
         }
 
-        // read version
-        let codec = StorageCodecKind::try_from(buf.get_u8())?;
+        StorageCodecKind::try_from(buf.get_u8())
+    }
 
-        // decode value
-        T::decode(buf, codec)
+    fn decompress<B: Buf>(
+        codec: StorageCodecKind,
+        buf: &mut B,
+    ) -> Result<Bytes, StorageDecodeError> {
+        if buf.remaining() < 2 * mem::size_of::<u32>() {
+            return Err(StorageDecodeError::DecodeValue(
+                anyhow::anyhow!(
+                    "insufficient data: expecting {} bytes for lengths",
+                    2 * mem::size_of::<u32>()
+                )
+                .into(),
+            ));
+        }
+        let uncompressed_length = usize::try_from(buf.get_u32_le()).expect("u32 to fit into usize");
+        let compressed_length = usize::try_from(buf.get_u32_le()).expect("u32 to fit into usize");
+
+        if uncompressed_length > StorageCompression::MAX_UNCOMPRESSED_LENGTH {
+            return Err(StorageDecodeError::DecodeValue(
+                anyhow::anyhow!(
+                    "uncompressed length {} exceeds the maximum of {} bytes",
+                    uncompressed_length,
+                    StorageCompression::MAX_UNCOMPRESSED_LENGTH
+                )
+                .into(),
+            ));
+        }
+
+        if buf.remaining() < compressed_length {
+            return Err(StorageDecodeError::DecodeValue(
+                anyhow::anyhow!(
+                    "insufficient data: expecting {} bytes for compressed value",
+                    compressed_length
+                )
+                .into(),
+            ));
+        }
+        let compressed = buf.copy_to_bytes(compressed_length);
+
+        let decompressed = match codec {
+            StorageCodecKind::Zstd => zstd::bulk::decompress(&compressed, uncompressed_length)
+                .map_err(|err| StorageDecodeError::DecodeValue(err.into()))?,
+            StorageCodecKind::Lz4 => lz4_flex::block::decompress(&compressed, uncompressed_length)
+                .map_err(|err| StorageDecodeError::DecodeValue(err.into()))?,
+            codec => return Err(StorageDecodeError::UnsupportedCodecKind(codec)),
+        };
+
+        Ok(Bytes::from(decompressed))
     }
 }
 
@@ -410,4 +559,50 @@ mod tests {
         let a: Arc<dyn StorageEncode> = Arc::new("hello".to_string());
         assert!(a.is::<String>());
     }
+
+    #[test]
+    fn test_compressed_roundtrip() {
+        let value = "restate".repeat(100);
+
+        for compression in [StorageCompression::Zstd, StorageCompression::Lz4] {
+            let mut buf = BytesMut::new();
+            assert!(StorageCodec::encode_compressed(&value, compression, 64, &mut buf).unwrap());
+            assert_eq!(buf[0], u8::from(compression.codec()));
+            assert!(buf.len() < value.len());
+
+            let decoded: String = StorageCodec::decode(&mut buf.freeze()).unwrap();
+            assert_eq!(decoded, value);
+        }
+    }
+
+    #[test]
+    fn test_small_values_are_not_compressed() {
+        let value = "restate".to_owned();
+
+        let mut buf = BytesMut::new();
+        assert!(
+            !StorageCodec::encode_compressed(&value, StorageCompression::Zstd, 64, &mut buf)
+                .unwrap()
+        );
+        assert_eq!(buf[0], u8::from(StorageCodecKind::LengthPrefixedRawBytes));
+
+        let decoded: String = StorageCodec::decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn test_oversized_uncompressed_length_is_rejected() {
+        let value = "restate".repeat(100);
+        let mut buf = BytesMut::new();
+        assert!(
+            StorageCodec::encode_compressed(&value, StorageCompression::Lz4, 64, &mut buf).unwrap()
+        );
+
+        // corrupt the uncompressed length
+        buf[1..5].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            StorageCodec::decode::<String, _>(&mut buf.freeze()),
+            Err(StorageDecodeError::DecodeValue(_))
+        ));
+    }
 }
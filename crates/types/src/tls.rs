@@ -0,0 +1,139 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Loading of the PEM encoded certificates and keys which are configured for TLS.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::RootCertStore;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PemError {
+    #[error("failed reading '{path}': {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("no certificate found in '{0}'")]
+    NoCertificate(PathBuf),
+    #[error("no private key found in '{0}'")]
+    NoPrivateKey(PathBuf),
+    #[error("invalid certificate in '{path}': {source}")]
+    InvalidCertificate {
+        path: PathBuf,
+        #[source]
+        source: rustls::Error,
+    },
+}
+
+/// Loads all certificates of the PEM file. Fails if the file contains no certificate.
+pub fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, PemError> {
+    let certs = rustls_pemfile::certs(&mut open(path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|source| PemError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+
+    if certs.is_empty() {
+        return Err(PemError::NoCertificate(path.to_owned()));
+    }
+
+    Ok(certs)
+}
+
+/// Loads the first private key of the PEM file. PKCS#1, PKCS#8 and SEC1 keys are supported.
+pub fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>, PemError> {
+    rustls_pemfile::private_key(&mut open(path)?)
+        .map_err(|source| PemError::Io {
+            path: path.to_owned(),
+            source,
+        })?
+        .ok_or_else(|| PemError::NoPrivateKey(path.to_owned()))
+}
+
+/// Loads the certificates of the PEM file as trust anchors.
+pub fn load_roots(path: &Path) -> Result<RootCertStore, PemError> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        roots
+            .add(cert)
+            .map_err(|source| PemError::InvalidCertificate {
+                path: path.to_owned(),
+                source,
+            })?;
+    }
+    Ok(roots)
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, PemError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|source| PemError::Io {
+            path: path.to_owned(),
+            source,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+
+    #[test]
+    fn missing_file() {
+        assert!(matches!(
+            load_certs(Path::new("/does/not/exist.pem")),
+            Err(PemError::Io { path, .. }) if path == Path::new("/does/not/exist.pem")
+        ));
+        assert!(matches!(
+            load_private_key(Path::new("/does/not/exist.key")),
+            Err(PemError::Io { path, .. }) if path == Path::new("/does/not/exist.key")
+        ));
+    }
+
+    #[test]
+    fn file_without_pem_items() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"not a pem file").unwrap();
+
+        assert!(matches!(
+            load_certs(file.path()),
+            Err(PemError::NoCertificate(path)) if path == file.path()
+        ));
+        assert!(matches!(
+            load_roots(file.path()),
+            Err(PemError::NoCertificate(path)) if path == file.path()
+        ));
+        assert!(matches!(
+            load_private_key(file.path()),
+            Err(PemError::NoPrivateKey(path)) if path == file.path()
+        ));
+    }
+
+    #[test]
+    fn invalid_root_certificate() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "-----BEGIN CERTIFICATE-----").unwrap();
+        writeln!(file, "bm90IGEgY2VydGlmaWNhdGU=").unwrap();
+        writeln!(file, "-----END CERTIFICATE-----").unwrap();
+
+        assert_eq!(1, load_certs(file.path()).unwrap().len());
+        assert!(matches!(
+            load_roots(file.path()),
+            Err(PemError::InvalidCertificate { path, .. }) if path == file.path()
+        ));
+    }
+}
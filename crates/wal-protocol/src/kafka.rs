@@ -0,0 +1,25 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::ops::RangeInclusive;
+
+use restate_types::identifiers::{PartitionKey, SubscriptionId};
+use restate_types::schema::subscriptions::Sink;
+
+/// Replays the Kafka records which a subscription parked on a partition, by invoking the current
+/// sink of the subscription. Records which fail again stay parked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReplayParkedKafkaRecords {
+    pub subscription_id: SubscriptionId,
+    pub sink: Sink,
+    /// Key range of the partition whose parked records are replayed
+    pub partition_key_range: RangeInclusive<PartitionKey>,
+}
@@ -14,16 +14,19 @@ use bytes::{Bytes, BytesMut};
 use restate_bifrost::Bifrost;
 use restate_core::{Metadata, ShutdownError};
 use restate_storage_api::deduplication_table::DedupInformation;
+use restate_storage_api::parked_kafka_record_table::ParkedKafkaRecord;
 use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey, WithPartitionKey};
 use restate_types::invocation::{
-    AttachInvocationRequest, InvocationResponse, InvocationTermination, PurgeInvocationRequest,
-    ServiceInvocation,
+    AttachInvocationRequest, InvocationResponse, InvocationTermination, PauseInvocationRequest,
+    PurgeInvocationRequest, RestartInvocationRequest, ResumeInvocationRequest,
+    RetryInvocationNowRequest, ServiceInvocation,
 };
 use restate_types::message::MessageIndex;
 use restate_types::state_mut::ExternalStateMutation;
 use restate_types::{flexbuffers_storage_encode_decode, logs, PlainNodeId, Version};
 
 use crate::control::AnnounceLeader;
+use crate::kafka::ReplayParkedKafkaRecords;
 use crate::timer::TimerKeyValue;
 use restate_types::logs::{HasRecordKeys, Keys, LogId, Lsn, MatchKeyQuery};
 use restate_types::partition_table::{FindPartition, PartitionTableError};
@@ -31,6 +34,7 @@ use restate_types::storage::{StorageCodec, StorageDecodeError, StorageEncodeErro
 use restate_types::GenerationalNodeId;
 
 pub mod control;
+pub mod kafka;
 pub mod timer;
 
 /// The primary envelope for all messages in the system.
@@ -142,6 +146,14 @@ pub enum Command {
     TerminateInvocation(InvocationTermination),
     /// Purge a completed invocation
     PurgeInvocation(PurgeInvocationRequest),
+    /// Put an ongoing invocation on hold, preventing the invoker from retrying it
+    PauseInvocation(PauseInvocationRequest),
+    /// Resume a paused invocation
+    ResumeInvocation(ResumeInvocationRequest),
+    /// Retry an invocation without waiting for its retry backoff to expire
+    RetryInvocationNow(RetryInvocationNowRequest),
+    /// Restart a paused or suspended invocation from the given journal entry
+    RestartInvocation(RestartInvocationRequest),
     /// Start an invocation on this partition
     Invoke(ServiceInvocation),
     /// Truncate the message outbox up to, and including, the specified index.
@@ -150,6 +162,10 @@ pub enum Command {
     ProxyThrough(ServiceInvocation),
     /// Attach to an existing invocation
     AttachInvocation(AttachInvocationRequest),
+    /// Park a Kafka record which could not be turned into an invocation
+    ParkKafkaRecord(ParkedKafkaRecord),
+    /// Replay the Kafka records a subscription parked on this partition
+    ReplayParkedKafkaRecords(ReplayParkedKafkaRecords),
 
     // -- Partition processor events for PP
     /// Invoker is reporting effect(s) from an ongoing invocation.
@@ -193,12 +209,22 @@ impl HasRecordKeys for Envelope {
                 Keys::Single(terminate.invocation_id.partition_key())
             }
             Command::PurgeInvocation(purge) => Keys::Single(purge.invocation_id.partition_key()),
+            Command::PauseInvocation(pause) => Keys::Single(pause.invocation_id.partition_key()),
+            Command::ResumeInvocation(resume) => Keys::Single(resume.invocation_id.partition_key()),
+            Command::RetryInvocationNow(retry) => Keys::Single(retry.invocation_id.partition_key()),
+            Command::RestartInvocation(restart) => {
+                Keys::Single(restart.invocation_id.partition_key())
+            }
             Command::Invoke(invoke) => Keys::Single(invoke.partition_key()),
             // todo: Remove this, or pass the partition key range but filter based on partition-id
             // on read if needed.
             Command::TruncateOutbox(_) => Keys::Single(self.partition_key()),
             Command::ProxyThrough(_) => Keys::Single(self.partition_key()),
             Command::AttachInvocation(_) => Keys::Single(self.partition_key()),
+            Command::ParkKafkaRecord(record) => Keys::Single(record.partition_key),
+            Command::ReplayParkedKafkaRecords(replay) => {
+                Keys::RangeInclusive(replay.partition_key_range.clone())
+            }
             // todo: Handle journal entries that request cross-partition invocations
             Command::InvokerEffect(effect) => Keys::Single(effect.invocation_id.partition_key()),
             Command::Timer(timer) => Keys::Single(timer.invocation_id().partition_key()),
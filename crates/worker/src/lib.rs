@@ -21,6 +21,7 @@ mod subscription_integration;
 
 use codederror::CodedError;
 use restate_core::TaskCenter;
+use std::sync::Arc;
 use std::time::Duration;
 
 use restate_bifrost::Bifrost;
@@ -33,6 +34,7 @@ use restate_core::{Metadata, TaskKind};
 use restate_ingress_kafka::Service as IngressKafkaService;
 use restate_invoker_impl::InvokerHandle as InvokerChannelServiceHandle;
 use restate_metadata_store::MetadataStoreClient;
+use restate_partition_store::encryption::ValueCipher;
 use restate_partition_store::{PartitionStore, PartitionStoreManager};
 use restate_storage_query_datafusion::context::{QueryContext, SelectPartitionsFromMetadata};
 use restate_storage_query_datafusion::remote_query_scanner_client::create_remote_scanner_service;
@@ -84,6 +86,9 @@ pub enum BuildError {
     #[error("failed constructing partition snapshot repository: {0}")]
     #[code(unknown)]
     SnapshotRepository(#[from] anyhow::Error),
+    #[error("failed setting up encryption at rest: {0}")]
+    #[code(unknown)]
+    Encryption(#[from] restate_partition_store::encryption::EncryptionError),
 }
 
 #[derive(Debug, thiserror::Error, CodedError)]
@@ -130,7 +135,7 @@ impl Worker {
             ingress_kafka.create_command_sender(),
         );
 
-        let partition_store_manager = PartitionStoreManager::create(
+        let mut partition_store_manager = PartitionStoreManager::create(
             updateable_config.clone().map(|c| &c.worker.storage),
             updateable_config
                 .clone()
@@ -140,8 +145,13 @@ impl Worker {
         )
         .await?;
 
+        if let Some(encryption) = &config.worker.storage.encryption {
+            let cipher = ValueCipher::create(encryption, &config.worker.storage.data_dir())?;
+            partition_store_manager = partition_store_manager.with_encryption(Arc::new(cipher));
+        }
+
         let snapshots_options = &config.worker.snapshots;
-        if snapshots_options.snapshot_interval_num_records.is_some()
+        if snapshots_options.is_automatic_snapshotting_enabled()
             && snapshots_options.destination.is_none()
         {
             return Err(BuildError::SnapshotRepository(anyhow::anyhow!(
@@ -160,6 +170,9 @@ impl Worker {
                 snapshots_options,
                 config.common.base_dir().join("pp-snapshots"),
                 config.common.cluster_name().to_owned(),
+                partition_store_manager
+                    .cipher()
+                    .map(|cipher| Arc::clone(cipher.key_provider())),
             )
             .await
             .map_err(BuildError::SnapshotRepository)?,
@@ -167,6 +180,10 @@ impl Worker {
 
         // handle RPCs
         router_builder.add_message_handler(partition_processor_manager.message_handler());
+        router_builder
+            .add_message_handler(partition_processor_manager.list_snapshots_message_handler());
+        router_builder
+            .add_message_handler(partition_processor_manager.prune_snapshots_message_handler());
 
         let remote_scanner_manager = RemoteScannerManager::new(
             create_remote_scanner_service(networking, router_builder),
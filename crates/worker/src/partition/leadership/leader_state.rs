@@ -399,6 +399,10 @@ impl LeaderState {
                 .abort_invocation(partition_leader_epoch, invocation_id, acknowledge)
                 .await
                 .map_err(Error::Invoker)?,
+            Action::RetryInvocationNow { invocation_id } => invoker_tx
+                .retry_invocation_now(partition_leader_epoch, invocation_id)
+                .await
+                .map_err(Error::Invoker)?,
             Action::IngressResponse {
                 request_id,
                 invocation_id,
@@ -582,7 +582,7 @@ impl restate_timer::TimerReader<TimerKeyValue> for TimerReader {
     }
 }
 
-#[derive(Debug, derive_more::From)]
+#[derive(Debug, Clone, derive_more::From)]
 struct OutboxReader(PartitionStore);
 
 impl shuffle::OutboxReader for OutboxReader {
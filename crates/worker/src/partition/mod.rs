@@ -54,7 +54,7 @@ use restate_types::invocation::{
 };
 use restate_types::journal::raw::RawEntryCodec;
 use restate_types::logs::MatchKeyQuery;
-use restate_types::logs::{KeyFilter, LogId, Lsn, SequenceNumber};
+use restate_types::logs::{KeyFilter, LogId, Lsn, Record, SequenceNumber};
 use restate_types::net::partition_processor::{
     AppendInvocationReplyOn, GetInvocationOutputResponseMode, IngressResponseResult,
     InvocationOutput, PartitionProcessorRpcError, PartitionProcessorRpcRequest,
@@ -355,11 +355,15 @@ where
             .map_ok(|entry| {
                 trace!(?entry, "Read entry");
                 let lsn = entry.sequence_number();
+                let record_size = entry
+                    .as_record()
+                    .map(Record::estimated_encode_size)
+                    .unwrap_or_default();
                 let Some(envelope) = entry.try_decode_arc::<Envelope>() else {
                     // trim-gap
                     unimplemented!("Handling trim gap is currently not supported")
                 };
-                anyhow::Ok((lsn, envelope?))
+                anyhow::Ok((lsn, envelope?, record_size))
             })
             .try_take_while(|entry| {
                 // a catch-all safety net if all lower layers didn't filter this record out. This
@@ -369,7 +373,7 @@ where
                 // stored correctly.
                 std::future::ready(Ok(entry
                     .as_ref()
-                    .is_ok_and(|(_, envelope)| envelope.matches_key_query(&key_query))))
+                    .is_ok_and(|(_, envelope, _)| envelope.matches_key_query(&key_query))))
             });
 
         // avoid synchronized timers. We pick a randomised timer between 500 and 1023 millis.
@@ -417,8 +421,9 @@ where
                     // clear buffers used when applying the next record
                     action_collector.clear();
 
-                    for (lsn, envelope) in command_buffer.drain(..) {
+                    for (lsn, envelope, record_size) in command_buffer.drain(..) {
                         let command_start = Instant::now();
+                        self.status.num_applied_log_bytes += record_size as u64;
 
                         trace!(%lsn, "Processing bifrost record for '{}': {:?}", envelope.command.name(), envelope.header);
 
@@ -817,11 +822,12 @@ where
     async fn read_commands<S>(
         log_reader: &mut S,
         max_batching_size: usize,
-        record_buffer: &mut Vec<(Lsn, Arc<Envelope>)>,
+        record_buffer: &mut Vec<(Lsn, Arc<Envelope>, usize)>,
     ) -> anyhow::Result<()>
     where
-        S: Stream<Item = Result<anyhow::Result<(Lsn, Arc<Envelope>)>, restate_bifrost::Error>>
-            + Unpin,
+        S: Stream<
+                Item = Result<anyhow::Result<(Lsn, Arc<Envelope>, usize)>, restate_bifrost::Error>,
+            > + Unpin,
     {
         // beyond this point we must not await; otherwise we are no longer cancellation safe
         let first_record = log_reader.next().await;
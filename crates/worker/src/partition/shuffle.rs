@@ -8,19 +8,33 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
 use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_channel::{TryRecvError, TrySendError};
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use futures::{FutureExt, StreamExt};
+use parking_lot::Mutex;
 use tokio::sync::mpsc;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use restate_bifrost::Bifrost;
-use restate_core::{cancellation_watcher, Metadata};
+use restate_core::{cancellation_watcher, Metadata, MetadataKind, ShutdownError};
+use restate_ingress_kafka::{EgressError, KafkaEgress};
 use restate_storage_api::deduplication_table::DedupInformation;
-use restate_storage_api::outbox_table::OutboxMessage;
-use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey, WithPartitionKey};
+use restate_storage_api::outbox_table::{KafkaEgressRecord, OutboxMessage};
+use restate_types::config::Configuration;
+use restate_types::identifiers::{
+    LeaderEpoch, PartitionId, PartitionKey, SubscriptionId, WithPartitionKey,
+};
 use restate_types::message::MessageIndex;
+use restate_types::retries::RetryPolicy;
+use restate_types::schema::subscriptions::SubscriptionResolver;
+use restate_types::Version;
 use restate_wal_protocol::{append_envelope_to_bifrost, Destination, Envelope, Header, Source};
 
 use crate::partition::shuffle::state_machine::StateMachine;
@@ -65,6 +79,97 @@ pub(crate) fn wrap_outbox_message_in_envelope(
     )
 }
 
+/// Kafka header carrying the id of the invocation which produced the record. Records are published
+/// at least once, consumers can use it to deduplicate them.
+const INVOCATION_ID_HEADER: &str = "restate-invocation-id";
+
+/// Publishes the record to the Kafka topic of its egress subscription. Retries until the record
+/// has been published, unless it can never be published because its subscription has been removed
+/// or does not publish to Kafka anymore. Such records are dropped. While the subscription is paused
+/// or its Kafka cluster is not configured, the record is held back.
+async fn publish_kafka_egress_record(
+    kafka_egress: &KafkaEgress,
+    record: KafkaEgressRecord,
+) -> Result<(), ShutdownError> {
+    let invocation_id = record.invocation_id.to_string();
+    let headers = [(INVOCATION_ID_HEADER, invocation_id.as_str())];
+    let mut retry_delays = RetryPolicy::exponential(
+        Duration::from_millis(100),
+        2.0,
+        None,
+        Some(Duration::from_secs(10)),
+    )
+    .into_iter();
+
+    loop {
+        // The subscription is resolved anew for every attempt, it might have been changed meanwhile
+        let (schema_version, subscription) = Metadata::with_current(|m| {
+            (
+                m.schema_version(),
+                m.schema_ref().get_subscription(record.subscription_id),
+            )
+        });
+
+        let Some(subscription) = subscription else {
+            warn!(
+                restate.subscription.id = %record.subscription_id,
+                restate.invocation.id = %record.invocation_id,
+                "Dropping Kafka egress record because its subscription does not exist anymore"
+            );
+            kafka_egress.remove(record.subscription_id);
+            kafka_egress.record_dropped(record.subscription_id);
+            return Ok(());
+        };
+
+        if subscription.is_paused() {
+            debug!(
+                restate.subscription.id = %record.subscription_id,
+                "Holding back Kafka egress records until the subscription is resumed"
+            );
+            Metadata::current()
+                .wait_for_version(MetadataKind::Schema, schema_version.next())
+                .await?;
+            continue;
+        }
+
+        let options = Configuration::pinned().ingress.clone();
+        match kafka_egress
+            .publish(
+                &options,
+                &subscription,
+                &record.key,
+                &record.payload,
+                &headers,
+            )
+            .await
+        {
+            Ok(()) => return Ok(()),
+            Err(err) if err.is_retryable() => {
+                warn!(
+                    restate.subscription.id = %record.subscription_id,
+                    restate.invocation.id = %record.invocation_id,
+                    "Failed publishing Kafka egress record, retrying: {err}"
+                );
+                tokio::time::sleep(
+                    retry_delays
+                        .next()
+                        .expect("retries of the egress records are unlimited"),
+                )
+                .await;
+            }
+            Err(err) => {
+                warn!(
+                    restate.subscription.id = %record.subscription_id,
+                    restate.invocation.id = %record.invocation_id,
+                    "Dropping Kafka egress record because it cannot be published: {err}"
+                );
+                kafka_egress.record_dropped(record.subscription_id);
+                return Ok(());
+            }
+        }
+    }
+}
+
 fn create_header(
     dest_partition_key: PartitionKey,
     seq_number: MessageIndex,
@@ -103,24 +208,48 @@ pub(super) trait OutboxReader {
 }
 
 /// The hint sender allows to send hints to the shuffle service. If more hints are sent than the
-/// channel can store, then the oldest hints will be dropped.
+/// channels can store, then the oldest hints will be dropped.
 #[derive(Debug, Clone)]
 pub(crate) struct HintSender {
+    shuffle: HintChannel,
+    egress: EgressHintChannels,
+}
+
+impl HintSender {
+    fn new(shuffle: HintChannel, egress: EgressHintChannels) -> Self {
+        Self { shuffle, egress }
+    }
+
+    pub(crate) fn send(&self, outbox_message: NewOutboxMessage) {
+        for egress in self.egress.lock().values() {
+            egress.send(NewOutboxMessage::new(
+                outbox_message.seq_number,
+                outbox_message.message.clone(),
+            ));
+        }
+        self.shuffle.send(outbox_message);
+    }
+}
+
+/// The hint channels of the egress cursors, one per egress subscription.
+type EgressHintChannels = Arc<Mutex<HashMap<SubscriptionId, HintChannel>>>;
+
+/// Hints for one of the cursors reading the outbox.
+#[derive(Debug, Clone)]
+struct HintChannel {
     tx: async_channel::Sender<NewOutboxMessage>,
 
     // receiver to pop the oldest messages from the hint channel
     rx: async_channel::Receiver<NewOutboxMessage>,
 }
 
-impl HintSender {
-    fn new(
-        tx: async_channel::Sender<NewOutboxMessage>,
-        rx: async_channel::Receiver<NewOutboxMessage>,
-    ) -> Self {
+impl HintChannel {
+    fn new(channel_size: usize) -> Self {
+        let (tx, rx) = async_channel::bounded(channel_size);
         Self { tx, rx }
     }
 
-    pub(crate) fn send(&self, mut outbox_message: NewOutboxMessage) {
+    fn send(&self, mut outbox_message: NewOutboxMessage) {
         loop {
             let result = self.tx.try_send(outbox_message);
 
@@ -164,6 +293,24 @@ impl ShuffleMetadata {
     }
 }
 
+type EgressSendOperation =
+    Box<dyn Fn(OutboxMessage, MessageIndex) -> BoxFuture<'static, anyhow::Result<()>> + Send>;
+
+/// Cursor over the outbox which publishes the Kafka egress records of one subscription.
+struct EgressCursor<OR> {
+    state_machine:
+        Pin<Box<StateMachine<OR, EgressSendOperation, BoxFuture<'static, anyhow::Result<()>>>>>,
+    /// Index of the last outbox message the cursor passed
+    published_message_index: Option<MessageIndex>,
+}
+
+/// Shuffles the outbox messages to the partitions they are addressed to and publishes the Kafka
+/// egress records of the outbox.
+///
+/// Kafka egress records are published from separate cursors over the outbox, one per egress
+/// subscription, so that an unavailable Kafka cluster only delays the records of its
+/// subscriptions and never the messages to other partitions. The outbox is truncated up to the
+/// lowest of all cursors.
 pub(super) struct Shuffle<OR> {
     metadata: ShuffleMetadata,
 
@@ -171,18 +318,21 @@ pub(super) struct Shuffle<OR> {
 
     bifrost: Bifrost,
 
+    kafka_egress: KafkaEgress,
+
     // used to tell partition processor about outbox truncations
     truncation_tx: mpsc::Sender<OutboxTruncation>,
 
-    hint_rx: async_channel::Receiver<NewOutboxMessage>,
+    channel_size: usize,
+
+    shuffle_hints: HintChannel,
 
-    // used to create the senders into the shuffle
-    hint_tx: async_channel::Sender<NewOutboxMessage>,
+    egress_hints: EgressHintChannels,
 }
 
 impl<OR> Shuffle<OR>
 where
-    OR: OutboxReader + Send + Sync + 'static,
+    OR: OutboxReader + Clone + Send + Sync + 'static,
 {
     pub(super) fn new(
         metadata: ShuffleMetadata,
@@ -191,70 +341,228 @@ where
         channel_size: usize,
         bifrost: Bifrost,
     ) -> Self {
-        let (hint_tx, hint_rx) = async_channel::bounded(channel_size);
-
         Self {
             metadata,
             outbox_reader,
             truncation_tx,
-            hint_rx,
-            hint_tx,
+            channel_size,
+            shuffle_hints: HintChannel::new(channel_size),
+            egress_hints: EgressHintChannels::default(),
             bifrost,
+            kafka_egress: KafkaEgress::default(),
         }
     }
 
     pub(super) fn create_hint_sender(&self) -> HintSender {
-        HintSender::new(self.hint_tx.clone(), self.hint_rx.clone())
+        HintSender::new(self.shuffle_hints.clone(), self.egress_hints.clone())
     }
 
     pub(super) async fn run(self) -> anyhow::Result<()> {
         let Self {
             metadata,
-            mut hint_rx,
+            channel_size,
+            shuffle_hints,
+            egress_hints,
             outbox_reader,
             truncation_tx,
             bifrost,
-            ..
+            kafka_egress,
         } = self;
 
         let node_id = Metadata::with_current(|m| m.my_node_id());
         debug!(restate.node = %node_id, restate.partition.id = %metadata.partition_id, "Running shuffle");
 
-        let state_machine = StateMachine::new(
-            metadata,
-            outbox_reader,
-            move |msg| {
-                let bifrost = bifrost.clone();
-                async move {
-                    append_envelope_to_bifrost(&bifrost, Arc::new(msg)).await?;
-                    Ok(())
+        let shuffle_state_machine = StateMachine::new(
+            outbox_reader.clone(),
+            {
+                let kafka_egress = kafka_egress.clone();
+                move |message, seq_number| {
+                    let bifrost = bifrost.clone();
+                    let kafka_egress = kafka_egress.clone();
+                    async move {
+                        match message {
+                            // Kafka egress records are published by the egress cursor of their
+                            // subscription, unless the subscription does not exist anymore
+                            OutboxMessage::KafkaEgress(record) => {
+                                drop_orphaned_kafka_egress_record(&kafka_egress, &record)
+                            }
+                            message => {
+                                let envelope =
+                                    wrap_outbox_message_in_envelope(message, seq_number, &metadata);
+                                append_envelope_to_bifrost(&bifrost, Arc::new(envelope)).await?;
+                            }
+                        }
+                        Ok(())
+                    }
                 }
             },
-            &mut hint_rx,
+            shuffle_hints.rx,
         );
+        tokio::pin!(shuffle_state_machine);
 
-        tokio::pin!(state_machine);
+        let metadata_watch = Metadata::current();
+        let mut schema_version = Version::INVALID;
+        let mut egress_cursors: HashMap<SubscriptionId, EgressCursor<OR>> = HashMap::new();
+        let mut shuffled_message_index = None;
 
         loop {
-            tokio::select! {
-                shuffled_message_index = state_machine.as_mut().shuffle_next_message() => {
-                    let shuffled_message_index = shuffled_message_index?;
+            let current_schema_version = metadata_watch.schema_version();
+            if current_schema_version != schema_version {
+                schema_version = current_schema_version;
+                sync_egress_cursors(
+                    &mut egress_cursors,
+                    &egress_hints,
+                    &outbox_reader,
+                    &kafka_egress,
+                    channel_size,
+                );
+            }
 
-                    // this is just a hint which we can drop
-                    let _ = truncation_tx.try_send(OutboxTruncation::new(shuffled_message_index));
-                },
-                _ = cancellation_watcher() => {
-                    break;
+            let published = {
+                let mut next_published: FuturesUnordered<_> = egress_cursors
+                    .iter_mut()
+                    .map(|(subscription_id, cursor)| async move {
+                        (
+                            *subscription_id,
+                            cursor.state_machine.as_mut().shuffle_next_message().await,
+                        )
+                    })
+                    .collect();
+
+                tokio::select! {
+                    message_index = shuffle_state_machine.as_mut().shuffle_next_message() => {
+                        shuffled_message_index = Some(message_index?);
+                        None
+                    },
+                    Some((subscription_id, message_index)) = next_published.next() => {
+                        Some((subscription_id, message_index?))
+                    },
+                    version = metadata_watch.wait_for_version(MetadataKind::Schema, schema_version.next()) => {
+                        version?;
+                        None
+                    },
+                    _ = cancellation_watcher() => {
+                        break;
+                    }
                 }
+            };
+
+            if let Some((subscription_id, message_index)) = published {
+                if let Some(cursor) = egress_cursors.get_mut(&subscription_id) {
+                    cursor.published_message_index = Some(message_index);
+                }
+            }
+
+            let published_message_index = egress_cursors
+                .values()
+                .map(|cursor| cursor.published_message_index)
+                .try_fold(MessageIndex::MAX, |min, index| Some(min.min(index?)));
+            if let (Some(shuffled_message_index), Some(published_message_index)) =
+                (shuffled_message_index, published_message_index)
+            {
+                // this is just a hint which we can drop
+                let _ = truncation_tx.try_send(OutboxTruncation::new(
+                    shuffled_message_index.min(published_message_index),
+                ));
             }
         }
 
+        egress_hints.lock().clear();
         debug!(restate.node = %node_id, "Stopping shuffle");
 
         Ok(())
     }
 }
 
+/// Creates the egress cursors of new egress subscriptions and removes the ones of removed
+/// subscriptions. New cursors start at the head of the outbox.
+fn sync_egress_cursors<OR>(
+    egress_cursors: &mut HashMap<SubscriptionId, EgressCursor<OR>>,
+    egress_hints: &EgressHintChannels,
+    outbox_reader: &OR,
+    kafka_egress: &KafkaEgress,
+    channel_size: usize,
+) where
+    OR: OutboxReader + Clone + Send + Sync + 'static,
+{
+    let subscriptions: Vec<_> = Metadata::with_current(|m| {
+        m.schema_ref()
+            .list_subscriptions(&[])
+            .into_iter()
+            .filter(|subscription| subscription.is_egress())
+            .map(|subscription| subscription.id())
+            .collect()
+    });
+
+    let mut egress_hints = egress_hints.lock();
+    egress_cursors.retain(|subscription_id, _| {
+        let retain = subscriptions.contains(subscription_id);
+        if !retain {
+            debug!(
+                restate.subscription.id = %subscription_id,
+                "Removing Kafka egress cursor of removed subscription"
+            );
+            egress_hints.remove(subscription_id);
+            kafka_egress.remove(*subscription_id);
+        }
+        retain
+    });
+
+    for subscription_id in subscriptions {
+        if egress_cursors.contains_key(&subscription_id) {
+            continue;
+        }
+
+        let hints = HintChannel::new(channel_size);
+        egress_hints.insert(subscription_id, hints.clone());
+        let kafka_egress = kafka_egress.clone();
+        let send_operation: EgressSendOperation = Box::new(move |message, _seq_number| {
+            let kafka_egress = kafka_egress.clone();
+            async move {
+                match message {
+                    OutboxMessage::KafkaEgress(record)
+                        if record.subscription_id == subscription_id =>
+                    {
+                        publish_kafka_egress_record(&kafka_egress, record).await?;
+                    }
+                    _ => {}
+                }
+                Ok(())
+            }
+            .boxed()
+        });
+        egress_cursors.insert(
+            subscription_id,
+            EgressCursor {
+                state_machine: Box::pin(StateMachine::new(
+                    outbox_reader.clone(),
+                    send_operation,
+                    hints.rx,
+                )),
+                published_message_index: None,
+            },
+        );
+    }
+}
+
+/// Drops a Kafka egress record which no egress cursor publishes because its subscription has been
+/// removed.
+fn drop_orphaned_kafka_egress_record(kafka_egress: &KafkaEgress, record: &KafkaEgressRecord) {
+    let subscription_exists = Metadata::with_current(|m| {
+        m.schema_ref()
+            .get_subscription(record.subscription_id)
+            .is_some_and(|subscription| subscription.is_egress())
+    });
+    if !subscription_exists {
+        warn!(
+            restate.subscription.id = %record.subscription_id,
+            restate.invocation.id = %record.invocation_id,
+            "Dropping Kafka egress record because its subscription does not exist anymore"
+        );
+        kafka_egress.record_dropped(record.subscription_id);
+    }
+}
+
 mod state_machine {
     use pin_project::pin_project;
     use std::cmp::Ordering;
@@ -265,12 +573,9 @@ mod state_machine {
 
     use restate_storage_api::outbox_table::OutboxMessage;
     use restate_types::message::MessageIndex;
-    use restate_wal_protocol::Envelope;
 
     use crate::partition::shuffle;
-    use crate::partition::shuffle::{
-        wrap_outbox_message_in_envelope, NewOutboxMessage, OutboxReaderError, ShuffleMetadata,
-    };
+    use crate::partition::shuffle::{NewOutboxMessage, OutboxReaderError};
 
     type ReadFuture<OutboxReader> = ReusableBoxFuture<
         'static,
@@ -288,13 +593,12 @@ mod state_machine {
     }
 
     #[pin_project]
-    pub(super) struct StateMachine<'a, OutboxReader, SendOp, SendFuture> {
-        metadata: ShuffleMetadata,
+    pub(super) struct StateMachine<OutboxReader, SendOp, SendFuture> {
         current_sequence_number: MessageIndex,
         outbox_reader: Option<OutboxReader>,
         read_future: ReadFuture<OutboxReader>,
         send_operation: SendOp,
-        hint_rx: &'a mut async_channel::Receiver<NewOutboxMessage>,
+        hint_rx: async_channel::Receiver<NewOutboxMessage>,
         #[pin]
         state: State<SendFuture>,
     }
@@ -310,17 +614,16 @@ mod state_machine {
         (result, outbox_reader)
     }
 
-    impl<'a, OutboxReader, SendOp, SendFuture> StateMachine<'a, OutboxReader, SendOp, SendFuture>
+    impl<OutboxReader, SendOp, SendFuture> StateMachine<OutboxReader, SendOp, SendFuture>
     where
         SendFuture: Future<Output = Result<(), anyhow::Error>>,
-        SendOp: Fn(Envelope) -> SendFuture,
+        SendOp: Fn(OutboxMessage, MessageIndex) -> SendFuture,
         OutboxReader: shuffle::OutboxReader + Send + Sync + 'static,
     {
         pub(super) fn new(
-            metadata: ShuffleMetadata,
             outbox_reader: OutboxReader,
             send_operation: SendOp,
-            hint_rx: &'a mut async_channel::Receiver<NewOutboxMessage>,
+            hint_rx: async_channel::Receiver<NewOutboxMessage>,
         ) -> Self {
             let current_sequence_number = 0;
             // find the first message from where to start shuffling; everyday I'm shuffling
@@ -329,7 +632,6 @@ mod state_machine {
             let reading_future = get_next_message(outbox_reader, current_sequence_number);
 
             Self {
-                metadata,
                 current_sequence_number,
                 outbox_reader: None,
                 read_future: ReusableBoxFuture::new(reading_future),
@@ -358,12 +660,8 @@ mod state_machine {
 
                             match seq_number.cmp(this.current_sequence_number) {
                                 Ordering::Equal => {
-                                    let envelope = wrap_outbox_message_in_envelope(
-                                        message.clone(),
-                                        seq_number,
-                                        this.metadata,
-                                    );
-                                    let send_future = (this.send_operation)(envelope);
+                                    let send_future =
+                                        (this.send_operation)(message.clone(), seq_number);
                                     this.state.set(State::Sending(send_future));
                                     break;
                                 }
@@ -396,9 +694,7 @@ mod state_machine {
 
                             *this.current_sequence_number = seq_number;
 
-                            let envelope =
-                                wrap_outbox_message_in_envelope(message, seq_number, this.metadata);
-                            let send_future = (this.send_operation)(envelope);
+                            let send_future = (this.send_operation)(message, seq_number);
 
                             this.state.set(State::Sending(send_future));
                         } else {
@@ -435,6 +731,7 @@ mod tests {
 
     use anyhow::anyhow;
     use assert2::let_assert;
+    use bytes::Bytes;
     use futures::{Stream, StreamExt};
     use test_log::test;
     use tokio::sync::mpsc;
@@ -442,9 +739,9 @@ mod tests {
     use restate_bifrost::{Bifrost, LogEntry};
     use restate_core::network::FailingConnector;
     use restate_core::{TaskCenter, TaskKind, TestCoreEnv, TestCoreEnvBuilder};
-    use restate_storage_api::outbox_table::OutboxMessage;
+    use restate_storage_api::outbox_table::{KafkaEgressRecord, OutboxMessage};
     use restate_storage_api::StorageError;
-    use restate_types::identifiers::{InvocationId, LeaderEpoch, PartitionId};
+    use restate_types::identifiers::{InvocationId, LeaderEpoch, PartitionId, SubscriptionId};
     use restate_types::invocation::ServiceInvocation;
     use restate_types::logs::{KeyFilter, LogId, Lsn, SequenceNumber};
     use restate_types::message::MessageIndex;
@@ -454,6 +751,7 @@ mod tests {
 
     use crate::partition::shuffle::{OutboxReader, OutboxReaderError, Shuffle, ShuffleMetadata};
 
+    #[derive(Clone)]
     struct MockOutboxReader {
         base_offset: MessageIndex,
         // there can be holes in our records
@@ -556,6 +854,24 @@ mod tests {
         }
     }
 
+    /// Outbox reader over a consecutive outbox which can contain any kind of message.
+    #[derive(Clone)]
+    struct OutboxMessagesReader {
+        messages: Vec<OutboxMessage>,
+    }
+
+    impl OutboxReader for OutboxMessagesReader {
+        async fn get_next_message(
+            &mut self,
+            next_sequence_number: MessageIndex,
+        ) -> Result<Option<(MessageIndex, OutboxMessage)>, OutboxReaderError> {
+            Ok(self
+                .messages
+                .get(usize::try_from(next_sequence_number).expect("index should fit in usize"))
+                .map(|message| (next_sequence_number, message.clone())))
+        }
+    }
+
     async fn collect_invoke_commands_until(
         stream: impl Stream<Item = restate_bifrost::Result<LogEntry>>,
         last_invocation_id: InvocationId,
@@ -708,6 +1024,76 @@ mod tests {
         Ok(())
     }
 
+    #[test(restate_core::test)]
+    async fn shuffle_skips_unpublishable_kafka_egress_records() -> anyhow::Result<()> {
+        let expected_messages: Vec<_> = iter::repeat_with(|| Some(ServiceInvocation::mock()))
+            .take(3)
+            .collect();
+        let last_invocation_id = expected_messages[2]
+            .as_ref()
+            .expect("service invocation should be present")
+            .invocation_id;
+
+        // the egress records belong to a subscription which does not exist
+        let egress_record = || {
+            OutboxMessage::KafkaEgress(KafkaEgressRecord {
+                subscription_id: SubscriptionId::new(),
+                invocation_id: InvocationId::mock_random(),
+                key: Bytes::from_static(b"key"),
+                payload: Bytes::from_static(b"payload"),
+            })
+        };
+        let mut messages = Vec::new();
+        for invocation in expected_messages.iter().flatten() {
+            messages.push(egress_record());
+            messages.push(OutboxMessage::ServiceInvocation(invocation.clone()));
+        }
+        let last_message_index = u64::try_from(messages.len() - 1).expect("fits in u64");
+
+        let _env = TestCoreEnvBuilder::with_incoming_only_connector()
+            .set_partition_table(PartitionTable::with_equally_sized_partitions(
+                Version::MIN,
+                1,
+            ))
+            .build()
+            .await;
+        let metadata = ShuffleMetadata::new(PartitionId::from(0), LeaderEpoch::from(0));
+        let (truncation_tx, mut truncation_rx) = mpsc::channel(16);
+        let bifrost = Bifrost::init_in_memory().await;
+        let shuffle = Shuffle::new(
+            metadata,
+            OutboxMessagesReader { messages },
+            truncation_tx,
+            1,
+            bifrost.clone(),
+        );
+
+        TaskCenter::spawn_child(TaskKind::Shuffle, "shuffle", shuffle.run())?;
+        let reader = bifrost.create_reader(
+            LogId::from(metadata.partition_id),
+            KeyFilter::Any,
+            Lsn::OLDEST,
+            Lsn::MAX,
+        )?;
+
+        let received_messages = collect_invoke_commands_until(reader, last_invocation_id).await?;
+        assert_received_invoke_commands(received_messages, expected_messages);
+
+        // without egress subscriptions, the outbox is truncated once the shuffle passed a message
+        loop {
+            let truncation = truncation_rx
+                .recv()
+                .await
+                .expect("shuffle should be running");
+            assert!(truncation.index() <= last_message_index);
+            if truncation.index() == last_message_index {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
     #[test(restate_core::test)]
     async fn shuffle_with_restarts() -> anyhow::Result<()> {
         let expected_messages: Vec<_> = iter::repeat_with(|| Some(ServiceInvocation::mock()))
@@ -8,7 +8,9 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
@@ -19,25 +21,29 @@ use aws_config::default_provider::credentials::DefaultCredentialsChain;
 use aws_config::BehaviorVersion;
 use aws_credential_types::provider::ProvideCredentials;
 use bytes::BytesMut;
+use futures::TryStreamExt;
 use object_store::aws::{AmazonS3Builder, S3ConditionalPut};
 use object_store::{MultipartUpload, ObjectStore, PutMode, PutOptions, PutPayload, UpdateVersion};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use tempfile::TempDir;
 use tokio::io;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
 use tokio_util::io::StreamReader;
 use tracing::{debug, info, instrument, warn};
 use url::Url;
 
+use restate_partition_store::encryption::{ArchiveCipher, KeyProvider};
 use restate_partition_store::snapshots::{
     LocalPartitionSnapshot, PartitionSnapshotMetadata, SnapshotFormatVersion,
 };
 use restate_types::config::SnapshotsOptions;
 use restate_types::identifiers::{PartitionId, SnapshotId};
 use restate_types::logs::Lsn;
+use restate_types::net::partition_processor_manager::SnapshotSummary;
+use restate_types::time::MillisSinceEpoch;
 
 /// Provides read and write access to the long-term partition snapshot storage destination.
 ///
@@ -53,6 +59,9 @@ use restate_types::logs::Lsn;
 /// - `[<prefix>/]<partition_id>/latest.json` - latest snapshot metadata for the partition
 /// - `[<prefix>/]<partition_id>/{lsn}_{snapshot_id}/metadata.json` - snapshot descriptor
 /// - `[<prefix>/]<partition_id>/{lsn}_{snapshot_id}/*.sst` - data files (explicitly named in `metadata.json`)
+///
+/// If encryption at rest is configured, the data files are encrypted with a key that is unique to
+/// the snapshot. The wrapped key is part of `metadata.json`.
 #[derive(Clone)]
 pub struct SnapshotRepository {
     object_store: Arc<dyn ObjectStore>,
@@ -62,6 +71,12 @@ pub struct SnapshotRepository {
     staging_dir: PathBuf,
     /// Expected cluster name for the snapshots in this repository.
     cluster_name: String,
+    /// Number of most recent snapshots to keep when pruning.
+    retain_num_snapshots: Option<NonZeroUsize>,
+    /// Maximum age of snapshots to keep when pruning.
+    retain_max_age: Option<Duration>,
+    /// Wraps the keys of encrypted snapshots if encryption at rest is configured.
+    key_provider: Option<Arc<dyn KeyProvider>>,
 }
 
 /// S3 and other stores require a certain minimum size for the parts of a multipart upload. It is an
@@ -112,6 +127,17 @@ impl LatestSnapshot {
             path,
         }
     }
+
+    pub fn to_summary(&self, is_latest: bool) -> SnapshotSummary {
+        SnapshotSummary {
+            snapshot_id: self.snapshot_id,
+            partition_id: self.partition_id,
+            min_applied_lsn: self.min_applied_lsn,
+            created_at: MillisSinceEpoch::from(*self.created_at),
+            node_name: self.node_name.clone(),
+            is_latest,
+        }
+    }
 }
 
 impl SnapshotRepository {
@@ -120,6 +146,7 @@ impl SnapshotRepository {
         snapshots_options: &SnapshotsOptions,
         staging_dir: PathBuf,
         cluster_name: String,
+        key_provider: Option<Arc<dyn KeyProvider>>,
     ) -> anyhow::Result<Option<SnapshotRepository>> {
         let mut destination = if let Some(ref destination) = snapshots_options.destination {
             Url::parse(destination).context("Failed parsing snapshot repository URL")?
@@ -148,9 +175,17 @@ impl SnapshotRepository {
             prefix,
             staging_dir,
             cluster_name,
+            retain_num_snapshots: snapshots_options.retain_num_snapshots,
+            retain_max_age: snapshots_options.retain_max_age.map(Into::into),
+            key_provider,
         }))
     }
 
+    /// Returns whether a retention policy is configured for this repository.
+    pub fn is_retention_enabled(&self) -> bool {
+        self.retain_num_snapshots.is_some() || self.retain_max_age.is_some()
+    }
+
     /// Write a partition snapshot to the snapshot repository.
     #[instrument(
         level = "debug",
@@ -223,20 +258,37 @@ impl SnapshotRepository {
         );
 
         let mut progress = SnapshotUploadProgress::with_snapshot_path(full_snapshot_path.clone());
+
+        let (archive_cipher, snapshot) = match &self.key_provider {
+            Some(key_provider) => {
+                let (cipher, encryption) = ArchiveCipher::create(key_provider.as_ref())
+                    .map_err(|e| PutSnapshotError::from(e, progress.clone()))?;
+                let mut snapshot = snapshot.clone();
+                snapshot.archive_encryption = Some(encryption);
+                (Some(Arc::new(cipher)), Cow::Owned(snapshot))
+            }
+            None => (None, Cow::Borrowed(snapshot)),
+        };
+        let snapshot = snapshot.as_ref();
+
         let mut buf = BytesMut::new();
-        for file in &snapshot.files {
+        for (file_index, file) in snapshot.files.iter().enumerate() {
             let filename = file.name.trim_start_matches("/");
             let key = object_store::path::Path::from(format!(
                 "{}/{}",
                 full_snapshot_path.as_str(),
                 filename
             ));
+            let file_cipher = archive_cipher
+                .as_ref()
+                .map(|cipher| FileCipher::new(Arc::clone(cipher), file_index, filename));
 
             let put_result = put_snapshot_object(
                 local_snapshot_path.join(filename).as_path(),
                 &key,
                 &self.object_store,
                 &mut buf,
+                file_cipher.as_ref(),
             )
             .await
             .map_err(|e| PutSnapshotError::from(e, progress.clone()))?;
@@ -349,24 +401,10 @@ impl SnapshotRepository {
         &self,
         partition_id: PartitionId,
     ) -> anyhow::Result<Option<LocalPartitionSnapshot>> {
-        let latest_path = object_store::path::Path::from(format!(
-            "{prefix}{partition_id}/latest.json",
-            prefix = self.prefix,
-            partition_id = partition_id,
-        ));
-
-        let latest = self.object_store.get(&latest_path).await;
-
-        let latest = match latest {
-            Ok(result) => result,
-            Err(object_store::Error::NotFound { .. }) => {
-                debug!("Latest snapshot data not found in repository");
-                return Ok(None);
-            }
-            Err(e) => return Err(e.into()),
+        let Some(latest) = self.get_latest_pointer(partition_id).await? else {
+            debug!("Latest snapshot data not found in repository");
+            return Ok(None);
         };
-
-        let latest: LatestSnapshot = serde_json::from_slice(&latest.bytes().await?)?;
         debug!("Latest snapshot metadata: {:?}", latest);
 
         let snapshot_metadata_path = object_store::path::Path::from(format!(
@@ -417,13 +455,27 @@ impl SnapshotRepository {
             "Getting snapshot data",
         );
 
+        let archive_cipher = match (&snapshot_metadata.archive_encryption, &self.key_provider) {
+            (Some(encryption), Some(key_provider)) => Some(Arc::new(
+                ArchiveCipher::open(key_provider.as_ref(), encryption)
+                    .context("Failed to decrypt the snapshot key")?,
+            )),
+            (Some(_), None) => {
+                bail!("The snapshot is encrypted but encryption at rest is not configured");
+            }
+            (None, _) => None,
+        };
+
         let directory = snapshot_dir.path().to_string_lossy().to_string();
         let concurrency_limiter = Arc::new(Semaphore::new(DOWNLOAD_CONCURRENCY_LIMIT));
         let mut downloads = JoinSet::new();
         let mut task_handles = HashMap::with_capacity(snapshot_metadata.files.len());
-        for file in &mut snapshot_metadata.files {
+        for (file_index, file) in snapshot_metadata.files.iter_mut().enumerate() {
             let filename = file.name.trim_start_matches("/");
             let expected_size = file.size;
+            let file_cipher = archive_cipher
+                .as_ref()
+                .map(|cipher| FileCipher::new(Arc::clone(cipher), file_index, filename));
             let key = object_store::path::Path::from(format!(
                 "{prefix}{partition_id}/{path}/{filename}",
                 prefix = self.prefix,
@@ -448,9 +500,17 @@ impl SnapshotRepository {
                     tokio::fs::File::create_new(&file_path).await.map_err(|e| {
                         anyhow!("Failed to create snapshot file {:?}: {}", file_path, e)
                     })?;
-                let size = io::copy(&mut file_data, &mut snapshot_file)
-                    .await
-                    .map_err(|e| anyhow!("Failed to download snapshot file {:?}: {}", key, e))?;
+                let size = match file_cipher {
+                    Some(file_cipher) => {
+                        file_cipher
+                            .decrypt_into(&mut file_data, &mut snapshot_file, expected_size as u64)
+                            .await
+                    }
+                    None => io::copy(&mut file_data, &mut snapshot_file)
+                        .await
+                        .map_err(Into::into),
+                }
+                .map_err(|e| anyhow!("Failed to download snapshot file {:?}: {}", key, e))?;
                 if size != expected_size as u64 {
                     return Err(anyhow!(
                         "Downloaded snapshot file {:?} has unexpected size: expected {}, got {}",
@@ -501,9 +561,198 @@ impl SnapshotRepository {
             db_comparator_name: snapshot_metadata.db_comparator_name,
             files: snapshot_metadata.files,
             key_range: snapshot_metadata.key_range.clone(),
+            data_keys: snapshot_metadata.data_keys,
         }))
     }
 
+    pub(crate) async fn get_latest_pointer(
+        &self,
+        partition_id: PartitionId,
+    ) -> anyhow::Result<Option<LatestSnapshot>> {
+        let latest_path = object_store::path::Path::from(format!(
+            "{prefix}{partition_id}/latest.json",
+            prefix = self.prefix,
+            partition_id = partition_id,
+        ));
+
+        match self.object_store.get(&latest_path).await {
+            Ok(result) => Ok(Some(serde_json::from_slice(&result.bytes().await?)?)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// List the snapshots of a partition stored in the repository, ordered by ascending LSN.
+    /// Snapshots without a metadata descriptor, e.g. because they are still being uploaded, are
+    /// not included.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        err,
+        fields(%partition_id),
+    )]
+    pub(crate) async fn list(
+        &self,
+        partition_id: PartitionId,
+    ) -> anyhow::Result<Vec<LatestSnapshot>> {
+        let partition_path = object_store::path::Path::from(format!(
+            "{prefix}{partition_id}",
+            prefix = self.prefix,
+            partition_id = partition_id,
+        ));
+        let listing = self
+            .object_store
+            .list_with_delimiter(Some(&partition_path))
+            .await?;
+
+        let mut snapshots = Vec::with_capacity(listing.common_prefixes.len());
+        for snapshot_path in listing.common_prefixes {
+            let Some(snapshot_prefix) = snapshot_path.filename().map(ToOwned::to_owned) else {
+                continue;
+            };
+
+            let metadata = match self
+                .object_store
+                .get(&snapshot_path.child("metadata.json"))
+                .await
+            {
+                Ok(result) => result,
+                Err(object_store::Error::NotFound { .. }) => {
+                    debug!(%snapshot_path, "Ignoring snapshot without metadata");
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let metadata: PartitionSnapshotMetadata =
+                serde_json::from_slice(&metadata.bytes().await?)?;
+            if metadata.cluster_name != self.cluster_name {
+                warn!(
+                    %snapshot_path,
+                    "Ignoring snapshot with unexpected cluster name \"{}\"",
+                    metadata.cluster_name
+                );
+                continue;
+            }
+
+            snapshots.push(LatestSnapshot::from_snapshot(&metadata, snapshot_prefix));
+        }
+
+        snapshots.sort_by_key(|snapshot| snapshot.min_applied_lsn);
+        Ok(snapshots)
+    }
+
+    /// Delete the snapshots of a partition which fall outside the configured retention policy.
+    /// A snapshot is only deleted if it is neither among the `retain-num-snapshots` most recent
+    /// snapshots, nor younger than `retain-max-age`. Regardless of the policy, we always retain
+    /// the snapshot referenced by the latest pointer, as well as the oldest snapshot at or above
+    /// the log `trim_point`, since the partition cannot be restored without it once the log has
+    /// been trimmed.
+    ///
+    /// Returns the deleted snapshots; with `dry_run`, the snapshots which would have been deleted.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        err,
+        fields(%partition_id, %trim_point, %dry_run),
+    )]
+    pub(crate) async fn prune(
+        &self,
+        partition_id: PartitionId,
+        trim_point: Lsn,
+        dry_run: bool,
+    ) -> anyhow::Result<Vec<LatestSnapshot>> {
+        if !self.is_retention_enabled() {
+            bail!("No snapshot retention policy is configured");
+        }
+
+        let latest_path = self
+            .get_latest_pointer(partition_id)
+            .await?
+            .map(|latest| latest.path);
+        let snapshots = self.list(partition_id).await?;
+
+        let oldest_covering_trim_point = snapshots
+            .iter()
+            .find(|snapshot| snapshot.min_applied_lsn >= trim_point)
+            .map(|snapshot| snapshot.snapshot_id);
+        let retained_by_count = self
+            .retain_num_snapshots
+            .map(|num_snapshots| snapshots.len().saturating_sub(num_snapshots.get()))
+            .unwrap_or(snapshots.len());
+
+        let expired: Vec<_> = snapshots
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, snapshot)| {
+                let is_expired_by_count = *idx < retained_by_count;
+                let is_expired_by_age = self.retain_max_age.is_none_or(|max_age| {
+                    snapshot.created_at.elapsed().unwrap_or_default() > max_age
+                });
+
+                is_expired_by_count
+                    && is_expired_by_age
+                    && latest_path.as_ref() != Some(&snapshot.path)
+                    && oldest_covering_trim_point != Some(snapshot.snapshot_id)
+            })
+            .map(|(_, snapshot)| snapshot)
+            .collect();
+
+        if dry_run {
+            return Ok(expired);
+        }
+
+        for snapshot in &expired {
+            self.delete_snapshot(partition_id, snapshot).await?;
+            info!(
+                snapshot_id = %snapshot.snapshot_id,
+                min_applied_lsn = %snapshot.min_applied_lsn,
+                "Deleted expired partition snapshot",
+            );
+        }
+
+        Ok(expired)
+    }
+
+    async fn delete_snapshot(
+        &self,
+        partition_id: PartitionId,
+        snapshot: &LatestSnapshot,
+    ) -> anyhow::Result<()> {
+        let snapshot_path = object_store::path::Path::from(format!(
+            "{prefix}{partition_id}/{path}",
+            prefix = self.prefix,
+            partition_id = partition_id,
+            path = snapshot.path,
+        ));
+
+        // Removing the metadata first hides the snapshot from readers, so that a failure midway
+        // never leaves behind a listed snapshot with missing data files.
+        match self
+            .object_store
+            .delete(&snapshot_path.child("metadata.json"))
+            .await
+        {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let data_files: Vec<_> = self
+            .object_store
+            .list(Some(&snapshot_path))
+            .map_ok(|object| object.location)
+            .try_collect()
+            .await?;
+        for path in data_files {
+            match self.object_store.delete(&path).await {
+                Ok(()) | Err(object_store::Error::NotFound { .. }) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(())
+    }
+
     async fn get_latest_snapshot_metadata_for_update(
         &self,
         snapshot: &PartitionSnapshotMetadata,
@@ -626,6 +875,81 @@ impl PutSnapshotError {
     }
 }
 
+/// Encrypts and decrypts a single file of an encrypted snapshot.
+struct FileCipher {
+    cipher: Arc<ArchiveCipher>,
+    file_index: u32,
+    file_name: String,
+}
+
+impl FileCipher {
+    fn new(cipher: Arc<ArchiveCipher>, file_index: usize, file_name: &str) -> Self {
+        FileCipher {
+            cipher,
+            file_index: u32::try_from(file_index).expect("snapshot file count fits into u32"),
+            file_name: file_name.to_owned(),
+        }
+    }
+
+    /// Encrypts the plaintext which starts at the given offset of the file.
+    fn encrypt(
+        &self,
+        file_size: u64,
+        offset: u64,
+        plaintext: &[u8],
+        out: &mut BytesMut,
+    ) -> anyhow::Result<()> {
+        self.cipher.encrypt_chunks(
+            self.file_index,
+            &self.file_name,
+            file_size,
+            offset / self.cipher.chunk_size() as u64,
+            plaintext,
+            out,
+        )?;
+        Ok(())
+    }
+
+    /// Decrypts the downloaded file chunk by chunk. Returns the size of the plaintext.
+    async fn decrypt_into(
+        &self,
+        mut encrypted: impl AsyncRead + Unpin,
+        out: &mut tokio::fs::File,
+        file_size: u64,
+    ) -> anyhow::Result<u64> {
+        let mut chunk = vec![0; self.cipher.encrypted_chunk_size()];
+        let mut size = 0;
+        for chunk_index in 0.. {
+            let mut len = 0;
+            while len < chunk.len() {
+                let read = encrypted.read(&mut chunk[len..]).await?;
+                if read == 0 {
+                    break;
+                }
+                len += read;
+            }
+            if len == 0 && chunk_index > 0 {
+                break;
+            }
+
+            let plaintext_len = self.cipher.decrypt_chunk(
+                self.file_index,
+                &self.file_name,
+                file_size,
+                chunk_index,
+                &mut chunk[..len],
+            )?;
+            out.write_all(&chunk[..plaintext_len]).await?;
+            size += plaintext_len as u64;
+
+            if len < chunk.len() {
+                break;
+            }
+        }
+        Ok(size)
+    }
+}
+
 // The object_store `put_multipart` method does not currently support PutMode, so we don't pass this
 // at all; however since we upload snapshots to a unique path on every attempt, we don't expect any
 // conflicts to arise.
@@ -634,12 +958,22 @@ async fn put_snapshot_object(
     key: &object_store::path::Path,
     object_store: &Arc<dyn ObjectStore>,
     buf: &mut BytesMut,
+    file_cipher: Option<&FileCipher>,
 ) -> anyhow::Result<object_store::PutResult> {
     debug!(path = ?file_path, "Putting snapshot object from local file");
     let mut snapshot = tokio::fs::File::open(file_path).await?;
-
-    if snapshot.metadata().await?.len() < MULTIPART_UPLOAD_CHUNK_SIZE_BYTES as u64 {
-        let payload = PutPayload::from(tokio::fs::read(file_path).await?);
+    let file_size = snapshot.metadata().await?.len();
+
+    if file_size < MULTIPART_UPLOAD_CHUNK_SIZE_BYTES as u64 {
+        let content = tokio::fs::read(file_path).await?;
+        let payload = match file_cipher {
+            Some(file_cipher) => {
+                let mut encrypted = BytesMut::new();
+                file_cipher.encrypt(file_size, 0, &content, &mut encrypted)?;
+                PutPayload::from_bytes(encrypted.freeze())
+            }
+            None => PutPayload::from(content),
+        };
         return object_store.put(key, payload).await.map_err(|e| e.into());
     }
 
@@ -647,6 +981,8 @@ async fn put_snapshot_object(
     let mut upload = object_store.put_multipart(key).await?;
 
     let result: anyhow::Result<_> = async {
+        let mut offset = 0;
+        let mut encrypted = BytesMut::new();
         loop {
             let mut len = 0;
             buf.reserve(MULTIPART_UPLOAD_CHUNK_SIZE_BYTES);
@@ -660,9 +996,23 @@ async fn put_snapshot_object(
             }
 
             if !buf.is_empty() {
-                upload
-                    .put_part(PutPayload::from_bytes(buf.split().freeze()))
-                    .await?;
+                let part = match file_cipher {
+                    Some(file_cipher) => {
+                        // Chunks must be complete, except for the last one. The part size is a
+                        // multiple of the chunk size, so parts don't shrink below the minimum.
+                        let plaintext_len = if len == 0 {
+                            buf.len()
+                        } else {
+                            buf.len() - buf.len() % file_cipher.cipher.chunk_size()
+                        };
+                        let plaintext = buf.split_to(plaintext_len);
+                        file_cipher.encrypt(file_size, offset, &plaintext, &mut encrypted)?;
+                        offset += plaintext_len as u64;
+                        encrypted.split().freeze()
+                    }
+                    None => buf.split().freeze(),
+                };
+                upload.put_part(PutPayload::from_bytes(part)).await?;
             }
 
             if len == 0 {
@@ -724,6 +1074,8 @@ mod tests {
     use bytes::Bytes;
     use object_store::path::Path;
     use object_store::ObjectStore;
+    use std::num::NonZeroUsize;
+    use std::sync::Arc;
     use std::time::SystemTime;
     use tempfile::TempDir;
     use tokio::io::AsyncWriteExt;
@@ -733,7 +1085,8 @@ mod tests {
     use tracing_subscriber::{fmt, EnvFilter};
     use url::Url;
 
-    use super::{LatestSnapshot, SnapshotRepository};
+    use super::{LatestSnapshot, SnapshotRepository, MULTIPART_UPLOAD_CHUNK_SIZE_BYTES};
+    use restate_partition_store::encryption::{KeyProvider, LocalKeyProvider};
     use restate_partition_store::snapshots::{PartitionSnapshotMetadata, SnapshotFormatVersion};
     use restate_types::config::SnapshotsOptions;
     use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId};
@@ -773,6 +1126,7 @@ mod tests {
             &opts,
             TempDir::new().unwrap().into_path(),
             "cluster".to_owned(),
+            None,
         )
         .await?
         .unwrap();
@@ -855,6 +1209,7 @@ mod tests {
             &opts,
             TempDir::new().unwrap().into_path(),
             "cluster".to_owned(),
+            None,
         )
         .await?
         .unwrap();
@@ -934,6 +1289,152 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_put_get_encrypted_snapshot() -> anyhow::Result<()> {
+        let snapshot_source = TempDir::new()?;
+        let source_dir = snapshot_source.path().to_path_buf();
+
+        // large enough for a multipart upload with a partial last chunk
+        let data: Vec<u8> = (0..MULTIPART_UPLOAD_CHUNK_SIZE_BYTES + 1536 * 1024)
+            .map(|i| i as u8)
+            .collect();
+        tokio::fs::write(source_dir.join("data.sst"), &data).await?;
+
+        let snapshot = mock_snapshot_metadata(
+            "/data.sst".to_owned(),
+            source_dir.to_string_lossy().to_string(),
+            data.len(),
+        );
+
+        let snapshots_destination = TempDir::new()?;
+        let opts = SnapshotsOptions {
+            destination: Some(
+                Url::from_file_path(snapshots_destination.path())
+                    .unwrap()
+                    .to_string(),
+            ),
+            ..SnapshotsOptions::default()
+        };
+        let keys_dir = TempDir::new()?;
+        let key_provider: Arc<dyn KeyProvider> =
+            Arc::new(LocalKeyProvider::from_kms_directory(keys_dir.path(), None)?);
+        let repository = SnapshotRepository::create_if_configured(
+            &opts,
+            TempDir::new().unwrap().into_path(),
+            "cluster".to_owned(),
+            Some(key_provider),
+        )
+        .await?
+        .unwrap();
+
+        repository.put(&snapshot, source_dir).await?;
+
+        let stored = tokio::fs::read(
+            snapshots_destination
+                .path()
+                .join(PartitionId::MIN.to_string())
+                .join(SnapshotRepository::get_snapshot_prefix(&snapshot))
+                .join("data.sst"),
+        )
+        .await?;
+        assert!(stored.len() > data.len());
+        assert!(!stored.windows(64).any(|window| window == &data[..64]));
+
+        let latest = repository.get_latest(PartitionId::MIN).await?.unwrap();
+        let restored = tokio::fs::read(latest.base_dir.join("data.sst")).await?;
+        assert!(restored == data);
+        tokio::fs::remove_dir_all(&latest.base_dir).await?;
+
+        // snapshots can't be restored without the key
+        let repository = SnapshotRepository {
+            key_provider: None,
+            ..repository
+        };
+        assert!(repository.get_latest(PartitionId::MIN).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prune_snapshots() -> anyhow::Result<()> {
+        let snapshots_destination = TempDir::new()?;
+        let opts = SnapshotsOptions {
+            destination: Some(
+                Url::from_file_path(snapshots_destination.path())
+                    .unwrap()
+                    .to_string(),
+            ),
+            retain_num_snapshots: Some(NonZeroUsize::new(1).unwrap()),
+            ..SnapshotsOptions::default()
+        };
+        let repository = SnapshotRepository::create_if_configured(
+            &opts,
+            TempDir::new().unwrap().into_path(),
+            "cluster".to_owned(),
+            None,
+        )
+        .await?
+        .unwrap();
+
+        let mut snapshots = Vec::new();
+        for lsn in [10, 20, 30] {
+            let snapshot_source = TempDir::new()?;
+            let source_dir = snapshot_source.path().to_path_buf();
+
+            let data = b"snapshot-data";
+            let mut data_file = tokio::fs::File::create(source_dir.join("data.sst")).await?;
+            data_file.write_all(data).await?;
+
+            let mut snapshot = mock_snapshot_metadata(
+                "/data.sst".to_owned(),
+                source_dir.to_string_lossy().to_string(),
+                data.len(),
+            );
+            snapshot.min_applied_lsn = Lsn::new(lsn);
+            repository.put(&snapshot, source_dir).await?;
+            snapshots.push(snapshot);
+        }
+
+        let listed = repository.list(PartitionId::MIN).await?;
+        assert_eq!(
+            listed
+                .iter()
+                .map(|snapshot| snapshot.snapshot_id)
+                .collect::<Vec<_>>(),
+            snapshots
+                .iter()
+                .map(|snapshot| snapshot.snapshot_id)
+                .collect::<Vec<_>>()
+        );
+
+        // The snapshot at LSN 20 is the oldest one covering the trim point and must be retained
+        let trim_point = Lsn::new(15);
+        let pruned = repository.prune(PartitionId::MIN, trim_point, true).await?;
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].snapshot_id, snapshots[0].snapshot_id);
+        assert_eq!(repository.list(PartitionId::MIN).await?.len(), 3);
+
+        repository
+            .prune(PartitionId::MIN, trim_point, false)
+            .await?;
+        let listed = repository.list(PartitionId::MIN).await?;
+        assert_eq!(
+            listed
+                .iter()
+                .map(|snapshot| snapshot.min_applied_lsn)
+                .collect::<Vec<_>>(),
+            vec![Lsn::new(20), Lsn::new(30)]
+        );
+        let pruned_dir = snapshots_destination.path().join(format!(
+            "{}/{}",
+            PartitionId::MIN,
+            SnapshotRepository::get_snapshot_prefix(&snapshots[0])
+        ));
+        assert!(!tokio::fs::try_exists(pruned_dir.join("data.sst")).await?);
+
+        Ok(())
+    }
+
     fn mock_snapshot_metadata(
         file_name: String,
         directory: String,
@@ -963,6 +1464,8 @@ mod tests {
                 smallest_seqno: 0,
                 largest_seqno: 0,
             }],
+            data_keys: vec![],
+            archive_encryption: None,
         }
     }
 }
@@ -88,6 +88,8 @@ impl SnapshotPartitionTask {
             min_applied_lsn: snapshot.min_applied_lsn,
             db_comparator_name: snapshot.db_comparator_name.clone(),
             files: snapshot.files.clone(),
+            data_keys: snapshot.data_keys.clone(),
+            archive_encryption: None,
         }
     }
 }
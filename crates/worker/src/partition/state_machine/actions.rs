@@ -51,6 +51,9 @@ pub enum Action {
         invocation_id: InvocationId,
         acknowledge: bool,
     },
+    RetryInvocationNow {
+        invocation_id: InvocationId,
+    },
     IngressResponse {
         request_id: PartitionProcessorRpcRequestId,
         invocation_id: Option<InvocationId>,
@@ -19,7 +19,7 @@ use assert2::let_assert;
 use bytes::Bytes;
 use bytestring::ByteString;
 use enumset::EnumSet;
-use futures::{StreamExt, TryStreamExt};
+use futures::{future, StreamExt, TryStreamExt};
 use metrics::{histogram, Histogram};
 use restate_invoker_api::InvokeInputJournal;
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
@@ -34,7 +34,8 @@ use restate_storage_api::invocation_status_table::{
 use restate_storage_api::invocation_status_table::{InvocationStatus, ScheduledInvocation};
 use restate_storage_api::journal_table::ReadOnlyJournalTable;
 use restate_storage_api::journal_table::{JournalEntry, JournalTable};
-use restate_storage_api::outbox_table::{OutboxMessage, OutboxTable};
+use restate_storage_api::outbox_table::{KafkaEgressRecord, OutboxMessage, OutboxTable};
+use restate_storage_api::parked_kafka_record_table::{ParkedKafkaRecord, ParkedKafkaRecordTable};
 use restate_storage_api::promise_table::{Promise, PromiseState, PromiseTable};
 use restate_storage_api::service_status_table::{
     ReadOnlyVirtualObjectStatusTable, VirtualObjectStatus, VirtualObjectStatusTable,
@@ -54,13 +55,14 @@ use restate_types::identifiers::{
     EntryIndex, InvocationId, PartitionKey, PartitionProcessorRpcRequestId, ServiceId,
 };
 use restate_types::identifiers::{
-    IdempotencyId, JournalEntryId, WithInvocationId, WithPartitionKey,
+    IdempotencyId, JournalEntryId, SubscriptionId, WithInvocationId, WithPartitionKey,
 };
 use restate_types::invocation::{
     AttachInvocationRequest, InvocationQuery, InvocationResponse, InvocationTarget,
-    InvocationTargetType, InvocationTermination, ResponseResult, ServiceInvocation,
-    ServiceInvocationResponseSink, ServiceInvocationSpanContext, Source, SubmitNotificationSink,
-    TerminationFlavor, VirtualObjectHandlerType, WorkflowHandlerType,
+    InvocationTargetType, InvocationTermination, ResponseResult, RestartInvocationRequest,
+    RetryInvocationNowRequest, ServiceInvocation, ServiceInvocationResponseSink,
+    ServiceInvocationSpanContext, Source, SubmitNotificationSink, TerminationFlavor,
+    VirtualObjectHandlerType, WorkflowHandlerType,
 };
 use restate_types::invocation::{InvocationInput, SpanRelation};
 use restate_types::journal::enriched::EnrichedRawEntry;
@@ -77,6 +79,7 @@ use restate_types::net::partition_processor::IngressResponseResult;
 use restate_types::state_mut::ExternalStateMutation;
 use restate_types::state_mut::StateMutationVersion;
 use restate_types::time::MillisSinceEpoch;
+use restate_wal_protocol::kafka::ReplayParkedKafkaRecords;
 use restate_wal_protocol::timer::TimerKeyDisplay;
 use restate_wal_protocol::timer::TimerKeyValue;
 use restate_wal_protocol::Command;
@@ -249,7 +252,8 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             + TimerTable
             + VirtualObjectStatusTable
             + InboxTable
-            + StateTable,
+            + StateTable
+            + ParkedKafkaRecordTable,
     >(
         &mut self,
         mut ctx: StateMachineApplyContext<'_, State>,
@@ -284,6 +288,10 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 self.handle_attach_invocation_request(&mut ctx, attach_invocation_request)
                     .await
             }
+            Command::ParkKafkaRecord(record) => Self::on_park_kafka_record(&mut ctx, record).await,
+            Command::ReplayParkedKafkaRecords(replay) => {
+                self.on_replay_parked_kafka_records(&mut ctx, replay).await
+            }
             Command::InvokerEffect(effect) => self.try_invoker_effect(&mut ctx, effect).await,
             Command::TruncateOutbox(index) => {
                 Self::do_truncate_outbox(
@@ -303,6 +311,18 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 self.on_purge_invocation(&mut ctx, purge_invocation_request.invocation_id)
                     .await
             }
+            Command::PauseInvocation(pause_invocation_request) => {
+                Self::on_pause_invocation(&mut ctx, pause_invocation_request.invocation_id).await
+            }
+            Command::ResumeInvocation(resume_invocation_request) => {
+                Self::on_resume_invocation(&mut ctx, resume_invocation_request.invocation_id).await
+            }
+            Command::RetryInvocationNow(retry_invocation_now_request) => {
+                Self::on_retry_invocation_now(&mut ctx, retry_invocation_now_request).await
+            }
+            Command::RestartInvocation(restart_invocation_request) => {
+                Self::on_restart_invocation(&mut ctx, restart_invocation_request).await
+            }
             Command::PatchState(mutation) => {
                 self.handle_external_state_mutation(&mut ctx, mutation)
                     .await
@@ -568,6 +588,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         match previous_invocation_status {
             is @ InvocationStatus::Invoked { .. }
             | is @ InvocationStatus::Suspended { .. }
+            | is @ InvocationStatus::Paused { .. }
             | is @ InvocationStatus::Inboxed { .. }
             | is @ InvocationStatus::Scheduled { .. } => {
                 if let Some(ref response_sink) = service_invocation.response_sink {
@@ -887,6 +908,11 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 self.kill_suspended_invocation(ctx, invocation_id, metadata)
                     .await?;
             }
+            InvocationStatus::Paused(metadata) => {
+                // Like for suspended invocations, the invoker is not running a paused invocation.
+                self.kill_suspended_invocation(ctx, invocation_id, metadata)
+                    .await?;
+            }
             InvocationStatus::Inboxed(inboxed) => {
                 self.terminate_inboxed_invocation(
                     ctx,
@@ -970,6 +996,17 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     Self::do_resume_service(ctx, invocation_id, metadata).await?;
                 }
             }
+            InvocationStatus::Paused(metadata) => {
+                // The cancellation is recorded in the journal,
+                // and will be handled by the invocation once it's resumed.
+                self.cancel_journal_leaves(
+                    ctx,
+                    invocation_id,
+                    InvocationStatusProjection::Paused,
+                    metadata.journal_metadata.length,
+                )
+                .await?;
+            }
             InvocationStatus::Inboxed(inboxed) => {
                 self.terminate_inboxed_invocation(
                     ctx,
@@ -1170,6 +1207,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 invocation_id,
                 metadata,
                 Some(ResponseResult::Failure(KILLED_INVOCATION_ERROR)),
+                vec![],
             )
             .await?;
             Self::do_send_abort_invocation_to_invoker(ctx, invocation_id, false);
@@ -1202,6 +1240,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             invocation_id,
             metadata,
             Some(ResponseResult::Failure(KILLED_INVOCATION_ERROR)),
+            vec![],
         )
         .await?;
         Self::do_send_abort_invocation_to_invoker(ctx, invocation_id, false);
@@ -1358,6 +1397,15 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 )
                 .await
             }
+            InvocationStatusProjection::Paused => {
+                Self::store_completion(
+                    ctx,
+                    invocation_id,
+                    Completion::new(journal_index, canceled_result),
+                )
+                .await?;
+                Ok(false)
+            }
         }
     }
 
@@ -1416,6 +1464,297 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(())
     }
 
+    async fn on_pause_invocation<State: InvocationStatusTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+    ) -> Result<(), Error> {
+        match ctx.get_invocation_status(&invocation_id).await? {
+            InvocationStatus::Invoked(metadata) => {
+                Self::do_pause_invocation(ctx, invocation_id, metadata).await;
+                // Stop the invoker from executing or retrying the invocation while it's paused
+                Self::do_send_abort_invocation_to_invoker(ctx, invocation_id, false);
+            }
+            InvocationStatus::Suspended { metadata, .. } => {
+                Self::do_pause_invocation(ctx, invocation_id, metadata).await;
+            }
+            InvocationStatus::Paused(_) => {
+                trace!(
+                    "Received pause command for an already paused invocation '{invocation_id}'."
+                );
+            }
+            InvocationStatus::Free => {
+                trace!("Received pause command for unknown invocation with id '{invocation_id}'.");
+            }
+            _ => {
+                debug!(
+                    "Ignoring pause command as the invocation '{invocation_id}' is not running."
+                );
+            }
+        };
+
+        Ok(())
+    }
+
+    async fn on_resume_invocation<State: InvocationStatusTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+    ) -> Result<(), Error> {
+        match ctx.get_invocation_status(&invocation_id).await? {
+            InvocationStatus::Paused(metadata) => {
+                Self::do_resume_service(ctx, invocation_id, metadata).await?;
+            }
+            InvocationStatus::Free => {
+                trace!("Received resume command for unknown invocation with id '{invocation_id}'.");
+            }
+            _ => {
+                debug!(
+                    "Ignoring resume command as the invocation '{invocation_id}' is not paused."
+                );
+            }
+        };
+
+        Ok(())
+    }
+
+    async fn on_park_kafka_record<State: ParkedKafkaRecordTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        record: ParkedKafkaRecord,
+    ) -> Result<(), Error> {
+        debug_if_leader!(
+            ctx.is_leader,
+            restate.subscription.id = %record.subscription_id,
+            "Park Kafka record of topic '{}' partition '{}' offset '{}': {}",
+            record.topic,
+            record.kafka_partition,
+            record.offset,
+            record.error
+        );
+        ctx.storage.put_parked_kafka_record(&record).await;
+        Ok(())
+    }
+
+    async fn on_replay_parked_kafka_records<
+        State: ParkedKafkaRecordTable + OutboxTable + FsmTable,
+    >(
+        &mut self,
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        ReplayParkedKafkaRecords {
+            subscription_id,
+            sink,
+            ..
+        }: ReplayParkedKafkaRecords,
+    ) -> Result<(), Error> {
+        let records: Vec<_> = ctx
+            .storage
+            .all_parked_kafka_records(self.partition_key_range.clone())
+            .try_filter(|record| future::ready(record.subscription_id == subscription_id))
+            .try_collect()
+            .await?;
+
+        for record in records {
+            let invocation_target = match sink.invocation_target(&record.key) {
+                Ok(invocation_target) => invocation_target,
+                Err(err) => {
+                    debug_if_leader!(
+                        ctx.is_leader,
+                        restate.subscription.id = %subscription_id,
+                        "Kafka record of topic '{}' partition '{}' offset '{}' stays parked: {err}",
+                        record.topic,
+                        record.kafka_partition,
+                        record.offset
+                    );
+                    continue;
+                }
+            };
+
+            // Replicas must agree on the invocation id, so it is derived from the record's
+            // position in Kafka rather than generated randomly. The deduplication index tells
+            // apart the parkings of the same record after the offsets were reset.
+            let invocation_id = InvocationId::generate(
+                &invocation_target,
+                Some(&format!(
+                    "{}-{}-{}-{}-{}",
+                    subscription_id,
+                    record.topic,
+                    record.kafka_partition,
+                    record.offset,
+                    record.deduplication_index
+                )),
+            );
+            let mut service_invocation = ServiceInvocation::initialize(
+                invocation_id,
+                invocation_target,
+                Source::Subscription(subscription_id),
+            );
+            service_invocation.argument = record.payload.clone();
+            service_invocation.headers = record.headers.clone();
+
+            debug_if_leader!(
+                ctx.is_leader,
+                restate.invocation.id = %invocation_id,
+                "Replay parked Kafka record of topic '{}' partition '{}' offset '{}'",
+                record.topic,
+                record.kafka_partition,
+                record.offset
+            );
+            self.handle_outgoing_message(ctx, OutboxMessage::ServiceInvocation(service_invocation))
+                .await?;
+            ctx.storage.delete_parked_kafka_record(&record).await;
+        }
+
+        Ok(())
+    }
+
+    async fn on_retry_invocation_now<State: InvocationStatusTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        RetryInvocationNowRequest {
+            invocation_id,
+            pinned_deployment,
+        }: RetryInvocationNowRequest,
+    ) -> Result<(), Error> {
+        match ctx.get_invocation_status(&invocation_id).await? {
+            InvocationStatus::Invoked(mut metadata) => {
+                if let Some(pinned_deployment) = pinned_deployment {
+                    Self::do_change_pinned_deployment(
+                        ctx,
+                        invocation_id,
+                        pinned_deployment,
+                        &mut metadata,
+                    );
+                    ctx.storage
+                        .put_invocation_status(&invocation_id, &InvocationStatus::Invoked(metadata))
+                        .await;
+                }
+                Self::do_send_retry_now_to_invoker(ctx, invocation_id);
+            }
+            InvocationStatus::Paused(mut metadata) => {
+                // A paused invocation is not retried by the invoker anymore, so retrying it now
+                // means resuming it.
+                if let Some(pinned_deployment) = pinned_deployment {
+                    Self::do_change_pinned_deployment(
+                        ctx,
+                        invocation_id,
+                        pinned_deployment,
+                        &mut metadata,
+                    );
+                }
+                Self::do_resume_service(ctx, invocation_id, metadata).await?;
+            }
+            InvocationStatus::Free => {
+                trace!(
+                    "Received retry now command for unknown invocation with id '{invocation_id}'."
+                );
+            }
+            _ => {
+                debug!(
+                    "Ignoring retry now command as the invocation '{invocation_id}' is not running."
+                );
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Removes the journal entries after `journal_index` and replays the invocation from there.
+    /// The admin API validates the request, invalid requests which get here anyway are ignored.
+    /// Effects of the removed entries, like calls, state changes or completed promises, are not
+    /// rolled back.
+    async fn on_restart_invocation<State: InvocationStatusTable + JournalTable + TimerTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        RestartInvocationRequest {
+            invocation_id,
+            journal_index,
+            pinned_deployment,
+        }: RestartInvocationRequest,
+    ) -> Result<(), Error> {
+        // Only invocations which are not executing can be restarted, otherwise effects
+        // of the running attempt could still be appended to the truncated journal.
+        let mut metadata = match ctx.get_invocation_status(&invocation_id).await? {
+            InvocationStatus::Paused(metadata) | InvocationStatus::Suspended { metadata, .. } => {
+                metadata
+            }
+            InvocationStatus::Invoked(_) => {
+                debug!(
+                    "Ignoring restart command as the invocation '{invocation_id}' is executing, it must be paused first."
+                );
+                return Ok(());
+            }
+            InvocationStatus::Free => {
+                trace!(
+                    "Received restart command for unknown invocation with id '{invocation_id}'."
+                );
+                return Ok(());
+            }
+            _ => {
+                debug!(
+                    "Ignoring restart command as the invocation '{invocation_id}' is not running."
+                );
+                return Ok(());
+            }
+        };
+
+        let journal_length = metadata.journal_metadata.length;
+        if journal_index >= journal_length {
+            debug!(
+                "Ignoring restart command for invocation '{invocation_id}' as the journal index {journal_index} is out of the journal bounds (length {journal_length})."
+            );
+            return Ok(());
+        }
+
+        let truncated_entries: Vec<(EntryIndex, EnrichedRawEntry)> = ctx
+            .storage
+            .get_journal(&invocation_id, journal_length)
+            .try_filter_map(|(entry_index, journal_entry)| async move {
+                if let JournalEntry::Entry(journal_entry) = journal_entry {
+                    if entry_index > journal_index {
+                        return Ok(Some((entry_index, journal_entry)));
+                    }
+                }
+
+                Ok(None)
+            })
+            .try_collect()
+            .await?;
+
+        // The completion of a pending entry, other than a sleep, would otherwise be applied to
+        // whichever entry takes its index after the replay.
+        if let Some((entry_index, _)) = truncated_entries.iter().find(|(_, entry)| {
+            entry.header().is_completed() == Some(false)
+                && !matches!(entry.header(), EnrichedEntryHeader::Sleep { .. })
+        }) {
+            debug!(
+                "Ignoring restart command for invocation '{invocation_id}' as the entry {entry_index} to remove is still waiting for its completion."
+            );
+            return Ok(());
+        }
+
+        for (entry_index, journal_entry) in truncated_entries {
+            let (header, entry) = journal_entry.into_inner();
+            if let EnrichedEntryHeader::Sleep {
+                is_completed: false,
+            } = header
+            {
+                let_assert!(
+                    Entry::Sleep(SleepEntry { wake_up_time, .. }) =
+                        ProtobufRawEntryCodec::deserialize(EntryType::Sleep, entry)?
+                );
+
+                let (timer_key, _) =
+                    Timer::complete_journal_entry(wake_up_time, invocation_id, entry_index);
+
+                Self::do_delete_timer(ctx, timer_key).await?;
+            }
+        }
+
+        Self::do_truncate_journal(ctx, invocation_id, journal_index + 1, &mut metadata).await;
+        if let Some(pinned_deployment) = pinned_deployment {
+            Self::do_change_pinned_deployment(ctx, invocation_id, pinned_deployment, &mut metadata);
+        }
+        Self::do_resume_service(ctx, invocation_id, metadata).await?;
+
+        Ok(())
+    }
+
     async fn on_timer<
         State: IdempotencyTable
             + InvocationStatusTable
@@ -1571,7 +1910,13 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             return Ok(());
         }
         if is_status_killed
-            && !matches!(kind, InvokerEffectKind::Failed(_) | InvokerEffectKind::End)
+            && !matches!(
+                kind,
+                InvokerEffectKind::Failed(_)
+                    | InvokerEffectKind::Paused(_)
+                    | InvokerEffectKind::End
+                    | InvokerEffectKind::EndWithEgress { .. }
+            )
         {
             warn!(
                 "Received non terminal invoker effect for killed invocation. Ignoring the effect."
@@ -1650,18 +1995,65 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     } else {
                         None
                     },
+                    vec![],
                 )
                 .await?;
             }
+            InvokerEffectKind::EndWithEgress {
+                egress_subscriptions,
+            } => {
+                if is_status_killed {
+                    // Killed invocations have no output to publish
+                    self.end_invocation(
+                        ctx,
+                        invocation_id,
+                        invocation_metadata,
+                        Some(ResponseResult::Failure(KILLED_INVOCATION_ERROR)),
+                        vec![],
+                    )
+                    .await?;
+                } else {
+                    self.end_invocation(
+                        ctx,
+                        invocation_id,
+                        invocation_metadata,
+                        None,
+                        egress_subscriptions,
+                    )
+                    .await?;
+                }
+            }
             InvokerEffectKind::Failed(e) => {
                 self.end_invocation(
                     ctx,
                     invocation_id,
                     invocation_metadata,
                     Some(ResponseResult::Failure(e)),
+                    vec![],
                 )
                 .await?;
             }
+            InvokerEffectKind::Paused(e) => {
+                if is_status_killed {
+                    // The invocation was killed meanwhile, there's nothing left to pause.
+                    self.end_invocation(
+                        ctx,
+                        invocation_id,
+                        invocation_metadata,
+                        Some(ResponseResult::Failure(KILLED_INVOCATION_ERROR)),
+                        vec![],
+                    )
+                    .await?;
+                } else {
+                    debug_if_leader!(
+                        ctx.is_leader,
+                        restate.invocation.id = %invocation_id,
+                        "Retries exhausted with error '{}', pausing the invocation",
+                        e
+                    );
+                    Self::do_pause_invocation(ctx, invocation_id, invocation_metadata).await;
+                }
+            }
         }
 
         Ok(())
@@ -1682,14 +2074,19 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         invocation_metadata: InFlightInvocationMetadata,
         // If given, this will override any Output Entry available in the journal table
         response_result_override: Option<ResponseResult>,
+        // Egress subscriptions publishing the output, if the invocation succeeded
+        egress_subscriptions: Vec<SubscriptionId>,
     ) -> Result<(), Error> {
         let invocation_target = invocation_metadata.invocation_target.clone();
         let journal_length = invocation_metadata.journal_metadata.length;
         let completion_retention_time = invocation_metadata.completion_retention_duration;
 
-        // If there are any response sinks, or we need to store back the completed status,
-        //  we need to find the latest output entry
-        if !invocation_metadata.response_sinks.is_empty() || !completion_retention_time.is_zero() {
+        // If there are any response sinks, egress subscriptions, or we need to store back the
+        //  completed status, we need to find the latest output entry
+        if !invocation_metadata.response_sinks.is_empty()
+            || !egress_subscriptions.is_empty()
+            || !completion_retention_time.is_zero()
+        {
             let response_result = if let Some(response_result) = response_result_override {
                 response_result
             } else if let Some(output_entry) = self
@@ -1714,6 +2111,18 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             )
             .await?;
 
+            // Publish the output
+            if let ResponseResult::Success(payload) = &response_result {
+                self.publish_to_egress_subscriptions(
+                    ctx,
+                    egress_subscriptions,
+                    invocation_id,
+                    &invocation_metadata.invocation_target,
+                    payload,
+                )
+                .await?;
+            }
+
             // Notify invocation result
             self.notify_invocation_result(
                 ctx,
@@ -1812,6 +2221,35 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(())
     }
 
+    async fn publish_to_egress_subscriptions<State: OutboxTable + FsmTable>(
+        &mut self,
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        egress_subscriptions: Vec<SubscriptionId>,
+        invocation_id: InvocationId,
+        invocation_target: &InvocationTarget,
+        payload: &Bytes,
+    ) -> Result<(), Error> {
+        // Records of keyed handlers are keyed by the object key, so that Kafka preserves their order
+        let key = match invocation_target.key() {
+            Some(key) => key.as_bytes().clone(),
+            None => Bytes::from(invocation_id.to_string()),
+        };
+
+        for subscription_id in egress_subscriptions {
+            self.handle_outgoing_message(
+                ctx,
+                OutboxMessage::KafkaEgress(KafkaEgressRecord {
+                    subscription_id,
+                    invocation_id,
+                    key: key.clone(),
+                    payload: payload.clone(),
+                }),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
     async fn consume_inbox<
         State: InboxTable
             + VirtualObjectStatusTable
@@ -2741,6 +3179,10 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     Self::do_resume_service(ctx, invocation_id, metadata).await?;
                 }
             }
+            InvocationStatus::Paused(_) => {
+                // Keep the completion, it will be replayed once the invocation is resumed.
+                Self::store_completion(ctx, invocation_id, completion).await?;
+            }
             _ => {
                 debug!(
                     rectx.storage.invocation.id = %invocation_id,
@@ -2933,6 +3375,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             }
             is @ InvocationStatus::Invoked(_)
             | is @ InvocationStatus::Suspended { .. }
+            | is @ InvocationStatus::Paused(_)
             | is @ InvocationStatus::Inboxed(_)
             | is @ InvocationStatus::Scheduled(_) => {
                 if attach_invocation_request.block_on_inflight {
@@ -3099,6 +3542,23 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             .await;
     }
 
+    async fn do_pause_invocation<State: InvocationStatusTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+        mut metadata: InFlightInvocationMetadata,
+    ) {
+        debug_if_leader!(
+            ctx.is_leader,
+            restate.invocation.id = %invocation_id,
+            "Effect: Pause invocation"
+        );
+
+        metadata.timestamps.update();
+        ctx.storage
+            .put_invocation_status(&invocation_id, &InvocationStatus::Paused(metadata))
+            .await;
+    }
+
     async fn do_store_completed_invocation<State: InvocationStatusTable>(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
@@ -3215,6 +3675,19 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     invocation_query,
                 )
             }
+            OutboxMessage::KafkaEgress(KafkaEgressRecord {
+                subscription_id,
+                invocation_id,
+                ..
+            }) => {
+                debug_if_leader!(
+                    ctx.is_leader,
+                    restate.invocation.id = %invocation_id,
+                    restate.subscription.id = %subscription_id,
+                    restate.outbox.seq = seq_number,
+                    "Effect: Enqueuing output for Kafka egress subscription"
+                )
+            }
         };
 
         ctx.storage.put_outbox_message(seq_number, &message).await;
@@ -3404,6 +3877,25 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(())
     }
 
+    fn do_change_pinned_deployment<State>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+        pinned_deployment: PinnedDeployment,
+        metadata: &mut InFlightInvocationMetadata,
+    ) {
+        debug_if_leader!(
+            ctx.is_leader,
+            restate.invocation.id = %invocation_id,
+            restate.deployment.id = %pinned_deployment.deployment_id,
+            restate.deployment.service_protocol_version = %pinned_deployment.service_protocol_version.as_repr(),
+            "Effect: Change pinned deployment"
+        );
+
+        // Unlike set_pinned_deployment, this is allowed to overwrite a previously chosen deployment.
+        metadata.pinned_deployment = Some(pinned_deployment);
+        metadata.timestamps.update();
+    }
+
     async fn do_store_pinned_deployment<State: InvocationStatusTable>(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
@@ -3487,6 +3979,26 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             .await;
     }
 
+    async fn do_truncate_journal<State: JournalTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+        new_length: EntryIndex,
+        metadata: &mut InFlightInvocationMetadata,
+    ) {
+        debug_if_leader!(
+            ctx.is_leader,
+            restate.invocation.id = %invocation_id,
+            restate.journal.length = new_length,
+            "Effect: Truncate journal"
+        );
+
+        ctx.storage
+            .truncate_journal(&invocation_id, new_length, metadata.journal_metadata.length)
+            .await;
+        metadata.journal_metadata.length = new_length;
+        metadata.timestamps.update();
+    }
+
     async fn do_truncate_outbox<State: OutboxTable>(
         ctx: &mut StateMachineApplyContext<'_, State>,
         range: RangeInclusive<MessageIndex>,
@@ -3678,6 +4190,16 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         });
     }
 
+    fn do_send_retry_now_to_invoker<State>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+    ) {
+        debug_if_leader!(ctx.is_leader, restate.invocation.id = %invocation_id, "Effect: Send retry now command to invoker");
+
+        ctx.action_collector
+            .push(Action::RetryInvocationNow { invocation_id });
+    }
+
     async fn do_mutate_state<State: StateTable>(
         ctx: &mut StateMachineApplyContext<'_, State>,
         state_mutation: ExternalStateMutation,
@@ -3778,6 +4300,7 @@ impl<'a> fmt::Display for CompletionResultFmt<'a> {
 enum InvocationStatusProjection {
     Invoked,
     Suspended(HashSet<EntryIndex>),
+    Paused,
 }
 
 #[cfg(test)]
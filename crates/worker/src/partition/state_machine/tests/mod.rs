@@ -15,6 +15,7 @@ mod fixtures;
 mod idempotency;
 mod kill_cancel;
 mod matchers;
+mod pause_resume;
 mod workflow;
 
 use crate::partition::state_machine::tests::fixtures::{
@@ -40,7 +41,10 @@ use restate_storage_api::invocation_status_table::{
     ReadOnlyInvocationStatusTable,
 };
 use restate_storage_api::journal_table::{JournalEntry, ReadOnlyJournalTable};
-use restate_storage_api::outbox_table::OutboxTable;
+use restate_storage_api::outbox_table::{KafkaEgressRecord, OutboxMessage, OutboxTable};
+use restate_storage_api::parked_kafka_record_table::{
+    ParkedKafkaRecord, ReadOnlyParkedKafkaRecordTable,
+};
 use restate_storage_api::service_status_table::{
     ReadOnlyVirtualObjectStatusTable, VirtualObjectStatus, VirtualObjectStatusTable,
 };
@@ -51,6 +55,7 @@ use restate_types::config::{CommonOptions, WorkerOptions};
 use restate_types::errors::{codes, InvocationError, KILLED_INVOCATION_ERROR};
 use restate_types::identifiers::{
     InvocationId, PartitionId, PartitionKey, PartitionProcessorRpcRequestId, ServiceId,
+    SubscriptionId,
 };
 use restate_types::invocation::{
     Header, InvocationResponse, InvocationTarget, InvocationTermination, ResponseResult,
@@ -62,6 +67,7 @@ use restate_types::journal::{
 };
 use restate_types::journal::{Entry, EntryType};
 use restate_types::live::{Constant, Live};
+use restate_types::schema::subscriptions::{EventInvocationTargetTemplate, Sink};
 use restate_types::state_mut::ExternalStateMutation;
 use std::collections::{HashMap, HashSet};
 use test_log::test;
@@ -922,6 +928,202 @@ async fn send_ingress_response_to_multiple_targets() -> TestResult {
     Ok(())
 }
 
+#[test(restate_core::test)]
+async fn end_with_egress_publishes_output() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_target = InvocationTarget::mock_virtual_object();
+    let invocation_id = fixtures::mock_start_invocation_with_invocation_target(
+        &mut test_env,
+        invocation_target.clone(),
+    )
+    .await;
+    let subscription_id = SubscriptionId::new();
+
+    let response_bytes = Bytes::from_static(b"123");
+    let _ = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            kind: InvokerEffectKind::JournalEntry {
+                entry_index: 1,
+                entry: ProtobufRawEntryCodec::serialize_enriched(Entry::output(
+                    EntryResult::Success(response_bytes.clone()),
+                )),
+            },
+        }))
+        .await;
+    let actions = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            kind: InvokerEffectKind::EndWithEgress {
+                egress_subscriptions: vec![subscription_id],
+            },
+        }))
+        .await;
+
+    // The record is keyed by the object key
+    let expected_message = OutboxMessage::KafkaEgress(KafkaEgressRecord {
+        subscription_id,
+        invocation_id,
+        key: invocation_target.key().unwrap().as_bytes().clone(),
+        payload: response_bytes,
+    });
+    assert_that!(
+        actions,
+        contains(pat!(Action::NewOutboxMessage {
+            seq_number: eq(0),
+            message: eq(expected_message.clone())
+        }))
+    );
+    assert_that!(
+        test_env.storage.get_outbox_message(0).await?,
+        some(eq(expected_message))
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Free)
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn replay_parked_kafka_records() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let subscription_id = SubscriptionId::new();
+    let parked_record = |offset, key: &'static [u8]| ParkedKafkaRecord {
+        partition_key: 10,
+        subscription_id,
+        topic: "orders".into(),
+        kafka_partition: 0,
+        offset,
+        deduplication_index: offset as u64,
+        key: Bytes::from_static(key),
+        payload: Bytes::from_static(b"{}"),
+        headers: vec![],
+        error: "The Kafka record key must be valid UTF-8".into(),
+        parked_at: MillisSinceEpoch::new(1),
+    };
+    let invalid_key = parked_record(1, &[0xff]);
+    let valid_key = parked_record(2, b"my-key");
+
+    let _ = test_env
+        .apply(Command::ParkKafkaRecord(invalid_key.clone()))
+        .await;
+    let _ = test_env
+        .apply(Command::ParkKafkaRecord(valid_key.clone()))
+        .await;
+
+    let actions = test_env
+        .apply(Command::ReplayParkedKafkaRecords(
+            ReplayParkedKafkaRecords {
+                subscription_id,
+                sink: Sink::Invocation {
+                    event_invocation_target_template:
+                        EventInvocationTargetTemplate::VirtualObject {
+                            name: "MyObject".to_string(),
+                            handler: "handle".to_string(),
+                            handler_ty: VirtualObjectHandlerType::Exclusive,
+                        },
+                },
+                partition_key_range: PartitionKey::MIN..=PartitionKey::MAX,
+            },
+        ))
+        .await;
+
+    assert_that!(
+        actions,
+        contains(pat!(Action::NewOutboxMessage {
+            seq_number: eq(0),
+            message: pat!(OutboxMessage::ServiceInvocation(pat!(ServiceInvocation {
+                invocation_target: eq(InvocationTarget::virtual_object(
+                    "MyObject",
+                    "my-key",
+                    "handle",
+                    VirtualObjectHandlerType::Exclusive
+                )),
+                argument: eq(valid_key.payload.clone()),
+                source: eq(Source::Subscription(subscription_id))
+            })))
+        }))
+    );
+    // The record with the invalid key stays parked
+    assert_eq!(
+        test_env
+            .storage
+            .all_parked_kafka_records(PartitionKey::MIN..=PartitionKey::MAX)
+            .try_collect::<Vec<_>>()
+            .await?,
+        vec![invalid_key]
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn replay_record_parked_again_after_offsets_reset() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let subscription_id = SubscriptionId::new();
+    let parked_record = |deduplication_index| ParkedKafkaRecord {
+        partition_key: 10,
+        subscription_id,
+        topic: "orders".into(),
+        kafka_partition: 0,
+        offset: 1,
+        deduplication_index,
+        key: Bytes::from_static(b"my-key"),
+        payload: Bytes::from_static(b"{}"),
+        headers: vec![],
+        error: "Failed decoding the Kafka record value".into(),
+        parked_at: MillisSinceEpoch::new(1),
+    };
+    let replay = || {
+        Command::ReplayParkedKafkaRecords(ReplayParkedKafkaRecords {
+            subscription_id,
+            sink: Sink::Invocation {
+                event_invocation_target_template: EventInvocationTargetTemplate::VirtualObject {
+                    name: "MyObject".to_string(),
+                    handler: "handle".to_string(),
+                    handler_ty: VirtualObjectHandlerType::Exclusive,
+                },
+            },
+            partition_key_range: PartitionKey::MIN..=PartitionKey::MAX,
+        })
+    };
+    let replayed_invocation_id = |actions: Vec<Action>| {
+        actions
+            .into_iter()
+            .find_map(|action| match action {
+                Action::NewOutboxMessage {
+                    message: OutboxMessage::ServiceInvocation(service_invocation),
+                    ..
+                } => Some(service_invocation.invocation_id),
+                _ => None,
+            })
+            .expect("the parked record is replayed")
+    };
+
+    let _ = test_env
+        .apply(Command::ParkKafkaRecord(parked_record(1)))
+        .await;
+    let first = replayed_invocation_id(test_env.apply(replay()).await);
+
+    // The same record is consumed and parked again after the offsets were reset
+    let _ = test_env
+        .apply(Command::ParkKafkaRecord(parked_record(101)))
+        .await;
+    let second = replayed_invocation_id(test_env.apply(replay()).await);
+
+    assert_ne!(first, second);
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
 #[test(restate_core::test)]
 async fn truncate_outbox_from_empty() -> Result<(), Error> {
     // An outbox message with index 0 has been successfully processed, and must now be truncated
@@ -0,0 +1,325 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::{fixtures, matchers, *};
+
+use restate_storage_api::journal_table::JournalTable;
+use restate_types::deployment::PinnedDeployment;
+use restate_types::identifiers::DeploymentId;
+use restate_types::invocation::{
+    PauseInvocationRequest, RestartInvocationRequest, ResumeInvocationRequest,
+    RetryInvocationNowRequest,
+};
+use restate_types::service_protocol::ServiceProtocolVersion;
+
+#[restate_core::test]
+async fn pause_and_resume_invoked_invocation() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    let actions = test_env
+        .apply(Command::PauseInvocation(PauseInvocationRequest {
+            invocation_id,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::AbortInvocation {
+            invocation_id: eq(invocation_id),
+            acknowledge: eq(false)
+        }))
+    );
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Paused(_))
+    );
+
+    // Effects still in flight from the invoker are discarded
+    let actions = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            kind: InvokerEffectKind::End,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        not(contains(matchers::actions::invoke_for_id(invocation_id)))
+    );
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Paused(_))
+    );
+
+    let actions = test_env
+        .apply(Command::ResumeInvocation(ResumeInvocationRequest {
+            invocation_id,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::Invoke {
+            invocation_id: eq(invocation_id),
+            invoke_input_journal: pat!(InvokeInputJournal::NoCachedJournal)
+        }))
+    );
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Invoked(_))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[restate_core::test]
+async fn invoker_pauses_invocation_when_retries_are_exhausted() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    let actions = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            kind: InvokerEffectKind::Paused(InvocationError::internal("boom")),
+        }))
+        .await;
+    assert_that!(actions, not(contains(pat!(Action::IngressResponse { .. }))));
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Paused(_))
+    );
+
+    let actions = test_env
+        .apply(Command::ResumeInvocation(ResumeInvocationRequest {
+            invocation_id,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(matchers::actions::invoke_for_id(invocation_id))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[restate_core::test]
+async fn completion_is_stored_while_paused() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    let callee_invocation_id = InvocationId::mock_random();
+
+    let mut tx = test_env.storage.transaction();
+    tx.put_journal_entry(
+        &invocation_id,
+        1,
+        &fixtures::incomplete_invoke_entry(callee_invocation_id),
+    )
+    .await;
+    let mut invocation_status = tx.get_invocation_status(&invocation_id).await?;
+    invocation_status.get_journal_metadata_mut().unwrap().length = 2;
+    tx.put_invocation_status(&invocation_id, &invocation_status)
+        .await;
+    tx.commit().await?;
+
+    let _ = test_env
+        .apply(Command::PauseInvocation(PauseInvocationRequest {
+            invocation_id,
+        }))
+        .await;
+
+    let actions = test_env
+        .apply(Command::InvocationResponse(InvocationResponse {
+            id: invocation_id,
+            entry_index: 1,
+            result: ResponseResult::Success(Bytes::default()),
+        }))
+        .await;
+    assert_that!(
+        actions,
+        not(contains(pat!(Action::ForwardCompletion {
+            invocation_id: eq(invocation_id),
+        })))
+    );
+
+    let_assert!(
+        Some(JournalEntry::Entry(entry)) = test_env
+            .storage()
+            .get_journal_entry(&invocation_id, 1)
+            .await?
+    );
+    assert_that!(entry, matchers::completed_entry());
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Paused(_))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[restate_core::test]
+async fn retry_now_invoked_invocation() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    let actions = test_env
+        .apply(Command::RetryInvocationNow(RetryInvocationNowRequest {
+            invocation_id,
+            pinned_deployment: None,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::RetryInvocationNow {
+            invocation_id: eq(invocation_id)
+        }))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[restate_core::test]
+async fn retry_now_paused_invocation_on_another_deployment() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    let pinned_deployment = PinnedDeployment::new(DeploymentId::new(), ServiceProtocolVersion::V1);
+
+    let _ = test_env
+        .apply(Command::PauseInvocation(PauseInvocationRequest {
+            invocation_id,
+        }))
+        .await;
+    let actions = test_env
+        .apply(Command::RetryInvocationNow(RetryInvocationNowRequest {
+            invocation_id,
+            pinned_deployment: Some(pinned_deployment.clone()),
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(matchers::actions::invoke_for_id(invocation_id))
+    );
+
+    let_assert!(
+        InvocationStatus::Invoked(metadata) = test_env
+            .storage()
+            .get_invocation_status(&invocation_id)
+            .await?
+    );
+    assert_eq!(metadata.pinned_deployment, Some(pinned_deployment));
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[restate_core::test]
+async fn restart_paused_invocation_from_journal_index() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    let pinned_deployment = PinnedDeployment::new(DeploymentId::new(), ServiceProtocolVersion::V1);
+
+    let mut tx = test_env.storage.transaction();
+    for journal_index in 1..3 {
+        tx.put_journal_entry(
+            &invocation_id,
+            journal_index,
+            &fixtures::completed_invoke_entry(InvocationId::mock_random()),
+        )
+        .await;
+    }
+    let mut invocation_status = tx.get_invocation_status(&invocation_id).await?;
+    invocation_status.get_journal_metadata_mut().unwrap().length = 3;
+    tx.put_invocation_status(&invocation_id, &invocation_status)
+        .await;
+    tx.commit().await?;
+
+    let _ = test_env
+        .apply(Command::PauseInvocation(PauseInvocationRequest {
+            invocation_id,
+        }))
+        .await;
+    let actions = test_env
+        .apply(Command::RestartInvocation(RestartInvocationRequest {
+            invocation_id,
+            journal_index: 1,
+            pinned_deployment: Some(pinned_deployment.clone()),
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::Invoke {
+            invocation_id: eq(invocation_id),
+            invoke_input_journal: pat!(InvokeInputJournal::NoCachedJournal)
+        }))
+    );
+
+    let_assert!(
+        InvocationStatus::Invoked(metadata) = test_env
+            .storage()
+            .get_invocation_status(&invocation_id)
+            .await?
+    );
+    assert_eq!(metadata.journal_metadata.length, 2);
+    assert_eq!(metadata.pinned_deployment, Some(pinned_deployment));
+    assert_that!(
+        test_env
+            .storage()
+            .get_journal_entry(&invocation_id, 1)
+            .await?,
+        some(anything())
+    );
+    assert_that!(
+        test_env
+            .storage()
+            .get_journal_entry(&invocation_id, 2)
+            .await?,
+        none()
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[restate_core::test]
+async fn restart_is_ignored_for_executing_invocation() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    let actions = test_env
+        .apply(Command::RestartInvocation(RestartInvocationRequest {
+            invocation_id,
+            journal_index: 0,
+            pinned_deployment: None,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        not(contains(matchers::actions::invoke_for_id(invocation_id)))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
@@ -46,6 +46,9 @@ impl OutboxMessageExt for OutboxMessage {
             OutboxMessage::ServiceResponse(sr) => Command::InvocationResponse(sr),
             OutboxMessage::InvocationTermination(it) => Command::TerminateInvocation(it),
             OutboxMessage::AttachInvocation(ai) => Command::AttachInvocation(ai),
+            OutboxMessage::KafkaEgress(_) => {
+                unreachable!("Kafka egress records are published by the shuffle")
+            }
         }
     }
 }
@@ -25,14 +25,14 @@ use metrics::gauge;
 use tokio::sync::oneshot;
 use tokio::sync::{mpsc, watch};
 use tokio::task::JoinSet;
-use tokio::time::MissedTickBehavior;
+use tokio::time::{Instant, MissedTickBehavior};
 use tracing::{debug, error, info, instrument, warn};
 
 use restate_bifrost::Bifrost;
 use restate_core::network::{Incoming, MessageRouterBuilder, MessageStream};
 use restate_core::worker_api::{
     ProcessorsManagerCommand, ProcessorsManagerHandle, SnapshotCreated, SnapshotError,
-    SnapshotResult,
+    SnapshotListResult, SnapshotResult,
 };
 use restate_core::{
     cancellation_watcher, my_node_id, Metadata, ShutdownError, TaskCenterFutureExt, TaskHandle,
@@ -46,12 +46,12 @@ use restate_partition_store::snapshots::PartitionSnapshotMetadata;
 use restate_partition_store::PartitionStoreManager;
 use restate_types::cluster::cluster_state::ReplayStatus;
 use restate_types::cluster::cluster_state::{PartitionProcessorStatus, RunMode};
-use restate_types::config::Configuration;
+use restate_types::config::{Configuration, SnapshotsOptions};
 use restate_types::epoch::EpochMetadata;
 use restate_types::health::HealthStatus;
 use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey};
 use restate_types::live::Live;
-use restate_types::logs::{Lsn, SequenceNumber};
+use restate_types::logs::{LogId, Lsn, SequenceNumber};
 use restate_types::metadata_store::keys::partition_processor_epoch_key;
 use restate_types::net::metadata::MetadataKind;
 use restate_types::net::partition_processor::{
@@ -62,6 +62,7 @@ use restate_types::net::partition_processor_manager::{
 };
 use restate_types::partition_table::PartitionTable;
 use restate_types::protobuf::common::WorkerStatus;
+use restate_types::retries::{RetryIter, RetryPolicy};
 use restate_types::GenerationalNodeId;
 
 use crate::metric_definitions::NUM_ACTIVE_PARTITIONS;
@@ -73,7 +74,10 @@ use crate::metric_definitions::PARTITION_LAST_PERSISTED_LOG_LSN;
 use crate::metric_definitions::PARTITION_TIME_SINCE_LAST_RECORD;
 use crate::metric_definitions::PARTITION_TIME_SINCE_LAST_STATUS_UPDATE;
 use crate::partition::snapshots::{SnapshotPartitionTask, SnapshotRepository};
-use crate::partition_processor_manager::message_handler::PartitionProcessorManagerMessageHandler;
+use crate::partition_processor_manager::message_handler::{
+    ListSnapshotsMessageHandler, PartitionProcessorManagerMessageHandler,
+    PruneSnapshotsMessageHandler,
+};
 use crate::partition_processor_manager::persisted_lsn_watchdog::PersistedLogLsnWatchdog;
 use crate::partition_processor_manager::processor_state::{
     LeaderEpochToken, ProcessorState, StartedProcessor,
@@ -102,15 +106,109 @@ pub struct PartitionProcessorManager {
     asynchronous_operations: JoinSet<AsynchronousEvent>,
 
     pending_snapshots: HashMap<PartitionId, PendingSnapshotTask>,
+    snapshot_baselines: HashMap<PartitionId, SnapshotBaseline>,
+    snapshot_backoffs: HashMap<PartitionId, SnapshotBackoff>,
     snapshot_export_tasks: FuturesUnordered<TaskHandle<SnapshotResultInternal>>,
     snapshot_repository: Option<SnapshotRepository>,
 }
 
 struct PendingSnapshotTask {
     snapshot_id: SnapshotId,
+    baseline: SnapshotBaseline,
     sender: Option<oneshot::Sender<SnapshotResult>>,
 }
 
+/// The point from which the time- and size-based automatic snapshot triggers of a partition are
+/// measured.
+#[derive(Debug, Clone, Copy)]
+struct SnapshotBaseline {
+    requested_at: Instant,
+    num_applied_log_bytes: u64,
+}
+
+impl SnapshotBaseline {
+    fn new(status: &PartitionProcessorStatus) -> Self {
+        SnapshotBaseline {
+            requested_at: Instant::now(),
+            num_applied_log_bytes: status.num_applied_log_bytes,
+        }
+    }
+}
+
+/// Delays the next automatic snapshot of a partition whose previous snapshot failed, so that a
+/// persistent failure, e.g. an unreachable snapshot repository, doesn't retry on every tick.
+struct SnapshotBackoff {
+    retry_at: Instant,
+    delays: RetryIter<'static>,
+}
+
+impl SnapshotBackoff {
+    fn new() -> Self {
+        SnapshotBackoff {
+            retry_at: Instant::now(),
+            delays: RetryPolicy::exponential(
+                Duration::from_secs(10),
+                2.0,
+                None,
+                Some(Duration::from_secs(600)),
+            )
+            .into_iter(),
+        }
+    }
+
+    fn retry_later(&mut self) {
+        let delay = self
+            .delays
+            .next()
+            .expect("snapshot retries are not limited");
+        self.retry_at = Instant::now() + delay;
+    }
+}
+
+/// Returns the automatic snapshot trigger which fired for the partition, if any.
+fn snapshot_trigger(
+    options: &SnapshotsOptions,
+    status: &PartitionProcessorStatus,
+    baseline: &SnapshotBaseline,
+    now: Instant,
+) -> Option<&'static str> {
+    let last_applied_lsn = status.last_applied_log_lsn.unwrap_or(Lsn::INVALID);
+    let last_archived_lsn = status.last_archived_log_lsn.unwrap_or(Lsn::OLDEST);
+    // The applied bytes counter restarts from zero together with the partition processor
+    let applied_bytes_since_snapshot = status
+        .num_applied_log_bytes
+        .checked_sub(baseline.num_applied_log_bytes)
+        .unwrap_or(status.num_applied_log_bytes);
+
+    if options
+        .snapshot_interval_num_records
+        .is_some_and(|records_per_snapshot| {
+            last_applied_lsn >= last_archived_lsn.add(Lsn::from(records_per_snapshot.get()))
+        })
+    {
+        Some("num-records")
+    } else if options
+        .snapshot_interval_num_bytes
+        .is_some_and(|bytes_per_snapshot| {
+            applied_bytes_since_snapshot >= bytes_per_snapshot.get() as u64
+        })
+    {
+        Some("num-bytes")
+    } else if options.snapshot_interval.is_some_and(|snapshot_interval| {
+        // only snapshot if there were any records applied since the last snapshot
+        now.saturating_duration_since(baseline.requested_at) >= snapshot_interval.into()
+            && status.last_applied_log_lsn.is_some_and(|last_applied_lsn| {
+                status
+                    .last_archived_log_lsn
+                    .is_none_or(|last_archived_lsn| last_applied_lsn > last_archived_lsn)
+            })
+    }) {
+        Some("interval")
+    } else {
+        None
+    }
+}
+
 type SnapshotResultInternal = Result<PartitionSnapshotMetadata, SnapshotError>;
 
 #[derive(Debug, thiserror::Error)]
@@ -200,6 +298,8 @@ impl PartitionProcessorManager {
             asynchronous_operations: JoinSet::default(),
             snapshot_export_tasks: FuturesUnordered::default(),
             pending_snapshots: HashMap::default(),
+            snapshot_baselines: HashMap::default(),
+            snapshot_backoffs: HashMap::default(),
             snapshot_repository,
         }
     }
@@ -216,6 +316,14 @@ impl PartitionProcessorManager {
         PartitionProcessorManagerMessageHandler::new(self.handle())
     }
 
+    pub(crate) fn list_snapshots_message_handler(&self) -> ListSnapshotsMessageHandler {
+        ListSnapshotsMessageHandler::new(self.handle())
+    }
+
+    pub(crate) fn prune_snapshots_message_handler(&self) -> PruneSnapshotsMessageHandler {
+        PruneSnapshotsMessageHandler::new(self.handle())
+    }
+
     pub async fn run(mut self) -> anyhow::Result<()> {
         let mut shutdown = std::pin::pin!(cancellation_watcher());
 
@@ -587,6 +695,16 @@ impl PartitionProcessorManager {
             ProcessorsManagerCommand::CreateSnapshot(partition_id, sender) => {
                 self.on_create_snapshot(partition_id, sender);
             }
+            ProcessorsManagerCommand::ListSnapshots(partition_id, sender) => {
+                self.on_list_snapshots(partition_id, sender);
+            }
+            ProcessorsManagerCommand::PruneSnapshots {
+                partition_id,
+                dry_run,
+                sender,
+            } => {
+                self.on_prune_snapshots(partition_id, dry_run, Some(sender));
+            }
             ProcessorsManagerCommand::GetState(sender) => {
                 let _ = sender.send(self.get_state());
             }
@@ -650,12 +768,12 @@ impl PartitionProcessorManager {
                             processor_state.stop();
                         }
                     }
-                } else if let Some(partition_key_range) = partition_table
-                    .get_partition(&partition_id)
-                    .map(|partition| &partition.key_range)
-                {
-                    let starting_task = self
-                        .start_partition_processor_task(partition_id, partition_key_range.clone());
+                } else if let Some(partition) = partition_table.get_partition(&partition_id) {
+                    let starting_task = self.start_partition_processor_task(
+                        partition_id,
+                        partition.key_range.clone(),
+                        partition.parents.clone(),
+                    );
 
                     self.asynchronous_operations.spawn(
                         async move {
@@ -724,6 +842,13 @@ impl PartitionProcessorManager {
             Ok(metadata) => {
                 self.archived_lsns
                     .insert(metadata.partition_id, metadata.min_applied_lsn);
+                if self
+                    .snapshot_repository
+                    .as_ref()
+                    .is_some_and(|repository| repository.is_retention_enabled())
+                {
+                    self.on_prune_snapshots(metadata.partition_id, false, None);
+                }
 
                 (
                     metadata.partition_id,
@@ -737,6 +862,16 @@ impl PartitionProcessorManager {
         };
 
         if let Some(pending_task) = self.pending_snapshots.remove(&partition_id) {
+            if response.is_ok() {
+                self.snapshot_baselines
+                    .insert(partition_id, pending_task.baseline);
+                self.snapshot_backoffs.remove(&partition_id);
+            } else {
+                self.snapshot_backoffs
+                    .entry(partition_id)
+                    .or_insert_with(SnapshotBackoff::new)
+                    .retry_later();
+            }
             if let Some(sender) = pending_task.sender {
                 let _ = sender.send(response);
             }
@@ -748,39 +883,132 @@ impl PartitionProcessorManager {
         }
     }
 
-    fn trigger_periodic_partition_snapshots(&mut self) {
-        let Some(records_per_snapshot) = self
-            .updateable_config
-            .live_load()
-            .worker
-            .snapshots
-            .snapshot_interval_num_records
-        else {
+    fn on_list_snapshots(
+        &mut self,
+        partition_id: PartitionId,
+        sender: oneshot::Sender<SnapshotListResult>,
+    ) {
+        let Some(snapshot_repository) = self.snapshot_repository.clone() else {
+            let _ = sender.send(Err(SnapshotError::RepositoryNotConfigured(partition_id)));
             return;
         };
 
-        let snapshot_partitions: Vec<_> = self
-            .processor_states
-            .iter()
-            .filter_map(|(partition_id, state)| {
-                state
-                    .partition_processor_status()
-                    .map(|status| (*partition_id, status))
-            })
-            .filter(|(_, status)| {
-                status.effective_mode == RunMode::Leader
-                    && status.replay_status == ReplayStatus::Active
-                    && status.last_applied_log_lsn.unwrap_or(Lsn::INVALID)
-                        >= status
-                            .last_archived_log_lsn
-                            .unwrap_or(Lsn::OLDEST)
-                            .add(Lsn::from(records_per_snapshot.get()))
-            })
-            .collect();
+        // ignore shutdown errors
+        let _ = TaskCenter::spawn(TaskKind::Disposable, "list-snapshots", async move {
+            let result = async {
+                let latest = snapshot_repository
+                    .get_latest_pointer(partition_id)
+                    .await?
+                    .map(|latest| latest.snapshot_id);
+                let snapshots = snapshot_repository.list(partition_id).await?;
+                anyhow::Ok(
+                    snapshots
+                        .iter()
+                        .map(|snapshot| snapshot.to_summary(Some(snapshot.snapshot_id) == latest))
+                        .collect(),
+                )
+            }
+            .await
+            .map_err(|err| SnapshotError::RepositoryIo(partition_id, err));
+
+            let _ = sender.send(result);
+            Ok(())
+        });
+    }
 
-        for (partition_id, status) in snapshot_partitions {
+    /// Prune the snapshots of the given partition which fall outside the configured retention
+    /// policy. Optionally, a sender will be notified of the pruned snapshots on completion.
+    fn on_prune_snapshots(
+        &mut self,
+        partition_id: PartitionId,
+        dry_run: bool,
+        sender: Option<oneshot::Sender<SnapshotListResult>>,
+    ) {
+        let Some(snapshot_repository) = self.snapshot_repository.clone() else {
+            if let Some(sender) = sender {
+                let _ = sender.send(Err(SnapshotError::RepositoryNotConfigured(partition_id)));
+            }
+            return;
+        };
+        let bifrost = self.bifrost.clone();
+
+        // ignore shutdown errors
+        let _ = TaskCenter::spawn(TaskKind::Disposable, "prune-snapshots", async move {
+            let result = async {
+                // Snapshots at or above the trim point are needed to restore the partition
+                let trim_point = bifrost.get_trim_point(LogId::from(partition_id)).await?;
+                let pruned = snapshot_repository
+                    .prune(partition_id, trim_point, dry_run)
+                    .await?;
+                anyhow::Ok(
+                    pruned
+                        .iter()
+                        .map(|snapshot| snapshot.to_summary(false))
+                        .collect(),
+                )
+            }
+            .await
+            .map_err(|err| SnapshotError::RepositoryIo(partition_id, err));
+
+            match sender {
+                Some(sender) => {
+                    let _ = sender.send(result);
+                }
+                None => {
+                    if let Err(err) = result {
+                        warn!(%partition_id, "Failed to prune partition snapshots: {err}");
+                    }
+                }
+            }
+            Ok(())
+        });
+    }
+
+    fn trigger_periodic_partition_snapshots(&mut self) {
+        let snapshots_options = &self.updateable_config.live_load().worker.snapshots;
+        if !snapshots_options.is_automatic_snapshotting_enabled() {
+            return;
+        }
+        let now = Instant::now();
+
+        let mut snapshot_partitions = Vec::new();
+        for (partition_id, state) in &self.processor_states {
+            let Some(mut status) = state.partition_processor_status() else {
+                continue;
+            };
+            status.last_archived_log_lsn = self.archived_lsns.get(partition_id).cloned();
+
+            if status.effective_mode != RunMode::Leader
+                || status.replay_status != ReplayStatus::Active
+            {
+                // The triggers are measured from the time we start leading the partition
+                self.snapshot_baselines.remove(partition_id);
+                continue;
+            }
+
+            if self.pending_snapshots.contains_key(partition_id)
+                || self
+                    .snapshot_backoffs
+                    .get(partition_id)
+                    .is_some_and(|backoff| backoff.retry_at > now)
+            {
+                continue;
+            }
+
+            let baseline = self
+                .snapshot_baselines
+                .entry(*partition_id)
+                .or_insert_with(|| SnapshotBaseline::new(&status));
+
+            if let Some(trigger) = snapshot_trigger(snapshots_options, &status, baseline, now) {
+                snapshot_partitions.push((*partition_id, status, trigger));
+            }
+        }
+
+        for (partition_id, status, trigger) in snapshot_partitions {
             debug!(
                 %partition_id,
+                %trigger,
                 last_archived_lsn = %status.last_archived_log_lsn.unwrap_or(SequenceNumber::OLDEST),
                 last_applied_lsn = %status.last_applied_log_lsn.unwrap_or(SequenceNumber::INVALID),
                 "Requesting partition snapshot",
@@ -826,9 +1054,20 @@ impl PartitionProcessorManager {
 
                 match spawn_task_result {
                     Ok(handle) => {
+                        let baseline = self
+                            .processor_states
+                            .get(&partition_id)
+                            .and_then(ProcessorState::partition_processor_status)
+                            .map(|status| SnapshotBaseline::new(&status))
+                            .unwrap_or_else(|| SnapshotBaseline {
+                                requested_at: Instant::now(),
+                                num_applied_log_bytes: 0,
+                            });
+
                         self.snapshot_export_tasks.push(handle);
                         entry.insert(PendingSnapshotTask {
                             snapshot_id,
+                            baseline,
                             sender,
                         });
                     }
@@ -860,6 +1099,7 @@ impl PartitionProcessorManager {
         &mut self,
         partition_id: PartitionId,
         key_range: RangeInclusive<PartitionKey>,
+        parents: Vec<PartitionId>,
     ) -> SpawnPartitionProcessorTask {
         // the name is also used as thread names for the corresponding tokio runtimes, let's keep
         // it short.
@@ -872,6 +1112,7 @@ impl PartitionProcessorManager {
             task_name,
             partition_id,
             key_range,
+            parents,
             self.updateable_config.clone(),
             self.bifrost.clone(),
             self.partition_store_manager.clone(),
@@ -931,7 +1172,9 @@ enum EventKind {
 
 #[cfg(test)]
 mod tests {
-    use crate::partition_processor_manager::PartitionProcessorManager;
+    use crate::partition_processor_manager::{
+        snapshot_trigger, PartitionProcessorManager, SnapshotBackoff, SnapshotBaseline,
+    };
     use googletest::IntoTestResult;
     use restate_bifrost::providers::memory_loglet;
     use restate_bifrost::BifrostService;
@@ -939,10 +1182,14 @@ mod tests {
     use restate_core::{TaskCenter, TaskKind, TestCoreEnvBuilder};
     use restate_partition_store::PartitionStoreManager;
     use restate_rocksdb::RocksDbManager;
-    use restate_types::config::{CommonOptions, Configuration, RocksDbOptions, StorageOptions};
+    use restate_types::cluster::cluster_state::{PartitionProcessorStatus, ReplayStatus, RunMode};
+    use restate_types::config::{
+        CommonOptions, Configuration, RocksDbOptions, SnapshotsOptions, StorageOptions,
+    };
     use restate_types::health::HealthStatus;
     use restate_types::identifiers::{PartitionId, PartitionKey};
     use restate_types::live::{Constant, Live};
+    use restate_types::logs::Lsn;
     use restate_types::net::partition_processor_manager::{
         ControlProcessor, ControlProcessors, ProcessorCommand,
     };
@@ -950,8 +1197,10 @@ mod tests {
     use restate_types::nodes_config::{LogServerConfig, NodeConfig, NodesConfiguration, Role};
     use restate_types::protobuf::node::Header;
     use restate_types::{GenerationalNodeId, Version};
+    use std::num::NonZeroUsize;
     use std::time::Duration;
     use test_log::test;
+    use tokio::time::Instant;
 
     /// This test ensures that the lifecycle of partition processors is properly managed by the
     /// [`PartitionProcessorManager`]. See https://github.com/restatedev/restate/issues/2258 for
@@ -1066,4 +1315,91 @@ mod tests {
         RocksDbManager::get().shutdown().await;
         Ok(())
     }
+
+    fn leader_status(
+        last_applied_log_lsn: u64,
+        last_archived_log_lsn: Option<u64>,
+        num_applied_log_bytes: u64,
+    ) -> PartitionProcessorStatus {
+        PartitionProcessorStatus {
+            effective_mode: RunMode::Leader,
+            replay_status: ReplayStatus::Active,
+            last_applied_log_lsn: Some(Lsn::from(last_applied_log_lsn)),
+            last_archived_log_lsn: last_archived_log_lsn.map(Lsn::from),
+            num_applied_log_bytes,
+            ..PartitionProcessorStatus::default()
+        }
+    }
+
+    #[test]
+    fn snapshot_interval_trigger() {
+        let options = SnapshotsOptions {
+            snapshot_interval: Some(Duration::from_secs(60).into()),
+            ..SnapshotsOptions::default()
+        };
+        let status = leader_status(10, Some(5), 0);
+        let baseline = SnapshotBaseline::new(&status);
+
+        assert_eq!(
+            snapshot_trigger(&options, &status, &baseline, baseline.requested_at),
+            None
+        );
+        let elapsed = baseline.requested_at + Duration::from_secs(60);
+        assert_eq!(
+            snapshot_trigger(&options, &status, &baseline, elapsed),
+            Some("interval")
+        );
+        // nothing was applied since the last snapshot
+        let archived_status = leader_status(10, Some(10), 0);
+        assert_eq!(
+            snapshot_trigger(&options, &archived_status, &baseline, elapsed),
+            None
+        );
+    }
+
+    #[test]
+    fn snapshot_num_bytes_trigger() {
+        let options = SnapshotsOptions {
+            snapshot_interval_num_bytes: Some(NonZeroUsize::new(1024).unwrap()),
+            ..SnapshotsOptions::default()
+        };
+        let baseline = SnapshotBaseline::new(&leader_status(10, None, 4096));
+        let now = baseline.requested_at;
+
+        assert_eq!(
+            snapshot_trigger(&options, &leader_status(20, None, 5119), &baseline, now),
+            None
+        );
+        assert_eq!(
+            snapshot_trigger(&options, &leader_status(20, None, 5120), &baseline, now),
+            Some("num-bytes")
+        );
+        // the counter restarted together with the partition processor
+        assert_eq!(
+            snapshot_trigger(&options, &leader_status(20, None, 2048), &baseline, now),
+            Some("num-bytes")
+        );
+    }
+
+    #[test]
+    fn snapshot_backoff_grows_until_capped() {
+        let mut backoff = SnapshotBackoff::new();
+        let delay_after_failure = |backoff: &mut SnapshotBackoff| {
+            let before = Instant::now();
+            backoff.retry_later();
+            backoff.retry_at - before
+        };
+
+        // the delays carry up to a third of jitter
+        let first_delay = delay_after_failure(&mut backoff);
+        assert!(first_delay >= Duration::from_secs(10) && first_delay < Duration::from_secs(15));
+
+        for _ in 0..10 {
+            delay_after_failure(&mut backoff);
+        }
+        let capped_delay = delay_after_failure(&mut backoff);
+        assert!(
+            capped_delay >= Duration::from_secs(600) && capped_delay < Duration::from_secs(801)
+        );
+    }
 }
@@ -12,7 +12,8 @@ use restate_core::network::{Incoming, MessageHandler};
 use restate_core::worker_api::ProcessorsManagerHandle;
 use restate_core::{TaskCenter, TaskKind};
 use restate_types::net::partition_processor_manager::{
-    CreateSnapshotRequest, CreateSnapshotResponse, SnapshotError,
+    CreateSnapshotRequest, CreateSnapshotResponse, ListSnapshotsRequest, ListSnapshotsResponse,
+    PruneSnapshotsRequest, PruneSnapshotsResponse, SnapshotError,
 };
 use tracing::warn;
 
@@ -66,3 +67,89 @@ impl MessageHandler for PartitionProcessorManagerMessageHandler {
         .ok();
     }
 }
+
+/// RPC message handler for listing the snapshots of a partition in the snapshot repository.
+pub struct ListSnapshotsMessageHandler {
+    processors_manager_handle: ProcessorsManagerHandle,
+}
+
+impl ListSnapshotsMessageHandler {
+    pub fn new(processors_manager_handle: ProcessorsManagerHandle) -> ListSnapshotsMessageHandler {
+        Self {
+            processors_manager_handle,
+        }
+    }
+}
+
+impl MessageHandler for ListSnapshotsMessageHandler {
+    type MessageType = ListSnapshotsRequest;
+
+    async fn on_message(&self, msg: Incoming<Self::MessageType>) {
+        let processors_manager_handle = self.processors_manager_handle.clone();
+        TaskCenter::spawn_child(
+            TaskKind::Disposable,
+            "list-snapshots-request-rpc",
+            async move {
+                let result = processors_manager_handle
+                    .list_snapshots(msg.body().partition_id)
+                    .await
+                    .map_err(|err| SnapshotError::SnapshotRepositoryFailed(err.to_string()));
+
+                msg.to_rpc_response(ListSnapshotsResponse { result })
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        warn!("Failed to send response: {}", e);
+                        anyhow::anyhow!("Failed to send response to list snapshots request: {}", e)
+                    })
+            },
+        )
+        .map_err(|e| {
+            warn!("Failed to spawn request handler: {}", e);
+        })
+        .ok();
+    }
+}
+
+/// RPC message handler for pruning the snapshots of a partition according to the retention policy.
+pub struct PruneSnapshotsMessageHandler {
+    processors_manager_handle: ProcessorsManagerHandle,
+}
+
+impl PruneSnapshotsMessageHandler {
+    pub fn new(processors_manager_handle: ProcessorsManagerHandle) -> PruneSnapshotsMessageHandler {
+        Self {
+            processors_manager_handle,
+        }
+    }
+}
+
+impl MessageHandler for PruneSnapshotsMessageHandler {
+    type MessageType = PruneSnapshotsRequest;
+
+    async fn on_message(&self, msg: Incoming<Self::MessageType>) {
+        let processors_manager_handle = self.processors_manager_handle.clone();
+        TaskCenter::spawn_child(
+            TaskKind::Disposable,
+            "prune-snapshots-request-rpc",
+            async move {
+                let result = processors_manager_handle
+                    .prune_snapshots(msg.body().partition_id, msg.body().dry_run)
+                    .await
+                    .map_err(|err| SnapshotError::SnapshotRepositoryFailed(err.to_string()));
+
+                msg.to_rpc_response(PruneSnapshotsResponse { result })
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        warn!("Failed to send response: {}", e);
+                        anyhow::anyhow!("Failed to send response to prune snapshots request: {}", e)
+                    })
+            },
+        )
+        .map_err(|e| {
+            warn!("Failed to spawn request handler: {}", e);
+        })
+        .ok();
+    }
+}
@@ -19,9 +19,10 @@ use restate_invoker_impl::Service as InvokerService;
 use restate_partition_store::{OpenMode, PartitionStore, PartitionStoreManager};
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
 use restate_types::cluster::cluster_state::PartitionProcessorStatus;
-use restate_types::config::Configuration;
+use restate_types::config::{Configuration, RocksDbOptions};
 use restate_types::identifiers::{PartitionId, PartitionKey};
 use restate_types::live::Live;
+use restate_types::logs::{LogId, SequenceNumber};
 use restate_types::schema::Schema;
 
 use crate::invoker_integration::EntryEnricher;
@@ -34,6 +35,7 @@ pub struct SpawnPartitionProcessorTask {
     task_name: &'static str,
     partition_id: PartitionId,
     key_range: RangeInclusive<PartitionKey>,
+    parents: Vec<PartitionId>,
     configuration: Live<Configuration>,
     bifrost: Bifrost,
     partition_store_manager: PartitionStoreManager,
@@ -46,6 +48,7 @@ impl SpawnPartitionProcessorTask {
         task_name: &'static str,
         partition_id: PartitionId,
         key_range: RangeInclusive<PartitionKey>,
+        parents: Vec<PartitionId>,
         configuration: Live<Configuration>,
         bifrost: Bifrost,
         partition_store_manager: PartitionStoreManager,
@@ -55,6 +58,7 @@ impl SpawnPartitionProcessorTask {
             task_name,
             partition_id,
             key_range,
+            parents,
             configuration,
             bifrost,
             partition_store_manager,
@@ -73,6 +77,7 @@ impl SpawnPartitionProcessorTask {
             task_name,
             partition_id,
             key_range,
+            parents,
             configuration,
             bifrost,
             partition_store_manager,
@@ -139,7 +144,7 @@ impl SpawnPartitionProcessorTask {
                                 partition_id = %partition_id,
                                 "Looking for partition snapshot from which to bootstrap partition store",
                             );
-                            snapshot_repository.expect("is some").get_latest(partition_id).await?
+                            snapshot_repository.as_ref().expect("is some").get_latest(partition_id).await?
                         };
 
 
@@ -181,6 +186,22 @@ impl SpawnPartitionProcessorTask {
                                     return Err(anyhow::anyhow!(e));
                                 }
                             }
+                        } else if !parents.is_empty() {
+                            info!(
+                                partition_id = %partition_id,
+                                ?parents,
+                                "Bootstrapping partition from the snapshots of its parent partitions",
+                            );
+                            open_from_parent_snapshots(
+                                partition_id,
+                                key_range,
+                                &parents,
+                                &bifrost,
+                                &partition_store_manager,
+                                snapshot_repository.as_ref(),
+                                &options.storage.rocksdb,
+                            )
+                            .await?
                         } else {
                             info!(
                                     partition_id = %partition_id,
@@ -233,3 +254,66 @@ impl SpawnPartitionProcessorTask {
         Ok((state, root_task_handle))
     }
 }
+
+/// Bootstraps the partition store of a partition which replaced the given parent partitions from
+/// their latest snapshots. The snapshots must have been taken at the tail of the parents' sealed
+/// logs, otherwise the records appended to them after the snapshot would be lost.
+async fn open_from_parent_snapshots(
+    partition_id: PartitionId,
+    key_range: RangeInclusive<PartitionKey>,
+    parents: &[PartitionId],
+    bifrost: &Bifrost,
+    partition_store_manager: &PartitionStoreManager,
+    snapshot_repository: Option<&SnapshotRepository>,
+    opts: &RocksDbOptions,
+) -> anyhow::Result<PartitionStore> {
+    let snapshot_repository = snapshot_repository.ok_or_else(|| {
+        anyhow::anyhow!(
+            "partition {partition_id} can only be bootstrapped from the snapshots of its parents \
+            {parents:?} but no snapshot repository is configured"
+        )
+    })?;
+
+    let mut snapshots = Vec::with_capacity(parents.len());
+    for parent in parents {
+        let tail = bifrost.find_tail(LogId::from(*parent)).await?;
+        if !tail.is_sealed() {
+            anyhow::bail!("the log of parent partition {parent} has not been sealed yet");
+        }
+
+        let snapshot = snapshot_repository
+            .get_latest(*parent)
+            .await?
+            .filter(|snapshot| snapshot.min_applied_lsn.next() >= tail.offset())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "no snapshot of parent partition {parent} at the tail {} of its sealed log \
+                    found yet",
+                    tail.offset()
+                )
+            })?;
+        snapshots.push((*parent, snapshot));
+    }
+
+    let snapshot_paths: Vec<_> = snapshots
+        .iter()
+        .map(|(_, snapshot)| snapshot.base_dir.clone())
+        .collect();
+
+    let partition_store = partition_store_manager
+        .open_partition_store_from_parent_snapshots(partition_id, key_range, snapshots, opts)
+        .await?;
+
+    for snapshot_path in snapshot_paths {
+        if let Err(e) = tokio::fs::remove_dir_all(&snapshot_path).await {
+            warn!(
+                partition_id = %partition_id,
+                ?snapshot_path,
+                "Failed to remove local snapshot directory, continuing with startup: {:?}",
+                e
+            );
+        }
+    }
+
+    Ok(partition_store)
+}
@@ -13,13 +13,18 @@ use std::time::Duration;
 
 use enumset::enum_set;
 use futures_util::StreamExt;
+use googletest::internal::test_outcome::TestAssertionFailure;
 use regex::Regex;
+use restate_core::metadata_store::{retry_on_network_error, Precondition};
 use restate_local_cluster_runner::{
     cluster::Cluster,
     node::{BinarySource, Node},
 };
 use restate_types::config::MetadataStoreClient;
 use restate_types::logs::metadata::ProviderKind;
+use restate_types::metadata_store::keys::NODES_CONFIG_KEY;
+use restate_types::nodes_config::NodesConfiguration;
+use restate_types::retries::RetryPolicy;
 use restate_types::{config::Configuration, nodes_config::Role, PlainNodeId};
 use test_log::test;
 
@@ -60,7 +65,7 @@ async fn node_id_mismatch() -> googletest::Result<()> {
         .build();
 
     *mismatch_node.metadata_store_client_mut() = MetadataStoreClient::Embedded {
-        address: cluster.nodes[0].node_address().clone(),
+        addresses: vec![cluster.nodes[0].node_address().clone()],
     };
 
     cluster.push_node(mismatch_node).await?;
@@ -105,7 +110,7 @@ async fn cluster_name_mismatch() -> googletest::Result<()> {
     );
 
     *mismatch_node.metadata_store_client_mut() = MetadataStoreClient::Embedded {
-        address: cluster.nodes[0].node_address().clone(),
+        addresses: vec![cluster.nodes[0].node_address().clone()],
     };
 
     let mut mismatch_node = mismatch_node
@@ -156,3 +161,78 @@ async fn replicated_loglet() -> googletest::Result<()> {
 
     Ok(())
 }
+
+#[test(restate_core::test)]
+async fn raft_metadata_store() -> googletest::Result<()> {
+    let nodes = Node::new_test_nodes_with_raft_metadata(
+        Configuration::default(),
+        BinarySource::CargoTest,
+        enum_set!(Role::Worker),
+        3,
+    );
+
+    let mut cluster = Cluster::builder()
+        .temp_base_dir()
+        .nodes(nodes)
+        .build()
+        .start()
+        .await?;
+
+    cluster.wait_healthy(Duration::from_secs(30)).await?;
+
+    // take down one member, the remaining majority keeps serving requests
+    cluster.nodes[2]
+        .graceful_shutdown(Duration::from_secs(2))
+        .await?;
+
+    let retry_policy = RetryPolicy::fixed_delay(Duration::from_millis(100), Some(100));
+    let client = cluster.nodes[1]
+        .metadata_client()
+        .await
+        .map_err(|err| TestAssertionFailure::create(err.to_string()))?;
+
+    // the nodes configuration written by the admin on node-1 is visible through node-2
+    let mut nodes_config = retry_on_network_error(retry_policy.clone(), || {
+        client.get::<NodesConfiguration>(NODES_CONFIG_KEY.clone())
+    })
+    .await?
+    .expect("nodes configuration to be present");
+
+    let version = nodes_config.version();
+    nodes_config.increment_version();
+    retry_on_network_error(retry_policy.clone(), || {
+        client.put(
+            NODES_CONFIG_KEY.clone(),
+            &nodes_config,
+            Precondition::MatchesVersion(version),
+        )
+    })
+    .await?;
+
+    let client = cluster.nodes[0]
+        .metadata_client()
+        .await
+        .map_err(|err| TestAssertionFailure::create(err.to_string()))?;
+    assert_eq!(
+        retry_on_network_error(retry_policy.clone(), || {
+            client.get_version(NODES_CONFIG_KEY.clone())
+        })
+        .await?,
+        Some(nodes_config.version())
+    );
+
+    // node-3 lists its own member first, which is down, requests fall back to the other members
+    let client = cluster.nodes[2]
+        .metadata_client()
+        .await
+        .map_err(|err| TestAssertionFailure::create(err.to_string()))?;
+    assert_eq!(
+        retry_on_network_error(retry_policy, || {
+            client.get_version(NODES_CONFIG_KEY.clone())
+        })
+        .await?,
+        Some(nodes_config.version())
+    );
+
+    Ok(())
+}
@@ -8,12 +8,14 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use cling::prelude::*;
 
 use restate_cli_util::CliContext;
 use restate_cli_util::CommonOpts;
+use restate_types::config::NetworkingTlsOptions;
 use restate_types::net::AdvertisedAddress;
 
 use crate::commands::cluster::overview::ClusterStatusOpts;
@@ -49,6 +51,38 @@ pub struct ConnectionInfo {
         global = true
     )]
     pub cluster_controller: AdvertisedAddress,
+
+    /// Client certificate, required if the nodes use mutual TLS
+    #[clap(long, value_hint = clap::ValueHint::FilePath, env = "RESTATE_TLS_CERT", global = true)]
+    pub tls_cert: Option<PathBuf>,
+
+    /// Private key of the client certificate
+    #[clap(long, value_hint = clap::ValueHint::FilePath, env = "RESTATE_TLS_KEY", global = true)]
+    pub tls_key: Option<PathBuf>,
+
+    /// CA certificates used to verify the certificates of the nodes
+    #[clap(long, value_hint = clap::ValueHint::FilePath, env = "RESTATE_TLS_CA", global = true)]
+    pub tls_ca: Option<PathBuf>,
+
+    /// Name to verify the certificates of the nodes against, defaults to the host of the address
+    #[clap(long, env = "RESTATE_TLS_SERVER_NAME", global = true)]
+    pub tls_server_name: Option<String>,
+}
+
+impl ConnectionInfo {
+    /// The TLS options for connecting to the nodes, if a client certificate is configured.
+    pub fn tls_options(&self) -> anyhow::Result<Option<NetworkingTlsOptions>> {
+        match (&self.tls_cert, &self.tls_key, &self.tls_ca) {
+            (Some(cert_path), Some(key_path), Some(ca_path)) => Ok(Some(NetworkingTlsOptions {
+                cert_path: cert_path.clone(),
+                key_path: key_path.clone(),
+                ca_path: ca_path.clone(),
+                server_name: self.tls_server_name.clone(),
+            })),
+            (None, None, None) => Ok(None),
+            _ => anyhow::bail!("--tls-cert, --tls-key and --tls-ca must be configured together"),
+        }
+    }
 }
 
 #[derive(Run, Subcommand, Clone)]
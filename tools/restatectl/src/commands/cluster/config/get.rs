@@ -27,7 +27,7 @@ use crate::{
 pub struct ConfigGetOpts {}
 
 async fn config_get(connection: &ConnectionInfo, _get_opts: &ConfigGetOpts) -> anyhow::Result<()> {
-    let channel = grpc_connect(connection.cluster_controller.clone())
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
         .await
         .with_context(|| {
             format!(
@@ -58,7 +58,7 @@ pub struct ConfigSetOpts {
 }
 
 async fn config_set(connection: &ConnectionInfo, set_opts: &ConfigSetOpts) -> anyhow::Result<()> {
-    let channel = grpc_connect(connection.cluster_controller.clone())
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
         .await
         .with_context(|| {
             format!(
@@ -103,6 +103,11 @@ async fn config_set(connection: &ConnectionInfo, set_opts: &ConfigSetOpts) -> an
                 };
                 DefaultProvider::Replicated(config)
             }
+            ProviderKind::ObjectStore => {
+                anyhow::bail!(
+                    "object-store loglets are read-only and can't be the default provider"
+                )
+            }
         };
 
         current.default_provider = Some(default_provider.into());
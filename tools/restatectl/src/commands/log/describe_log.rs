@@ -71,7 +71,7 @@ async fn describe_logs(
     connection: &ConnectionInfo,
     opts: &DescribeLogIdOpts,
 ) -> anyhow::Result<()> {
-    let channel = grpc_connect(connection.cluster_controller.clone())
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
         .await
         .with_context(|| {
             format!(
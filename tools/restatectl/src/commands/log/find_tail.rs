@@ -32,7 +32,7 @@ pub struct FindTailOpts {
 }
 
 async fn find_tail(connection: &ConnectionInfo, opts: &FindTailOpts) -> anyhow::Result<()> {
-    let channel = grpc_connect(connection.cluster_controller.clone())
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
         .await
         .with_context(|| {
             format!(
@@ -34,7 +34,7 @@ use crate::util::grpc_connect;
 pub struct ListLogsOpts {}
 
 pub async fn list_logs(connection: &ConnectionInfo, _opts: &ListLogsOpts) -> anyhow::Result<()> {
-    let channel = grpc_connect(connection.cluster_controller.clone())
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
         .await
         .with_context(|| {
             format!(
@@ -68,7 +68,7 @@ pub struct ReconfigureOpts {
 }
 
 async fn reconfigure(connection: &ConnectionInfo, opts: &ReconfigureOpts) -> anyhow::Result<()> {
-    let channel = grpc_connect(connection.cluster_controller.clone())
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
         .await
         .with_context(|| {
             format!(
@@ -88,6 +88,9 @@ async fn reconfigure(connection: &ConnectionInfo, opts: &ReconfigureOpts) -> any
                 ProviderKind::InMemory => rand::random::<u64>().to_string(),
                 #[cfg(feature = "replicated-loglet")]
                 ProviderKind::Replicated => replicated_loglet_params(&mut client, opts).await?,
+                ProviderKind::ObjectStore => {
+                    anyhow::bail!("object-store loglets are read-only and can't extend a chain")
+                }
             };
 
             Some(ChainExtension {
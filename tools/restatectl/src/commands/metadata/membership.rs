@@ -0,0 +1,81 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::str::FromStr;
+
+use clap::Parser;
+use cling::{Collect, Run};
+
+use restate_metadata_store::raft::RaftMembershipClient;
+use restate_types::config::MetadataStoreClientOptions;
+use restate_types::net::AdvertisedAddress;
+
+use crate::commands::metadata::MetadataCommonOpts;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[clap()]
+#[cling(run = "add_member")]
+pub struct AddMemberOpts {
+    #[clap(flatten)]
+    metadata: MetadataCommonOpts,
+
+    /// Raft member id of the new member
+    #[arg(long)]
+    id: u64,
+
+    /// Advertised address of the new member's node
+    #[arg(long)]
+    member_address: String,
+}
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[clap()]
+#[cling(run = "remove_member")]
+pub struct RemoveMemberOpts {
+    #[clap(flatten)]
+    metadata: MetadataCommonOpts,
+
+    /// Raft member id of the member to remove
+    #[arg(long)]
+    id: u64,
+}
+
+async fn add_member(opts: &AddMemberOpts) -> anyhow::Result<()> {
+    let member_address = AdvertisedAddress::from_str(&opts.member_address)
+        .map_err(|e| anyhow::anyhow!("Failed to parse member address: {}", e))?;
+
+    membership_client(&opts.metadata)?
+        .add_member(opts.id, &member_address)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to add member: {}", e.message()))?;
+
+    println!("Added member {} at {}", opts.id, member_address);
+    Ok(())
+}
+
+async fn remove_member(opts: &RemoveMemberOpts) -> anyhow::Result<()> {
+    membership_client(&opts.metadata)?
+        .remove_member(opts.id)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to remove member: {}", e.message()))?;
+
+    println!("Removed member {}", opts.id);
+    Ok(())
+}
+
+fn membership_client(opts: &MetadataCommonOpts) -> anyhow::Result<RaftMembershipClient> {
+    let address = AdvertisedAddress::from_str(opts.address.as_str())
+        .map_err(|e| anyhow::anyhow!("Failed to parse address: {}", e))?;
+
+    Ok(RaftMembershipClient::new(
+        address,
+        &MetadataStoreClientOptions::default(),
+    ))
+}
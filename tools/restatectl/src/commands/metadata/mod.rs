@@ -20,6 +20,7 @@ use restate_types::net::AdvertisedAddress;
 use restate_types::{flexbuffers_storage_encode_decode, Version, Versioned};
 
 mod get;
+mod membership;
 mod patch;
 mod put;
 
@@ -31,12 +32,16 @@ pub enum Metadata {
     Patch(patch::PatchValueOpts),
     /// Replace a single key's value from the metastore
     Put(put::PutValueOpts),
+    /// Add a member to a Raft-replicated metadata store
+    AddMember(membership::AddMemberOpts),
+    /// Remove a member from a Raft-replicated metadata store
+    RemoveMember(membership::RemoveMemberOpts),
 }
 
 #[derive(Args, Clone, Debug)]
 #[clap()]
 pub struct MetadataCommonOpts {
-    /// Metadata store server address; for Etcd addresses use comma-separated list
+    /// Metadata store server address; for multiple addresses use comma-separated list
     #[arg(
         short,
         long = "address",
@@ -109,8 +114,14 @@ pub async fn create_metadata_store_client(
 ) -> anyhow::Result<MetadataStoreClient> {
     let client = match opts.remote_service_type {
         RemoteServiceType::Restate => restate_types::config::MetadataStoreClient::Embedded {
-            address: AdvertisedAddress::from_str(opts.address.as_str())
-                .map_err(|e| anyhow::anyhow!("Failed to parse address: {}", e))?,
+            addresses: opts
+                .address
+                .split(',')
+                .map(|address| {
+                    AdvertisedAddress::from_str(address)
+                        .map_err(|e| anyhow::anyhow!("Failed to parse address: {}", e))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
         },
         RemoteServiceType::Etcd => restate_types::config::MetadataStoreClient::Etcd {
             addresses: opts
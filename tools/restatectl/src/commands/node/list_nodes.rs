@@ -47,7 +47,7 @@ pub struct ListNodesOpts {
 }
 
 pub async fn list_nodes(connection: &ConnectionInfo, opts: &ListNodesOpts) -> anyhow::Result<()> {
-    let channel = grpc_connect(connection.cluster_controller.clone())
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
         .await
         .with_context(|| {
             format!(
@@ -69,7 +69,7 @@ pub async fn list_nodes(connection: &ConnectionInfo, opts: &ListNodesOpts) -> an
     let nodes = nodes_configuration.iter().collect::<BTreeMap<_, _>>();
 
     let nodes_extra_info = if opts.extra {
-        fetch_extra_info(&nodes_configuration).await?
+        fetch_extra_info(connection, &nodes_configuration).await?
     } else {
         HashMap::new()
     };
@@ -147,14 +147,16 @@ where
 }
 
 async fn fetch_extra_info(
+    connection: &ConnectionInfo,
     nodes_configuration: &NodesConfiguration,
 ) -> anyhow::Result<HashMap<PlainNodeId, IdentResponse>> {
     let mut get_ident_tasks = JoinSet::<anyhow::Result<IdentResponse>>::new();
 
     for (node_id, node_config) in nodes_configuration.iter() {
         let address = node_config.address.clone();
+        let connection = connection.clone();
         let get_ident = async move {
-            let node_channel = grpc_connect(address).await?;
+            let node_channel = grpc_connect(&connection, address).await?;
             let mut node_ctl_svc_client =
                 NodeCtlSvcClient::new(node_channel).accept_compressed(CompressionEncoding::Gzip);
 
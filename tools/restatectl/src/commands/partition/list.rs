@@ -72,7 +72,7 @@ pub async fn list_partitions(
     connection: &ConnectionInfo,
     opts: &ListPartitionsOpts,
 ) -> anyhow::Result<()> {
-    let channel = grpc_connect(connection.cluster_controller.clone())
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
         .await
         .with_context(|| {
             format!(
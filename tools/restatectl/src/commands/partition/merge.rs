@@ -0,0 +1,67 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Context;
+use cling::prelude::*;
+use tonic::codec::CompressionEncoding;
+
+use restate_admin::cluster_controller::protobuf::cluster_ctrl_svc_client::ClusterCtrlSvcClient;
+use restate_admin::cluster_controller::protobuf::MergePartitionsRequest;
+use restate_cli_util::c_println;
+
+use crate::app::ConnectionInfo;
+use crate::util::grpc_connect;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "merge_partitions")]
+pub struct MergePartitionsOpts {
+    /// The first partition to merge
+    #[arg(short, long)]
+    partition_id: u16,
+    /// The partition to merge with, its key range must be adjacent to the first partition's
+    #[arg(short, long)]
+    other_partition_id: u16,
+}
+
+async fn merge_partitions(
+    connection: &ConnectionInfo,
+    opts: &MergePartitionsOpts,
+) -> anyhow::Result<()> {
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
+        .await
+        .with_context(|| {
+            format!(
+                "cannot connect to cluster controller at {}",
+                connection.cluster_controller
+            )
+        })?;
+    let mut client =
+        ClusterCtrlSvcClient::new(channel).accept_compressed(CompressionEncoding::Gzip);
+
+    let request = MergePartitionsRequest {
+        partition_id: opts.partition_id as u32,
+        other_partition_id: opts.other_partition_id as u32,
+    };
+
+    let response = client
+        .merge_partitions(request)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to merge partitions: {:?}", e))?
+        .into_inner();
+
+    c_println!(
+        "Partitions {} and {} were merged into partition {}",
+        opts.partition_id,
+        opts.other_partition_id,
+        response.partition_id
+    );
+
+    Ok(())
+}
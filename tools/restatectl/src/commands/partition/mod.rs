@@ -10,6 +10,8 @@
 
 mod gen_metadata;
 pub mod list;
+mod merge;
+mod split;
 
 use cling::prelude::*;
 
@@ -20,4 +22,8 @@ pub enum Partitions {
     List(list::ListPartitionsOpts),
     /// Prints a generated partition table in JSON format
     GenerateMetadata(gen_metadata::GeneratePartitionTableOpts),
+    /// Split a partition into two partitions at the given partition key
+    Split(split::SplitPartitionOpts),
+    /// Merge two partitions with adjacent key ranges into one partition
+    Merge(merge::MergePartitionsOpts),
 }
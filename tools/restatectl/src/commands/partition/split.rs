@@ -0,0 +1,66 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Context;
+use cling::prelude::*;
+use tonic::codec::CompressionEncoding;
+
+use restate_admin::cluster_controller::protobuf::cluster_ctrl_svc_client::ClusterCtrlSvcClient;
+use restate_admin::cluster_controller::protobuf::SplitPartitionRequest;
+use restate_cli_util::c_println;
+
+use crate::app::ConnectionInfo;
+use crate::util::grpc_connect;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "split_partition")]
+pub struct SplitPartitionOpts {
+    /// The partition to split
+    #[arg(short, long)]
+    partition_id: u16,
+    /// The first partition key of the second partition
+    #[arg(short, long)]
+    split_key: u64,
+}
+
+async fn split_partition(
+    connection: &ConnectionInfo,
+    opts: &SplitPartitionOpts,
+) -> anyhow::Result<()> {
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
+        .await
+        .with_context(|| {
+            format!(
+                "cannot connect to cluster controller at {}",
+                connection.cluster_controller
+            )
+        })?;
+    let mut client =
+        ClusterCtrlSvcClient::new(channel).accept_compressed(CompressionEncoding::Gzip);
+
+    let request = SplitPartitionRequest {
+        partition_id: opts.partition_id as u32,
+        split_key: opts.split_key,
+    };
+
+    let response = client
+        .split_partition(request)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to split partition: {:?}", e))?
+        .into_inner();
+
+    c_println!(
+        "Partition {} was split into partitions {:?}",
+        opts.partition_id,
+        response.partition_ids
+    );
+
+    Ok(())
+}
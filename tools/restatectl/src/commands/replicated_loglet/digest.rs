@@ -53,7 +53,7 @@ pub struct DigestOpts {
 }
 
 async fn get_digest(connection: &ConnectionInfo, opts: &DigestOpts) -> anyhow::Result<()> {
-    let channel = grpc_connect(connection.cluster_controller.clone())
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
         .await
         .with_context(|| {
             format!(
@@ -105,7 +105,7 @@ async fn get_digest(connection: &ConnectionInfo, opts: &DigestOpts) -> anyhow::R
             continue;
         }
 
-        let Ok(channel) = grpc_connect(node.address.clone()).await else {
+        let Ok(channel) = grpc_connect(connection, node.address.clone()).await else {
             warn!("Failed to connect to node {} at {}", node_id, node.address);
             continue;
         };
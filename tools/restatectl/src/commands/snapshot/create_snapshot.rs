@@ -32,7 +32,7 @@ async fn create_snapshot(
     connection: &ConnectionInfo,
     opts: &CreateSnapshotOpts,
 ) -> anyhow::Result<()> {
-    let channel = grpc_connect(connection.cluster_controller.clone())
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
         .await
         .with_context(|| {
             format!(
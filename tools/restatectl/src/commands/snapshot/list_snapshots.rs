@@ -0,0 +1,65 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Context;
+use cling::prelude::*;
+use tonic::codec::CompressionEncoding;
+
+use restate_admin::cluster_controller::protobuf::cluster_ctrl_svc_client::ClusterCtrlSvcClient;
+use restate_admin::cluster_controller::protobuf::ListPartitionSnapshotsRequest;
+use restate_cli_util::c_println;
+
+use crate::app::ConnectionInfo;
+use crate::commands::snapshot::render_snapshots;
+use crate::util::grpc_connect;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[clap(visible_alias = "list")]
+#[cling(run = "list_snapshots")]
+pub struct ListSnapshotsOpts {
+    /// The partition to list the snapshots of
+    #[arg(short, long)]
+    partition_id: u16,
+}
+
+async fn list_snapshots(
+    connection: &ConnectionInfo,
+    opts: &ListSnapshotsOpts,
+) -> anyhow::Result<()> {
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
+        .await
+        .with_context(|| {
+            format!(
+                "cannot connect to cluster controller at {}",
+                connection.cluster_controller
+            )
+        })?;
+    let mut client =
+        ClusterCtrlSvcClient::new(channel).accept_compressed(CompressionEncoding::Gzip);
+
+    let request = ListPartitionSnapshotsRequest {
+        partition_id: opts.partition_id as u32,
+    };
+
+    let response = client
+        .list_partition_snapshots(request)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to list snapshots: {:?}", e))?
+        .into_inner();
+
+    if response.snapshots.is_empty() {
+        c_println!("No snapshots found for partition {}", opts.partition_id);
+        return Ok(());
+    }
+
+    c_println!("{}", render_snapshots(&response.snapshots));
+
+    Ok(())
+}
@@ -9,11 +9,49 @@
 // by the Apache License, Version 2.0.
 
 mod create_snapshot;
+mod list_snapshots;
+mod prune_snapshots;
 
+use chrono::DateTime;
 use cling::prelude::*;
 
+use restate_admin::cluster_controller::protobuf::PartitionSnapshot;
+use restate_cli_util::_comfy_table::{Cell, Table};
+use restate_cli_util::ui::console::StyledTable;
+use restate_cli_util::ui::{timestamp_as_human_duration, Tense};
+
 #[derive(Run, Subcommand, Clone)]
 pub enum Snapshot {
     /// Create.
     CreateSnapshot(create_snapshot::CreateSnapshotOpts),
+    /// List the snapshots of a partition stored in the snapshot repository.
+    ListSnapshots(list_snapshots::ListSnapshotsOpts),
+    /// Delete the snapshots of a partition which fall outside the configured retention policy.
+    PruneSnapshots(prune_snapshots::PruneSnapshotsOpts),
+}
+
+fn render_snapshots(snapshots: &[PartitionSnapshot]) -> Table {
+    let mut snapshots_table = Table::new_styled();
+    snapshots_table.set_styled_header(vec![
+        "SNAPSHOT-ID",
+        "MIN-APPLIED-LSN",
+        "CREATED",
+        "NODE",
+        "",
+    ]);
+
+    for snapshot in snapshots {
+        let created_at = DateTime::from_timestamp_millis(snapshot.created_at as i64)
+            .map(|ts| timestamp_as_human_duration(ts, Tense::Past))
+            .unwrap_or_else(|| "-".to_owned());
+        snapshots_table.add_row(vec![
+            Cell::new(&snapshot.snapshot_id),
+            Cell::new(snapshot.min_applied_lsn),
+            Cell::new(created_at),
+            Cell::new(&snapshot.node_name),
+            Cell::new(if snapshot.is_latest { "latest" } else { "" }),
+        ]);
+    }
+
+    snapshots_table
 }
@@ -0,0 +1,75 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Context;
+use cling::prelude::*;
+use tonic::codec::CompressionEncoding;
+
+use restate_admin::cluster_controller::protobuf::cluster_ctrl_svc_client::ClusterCtrlSvcClient;
+use restate_admin::cluster_controller::protobuf::PrunePartitionSnapshotsRequest;
+use restate_cli_util::c_println;
+
+use crate::app::ConnectionInfo;
+use crate::commands::snapshot::render_snapshots;
+use crate::util::grpc_connect;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[clap(visible_alias = "prune")]
+#[cling(run = "prune_snapshots")]
+pub struct PruneSnapshotsOpts {
+    /// The partition to prune the snapshots of
+    #[arg(short, long)]
+    partition_id: u16,
+
+    /// Only list the snapshots which would be deleted by the retention policy
+    #[arg(short = 'n', long, default_value_t = false)]
+    dry_run: bool,
+}
+
+async fn prune_snapshots(
+    connection: &ConnectionInfo,
+    opts: &PruneSnapshotsOpts,
+) -> anyhow::Result<()> {
+    let channel = grpc_connect(connection, connection.cluster_controller.clone())
+        .await
+        .with_context(|| {
+            format!(
+                "cannot connect to cluster controller at {}",
+                connection.cluster_controller
+            )
+        })?;
+    let mut client =
+        ClusterCtrlSvcClient::new(channel).accept_compressed(CompressionEncoding::Gzip);
+
+    let request = PrunePartitionSnapshotsRequest {
+        partition_id: opts.partition_id as u32,
+        dry_run: opts.dry_run,
+    };
+
+    let response = client
+        .prune_partition_snapshots(request)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to prune snapshots: {:?}", e))?
+        .into_inner();
+
+    if response.pruned_snapshots.is_empty() {
+        c_println!("No snapshots to prune for partition {}", opts.partition_id);
+        return Ok(());
+    }
+
+    if opts.dry_run {
+        c_println!("Snapshots which would be deleted:");
+    } else {
+        c_println!("Deleted snapshots:");
+    }
+    c_println!("{}", render_snapshots(&response.pruned_snapshots));
+
+    Ok(())
+}
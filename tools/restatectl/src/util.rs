@@ -10,33 +10,55 @@
 
 use hyper_util::rt::TokioIo;
 use restate_cli_util::CliContext;
+use restate_core::network::net_util::connect_tls;
+use restate_core::network::tls;
 use restate_types::net::AdvertisedAddress;
 use tokio::io;
 use tokio::net::UnixStream;
 use tonic::transport::{Channel, Endpoint, Uri};
 use tower::service_fn;
 
-pub async fn grpc_connect(address: AdvertisedAddress) -> Result<Channel, tonic::transport::Error> {
+use crate::app::ConnectionInfo;
+
+pub async fn grpc_connect(
+    connection: &ConnectionInfo,
+    address: AdvertisedAddress,
+) -> anyhow::Result<Channel> {
     let ctx = CliContext::get();
     match address {
         AdvertisedAddress::Uds(uds_path) => {
             // dummy endpoint required to specify an uds connector, it is not used anywhere
-            Endpoint::try_from("http://127.0.0.1")
-                .expect("/ should be a valid Uri")
-                .connect_with_connector(service_fn(move |_: Uri| {
-                    let uds_path = uds_path.clone();
-                    async move {
-                        Ok::<_, io::Error>(TokioIo::new(UnixStream::connect(uds_path).await?))
-                    }
-                })).await
+            Ok(
+                Endpoint::try_from("http://127.0.0.1")
+                    .expect("/ should be a valid Uri")
+                    .connect_with_connector(service_fn(move |_: Uri| {
+                        let uds_path = uds_path.clone();
+                        async move {
+                            Ok::<_, io::Error>(TokioIo::new(UnixStream::connect(uds_path).await?))
+                        }
+                    }))
+                    .await?,
+            )
         }
         AdvertisedAddress::Http(uri) => {
-            Channel::builder(uri)
+            let endpoint = Channel::builder(uri.clone())
                 .connect_timeout(ctx.connect_timeout())
                 .timeout(ctx.request_timeout())
-                .http2_adaptive_window(true)
-                .connect()
-                .await
+                .http2_adaptive_window(true);
+
+            match connection.tls_options()? {
+                None => Ok(endpoint.connect().await?),
+                Some(tls_options) => {
+                    let connector = tls::tls_connector(&tls_options)?;
+                    let server_name =
+                        tls::server_name(&tls_options, uri.host().unwrap_or_default())?;
+                    Ok(endpoint
+                        .connect_with_connector(service_fn(move |uri: Uri| {
+                            connect_tls(uri, connector.clone(), server_name.clone())
+                        }))
+                        .await?)
+                }
+            }
         }
     }
 }